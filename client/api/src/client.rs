@@ -190,6 +190,15 @@ pub struct IoInfo {
 	pub state_writes_nodes: u64,
 }
 
+/// Database statistics for client instance.
+#[derive(Default, Clone, Debug)]
+pub struct DatabaseInfo {
+	/// On-disk size in bytes of each database column, by column name.
+	///
+	/// Empty if the database doesn't report the sizes of its columns.
+	pub column_sizes: Vec<(&'static str, u64)>,
+}
+
 /// Usage statistics for running client instance.
 ///
 /// Returning backend determines the scope of these stats,
@@ -201,6 +210,8 @@ pub struct UsageInfo {
 	pub memory: MemoryInfo,
 	/// I/O statistics.
 	pub io: IoInfo,
+	/// Database statistics.
+	pub database: DatabaseInfo,
 }
 
 impl fmt::Display for UsageInfo {
@@ -42,6 +42,28 @@ pub trait ProofProvider<Block: BlockT> {
 		keys: &mut dyn Iterator<Item=&[u8]>,
 	) -> sp_blockchain::Result<StorageProof>;
 
+	/// Reads the storage entries following `start_key` in key order, in the top trie or in
+	/// the given child trie, until `size_limit` bytes of keys and values are read. Returns
+	/// the entries, whether the end of the trie was reached and the read proof.
+	fn read_proof_collection(
+		&self,
+		id: &BlockId<Block>,
+		child_info: Option<&ChildInfo>,
+		start_key: &[u8],
+		size_limit: usize,
+	) -> sp_blockchain::Result<(Vec<(Vec<u8>, Vec<u8>)>, bool, StorageProof)>;
+
+	/// Checks a proof returned by `read_proof_collection` against the given state root.
+	fn verify_range_proof(
+		&self,
+		root: Block::Hash,
+		proof: StorageProof,
+		child_info: Option<&ChildInfo>,
+		start_key: &[u8],
+		entries: &[(Vec<u8>, Vec<u8>)],
+		complete: bool,
+	) -> sp_blockchain::Result<()>;
+
 	/// Execute a call to a contract on top of state in a block of given hash
 	/// AND returning execution proof.
 	///
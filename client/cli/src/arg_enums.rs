@@ -177,6 +177,26 @@ arg_enum! {
 	}
 }
 
+arg_enum! {
+	/// Syncing mode.
+	#[allow(missing_docs)]
+	#[derive(Debug, Clone, Copy, PartialEq)]
+	pub enum SyncMode {
+		// Download and execute all the blocks.
+		Full,
+		// Download the headers and the state of a recent finalized block, then the blocks.
+		Fast,
+	}
+}
+
+impl Into<sc_network::config::SyncMode> for SyncMode {
+	fn into(self) -> sc_network::config::SyncMode {
+		match self {
+			SyncMode::Full => sc_network::config::SyncMode::Full,
+			SyncMode::Fast => sc_network::config::SyncMode::Fast,
+		}
+	}
+}
 
 arg_enum! {
 	/// Whether off-chain workers are enabled.
@@ -0,0 +1,87 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::error;
+use crate::params::{DatabaseParams, PruningParams, SharedParams};
+use crate::CliConfiguration;
+use log::info;
+use sc_client_api::{BlockBackend, StorageProvider, UsageProvider, backend::AuxStore};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use std::{fs, io, path::PathBuf, sync::Arc};
+use structopt::StructOpt;
+
+/// The `export-snapshot` command used to write the state of the last finalized block
+/// into a snapshot file.
+#[derive(Debug, StructOpt)]
+pub struct ExportSnapshotCmd {
+	/// Output file name.
+	#[structopt(parse(from_os_str))]
+	pub output: PathBuf,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl ExportSnapshotCmd {
+	/// Run the `export-snapshot` command.
+	///
+	/// `aux_keys` returns the keys of the auxiliary database entries, such as the
+	/// consensus authority sets, that the node needs to continue from the exported block.
+	pub async fn run<B, BA, C, F>(
+		&self,
+		client: Arc<C>,
+		aux_keys: F,
+	) -> error::Result<()>
+	where
+		B: BlockT,
+		C: UsageProvider<B> + StorageProvider<B, BA> + BlockBackend<B> + HeaderBackend<B> + AuxStore,
+		BA: sc_client_api::backend::Backend<B>,
+		F: Fn(&B::Header) -> Vec<Vec<u8>>,
+	{
+		let file = io::BufWriter::new(fs::File::create(&self.output)?);
+
+		info!("Exporting snapshot...");
+		let header = sc_service::chain_ops::export_snapshot(client, aux_keys, file)?;
+		info!("Exported snapshot of block #{} ({})", header.number(), header.hash());
+		Ok(())
+	}
+}
+
+impl CliConfiguration for ExportSnapshotCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
@@ -0,0 +1,74 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::error;
+use crate::params::{DatabaseParams, PruningParams, SharedParams};
+use crate::CliConfiguration;
+use log::info;
+use sc_service::config::Configuration;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use std::{fs, io, path::PathBuf};
+use structopt::StructOpt;
+
+/// The `import-snapshot` command used to create a database from a snapshot file.
+#[derive(Debug, StructOpt)]
+pub struct ImportSnapshotCmd {
+	/// Input file name.
+	#[structopt(parse(from_os_str))]
+	pub input: PathBuf,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl ImportSnapshotCmd {
+	/// Run the `import-snapshot` command.
+	///
+	/// The database configured in `config` must not exist yet.
+	pub fn run<B: BlockT>(&self, config: &Configuration) -> error::Result<()> {
+		let file = io::BufReader::new(fs::File::open(&self.input)?);
+
+		info!("Importing snapshot...");
+		let header = sc_service::chain_ops::import_snapshot::<B>(config, file)?;
+		info!("Imported snapshot of block #{} ({})", header.number(), header.hash());
+		Ok(())
+	}
+}
+
+impl CliConfiguration for ImportSnapshotCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
@@ -0,0 +1,90 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::arg_enums::Database;
+use crate::error;
+use crate::params::{DatabaseParams, PruningParams, SharedParams};
+use crate::CliConfiguration;
+use log::info;
+use sc_service::config::Configuration;
+use sp_runtime::traits::Block as BlockT;
+use structopt::StructOpt;
+
+/// The `migrate-database` command used to copy the chain into a database of another kind.
+///
+/// The whole database is copied column by column. Only RocksDB databases can be migrated, as
+/// ParityDB can't iterate over its keys.
+#[derive(Debug, StructOpt)]
+pub struct MigrateDatabaseCmd {
+	/// Kind of the database to create.
+	///
+	/// It is created next to the database selected with `--database`, which isn't modified.
+	#[structopt(
+		long = "to",
+		value_name = "DB",
+		possible_values = &Database::variants(),
+		case_insensitive = true,
+	)]
+	pub to: Database,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl MigrateDatabaseCmd {
+	/// Run the `migrate-database` command.
+	pub fn run<B: BlockT>(&self, config: &Configuration) -> error::Result<()> {
+		let base_path = config.database.path()
+			.and_then(|path| path.parent())
+			.ok_or_else(|| error::Error::Input("The database is not stored on disk".into()))?
+			.to_path_buf();
+		let cache_size = self.database_cache_size()?.unwrap_or(128);
+		let target = self.database_config(&base_path, cache_size, self.to)?;
+		if target.path() == config.database.path() {
+			return Err(error::Error::Input(format!("The database is already a {} database", target)));
+		}
+
+		info!("Migrating the {} database to {}...", config.database, target);
+		sc_service::chain_ops::migrate_database::<B>(config, target)?;
+		info!("Migrated the database");
+		Ok(())
+	}
+}
+
+impl CliConfiguration for MigrateDatabaseCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
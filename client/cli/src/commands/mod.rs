@@ -19,8 +19,11 @@ mod build_spec_cmd;
 mod build_sync_spec_cmd;
 mod check_block_cmd;
 mod export_blocks_cmd;
+mod export_snapshot_cmd;
 mod export_state_cmd;
 mod import_blocks_cmd;
+mod import_snapshot_cmd;
+mod migrate_database_cmd;
 mod purge_chain_cmd;
 mod sign;
 mod verify;
@@ -40,8 +43,11 @@ pub use self::{
 	build_sync_spec_cmd::BuildSyncSpecCmd,
 	check_block_cmd::CheckBlockCmd,
 	export_blocks_cmd::ExportBlocksCmd,
+	export_snapshot_cmd::ExportSnapshotCmd,
 	export_state_cmd::ExportStateCmd,
 	import_blocks_cmd::ImportBlocksCmd,
+	import_snapshot_cmd::ImportSnapshotCmd,
+	migrate_database_cmd::MigrateDatabaseCmd,
 	purge_chain_cmd::PurgeChainCmd,
 	sign::SignCmd,
 	generate::GenerateCmd,
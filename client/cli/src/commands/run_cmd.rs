@@ -22,16 +22,18 @@ use crate::params::ImportParams;
 use crate::params::KeystoreParams;
 use crate::params::NetworkParams;
 use crate::params::OffchainWorkerParams;
+use crate::params::RpcLimitsParams;
 use crate::params::SharedParams;
 use crate::params::TransactionPoolParams;
 use crate::CliConfiguration;
 use regex::Regex;
 use sc_service::{
-	config::{BasePath, MultiaddrWithPeerId, PrometheusConfig, TransactionPoolOptions},
+	config::{BasePath, MultiaddrWithPeerId, PrometheusConfig, RpcLimits, TransactionPoolOptions},
 	ChainSpec, Role,
 };
 use sc_telemetry::TelemetryEndpoints;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
 use structopt::StructOpt;
 
 /// The `run` command used to run a node.
@@ -147,6 +149,10 @@ pub struct RunCmd {
 	#[structopt(long = "rpc-cors", value_name = "ORIGINS", parse(try_from_str = parse_cors))]
 	pub rpc_cors: Option<Cors>,
 
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub rpc_limits: RpcLimitsParams,
+
 	/// Specify Prometheus data source server TCP Port.
 	#[structopt(long = "prometheus-port", value_name = "PORT")]
 	pub prometheus_port: Option<u16>,
@@ -409,6 +415,10 @@ impl CliConfiguration for RunCmd {
 		Ok(self.ws_max_connections)
 	}
 
+	fn rpc_limits(&self) -> Result<RpcLimits> {
+		Ok(self.rpc_limits.rpc_limits())
+	}
+
 	fn rpc_cors(&self, is_dev: bool) -> Result<Option<Vec<String>>> {
 		Ok(self
 			.rpc_cors
@@ -464,6 +474,10 @@ impl CliConfiguration for RunCmd {
 		Ok(self.pool_config.transaction_pool())
 	}
 
+	fn transaction_pool_persistence(&self, config_dir: &PathBuf) -> Result<Option<PathBuf>> {
+		Ok(self.pool_config.persistence_path(config_dir))
+	}
+
 	fn max_runtime_instances(&self) -> Result<Option<usize>> {
 		Ok(self.max_runtime_instances.map(|x| x.min(256)))
 	}
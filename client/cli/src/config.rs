@@ -28,8 +28,9 @@ use log::warn;
 use names::{Generator, Name};
 use sc_client_api::execution_extensions::ExecutionStrategies;
 use sc_service::config::{
-	BasePath, Configuration, DatabaseConfig, ExtTransport, KeystoreConfig, NetworkConfiguration,
-	NodeKeyConfig, OffchainWorkerConfig, PrometheusConfig, PruningMode, Role, RpcMethods,
+	BasePath, BlocksPruning, Configuration, DatabaseConfig, ExtTransport, KeystoreConfig,
+	NetworkConfiguration,
+	NodeKeyConfig, OffchainWorkerConfig, PrometheusConfig, PruningMode, Role, RpcLimits, RpcMethods,
 	TaskExecutor, TelemetryEndpoints, TransactionPoolOptions, WasmExecutionMethod,
 };
 use sc_service::{ChainSpec, TracingReceiver};
@@ -149,6 +150,13 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 		Ok(Default::default())
 	}
 
+	/// Get the file the transactions of the pool are saved to.
+	///
+	/// By default this is `None`, so that the transactions are kept in memory only.
+	fn transaction_pool_persistence(&self, _config_dir: &PathBuf) -> Result<Option<PathBuf>> {
+		Ok(None)
+	}
+
 	/// Get the network configuration
 	///
 	/// By default this is retrieved from `NetworkParams` if it is available otherwise it creates
@@ -254,6 +262,16 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 			.unwrap_or_else(|| Ok(Default::default()))
 	}
 
+	/// Get the block body pruning mode.
+	///
+	/// By default this is retrieved from `PruningParams` if it is available. Otherwise the bodies
+	/// of all blocks are kept.
+	fn blocks_pruning(&self) -> Result<BlocksPruning> {
+		self.pruning_params()
+			.map(|x| x.blocks_pruning())
+			.unwrap_or_else(|| Ok(Default::default()))
+	}
+
 	/// Get the chain ID (string).
 	///
 	/// By default this is retrieved from `SharedParams`.
@@ -329,6 +347,13 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 		Ok(None)
 	}
 
+	/// Get the limits applied to the calls of the HTTP & WS RPC servers.
+	///
+	/// By default these are `RpcLimits::default()`.
+	fn rpc_limits(&self) -> Result<RpcLimits> {
+		Ok(Default::default())
+	}
+
 	/// Get the RPC cors (`None` if disabled)
 	///
 	/// By default this is `Some(Vec::new())`.
@@ -476,7 +501,10 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 			impl_name: C::impl_name(),
 			impl_version: C::impl_version(),
 			task_executor,
-			transaction_pool: self.transaction_pool()?,
+			transaction_pool: TransactionPoolOptions {
+				persistence_path: self.transaction_pool_persistence(&config_dir)?,
+				..self.transaction_pool()?
+			},
 			network: self.network_config(
 				&chain_spec,
 				is_dev,
@@ -491,6 +519,7 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 			state_cache_size: self.state_cache_size()?,
 			state_cache_child_ratio: self.state_cache_child_ratio()?,
 			pruning: self.pruning(unsafe_pruning, &role)?,
+			blocks_pruning: self.blocks_pruning()?,
 			wasm_method: self.wasm_method()?,
 			execution_strategies: self.execution_strategies(is_dev, is_validator)?,
 			rpc_http: self.rpc_http(DCV::rpc_http_listen_port())?,
@@ -498,6 +527,7 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 			rpc_ipc: self.rpc_ipc()?,
 			rpc_methods: self.rpc_methods()?,
 			rpc_ws_max_connections: self.rpc_ws_max_connections()?,
+			rpc_limits: self.rpc_limits()?,
 			rpc_cors: self.rpc_cors(is_dev)?,
 			prometheus_config: self.prometheus_config(DCV::prometheus_listen_port())?,
 			telemetry_endpoints: self.telemetry_endpoints(&chain_spec)?,
@@ -23,6 +23,8 @@ use structopt::StructOpt;
 #[derive(Debug, StructOpt)]
 pub struct DatabaseParams {
 	/// Select database backend to use.
+	///
+	/// The `database_column_size_bytes` metric is only reported for ParityDB.
 	#[structopt(
 		long,
 		alias = "db",
@@ -22,6 +22,7 @@ mod network_params;
 mod node_key_params;
 mod offchain_worker_params;
 mod pruning_params;
+mod rpc_limits_params;
 mod shared_params;
 mod transaction_pool_params;
 
@@ -38,6 +39,7 @@ pub use crate::params::network_params::*;
 pub use crate::params::node_key_params::*;
 pub use crate::params::offchain_worker_params::*;
 pub use crate::params::pruning_params::*;
+pub use crate::params::rpc_limits_params::*;
 pub use crate::params::shared_params::*;
 pub use crate::params::transaction_pool_params::*;
 
@@ -17,7 +17,7 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::error;
-use sc_service::{PruningMode, Role};
+use sc_service::{BlocksPruning, PruningMode, Role};
 use structopt::StructOpt;
 
 /// Parameters to define the pruning mode
@@ -30,6 +30,14 @@ pub struct PruningParams {
 	/// 256 blocks.
 	#[structopt(long = "pruning", value_name = "PRUNING_MODE")]
 	pub pruning: Option<String>,
+
+	/// Specify the block body pruning mode: a number of finalized blocks to keep the bodies of,
+	/// 'finalized' to keep the bodies of all finalized blocks, or 'archive'.
+	///
+	/// Other than in 'archive' mode, the bodies of blocks on forks displaced by finality are
+	/// removed. Headers and justifications are always kept. Default is 'archive'.
+	#[structopt(long = "blocks-pruning", value_name = "BLOCKS_PRUNING_MODE")]
+	pub blocks_pruning: Option<String>,
 }
 
 impl PruningParams {
@@ -58,4 +66,19 @@ impl PruningParams {
 			}
 		})
 	}
+
+	/// Get the block body pruning value from the parameters
+	pub fn blocks_pruning(&self) -> error::Result<BlocksPruning> {
+		Ok(match self.blocks_pruning.as_ref().map(String::as_str) {
+			None | Some("archive") => BlocksPruning::All,
+			Some("finalized") => BlocksPruning::KeepFinalized,
+			Some(s) => match s.parse() {
+				Ok(keep) if keep > 0 => BlocksPruning::Some(keep),
+				_ => return Err(error::Error::Input(
+					"Invalid blocks pruning mode specified, expected a positive number of blocks, \
+					'finalized' or 'archive'".to_string(),
+				)),
+			},
+		})
+	}
 }
@@ -0,0 +1,85 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! RPC server limits configuration parameters.
+
+use std::{net::IpAddr, num::NonZeroU32};
+use structopt::StructOpt;
+use sc_service::config::RpcLimits;
+
+/// Parameters limiting the calls served by the HTTP and WS RPC servers.
+#[derive(Debug, StructOpt)]
+pub struct RpcLimitsParams {
+	/// Comma separated list of RPC methods that may be called; all methods if not set.
+	///
+	/// A trailing `*` matches all methods with the given prefix, e.g. `chain_*`.
+	#[structopt(long = "rpc-methods-allow", value_name = "METHODS", use_delimiter = true)]
+	pub rpc_methods_allow: Option<Vec<String>>,
+
+	/// Comma separated list of RPC methods that may never be called.
+	///
+	/// Uses the same format as `--rpc-methods-allow` and takes precedence over it.
+	#[structopt(long = "rpc-methods-deny", value_name = "METHODS", use_delimiter = true)]
+	pub rpc_methods_deny: Vec<String>,
+
+	/// Maximum number of HTTP requests or new WS connections per second from a single IP address.
+	///
+	/// The limit is keyed on the address of the remote socket, or on the address forwarded
+	/// by one of the `--rpc-trusted-proxies`.
+	#[structopt(long = "rpc-rate-limit-ip", value_name = "COUNT")]
+	pub rpc_rate_limit_ip: Option<NonZeroU32>,
+
+	/// Comma separated list of the addresses of the reverse proxies in front of the RPC servers.
+	///
+	/// The address of the client is taken from the `X-Forwarded-For` or `X-Real-IP` headers
+	/// of the requests of these proxies for `--rpc-rate-limit-ip`.
+	#[structopt(long = "rpc-trusted-proxies", value_name = "ADDRESSES", use_delimiter = true)]
+	pub rpc_trusted_proxies: Vec<IpAddr>,
+
+	/// Maximum number of calls per second on a single WS connection.
+	#[structopt(long = "rpc-rate-limit-connection", value_name = "COUNT")]
+	pub rpc_rate_limit_connection: Option<NonZeroU32>,
+
+	/// Maximum size of an RPC request in kilobytes.
+	#[structopt(long = "rpc-max-request-size", value_name = "KB", default_value = "15360")]
+	pub rpc_max_request_size: usize,
+
+	/// Maximum size of an RPC response in kilobytes.
+	#[structopt(long = "rpc-max-response-size", value_name = "KB", default_value = "15360")]
+	pub rpc_max_response_size: usize,
+
+	/// Maximum number of subscriptions on a single WS connection.
+	#[structopt(long = "rpc-max-subscriptions-per-connection", value_name = "COUNT", default_value = "1024")]
+	pub rpc_max_subscriptions_per_connection: usize,
+}
+
+impl RpcLimitsParams {
+	/// Get the limits applied to the calls of the RPC servers.
+	pub fn rpc_limits(&self) -> RpcLimits {
+		RpcLimits {
+			methods_allow: self.rpc_methods_allow.clone(),
+			methods_deny: self.rpc_methods_deny.clone(),
+			rate_limit_ip: self.rpc_rate_limit_ip,
+			trusted_proxies: self.rpc_trusted_proxies.clone(),
+			rate_limit_connection: self.rpc_rate_limit_connection,
+			max_request_size: self.rpc_max_request_size * 1024,
+			max_response_size: self.rpc_max_response_size * 1024,
+			max_subscriptions_per_connection: self.rpc_max_subscriptions_per_connection,
+		}
+	}
+}
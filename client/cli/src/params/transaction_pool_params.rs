@@ -17,8 +17,13 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use sc_service::config::TransactionPoolOptions;
+use sp_runtime::Percent;
+use std::path::{Path, PathBuf};
 use structopt::StructOpt;
 
+/// Name of the directory the transactions of the pool are saved to.
+const DEFAULT_PERSISTENCE_DIR: &str = "txpool";
+
 /// Parameters used to create the pool configuration.
 #[derive(Debug, StructOpt)]
 pub struct TransactionPoolParams {
@@ -29,6 +34,22 @@ pub struct TransactionPoolParams {
 	/// Maximum number of kilobytes of all transactions stored in the pool.
 	#[structopt(long = "pool-kbytes", value_name = "COUNT", default_value = "20480")]
 	pub pool_kbytes: usize,
+
+	/// Maximum number of ready transactions of a single sender in the transaction pool.
+	#[structopt(long = "pool-sender-ready", value_name = "COUNT", default_value = "1024")]
+	pub pool_sender_ready: usize,
+
+	/// Maximum number of future transactions of a single sender in the transaction pool.
+	#[structopt(long = "pool-sender-future", value_name = "COUNT", default_value = "64")]
+	pub pool_sender_future: usize,
+
+	/// Minimal priority increase, in percent, required to replace a transaction in the pool.
+	#[structopt(long = "pool-priority-bump", value_name = "PERCENT", default_value = "10")]
+	pub pool_priority_bump: u8,
+
+	/// Save the transactions of the pool to disk and import them back after a restart.
+	#[structopt(long = "pool-persist")]
+	pub pool_persist: bool,
 }
 
 impl TransactionPoolParams {
@@ -45,6 +66,20 @@ impl TransactionPoolParams {
 		opts.future.count = self.pool_limit / factor;
 		opts.future.total_bytes = self.pool_kbytes * 1024 / factor;
 
+		// per-sender limits and replacement
+		opts.sender_limit.ready = self.pool_sender_ready;
+		opts.sender_limit.future = self.pool_sender_future;
+		opts.min_priority_bump = Percent::from_percent(self.pool_priority_bump);
+
 		opts
 	}
+
+	/// Get the file the transactions of the pool are saved to, if persistence is enabled.
+	pub fn persistence_path(&self, config_dir: &Path) -> Option<PathBuf> {
+		if self.pool_persist {
+			Some(config_dir.join(DEFAULT_PERSISTENCE_DIR).join("transactions"))
+		} else {
+			None
+		}
+	}
 }
@@ -33,6 +33,9 @@ mod codes {
 	pub const CONSENSUS_ERROR: i64 = 14_000;
 	pub const INHERENTS_ERROR: i64 = 15_000;
 	pub const BLOCKCHAIN_ERROR: i64 = 16_000;
+	pub const TIMESTAMP_NOT_SET: i64 = 17_000;
+	pub const PARENT_FINALIZED: i64 = 18_000;
+	pub const TOO_MANY_BLOCKS: i64 = 19_000;
 	pub const UNKNOWN_ERROR: i64 = 20_000;
 }
 
@@ -59,6 +62,29 @@ pub enum Error {
 	#[display(fmt = "Supplied parent_hash: {} doesn't exist in chain", _0)]
 	#[from(ignore)]
 	BlockNotFound(String),
+	/// Supplied parent is an ancestor of the last finalized block, so no fork can be created on it.
+	#[display(fmt = "Supplied parent_hash: {} is below the last finalized block", _0)]
+	#[from(ignore)]
+	ParentFinalized(String),
+	/// The timestamp can only be advanced once it is set.
+	#[display(fmt = "Timestamp is not under manual control, set it first")]
+	TimestampNotSet,
+	/// More blocks requested than can be sealed in one call.
+	#[display(fmt = "Requested {} blocks, at most {} can be sealed at once", requested, max)]
+	TooManyBlocks {
+		/// Number of blocks requested.
+		requested: u32,
+		/// Maximum number of blocks allowed.
+		max: u32,
+	},
+	/// Sealing stopped at a failing block, after sealing some of the requested blocks.
+	#[display(fmt = "Sealed {} of the requested blocks: {}", "sealed.len()", error)]
+	PartiallySealed {
+		/// The blocks sealed before the failure, as returned on success.
+		sealed: Vec<jsonrpc_core::Value>,
+		/// The error that stopped sealing.
+		error: Box<Error>,
+	},
 	/// Some string error
 	#[display(fmt = "{}", _0)]
 	#[from(ignore)]
@@ -84,6 +110,10 @@ impl Error {
 			ConsensusError(_) => codes::CONSENSUS_ERROR,
 			InherentError(_) => codes::INHERENTS_ERROR,
 			BlockchainError(_) => codes::BLOCKCHAIN_ERROR,
+			TimestampNotSet => codes::TIMESTAMP_NOT_SET,
+			ParentFinalized(_) => codes::PARENT_FINALIZED,
+			TooManyBlocks { .. } => codes::TOO_MANY_BLOCKS,
+			PartiallySealed { error, .. } => error.to_code(),
 			SendError(_) | Canceled(_) => codes::SERVER_SHUTTING_DOWN,
 			_ => codes::UNKNOWN_ERROR
 		}
@@ -92,10 +122,14 @@ impl Error {
 
 impl std::convert::From<Error> for jsonrpc_core::Error {
 	fn from(error: Error) -> Self {
+		let data = match error {
+			Error::PartiallySealed { ref sealed, .. } => Some(sealed.clone().into()),
+			_ => None,
+		};
 		jsonrpc_core::Error {
 			code: jsonrpc_core::ErrorCode::ServerError(error.to_code()),
 			message: format!("{}", error),
-			data: None
+			data,
 		}
 	}
 }
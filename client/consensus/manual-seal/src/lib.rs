@@ -30,12 +30,14 @@ use sp_inherents::InherentDataProviders;
 use sp_runtime::{traits::Block as BlockT, Justification};
 use sc_client_api::backend::{Backend as ClientBackend, Finalizer};
 use sc_transaction_pool::txpool;
-use std::{sync::Arc, marker::PhantomData};
+use std::{sync::Arc, marker::PhantomData, time::Duration};
 use prometheus_endpoint::Registry;
+use futures_timer::Delay;
 
 mod error;
 mod finalize_block;
 mod seal_block;
+mod timestamp;
 
 pub mod consensus;
 pub mod rpc;
@@ -44,8 +46,9 @@ pub use self::{
 	error::Error,
 	consensus::ConsensusDataProvider,
 	finalize_block::{finalize_block, FinalizeBlockParams},
-	seal_block::{SealBlockParams, seal_block, MAX_PROPOSAL_DURATION},
+	seal_block::{SealBlockParams, seal_block, try_seal_block, MAX_PROPOSAL_DURATION, MAX_BLOCKS_PER_REQUEST},
 	rpc::{EngineCommand, CreatedBlock},
+	timestamp::{ManualTimestamp, DEFAULT_TIMESTAMP_STEP},
 };
 use sp_api::{ProvideRuntimeApi, TransactionFor};
 
@@ -142,6 +145,33 @@ pub struct InstantSealParams<B: BlockT, BI, E, C: ProvideRuntimeApi<B>, A: txpoo
 	pub inherent_data_providers: InherentDataProviders,
 }
 
+/// Params required to start the idle sealing authorship task.
+pub struct IdleSealParams<B: BlockT, BI, E, C: ProvideRuntimeApi<B>, A: txpool::ChainApi, SC> {
+	/// Block import instance for well. importing blocks.
+	pub block_import: BI,
+
+	/// The environment we are producing blocks for.
+	pub env: E,
+
+	/// Client instance
+	pub client: Arc<C>,
+
+	/// Shared reference to the transaction pool.
+	pub pool: Arc<txpool::Pool<A>>,
+
+	/// SelectChain strategy.
+	pub select_chain: SC,
+
+	/// Digest provider for inclusion in blocks.
+	pub consensus_data_provider: Option<Box<dyn ConsensusDataProvider<B, Transaction = TransactionFor<C, B>>>>,
+
+	/// Provider for inherents to include in blocks.
+	pub inherent_data_providers: InherentDataProviders,
+
+	/// How long no new transaction must have been imported into the pool before sealing.
+	pub idle_interval: Duration,
+}
+
 /// Creates the background authorship task for the manual seal engine.
 pub async fn run_manual_seal<B, BI, CB, E, C, A, SC, CS>(
 	ManualSealParams {
@@ -169,17 +199,19 @@ pub async fn run_manual_seal<B, BI, CB, E, C, A, SC, CS>(
 		CS: Stream<Item=EngineCommand<<B as BlockT>::Hash>> + Unpin + 'static,
 		SC: SelectChain<B> + 'static,
 {
+	let mut timestamp = ManualTimestamp::default();
+
 	while let Some(command) = commands_stream.next().await {
 		match command {
 			EngineCommand::SealNewBlock {
 				create_empty,
 				finalize,
 				parent_hash,
-				sender,
+				mut sender,
 			} => {
-				seal_block(
+				let result = try_seal_block(
 					SealBlockParams {
-						sender,
+						sender: None,
 						parent_hash,
 						finalize,
 						create_empty,
@@ -190,8 +222,74 @@ pub async fn run_manual_seal<B, BI, CB, E, C, A, SC, CS>(
 						consensus_data_provider: consensus_data_provider.as_ref().map(|p| &**p),
 						pool: pool.clone(),
 						client: client.clone(),
+						timestamp: timestamp.next(),
 					}
 				).await;
+				if result.is_ok() {
+					timestamp.note_sealed();
+				}
+				rpc::send_result(&mut sender, result);
+			}
+			EngineCommand::SealNewBlocks {
+				count,
+				create_empty,
+				finalize,
+				parent_hash,
+				mut sender,
+			} => {
+				if count > MAX_BLOCKS_PER_REQUEST {
+					rpc::send_result(
+						&mut sender,
+						Err(Error::TooManyBlocks { requested: count, max: MAX_BLOCKS_PER_REQUEST }),
+					);
+					continue;
+				}
+
+				let mut created = Vec::with_capacity(count as usize);
+				let mut parent_hash = parent_hash;
+				let mut result = Ok(());
+				for _ in 0..count {
+					let sealed = try_seal_block(
+						SealBlockParams {
+							sender: None,
+							parent_hash,
+							finalize,
+							create_empty,
+							env: &mut env,
+							select_chain: &select_chain,
+							block_import: &mut block_import,
+							inherent_data_provider: &inherent_data_providers,
+							consensus_data_provider: consensus_data_provider.as_ref().map(|p| &**p),
+							pool: pool.clone(),
+							client: client.clone(),
+							timestamp: timestamp.next(),
+						}
+					).await;
+					match sealed {
+						Ok(block) => {
+							timestamp.note_sealed();
+							parent_hash = Some(block.hash);
+							created.push(block);
+						},
+						Err(e) => {
+							result = Err(e);
+							break;
+						},
+					}
+				}
+				let result = match result {
+					Ok(()) => Ok(created),
+					Err(error) if created.is_empty() => Err(error),
+					// the blocks sealed before the failure stay imported, report them too.
+					Err(error) => Err(Error::PartiallySealed {
+						sealed: created.iter()
+							.map(|block| jsonrpc_core::serde_json::to_value(block)
+								.expect("Created blocks serialize to JSON; qed"))
+							.collect(),
+						error: Box::new(error),
+					}),
+				};
+				rpc::send_result(&mut sender, result);
 			}
 			EngineCommand::FinalizeBlock { hash, sender, justification } => {
 				finalize_block(
@@ -204,6 +302,13 @@ pub async fn run_manual_seal<B, BI, CB, E, C, A, SC, CS>(
 					}
 				).await
 			}
+			EngineCommand::SetTimestamp { timestamp: new_timestamp, step, mut sender } => {
+				timestamp.set(new_timestamp, step);
+				rpc::send_result(&mut sender, Ok(()))
+			}
+			EngineCommand::AdvanceTimestamp { delta, mut sender } => {
+				rpc::send_result(&mut sender, timestamp.advance(delta))
+			}
 		}
 	}
 }
@@ -262,6 +367,83 @@ pub async fn run_instant_seal<B, BI, CB, E, C, A, SC>(
 	).await
 }
 
+/// runs the background authorship task for the idle seal engine.
+/// idle-seal creates a new block once no new transaction was imported into
+/// the transaction pool for `idle_interval`.
+pub async fn run_idle_seal<B, BI, CB, E, C, A, SC>(
+	IdleSealParams {
+		block_import,
+		env,
+		client,
+		pool,
+		select_chain,
+		consensus_data_provider,
+		inherent_data_providers,
+		idle_interval,
+	}: IdleSealParams<B, BI, E, C, A, SC>
+)
+	where
+		A: txpool::ChainApi<Block=B> + 'static,
+		B: BlockT + 'static,
+		BI: BlockImport<B, Error = sp_consensus::Error, Transaction = sp_api::TransactionFor<C, B>>
+			+ Send + Sync + 'static,
+		C: HeaderBackend<B> + Finalizer<B, CB> + ProvideRuntimeApi<B> + 'static,
+		CB: ClientBackend<B> + 'static,
+		E: Environment<B> + 'static,
+		E::Error: std::fmt::Display,
+		<E::Proposer as Proposer<B>>::Error: std::fmt::Display,
+		SC: SelectChain<B> + 'static
+{
+	let commands_stream = idle_seal_commands(
+		pool.validated_pool().import_notification_stream(),
+		idle_interval,
+	);
+
+	run_manual_seal(
+		ManualSealParams {
+			block_import,
+			env,
+			client,
+			pool,
+			commands_stream,
+			select_chain,
+			consensus_data_provider,
+			inherent_data_providers,
+		}
+	).await
+}
+
+/// Turns a stream of transaction import notifications into a stream of commands that seal
+/// a block once no notification was received for `idle_interval`.
+fn idle_seal_commands<Hash, S>(
+	notifications: S,
+	idle_interval: Duration,
+) -> impl Stream<Item = EngineCommand<Hash>> + Unpin
+	where S: Stream + Unpin,
+{
+	Box::pin(stream::unfold(notifications, move |mut notifications| async move {
+		// wait for the first transaction.
+		notifications.next().await?;
+
+		// then until the pool is idle.
+		loop {
+			match future::select(notifications.next(), Delay::new(idle_interval)).await {
+				future::Either::Left((Some(_), _)) => continue,
+				future::Either::Left((None, _)) => return None,
+				future::Either::Right(_) => break,
+			}
+		}
+
+		let command = EngineCommand::SealNewBlock {
+			create_empty: false,
+			finalize: false,
+			parent_hash: None,
+			sender: None,
+		};
+		Some((command, notifications))
+	}))
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -533,4 +715,209 @@ mod tests {
 		// assert that fork block is in the db
 		assert!(client.header(&BlockId::Hash(imported.hash)).unwrap().is_some())
 	}
+
+	#[tokio::test]
+	async fn manual_seal_bulk_blocks_and_forks() {
+		let builder = TestClientBuilder::new();
+		let (client, select_chain) = builder.build_with_longest_chain();
+		let client = Arc::new(client);
+		let inherent_data_providers = InherentDataProviders::new();
+		let spawner = sp_core::testing::TaskExecutor::new();
+		let pool = Arc::new(BasicPool::with_revalidation_type(
+			Options::default(), api(), None, RevalidationType::Full, spawner,
+		));
+		let env = ProposerFactory::new(
+			client.clone(),
+			pool.clone(),
+			None,
+		);
+		let (mut sink, commands_stream) = futures::channel::mpsc::channel(1024);
+		let future = run_manual_seal(
+			ManualSealParams {
+				block_import: client.clone(),
+				env,
+				client: client.clone(),
+				pool: pool.pool().clone(),
+				commands_stream,
+				select_chain,
+				consensus_data_provider: None,
+				inherent_data_providers,
+			}
+		);
+		std::thread::spawn(|| {
+			let mut rt = tokio::runtime::Runtime::new().unwrap();
+			// spawn the background authorship task
+			rt.block_on(future);
+		});
+
+		// the timestamp can only be advanced once set.
+		let (tx, rx) = futures::channel::oneshot::channel();
+		sink.send(EngineCommand::AdvanceTimestamp { delta: 1_000, sender: Some(tx) }).await.unwrap();
+		assert_matches::assert_matches!(rx.await.unwrap(), Err(Error::TimestampNotSet));
+		let (tx, rx) = futures::channel::oneshot::channel();
+		sink.send(EngineCommand::SetTimestamp {
+			timestamp: 1_000_000,
+			step: Some(2_000),
+			sender: Some(tx),
+		}).await.unwrap();
+		assert!(rx.await.unwrap().is_ok());
+
+		// seal three empty blocks in a row.
+		let (tx, rx) = futures::channel::oneshot::channel();
+		sink.send(EngineCommand::SealNewBlocks {
+			count: 3,
+			create_empty: true,
+			finalize: false,
+			parent_hash: None,
+			sender: Some(tx),
+		}).await.unwrap();
+		let created = rx.await.unwrap().unwrap();
+		assert_eq!(created.len(), 3);
+		for (number, block) in created.iter().enumerate() {
+			let header = client.header(&BlockId::Number(number as u64 + 1)).unwrap().unwrap();
+			assert_eq!(header.hash(), block.hash);
+		}
+
+		// every sealed block advanced the timestamp.
+		let (tx, rx) = futures::channel::oneshot::channel();
+		sink.send(EngineCommand::AdvanceTimestamp { delta: 0, sender: Some(tx) }).await.unwrap();
+		assert_eq!(rx.await.unwrap().unwrap(), 1_006_000);
+
+		// fork off the first block.
+		let (tx, rx) = futures::channel::oneshot::channel();
+		sink.send(EngineCommand::SealNewBlocks {
+			count: 2,
+			create_empty: true,
+			finalize: false,
+			parent_hash: Some(created[0].hash),
+			sender: Some(tx),
+		}).await.unwrap();
+		let fork = rx.await.unwrap().unwrap();
+		assert_eq!(fork.len(), 2);
+		assert!(fork.iter().all(|block| created.iter().all(|c| c.hash != block.hash)));
+		let fork_header = client.header(&BlockId::Hash(fork[1].hash)).unwrap().unwrap();
+		assert_eq!(fork_header.number, 3);
+
+		// blocks below the last finalized block can't be forked off.
+		let (tx, rx) = futures::channel::oneshot::channel();
+		sink.send(EngineCommand::FinalizeBlock {
+			sender: Some(tx),
+			hash: created[2].hash,
+			justification: None,
+		}).await.unwrap();
+		assert!(rx.await.unwrap().is_ok());
+		let (tx, rx) = futures::channel::oneshot::channel();
+		sink.send(EngineCommand::SealNewBlock {
+			parent_hash: Some(created[0].hash),
+			sender: Some(tx),
+			create_empty: true,
+			finalize: false,
+		}).await.unwrap();
+		assert_matches::assert_matches!(rx.await.unwrap(), Err(Error::ParentFinalized(_)));
+
+		// too many blocks at once are rejected.
+		let (tx, rx) = futures::channel::oneshot::channel();
+		sink.send(EngineCommand::SealNewBlocks {
+			count: MAX_BLOCKS_PER_REQUEST + 1,
+			create_empty: true,
+			finalize: false,
+			parent_hash: None,
+			sender: Some(tx),
+		}).await.unwrap();
+		assert_matches::assert_matches!(rx.await.unwrap(), Err(Error::TooManyBlocks { .. }));
+	}
+
+	#[tokio::test]
+	async fn manual_seal_bulk_blocks_report_partial_failures() {
+		let builder = TestClientBuilder::new();
+		let (client, select_chain) = builder.build_with_longest_chain();
+		let client = Arc::new(client);
+		let inherent_data_providers = InherentDataProviders::new();
+		let spawner = sp_core::testing::TaskExecutor::new();
+		let pool = Arc::new(BasicPool::with_revalidation_type(
+			Options::default(), api(), None, RevalidationType::Full, spawner,
+		));
+		let env = ProposerFactory::new(
+			client.clone(),
+			pool.clone(),
+			None,
+		);
+		let (mut sink, commands_stream) = futures::channel::mpsc::channel(1024);
+		let future = run_manual_seal(
+			ManualSealParams {
+				block_import: client.clone(),
+				env,
+				client: client.clone(),
+				pool: pool.pool().clone(),
+				commands_stream,
+				select_chain,
+				consensus_data_provider: None,
+				inherent_data_providers,
+			}
+		);
+		std::thread::spawn(|| {
+			let mut rt = tokio::runtime::Runtime::new().unwrap();
+			// spawn the background authorship task
+			rt.block_on(future);
+		});
+		// a single transaction fills the first block only.
+		assert!(pool.submit_one(&BlockId::Number(0), SOURCE, uxt(Alice, 0)).await.is_ok());
+
+		let (tx, rx) = futures::channel::oneshot::channel();
+		sink.send(EngineCommand::SealNewBlocks {
+			count: 2,
+			create_empty: false,
+			finalize: false,
+			parent_hash: None,
+			sender: Some(tx),
+		}).await.unwrap();
+		let error = rx.await.unwrap().unwrap_err();
+		let header = client.header(&BlockId::Number(1)).unwrap().unwrap();
+		match error {
+			Error::PartiallySealed { ref sealed, error: ref cause } => {
+				assert_eq!(sealed.len(), 1);
+				assert_eq!(
+					sealed[0]["hash"],
+					jsonrpc_core::serde_json::to_value(header.hash()).unwrap(),
+				);
+				assert_matches::assert_matches!(**cause, Error::EmptyTransactionPool);
+			},
+			other => panic!("Unexpected error: {:?}", other),
+		}
+
+		// the sealed blocks are part of the rpc error.
+		let error = jsonrpc_core::Error::from(error);
+		assert_eq!(error.data.and_then(|data| data.as_array().map(|blocks| blocks.len())), Some(1));
+	}
+
+	#[tokio::test]
+	async fn idle_seal_waits_for_idle_pool() {
+		let (mut notifications, receiver) = futures::channel::mpsc::unbounded::<()>();
+		let mut commands = idle_seal_commands::<sp_core::H256, _>(
+			receiver,
+			std::time::Duration::from_millis(200),
+		);
+
+		// nothing is sealed without transactions.
+		assert!(futures::poll!(commands.next()).is_pending());
+
+		notifications.send(()).await.unwrap();
+		let sealing = commands.next();
+		futures::pin_mut!(sealing);
+		// a transaction imported before the interval elapsed postpones sealing.
+		Delay::new(std::time::Duration::from_millis(100)).await;
+		assert!(futures::poll!(&mut sealing).is_pending());
+		notifications.send(()).await.unwrap();
+		Delay::new(std::time::Duration::from_millis(100)).await;
+		assert!(futures::poll!(&mut sealing).is_pending());
+
+		assert_matches::assert_matches!(
+			sealing.await,
+			Some(EngineCommand::SealNewBlock { create_empty: false, sender: None, .. })
+		);
+
+		// the stream ends with the notifications.
+		drop(notifications);
+		assert!(commands.next().await.is_none());
+	}
 }
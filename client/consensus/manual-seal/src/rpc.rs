@@ -53,6 +53,23 @@ pub enum EngineCommand<Hash> {
 		/// sender to report errors/success to the rpc.
 		sender: Sender<CreatedBlock<Hash>>,
 	},
+	/// Tells the engine to propose `count` blocks, each on top of the previous one.
+	///
+	/// Sealing stops at the first block that fails, the blocks sealed before stay imported and
+	/// are reported with the error as `Error::PartiallySealed`.
+	SealNewBlocks {
+		/// number of blocks to create.
+		count: u32,
+		/// if true, empty blocks(without extrinsics) will be created.
+		/// otherwise, will return Error::EmptyTransactionPool.
+		create_empty: bool,
+		/// instantly finalize the blocks?
+		finalize: bool,
+		/// specify the parent hash of the first block, e.g. to create a fork.
+		parent_hash: Option<Hash>,
+		/// sender to report errors/success to the rpc.
+		sender: Sender<Vec<CreatedBlock<Hash>>>,
+	},
 	/// Tells the engine to finalize the block with the supplied hash
 	FinalizeBlock {
 		/// hash of the block
@@ -61,7 +78,23 @@ pub enum EngineCommand<Hash> {
 		sender: Sender<()>,
 		/// finalization justification
 		justification: Option<Justification>,
-	}
+	},
+	/// Tells the engine to use the supplied timestamp for the next block.
+	SetTimestamp {
+		/// timestamp in milliseconds since the unix epoch.
+		timestamp: u64,
+		/// milliseconds to advance the timestamp by for every sealed block.
+		step: Option<u64>,
+		/// sender to report errors/success to the rpc.
+		sender: Sender<()>,
+	},
+	/// Tells the engine to advance the timestamp of the next block.
+	AdvanceTimestamp {
+		/// milliseconds to advance the timestamp by.
+		delta: u64,
+		/// sender to report the new timestamp to the rpc.
+		sender: Sender<u64>,
+	},
 }
 
 /// RPC trait that provides methods for interacting with the manual-seal authorship task over rpc.
@@ -76,6 +109,20 @@ pub trait ManualSealApi<Hash> {
 		parent_hash: Option<Hash>
 	) -> FutureResult<CreatedBlock<Hash>>;
 
+	/// Instructs the manual-seal authorship task to create `count` blocks in a row.
+	///
+	/// The first block is built on `parent_hash` if given, which can be any block that is not
+	/// below the last finalized block. If sealing fails after some blocks were created, the `data`
+	/// of the error lists them.
+	#[rpc(name = "engine_createBlocks")]
+	fn create_blocks(
+		&self,
+		count: u32,
+		create_empty: bool,
+		finalize: bool,
+		parent_hash: Option<Hash>
+	) -> FutureResult<Vec<CreatedBlock<Hash>>>;
+
 	/// Instructs the manual-seal authorship task to finalize a block
 	#[rpc(name = "engine_finalizeBlock")]
 	fn finalize_block(
@@ -83,6 +130,18 @@ pub trait ManualSealApi<Hash> {
 		hash: Hash,
 		justification: Option<Justification>
 	) -> FutureResult<bool>;
+
+	/// Sets the timestamp (in milliseconds) of the next sealed block.
+	///
+	/// Every following block advances the timestamp by `step` milliseconds.
+	#[rpc(name = "engine_setTimestamp")]
+	fn set_timestamp(&self, timestamp: u64, step: Option<u64>) -> FutureResult<bool>;
+
+	/// Advances the timestamp of the next sealed block by `delta` milliseconds.
+	///
+	/// Returns the new timestamp of the next block.
+	#[rpc(name = "engine_advanceTimestamp")]
+	fn advance_timestamp(&self, delta: u64) -> FutureResult<u64>;
 }
 
 /// A struct that implements the [`ManualSealApi`].
@@ -129,6 +188,30 @@ impl<Hash: Send + 'static> ManualSealApi<Hash> for ManualSeal<Hash> {
 		Box::new(future.map_err(Error::from).compat())
 	}
 
+	fn create_blocks(
+		&self,
+		count: u32,
+		create_empty: bool,
+		finalize: bool,
+		parent_hash: Option<Hash>
+	) -> FutureResult<Vec<CreatedBlock<Hash>>> {
+		let mut sink = self.import_block_channel.clone();
+		let future = async move {
+			let (sender, receiver) = oneshot::channel();
+			let command = EngineCommand::SealNewBlocks {
+				count,
+				create_empty,
+				finalize,
+				parent_hash,
+				sender: Some(sender),
+			};
+			sink.send(command).await?;
+			receiver.await?
+		}.boxed();
+
+		Box::new(future.map_err(Error::from).compat())
+	}
+
 	fn finalize_block(&self, hash: Hash, justification: Option<Justification>) -> FutureResult<bool> {
 		let mut sink = self.import_block_channel.clone();
 		let future = async move {
@@ -142,6 +225,32 @@ impl<Hash: Send + 'static> ManualSealApi<Hash> for ManualSeal<Hash> {
 
 		Box::new(future.boxed().map_err(Error::from).compat())
 	}
+
+	fn set_timestamp(&self, timestamp: u64, step: Option<u64>) -> FutureResult<bool> {
+		let mut sink = self.import_block_channel.clone();
+		let future = async move {
+			let (sender, receiver) = oneshot::channel();
+			sink.send(
+				EngineCommand::SetTimestamp { timestamp, step, sender: Some(sender) }
+			).await?;
+
+			receiver.await?.map(|_| true)
+		};
+
+		Box::new(future.boxed().map_err(Error::from).compat())
+	}
+
+	fn advance_timestamp(&self, delta: u64) -> FutureResult<u64> {
+		let mut sink = self.import_block_channel.clone();
+		let future = async move {
+			let (sender, receiver) = oneshot::channel();
+			sink.send(EngineCommand::AdvanceTimestamp { delta, sender: Some(sender) }).await?;
+
+			receiver.await?
+		};
+
+		Box::new(future.boxed().map_err(Error::from).compat())
+	}
 }
 
 /// report any errors or successes encountered by the authorship task back
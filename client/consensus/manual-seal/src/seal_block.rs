@@ -37,6 +37,9 @@ use sp_api::{ProvideRuntimeApi, TransactionFor};
 /// max duration for creating a proposal in secs
 pub const MAX_PROPOSAL_DURATION: u64 = 10;
 
+/// max number of blocks that can be sealed by a single command
+pub const MAX_BLOCKS_PER_REQUEST: u32 = 1_000;
+
 /// params for sealing a new block
 pub struct SealBlockParams<'a, B: BlockT, BI, SC, C: ProvideRuntimeApi<B>, E, P: txpool::ChainApi> {
 	/// if true, empty blocks(without extrinsics) will be created.
@@ -62,10 +65,31 @@ pub struct SealBlockParams<'a, B: BlockT, BI, SC, C: ProvideRuntimeApi<B>, E, P:
 	pub block_import: &'a mut BI,
 	/// inherent data provider
 	pub inherent_data_provider: &'a InherentDataProviders,
+	/// overrides the timestamp inherent provided by `inherent_data_provider`.
+	pub timestamp: Option<u64>,
 }
 
 /// seals a new block with the given params
-pub async fn seal_block<B, BI, SC, C, E, P>(
+pub async fn seal_block<B, BI, SC, C, E, P>(mut params: SealBlockParams<'_, B, BI, SC, C, E, P>)
+	where
+		B: BlockT,
+		BI: BlockImport<B, Error = sp_consensus::Error, Transaction = sp_api::TransactionFor<C, B>>
+			+ Send + Sync + 'static,
+		C: HeaderBackend<B> + ProvideRuntimeApi<B>,
+		E: Environment<B>,
+		<E as Environment<B>>::Error: std::fmt::Display,
+		<E::Proposer as Proposer<B>>::Error: std::fmt::Display,
+		P: txpool::ChainApi<Block=B>,
+		SC: SelectChain<B>,
+{
+	let mut sender = params.sender.take();
+	let result = try_seal_block(params).await;
+	rpc::send_result(&mut sender, result)
+}
+
+/// seals a new block with the given params and returns the result instead of reporting it
+/// to the `sender` of the params.
+pub async fn try_seal_block<B, BI, SC, C, E, P>(
 	SealBlockParams {
 		create_empty,
 		finalize,
@@ -77,10 +101,10 @@ pub async fn seal_block<B, BI, SC, C, E, P>(
 		env,
 		inherent_data_provider,
 		consensus_data_provider: digest_provider,
-		mut sender,
+		timestamp,
 		..
 	}: SealBlockParams<'_, B, BI, SC, C, E, P>
-)
+) -> Result<CreatedBlock<<B as BlockT>::Hash>, Error>
 	where
 		B: BlockT,
 		BI: BlockImport<B, Error = sp_consensus::Error, Transaction = sp_api::TransactionFor<C, B>>
@@ -92,59 +116,63 @@ pub async fn seal_block<B, BI, SC, C, E, P>(
 		P: txpool::ChainApi<Block=B>,
 		SC: SelectChain<B>,
 {
-	let future = async {
-		if pool.validated_pool().status().ready == 0 && !create_empty {
-			return Err(Error::EmptyTransactionPool)
-		}
-
-		// get the header to build this new block on.
-		// use the parent_hash supplied via `EngineCommand`
-		// or fetch the best_block.
-		let parent = match parent_hash {
-			Some(hash) => {
-				match client.header(BlockId::Hash(hash))? {
-					Some(header) => header,
-					None => return Err(Error::BlockNotFound(format!("{}", hash))),
-				}
+	if pool.validated_pool().status().ready == 0 && !create_empty {
+		return Err(Error::EmptyTransactionPool)
+	}
+
+	// get the header to build this new block on.
+	// use the parent_hash supplied via `EngineCommand`
+	// or fetch the best_block.
+	let parent = match parent_hash {
+		Some(hash) => {
+			match client.header(BlockId::Hash(hash))? {
+				Some(header) => header,
+				None => return Err(Error::BlockNotFound(format!("{}", hash))),
 			}
-			None => select_chain.best_chain()?
-		};
-
-		let proposer = env.init(&parent)
-			.map_err(|err| Error::StringError(format!("{}", err))).await?;
-		let id = inherent_data_provider.create_inherent_data()?;
-		let inherents_len = id.len();
-
-		let digest = if let Some(digest_provider) = digest_provider {
-			digest_provider.create_digest(&parent, &id)?
-		} else {
-			Default::default()
-		};
-
-		let proposal = proposer.propose(id.clone(), digest, Duration::from_secs(MAX_PROPOSAL_DURATION), false.into())
-			.map_err(|err| Error::StringError(format!("{}", err))).await?;
-
-		if proposal.block.extrinsics().len() == inherents_len && !create_empty {
-			return Err(Error::EmptyTransactionPool)
-		}
-
-		let (header, body) = proposal.block.deconstruct();
-		let mut params = BlockImportParams::new(BlockOrigin::Own, header.clone());
-		params.body = Some(body);
-		params.finalized = finalize;
-		params.fork_choice = Some(ForkChoiceStrategy::LongestChain);
-
-		if let Some(digest_provider) = digest_provider {
-			digest_provider.append_block_import(&parent, &mut params, &id)?;
 		}
+		None => select_chain.best_chain()?
+	};
 
-		match block_import.import_block(params, HashMap::new())? {
-			ImportResult::Imported(aux) => {
-				Ok(CreatedBlock { hash: <B as BlockT>::Header::hash(&header), aux })
-			},
-			other => Err(other.into()),
-		}
+	// forks can be created on top of any block that is not below the last finalized one.
+	if *parent.number() < client.info().finalized_number {
+		return Err(Error::ParentFinalized(format!("{}", parent.hash())))
+	}
+
+	let proposer = env.init(&parent)
+		.map_err(|err| Error::StringError(format!("{}", err))).await?;
+	let mut id = inherent_data_provider.create_inherent_data()?;
+	if let Some(timestamp) = timestamp {
+		id.replace_data(sp_timestamp::INHERENT_IDENTIFIER, &timestamp);
+	}
+	let inherents_len = id.len();
+
+	let digest = if let Some(digest_provider) = digest_provider {
+		digest_provider.create_digest(&parent, &id)?
+	} else {
+		Default::default()
 	};
 
-	rpc::send_result(&mut sender, future.await)
+	let proposal = proposer.propose(id.clone(), digest, Duration::from_secs(MAX_PROPOSAL_DURATION), false.into())
+		.map_err(|err| Error::StringError(format!("{}", err))).await?;
+
+	if proposal.block.extrinsics().len() == inherents_len && !create_empty {
+		return Err(Error::EmptyTransactionPool)
+	}
+
+	let (header, body) = proposal.block.deconstruct();
+	let mut params = BlockImportParams::new(BlockOrigin::Own, header.clone());
+	params.body = Some(body);
+	params.finalized = finalize;
+	params.fork_choice = Some(ForkChoiceStrategy::LongestChain);
+
+	if let Some(digest_provider) = digest_provider {
+		digest_provider.append_block_import(&parent, &mut params, &id)?;
+	}
+
+	match block_import.import_block(params, HashMap::new())? {
+		ImportResult::Imported(aux) => {
+			Ok(CreatedBlock { hash: <B as BlockT>::Header::hash(&header), aux })
+		},
+		other => Err(other.into()),
+	}
 }
@@ -0,0 +1,104 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Deterministic control over the timestamp inherent of the sealed blocks.
+
+use crate::Error;
+
+/// The default amount of milliseconds the timestamp advances by per sealed block.
+pub const DEFAULT_TIMESTAMP_STEP: u64 = 6_000;
+
+/// Timestamp of the blocks sealed by the engine.
+///
+/// As long as no timestamp was set, the timestamp inherent is left to the inherent data
+/// providers. Once set, every sealed block uses the managed timestamp, which then advances by
+/// `step` milliseconds for the next block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ManualTimestamp {
+	/// The timestamp of the next block, `None` if not under manual control.
+	next: Option<u64>,
+	/// The amount of milliseconds to advance by per sealed block.
+	step: u64,
+}
+
+impl Default for ManualTimestamp {
+	fn default() -> Self {
+		ManualTimestamp {
+			next: None,
+			step: DEFAULT_TIMESTAMP_STEP,
+		}
+	}
+}
+
+impl ManualTimestamp {
+	/// Use `timestamp` for the next block.
+	///
+	/// If `step` is given, it replaces the amount of milliseconds added per sealed block.
+	pub fn set(&mut self, timestamp: u64, step: Option<u64>) {
+		self.next = Some(timestamp);
+		if let Some(step) = step {
+			self.step = step;
+		}
+	}
+
+	/// Advance the timestamp of the next block by `delta` milliseconds.
+	///
+	/// Returns the new timestamp of the next block, or an error if the timestamp was not set.
+	pub fn advance(&mut self, delta: u64) -> Result<u64, Error> {
+		let next = self.next
+			.ok_or(Error::TimestampNotSet)?
+			.checked_add(delta)
+			.ok_or_else(|| Error::StringError("Timestamp overflow".into()))?;
+		self.next = Some(next);
+		Ok(next)
+	}
+
+	/// The timestamp to use for the next block, if under manual control.
+	pub fn next(&self) -> Option<u64> {
+		self.next
+	}
+
+	/// Note that a block was sealed with the current timestamp.
+	pub fn note_sealed(&mut self) {
+		self.next = self.next.map(|next| next.saturating_add(self.step));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn timestamp_advances_per_block() {
+		let mut timestamp = ManualTimestamp::default();
+		assert!(timestamp.advance(1_000).is_err());
+		timestamp.note_sealed();
+		assert_eq!(timestamp.next(), None);
+
+		timestamp.set(10_000, Some(2_000));
+		assert_eq!(timestamp.next(), Some(10_000));
+		timestamp.note_sealed();
+		assert_eq!(timestamp.next(), Some(12_000));
+		assert_eq!(timestamp.advance(500).unwrap(), 12_500);
+
+		// the step is kept if not given.
+		timestamp.set(20_000, None);
+		timestamp.note_sealed();
+		assert_eq!(timestamp.next(), Some(22_000));
+	}
+}
@@ -0,0 +1,204 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! A reference Blake2-256 proof-of-work algorithm.
+//!
+//! The work of a seal is the Blake2-256 hash of the difficulty, the pre-hash and a nonce. A seal
+//! is valid when its work, interpreted as a big-endian number, multiplied by the difficulty does
+//! not overflow 256 bits. The difficulty itself is read from the runtime through
+//! [`DifficultyApi`], so that retargeting can be done on-chain.
+//!
+//! This algorithm is meant for development and test networks: it is not memory-hard, and is
+//! therefore trivially accelerated by dedicated hardware.
+
+use std::{marker::PhantomData, sync::Arc};
+use codec::{Encode, Decode};
+use sp_api::ProvideRuntimeApi;
+use sp_core::{H256, U256, hashing::blake2_256};
+use sp_consensus_pow::{DifficultyApi, Seal as RawSeal};
+use sp_runtime::generic::BlockId;
+use sp_runtime::traits::Block as BlockT;
+
+use crate::{Error, PowAlgorithm};
+
+/// Check whether the given hash satisfies the given difficulty.
+///
+/// The test is that `hash * difficulty` does not overflow 256 bits.
+pub fn hash_meets_difficulty(hash: &H256, difficulty: U256) -> bool {
+	let num_hash = U256::from(&hash[..]);
+	let (_, overflowed) = num_hash.overflowing_mul(difficulty);
+
+	!overflowed
+}
+
+/// A seal of the Blake2 algorithm.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, Debug)]
+pub struct Blake2Seal {
+	/// Difficulty the seal was mined at.
+	pub difficulty: U256,
+	/// Resulting work hash.
+	pub work: H256,
+	/// Nonce used to produce the work.
+	pub nonce: U256,
+}
+
+/// The input hashed to produce the work of a seal.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, Debug)]
+pub struct Compute {
+	/// Difficulty the work is computed for.
+	pub difficulty: U256,
+	/// Pre-hash of the block being sealed.
+	pub pre_hash: H256,
+	/// Nonce.
+	pub nonce: U256,
+}
+
+impl Compute {
+	/// Compute the work and turn it into a seal.
+	pub fn compute(self) -> Blake2Seal {
+		let work = H256::from(blake2_256(&self.encode()));
+
+		Blake2Seal {
+			difficulty: self.difficulty,
+			work,
+			nonce: self.nonce,
+		}
+	}
+}
+
+/// Try `rounds` consecutive nonces starting at `start_nonce`, returning the first valid seal.
+pub fn mine(
+	pre_hash: &H256,
+	difficulty: U256,
+	start_nonce: U256,
+	rounds: usize,
+) -> Option<Blake2Seal> {
+	let mut nonce = start_nonce;
+
+	for _ in 0..rounds {
+		let seal = Compute { difficulty, pre_hash: *pre_hash, nonce }.compute();
+		if hash_meets_difficulty(&seal.work, difficulty) {
+			return Some(seal)
+		}
+		nonce = nonce.overflowing_add(U256::one()).0;
+	}
+
+	None
+}
+
+/// A Blake2-256 proof-of-work algorithm reading its difficulty from the runtime.
+pub struct Blake2Algorithm<B, C> {
+	client: Arc<C>,
+	_marker: PhantomData<B>,
+}
+
+impl<B, C> Blake2Algorithm<B, C> {
+	/// Create a new instance of the algorithm, reading difficulty through the given client.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: PhantomData }
+	}
+}
+
+impl<B, C> Clone for Blake2Algorithm<B, C> {
+	fn clone(&self) -> Self {
+		Self::new(self.client.clone())
+	}
+}
+
+impl<B, C> PowAlgorithm<B> for Blake2Algorithm<B, C> where
+	B: BlockT<Hash = H256>,
+	C: ProvideRuntimeApi<B>,
+	C::Api: DifficultyApi<B, U256>,
+{
+	type Difficulty = U256;
+
+	fn difficulty(&self, parent: B::Hash) -> Result<U256, Error<B>> {
+		self.client.runtime_api().difficulty(&BlockId::Hash(parent))
+			.map_err(|e| Error::Environment(
+				format!("Fetching difficulty from runtime failed: {:?}", e)
+			))
+	}
+
+	fn preliminary_verify(
+		&self,
+		pre_hash: &H256,
+		seal: &RawSeal,
+	) -> Result<Option<bool>, Error<B>> {
+		let seal = match Blake2Seal::decode(&mut &seal[..]) {
+			Ok(seal) => seal,
+			Err(_) => return Ok(Some(false)),
+		};
+
+		Ok(Some(is_valid_seal(pre_hash, &seal, seal.difficulty)))
+	}
+
+	fn verify(
+		&self,
+		_parent: &BlockId<B>,
+		pre_hash: &H256,
+		_pre_digest: Option<&[u8]>,
+		seal: &RawSeal,
+		difficulty: U256,
+	) -> Result<bool, Error<B>> {
+		let seal = match Blake2Seal::decode(&mut &seal[..]) {
+			Ok(seal) => seal,
+			Err(_) => return Ok(false),
+		};
+
+		Ok(is_valid_seal(pre_hash, &seal, difficulty))
+	}
+}
+
+fn is_valid_seal(pre_hash: &H256, seal: &Blake2Seal, difficulty: U256) -> bool {
+	if seal.difficulty != difficulty || !hash_meets_difficulty(&seal.work, difficulty) {
+		return false
+	}
+
+	let compute = Compute { difficulty, pre_hash: *pre_hash, nonce: seal.nonce };
+	compute.compute().work == seal.work
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn mined_seal_is_valid() {
+		let pre_hash = H256::repeat_byte(7);
+		let difficulty = U256::from(1_000);
+
+		let seal = mine(&pre_hash, difficulty, U256::zero(), 1_000_000)
+			.expect("difficulty is low enough to be mined quickly; qed");
+
+		assert!(is_valid_seal(&pre_hash, &seal, difficulty));
+		assert!(!is_valid_seal(&H256::repeat_byte(8), &seal, difficulty));
+		assert!(!is_valid_seal(&pre_hash, &seal, difficulty + 1));
+	}
+
+	#[test]
+	fn tampered_work_is_rejected() {
+		let pre_hash = H256::repeat_byte(1);
+		let difficulty = U256::one();
+
+		let mut seal = mine(&pre_hash, difficulty, U256::zero(), 1).unwrap();
+		seal.work = H256::zero();
+
+		assert!(hash_meets_difficulty(&seal.work, difficulty));
+		assert!(!is_valid_seal(&pre_hash, &seal, difficulty));
+	}
+}
@@ -30,8 +30,12 @@
 //! for the auxiliary storage. It is also possible to just use the runtime
 //! as the storage, but it is not recommended as it won't work well with light
 //! clients.
+//!
+//! A reference Blake2-256 algorithm reading its difficulty from the runtime's
+//! `DifficultyApi` is provided in the [`blake2`] module.
 
 mod worker;
+pub mod blake2;
 
 pub use crate::worker::{MiningWorker, MiningMetadata, MiningBuild};
 
@@ -51,7 +51,7 @@ use std::io;
 use std::collections::{HashMap, HashSet};
 
 use sc_client_api::{
-	UsageInfo, MemoryInfo, IoInfo, MemorySize,
+	UsageInfo, MemoryInfo, IoInfo, MemorySize, DatabaseInfo,
 	backend::{NewBlockState, PrunableStateChangesTrieStorage, ProvideChtRoots},
 	leaves::{LeafSet, FinalizationDisplaced}, cht,
 };
@@ -83,7 +83,7 @@ use sc_state_db::StateDb;
 use sp_blockchain::{CachedHeaderMetadata, HeaderMetadata, HeaderMetadataCache};
 use crate::storage_cache::{CachingState, SyncingCachingState, SharedCache, new_shared_cache};
 use crate::stats::StateUsageStats;
-use log::{trace, debug, warn};
+use log::{trace, debug, info, warn};
 
 // Re-export the Database trait so that one can pass an implementation of it.
 pub use sp_database::Database;
@@ -103,6 +103,10 @@ pub type DbState<B> = sp_state_machine::TrieBackend<
 >;
 
 const DB_HASH_LEN: usize = 32;
+
+/// Number of block bodies removed per database transaction when pruning the blocks of a
+/// previous run.
+const BLOCKS_PRUNING_BATCH: u64 = 10_000;
 /// Hash type that this backend uses for the database.
 pub type DbHash = [u8; DB_HASH_LEN];
 
@@ -264,10 +268,31 @@ pub struct DatabaseSettings {
 	pub state_cache_child_ratio: Option<(usize, usize)>,
 	/// Pruning mode.
 	pub pruning: PruningMode,
+	/// Block body pruning mode.
+	pub blocks_pruning: BlocksPruning,
 	/// Where to find the database.
 	pub source: DatabaseSettingsSrc,
 }
 
+/// How long to keep the bodies of blocks. Headers and justifications are always kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub enum BlocksPruning {
+	/// Keep the bodies of all blocks, including the ones on abandoned forks.
+	All,
+	/// Keep the bodies of all finalized blocks, and remove the ones on forks displaced by
+	/// finalization.
+	KeepFinalized,
+	/// Keep the bodies of the given number of latest finalized blocks, and remove the ones on
+	/// forks displaced by finalization.
+	Some(u32),
+}
+
+impl Default for BlocksPruning {
+	fn default() -> Self {
+		BlocksPruning::All
+	}
+}
+
 /// Where to find the database..
 #[derive(Debug, Clone)]
 pub enum DatabaseSettingsSrc {
@@ -318,6 +343,69 @@ impl std::fmt::Display for DatabaseSettingsSrc {
 	}
 }
 
+/// Number of entries written to the target database at once by `copy_database`.
+const COPY_BATCH: u64 = 10_000;
+
+/// Copy the full database of `source` into the new database of `target`, column by column, e.g.
+/// to switch from RocksDB to ParityDB.
+///
+/// Everything is copied, including the state of older blocks, the unfinalized blocks and the
+/// offchain storage. When the target database counts the references of the state nodes itself,
+/// the prefixed state keys of the source are rewritten along with the state database journals.
+/// The source database must be able to iterate over its keys, which ParityDB can't.
+pub fn copy_database<Block: BlockT>(
+	source: &DatabaseSettings,
+	target: &DatabaseSettings,
+) -> ClientResult<()> {
+	let unprefix = match (source.source.supports_ref_counting(), target.source.supports_ref_counting()) {
+		(true, false) => return Err(ClientError::Backend(format!(
+			"The state of a {} database can't be copied to a {} database",
+			source.source,
+			target.source,
+		))),
+		(source, target) => !source && target,
+	};
+	// The prefixed keys end with the hash of the node.
+	let unprefixed_key = |key: &[u8]| key[key.len().saturating_sub(DB_HASH_LEN)..].to_vec();
+
+	let from = utils::open_database::<Block>(source, DatabaseType::Full)?;
+	let to = utils::open_database::<Block>(target, DatabaseType::Full)?;
+	for col in 0..utils::NUM_COLUMNS {
+		let mut transaction = Transaction::new();
+		let mut copied = 0u64;
+		let mut result = Ok(());
+		from.iter_with_prefix(col, &[], &mut |key, value| {
+			if unprefix && col == columns::STATE {
+				transaction.set(col, &unprefixed_key(key), value);
+			} else {
+				transaction.set(col, key, value);
+			}
+			copied += 1;
+			if copied % COPY_BATCH == 0 {
+				result = to.commit(std::mem::take(&mut transaction));
+			}
+			result.is_ok()
+		})?;
+		result?;
+		to.commit(transaction)?;
+		info!("Copied {} entries of the {} column", copied, columns::name(col));
+	}
+
+	if unprefix {
+		let journals = sc_state_db::unprefix_journals::<Block::Hash, Vec<u8>, _>(
+			&StateMetaDb(&*from),
+			&|key: &Vec<u8>| unprefixed_key(&key[..]),
+		).map_err(|e| ClientError::from(format!("State database error: {:?}", e)))?;
+		let mut transaction = Transaction::new();
+		for (key, value) in journals {
+			transaction.set_from_vec(columns::STATE_META, &key, value);
+		}
+		to.commit(transaction)?;
+	}
+
+	Ok(())
+}
+
 pub(crate) mod columns {
 	pub const META: u32 = crate::utils::COLUMN_META;
 	pub const STATE: u32 = 1;
@@ -332,6 +420,24 @@ pub(crate) mod columns {
 	/// Offchain workers local storage
 	pub const OFFCHAIN: u32 = 9;
 	pub const CACHE: u32 = 10;
+
+	/// Name of the given column, as reported in the database statistics.
+	pub fn name(col: u32) -> &'static str {
+		match col {
+			META => "meta",
+			STATE => "state",
+			STATE_META => "state_meta",
+			KEY_LOOKUP => "key_lookup",
+			HEADER => "header",
+			BODY => "body",
+			JUSTIFICATION => "justification",
+			CHANGES_TRIE => "changes_trie",
+			AUX => "aux",
+			OFFCHAIN => "offchain",
+			CACHE => "cache",
+			_ => "unknown",
+		}
+	}
 }
 
 struct PendingBlock<Block: BlockT> {
@@ -590,21 +696,17 @@ pub struct BlockImportOperation<Block: BlockT> {
 	finalized_blocks: Vec<(BlockId<Block>, Option<Justification>)>,
 	set_head: Option<BlockId<Block>>,
 	commit_state: bool,
+	reset_storage: bool,
 }
 
 impl<Block: BlockT> BlockImportOperation<Block> {
-	fn apply_offchain(&mut self, transaction: &mut Transaction<DbHash>) {
-		for ((prefix, key), value_operation) in self.offchain_storage_updates.drain() {
-			let key: Vec<u8> = prefix
-				.into_iter()
-				.chain(sp_core::sp_std::iter::once(b'/'))
-				.chain(key.into_iter())
-				.collect();
-			match value_operation {
-				OffchainOverlayedChange::SetValue(val) => transaction.set_from_vec(columns::OFFCHAIN, &key, val),
-				OffchainOverlayedChange::Remove => transaction.remove(columns::OFFCHAIN, &key),
-			}
-		}
+	fn take_offchain_changes(&mut self) -> offchain::IndexedChanges {
+		self.offchain_storage_updates.drain()
+			.map(|((prefix, key), value_operation)| match value_operation {
+				OffchainOverlayedChange::SetValue(val) => (prefix, key, Some(val)),
+				OffchainOverlayedChange::Remove => (prefix, key, None),
+			})
+			.collect()
 	}
 
 	fn apply_aux(&mut self, transaction: &mut Transaction<DbHash>) {
@@ -683,6 +785,7 @@ impl<Block: BlockT> sc_client_api::backend::BlockImportOperation<Block> for Bloc
 		self.db_updates = transaction;
 		self.changes_trie_config_update = Some(changes_trie_config);
 		self.commit_state = true;
+		self.reset_storage = true;
 		Ok(root)
 	}
 
@@ -832,8 +935,10 @@ pub struct Backend<Block: BlockT> {
 	shared_cache: SharedCache<Block>,
 	import_lock: Arc<RwLock<()>>,
 	is_archive: bool,
-	io_stats: FrozenForDuration<(kvdb::IoStats, StateUsageInfo)>,
+	blocks_pruning: BlocksPruning,
+	io_stats: FrozenForDuration<(kvdb::IoStats, StateUsageInfo, Vec<(&'static str, u64)>)>,
 	state_usage: Arc<StateUsageStats>,
+	parity_db_path: Option<PathBuf>,
 }
 
 impl<Block: BlockT> Backend<Block> {
@@ -854,12 +959,208 @@ impl<Block: BlockT> Backend<Block> {
 			state_cache_size: 16777216,
 			state_cache_child_ratio: Some((50, 100)),
 			pruning: PruningMode::keep_blocks(keep_blocks),
+			blocks_pruning: BlocksPruning::All,
+			source: DatabaseSettingsSrc::Custom(db),
+		};
+
+		Self::new(db_setting, canonicalization_delay).expect("failed to create test-db")
+	}
+
+	/// Create new memory-backed client backend for tests, pruning block bodies.
+	#[cfg(any(test, feature = "test-helpers"))]
+	pub fn new_test_with_blocks_pruning(
+		blocks_pruning: BlocksPruning,
+		canonicalization_delay: u64,
+	) -> Self {
+		let db = kvdb_memorydb::create(crate::utils::NUM_COLUMNS);
+		let db = sp_database::as_database(db);
+		let db_setting = DatabaseSettings {
+			state_cache_size: 16777216,
+			state_cache_child_ratio: Some((50, 100)),
+			pruning: PruningMode::ArchiveAll,
+			blocks_pruning,
 			source: DatabaseSettingsSrc::Custom(db),
 		};
 
 		Self::new(db_setting, canonicalization_delay).expect("failed to create test-db")
 	}
 
+	/// Initialize an empty database from a state snapshot.
+	///
+	/// `header` must be a finalized block and `storage` its full state. The block becomes the
+	/// best and finalized block of the database, while `genesis` is stored without state or body
+	/// so that the genesis block does not get built again. `history` holds the headers of the
+	/// blocks from #1 up to the parent of `header`, which are stored without state or body so
+	/// that the consensus engines can look up the blocks their epochs and sets changed at.
+	pub fn import_state_snapshot(
+		&self,
+		genesis: Block::Header,
+		history: Vec<Block::Header>,
+		header: Block::Header,
+		justification: Option<Justification>,
+		storage: Storage,
+		aux: Vec<(Vec<u8>, Vec<u8>)>,
+	) -> ClientResult<()> {
+		if self.blockchain.meta.read().genesis_hash != Default::default() {
+			return Err(ClientError::Backend(
+				"State snapshots can only be imported into an empty database".into(),
+			));
+		}
+		if header.number().is_zero() {
+			return Err(ClientError::Backend("State snapshot must be taken above genesis".into()));
+		}
+		if storage.top.contains_key(well_known_keys::CHANGES_TRIE_CONFIG) {
+			return Err(ClientError::Backend(
+				"State snapshots are not supported with changes tries enabled".into(),
+			));
+		}
+
+		let mut operation = sc_client_api::backend::Backend::begin_operation(self)?;
+		let root = sc_client_api::backend::BlockImportOperation::reset_storage(
+			&mut operation,
+			storage,
+		)?;
+		if root != *header.state_root() {
+			return Err(ClientError::Backend(format!(
+				"State snapshot root mismatch: expected {:?}, got {:?}",
+				header.state_root(),
+				root,
+			)));
+		}
+
+		let mut transaction = Transaction::new();
+		let genesis_hash = genesis.hash();
+		let hash = header.hash();
+		let number = *header.number();
+		let parent_hash = *header.parent_hash();
+
+		let mut parent = (genesis_hash, Zero::zero());
+		for block in history.iter().chain(std::iter::once(&header)) {
+			if (*block.parent_hash(), *block.number()) != (parent.0, parent.1 + One::one()) {
+				return Err(ClientError::Backend(format!(
+					"Block #{} ({:?}) is not a child of block #{} ({:?})",
+					block.number(),
+					block.hash(),
+					parent.1,
+					parent.0,
+				)));
+			}
+			parent = (block.hash(), *block.number());
+		}
+
+		transaction.set(columns::META, meta_keys::GENESIS_HASH, genesis_hash.as_ref());
+		for block in std::iter::once(&genesis).chain(history.iter()).chain(std::iter::once(&header)) {
+			let block_hash = block.hash();
+			let block_number = *block.number();
+			let block_key = utils::number_and_hash_to_lookup_key(block_number, block_hash)?;
+			utils::insert_hash_to_key_mapping(&mut transaction, columns::KEY_LOOKUP, block_number, block_hash)?;
+			utils::insert_number_to_key_mapping(&mut transaction, columns::KEY_LOOKUP, block_number, block_hash)?;
+			transaction.set_from_vec(columns::HEADER, &block_key, block.encode());
+			if !block_number.is_zero() {
+				children::write_children(
+					&mut transaction,
+					columns::META,
+					meta_keys::CHILDREN_PREFIX,
+					*block.parent_hash(),
+					vec![block_hash],
+				);
+			}
+		}
+
+		let lookup_key = utils::number_and_hash_to_lookup_key(number, hash)?;
+		if let Some(justification) = justification {
+			transaction.set_from_vec(columns::JUSTIFICATION, &lookup_key, justification.encode());
+		}
+		transaction.set_from_vec(columns::META, meta_keys::BEST_BLOCK, lookup_key.clone());
+		transaction.set_from_vec(columns::META, meta_keys::FINALIZED_BLOCK, lookup_key);
+
+		for (key, value) in aux {
+			transaction.set_from_vec(columns::AUX, &key, value);
+		}
+
+		let mut changeset: sc_state_db::ChangeSet<Vec<u8>> = sc_state_db::ChangeSet::default();
+		for (mut key, (val, rc)) in operation.db_updates.drain() {
+			if !self.storage.prefix_keys {
+				// Strip prefix
+				key.drain(0 .. key.len() - DB_HASH_LEN);
+			};
+			for _ in 0 .. rc {
+				changeset.inserted.push((key.clone(), val.to_vec()));
+			}
+		}
+
+		let map_e = |e: sc_state_db::Error<io::Error>|
+			sp_blockchain::Error::from(format!("State database error: {:?}", e));
+		// The state database assumes the parent of the first inserted block was canonicalized.
+		let commit = self.storage.state_db.insert_block(
+			&hash,
+			number.saturated_into::<u64>(),
+			&parent_hash,
+			changeset,
+		).map_err(map_e)?;
+		apply_state_commit(&mut transaction, commit);
+		let commit = self.storage.state_db.canonicalize_block(&hash).map_err(map_e)?;
+		apply_state_commit(&mut transaction, commit);
+
+		let changes_trie_cache_ops = self.changes_tries_storage.commit(
+			&mut transaction,
+			MemoryDB::default(),
+			cache::ComplexBlockId::new(parent_hash, number - One::one()),
+			cache::ComplexBlockId::new(hash, number),
+			&header,
+			true,
+			Some(None),
+			None,
+		)?;
+
+		{
+			let mut leaves = self.blockchain.leaves.write();
+			leaves.import(hash, number, parent_hash);
+			leaves.prepare_transaction(&mut transaction, columns::META, meta_keys::LEAF_PREFIX);
+		}
+
+		self.storage.db.commit(transaction)?;
+		self.changes_tries_storage.post_commit(Some(changes_trie_cache_ops));
+
+		let header_metadata = CachedHeaderMetadata::from(&header);
+		self.blockchain.insert_header_metadata(header_metadata.hash, header_metadata);
+		self.blockchain.update_meta(genesis_hash, Zero::zero(), false, false);
+		self.blockchain.update_meta(hash, number, true, true);
+
+		Ok(())
+	}
+
+	/// Write the trie `nodes` straight into the state column and remove them again, returning
+	/// how long the write took.
+	///
+	/// Nodes already in the database are skipped, so that it is left untouched. Only meant for
+	/// benchmarking the write latency of the database, as it bypasses pruning.
+	pub fn benchmark_state_write(
+		&self,
+		mut nodes: PrefixedMemoryDB<HashFor<Block>>,
+	) -> ClientResult<std::time::Duration> {
+		let mut transaction = Transaction::new();
+		let mut revert = Transaction::new();
+		for (mut key, (value, rc)) in nodes.drain() {
+			if !self.storage.prefix_keys {
+				// Strip prefix
+				key.drain(0 .. key.len() - DB_HASH_LEN);
+			};
+			if rc <= 0 || self.storage.db.get(columns::STATE, &key).is_some() {
+				continue
+			}
+			transaction.set_from_vec(columns::STATE, &key, value);
+			revert.remove(columns::STATE, &key);
+		}
+
+		let start = std::time::Instant::now();
+		self.storage.db.commit(transaction)?;
+		let elapsed = start.elapsed();
+
+		self.storage.db.commit(revert)?;
+		Ok(elapsed)
+	}
+
 	fn from_database(
 		db: Arc<dyn Database<DbHash>>,
 		canonicalization_delay: u64,
@@ -898,7 +1199,7 @@ impl<Block: BlockT> Backend<Block> {
 			},
 		)?;
 
-		Ok(Backend {
+		let backend = Backend {
 			storage: Arc::new(storage_db),
 			offchain_storage,
 			changes_tries_storage,
@@ -910,9 +1211,85 @@ impl<Block: BlockT> Backend<Block> {
 			),
 			import_lock: Default::default(),
 			is_archive: is_archive_pruning,
+			blocks_pruning: config.blocks_pruning,
 			io_stats: FrozenForDuration::new(std::time::Duration::from_secs(1)),
 			state_usage: Arc::new(StateUsageStats::new()),
-		})
+			parity_db_path: match &config.source {
+				DatabaseSettingsSrc::ParityDb { path } => Some(path.clone()),
+				_ => None,
+			},
+		};
+
+		backend.migrate_blocks_pruning()?;
+
+		Ok(backend)
+	}
+
+	/// On-disk size of each database column, by column name.
+	///
+	/// Only ParityDB keeps the columns in separate files, other databases report no sizes.
+	fn column_sizes(&self) -> Vec<(&'static str, u64)> {
+		let path = match &self.parity_db_path {
+			Some(path) => path,
+			None => return Vec::new(),
+		};
+		match stats::parity_db_column_sizes(path) {
+			Ok(sizes) => sizes.into_iter()
+				.enumerate()
+				.map(|(col, size)| (columns::name(col as u32), size))
+				.collect(),
+			Err(e) => {
+				warn!(target: "db", "Failed to read database column sizes: {}", e);
+				Vec::new()
+			},
+		}
+	}
+
+	/// Remove the bodies that a previous run kept but the configured blocks pruning doesn't,
+	/// e.g. when it is enabled on a database created before blocks pruning, which kept every
+	/// body. The pruning mode is then recorded in the database.
+	fn migrate_blocks_pruning(&self) -> ClientResult<()> {
+		let previous = match self.storage.db.get(columns::META, meta_keys::BLOCKS_PRUNING) {
+			Some(encoded) => BlocksPruning::decode(&mut &encoded[..]).map_err(|e| {
+				ClientError::Backend(format!("Error decoding blocks pruning mode: {}", e))
+			})?,
+			None => BlocksPruning::All,
+		};
+
+		if previous == self.blocks_pruning {
+			return Ok(())
+		}
+
+		let mut transaction = Transaction::new();
+		if let BlocksPruning::Some(keep) = self.blocks_pruning {
+			let finalized = self.blockchain.meta.read().finalized_number.saturated_into::<u64>();
+			// the bodies up to `finalized - previous` were removed by the previous run.
+			let start = match previous {
+				BlocksPruning::Some(previous) => (finalized + 1).saturating_sub(previous as u64),
+				_ => 0,
+			};
+
+			if finalized >= keep as u64 && start <= finalized - keep as u64 {
+				let end = finalized - keep as u64;
+				info!(target: "db", "Removing the bodies of blocks #{} to #{}", start, end);
+
+				for number in start..=end {
+					let id = BlockId::<Block>::Number(number.saturated_into());
+					if let Some(key) = utils::block_id_to_lookup_key(&*self.storage.db, columns::KEY_LOOKUP, id)? {
+						transaction.remove(columns::BODY, &key);
+					}
+
+					if (number - start + 1) % BLOCKS_PRUNING_BATCH == 0 {
+						self.storage.db.commit(std::mem::replace(&mut transaction, Transaction::new()))?;
+					}
+				}
+			}
+		}
+
+		transaction.set_from_vec(columns::META, meta_keys::BLOCKS_PRUNING, self.blocks_pruning.encode());
+		self.storage.db.commit(transaction)?;
+
+		Ok(())
 	}
 
 	/// Handle setting head within a transaction. `route_to` should be the last
@@ -1057,6 +1434,11 @@ impl<Block: BlockT> Backend<Block> {
 						implies existence of blocks with all numbers before it; qed")
 			};
 
+			// blocks imported without state, e.g. during state sync, have nothing to canonicalize.
+			if self.storage.state_db.is_pruned(&hash, new_canonical) {
+				return Ok(())
+			}
+
 			trace!(target: "db", "Canonicalize block #{} ({:?})", new_canonical, hash);
 			let commit = self.storage.state_db.canonicalize_block(&hash)
 				.map_err(|e: sc_state_db::Error<io::Error>| sp_blockchain::Error::from(format!("State database error: {:?}", e)))?;
@@ -1074,10 +1456,12 @@ impl<Block: BlockT> Backend<Block> {
 		let mut finalization_displaced_leaves = None;
 
 		operation.apply_aux(&mut transaction);
-		operation.apply_offchain(&mut transaction);
+		let offchain_changes = operation.take_offchain_changes();
+		let mut offchain_indexer = offchain::OffchainIndexer::new(&*self.storage.db);
 
 		let mut meta_updates = Vec::with_capacity(operation.finalized_blocks.len());
 		let mut last_finalized_hash = self.blockchain.meta.read().finalized_hash;
+		let mut last_finalized_number = self.blockchain.meta.read().finalized_number;
 
 		let mut changes_trie_cache_ops = None;
 		for (block, justification) in operation.finalized_blocks {
@@ -1093,7 +1477,13 @@ impl<Block: BlockT> Backend<Block> {
 				&mut changes_trie_cache_ops,
 				&mut finalization_displaced_leaves,
 			)?);
+			offchain_indexer.finalize(
+				&mut transaction,
+				block_header.number().saturated_into::<u64>(),
+				block_hash.as_ref(),
+			);
 			last_finalized_hash = block_hash;
+			last_finalized_number = *block_header.number();
 		}
 
 		let imported = if let Some(pending_block) = operation.pending_block {
@@ -1190,18 +1580,39 @@ impl<Block: BlockT> Backend<Block> {
 				}
 				self.state_usage.tally_writes(ops, bytes);
 				let number_u64 = number.saturated_into::<u64>();
-				let commit = self.storage.state_db.insert_block(
-					&hash,
-					number_u64,
-					&pending_block.header.parent_hash(),
-					changeset,
-				).map_err(|e: sc_state_db::Error<io::Error>|
+				let commit = if operation.reset_storage && number_u64 != 0 {
+					// the full state was imported, the parent state isn't needed.
+					self.storage.state_db.insert_detached_block(
+						&hash,
+						number_u64,
+						&pending_block.header.parent_hash(),
+						changeset,
+					)
+				} else {
+					self.storage.state_db.insert_block(
+						&hash,
+						number_u64,
+						&pending_block.header.parent_hash(),
+						changeset,
+					)
+				}.map_err(|e: sc_state_db::Error<io::Error>|
 					sp_blockchain::Error::from(format!("State database error: {:?}", e))
 				)?;
 				apply_state_commit(&mut transaction, commit);
 
 				// Check if need to finalize. Genesis is always finalized instantly.
 				let finalized = number_u64 == 0 || pending_block.leaf_state.is_final();
+
+				// the state of a block that was finalized while imported without state, e.g.
+				// during state sync, is canonicalized right away.
+				if !finalized && number <= self.blockchain.meta.read().finalized_number {
+					let commit = self.storage.state_db.canonicalize_block(&hash)
+						.map_err(|e: sc_state_db::Error<io::Error>|
+							sp_blockchain::Error::from(format!("State database error: {:?}", e))
+						)?;
+					apply_state_commit(&mut transaction, commit);
+				}
+
 				finalized
 			} else {
 				false
@@ -1239,9 +1650,20 @@ impl<Block: BlockT> Backend<Block> {
 					&mut changes_trie_cache_ops,
 					&mut finalization_displaced_leaves,
 				)?;
+				offchain_indexer.finalize(&mut transaction, number.saturated_into::<u64>(), hash.as_ref());
+				offchain_indexer.apply(&mut transaction, offchain_changes);
 			} else {
 				// canonicalize blocks which are old enough, regardless of finality.
-				self.force_delayed_canonicalize(&mut transaction, hash, *header.number())?
+				self.force_delayed_canonicalize(&mut transaction, hash, *header.number())?;
+				// the changes of blocks below the finalized one could never be committed.
+				if number > last_finalized_number {
+					offchain_indexer.stage(
+						&mut transaction,
+						number.saturated_into::<u64>(),
+						hash.as_ref(),
+						offchain_changes,
+					);
+				}
 			}
 
 			debug!(target: "db", "DB Commit {:?} ({}), best = {}", hash, number, is_best);
@@ -1260,7 +1682,9 @@ impl<Block: BlockT> Backend<Block> {
 				meta_keys::CHILDREN_PREFIX,
 				parent_hash,
 			)?;
-			children.push(hash);
+			if !children.contains(&hash) {
+				children.push(hash);
+			}
 			children::write_children(
 				&mut transaction,
 				columns::META,
@@ -1295,6 +1719,7 @@ impl<Block: BlockT> Backend<Block> {
 			None
 		};
 
+		offchain_indexer.flush(&mut transaction);
 		self.storage.db.commit(transaction)?;
 
 		if let Some((
@@ -1351,9 +1776,12 @@ impl<Block: BlockT> Backend<Block> {
 			let lookup_key = utils::number_and_hash_to_lookup_key(f_num, f_hash.clone())?;
 			transaction.set_from_vec(columns::META, meta_keys::FINALIZED_BLOCK, lookup_key);
 
-			let commit = self.storage.state_db.canonicalize_block(&f_hash)
-				.map_err(|e: sc_state_db::Error<io::Error>| sp_blockchain::Error::from(format!("State database error: {:?}", e)))?;
-			apply_state_commit(transaction, commit);
+			// blocks imported without state, e.g. during state sync, have nothing to canonicalize.
+			if !self.storage.state_db.is_pruned(&f_hash, f_num.saturated_into::<u64>()) {
+				let commit = self.storage.state_db.canonicalize_block(&f_hash)
+					.map_err(|e: sc_state_db::Error<io::Error>| sp_blockchain::Error::from(format!("State database error: {:?}", e)))?;
+				apply_state_commit(transaction, commit);
+			}
 
 			if !f_num.is_zero() {
 				let new_changes_trie_cache_ops = self.changes_tries_storage.finalize(
@@ -1369,6 +1797,7 @@ impl<Block: BlockT> Backend<Block> {
 		}
 
 		let new_displaced = self.blockchain.leaves.write().finalize_height(f_num);
+		self.prune_blocks(transaction, f_num, &new_displaced)?;
 		match displaced {
 			x @ &mut None => *x = Some(new_displaced),
 			&mut Some(ref mut displaced) => displaced.merge(new_displaced),
@@ -1376,6 +1805,62 @@ impl<Block: BlockT> Backend<Block> {
 
 		Ok(())
 	}
+
+	// removes the body of the finalized block leaving the blocks pruning window, and the bodies
+	// of the forks displaced by the finalization of `finalized`.
+	fn prune_blocks(
+		&self,
+		transaction: &mut Transaction<DbHash>,
+		finalized: NumberFor<Block>,
+		displaced: &FinalizationDisplaced<Block::Hash, NumberFor<Block>>,
+	) -> ClientResult<()> {
+		if let BlocksPruning::Some(keep) = self.blocks_pruning {
+			let finalized = finalized.saturated_into::<u64>();
+			if finalized >= keep as u64 {
+				let id = BlockId::<Block>::Number((finalized - keep as u64).saturated_into());
+				if let Some(key) = utils::block_id_to_lookup_key(&*self.storage.db, columns::KEY_LOOKUP, id)? {
+					transaction.remove(columns::BODY, &key);
+				}
+			}
+		}
+
+		if self.blocks_pruning != BlocksPruning::All {
+			let mut pruned = HashSet::new();
+			for leaf in displaced.leaves() {
+				self.prune_displaced_branch(transaction, *leaf, &mut pruned)?;
+			}
+		}
+
+		Ok(())
+	}
+
+	// removes the bodies of the blocks from `leaf` down to the canonical chain. The branches of
+	// the displaced leaves fork off below the finalized block, so none of their blocks can become
+	// canonical, even those shared with other forks. `pruned` holds the blocks already removed by
+	// the walks from the other displaced leaves.
+	fn prune_displaced_branch(
+		&self,
+		transaction: &mut Transaction<DbHash>,
+		leaf: Block::Hash,
+		pruned: &mut HashSet<Block::Hash>,
+	) -> ClientResult<()> {
+		let mut hash = leaf;
+		while !pruned.contains(&hash) {
+			let header = self.blockchain.header_metadata(hash)?;
+			let canonical_hash = sc_client_api::blockchain::HeaderBackend::hash(&self.blockchain, header.number)?;
+			if canonical_hash == Some(hash) {
+				break;
+			}
+
+			trace!(target: "db", "Removing the body of displaced block #{} ({:?})", header.number, hash);
+			transaction.remove(columns::BODY, &utils::number_and_hash_to_lookup_key(header.number, hash)?);
+			pruned.insert(hash);
+
+			hash = header.parent;
+		}
+
+		Ok(())
+	}
 }
 
 fn apply_state_commit(transaction: &mut Transaction<DbHash>, commit: sc_state_db::CommitSet<Vec<u8>>) {
@@ -1441,6 +1926,7 @@ impl<Block: BlockT> sc_client_api::backend::Backend<Block> for Backend<Block> {
 			finalized_blocks: Vec::new(),
 			set_head: None,
 			commit_state: false,
+			reset_storage: false,
 		})
 	}
 
@@ -1495,6 +1981,9 @@ impl<Block: BlockT> sc_client_api::backend::Backend<Block> for Backend<Block> {
 			&mut changes_trie_cache_ops,
 			&mut displaced,
 		)?;
+		let mut offchain_indexer = offchain::OffchainIndexer::new(&*self.storage.db);
+		offchain_indexer.finalize(&mut transaction, number.saturated_into::<u64>(), hash.as_ref());
+		offchain_indexer.flush(&mut transaction);
 		self.storage.db.commit(transaction)?;
 		self.blockchain.update_meta(hash, number, is_best, is_finalized);
 		self.changes_tries_storage.post_commit(changes_trie_cache_ops);
@@ -1510,11 +1999,12 @@ impl<Block: BlockT> sc_client_api::backend::Backend<Block> for Backend<Block> {
 	}
 
 	fn usage_info(&self) -> Option<UsageInfo> {
-		let (io_stats, state_stats) = self.io_stats.take_or_else(||
+		let (io_stats, state_stats, column_sizes) = self.io_stats.take_or_else(||
 			(
 				// TODO: implement DB stats and cache size retrieval
 				kvdb::IoStats::empty(),
 				self.state_usage.take(),
+				self.column_sizes(),
 			)
 		);
 		let database_cache = MemorySize::from_bytes(0);
@@ -1542,6 +2032,9 @@ impl<Block: BlockT> sc_client_api::backend::Backend<Block> for Backend<Block> {
 				state_reads_cache: state_stats.cache_reads.ops,
 				state_writes_nodes: state_stats.nodes_writes.ops,
 			},
+			database: DatabaseInfo {
+				column_sizes,
+			},
 		})
 	}
 
@@ -1814,6 +2307,40 @@ pub(crate) mod tests {
 		header_hash
 	}
 
+	#[test]
+	fn copy_database_copies_every_column() {
+		let settings = |db: &Arc<dyn Database<DbHash>>| DatabaseSettings {
+			state_cache_size: 16777216,
+			state_cache_child_ratio: Some((50, 100)),
+			pruning: PruningMode::keep_blocks(1),
+			blocks_pruning: BlocksPruning::All,
+			source: DatabaseSettingsSrc::Custom(db.clone()),
+		};
+		let source = sp_database::as_database(kvdb_memorydb::create(crate::utils::NUM_COLUMNS));
+		let target = sp_database::as_database(kvdb_memorydb::create(crate::utils::NUM_COLUMNS));
+
+		let hashes = {
+			let backend = Backend::<Block>::new(settings(&source), 0).unwrap();
+			let mut hashes = vec![insert_header(&backend, 0, Default::default(), None, Default::default())];
+			for number in 1..3 {
+				let parent = hashes[number as usize - 1];
+				hashes.push(insert_header(&backend, number, parent, None, Default::default()));
+			}
+			backend.insert_aux(&[(&b"aux"[..], &b"value"[..])], &[]).unwrap();
+			hashes
+		};
+
+		copy_database::<Block>(&settings(&source), &settings(&target)).unwrap();
+
+		let backend = Backend::<Block>::new(settings(&target), 0).unwrap();
+		for (number, hash) in hashes.iter().enumerate() {
+			assert_eq!(backend.blockchain().hash(number as u64).unwrap(), Some(*hash));
+		}
+		assert_eq!(backend.blockchain().info().best_hash, hashes[2]);
+		assert_eq!(backend.get_aux(b"aux").unwrap(), Some(b"value".to_vec()));
+		assert!(backend.state_at(BlockId::Hash(hashes[2])).is_ok());
+	}
+
 	#[test]
 	fn block_hash_inserted_correctly() {
 		let backing = {
@@ -1860,6 +2387,7 @@ pub(crate) mod tests {
 			state_cache_size: 16777216,
 			state_cache_child_ratio: Some((50, 100)),
 			pruning: PruningMode::keep_blocks(1),
+			blocks_pruning: BlocksPruning::All,
 			source: DatabaseSettingsSrc::Custom(backing),
 		}, 0).unwrap();
 		assert_eq!(backend.blockchain().info().best_number, 9);
@@ -1956,6 +2484,149 @@ pub(crate) mod tests {
 		}
 	}
 
+	#[test]
+	fn import_state_snapshot_into_empty_db() {
+		let backend = Backend::<Block>::new_test(2, 0);
+		let genesis = Header {
+			number: 0,
+			parent_hash: Default::default(),
+			state_root: Default::default(),
+			digest: Default::default(),
+			extrinsics_root: Default::default(),
+		};
+		let storage = vec![
+			(vec![1, 3, 5], vec![2, 4, 6]),
+			(vec![1, 2, 3], vec![9, 9, 9]),
+		];
+		let mut history: Vec<Header> = Vec::new();
+		for number in 1..10 {
+			let parent_hash = history.last().map_or(genesis.hash(), |parent| parent.hash());
+			history.push(Header {
+				number,
+				parent_hash,
+				state_root: Default::default(),
+				digest: Default::default(),
+				extrinsics_root: Default::default(),
+			});
+		}
+		let header = Header {
+			number: 10,
+			parent_hash: history[8].hash(),
+			state_root: BlakeTwo256::trie_root(storage.clone()),
+			digest: Default::default(),
+			extrinsics_root: Default::default(),
+		};
+		let hash = header.hash();
+		let snapshot = || Storage {
+			top: storage.clone().into_iter().collect(),
+			children_default: Default::default(),
+		};
+
+		// a snapshot with a wrong state root is rejected without touching the db.
+		let mut wrong = header.clone();
+		wrong.state_root = Default::default();
+		assert!(backend.import_state_snapshot(
+			genesis.clone(),
+			history.clone(),
+			wrong,
+			None,
+			snapshot(),
+			vec![],
+		).is_err());
+
+		// so is a snapshot with a gap in the header history.
+		let mut gap = history.clone();
+		gap.remove(4);
+		assert!(backend.import_state_snapshot(
+			genesis.clone(),
+			gap,
+			header.clone(),
+			None,
+			snapshot(),
+			vec![],
+		).is_err());
+
+		backend.import_state_snapshot(
+			genesis.clone(),
+			history.clone(),
+			header.clone(),
+			Some(vec![1, 2, 3]),
+			snapshot(),
+			vec![(b"aux".to_vec(), vec![42])],
+		).unwrap();
+
+		let info = backend.blockchain().info();
+		assert_eq!(info.genesis_hash, genesis.hash());
+		assert_eq!((info.best_number, info.best_hash), (10, hash));
+		assert_eq!((info.finalized_number, info.finalized_hash), (10, hash));
+		assert_eq!(backend.blockchain().header(BlockId::Number(0)).unwrap(), Some(genesis.clone()));
+		for block in &history {
+			assert_eq!(backend.blockchain().header(BlockId::Hash(block.hash())).unwrap(), Some(block.clone()));
+			assert_eq!(backend.blockchain().hash(block.number).unwrap(), Some(block.hash()));
+		}
+		assert_eq!(backend.blockchain().children(history[8].hash()).unwrap(), vec![hash]);
+		assert!(sp_blockchain::tree_route(backend.blockchain(), genesis.hash(), hash).is_ok());
+		assert_eq!(backend.blockchain().justification(BlockId::Hash(hash)).unwrap(), Some(vec![1, 2, 3]));
+		assert_eq!(
+			sc_client_api::backend::AuxStore::get_aux(&backend, b"aux").unwrap(),
+			Some(vec![42]),
+		);
+
+		let state = backend.state_at(BlockId::Hash(hash)).unwrap();
+		assert_eq!(state.storage(&[1, 3, 5]).unwrap(), Some(vec![2, 4, 6]));
+		assert_eq!(state.storage(&[1, 2, 3]).unwrap(), Some(vec![9, 9, 9]));
+
+		// the chain continues from the snapshot block.
+		let child = insert_header(&backend, 11, hash, None, Default::default());
+		backend.finalize_block(BlockId::Hash(child), None).unwrap();
+		assert_eq!(backend.blockchain().info().finalized_hash, child);
+
+		// a second snapshot can't be imported on top of an existing chain.
+		assert!(backend.import_state_snapshot(genesis, history, header, None, snapshot(), vec![]).is_err());
+	}
+
+	#[test]
+	fn offchain_indexing_is_committed_on_finality() {
+		use sp_core::offchain::{OffchainStorage, STORAGE_PREFIX};
+
+		let backend = Backend::<Block>::new_test(10, 10);
+		let genesis = insert_header(&backend, 0, Default::default(), None, Default::default());
+
+		let import = |extrinsics_root: H256, key: &[u8], value: &[u8]| {
+			let header = Header {
+				number: 1,
+				parent_hash: genesis,
+				state_root: BlakeTwo256::trie_root(Vec::new()),
+				digest: Default::default(),
+				extrinsics_root,
+			};
+			let hash = header.hash();
+			let mut op = backend.begin_operation().unwrap();
+			backend.begin_state_operation(&mut op, BlockId::Hash(genesis)).unwrap();
+			op.set_block_data(header, Some(Vec::new()), None, NewBlockState::Normal).unwrap();
+			let mut changes = OffchainOverlayedChanges::enabled();
+			changes.set(STORAGE_PREFIX, key, value);
+			op.update_offchain_storage(changes).unwrap();
+			backend.commit_operation(op).unwrap();
+			hash
+		};
+		let block1_a = import(H256::from_low_u64_be(1), b"key_a", b"a");
+		import(H256::from_low_u64_be(2), b"key_b", b"b");
+
+		// nothing is visible until the block is finalized.
+		let storage = backend.offchain_storage().unwrap();
+		assert_eq!(storage.get(STORAGE_PREFIX, b"key_a"), None);
+		assert!(storage.indexed_entries(b"", None, None, 10).is_empty());
+
+		backend.finalize_block(BlockId::Hash(block1_a), None).unwrap();
+		assert_eq!(storage.get(STORAGE_PREFIX, b"key_a"), Some(b"a".to_vec()));
+		assert_eq!(storage.get(STORAGE_PREFIX, b"key_b"), None);
+		assert_eq!(
+			storage.indexed_entries(b"key", None, None, 10),
+			vec![(b"key_a".to_vec(), b"a".to_vec())],
+		);
+	}
+
 	#[test]
 	fn delete_only_when_negative_rc() {
 		sp_tracing::try_init_simple();
@@ -2404,4 +3075,119 @@ pub(crate) mod tests {
 		assert_eq!(cht_root_1, cht_root_2);
 		assert_eq!(cht_root_2, cht_root_3);
 	}
+
+	#[test]
+	fn prunes_block_bodies() {
+		use sc_client_api::blockchain::{Backend as BlockChainBackend};
+
+		let backend = Backend::<Block>::new_test_with_blocks_pruning(BlocksPruning::Some(2), 0);
+
+		let block0 = insert_header(&backend, 0, Default::default(), None, Default::default());
+		let block1 = insert_header(&backend, 1, block0, None, Default::default());
+		let fork2 = insert_header(&backend, 2, block1, None, H256::from_low_u64_be(42));
+		let block2 = insert_header(&backend, 2, block1, None, Default::default());
+		let block3 = insert_header(&backend, 3, block2, None, Default::default());
+		let block4 = insert_header(&backend, 4, block3, None, Default::default());
+		{
+			let mut op = backend.begin_operation().unwrap();
+			backend.begin_state_operation(&mut op, BlockId::Hash(block0)).unwrap();
+			op.mark_finalized(BlockId::Hash(block1), None).unwrap();
+			op.mark_finalized(BlockId::Hash(block2), None).unwrap();
+			op.mark_finalized(BlockId::Hash(block3), None).unwrap();
+			op.mark_finalized(BlockId::Hash(block4), None).unwrap();
+			backend.commit_operation(op).unwrap();
+		}
+
+		let blockchain = backend.blockchain();
+		for hash in &[block0, block1, block2, fork2] {
+			assert_eq!(blockchain.body(BlockId::Hash(*hash)).unwrap(), None);
+			assert!(blockchain.header(BlockId::Hash(*hash)).unwrap().is_some());
+		}
+		for hash in &[block3, block4] {
+			assert_eq!(blockchain.body(BlockId::Hash(*hash)).unwrap(), Some(Vec::new()));
+		}
+	}
+
+	#[test]
+	fn prunes_every_displaced_branch() {
+		use sc_client_api::blockchain::{Backend as BlockChainBackend};
+
+		let backend = Backend::<Block>::new_test_with_blocks_pruning(BlocksPruning::KeepFinalized, 0);
+
+		let mut canonical = vec![insert_header(&backend, 0, Default::default(), None, Default::default())];
+		for number in 1..5 {
+			let parent = canonical[number as usize - 1];
+			canonical.push(insert_header(&backend, number, parent, None, Default::default()));
+		}
+		// a fork shared by two displaced leaves.
+		let fork2 = insert_header(&backend, 2, canonical[1], None, H256::from_low_u64_be(42));
+		let fork3a = insert_header(&backend, 3, fork2, None, H256::from_low_u64_be(43));
+		let fork3b = insert_header(&backend, 3, fork2, None, H256::from_low_u64_be(44));
+
+		for hash in &canonical[1..] {
+			backend.finalize_block(BlockId::Hash(*hash), None).unwrap();
+		}
+
+		let blockchain = backend.blockchain();
+		for hash in &[fork2, fork3a, fork3b] {
+			assert_eq!(blockchain.body(BlockId::Hash(*hash)).unwrap(), None);
+			assert!(blockchain.header(BlockId::Hash(*hash)).unwrap().is_some());
+		}
+		for hash in &canonical {
+			assert_eq!(blockchain.body(BlockId::Hash(*hash)).unwrap(), Some(Vec::new()));
+		}
+	}
+
+	#[test]
+	fn prunes_block_bodies_kept_by_previous_run() {
+		use sc_client_api::blockchain::{Backend as BlockChainBackend};
+
+		let db = sp_database::as_database(kvdb_memorydb::create(crate::utils::NUM_COLUMNS));
+		let open = |blocks_pruning| Backend::<Block>::new(DatabaseSettings {
+			state_cache_size: 16777216,
+			state_cache_child_ratio: Some((50, 100)),
+			pruning: PruningMode::ArchiveAll,
+			blocks_pruning,
+			source: DatabaseSettingsSrc::Custom(db.clone()),
+		}, 0).unwrap();
+
+		let mut hashes = Vec::new();
+		{
+			let backend = open(BlocksPruning::All);
+			let mut parent = Default::default();
+			for number in 0..5 {
+				parent = insert_header(&backend, number, parent, None, Default::default());
+				hashes.push(parent);
+			}
+			backend.finalize_block(BlockId::Hash(hashes[1]), None).unwrap();
+			backend.finalize_block(BlockId::Hash(hashes[2]), None).unwrap();
+			backend.finalize_block(BlockId::Hash(hashes[3]), None).unwrap();
+			backend.finalize_block(BlockId::Hash(hashes[4]), None).unwrap();
+
+			for hash in &hashes {
+				assert!(backend.blockchain().body(BlockId::Hash(*hash)).unwrap().is_some());
+			}
+		}
+
+		let backend = open(BlocksPruning::Some(2));
+		let blockchain = backend.blockchain();
+		for hash in &hashes[..3] {
+			assert_eq!(blockchain.body(BlockId::Hash(*hash)).unwrap(), None);
+		}
+		for hash in &hashes[3..] {
+			assert_eq!(blockchain.body(BlockId::Hash(*hash)).unwrap(), Some(Vec::new()));
+		}
+	}
+
+	#[test]
+	fn benchmark_state_write_leaves_database_untouched() {
+		let backend = Backend::<Block>::new_test(2, 0);
+		let mut nodes = PrefixedMemoryDB::<BlakeTwo256>::default();
+		let hash = nodes.insert(EMPTY_PREFIX, b"node");
+		let key = prefixed_key::<BlakeTwo256>(&hash, EMPTY_PREFIX);
+
+		backend.benchmark_state_write(nodes).unwrap();
+
+		assert!(backend.storage.db.get(columns::STATE, &key).is_none());
+	}
 }
@@ -586,7 +586,8 @@ impl<Block> Storage<Block> for LightStorage<Block>
 				state_reads_cache: 0,
 				state_writes_cache: 0,
 				state_writes_nodes: 0,
-			}
+			},
+			database: Default::default(),
 		})
 	}
 
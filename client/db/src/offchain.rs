@@ -17,6 +17,9 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 //! RocksDB-based offchain workers local storage.
+//!
+//! Values written by the runtime through the offchain indexing API are staged per block and
+//! only written to the local storage once the block is finalized, see [`OffchainIndexer`].
 
 use std::{
 	collections::HashMap,
@@ -24,8 +27,22 @@ use std::{
 };
 
 use crate::{columns, Database, DbHash, Transaction};
+use codec::{Decode, Encode};
 use parking_lot::Mutex;
 use log::error;
+use sp_core::offchain::STORAGE_PREFIX;
+
+/// Prefix of the offchain indexing changes of a block, staged until the block is finalized.
+const STAGED_CHANGES_PREFIX: &[u8] = b":offchain_index:staged:";
+/// Prefix of the hashes of the blocks with staged changes at a given height.
+const STAGED_JOURNAL_PREFIX: &[u8] = b":offchain_index:journal:";
+/// Prefix of the index of the keys written through the offchain indexing API, with one empty
+/// entry per key, so the keys are sorted by the database.
+const INDEXED_KEYS_PREFIX: &[u8] = b":offchain_index:indexed:";
+
+/// Changes made through the offchain indexing API, as `(prefix, key, value)`. A value of `None`
+/// removes the key.
+pub type IndexedChanges = Vec<(Vec<u8>, Vec<u8>, Option<Vec<u8>>)>;
 
 /// Offchain local storage
 #[derive(Clone)]
@@ -120,6 +137,148 @@ impl sp_core::offchain::OffchainStorage for LocalStorage {
 		}
 		is_set
 	}
+
+	fn indexed_entries(
+		&self,
+		key_prefix: &[u8],
+		start_key: Option<&[u8]>,
+		end_key: Option<&[u8]>,
+		count: usize,
+	) -> Vec<(Vec<u8>, Vec<u8>)> {
+		let mut entries = Vec::new();
+		let index_prefix = indexed_key(key_prefix);
+		let result = self.db.iter_with_prefix(columns::OFFCHAIN, &index_prefix, &mut |index_key, _| {
+			let key = &index_key[INDEXED_KEYS_PREFIX.len()..];
+			if entries.len() >= count || end_key.map_or(false, |end| key >= end) {
+				return false;
+			}
+			if start_key.map_or(true, |start| key > start) {
+				if let Some(value) = self.get(STORAGE_PREFIX, key) {
+					entries.push((key.to_vec(), value));
+				}
+			}
+			true
+		});
+		if let Err(err) = result {
+			error!("Error querying indexed entries of the local storage: {}", err);
+		}
+		entries
+	}
+}
+
+/// Writes offchain indexing changes to the database as part of a block import or finalization.
+///
+/// The changes of a block are staged under its hash until the block is finalized. They are then
+/// written to the local storage, while the changes staged by the other blocks at the same height,
+/// which can no longer be finalized, are discarded. The keys written under [`STORAGE_PREFIX`]
+/// are also recorded in an index with one entry per key, which allows querying them by range.
+pub(crate) struct OffchainIndexer<'a> {
+	db: &'a dyn Database<DbHash>,
+	/// Staged block hashes by height, as modified by this commit.
+	journals: HashMap<u64, Vec<Vec<u8>>>,
+}
+
+impl<'a> OffchainIndexer<'a> {
+	/// Create a new indexer on top of the given database.
+	pub fn new(db: &'a dyn Database<DbHash>) -> Self {
+		OffchainIndexer {
+			db,
+			journals: HashMap::new(),
+		}
+	}
+
+	/// Stage the changes of the non-finalized block `hash` at height `number`.
+	pub fn stage(
+		&mut self,
+		transaction: &mut Transaction<DbHash>,
+		number: u64,
+		hash: &[u8],
+		changes: IndexedChanges,
+	) {
+		if changes.is_empty() {
+			return;
+		}
+
+		transaction.set_from_vec(columns::OFFCHAIN, &staged_changes_key(number, hash), changes.encode());
+		let journal = self.journal(number);
+		if !journal.iter().any(|h| h.as_slice() == hash) {
+			journal.push(hash.to_vec());
+		}
+	}
+
+	/// Commit the changes staged by the finalized block `hash` at height `number` and discard
+	/// the ones staged by the other blocks at this height.
+	pub fn finalize(&mut self, transaction: &mut Transaction<DbHash>, number: u64, hash: &[u8]) {
+		let staged = std::mem::replace(self.journal(number), Vec::new());
+		for staged_hash in staged {
+			let key = staged_changes_key(number, &staged_hash);
+			if staged_hash.as_slice() == hash {
+				let changes = self.db.get(columns::OFFCHAIN, &key)
+					.and_then(|changes| IndexedChanges::decode(&mut &changes[..]).ok());
+				if let Some(changes) = changes {
+					self.apply(transaction, changes);
+				}
+			}
+			transaction.remove(columns::OFFCHAIN, &key);
+		}
+	}
+
+	/// Write the changes of a finalized block to the local storage.
+	pub fn apply(&mut self, transaction: &mut Transaction<DbHash>, changes: IndexedChanges) {
+		for (prefix, key, value) in changes {
+			let db_key: Vec<u8> = prefix.iter().chain(key.iter()).cloned().collect();
+			if prefix == STORAGE_PREFIX {
+				let index_key = indexed_key(&key);
+				if value.is_some() {
+					transaction.set(columns::OFFCHAIN, &index_key, &[]);
+				} else {
+					transaction.remove(columns::OFFCHAIN, &index_key);
+				}
+			}
+			match value {
+				Some(value) => transaction.set_from_vec(columns::OFFCHAIN, &db_key, value),
+				None => transaction.remove(columns::OFFCHAIN, &db_key),
+			}
+		}
+	}
+
+	/// Write the updated journals to the transaction.
+	pub fn flush(self, transaction: &mut Transaction<DbHash>) {
+		for (number, journal) in self.journals {
+			let key = staged_journal_key(number);
+			if journal.is_empty() {
+				transaction.remove(columns::OFFCHAIN, &key);
+			} else {
+				transaction.set_from_vec(columns::OFFCHAIN, &key, journal.encode());
+			}
+		}
+	}
+
+	fn journal(&mut self, number: u64) -> &mut Vec<Vec<u8>> {
+		let db = self.db;
+		self.journals.entry(number).or_insert_with(|| {
+			db.get(columns::OFFCHAIN, &staged_journal_key(number))
+				.and_then(|journal| Decode::decode(&mut &journal[..]).ok())
+				.unwrap_or_default()
+		})
+	}
+}
+
+fn staged_changes_key(number: u64, hash: &[u8]) -> Vec<u8> {
+	let mut key = STAGED_CHANGES_PREFIX.to_vec();
+	key.extend_from_slice(&number.to_be_bytes());
+	key.extend_from_slice(hash);
+	key
+}
+
+fn staged_journal_key(number: u64) -> Vec<u8> {
+	let mut key = STAGED_JOURNAL_PREFIX.to_vec();
+	key.extend_from_slice(&number.to_be_bytes());
+	key
+}
+
+fn indexed_key(key: &[u8]) -> Vec<u8> {
+	INDEXED_KEYS_PREFIX.iter().chain(key).cloned().collect()
 }
 
 #[cfg(test)]
@@ -153,4 +312,35 @@ mod tests {
 		assert!(storage.locks.lock().is_empty(), "Locks map should be empty!");
 	}
 
+	#[test]
+	fn should_query_indexed_entries_by_range() {
+		let storage = LocalStorage::new_test();
+		let mut transaction = Transaction::new();
+		let mut indexer = OffchainIndexer::new(&*storage.db);
+		indexer.apply(&mut transaction, vec![
+			(STORAGE_PREFIX.to_vec(), b"a1".to_vec(), Some(b"1".to_vec())),
+			(STORAGE_PREFIX.to_vec(), b"a2".to_vec(), Some(b"2".to_vec())),
+			(STORAGE_PREFIX.to_vec(), b"a3".to_vec(), Some(b"3".to_vec())),
+			(STORAGE_PREFIX.to_vec(), b"b1".to_vec(), Some(b"4".to_vec())),
+			(b"other".to_vec(), b"a4".to_vec(), Some(b"5".to_vec())),
+		]);
+		indexer.flush(&mut transaction);
+		storage.db.commit(transaction).unwrap();
+
+		let keys = |entries: Vec<(Vec<u8>, Vec<u8>)>| entries.into_iter().map(|(k, _)| k).collect::<Vec<_>>();
+		assert_eq!(
+			keys(storage.indexed_entries(b"a", None, None, 2)),
+			vec![b"a1".to_vec(), b"a2".to_vec()],
+		);
+		assert_eq!(
+			keys(storage.indexed_entries(b"a", Some(b"a2"), None, 2)),
+			vec![b"a3".to_vec()],
+		);
+		assert_eq!(
+			keys(storage.indexed_entries(b"", Some(b"a1"), Some(b"b1"), 10)),
+			vec![b"a2".to_vec(), b"a3".to_vec()],
+		);
+		assert_eq!(storage.indexed_entries(b"b", None, None, 10), vec![(b"b1".to_vec(), b"4".to_vec())]);
+	}
+
 }
@@ -21,7 +21,11 @@ use sp_database::{Database, Change, ColumnId, Transaction, error::DatabaseError}
 use crate::utils::{DatabaseType, NUM_COLUMNS};
 use crate::columns;
 
-struct DbAdapter(parity_db::Db);
+struct DbAdapter {
+	db: parity_db::Db,
+	/// The reference counted column serving the hash-keyed operations, if any.
+	preimages: Option<u8>,
+}
 
 fn handle_err<T>(result: parity_db::Result<T>) -> T {
 	match result {
@@ -33,27 +37,53 @@ fn handle_err<T>(result: parity_db::Result<T>) -> T {
 }
 
 /// Wrap parity-db database into a trait object that implements `sp_database::Database`
-pub fn open<H: Clone>(path: &std::path::Path, db_type: DatabaseType)
+///
+/// The state column of a full database is reference counted and keyed by the hash of the
+/// values, so it also serves the hash-keyed `store`, `release` and `lookup` operations. Light
+/// databases have no reference counted column, they reject `store` and `release`.
+pub fn open<H: Clone + AsRef<[u8]>>(path: &std::path::Path, db_type: DatabaseType)
 	-> parity_db::Result<std::sync::Arc<dyn Database<H>>>
 {
 	let mut config = parity_db::Options::with_columns(path, NUM_COLUMNS as u8);
-	if db_type == DatabaseType::Full {
-		let mut state_col = &mut config.columns[columns::STATE as usize];
-		state_col.ref_counted = true;
-		state_col.preimage = true;
-		state_col.uniform = true;
-	}
+	let preimages = match db_type {
+		DatabaseType::Full => {
+			let mut state_col = &mut config.columns[columns::STATE as usize];
+			state_col.ref_counted = true;
+			state_col.preimage = true;
+			state_col.uniform = true;
+			Some(columns::STATE as u8)
+		},
+		DatabaseType::Light => None,
+	};
 	let db = parity_db::Db::open(&config)?;
-	Ok(std::sync::Arc::new(DbAdapter(db)))
+	Ok(std::sync::Arc::new(DbAdapter { db, preimages }))
 }
 
-impl<H: Clone> Database<H> for DbAdapter {
+impl<H: Clone + AsRef<[u8]>> Database<H> for DbAdapter {
 	fn commit(&self, transaction: Transaction<H>) -> Result<(), DatabaseError> {
-		handle_err(self.0.commit(transaction.0.into_iter().map(|change|
-			match change {
-				Change::Set(col, key, value) => (col as u8, key, Some(value)),
-				Change::Remove(col, key) => (col as u8, key, None),
-				_ => unimplemented!(),
+		let preimages = self.preimages;
+		let ref_counted = |change: &Change<H>| match change {
+			Change::Store(..) | Change::Release(..) => true,
+			Change::Set(..) | Change::Remove(..) => false,
+		};
+		if preimages.is_none() && transaction.0.iter().any(ref_counted) {
+			return Err(DatabaseError(Box::new(std::io::Error::new(
+				std::io::ErrorKind::Other,
+				"The database has no reference counted column to store preimages in",
+			))));
+		}
+
+		handle_err(self.db.commit(transaction.0.into_iter().map(|change|
+			match (change, preimages) {
+				(Change::Set(col, key, value), _) => (col as u8, key, Some(value)),
+				(Change::Remove(col, key), _) => (col as u8, key, None),
+				// Storing a preimage again only increments its reference counter.
+				(Change::Store(hash, preimage), Some(col)) =>
+					(col, hash.as_ref().to_vec(), Some(preimage)),
+				// The preimage is removed once all of its references are released.
+				(Change::Release(hash), Some(col)) => (col, hash.as_ref().to_vec(), None),
+				(Change::Store(..), None) | (Change::Release(..), None) =>
+					unreachable!("Rejected above without a reference counted column; qed"),
 			}))
 		);
 
@@ -61,10 +91,52 @@ impl<H: Clone> Database<H> for DbAdapter {
 	}
 
 	fn get(&self, col: ColumnId, key: &[u8]) -> Option<Vec<u8>> {
-		handle_err(self.0.get(col as u8, key))
+		handle_err(self.db.get(col as u8, key))
+	}
+
+	fn lookup(&self, hash: &H) -> Option<Vec<u8>> {
+		self.preimages.and_then(|col| handle_err(self.db.get(col, hash.as_ref())))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::H256;
+
+	#[test]
+	fn preimages_are_reference_counted() {
+		let dir = tempfile::tempdir().unwrap();
+		let db = open::<H256>(dir.path(), DatabaseType::Full).unwrap();
+		let hash = H256::repeat_byte(1);
+
+		let mut transaction = Transaction::new();
+		transaction.store(hash, b"preimage");
+		transaction.store(hash, b"preimage");
+		db.commit(transaction).unwrap();
+		assert_eq!(db.lookup(&hash), Some(b"preimage".to_vec()));
+
+		let mut transaction = Transaction::new();
+		transaction.release(hash);
+		db.commit(transaction).unwrap();
+		assert_eq!(db.lookup(&hash), Some(b"preimage".to_vec()));
+
+		let mut transaction = Transaction::new();
+		transaction.release(hash);
+		db.commit(transaction).unwrap();
+		assert_eq!(db.lookup(&hash), None);
 	}
 
-	fn lookup(&self, _hash: &H) -> Option<Vec<u8>> {
-		unimplemented!();
+	#[test]
+	fn light_databases_reject_preimages() {
+		let dir = tempfile::tempdir().unwrap();
+		let db = open::<H256>(dir.path(), DatabaseType::Light).unwrap();
+		let hash = H256::repeat_byte(1);
+		db.set(columns::STATE, hash.as_ref(), b"value").unwrap();
+
+		assert!(db.store(&hash, b"preimage").is_err());
+		assert!(db.release(&hash).is_err());
+		assert_eq!(db.lookup(&hash), None);
+		assert_eq!(db.get(columns::STATE, hash.as_ref()), Some(b"value".to_vec()));
 	}
 }
@@ -19,6 +19,7 @@
 //! Database usage statistics
 
 use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
+use std::{io, path::Path};
 
 /// Accumulated usage statistics for state queries.
 pub struct StateUsageStats {
@@ -138,3 +139,49 @@ impl StateUsageStats {
 		}
 	}
 }
+
+/// On-disk size in bytes of every column of the ParityDB database at `path`, by column index.
+///
+/// ParityDB keeps the index and the value tables of each column in separate files, named
+/// `index_<column>_<bits>` and `table_<column>_<tier>`.
+pub fn parity_db_column_sizes(path: &Path) -> io::Result<Vec<u64>> {
+	let mut sizes = Vec::new();
+	for entry in std::fs::read_dir(path)? {
+		let entry = entry?;
+		let name = entry.file_name();
+		let mut parts = match name.to_str() {
+			Some(name) => name.split('_'),
+			None => continue,
+		};
+		match parts.next() {
+			Some("index") | Some("table") => (),
+			_ => continue,
+		}
+		if let Some(col) = parts.next().and_then(|col| col.parse::<usize>().ok()) {
+			if sizes.len() <= col {
+				sizes.resize(col + 1, 0);
+			}
+			sizes[col] += entry.metadata()?.len();
+		}
+	}
+	Ok(sizes)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parity_db_column_sizes_sums_column_files() {
+		let dir = tempfile::tempdir().unwrap();
+		std::fs::write(dir.path().join("index_01_16"), vec![0u8; 10]).unwrap();
+		std::fs::write(dir.path().join("table_01_3"), vec![0u8; 5]).unwrap();
+		std::fs::write(dir.path().join("table_02_0"), vec![0u8; 7]).unwrap();
+		std::fs::write(dir.path().join("table_02_1"), vec![0u8; 3]).unwrap();
+		std::fs::write(dir.path().join("log0"), vec![0u8; 100]).unwrap();
+		std::fs::write(dir.path().join("metadata"), vec![0u8; 100]).unwrap();
+
+		let sizes = parity_db_column_sizes(dir.path()).unwrap();
+		assert_eq!(sizes, vec![0, 15, 10]);
+	}
+}
@@ -59,6 +59,8 @@ pub mod meta_keys {
 	pub const LEAF_PREFIX: &[u8; 4] = b"leaf";
 	/// Children prefix list key.
 	pub const CHILDREN_PREFIX: &[u8; 8] = b"children";
+	/// Blocks pruning mode the bodies were last pruned with.
+	pub const BLOCKS_PRUNING: &[u8; 8] = b"bpruning";
 }
 
 /// Database metadata.
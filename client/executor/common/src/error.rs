@@ -129,6 +129,8 @@ pub enum WasmError {
 	InvalidHeapPages,
 	/// Instantiation error.
 	Instantiation(String),
+	/// The code could not be instrumented.
+	Instrumentation(String),
 	/// Other error happenend.
 	Other(String),
 }
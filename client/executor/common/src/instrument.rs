@@ -0,0 +1,163 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Instrumentation of the runtime code that makes resource limits deterministic.
+//!
+//! The execution engines have their own, engine specific, limits for the stack and the linear
+//! memory. Hitting one of them makes the outcome of a call depend on the engine (and even on the
+//! machine) executing it. The instrumentation implemented here enforces the limits within the
+//! wasm code itself, so that every engine traps at exactly the same point.
+
+use std::borrow::Cow;
+use parity_wasm::elements::{self, External, MemoryType, Module as RawModule};
+use crate::error::WasmError;
+
+/// The maximum height of the wasm stack enforced on the runtimes that opt into it.
+///
+/// The limit is part of the consensus rules of the chain, so it is the same for every node and
+/// can't be configured. It is chosen below the stack limits of the execution engines.
+pub const MAX_STACK_HEIGHT: u32 = 64 * 1024;
+
+/// The native stack, in bytes, the compiled code may use before the engine traps on its own.
+///
+/// A unit of the wasm stack holds a value of at most 8 bytes and every frame costs at least two
+/// units, which leaves room for the return address and the saved registers of the native frame.
+/// The engine is thus never the first to trap on a runtime limited to [`MAX_STACK_HEIGHT`].
+pub const MAX_NATIVE_STACK: usize = MAX_STACK_HEIGHT as usize * 64;
+
+/// Deterministic limits enforced on the execution of the runtime code.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ExecutionLimits {
+	/// The maximum height of the wasm stack.
+	///
+	/// The height is measured in the logical units of the wasm value stack, i.e. the sum of the
+	/// number of locals, parameters and the maximal operand stack height of all active frames.
+	/// Exceeding the limit traps. `None` disables the limit.
+	pub max_stack_height: Option<u32>,
+	/// The maximum number of 64KB pages the linear memory can have beyond its initial size.
+	///
+	/// `memory.grow` fails beyond this limit. `None` keeps the maximum declared by the module.
+	pub max_heap_pages: Option<u32>,
+}
+
+impl ExecutionLimits {
+	/// The limits enforced on a runtime executed with the given number of `heap_pages`.
+	///
+	/// They only depend on the heap pages of the chain, i.e. the `:heappages` storage item or the
+	/// default, and on whether the runtime opted into the stack height limit, so every node of
+	/// the chain traps at the same point. The linear memory can't grow beyond the heap pages,
+	/// like with the interpreter.
+	pub fn deterministic(heap_pages: u64, limit_stack: bool) -> Self {
+		ExecutionLimits {
+			max_stack_height: if limit_stack { Some(MAX_STACK_HEIGHT) } else { None },
+			max_heap_pages: Some(heap_pages.min(u32::max_value() as u64) as u32),
+		}
+	}
+
+	/// Returns `true` if no limit is set.
+	pub fn is_unlimited(&self) -> bool {
+		self.max_stack_height.is_none() && self.max_heap_pages.is_none()
+	}
+}
+
+/// Instrument the given wasm `code` to enforce the given `limits`.
+///
+/// Returns the original code if no limit is set.
+pub fn instrument<'a>(code: &'a [u8], limits: &ExecutionLimits) -> Result<Cow<'a, [u8]>, WasmError> {
+	if limits.is_unlimited() {
+		return Ok(Cow::Borrowed(code));
+	}
+
+	let mut module: RawModule = elements::deserialize_buffer(code)
+		.map_err(|_| WasmError::CantDeserializeWasm)?;
+
+	if let Some(max_heap_pages) = limits.max_heap_pages {
+		limit_memory(&mut module, max_heap_pages);
+	}
+
+	if let Some(max_stack_height) = limits.max_stack_height {
+		module = pwasm_utils::stack_height::inject_limiter(module, max_stack_height)
+			.map_err(|e| WasmError::Instrumentation(
+				format!("failed to inject the stack height limiter: {:?}", e),
+			))?;
+	}
+
+	elements::serialize(module)
+		.map(Cow::Owned)
+		.map_err(|e| WasmError::Instrumentation(format!("cannot encode module: {}", e)))
+}
+
+/// Cap the maximum of the imported or declared linear memory at its initial size plus
+/// `heap_pages`.
+fn limit_memory(module: &mut RawModule, heap_pages: u32) {
+	let cap = |memory_type: &mut MemoryType| {
+		let limits = memory_type.limits();
+		let max_pages = limits.initial().saturating_add(heap_pages);
+		let maximum = limits.maximum().map_or(max_pages, |maximum| maximum.min(max_pages));
+		*memory_type = MemoryType::new(limits.initial(), Some(maximum));
+	};
+
+	if let Some(imports) = module.import_section_mut() {
+		for entry in imports.entries_mut() {
+			if let External::Memory(memory_type) = entry.external_mut() {
+				cap(memory_type);
+			}
+		}
+	}
+
+	if let Some(memories) = module.memory_section_mut() {
+		for memory_type in memories.entries_mut() {
+			cap(memory_type);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn memory_limits(code: &[u8]) -> (u32, Option<u32>) {
+		let module: RawModule = elements::deserialize_buffer(code).unwrap();
+		let entries = module.import_section().unwrap().entries();
+		entries.iter().find_map(|entry| match entry.external() {
+			External::Memory(memory_type) =>
+				Some((memory_type.limits().initial(), memory_type.limits().maximum())),
+			_ => None,
+		}).unwrap()
+	}
+
+	#[test]
+	fn unlimited_code_is_not_touched() {
+		let code = wat::parse_str(r#"(module (import "env" "memory" (memory 1)))"#).unwrap();
+		let instrumented = instrument(&code, &Default::default()).unwrap();
+		assert!(matches!(instrumented, Cow::Borrowed(_)));
+	}
+
+	#[test]
+	fn memory_maximum_is_capped() {
+		let code = wat::parse_str(r#"(module (import "env" "memory" (memory 1)))"#).unwrap();
+		let limits = ExecutionLimits { max_stack_height: None, max_heap_pages: Some(16) };
+		assert_eq!(memory_limits(&instrument(&code, &limits).unwrap()), (1, Some(17)));
+
+		let code = wat::parse_str(r#"(module (import "env" "memory" (memory 1 8)))"#).unwrap();
+		assert_eq!(memory_limits(&instrument(&code, &limits).unwrap()), (1, Some(8)));
+
+		let code = wat::parse_str(r#"(module (import "env" "memory" (memory 4)))"#).unwrap();
+		assert_eq!(memory_limits(&instrument(&code, &limits).unwrap()), (4, Some(20)));
+	}
+}
@@ -34,6 +34,19 @@ extern "C" {
 	fn yet_another_missing_external();
 }
 
+#[cfg(not(feature = "std"))]
+/// Recurse `depth` times, using a stack frame per level.
+#[inline(never)]
+fn recurse(depth: u32) -> u32 {
+	if depth == 0 {
+		return 0;
+	}
+
+	let result = recurse(depth - 1);
+	// The volatile read keeps the compiler from turning the recursion into a loop.
+	unsafe { core::ptr::read_volatile(&result) + 1 }
+}
+
 #[cfg(not(feature = "std"))]
 /// Mutable static variables should be always observed to have
 /// the initialized value at the start of a runtime call.
@@ -268,6 +281,14 @@ sp_core::wasm_export_functions! {
 		}
 	}
 
+	fn test_recursion(depth: u32) -> u32 {
+		recurse(depth)
+	}
+
+	fn test_grow_memory(pages: u32) -> i32 {
+		core::arch::wasm32::memory_grow(0, pages as usize) as i32
+	}
+
 	fn allocates_huge_stack_array(trap: bool) -> Vec<u8> {
 		// Allocate a stack frame that is approx. 75% of the stack (assuming it is 1MB).
 		// This will just decrease (stacks in wasm32-u-u grow downwards) the stack
@@ -48,6 +48,7 @@ fn call_in_wasm<E: Externalities>(
 		Some(1024),
 		HostFunctions::host_functions(),
 		8,
+		None,
 	);
 	executor.call_in_wasm(
 		&wasm_binary_unwrap()[..],
@@ -532,6 +533,7 @@ fn should_trap_when_heap_exhausted(wasm_method: WasmExecutionMethod) {
 		Some(17),  // `17` is the initial number of pages compiled into the binary.
 		HostFunctions::host_functions(),
 		8,
+		None,
 	);
 	executor.call_in_wasm(
 		&wasm_binary_unwrap()[..],
@@ -549,9 +551,11 @@ fn returns_mutable_static(wasm_method: WasmExecutionMethod) {
 	let runtime = crate::wasm_runtime::create_wasm_runtime_with_code(
 		wasm_method,
 		1024,
+		false,
 		&wasm_binary_unwrap()[..],
 		HostFunctions::host_functions(),
 		true,
+		None,
 	).expect("Creates runtime");
 
 	let instance = runtime.new_instance().unwrap();
@@ -583,9 +587,11 @@ fn restoration_of_globals(wasm_method: WasmExecutionMethod) {
 	let runtime = crate::wasm_runtime::create_wasm_runtime_with_code(
 		wasm_method,
 		REQUIRED_MEMORY_PAGES,
+		false,
 		&wasm_binary_unwrap()[..],
 		HostFunctions::host_functions(),
 		true,
+		None,
 	).expect("Creates runtime");
 	let instance = runtime.new_instance().unwrap();
 
@@ -603,9 +609,11 @@ fn heap_is_reset_between_calls(wasm_method: WasmExecutionMethod) {
 	let runtime = crate::wasm_runtime::create_wasm_runtime_with_code(
 		wasm_method,
 		1024,
+		false,
 		&wasm_binary_unwrap()[..],
 		HostFunctions::host_functions(),
 		true,
+		None,
 	).expect("Creates runtime");
 	let instance = runtime.new_instance().unwrap();
 
@@ -630,6 +638,7 @@ fn parallel_execution(wasm_method: WasmExecutionMethod) {
 		Some(1024),
 		HostFunctions::host_functions(),
 		8,
+		None,
 	));
 	let code_hash = blake2_256(wasm_binary_unwrap()).to_vec();
 	let threads: Vec<_> = (0..8).map(|_|
@@ -658,6 +667,162 @@ fn parallel_execution(wasm_method: WasmExecutionMethod) {
 	}
 }
 
+#[test_case(WasmExecutionMethod::Interpreted)]
+#[cfg_attr(feature = "wasmtime", test_case(WasmExecutionMethod::Compiled))]
+fn precompiled_runtime_is_used(wasm_method: WasmExecutionMethod) {
+	let executor = crate::WasmExecutor::new(
+		wasm_method,
+		Some(1024),
+		HostFunctions::host_functions(),
+		8,
+		None,
+	);
+	let code_hash = blake2_256(wasm_binary_unwrap()).to_vec();
+	executor.precompile_runtime(
+		&wasm_binary_unwrap()[..],
+		code_hash.clone(),
+		None,
+		sp_core::traits::MissingHostFunctions::Allow,
+	).unwrap();
+
+	let mut ext = TestExternalities::default();
+	let mut ext = ext.ext();
+	// The code passed here is garbage, so the call only succeeds if the precompiled runtime is
+	// picked up by its hash.
+	assert_eq!(
+		executor.call_in_wasm(
+			&[],
+			Some(code_hash),
+			"test_twox_128",
+			&[0],
+			&mut ext,
+			sp_core::traits::MissingHostFunctions::Allow,
+		).unwrap(),
+		hex!("99e9d85137db46ef4bbea33613baafd5").to_vec().encode(),
+	);
+}
+
+#[cfg(feature = "wasmtime")]
+#[test]
+fn compiled_runtime_is_cached_on_disk() {
+	let cache_dir = tempfile::tempdir().unwrap();
+	let code_hash = blake2_256(wasm_binary_unwrap()).to_vec();
+
+	for _ in 0..2 {
+		let executor = crate::WasmExecutor::new(
+			WasmExecutionMethod::Compiled,
+			Some(1024),
+			HostFunctions::host_functions(),
+			8,
+			Some(cache_dir.path().to_path_buf()),
+		);
+		let mut ext = TestExternalities::default();
+		let mut ext = ext.ext();
+		assert_eq!(
+			executor.call_in_wasm(
+				&wasm_binary_unwrap()[..],
+				Some(code_hash.clone()),
+				"test_twox_128",
+				&[0],
+				&mut ext,
+				sp_core::traits::MissingHostFunctions::Allow,
+			).unwrap(),
+			hex!("99e9d85137db46ef4bbea33613baafd5").to_vec().encode(),
+		);
+	}
+
+	assert!(std::fs::read_dir(cache_dir.path().join("wasmtime")).unwrap().next().is_some());
+}
+
+fn available_execution_methods() -> Vec<WasmExecutionMethod> {
+	let mut methods = vec![WasmExecutionMethod::Interpreted];
+	#[cfg(feature = "wasmtime")]
+	methods.push(WasmExecutionMethod::Compiled);
+	methods
+}
+
+fn call_in_test_runtime(
+	executor: &crate::WasmExecutor,
+	code_hash: Option<Vec<u8>>,
+	function: &str,
+	call_data: &[u8],
+) -> Result<Vec<u8>, String> {
+	let mut ext = TestExternalities::default();
+	let mut ext = ext.ext();
+	executor.call_in_wasm(
+		&wasm_binary_unwrap()[..],
+		code_hash,
+		function,
+		call_data,
+		&mut ext,
+		sp_core::traits::MissingHostFunctions::Allow,
+	)
+}
+
+#[test]
+fn stack_height_limit_is_identical_across_executors() {
+	let max_depths = available_execution_methods().into_iter().map(|wasm_method| {
+		// The test runtime has no version to opt into the limit with, so it is set directly.
+		let runtime = crate::wasm_runtime::create_wasm_runtime_with_code(
+			wasm_method,
+			1024,
+			true,
+			&wasm_binary_unwrap()[..],
+			HostFunctions::host_functions(),
+			true,
+			None,
+		).expect("Creates runtime");
+		let instance = runtime.new_instance().unwrap();
+		let recurse = |depth: u32| instance.call("test_recursion", &depth.encode());
+
+		// Find the deepest recursion that still succeeds. Every level uses at least one value of
+		// the stack, so the limit is hit before `MAX_STACK_HEIGHT` levels.
+		let (mut ok, mut trap) = (0u32, sc_executor_common::instrument::MAX_STACK_HEIGHT);
+		assert_eq!(recurse(ok).unwrap(), ok.encode());
+		assert!(recurse(trap).is_err());
+		while trap - ok > 1 {
+			let depth = ok + (trap - ok) / 2;
+			match recurse(depth) {
+				Ok(res) => {
+					assert_eq!(res, depth.encode());
+					ok = depth;
+				},
+				Err(_) => trap = depth,
+			}
+		}
+
+		// The instance must stay usable after hitting the limit.
+		assert_eq!(recurse(ok).unwrap(), ok.encode());
+		ok
+	}).collect::<Vec<_>>();
+
+	assert!(max_depths[0] > 0);
+	assert!(max_depths.iter().all(|depth| *depth == max_depths[0]), "{:?}", max_depths);
+}
+
+#[test]
+fn memory_cannot_grow_beyond_heap_pages() {
+	let results = available_execution_methods().into_iter().map(|wasm_method| {
+		[0u32, 1, 16].iter().map(|pages| {
+			let executor = crate::WasmExecutor::new(
+				wasm_method,
+				Some(8),
+				HostFunctions::host_functions(),
+				1,
+				None,
+			);
+			let res = call_in_test_runtime(&executor, None, "test_grow_memory", &pages.encode())
+				.unwrap();
+			i32::decode(&mut &res[..]).unwrap()
+		}).collect::<Vec<_>>()
+	}).collect::<Vec<_>>();
+
+	// Growing by zero pages returns the current size, anything else fails.
+	assert!(results[0][0] > 0);
+	assert_eq!(&results[0][1..], &[-1, -1]);
+	assert!(results.iter().all(|r| *r == results[0]), "{:?}", results);
+}
+
 #[test_case(WasmExecutionMethod::Interpreted)]
 fn wasm_tracing_should_work(wasm_method: WasmExecutionMethod) {
 
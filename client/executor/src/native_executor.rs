@@ -26,7 +26,7 @@ use sp_core::{
 	NativeOrEncoded, traits::{CodeExecutor, Externalities, RuntimeCode, MissingHostFunctions},
 };
 use log::trace;
-use std::{result, panic::{UnwindSafe, AssertUnwindSafe}, sync::Arc};
+use std::{result, panic::{UnwindSafe, AssertUnwindSafe}, sync::Arc, path::PathBuf};
 use sp_wasm_interface::{HostFunctions, Function};
 use sc_executor_common::wasm_runtime::WasmInstance;
 
@@ -100,21 +100,51 @@ impl WasmExecutor {
 	///
 	/// `default_heap_pages` - Number of 64KB pages to allocate for Wasm execution.
 	/// 	Defaults to `DEFAULT_HEAP_PAGES` if `None` is provided.
+	///
+	/// `host_functions` - The set of host functions to be available for import provided by this
+	/// 	executor.
+	///
+	/// `max_runtime_instances` - The number of runtime instances to keep in memory ready for reuse.
+	///
+	/// `cache_path` - A path to a directory where the executor can place its files for purposes of
+	/// 	caching compiled runtimes across restarts.
 	pub fn new(
 		method: WasmExecutionMethod,
 		default_heap_pages: Option<u64>,
 		host_functions: Vec<&'static dyn Function>,
 		max_runtime_instances: usize,
+		cache_path: Option<PathBuf>,
 	) -> Self {
 		WasmExecutor {
 			method,
 			default_heap_pages: default_heap_pages.unwrap_or(DEFAULT_HEAP_PAGES),
 			host_functions: Arc::new(host_functions),
-			cache: Arc::new(RuntimeCache::new(max_runtime_instances)),
+			cache: Arc::new(RuntimeCache::new(max_runtime_instances, cache_path)),
 			max_runtime_instances,
 		}
 	}
 
+	/// Compile the given runtime `code` and prepare its instance pool ahead of time.
+	///
+	/// The next call using a runtime with the same `code_hash` and heap pages will not need to
+	/// compile the runtime anymore. `heap_pages` defaults to the executor's default heap pages.
+	pub fn precompile_runtime(
+		&self,
+		code: &[u8],
+		code_hash: Vec<u8>,
+		heap_pages: Option<u64>,
+		missing_host_functions: MissingHostFunctions,
+	) -> Result<()> {
+		self.cache.precompile(
+			code,
+			code_hash,
+			self.method,
+			heap_pages.unwrap_or(self.default_heap_pages),
+			&*self.host_functions,
+			missing_host_functions.allowed(),
+		).map_err(Into::into)
+	}
+
 	/// Execute the given closure `f` with the latest runtime (based on `runtime_code`).
 	///
 	/// The closure `f` is expected to return `Err(_)` when there happened a `panic!` in native code
@@ -186,12 +216,16 @@ impl sp_core::traits::CallInWasm for WasmExecutor {
 				)
 			}).map_err(|e| e.to_string())
 		} else {
+			// The version of the code isn't known, so it is executed without the stack height
+			// limit, like the runtimes that don't opt into it.
 			let module = crate::wasm_runtime::create_wasm_runtime_with_code(
 				self.method,
 				self.default_heap_pages,
+				false,
 				&wasm_code,
 				self.host_functions.to_vec(),
 				allow_missing_host_functions,
+				None,
 			)
 				.map_err(|e| format!("Failed to create module: {:?}", e))?;
 
@@ -235,6 +269,18 @@ impl<D: NativeExecutionDispatch> NativeExecutor<D> {
 		fallback_method: WasmExecutionMethod,
 		default_heap_pages: Option<u64>,
 		max_runtime_instances: usize,
+	) -> Self {
+		Self::new_with_cache_path(fallback_method, default_heap_pages, max_runtime_instances, None)
+	}
+
+	/// Create new instance that persists compiled runtimes in `cache_path`.
+	///
+	/// See [`NativeExecutor::new`] for the other parameters.
+	pub fn new_with_cache_path(
+		fallback_method: WasmExecutionMethod,
+		default_heap_pages: Option<u64>,
+		max_runtime_instances: usize,
+		cache_path: Option<PathBuf>,
 	) -> Self {
 		let mut host_functions = sp_io::SubstrateHostFunctions::host_functions();
 
@@ -245,6 +291,7 @@ impl<D: NativeExecutionDispatch> NativeExecutor<D> {
 			default_heap_pages,
 			host_functions,
 			max_runtime_instances,
+			cache_path,
 		);
 
 		NativeExecutor {
@@ -253,6 +300,18 @@ impl<D: NativeExecutionDispatch> NativeExecutor<D> {
 			wasm: wasm_executor,
 		}
 	}
+
+	/// Compile the given runtime `code` ahead of its first use.
+	///
+	/// See [`WasmExecutor::precompile_runtime`].
+	pub fn precompile_runtime(
+		&self,
+		code: &[u8],
+		code_hash: Vec<u8>,
+		heap_pages: Option<u64>,
+	) -> Result<()> {
+		self.wasm.precompile_runtime(code, code_hash, heap_pages, MissingHostFunctions::Disallow)
+	}
 }
 
 impl<D: NativeExecutionDispatch> RuntimeInfo for NativeExecutor<D> {
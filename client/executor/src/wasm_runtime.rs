@@ -20,13 +20,17 @@
 //! components of the runtime that are expensive to initialize.
 
 use std::sync::Arc;
+use std::path::{Path, PathBuf};
 use crate::error::{Error, WasmError};
 use parking_lot::Mutex;
 use codec::Decode;
 use sp_core::traits::{Externalities, RuntimeCode, FetchRuntimeCode};
 use sp_version::RuntimeVersion;
 use std::panic::AssertUnwindSafe;
-use sc_executor_common::wasm_runtime::{WasmModule, WasmInstance};
+use sc_executor_common::{
+	instrument::{self, ExecutionLimits},
+	wasm_runtime::{WasmModule, WasmInstance},
+};
 
 use sp_wasm_interface::Function;
 
@@ -129,6 +133,24 @@ impl VersionedRuntime {
 	}
 }
 
+/// A runtime that was compiled ahead of its first use, e.g. right after a runtime upgrade was
+/// imported.
+///
+/// The runtime version is not known yet, since determining it requires externalities. It is
+/// resolved when the runtime is first requested and moved into the runtime cache.
+struct PrecompiledRuntime {
+	/// Runtime code hash.
+	code_hash: Vec<u8>,
+	/// Wasm runtime type.
+	wasm_method: WasmExecutionMethod,
+	/// The number of WebAssembly heap pages this runtime was created with.
+	heap_pages: u64,
+	/// Shared runtime that can spawn instances, compiled with the stack height limit.
+	module: Box<dyn WasmModule>,
+	/// Instances created ahead of time to fill the instance pool.
+	instances: Vec<Box<dyn WasmInstance>>,
+}
+
 const MAX_RUNTIMES: usize = 2;
 
 /// Cache for the runtimes.
@@ -142,22 +164,93 @@ const MAX_RUNTIMES: usize = 2;
 /// request.
 ///
 /// The size of cache is equal to `MAX_RUNTIMES`.
+///
+/// If a cache path is given, the compiled machine code of the runtimes is additionally persisted
+/// in that directory, so a restarted node does not need to compile the same runtime again.
 pub struct RuntimeCache {
 	/// A cache of runtimes along with metadata.
 	///
 	/// Runtimes sorted by recent usage. The most recently used is at the front.
 	runtimes: Mutex<[Option<Arc<VersionedRuntime>>; MAX_RUNTIMES]>,
+	/// A runtime compiled ahead of time that was not requested yet.
+	precompiled: Mutex<Option<PrecompiledRuntime>>,
 	/// The size of the instances cache for each runtime.
 	max_runtime_instances: usize,
+	/// The directory of the on-disk code cache, if any.
+	cache_path: Option<PathBuf>,
 }
 
 impl RuntimeCache {
 	/// Creates a new instance of a runtimes cache.
-	pub fn new(max_runtime_instances: usize) -> RuntimeCache {
+	///
+	/// `cache_path` - Directory to persist compiled runtimes in. Only used by the compiled
+	/// execution method.
+	pub fn new(max_runtime_instances: usize, cache_path: Option<PathBuf>) -> RuntimeCache {
 		RuntimeCache {
 			runtimes: Default::default(),
+			precompiled: Mutex::new(None),
 			max_runtime_instances,
+			cache_path,
+		}
+	}
+
+	/// Compiles the given runtime code and instantiates the instance pool for it ahead of time.
+	///
+	/// The prepared runtime is picked up by the next call to [`RuntimeCache::with_instance`]
+	/// requesting the same code hash, execution method and heap pages. Only one runtime is kept
+	/// prepared at a time, a later call replaces the previous one.
+	///
+	/// The version of the runtime is only known once it is executed, so the runtime is assumed
+	/// to opt into the stack height limit. It is compiled again otherwise.
+	pub fn precompile(
+		&self,
+		code: &[u8],
+		code_hash: Vec<u8>,
+		wasm_method: WasmExecutionMethod,
+		heap_pages: u64,
+		host_functions: &[&'static dyn Function],
+		allow_missing_func_imports: bool,
+	) -> Result<(), WasmError> {
+		let is_cached = self.runtimes.lock().iter().any(|r| r.as_ref().map_or(
+			false,
+			|r| r.wasm_method == wasm_method &&
+				r.code_hash == code_hash &&
+				r.heap_pages == heap_pages
+		));
+		if is_cached {
+			return Ok(());
 		}
+
+		#[cfg(not(target_os = "unknown"))]
+		let time = std::time::Instant::now();
+		let module = create_wasm_runtime_with_code(
+			wasm_method,
+			heap_pages,
+			true,
+			code,
+			host_functions.into(),
+			allow_missing_func_imports,
+			self.cache_path.as_deref(),
+		)?;
+		let instances = (0..self.max_runtime_instances)
+			.map(|_| module.new_instance())
+			.collect::<Result<Vec<_>, _>>()?;
+		#[cfg(not(target_os = "unknown"))]
+		log::debug!(
+			target: "wasm-runtime",
+			"Precompiled runtime with {} instances in {} ms.",
+			instances.len(),
+			time.elapsed().as_millis(),
+		);
+
+		*self.precompiled.lock() = Some(PrecompiledRuntime {
+			code_hash,
+			wasm_method,
+			heap_pages,
+			module,
+			instances,
+		});
+		Ok(())
 	}
 
 	/// Prepares a WASM module instance and executes given function for it.
@@ -220,18 +313,47 @@ impl RuntimeCache {
 				.clone()
 				.expect("`position` only returns `Some` for entries that are `Some`"),
 			None =>  {
-				let code = runtime_code.fetch_runtime_code().ok_or(WasmError::CodeNotFound)?;
-
-				let result = create_versioned_wasm_runtime(
-					&code,
-					code_hash.clone(),
+				let precompiled = {
+					let mut precompiled = self.precompiled.lock();
+					let matches = precompiled.as_ref().map_or(
+						false,
+						|p| p.wasm_method == wasm_method &&
+							p.code_hash == *code_hash &&
+							p.heap_pages == heap_pages
+					);
+					if matches { precompiled.take() } else { None }
+				};
+
+				let precompiled = precompiled.map(|precompiled| versioned_wasm_runtime_from_module(
+					precompiled.module,
+					precompiled.instances,
+					precompiled.code_hash,
 					ext,
 					wasm_method,
 					heap_pages,
-					host_functions.into(),
-					allow_missing_func_imports,
 					self.max_runtime_instances,
-				);
+				));
+
+				let result = match precompiled {
+					Some(Ok(runtime)) if limits_stack(runtime.version.as_ref()) => Ok(runtime),
+					Some(Err(e)) => Err(e),
+					_ => {
+						let code = runtime_code.fetch_runtime_code()
+							.ok_or(WasmError::CodeNotFound)?;
+
+						create_versioned_wasm_runtime(
+							&code,
+							code_hash.clone(),
+							ext,
+							wasm_method,
+							heap_pages,
+							host_functions.into(),
+							allow_missing_func_imports,
+							self.max_runtime_instances,
+							self.cache_path.as_deref(),
+						)
+					},
+				};
 				if let Err(ref err) = result {
 					log::warn!(target: "wasm-runtime", "Cannot create a runtime: {:?}", err);
 				}
@@ -261,13 +383,25 @@ impl RuntimeCache {
 }
 
 /// Create a wasm runtime with the given `code`.
+///
+/// The code is instrumented to enforce the deterministic execution limits for the given
+/// `heap_pages` before it is handed to the execution engine. The stack height is only limited
+/// if `limit_stack` is set, i.e. for the runtimes that opt into it.
+///
+/// `cache_path` - Directory of the on-disk cache for compiled code. Ignored by the interpreter.
+#[cfg_attr(not(feature = "wasmtime"), allow(unused_variables))]
 pub fn create_wasm_runtime_with_code(
 	wasm_method: WasmExecutionMethod,
 	heap_pages: u64,
+	limit_stack: bool,
 	code: &[u8],
 	host_functions: Vec<&'static dyn Function>,
 	allow_missing_func_imports: bool,
+	cache_path: Option<&Path>,
 ) -> Result<Box<dyn WasmModule>, WasmError> {
+	let code = instrument::instrument(code, &ExecutionLimits::deterministic(heap_pages, limit_stack))?;
+	let code = &code[..];
+
 	match wasm_method {
 		WasmExecutionMethod::Interpreted =>
 			sc_executor_wasmi::create_runtime(
@@ -282,7 +416,8 @@ pub fn create_wasm_runtime_with_code(
 				code,
 				heap_pages,
 				host_functions,
-				allow_missing_func_imports
+				allow_missing_func_imports,
+				cache_path,
 			).map(|runtime| -> Box<dyn WasmModule> { Box::new(runtime) }),
 	}
 }
@@ -306,6 +441,13 @@ fn decode_version(version: &[u8]) -> Result<RuntimeVersion, WasmError> {
 	}
 }
 
+/// Returns `true` if the runtime with the given `version` opted into the deterministic stack
+/// height limit, by implementing the `DeterministicStackLimit` runtime api.
+fn limits_stack(version: Option<&RuntimeVersion>) -> bool {
+	let api_id = sp_core::hashing::blake2_64(b"DeterministicStackLimit");
+	version.map_or(false, |version| version.has_api_with(&api_id, |_| true))
+}
+
 fn create_versioned_wasm_runtime(
 	code: &[u8],
 	code_hash: Vec<u8>,
@@ -315,16 +457,80 @@ fn create_versioned_wasm_runtime(
 	host_functions: Vec<&'static dyn Function>,
 	allow_missing_func_imports: bool,
 	max_instances: usize,
+	cache_path: Option<&Path>,
 ) -> Result<VersionedRuntime, WasmError> {
 	#[cfg(not(target_os = "unknown"))]
 	let time = std::time::Instant::now();
-	let mut runtime = create_wasm_runtime_with_code(
+	let module = create_wasm_runtime_with_code(
 		wasm_method,
 		heap_pages,
+		false,
 		&code,
-		host_functions,
+		host_functions.clone(),
 		allow_missing_func_imports,
+		cache_path,
 	)?;
+	let instance = module.new_instance()?;
+
+	let mut runtime = versioned_wasm_runtime_from_module(
+		module,
+		vec![instance],
+		code_hash.clone(),
+		ext,
+		wasm_method,
+		heap_pages,
+		max_instances,
+	)?;
+
+	// The version is only known once the code runs, the runtimes that opt into the stack height
+	// limit are compiled again with it.
+	if limits_stack(runtime.version.as_ref()) {
+		let module = create_wasm_runtime_with_code(
+			wasm_method,
+			heap_pages,
+			true,
+			&code,
+			host_functions,
+			allow_missing_func_imports,
+			cache_path,
+		)?;
+		runtime = versioned_wasm_runtime_from_module(
+			module,
+			Vec::new(),
+			code_hash,
+			ext,
+			wasm_method,
+			heap_pages,
+			max_instances,
+		)?;
+	}
+	#[cfg(not(target_os = "unknown"))]
+	log::debug!(
+		target: "wasm-runtime",
+		"Prepared new runtime version {:?} in {} ms.",
+		runtime.version,
+		time.elapsed().as_millis(),
+	);
+
+	Ok(runtime)
+}
+
+/// Build a `VersionedRuntime` out of a compiled `module` and already created `instances`.
+///
+/// The first instance is used to determine the runtime version. All instances are put into the
+/// instance pool, up to `max_instances`.
+fn versioned_wasm_runtime_from_module(
+	module: Box<dyn WasmModule>,
+	mut instances: Vec<Box<dyn WasmInstance>>,
+	code_hash: Vec<u8>,
+	ext: &mut dyn Externalities,
+	wasm_method: WasmExecutionMethod,
+	heap_pages: u64,
+	max_instances: usize,
+) -> Result<VersionedRuntime, WasmError> {
+	if instances.is_empty() {
+		instances.push(module.new_instance()?);
+	}
 
 	// Call to determine runtime version.
 	let version_result = {
@@ -332,31 +538,29 @@ fn create_versioned_wasm_runtime(
 		let mut ext = AssertUnwindSafe(ext);
 
 		// The following unwind safety assertion is OK because if the method call panics, the
-		// runtime will be dropped.
-		let runtime = AssertUnwindSafe(runtime.as_mut());
+		// instance will be dropped.
+		let instance = AssertUnwindSafe(&instances[0]);
 		crate::native_executor::with_externalities_safe(
 			&mut **ext,
-			move || runtime.new_instance()?.call("Core_version", &[])
+			move || instance.call("Core_version", &[])
 		).map_err(|_| WasmError::Instantiation("panic in call to get runtime version".into()))?
 	};
 	let version = match version_result {
 		Ok(version) => Some(decode_version(&version)?),
-		Err(_) => None,
+		Err(_) => {
+			// A failed call leaves the instance in an unknown state, don't reuse it.
+			instances.remove(0);
+			None
+		},
 	};
-	#[cfg(not(target_os = "unknown"))]
-	log::debug!(
-		target: "wasm-runtime",
-		"Prepared new runtime version {:?} in {} ms.",
-		version,
-		time.elapsed().as_millis(),
-	);
 
-	let mut instances = Vec::with_capacity(max_instances);
+	let mut instances = instances.into_iter().map(|i| Mutex::new(Some(i))).collect::<Vec<_>>();
+	instances.truncate(max_instances);
 	instances.resize_with(max_instances, || Mutex::new(None));
 
 	Ok(VersionedRuntime {
 		code_hash,
-		module: runtime,
+		module,
 		version,
 		heap_pages,
 		wasm_method,
@@ -424,4 +628,24 @@ mod tests {
 		let version = decode_version(&old_runtime_version.encode()).unwrap();
 		assert_eq!(3, version.transaction_version);
 	}
+
+	#[test]
+	fn only_opted_in_runtimes_limit_the_stack() {
+		let version = |apis| sp_api::RuntimeVersion {
+			spec_name: "test".into(),
+			impl_name: "test".into(),
+			authoring_version: 1,
+			spec_version: 1,
+			impl_version: 1,
+			apis,
+			transaction_version: 3,
+		};
+
+		assert!(!limits_stack(None));
+		assert!(!limits_stack(Some(&version(sp_api::create_apis_vec!([(Core::<Block, Error = ()>::ID, 3)])))));
+		assert!(limits_stack(Some(&version(sp_api::create_apis_vec!([
+			(Core::<Block, Error = ()>::ID, 3),
+			(sp_api::DeterministicStackLimit::<Block, Error = ()>::ID, 1),
+		])))));
+	}
 }
@@ -21,10 +21,12 @@ use crate::imports::{Imports, resolve_imports};
 use crate::instance_wrapper::{ModuleWrapper, InstanceWrapper, GlobalsSnapshot};
 use crate::state_holder;
 
+use std::path::Path;
 use std::rc::Rc;
 use std::sync::Arc;
 use sc_executor_common::{
 	error::{Error, Result, WasmError},
+	instrument::MAX_NATIVE_STACK,
 	wasm_runtime::{WasmModule, WasmInstance},
 };
 use sp_allocator::FreeingBumpHeapAllocator;
@@ -119,15 +121,31 @@ impl WasmInstance for WasmtimeInstance {
 
 /// Create a new `WasmtimeRuntime` given the code. This function performs translation from Wasm to
 /// machine code, which can be computationally heavy.
+///
+/// If `cache_path` is given, the compiled machine code is cached on disk in that directory and
+/// reused by later calls with the same code and compilation settings, including across restarts.
 pub fn create_runtime(
 	code: &[u8],
 	heap_pages: u64,
 	host_functions: Vec<&'static dyn Function>,
 	allow_missing_func_imports: bool,
+	cache_path: Option<&Path>,
 ) -> std::result::Result<WasmtimeRuntime, WasmError> {
 	// Create the engine, store and finally the module from the given code.
 	let mut config = Config::new();
 	config.cranelift_opt_level(wasmtime::OptLevel::SpeedAndSize);
+	// Leave enough native stack for the deterministic stack height limit to be hit first.
+	config.max_wasm_stack(MAX_NATIVE_STACK);
+	if let Some(cache_path) = cache_path {
+		if let Err(e) = setup_cache(&mut config, cache_path) {
+			log::warn!(
+				target: "wasm-runtime",
+				"Failed to setup the wasmtime code cache at {}: {}",
+				cache_path.display(),
+				e,
+			);
+		}
+	}
 
 	let engine = Engine::new(&config);
 
@@ -143,6 +161,22 @@ pub fn create_runtime(
 	})
 }
 
+/// Enable the wasmtime on-disk cache of compiled modules in the given directory.
+///
+/// Wasmtime keys the cached artifacts by the hash of the module code, the compilation settings
+/// and its own version, so stale artifacts are never loaded.
+fn setup_cache(config: &mut Config, cache_path: &Path) -> std::result::Result<(), String> {
+	std::fs::create_dir_all(cache_path).map_err(|e| e.to_string())?;
+	let cache_config_path = cache_path.join("cache-config.toml");
+	let cache_config = format!(
+		"[cache]\nenabled = true\ndirectory = {:?}\n",
+		cache_path.join("wasmtime"),
+	);
+	std::fs::write(&cache_config_path, cache_config).map_err(|e| e.to_string())?;
+	config.cache_config_load(&cache_config_path).map_err(|e| e.to_string())?;
+	Ok(())
+}
+
 fn perform_call(
 	data: &[u8],
 	instance_wrapper: Rc<InstanceWrapper>,
@@ -81,6 +81,11 @@ where N: Add<Output=N> + Ord + Clone + Debug,
 			 qed.",
 		)
 	}
+
+	/// Get the blocks at which the past authority sets were replaced.
+	pub fn authority_set_changes(&self) -> AuthoritySetChanges<N> {
+		self.inner.read().authority_set_changes.clone()
+	}
 }
 
 impl<H, N> From<AuthoritySet<H, N>> for SharedAuthoritySet<H, N> {
@@ -114,6 +119,8 @@ pub(crate) struct AuthoritySet<H, N> {
 	/// Forced changes are enacted on block depth (not finality), for this reason
 	/// only one forced change should exist per fork.
 	pending_forced_changes: Vec<PendingChange<H, N>>,
+	/// The blocks at which each past authority set was replaced by the next one.
+	pub(crate) authority_set_changes: AuthoritySetChanges<N>,
 }
 
 impl<H, N> AuthoritySet<H, N>
@@ -136,6 +143,7 @@ where H: PartialEq,
 			set_id: 0,
 			pending_standard_changes: ForkTree::new(),
 			pending_forced_changes: Vec::new(),
+			authority_set_changes: AuthoritySetChanges::empty(),
 		})
 	}
 
@@ -145,6 +153,7 @@ where H: PartialEq,
 		set_id: u64,
 		pending_standard_changes: ForkTree<H, N, PendingChange<H, N>>,
 		pending_forced_changes: Vec<PendingChange<H, N>>,
+		authority_set_changes: AuthoritySetChanges<N>,
 	) -> Option<Self> {
 		if Self::invalid_authority_list(&authorities) {
 			return None;
@@ -155,6 +164,7 @@ where H: PartialEq,
 			set_id,
 			pending_standard_changes,
 			pending_forced_changes,
+			authority_set_changes,
 		})
 	}
 
@@ -373,11 +383,15 @@ where
 					_ => unreachable!("pending_forced_changes only contains forced changes; forced changes have delay kind Best; qed."),
 				};
 
+				let mut authority_set_changes = self.authority_set_changes.clone();
+				authority_set_changes.append(self.set_id, median_last_finalized.clone());
+
 				new_set = Some((median_last_finalized, AuthoritySet {
 					current_authorities: change.next_authorities.clone(),
 					set_id: self.set_id + 1,
 					pending_standard_changes: ForkTree::new(), // new set, new changes.
 					pending_forced_changes: Vec::new(),
+					authority_set_changes,
 				}));
 
 				break;
@@ -449,6 +463,7 @@ where
 						"block" => ?change.canon_height
 					);
 
+					self.authority_set_changes.append(self.set_id, finalized_number.clone());
 					self.current_authorities = change.next_authorities;
 					self.set_id += 1;
 
@@ -492,6 +507,49 @@ where
 	}
 }
 
+/// The blocks at which the past authority sets were replaced, as `(set_id, block_number)`
+/// pairs sorted by block number, where `block_number` is the last block finalized by the set
+/// `set_id`. The justification of each of those blocks is the one handing off to the next set.
+#[derive(Debug, Clone, Encode, Decode, PartialEq)]
+pub struct AuthoritySetChanges<N>(Vec<(u64, N)>);
+
+impl<N: Ord + Clone> AuthoritySetChanges<N> {
+	/// No recorded authority set change.
+	pub(crate) fn empty() -> Self {
+		AuthoritySetChanges(Vec::new())
+	}
+
+	/// Record that the set `set_id` finalized its last block at `block_number`.
+	pub(crate) fn append(&mut self, set_id: u64, block_number: N) {
+		self.0.push((set_id, block_number));
+	}
+
+	/// Iterate over the changes of the sets that were still active after the given block, up to
+	/// the current set `set_id`.
+	///
+	/// Returns `None` if some of those changes are unknown, e.g. because they weren't tracked
+	/// before a database migration, in which case the history can't be proven.
+	pub fn iter_from(&self, block_number: N, set_id: u64) -> Option<impl Iterator<Item = &(u64, N)>> {
+		// the change to the current set must be known...
+		if self.0.last().map_or(0, |(id, _)| id + 1) != set_id {
+			return None;
+		}
+
+		let idx = self.0.binary_search_by_key(&block_number, |(_, n)| n.clone())
+			// the set which finalized its last block at `block_number` is not needed.
+			.map(|n| n + 1)
+			.unwrap_or_else(|n| n);
+
+		// ...and so must be all the changes after `block_number`, back to the first set if
+		// none is known before it.
+		if idx == 0 && self.0.first().map_or(false, |(id, _)| *id != 0) {
+			return None;
+		}
+
+		Some(self.0[idx..].iter())
+	}
+}
+
 /// Kinds of delays for pending changes.
 #[derive(Debug, Clone, Encode, Decode, PartialEq)]
 pub(crate) enum DelayKind<N> {
@@ -573,6 +631,7 @@ mod tests {
 			set_id: 0,
 			pending_standard_changes: ForkTree::new(),
 			pending_forced_changes: Vec::new(),
+			authority_set_changes: AuthoritySetChanges::empty(),
 		};
 
 		let change = |height| {
@@ -620,6 +679,7 @@ mod tests {
 			set_id: 0,
 			pending_standard_changes: ForkTree::new(),
 			pending_forced_changes: Vec::new(),
+			authority_set_changes: AuthoritySetChanges::empty(),
 		};
 
 		let change_a = PendingChange {
@@ -687,6 +747,7 @@ mod tests {
 			set_id: 0,
 			pending_standard_changes: ForkTree::new(),
 			pending_forced_changes: Vec::new(),
+			authority_set_changes: AuthoritySetChanges::empty(),
 		};
 
 		let set_a = vec![(AuthorityId::from_slice(&[1; 32]), 5)];
@@ -762,6 +823,7 @@ mod tests {
 			set_id: 0,
 			pending_standard_changes: ForkTree::new(),
 			pending_forced_changes: Vec::new(),
+			authority_set_changes: AuthoritySetChanges::empty(),
 		};
 
 		let set_a = vec![(AuthorityId::from_slice(&[1; 32]), 5)];
@@ -831,6 +893,7 @@ mod tests {
 
 		assert_eq!(authorities.current_authorities, set_c);
 		assert_eq!(authorities.set_id, 2);
+		assert_eq!(authorities.authority_set_changes, AuthoritySetChanges(vec![(0, 15), (1, 40)]));
 	}
 
 	#[test]
@@ -840,6 +903,7 @@ mod tests {
 			set_id: 0,
 			pending_standard_changes: ForkTree::new(),
 			pending_forced_changes: Vec::new(),
+			authority_set_changes: AuthoritySetChanges::empty(),
 		};
 
 		let set_a = vec![(AuthorityId::from_slice(&[1; 32]), 5)];
@@ -906,6 +970,7 @@ mod tests {
 			set_id: 0,
 			pending_standard_changes: ForkTree::new(),
 			pending_forced_changes: Vec::new(),
+			authority_set_changes: AuthoritySetChanges::empty(),
 		};
 
 		let set_a = vec![(AuthorityId::from_slice(&[1; 32]), 5)];
@@ -981,6 +1046,7 @@ mod tests {
 				set_id: 1,
 				pending_standard_changes: ForkTree::new(),
 				pending_forced_changes: Vec::new(),
+				authority_set_changes: AuthoritySetChanges(vec![(0, 42)]),
 			}),
 		);
 	}
@@ -993,6 +1059,7 @@ mod tests {
 			set_id: 0,
 			pending_standard_changes: ForkTree::new(),
 			pending_forced_changes: Vec::new(),
+			authority_set_changes: AuthoritySetChanges::empty(),
 		};
 
 		let set_a = vec![(AuthorityId::from_slice(&[1; 32]), 5)];
@@ -1031,6 +1098,7 @@ mod tests {
 			set_id: 0,
 			pending_standard_changes: ForkTree::new(),
 			pending_forced_changes: Vec::new(),
+			authority_set_changes: AuthoritySetChanges::empty(),
 		};
 
 		let new_set = current_authorities.clone();
@@ -1223,6 +1291,7 @@ mod tests {
 			set_id: 0,
 			pending_standard_changes: ForkTree::new(),
 			pending_forced_changes: Vec::new(),
+			authority_set_changes: AuthoritySetChanges::empty(),
 		};
 
 		let new_set = current_authorities.clone();
@@ -1333,4 +1402,36 @@ mod tests {
 			"D"
 		);
 	}
+
+	#[test]
+	fn iter_from_authority_set_changes() {
+		let mut authority_set_changes = AuthoritySetChanges::empty();
+		authority_set_changes.append(0, 41);
+		authority_set_changes.append(1, 81);
+		authority_set_changes.append(2, 121);
+
+		let changes_from = |changes: &AuthoritySetChanges<u64>, block_number, set_id| changes
+			.iter_from(block_number, set_id)
+			.map(|iter| iter.cloned().collect::<Vec<_>>());
+
+		assert_eq!(changes_from(&authority_set_changes, 0, 3), Some(vec![(0, 41), (1, 81), (2, 121)]));
+		assert_eq!(changes_from(&authority_set_changes, 41, 3), Some(vec![(1, 81), (2, 121)]));
+		assert_eq!(changes_from(&authority_set_changes, 60, 3), Some(vec![(1, 81), (2, 121)]));
+		assert_eq!(changes_from(&authority_set_changes, 121, 3), Some(vec![]));
+		assert_eq!(changes_from(&authority_set_changes, 200, 3), Some(vec![]));
+
+		// the change to the current set is unknown.
+		assert_eq!(changes_from(&authority_set_changes, 200, 4), None);
+
+		// the changes of the first sets are unknown.
+		let authority_set_changes = AuthoritySetChanges(vec![(3, 161), (4, 201)]);
+		assert_eq!(changes_from(&authority_set_changes, 100, 5), None);
+		assert_eq!(changes_from(&authority_set_changes, 170, 5), Some(vec![(4, 201)]));
+
+		// no change was tracked on a node migrated while the set 5 was active.
+		let authority_set_changes = AuthoritySetChanges::empty();
+		assert_eq!(changes_from(&authority_set_changes, 0, 5), None);
+		assert_eq!(changes_from(&authority_set_changes, 300, 5), None);
+		assert_eq!(changes_from(&authority_set_changes, 0, 0), Some(vec![]));
+	}
 }
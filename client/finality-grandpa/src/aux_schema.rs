@@ -27,7 +27,9 @@ use sp_runtime::traits::{Block as BlockT, NumberFor};
 use log::{info, warn};
 use sp_finality_grandpa::{AuthorityList, SetId, RoundNumber};
 
-use crate::authorities::{AuthoritySet, SharedAuthoritySet, PendingChange, DelayKind};
+use crate::authorities::{
+	AuthoritySet, AuthoritySetChanges, SharedAuthoritySet, PendingChange, DelayKind,
+};
 use crate::consensus_changes::{SharedConsensusChanges, ConsensusChanges};
 use crate::environment::{
 	CompletedRound, CompletedRounds, CurrentRounds, HasVoted, SharedVoterSetState, VoterSetState,
@@ -40,7 +42,17 @@ const CONCLUDED_ROUNDS: &[u8] = b"grandpa_concluded_rounds";
 const AUTHORITY_SET_KEY: &[u8] = b"grandpa_voters";
 const CONSENSUS_CHANGES_KEY: &[u8] = b"grandpa_consensus_changes";
 
-const CURRENT_VERSION: u32 = 2;
+const CURRENT_VERSION: u32 = 3;
+
+/// Keys of the aux-db entries needed to keep following finality from a state snapshot.
+///
+/// The concluded rounds are only kept as history and are not needed to continue voting.
+pub fn snapshot_aux_keys() -> Vec<Vec<u8>> {
+	[VERSION_KEY, SET_STATE_KEY, AUTHORITY_SET_KEY, CONSENSUS_CHANGES_KEY]
+		.iter()
+		.map(|key| key.to_vec())
+		.collect()
+}
 
 /// The voter set state.
 #[derive(Debug, Clone, Encode, Decode)]
@@ -102,6 +114,34 @@ where H: Clone + Debug + PartialEq,
 			self.set_id,
 			pending_standard_changes,
 			Vec::new(),
+			AuthoritySetChanges::empty(),
+		);
+
+		authority_set.expect("current_authorities is non-empty and weights are non-zero; qed.")
+	}
+}
+
+#[derive(Debug, Clone, Encode, Decode, PartialEq)]
+struct V2AuthoritySet<H, N> {
+	current_authorities: AuthorityList,
+	set_id: u64,
+	pending_standard_changes: ForkTree<H, N, PendingChange<H, N>>,
+	pending_forced_changes: Vec<PendingChange<H, N>>,
+}
+
+impl<H, N> Into<AuthoritySet<H, N>> for V2AuthoritySet<H, N>
+where H: Clone + Debug + PartialEq,
+	  N: Clone + Debug + Ord,
+{
+	fn into(self) -> AuthoritySet<H, N> {
+		// the blocks at which the previous sets changed were not tracked, so proofs of
+		// these changes can only be served from the current set onwards.
+		let authority_set = AuthoritySet::new(
+			self.current_authorities,
+			self.set_id,
+			self.pending_standard_changes,
+			self.pending_forced_changes,
+			AuthoritySetChanges::empty(),
 		);
 
 		authority_set.expect("current_authorities is non-empty and weights are non-zero; qed.")
@@ -197,10 +237,13 @@ fn migrate_from_version1<Block: BlockT, B, G>(
 		backend.insert_aux(&[(VERSION_KEY, s)], &[])
 	)?;
 
-	if let Some(set) = load_decode::<_, AuthoritySet<Block::Hash, NumberFor<Block>>>(
+	if let Some(set) = load_decode::<_, V2AuthoritySet<Block::Hash, NumberFor<Block>>>(
 		backend,
 		AUTHORITY_SET_KEY,
 	)? {
+		let set: AuthoritySet<Block::Hash, NumberFor<Block>> = set.into();
+		backend.insert_aux(&[(AUTHORITY_SET_KEY, set.encode().as_slice())], &[])?;
+
 		let set_id = set.set_id;
 
 		let completed_rounds = |number, state, base| CompletedRounds::new(
@@ -259,6 +302,50 @@ fn migrate_from_version1<Block: BlockT, B, G>(
 	Ok(None)
 }
 
+fn migrate_from_version2<Block: BlockT, B, G>(
+	backend: &B,
+	genesis_round: &G,
+) -> ClientResult<Option<(
+	AuthoritySet<Block::Hash, NumberFor<Block>>,
+	VoterSetState<Block>,
+)>> where B: AuxStore,
+		  G: Fn() -> RoundState<Block::Hash, NumberFor<Block>>,
+{
+	CURRENT_VERSION.using_encoded(|s|
+		backend.insert_aux(&[(VERSION_KEY, s)], &[])
+	)?;
+
+	if let Some(old_set) = load_decode::<_, V2AuthoritySet<Block::Hash, NumberFor<Block>>>(
+		backend,
+		AUTHORITY_SET_KEY,
+	)? {
+		let new_set: AuthoritySet<Block::Hash, NumberFor<Block>> = old_set.into();
+		backend.insert_aux(&[(AUTHORITY_SET_KEY, new_set.encode().as_slice())], &[])?;
+
+		let set_state = match load_decode::<_, VoterSetState<Block>>(
+			backend,
+			SET_STATE_KEY,
+		)? {
+			Some(state) => state,
+			None => {
+				let state = genesis_round();
+				let base = state.prevote_ghost
+					.expect("state is for completed round; completed rounds must have a prevote ghost; qed.");
+
+				VoterSetState::live(
+					new_set.set_id,
+					&new_set,
+					base,
+				)
+			}
+		};
+
+		return Ok(Some((new_set, set_state)));
+	}
+
+	Ok(None)
+}
+
 /// Load or initialize persistent data from backend.
 pub(crate) fn load_persistent<Block: BlockT, B, G>(
 	backend: &B,
@@ -297,6 +384,15 @@ pub(crate) fn load_persistent<Block: BlockT, B, G>(
 			}
 		},
 		Some(2) => {
+			if let Some((new_set, set_state)) = migrate_from_version2::<Block, _, _>(backend, &make_genesis_round)? {
+				return Ok(PersistentData {
+					authority_set: new_set.into(),
+					consensus_changes: Arc::new(consensus_changes.into()),
+					set_state: set_state.into(),
+				});
+			}
+		},
+		Some(3) => {
 			if let Some(set) = load_decode::<_, AuthoritySet<Block::Hash, NumberFor<Block>>>(
 				backend,
 				AUTHORITY_SET_KEY,
@@ -494,7 +590,7 @@ mod test {
 
 		assert_eq!(
 			load_decode::<_, u32>(&client, VERSION_KEY).unwrap(),
-			Some(2),
+			Some(3),
 		);
 
 		let PersistentData { authority_set, set_state, .. } = load_persistent::<substrate_test_runtime_client::runtime::Block, _, _>(
@@ -511,6 +607,7 @@ mod test {
 				set_id,
 				ForkTree::new(),
 				Vec::new(),
+				AuthoritySetChanges::empty(),
 			).unwrap(),
 		);
 
@@ -550,12 +647,12 @@ mod test {
 		};
 
 		{
-			let authority_set = AuthoritySet::<H256, u64>::new(
-				authorities.clone(),
+			let authority_set = V2AuthoritySet::<H256, u64> {
+				current_authorities: authorities.clone(),
 				set_id,
-				ForkTree::new(),
-				Vec::new(),
-			).unwrap();
+				pending_standard_changes: ForkTree::new(),
+				pending_forced_changes: Vec::new(),
+			};
 
 			let voter_set_state = V1VoterSetState::Live(round_number, round_state.clone());
 
@@ -584,7 +681,7 @@ mod test {
 
 		assert_eq!(
 			load_decode::<_, u32>(&client, VERSION_KEY).unwrap(),
-			Some(2),
+			Some(3),
 		);
 
 		let PersistentData { authority_set, set_state, .. } = load_persistent::<substrate_test_runtime_client::runtime::Block, _, _>(
@@ -601,6 +698,7 @@ mod test {
 				set_id,
 				ForkTree::new(),
 				Vec::new(),
+				AuthoritySetChanges::empty(),
 			).unwrap(),
 		);
 
@@ -625,6 +723,79 @@ mod test {
 		);
 	}
 
+	#[test]
+	fn load_decode_from_v2_migrates_data_format() {
+		let client = substrate_test_runtime_client::new();
+
+		let authorities = vec![(AuthorityId::default(), 100)];
+		let set_id = 3;
+
+		{
+			let authority_set = V2AuthoritySet::<H256, u64> {
+				current_authorities: authorities.clone(),
+				set_id,
+				pending_standard_changes: ForkTree::new(),
+				pending_forced_changes: Vec::new(),
+			};
+
+			let genesis_state = (H256::random(), 32);
+			let voter_set_state: VoterSetState<substrate_test_runtime_client::runtime::Block> =
+				VoterSetState::live(
+					set_id,
+					&authority_set.clone().into(),
+					genesis_state,
+				);
+
+			client.insert_aux(
+				&[
+					(AUTHORITY_SET_KEY, authority_set.encode().as_slice()),
+					(SET_STATE_KEY, voter_set_state.encode().as_slice()),
+					(VERSION_KEY, 2u32.encode().as_slice()),
+				],
+				&[],
+			).unwrap();
+		}
+
+		assert_eq!(
+			load_decode::<_, u32>(&client, VERSION_KEY).unwrap(),
+			Some(2),
+		);
+
+		// should perform the migration
+		load_persistent::<substrate_test_runtime_client::runtime::Block, _, _>(
+			&client,
+			H256::random(),
+			0,
+			|| unreachable!(),
+		).unwrap();
+
+		assert_eq!(
+			load_decode::<_, u32>(&client, VERSION_KEY).unwrap(),
+			Some(3),
+		);
+
+		let PersistentData { authority_set, .. } = load_persistent::<substrate_test_runtime_client::runtime::Block, _, _>(
+			&client,
+			H256::random(),
+			0,
+			|| unreachable!(),
+		).unwrap();
+
+		assert_eq!(
+			*authority_set.inner().read(),
+			AuthoritySet::new(
+				authorities.clone(),
+				set_id,
+				ForkTree::new(),
+				Vec::new(),
+				AuthoritySetChanges::empty(),
+			).unwrap(),
+		);
+
+		// the changes of the past sets weren't tracked, so they can't be proven.
+		assert!(authority_set.authority_set_changes().iter_from(0, set_id).is_none());
+	}
+
 	#[test]
 	fn write_read_concluded_rounds() {
 		let client = substrate_test_runtime_client::new();
@@ -185,7 +185,7 @@ impl<'a, Block: 'a + BlockT> Drop for PendingSetChanges<'a, Block> {
 	}
 }
 
-fn find_scheduled_change<B: BlockT>(header: &B::Header)
+pub(crate) fn find_scheduled_change<B: BlockT>(header: &B::Header)
 	-> Option<ScheduledChange<NumberFor<B>>>
 {
 	let id = OpaqueDigestItemId::Consensus(&GRANDPA_ENGINE_ID);
@@ -425,9 +425,15 @@ impl<BE, Block: BlockT, Client, SC> BlockImport<Block>
 		let number = *block.header.number();
 
 		// early exit if block already in chain, otherwise the check for
-		// authority changes will error when trying to re-import a change block
+		// authority changes will error when trying to re-import a change block.
+		// existing blocks are passed on, they may be re-imported with their state.
 		match self.inner.status(BlockId::Hash(hash)) {
-			Ok(BlockStatus::InChain) => return Ok(ImportResult::AlreadyInChain),
+			Ok(BlockStatus::InChain) => {
+				// the block is already finalized or not, its justification is not needed.
+				block.justification = None;
+				block.finalized = false;
+				return (&*self.inner).import_block(block, new_cache)
+			},
 			Ok(BlockStatus::Unknown) => {},
 			Err(e) => return Err(ConsensusError::ClientImport(e.to_string())),
 		}
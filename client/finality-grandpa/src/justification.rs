@@ -87,6 +87,11 @@ impl<Block: BlockT> GrandpaJustification<Block> {
 		Ok(GrandpaJustification { round, commit, votes_ancestries })
 	}
 
+	/// The target block number and hash that this justification finalizes.
+	pub fn target(&self) -> (NumberFor<Block>, Block::Hash) {
+		(self.commit.target_number, self.commit.target_hash)
+	}
+
 	/// Decode a GRANDPA justification and validate the commit and the votes'
 	/// ancestry proofs finalize the given block.
 	pub fn decode_and_verify_finalizes(
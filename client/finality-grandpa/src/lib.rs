@@ -123,13 +123,21 @@ mod notification;
 mod observer;
 mod until_imported;
 mod voting_rule;
+mod warp_proof;
 
-pub use authorities::SharedAuthoritySet;
+pub use authorities::{AuthoritySetChanges, SharedAuthoritySet};
+pub use aux_schema::snapshot_aux_keys;
 pub use finality_proof::{FinalityProofFragment, FinalityProofProvider, StorageAndProofProvider};
 pub use notification::{GrandpaJustificationSender, GrandpaJustificationStream};
 pub use import::GrandpaBlockImport;
 pub use justification::GrandpaJustification;
 pub use light_import::{light_block_import, GrandpaLightBlockImport};
+pub use warp_proof::{
+	GrandpaWarpSyncRequestHandler, WarpSyncError, WarpSyncFragment, WarpSyncProof, WarpSyncRequest,
+	WarpSyncVerifier, MAX_WARP_SYNC_PROOF_SIZE,
+	generate_protocol_config as warp_sync_protocol_config,
+	generate_protocol_name as warp_sync_protocol_name,
+};
 pub use voting_rule::{
 	BeforeBestBlockBy, ThreeQuartersOfTheUnfinalizedChain, VotingRule, VotingRulesBuilder
 };
@@ -0,0 +1,582 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! GRANDPA warp sync proof generation and verification.
+//!
+//! Rather than checking every justification since genesis, a node can "warp" across the
+//! authority set changes: a warp sync proof holds, for each authority set since a given block,
+//! the header of the last block finalized by that set along with its justification. That header
+//! carries the scheduled change signalling the next set, so checking the proof only requires
+//! knowing the authorities the walk starts from. The last fragment of a complete proof is the
+//! latest justification known to the prover.
+//!
+//! The proof relies on the changes being enacted in the block signalling them, which is how
+//! `pallet-grandpa` schedules its changes on new sessions. Forced changes and delayed changes
+//! can't be proven this way.
+
+use std::{sync::Arc, time::Duration};
+
+use futures::{channel::mpsc, StreamExt};
+use log::debug;
+use parity_scale_codec::{Decode, Encode};
+use sc_client_api::Backend;
+use sc_network::config::{IncomingRequest, ProtocolId, RequestResponseConfig};
+use sp_blockchain::{Backend as BlockchainBackend, HeaderBackend};
+use sp_finality_grandpa::{AuthorityList, SetId};
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, Header as HeaderT, NumberFor, One},
+};
+
+use crate::authorities::{AuthoritySetChanges, SharedAuthoritySet};
+use crate::import::find_scheduled_change;
+use crate::justification::GrandpaJustification;
+use crate::VoterSet;
+
+/// Maximum size in bytes of the encoded warp sync proof sent in a single response.
+pub const MAX_WARP_SYNC_PROOF_SIZE: usize = 8 * 1024 * 1024;
+
+/// Maximum number of requests waiting to be answered.
+const MAX_PENDING_REQUESTS: usize = 16;
+
+/// Error raised when generating, serving or checking a warp sync proof.
+#[derive(Debug, derive_more::Display, derive_more::From)]
+pub enum WarpSyncError {
+	/// The request or the proof couldn't be decoded.
+	#[display(fmt = "Failed to decode: {}", _0)]
+	Decoding(parity_scale_codec::Error),
+	/// Client backend error.
+	#[display(fmt = "Client error: {}", _0)]
+	Client(sp_blockchain::Error),
+	/// The request can't be answered.
+	#[display(fmt = "Invalid request: {}", _0)]
+	#[from(ignore)]
+	InvalidRequest(String),
+	/// The proof doesn't prove the finality of its headers.
+	#[display(fmt = "Invalid warp sync proof: {}", _0)]
+	#[from(ignore)]
+	InvalidProof(String),
+	/// Some data needed to build the proof is missing from the database.
+	#[display(fmt = "Missing data to build the warp sync proof")]
+	MissingData,
+}
+
+/// A request for the warp sync proof from the given finalized block to the latest one.
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct WarpSyncRequest<H> {
+	/// The last finalized block known to the requester.
+	pub begin: H,
+}
+
+/// The header of the last block finalized by an authority set, along with its justification.
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+pub struct WarpSyncFragment<Block: BlockT> {
+	/// The header of the block. It signals the authority set change, unless this is the last
+	/// fragment of a complete proof.
+	pub header: Block::Header,
+	/// The justification of the block, by the authority set it hands off from.
+	pub justification: GrandpaJustification<Block>,
+}
+
+/// A proof of the authority set changes since a given block.
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+pub struct WarpSyncProof<Block: BlockT> {
+	/// One fragment per authority set change, in order.
+	pub proofs: Vec<WarpSyncFragment<Block>>,
+	/// Whether the proof reaches the latest finalized block. Otherwise it was cut short to fit
+	/// the size limit, and should be continued from its last fragment.
+	pub is_finished: bool,
+}
+
+impl<Block: BlockT> WarpSyncProof<Block> {
+	/// Generate the proof of the authority set changes since the finalized block `begin`,
+	/// of at most `max_size` bytes once encoded. `set_changes` must lead up to the current
+	/// set `set_id`.
+	pub fn generate<B: Backend<Block>>(
+		backend: &B,
+		begin: Block::Hash,
+		set_changes: &AuthoritySetChanges<NumberFor<Block>>,
+		set_id: SetId,
+		max_size: usize,
+	) -> Result<WarpSyncProof<Block>, WarpSyncError> {
+		let blockchain = backend.blockchain();
+
+		let begin_number = blockchain.block_number_from_id(&BlockId::Hash(begin))?
+			.ok_or_else(|| WarpSyncError::InvalidRequest("Missing start block".to_string()))?;
+
+		let finalized_number = blockchain.info().finalized_number;
+		if begin_number > finalized_number {
+			return Err(WarpSyncError::InvalidRequest("Start block is not finalized".to_string()));
+		}
+
+		if blockchain.hash(begin_number)? != Some(begin) {
+			return Err(WarpSyncError::InvalidRequest(
+				"Start block is not in the finalized chain".to_string(),
+			));
+		}
+
+		let set_changes = set_changes.iter_from(begin_number, set_id).ok_or(WarpSyncError::MissingData)?;
+
+		let mut proofs = Vec::new();
+		let mut proofs_encoded_len = 0;
+		let mut proof_limit_reached = false;
+
+		for (_, last_block) in set_changes {
+			let fragment = fragment_at(blockchain, *last_block)?.ok_or(WarpSyncError::MissingData)?;
+
+			let fragment_size = fragment.encoded_size();
+			if proofs_encoded_len + fragment_size >= max_size {
+				proof_limit_reached = true;
+				break;
+			}
+
+			proofs_encoded_len += fragment_size;
+			proofs.push(fragment);
+		}
+
+		let is_finished = if proof_limit_reached {
+			false
+		} else {
+			// the latest justification must be for a block past the last set change, or past the
+			// start block if no set change was proven.
+			let limit = proofs.last()
+				.map(|fragment: &WarpSyncFragment<Block>| *fragment.header.number() + One::one())
+				.unwrap_or(begin_number + One::one());
+
+			let mut number = finalized_number;
+			while number >= limit {
+				if let Some(fragment) = fragment_at(blockchain, number)? {
+					if proofs_encoded_len + fragment.encoded_size() < max_size {
+						proofs.push(fragment);
+					} else {
+						proof_limit_reached = true;
+					}
+					break;
+				}
+
+				number = number - One::one();
+			}
+
+			!proof_limit_reached
+		};
+
+		Ok(WarpSyncProof { proofs, is_finished })
+	}
+
+	/// Check the proof, starting from the authority set `set_id`. Returns the authority set
+	/// which is current at the end of the proof.
+	pub fn verify(
+		&self,
+		set_id: SetId,
+		authorities: AuthorityList,
+	) -> Result<(SetId, AuthorityList), WarpSyncError>
+	where
+		NumberFor<Block>: finality_grandpa::BlockNumberOps,
+	{
+		let mut current_set_id = set_id;
+		let mut current_authorities = authorities;
+
+		for (fragment_num, fragment) in self.proofs.iter().enumerate() {
+			let voters = VoterSet::new(current_authorities.iter().cloned())
+				.ok_or_else(|| WarpSyncError::InvalidProof("Invalid authority set".to_string()))?;
+
+			fragment.justification.verify(current_set_id, &voters)
+				.map_err(|e| WarpSyncError::InvalidProof(e.to_string()))?;
+
+			if fragment.justification.target().1 != fragment.header.hash() {
+				return Err(WarpSyncError::InvalidProof(
+					"Mismatch between header and justification".to_string(),
+				));
+			}
+
+			if let Some(scheduled_change) = find_scheduled_change::<Block>(&fragment.header) {
+				current_authorities = scheduled_change.next_authorities;
+				current_set_id += 1;
+			} else if fragment_num != self.proofs.len() - 1 || !self.is_finished {
+				// only the latest justification may finalize a block without a set change.
+				return Err(WarpSyncError::InvalidProof(
+					"Header is missing authority set change digest".to_string(),
+				));
+			}
+		}
+
+		Ok((current_set_id, current_authorities))
+	}
+}
+
+/// The header and justification of the given block, if it has a justification.
+fn fragment_at<Block: BlockT, B: BlockchainBackend<Block>>(
+	blockchain: &B,
+	number: NumberFor<Block>,
+) -> Result<Option<WarpSyncFragment<Block>>, WarpSyncError> {
+	let justification = match blockchain.justification(BlockId::Number(number))? {
+		Some(justification) => justification,
+		None => return Ok(None),
+	};
+
+	let justification = GrandpaJustification::<Block>::decode(&mut &justification[..])?;
+	let header = blockchain.header(BlockId::Number(number))?.ok_or(WarpSyncError::MissingData)?;
+
+	Ok(Some(WarpSyncFragment { header, justification }))
+}
+
+/// Walks the warp sync proofs, one response at a time, from a known authority set to the latest
+/// finalized block.
+pub struct WarpSyncVerifier<Block: BlockT> {
+	set_id: SetId,
+	authorities: AuthorityList,
+	last_hash: Block::Hash,
+	last_header: Option<Block::Header>,
+	is_finished: bool,
+}
+
+impl<Block: BlockT> WarpSyncVerifier<Block> where
+	NumberFor<Block>: finality_grandpa::BlockNumberOps,
+{
+	/// Start from the finalized block `begin`, where `authorities` is the set `set_id`.
+	/// This is usually the genesis block and set.
+	pub fn new(begin: Block::Hash, set_id: SetId, authorities: AuthorityList) -> Self {
+		WarpSyncVerifier {
+			set_id,
+			authorities,
+			last_hash: begin,
+			last_header: None,
+			is_finished: false,
+		}
+	}
+
+	/// The next request to make, or `None` once the latest finalized block is reached.
+	pub fn next_request(&self) -> Option<WarpSyncRequest<Block::Hash>> {
+		if self.is_finished {
+			None
+		} else {
+			Some(WarpSyncRequest { begin: self.last_hash })
+		}
+	}
+
+	/// Check the encoded proof answering the last request, and continue from its end.
+	pub fn import_proof(&mut self, encoded: &[u8]) -> Result<(), WarpSyncError> {
+		let proof = WarpSyncProof::<Block>::decode(&mut &encoded[..])?;
+
+		if proof.proofs.is_empty() && !proof.is_finished {
+			return Err(WarpSyncError::InvalidProof("Incomplete proof makes no progress".to_string()));
+		}
+
+		if let Some(last_header) = &self.last_header {
+			let first_number = proof.proofs.first().map(|fragment| *fragment.header.number());
+			if first_number.map_or(false, |number| number <= *last_header.number()) {
+				return Err(WarpSyncError::InvalidProof(
+					"Proof doesn't continue from the last finalized block".to_string(),
+				));
+			}
+		}
+
+		let (set_id, authorities) = proof.verify(self.set_id, self.authorities.clone())?;
+
+		self.set_id = set_id;
+		self.authorities = authorities;
+		self.is_finished = proof.is_finished;
+		if let Some(fragment) = proof.proofs.into_iter().last() {
+			self.last_hash = fragment.header.hash();
+			self.last_header = Some(fragment.header);
+		}
+
+		Ok(())
+	}
+
+	/// Whether the latest finalized block was reached.
+	pub fn is_finished(&self) -> bool {
+		self.is_finished
+	}
+
+	/// The current authority set id and authorities.
+	pub fn authorities(&self) -> (SetId, &AuthorityList) {
+		(self.set_id, &self.authorities)
+	}
+
+	/// The last finalized header proven so far, if any.
+	pub fn finalized_header(&self) -> Option<&Block::Header> {
+		self.last_header.as_ref()
+	}
+}
+
+/// Generate the name of the warp sync request protocol.
+pub fn generate_protocol_name(protocol_id: &ProtocolId) -> String {
+	format!("/{}/sync/warp", protocol_id.as_ref())
+}
+
+/// Generate the configuration of the warp sync request protocol, without an inbound queue.
+pub fn generate_protocol_config(protocol_id: &ProtocolId) -> RequestResponseConfig {
+	RequestResponseConfig {
+		name: generate_protocol_name(protocol_id).into(),
+		max_request_size: 32,
+		max_response_size: MAX_WARP_SYNC_PROOF_SIZE as u64,
+		request_timeout: Duration::from_secs(10),
+		inbound_queue: None,
+	}
+}
+
+/// Answers the warp sync requests received on the warp sync request protocol.
+pub struct GrandpaWarpSyncRequestHandler<Block: BlockT, B> {
+	backend: Arc<B>,
+	authority_set: SharedAuthoritySet<Block::Hash, NumberFor<Block>>,
+	request_receiver: mpsc::Receiver<IncomingRequest>,
+}
+
+impl<Block: BlockT, B: Backend<Block>> GrandpaWarpSyncRequestHandler<Block, B> {
+	/// Create a new handler, along with the configuration of the protocol it answers.
+	pub fn new(
+		protocol_id: &ProtocolId,
+		backend: Arc<B>,
+		authority_set: SharedAuthoritySet<Block::Hash, NumberFor<Block>>,
+	) -> (Self, RequestResponseConfig) {
+		let (tx, request_receiver) = mpsc::channel(MAX_PENDING_REQUESTS);
+
+		let mut protocol_config = generate_protocol_config(protocol_id);
+		protocol_config.inbound_queue = Some(tx);
+
+		(GrandpaWarpSyncRequestHandler { backend, authority_set, request_receiver }, protocol_config)
+	}
+
+	fn handle_request(&self, payload: &[u8]) -> Result<Vec<u8>, WarpSyncError> {
+		let request = WarpSyncRequest::<Block::Hash>::decode(&mut &payload[..])?;
+
+		let (set_changes, set_id) = {
+			let authority_set = self.authority_set.inner().read();
+			(authority_set.authority_set_changes.clone(), authority_set.set_id)
+		};
+		let proof = WarpSyncProof::<Block>::generate(
+			&*self.backend,
+			request.begin,
+			&set_changes,
+			set_id,
+			MAX_WARP_SYNC_PROOF_SIZE,
+		)?;
+
+		Ok(proof.encode())
+	}
+
+	/// Answer the requests until the network is shut down.
+	pub async fn run(mut self) {
+		while let Some(request) = self.request_receiver.next().await {
+			let IncomingRequest { peer, payload, pending_response } = request;
+			match self.handle_request(&payload) {
+				Ok(response) => {
+					let _ = pending_response.send(response);
+				},
+				Err(e) => {
+					// dropping `pending_response` reports the failure to the remote.
+					debug!(target: "afg", "Failed to answer warp sync request from {}: {}", peer, e);
+				},
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sc_block_builder::BlockBuilderProvider;
+	use sp_consensus::BlockOrigin;
+	use sp_finality_grandpa::{ConsensusLog, ScheduledChange, GRANDPA_ENGINE_ID};
+	use sp_keyring::Ed25519Keyring;
+	use sp_runtime::generic::DigestItem;
+	use substrate_test_runtime_client::{
+		ClientBlockImportExt, ClientExt, DefaultTestClientBuilderExt, TestClientBuilder,
+		TestClientBuilderExt, runtime::Block,
+	};
+
+	fn make_ids(keys: &[Ed25519Keyring]) -> AuthorityList {
+		keys.iter().map(|key| (key.public().into(), 1)).collect()
+	}
+
+	/// Build a chain of 105 blocks changing the authority set every 10 blocks, and finalize the
+	/// change blocks and the last block with a justification.
+	fn make_chain() -> (
+		Arc<substrate_test_runtime_client::Backend>,
+		<Block as BlockT>::Hash,
+		AuthoritySetChanges<u64>,
+		SetId,
+		AuthorityList,
+	) {
+		let builder = TestClientBuilder::with_default_backend();
+		let backend = builder.backend();
+		let mut client = Arc::new(builder.build());
+
+		let available_authorities = Ed25519Keyring::iter().collect::<Vec<_>>();
+		let mut current_authorities = vec![Ed25519Keyring::Alice];
+		let mut current_set_id = 0;
+		let mut authority_set_changes = AuthoritySetChanges::empty();
+		let genesis_hash = client.info().genesis_hash;
+
+		for n in 1..=105u64 {
+			let mut block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+
+			let mut new_authorities = None;
+			if n % 10 == 0 {
+				let next_authorities = available_authorities.iter()
+					.cycle()
+					.skip(n as usize / 10)
+					.take(1 + (n as usize / 10) % 3)
+					.cloned()
+					.collect::<Vec<_>>();
+
+				let change = ScheduledChange { delay: 0u64, next_authorities: make_ids(&next_authorities) };
+				block.header.digest_mut().push(DigestItem::Consensus(
+					GRANDPA_ENGINE_ID,
+					ConsensusLog::ScheduledChange(change).encode(),
+				));
+
+				new_authorities = Some(next_authorities);
+			}
+
+			let hash = block.header.hash();
+			client.import(BlockOrigin::Own, block).unwrap();
+
+			if new_authorities.is_none() && n != 105 {
+				continue;
+			}
+
+			let round = 42;
+			let precommit = finality_grandpa::Precommit { target_hash: hash, target_number: n };
+			let msg = finality_grandpa::Message::Precommit(precommit.clone());
+			let encoded = sp_finality_grandpa::localized_payload(round, current_set_id, &msg);
+			let precommits = current_authorities.iter()
+				.map(|key| finality_grandpa::SignedPrecommit {
+					precommit: precommit.clone(),
+					signature: key.sign(&encoded[..]).into(),
+					id: key.public().into(),
+				})
+				.collect();
+
+			let commit = finality_grandpa::Commit { target_hash: hash, target_number: n, precommits };
+			let justification = GrandpaJustification::from_commit(&client, round, commit).unwrap();
+			client.finalize_block(BlockId::Hash(hash), Some(justification.encode())).unwrap();
+
+			if let Some(new_authorities) = new_authorities {
+				authority_set_changes.append(current_set_id, n);
+				current_set_id += 1;
+				current_authorities = new_authorities;
+			}
+		}
+
+		(
+			backend,
+			genesis_hash,
+			authority_set_changes,
+			current_set_id,
+			make_ids(&current_authorities),
+		)
+	}
+
+	#[test]
+	fn warp_sync_proof_generate_verify() {
+		let (backend, genesis_hash, authority_set_changes, set_id, authorities) = make_chain();
+		let genesis_authorities = make_ids(&[Ed25519Keyring::Alice]);
+
+		let proof = WarpSyncProof::<Block>::generate(
+			&*backend,
+			genesis_hash,
+			&authority_set_changes,
+			set_id,
+			MAX_WARP_SYNC_PROOF_SIZE,
+		).unwrap();
+
+		// one fragment per set change, and the latest justification.
+		assert!(proof.is_finished);
+		assert_eq!(proof.proofs.len(), 11);
+		assert_eq!(*proof.proofs.last().unwrap().header.number(), 105);
+
+		assert_eq!(proof.verify(0, genesis_authorities.clone()).unwrap(), (set_id, authorities));
+
+		// the proof doesn't check against another set.
+		assert!(proof.verify(1, genesis_authorities.clone()).is_err());
+		assert!(proof.verify(0, make_ids(&[Ed25519Keyring::Bob])).is_err());
+
+		// fragments can't be skipped.
+		let mut skipping = proof.clone();
+		skipping.proofs.remove(3);
+		assert!(skipping.verify(0, genesis_authorities).is_err());
+	}
+
+	#[test]
+	fn warp_sync_proof_needs_every_set_change() {
+		let (backend, genesis_hash, authority_set_changes, set_id, _) = make_chain();
+
+		// a node migrated from a database version without the set changes doesn't know them.
+		let proof = WarpSyncProof::<Block>::generate(
+			&*backend,
+			genesis_hash,
+			&AuthoritySetChanges::empty(),
+			set_id,
+			MAX_WARP_SYNC_PROOF_SIZE,
+		);
+		assert!(matches!(proof, Err(WarpSyncError::MissingData)));
+
+		// nor can the changes be proven up to a later set.
+		let proof = WarpSyncProof::<Block>::generate(
+			&*backend,
+			genesis_hash,
+			&authority_set_changes,
+			set_id + 1,
+			MAX_WARP_SYNC_PROOF_SIZE,
+		);
+		assert!(matches!(proof, Err(WarpSyncError::MissingData)));
+	}
+
+	#[test]
+	fn warp_sync_verifier_reaches_latest_finalized_block() {
+		let (backend, genesis_hash, authority_set_changes, set_id, authorities) = make_chain();
+
+		let mut verifier = WarpSyncVerifier::<Block>::new(
+			genesis_hash,
+			0,
+			make_ids(&[Ed25519Keyring::Alice]),
+		);
+
+		let mut round_trips = 0;
+		while let Some(request) = verifier.next_request() {
+			// small responses, so that it takes a few round trips.
+			let proof = WarpSyncProof::<Block>::generate(
+				&*backend,
+				request.begin,
+				&authority_set_changes,
+				set_id,
+				2048,
+			).unwrap();
+
+			verifier.import_proof(&proof.encode()).unwrap();
+			round_trips += 1;
+		}
+
+		assert!(verifier.is_finished());
+		assert!(round_trips > 1);
+		assert_eq!(verifier.authorities(), (set_id, &authorities));
+		assert_eq!(*verifier.finalized_header().unwrap().number(), 105);
+
+		// proofs from unknown blocks are refused.
+		let unknown = WarpSyncProof::<Block>::generate(
+			&*backend,
+			Default::default(),
+			&authority_set_changes,
+			set_id,
+			MAX_WARP_SYNC_PROOF_SIZE,
+		);
+		assert!(unknown.is_err());
+	}
+}
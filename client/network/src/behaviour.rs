@@ -16,7 +16,8 @@
 
 use crate::{
 	config::{ProtocolId, Role}, block_requests, light_client_handler, finality_requests,
-	peer_info, request_responses, discovery::{DiscoveryBehaviour, DiscoveryConfig, DiscoveryOut},
+	peer_info, request_responses,
+	discovery::{DiscoveryBehaviour, DiscoveryConfig, DiscoveryOut},
 	protocol::{message::{self, Roles}, CustomMessageOutcome, NotificationsSink, Protocol},
 	ObservedRole, DhtEvent, ExHashT,
 };
@@ -33,7 +34,7 @@ use sp_consensus::{BlockOrigin, import_queue::{IncomingBlock, Origin}};
 use sp_runtime::{traits::{Block as BlockT, NumberFor}, ConsensusEngineId, Justification};
 use std::{
 	borrow::Cow,
-	collections::{HashSet, VecDeque},
+	collections::{HashMap, HashSet, VecDeque},
 	iter,
 	task::{Context, Poll},
 	time::Duration,
@@ -63,6 +64,14 @@ pub struct Behaviour<B: BlockT, H: ExHashT> {
 	/// Light client request handling.
 	light_client_handler: light_client_handler::LightClientHandler<B>,
 
+	/// Name of the state request protocol.
+	#[behaviour(ignore)]
+	state_request_protocol: String,
+
+	/// State requests emitted by the sync, and the peers they were sent to.
+	#[behaviour(ignore)]
+	pending_state_requests: HashMap<RequestId, PeerId>,
+
 	/// Queue of events to produce for the outside.
 	#[behaviour(ignore)]
 	events: VecDeque<BehaviourOut<B>>,
@@ -184,6 +193,7 @@ impl<B: BlockT, H: ExHashT> Behaviour<B, H> {
 		block_requests: block_requests::BlockRequests<B>,
 		finality_proof_requests: finality_requests::FinalityProofRequests<B>,
 		light_client_handler: light_client_handler::LightClientHandler<B>,
+		state_request_protocol: String,
 		disco_config: DiscoveryConfig,
 		request_response_protocols: Vec<request_responses::ProtocolConfig>,
 	) -> Result<Self, request_responses::RegisterError> {
@@ -196,6 +206,8 @@ impl<B: BlockT, H: ExHashT> Behaviour<B, H> {
 			block_requests,
 			finality_proof_requests,
 			light_client_handler,
+			state_request_protocol,
+			pending_state_requests: HashMap::new(),
 			events: VecDeque::new(),
 			role,
 		})
@@ -361,6 +373,17 @@ Behaviour<B, H> {
 			CustomMessageOutcome::FinalityProofRequest { target, block_hash, request } => {
 				self.finality_proof_requests.send_request(&target, block_hash, request);
 			},
+			CustomMessageOutcome::StateRequest { target, request } => {
+				match self.request_responses.send_request(&target, &self.state_request_protocol, request) {
+					Ok(request_id) => {
+						self.pending_state_requests.insert(request_id, target);
+					},
+					Err(e) => {
+						debug!(target: "sync", "Failed to send state request to {}: {}", target, e);
+						self.substrate.on_state_request_failed(&target);
+					},
+				}
+			},
 			CustomMessageOutcome::NotificationStreamOpened { remote, protocols, roles, notifications_sink } => {
 				let role = reported_roles_to_observed_role(&self.role, &remote, roles);
 				for engine_id in protocols {
@@ -410,6 +433,19 @@ impl<B: BlockT, H: ExHashT> NetworkBehaviourEventProcess<request_responses::Even
 			}
 
 			request_responses::Event::RequestFinished { request_id, result } => {
+				if let Some(peer) = self.pending_state_requests.remove(&request_id) {
+					let ev = match result {
+						Ok(response) => self.substrate.on_state_response(peer, response),
+						Err(e) => {
+							debug!(target: "sync", "State request to {} failed: {}", peer, e);
+							self.substrate.on_state_request_failed(&peer);
+							CustomMessageOutcome::None
+						},
+					};
+					self.inject_event(ev);
+					return
+				}
+
 				self.events.push_back(BehaviourOut::RequestFinished {
 					request_id,
 					result,
@@ -106,6 +106,13 @@ pub struct Params<B: BlockT, H: ExHashT> {
 
 	/// Registry for recording prometheus metrics to.
 	pub metrics_registry: Option<Registry>,
+
+	/// Configuration of the state request protocol, used by the sync to download the state.
+	///
+	/// Incoming requests are answered by the
+	/// [`StateRequestHandler`](crate::state_requests::StateRequestHandler) that created the
+	/// configuration, which must be run separately.
+	pub state_request_protocol_config: RequestResponseConfig,
 }
 
 /// Role of the local node.
@@ -421,6 +428,8 @@ pub struct NetworkConfiguration {
 	pub transport: TransportConfig,
 	/// Maximum number of peers to ask the same blocks in parallel.
 	pub max_parallel_downloads: u32,
+	/// How the chain is synchronized on startup.
+	pub sync_mode: SyncMode,
 	/// Should we insert non-global addresses into the DHT?
 	pub allow_non_globals_in_dht: bool,
 }
@@ -454,6 +463,7 @@ impl NetworkConfiguration {
 				use_yamux_flow_control: false,
 			},
 			max_parallel_downloads: 5,
+			sync_mode: SyncMode::Full,
 			allow_non_globals_in_dht: false,
 		}
 	}
@@ -497,6 +507,16 @@ impl NetworkConfiguration {
 	}
 }
 
+/// How the chain is synchronized.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SyncMode {
+	/// Download and execute all the blocks.
+	Full,
+	/// Download the headers and justifications, then the state of the last finalized block, and
+	/// continue with full blocks from there. Only used when the node has no block but genesis.
+	Fast,
+}
+
 /// Configuration for the transport layer.
 #[derive(Clone, Debug)]
 pub enum TransportConfig {
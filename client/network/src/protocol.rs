@@ -19,8 +19,8 @@
 use crate::{
 	ExHashT,
 	chain::Client,
-	config::{BoxFinalityProofRequestBuilder, ProtocolId, TransactionPool, TransactionImportFuture, TransactionImport},
-	error,
+	config::{BoxFinalityProofRequestBuilder, ProtocolId, SyncMode, TransactionPool, TransactionImportFuture, TransactionImport},
+	error, schema,
 	utils::{interval, LruHashSet},
 };
 
@@ -28,6 +28,7 @@ use bytes::{Bytes, BytesMut};
 use futures::{prelude::*, stream::FuturesUnordered};
 use generic_proto::{GenericProto, GenericProtoOut};
 use libp2p::{Multiaddr, PeerId};
+use prost::Message as _;
 use libp2p::core::{ConnectedPoint, connection::{ConnectionId, ListenerId}};
 use libp2p::swarm::{ProtocolsHandler, IntoProtocolsHandler};
 use libp2p::swarm::{NetworkBehaviour, NetworkBehaviourAction, PollParameters};
@@ -292,6 +293,8 @@ pub struct ProtocolConfig {
 	pub roles: Roles,
 	/// Maximum number of peers to ask the same blocks in parallel.
 	pub max_parallel_downloads: u32,
+	/// How the chain is synchronized.
+	pub sync_mode: SyncMode,
 }
 
 impl Default for ProtocolConfig {
@@ -299,6 +302,7 @@ impl Default for ProtocolConfig {
 		ProtocolConfig {
 			roles: Roles::FULL,
 			max_parallel_downloads: 5,
+			sync_mode: SyncMode::Full,
 		}
 	}
 }
@@ -377,6 +381,7 @@ impl<B: BlockT, H: ExHashT> Protocol<B, H> {
 			finality_proof_request_builder,
 			block_announce_validator,
 			config.max_parallel_downloads,
+			config.sync_mode,
 		);
 
 		let important_peers = {
@@ -784,6 +789,49 @@ impl<B: BlockT, H: ExHashT> Protocol<B, H> {
 		}
 	}
 
+	/// Must be called in response to a [`CustomMessageOutcome::StateRequest`] being emitted.
+	/// Must contain the same `PeerId` that has been emitted.
+	pub fn on_state_response(
+		&mut self,
+		peer: PeerId,
+		response: Vec<u8>,
+	) -> CustomMessageOutcome<B> {
+		let response = match schema::v1::state::StateResponse::decode(&response[..]) {
+			Ok(response) => response,
+			Err(e) => {
+				debug!(target: "sync", "Failed to decode state response from {}: {}", peer, e);
+				self.behaviour.disconnect_peer(&peer);
+				self.peerset_handle.report_peer(peer, rep::BAD_MESSAGE);
+				return CustomMessageOutcome::None
+			}
+		};
+
+		trace!(target: "sync", "StateResponse from {} with {} entries, complete: {}",
+			peer,
+			response.entries.len(),
+			response.complete,
+		);
+
+		match self.sync.on_state_data(&peer, response) {
+			Ok(Some((origin, block))) => CustomMessageOutcome::BlockImport(origin, vec![block]),
+			Ok(None) => CustomMessageOutcome::None,
+			Err(sync::BadPeer(id, repu)) => {
+				self.behaviour.disconnect_peer(&id);
+				self.peerset_handle.report_peer(id, repu);
+				CustomMessageOutcome::None
+			}
+		}
+	}
+
+	/// Must be called in response to a [`CustomMessageOutcome::StateRequest`] if it has failed.
+	pub fn on_state_request_failed(
+		&mut self,
+		peer: &PeerId,
+	) {
+		self.peerset_handle.report_peer(peer.clone(), rep::TIMEOUT);
+		self.behaviour.disconnect_peer(peer);
+	}
+
 	/// Must be called in response to a [`CustomMessageOutcome::BlockRequest`] if it has failed.
 	pub fn on_block_request_failed(
 		&mut self,
@@ -1438,6 +1486,10 @@ pub enum CustomMessageOutcome<B: BlockT> {
 	/// If the request times out, or the peer responds in an invalid way, the peer has to be
 	/// disconnect. This will inform the state machine that the request it has emitted is stale.
 	FinalityProofRequest { target: PeerId, block_hash: B::Hash, request: Vec<u8> },
+	/// A new state request must be emitted, encoded as a `StateRequest` protobuf message.
+	/// You must later call either [`Protocol::on_state_response`] or
+	/// [`Protocol::on_state_request_failed`].
+	StateRequest { target: PeerId, request: Vec<u8> },
 	/// Peer has a reported a new head of chain.
 	PeerNewBest(PeerId, NumberFor<B>),
 	None,
@@ -1542,6 +1594,18 @@ impl<B: BlockT, H: ExHashT> NetworkBehaviour for Protocol<B, H> {
 			};
 			self.pending_messages.push_back(event);
 		}
+		if let Some((id, r)) = self.sync.state_request() {
+			let mut request = Vec::with_capacity(r.encoded_len());
+			if let Err(e) = prost::Message::encode(&r, &mut request) {
+				warn!(target: "sync", "Failed to encode state request: {}", e);
+			} else {
+				let event = CustomMessageOutcome::StateRequest {
+					target: id,
+					request,
+				};
+				self.pending_messages.push_back(event);
+			}
+		}
 		if let Poll::Ready(Some((tx_hash, result))) = self.pending_transactions.poll_next_unpin(cx) {
 			if let Some(peers) = self.pending_transactions_peers.remove(&tx_hash) {
 				peers.into_iter().for_each(|p| self.on_handle_transaction_import(p, result));
@@ -35,12 +35,14 @@ use sp_consensus::{BlockOrigin, BlockStatus,
 	import_queue::{IncomingBlock, BlockImportResult, BlockImportError}
 };
 use crate::{
-	config::BoxFinalityProofRequestBuilder,
+	config::{BoxFinalityProofRequestBuilder, SyncMode},
 	protocol::message::{self, generic::FinalityProofRequest, BlockAnnounce, BlockAttributes, BlockRequest, BlockResponse,
 	FinalityProofResponse, Roles},
+	schema::v1::state::{StateRequest, StateResponse},
 };
 use either::Either;
 use extra_requests::ExtraRequests;
+use state::StateSync;
 use libp2p::PeerId;
 use log::{debug, trace, warn, info, error};
 use sp_runtime::{
@@ -53,6 +55,7 @@ use std::{fmt, ops::Range, collections::{HashMap, HashSet, VecDeque}, sync::Arc}
 
 mod blocks;
 mod extra_requests;
+mod state;
 
 /// Maximum blocks to request in a single packet.
 const MAX_BLOCKS_TO_REQUEST: usize = 128;
@@ -106,6 +109,9 @@ mod rep {
 
 	/// Reputation change when a peer sent us invlid ancestry result.
 	pub const UNKNOWN_ANCESTOR:Rep = Rep::new(-(1 << 16), "DB Error");
+
+	/// Reputation change for peers which send us a state chunk that doesn't match its proof.
+	pub const BAD_STATE: Rep = Rep::new(-(1 << 29), "Bad state");
 }
 
 enum PendingRequests {
@@ -192,6 +198,13 @@ pub struct ChainSync<B: BlockT> {
 	max_parallel_downloads: u32,
 	/// Total number of downloaded blocks.
 	downloaded_blocks: usize,
+	/// How the chain is being synchronized. Switches to `Full` once fast sync is done.
+	mode: SyncMode,
+	/// State download in progress, during fast sync.
+	state_sync: Option<StateSync<B>>,
+	/// Blocks up to this number may have been imported without state during fast sync, and
+	/// are imported again once their parent state is known.
+	import_existing_up_to: Option<NumberFor<B>>,
 }
 
 /// All the data we have about a Peer that we are trying to sync with
@@ -250,7 +263,9 @@ pub enum PeerSyncState<B: BlockT> {
 	/// Downloading justification for given block hash.
 	DownloadingJustification(B::Hash),
 	/// Downloading finality proof for given block hash.
-	DownloadingFinalityProof(B::Hash)
+	DownloadingFinalityProof(B::Hash),
+	/// Downloading the state of the given block.
+	DownloadingState(B::Hash),
 }
 
 impl<B: BlockT> PeerSyncState<B> {
@@ -352,10 +367,18 @@ impl<B: BlockT> ChainSync<B> {
 		request_builder: Option<BoxFinalityProofRequestBuilder<B>>,
 		block_announce_validator: Box<dyn BlockAnnounceValidator<B> + Send>,
 		max_parallel_downloads: u32,
+		mode: SyncMode,
 	) -> Self {
 		let mut required_block_attributes = BlockAttributes::HEADER | BlockAttributes::JUSTIFICATION;
 
-		if role.is_full() {
+		// fast sync only makes sense for a full node that has nothing but the genesis block.
+		let mode = if mode == SyncMode::Fast && role.is_full() && info.best_number.is_zero() {
+			SyncMode::Fast
+		} else {
+			SyncMode::Full
+		};
+
+		if role.is_full() && mode == SyncMode::Full {
 			required_block_attributes |= BlockAttributes::BODY
 		}
 
@@ -377,6 +400,9 @@ impl<B: BlockT> ChainSync<B> {
 			block_announce_validator,
 			max_parallel_downloads,
 			downloaded_blocks: 0,
+			mode,
+			state_sync: None,
+			import_existing_up_to: None,
 		}
 	}
 
@@ -641,7 +667,7 @@ impl<B: BlockT> ChainSync<B> {
 
 	/// Get an iterator over all block requests of all peers.
 	pub fn block_requests(&mut self) -> impl Iterator<Item = (&PeerId, BlockRequest<B>)> + '_ {
-		if self.pending_requests.is_empty() {
+		if self.pending_requests.is_empty() || self.state_sync.is_some() {
 			return Either::Left(std::iter::empty())
 		}
 		if self.queue_blocks.len() > MAX_IMPORTING_BLOCKS {
@@ -704,6 +730,109 @@ impl<B: BlockT> ChainSync<B> {
 		Either::Right(iter)
 	}
 
+	/// Get the state request to make, if any.
+	///
+	/// During fast sync, once the headers are downloaded, this starts downloading the state of the
+	/// last finalized block.
+	pub fn state_request(&mut self) -> Option<(PeerId, StateRequest)> {
+		if self.mode != SyncMode::Fast {
+			return None
+		}
+
+		if self.state_sync.is_none() {
+			if !self.queue_blocks.is_empty() || self.status().state != SyncState::Idle {
+				return None
+			}
+			let info = self.client.info();
+			if info.finalized_number.is_zero() {
+				return None
+			}
+			let header = match self.client.header(BlockId::Hash(info.finalized_hash)) {
+				Ok(Some(header)) => header,
+				Ok(None) => return None,
+				Err(e) => {
+					debug!(target: "sync", "Error reading the last finalized header: {:?}", e);
+					return None
+				},
+			};
+			info!(
+				"⬇️  Downloading the state of block #{} ({})",
+				info.finalized_number,
+				info.finalized_hash,
+			);
+			self.state_sync = Some(StateSync::new(self.client.clone(), header));
+		}
+
+		let state_sync = self.state_sync.as_ref()?;
+		if state_sync.is_complete() {
+			return None
+		}
+		if self.peers.values().any(|peer| matches!(peer.state, PeerSyncState::DownloadingState(_))) {
+			// only one request is made at a time.
+			return None
+		}
+		let target = state_sync.target();
+		let target_number = state_sync.target_number();
+		let (id, peer) = self.peers.iter_mut()
+			.find(|(_, peer)| peer.state.is_available() && peer.best_number >= target_number)?;
+		trace!(target: "sync", "New state request to {} for {}", id, target);
+		peer.state = PeerSyncState::DownloadingState(target);
+		Some((id.clone(), state_sync.next_request()))
+	}
+
+	/// Handle a response from the remote to a state request that we made.
+	///
+	/// Returns the target block to import along with its state once the state is complete.
+	pub fn on_state_data(
+		&mut self,
+		who: &PeerId,
+		response: StateResponse,
+	) -> Result<Option<(BlockOrigin, IncomingBlock<B>)>, BadPeer> {
+		if let Some(peer) = self.peers.get_mut(who) {
+			if let PeerSyncState::DownloadingState(_) = peer.state {
+				peer.state = PeerSyncState::Available;
+			}
+		}
+		self.pending_requests.add(who);
+
+		let state_sync = match &mut self.state_sync {
+			Some(state_sync) => state_sync,
+			None => {
+				debug!(target: "sync", "Unexpected state response from {}", who);
+				return Ok(None)
+			},
+		};
+
+		match state_sync.import(response) {
+			state::ImportResult::Import(hash, header, state) => {
+				info!(
+					"✅ Downloaded the state of block #{} ({}), {} bytes",
+					header.number(),
+					hash,
+					state_sync.imported_bytes(),
+				);
+				// the block is already in the chain and finalized, only its state is imported.
+				let block = IncomingBlock {
+					hash,
+					header: Some(header),
+					body: None,
+					justification: None,
+					origin: Some(who.clone()),
+					allow_missing_state: true,
+					import_existing: true,
+					state: Some(state),
+				};
+				self.queue_blocks.insert(hash);
+				Ok(Some((BlockOrigin::NetworkInitialSync, block)))
+			},
+			state::ImportResult::Continue => Ok(None),
+			state::ImportResult::BadResponse => {
+				debug!(target: "sync", "Bad state data received from {}", who);
+				Err(BadPeer(who.clone(), rep::BAD_STATE))
+			},
+		}
+	}
+
 	/// Handle a response from the remote to a block request that we made.
 	///
 	/// `request` must be the original request that triggered `response`.
@@ -734,10 +863,15 @@ impl<B: BlockT> ChainSync<B> {
 							peer.state = PeerSyncState::Available;
 							validate_blocks::<B>(&blocks, who)?;
 							self.blocks.insert(start_block, blocks, who.clone());
+							let import_existing_up_to = self.import_existing_up_to;
 							self.blocks
 								.drain(self.best_queued_number + One::one())
 								.into_iter()
 								.map(|block_data| {
+									let import_existing = match (&block_data.block.header, import_existing_up_to) {
+										(Some(header), Some(up_to)) => *header.number() <= up_to,
+										_ => false,
+									};
 									IncomingBlock {
 										hash: block_data.block.hash,
 										header: block_data.block.header,
@@ -745,7 +879,8 @@ impl<B: BlockT> ChainSync<B> {
 										justification: block_data.block.justification,
 										origin: block_data.origin,
 										allow_missing_state: true,
-										import_existing: false,
+										import_existing,
+										state: None,
 									}
 								}).collect()
 						}
@@ -765,6 +900,7 @@ impl<B: BlockT> ChainSync<B> {
 									origin: Some(who.clone()),
 									allow_missing_state: true,
 									import_existing: false,
+									state: None,
 								}
 							}).collect()
 						}
@@ -836,7 +972,8 @@ impl<B: BlockT> ChainSync<B> {
 
 						| PeerSyncState::Available
 						| PeerSyncState::DownloadingJustification(..)
-						| PeerSyncState::DownloadingFinalityProof(..) => Vec::new()
+						| PeerSyncState::DownloadingFinalityProof(..)
+						| PeerSyncState::DownloadingState(..) => Vec::new()
 					}
 				} else {
 					// When request.is_none() this is a block announcement. Just accept blocks.
@@ -850,6 +987,7 @@ impl<B: BlockT> ChainSync<B> {
 							origin: Some(who.clone()),
 							allow_missing_state: true,
 							import_existing: false,
+							state: None,
 						}
 					}).collect()
 				}
@@ -866,7 +1004,8 @@ impl<B: BlockT> ChainSync<B> {
 			})
 			.unwrap_or(false);
 
-		if !is_recent && new_blocks.last().map_or(false, |b| self.is_known(&b.hash)) {
+		let is_reimport = new_blocks.last().map_or(false, |b| b.import_existing);
+		if !is_recent && !is_reimport && new_blocks.last().map_or(false, |b| self.is_known(&b.hash)) {
 			// When doing initial sync we don't request blocks in parallel.
 			// So the only way this can happen is when peers lie about the
 			// common block.
@@ -1004,6 +1143,17 @@ impl<B: BlockT> ChainSync<B> {
 			self.queue_blocks.remove(&hash);
 		}
 		for (result, hash) in results {
+			if self.state_sync.as_ref().map_or(false, |s| s.target() == hash) {
+				match result {
+					Ok(_) => self.on_state_imported(),
+					Err(e) => {
+						warn!(target: "sync", "💔 Error importing the downloaded state of {:?}: {:?}", hash, e);
+						self.state_sync = None;
+					},
+				}
+				continue;
+			}
+
 			if has_error {
 				continue;
 			}
@@ -1270,6 +1420,38 @@ impl<B: BlockT> ChainSync<B> {
 		self.pending_requests.set_all();
 	}
 
+	/// Called when the block with the downloaded state has been imported. Switches to full
+	/// sync, starting from that block.
+	fn on_state_imported(&mut self) {
+		let state_sync = match self.state_sync.take() {
+			Some(state_sync) => state_sync,
+			None => return,
+		};
+		let (hash, number) = (state_sync.target(), state_sync.target_number());
+		info!("🎉 Imported the state of block #{} ({}), continuing with full sync", number, hash);
+
+		self.mode = SyncMode::Full;
+		self.required_block_attributes |= BlockAttributes::BODY;
+
+		// the blocks imported without state above the target are downloaded and imported again.
+		let best_number = self.client.info().best_number;
+		if best_number > number {
+			self.import_existing_up_to = Some(best_number);
+		}
+
+		self.blocks.clear();
+		self.best_queued_hash = hash;
+		self.best_queued_number = number;
+		for peer in self.peers.values_mut() {
+			if let PeerSyncState::AncestorSearch {..} = peer.state {
+				continue;
+			}
+			peer.common_number = std::cmp::min(peer.common_number, number);
+			peer.state = PeerSyncState::Available;
+		}
+		self.pending_requests.set_all();
+	}
+
 	/// Restart the sync process.
 	fn restart<'a>(&'a mut self) -> impl Iterator<Item = Result<(PeerId, BlockRequest<B>), BadPeer>> + 'a {
 		self.blocks.clear();
@@ -1580,6 +1762,7 @@ mod test {
 			None,
 			block_announce_validator,
 			1,
+			SyncMode::Full,
 		);
 
 		let (a1_hash, a1_number) = {
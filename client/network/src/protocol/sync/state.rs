@@ -0,0 +1,269 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+
+//! Download of the state of a block, used by fast sync.
+//!
+//! The top trie is downloaded first, in chunks. The child tries found along the way are
+//! downloaded afterwards, one after the other. Every chunk is checked against the state root of
+//! the target block, or the root of the child trie, before being kept.
+
+use crate::{chain::Client, schema::v1::state::{StateRequest, StateResponse}};
+use codec::{Decode, Encode};
+use sc_client_api::StorageProof;
+use sp_consensus::ImportedState;
+use sp_core::storage::{well_known_keys, ChildInfo, Storage, StorageChild};
+use sp_runtime::traits::{Block as BlockT, Header, NumberFor};
+use std::{collections::VecDeque, sync::Arc};
+
+/// Result of [`StateSync::import`].
+pub enum ImportResult<B: BlockT> {
+	/// The state is complete and the target block should be imported with it.
+	Import(B::Hash, B::Header, ImportedState<B>),
+	/// More state must be requested.
+	Continue,
+	/// The response is invalid.
+	BadResponse,
+}
+
+/// State download of a single block.
+pub struct StateSync<B: BlockT> {
+	client: Arc<dyn Client<B>>,
+	target_header: B::Header,
+	/// Child trie being downloaded with its root, `None` while downloading the top trie.
+	current_child: Option<(Vec<u8>, B::Hash)>,
+	/// Child tries left to download, with their root.
+	pending_children: VecDeque<(Vec<u8>, B::Hash)>,
+	/// Last key received for the trie being downloaded.
+	last_key: Vec<u8>,
+	state: Storage,
+	complete: bool,
+	imported_bytes: u64,
+}
+
+impl<B: BlockT> StateSync<B> {
+	/// Create a new instance downloading the state of the block with the given header.
+	pub fn new(client: Arc<dyn Client<B>>, target_header: B::Header) -> Self {
+		StateSync {
+			client,
+			target_header,
+			current_child: None,
+			pending_children: VecDeque::new(),
+			last_key: Vec::new(),
+			state: Default::default(),
+			complete: false,
+			imported_bytes: 0,
+		}
+	}
+
+	/// Hash of the target block.
+	pub fn target(&self) -> B::Hash {
+		self.target_header.hash()
+	}
+
+	/// Number of the target block.
+	pub fn target_number(&self) -> NumberFor<B> {
+		*self.target_header.number()
+	}
+
+	/// Number of bytes of keys and values downloaded so far.
+	pub fn imported_bytes(&self) -> u64 {
+		self.imported_bytes
+	}
+
+	/// Whether the whole state has been downloaded.
+	pub fn is_complete(&self) -> bool {
+		self.complete
+	}
+
+	/// The next request to make.
+	pub fn next_request(&self) -> StateRequest {
+		StateRequest {
+			block: self.target().encode(),
+			child: self.current_child.as_ref().map(|(key, _)| key.clone()).unwrap_or_default(),
+			start: self.last_key.clone(),
+		}
+	}
+
+	/// Check and keep the entries of a response to the request returned by `next_request`.
+	pub fn import(&mut self, response: StateResponse) -> ImportResult<B> {
+		if self.complete {
+			return ImportResult::BadResponse
+		}
+		// a chunk must make progress, otherwise the same request would be made again.
+		if response.entries.is_empty() && !response.complete {
+			log::debug!(target: "sync", "Empty state response for an incomplete trie");
+			return ImportResult::BadResponse
+		}
+
+		let proof = match StorageProof::decode(&mut response.proof.as_ref()) {
+			Ok(proof) => proof,
+			Err(e) => {
+				log::debug!(target: "sync", "Failed to decode state proof: {:?}", e);
+				return ImportResult::BadResponse
+			},
+		};
+		let entries = response.entries.into_iter()
+			.map(|entry| (entry.key, entry.value))
+			.collect::<Vec<_>>();
+		let child_info = self.current_child.as_ref().map(|(key, _)| ChildInfo::new_default(key));
+		let root = match &self.current_child {
+			Some((_, root)) => *root,
+			None => *self.target_header.state_root(),
+		};
+
+		if let Err(e) = self.client.verify_range_proof(
+			root,
+			proof,
+			child_info.as_ref(),
+			&self.last_key,
+			&entries,
+			response.complete,
+		) {
+			log::debug!(target: "sync", "Bad state response: {:?}", e);
+			return ImportResult::BadResponse
+		}
+
+		if let Some((key, _)) = entries.last() {
+			self.last_key = key.clone();
+		}
+		for (key, value) in entries {
+			self.imported_bytes += (key.len() + value.len()) as u64;
+			match &self.current_child {
+				Some((storage_key, _)) => {
+					self.state.children_default
+						.entry(storage_key.clone())
+						.or_insert_with(|| StorageChild {
+							data: Default::default(),
+							child_info: ChildInfo::new_default(storage_key),
+						})
+						.data
+						.insert(key, value);
+				},
+				None if key.starts_with(well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX) => {
+					let storage_key = key[well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX.len()..].to_vec();
+					match B::Hash::decode(&mut value.as_ref()) {
+						Ok(child_root) => self.pending_children.push_back((storage_key, child_root)),
+						Err(_) => return ImportResult::BadResponse,
+					}
+				},
+				None => {
+					self.state.top.insert(key, value);
+				},
+			}
+		}
+
+		if response.complete {
+			self.last_key = Vec::new();
+			self.current_child = self.pending_children.pop_front();
+			if self.current_child.is_none() {
+				self.complete = true;
+				let target = self.target();
+				return ImportResult::Import(
+					target,
+					self.target_header.clone(),
+					ImportedState {
+						block: target,
+						state: std::mem::take(&mut self.state),
+					},
+				)
+			}
+		}
+
+		ImportResult::Continue
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::schema::v1::state::KeyValue;
+	use sc_block_builder::BlockBuilderProvider;
+	use sc_client_api::ProofProvider;
+	use sp_consensus::BlockOrigin;
+	use sp_runtime::generic::BlockId;
+	use substrate_test_runtime_client::{
+		runtime::Block, ClientBlockImportExt, DefaultTestClientBuilderExt, TestClientBuilder,
+		TestClientBuilderExt,
+	};
+
+	#[test]
+	fn downloads_and_checks_state_in_chunks() {
+		let child_info = ChildInfo::new_default(b"child");
+		let mut client = Arc::new(TestClientBuilder::new()
+			.add_extra_child_storage(&child_info, b"key".to_vec(), b"value".to_vec())
+			.build());
+		let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+		client.import(BlockOrigin::Own, block.clone()).unwrap();
+		let header = block.header;
+
+		// answers requests the way the state request handler does, in small chunks.
+		let answer = |request: &StateRequest| {
+			let child_info = if request.child.is_empty() {
+				None
+			} else {
+				Some(ChildInfo::new_default(&request.child))
+			};
+			let (entries, complete, proof) = client.read_proof_collection(
+				&BlockId::Hash(header.hash()),
+				child_info.as_ref(),
+				&request.start,
+				1024,
+			).unwrap();
+			StateResponse {
+				entries: entries.into_iter().map(|(key, value)| KeyValue { key, value }).collect(),
+				complete,
+				proof: proof.encode(),
+			}
+		};
+
+		let mut sync = StateSync::<Block>::new(client.clone(), header.clone());
+
+		// a chunk with a missing entry is rejected.
+		let mut response = answer(&sync.next_request());
+		response.entries.remove(0);
+		assert!(matches!(sync.import(response), ImportResult::BadResponse));
+
+		// so is an empty chunk of a trie that is not complete.
+		let mut response = answer(&sync.next_request());
+		response.entries.clear();
+		response.complete = false;
+		assert!(matches!(sync.import(response), ImportResult::BadResponse));
+
+		let state = loop {
+			let response = answer(&sync.next_request());
+			match sync.import(response) {
+				ImportResult::Import(hash, _, state) => {
+					assert_eq!(hash, header.hash());
+					break state
+				},
+				ImportResult::Continue => {},
+				ImportResult::BadResponse => panic!("A valid state response was rejected"),
+			}
+		};
+
+		assert!(sync.is_complete());
+		assert_eq!(state.block, header.hash());
+		assert!(state.state.top.contains_key(well_known_keys::CODE));
+		assert!(!state.state.top.keys().any(|key| well_known_keys::is_child_storage_key(key)));
+		assert_eq!(
+			state.state.children_default.get(child_info.storage_key()).unwrap().data.get(&b"key"[..]),
+			Some(&b"value".to_vec()),
+		);
+	}
+}
@@ -251,6 +251,7 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 			protocol::ProtocolConfig {
 				roles: From::from(&params.role),
 				max_parallel_downloads: params.network_config.max_parallel_downloads,
+				sync_mode: params.network_config.sync_mode,
 			},
 			local_peer_id.clone(),
 			params.chain.clone(),
@@ -278,6 +279,9 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 				let config = finality_requests::Config::new(&params.protocol_id);
 				finality_requests::FinalityProofRequests::new(config, params.finality_proof_provider.clone())
 			};
+			let state_request_protocol = params.state_request_protocol_config.name.to_string();
+			let mut request_response_protocols = params.network_config.request_response_protocols;
+			request_response_protocols.push(params.state_request_protocol_config);
 			let light_client_handler = {
 				let config = light_client_handler::Config::new(&params.protocol_id);
 				light_client_handler::LightClientHandler::new(
@@ -318,8 +322,9 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 					block_requests,
 					finality_proof_requests,
 					light_client_handler,
+					state_request_protocol,
 					discovery_config,
-					params.network_config.request_response_protocols,
+					request_response_protocols,
 				);
 
 				match result {
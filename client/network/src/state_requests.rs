@@ -0,0 +1,137 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+
+//! Handler answering incoming state requests.
+//!
+//! A state request asks for the storage entries of a block that follow a given key, in the top
+//! trie or in a child trie. The response contains a chunk of the entries, along with a proof
+//! against the state root of the block. Requests and responses are encoded as protocol buffers
+//! (cf. `state.v1.proto`) and are carried by the generic request-response protocols.
+
+use crate::{
+	chain::Client,
+	config::ProtocolId,
+	request_responses::{IncomingRequest, ProtocolConfig},
+	schema,
+};
+use codec::{Decode, Encode};
+use futures::{channel::mpsc, prelude::*};
+use log::debug;
+use prost::Message;
+use sp_core::storage::ChildInfo;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+use std::{sync::Arc, time::Duration};
+
+/// Maximum size, in bytes, of the keys and values returned in a single response.
+const MAX_RESPONSE_BYTES: usize = 2 * 1024 * 1024;
+
+/// Maximum number of requests waiting to be answered.
+const MAX_PENDING_REQUESTS: usize = 20;
+
+/// Generate the name of the state request protocol for the given protocol id.
+pub fn generate_protocol_name(protocol_id: &ProtocolId) -> String {
+	format!("/{}/state/1", protocol_id.as_ref())
+}
+
+/// Generate the configuration of the state request protocol, without an inbound queue.
+pub fn generate_protocol_config(protocol_id: &ProtocolId) -> ProtocolConfig {
+	ProtocolConfig {
+		name: generate_protocol_name(protocol_id).into(),
+		max_request_size: 1024 * 1024,
+		max_response_size: 16 * 1024 * 1024,
+		request_timeout: Duration::from_secs(40),
+		inbound_queue: None,
+	}
+}
+
+/// Answers the state requests received on the state request protocol.
+pub struct StateRequestHandler<B: BlockT> {
+	client: Arc<dyn Client<B>>,
+	request_receiver: mpsc::Receiver<IncomingRequest>,
+}
+
+impl<B: BlockT> StateRequestHandler<B> {
+	/// Create a new handler, along with the configuration of the protocol it answers.
+	pub fn new(protocol_id: &ProtocolId, client: Arc<dyn Client<B>>) -> (Self, ProtocolConfig) {
+		let (tx, request_receiver) = mpsc::channel(MAX_PENDING_REQUESTS);
+
+		let mut protocol_config = generate_protocol_config(protocol_id);
+		protocol_config.inbound_queue = Some(tx);
+
+		(Self { client, request_receiver }, protocol_config)
+	}
+
+	/// Answer the requests until the network is shut down.
+	pub async fn run(mut self) {
+		while let Some(request) = self.request_receiver.next().await {
+			let IncomingRequest { peer, payload, pending_response } = request;
+			match self.handle_request(&payload) {
+				Ok(response) => {
+					let _ = pending_response.send(response);
+				},
+				Err(e) => {
+					// dropping `pending_response` reports the failure to the remote.
+					debug!(target: "sync", "Failed to answer state request from {}: {}", peer, e);
+				},
+			}
+		}
+	}
+
+	fn handle_request(&self, payload: &[u8]) -> Result<Vec<u8>, HandleRequestError> {
+		let request = schema::v1::state::StateRequest::decode(payload)?;
+		let block = Decode::decode(&mut request.block.as_ref())?;
+		let child_info = if request.child.is_empty() {
+			None
+		} else {
+			Some(ChildInfo::new_default(&request.child))
+		};
+
+		let (entries, complete, proof) = self.client.read_proof_collection(
+			&BlockId::<B>::Hash(block),
+			child_info.as_ref(),
+			&request.start,
+			MAX_RESPONSE_BYTES,
+		).map_err(|e| HandleRequestError::Client(e.to_string()))?;
+
+		let response = schema::v1::state::StateResponse {
+			entries: entries.into_iter()
+				.map(|(key, value)| schema::v1::state::KeyValue { key, value })
+				.collect(),
+			complete,
+			proof: proof.encode(),
+		};
+
+		let mut data = Vec::with_capacity(response.encoded_len());
+		response.encode(&mut data)?;
+		Ok(data)
+	}
+}
+
+#[derive(Debug, derive_more::Display, derive_more::From)]
+enum HandleRequestError {
+	#[display(fmt = "Failed to decode request: {}.", _0)]
+	DecodeProto(prost::DecodeError),
+	#[display(fmt = "Failed to encode response: {}.", _0)]
+	EncodeProto(prost::EncodeError),
+	#[display(fmt = "Failed to decode block hash: {}.", _0)]
+	DecodeScale(codec::Error),
+	#[display(fmt = "Failed to read the state: {}.", _0)]
+	#[from(ignore)]
+	Client(String),
+}
@@ -670,6 +670,7 @@ pub trait TestNetFactory: Sized {
 		network_config.allow_non_globals_in_dht = true;
 		network_config.notifications_protocols = config.notifications_protocols;
 
+		let protocol_id = ProtocolId::from("test-protocol-name");
 		let network = NetworkWorker::new(sc_network::config::Params {
 			role: Role::Full,
 			executor: None,
@@ -681,11 +682,14 @@ pub trait TestNetFactory: Sized {
 			finality_proof_request_builder,
 			on_demand: None,
 			transaction_pool: Arc::new(EmptyTransactionPool),
-			protocol_id: ProtocolId::from("test-protocol-name"),
+			protocol_id: protocol_id.clone(),
 			import_queue,
 			block_announce_validator: config.block_announce_validator
 				.unwrap_or_else(|| Box::new(DefaultBlockAnnounceValidator)),
 			metrics_registry: None,
+			state_request_protocol_config: sc_network::state_requests::generate_protocol_config(
+				&protocol_id,
+			),
 		}).unwrap();
 
 		self.mut_peers(|peers| {
@@ -750,6 +754,7 @@ pub trait TestNetFactory: Sized {
 		network_config.listen_addresses = vec![listen_addr.clone()];
 		network_config.allow_non_globals_in_dht = true;
 
+		let protocol_id = ProtocolId::from("test-protocol-name");
 		let network = NetworkWorker::new(sc_network::config::Params {
 			role: Role::Light,
 			executor: None,
@@ -761,10 +766,13 @@ pub trait TestNetFactory: Sized {
 			finality_proof_request_builder,
 			on_demand: None,
 			transaction_pool: Arc::new(EmptyTransactionPool),
-			protocol_id: ProtocolId::from("test-protocol-name"),
+			protocol_id: protocol_id.clone(),
 			import_queue,
 			block_announce_validator: Box::new(DefaultBlockAnnounceValidator),
 			metrics_registry: None,
+			state_request_protocol_config: sc_network::state_requests::generate_protocol_config(
+				&protocol_id,
+			),
 		}).unwrap();
 
 		self.mut_peers(|peers| {
@@ -33,6 +33,10 @@ pub enum Error {
 	/// Client error.
 	#[display(fmt="Client error: {}", _0)]
 	Client(Box<dyn std::error::Error + Send>),
+	/// The body of the requested block was pruned.
+	#[display(fmt="Body of block {} is not available, it was pruned", _0)]
+	#[from(ignore)]
+	BlockBodyPruned(String),
 	/// Other error type.
 	Other(String),
 }
@@ -57,6 +61,11 @@ impl From<Error> for rpc::Error {
 				message,
 				data: None,
 			},
+			Error::BlockBodyPruned(_) => rpc::Error {
+				code: rpc::ErrorCode::ServerError(BASE_ERROR + 2),
+				message: e.to_string(),
+				data: None,
+			},
 			e => errors::internal(e),
 		}
 	}
@@ -24,11 +24,13 @@ mod errors;
 mod helpers;
 mod metadata;
 mod policy;
+mod subscriptions;
 
 pub use helpers::Receiver;
 pub use jsonrpc_core::IoHandlerExtension as RpcExtension;
 pub use metadata::Metadata;
 pub use policy::DenyUnsafe;
+pub use subscriptions::SubscriptionManager;
 
 pub mod author;
 pub mod chain;
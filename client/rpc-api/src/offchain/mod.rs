@@ -21,6 +21,7 @@
 pub mod error;
 
 use jsonrpc_derive::rpc;
+use serde::{Serialize, Deserialize};
 use self::error::Result;
 use sp_core::{Bytes, offchain::StorageKind};
 
@@ -36,4 +37,28 @@ pub trait OffchainApi {
 	/// Get offchain local storage under given key and prefix.
 	#[rpc(name = "offchain_localStorageGet")]
 	fn get_local_storage(&self, kind: StorageKind, key: Bytes) -> Result<Option<Bytes>>;
+
+	/// Returns up to `count` entries written by the runtime through the offchain indexing API
+	/// in finalized blocks, with keys starting with `prefix`.
+	///
+	/// If `start_key` is passed, only the keys after it in lexicographic order are returned.
+	/// If `end_key` is passed, only the keys before it are returned.
+	#[rpc(name = "offchain_queryIndexed")]
+	fn query_indexed(
+		&self,
+		prefix: Bytes,
+		count: u32,
+		start_key: Option<Bytes>,
+		end_key: Option<Bytes>,
+	) -> Result<IndexedEntries>;
+}
+
+/// A page of entries written through the offchain indexing API.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexedEntries {
+	/// Key-value pairs, ordered by key.
+	pub entries: Vec<(Bytes, Bytes)>,
+	/// Key to pass as `start_key` to query the next page, `None` on the last page.
+	pub next_key: Option<Bytes>,
 }
@@ -27,6 +27,7 @@ use jsonrpc_pubsub::{typed::Subscriber, SubscriptionId};
 use sp_core::Bytes;
 use sp_core::storage::{StorageKey, StorageData, StorageChangeSet};
 use sp_version::RuntimeVersion;
+use sp_rpc::tracing::TraceBlockResponse;
 use self::error::FutureResult;
 
 pub use self::gen_client::Client as StateClient;
@@ -107,6 +108,23 @@ pub trait StateApi<Hash> {
 	#[rpc(name = "state_getReadProof")]
 	fn read_proof(&self, keys: Vec<StorageKey>, hash: Option<Hash>) -> FutureResult<ReadProof<Hash>>;
 
+	/// Re-executes the given block with tracing enabled and returns the recorded spans and events.
+	///
+	/// `targets` is a comma separated list of tracing targets, optionally with a level
+	/// (`"pallet=debug,state"`); runtime pallets, the executive and storage accesses are traced
+	/// if omitted. `storage_keys` is a comma separated list of hex encoded key prefixes that the
+	/// recorded storage reads and writes are restricted to.
+	///
+	/// Runtime spans are only available if the runtime was built with the `with-tracing`
+	/// feature. This is an unsafe RPC: re-executing blocks is expensive.
+	#[rpc(name = "state_traceBlock")]
+	fn trace_block(
+		&self,
+		block: Hash,
+		targets: Option<String>,
+		storage_keys: Option<String>,
+	) -> FutureResult<TraceBlockResponse>;
+
 	/// New runtime version subscription
 	#[pubsub(
 		subscription = "state_runtimeVersion",
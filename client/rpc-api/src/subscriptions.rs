@@ -0,0 +1,141 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Subscriptions manager notifying the subscriptions ended by the server.
+
+use std::sync::Arc;
+use jsonrpc_core::futures::{Future, IntoFuture};
+use jsonrpc_pubsub::{
+	manager::{self, TaskExecutor},
+	typed::{Sink, Subscriber},
+	SubscriptionId,
+};
+use parking_lot::Mutex;
+
+/// Callback notified of the subscriptions ended by the server.
+type OnEnd = Arc<dyn Fn(&SubscriptionId) + Send + Sync>;
+
+/// Subscriptions manager, see [`jsonrpc_pubsub::manager::SubscriptionManager`].
+///
+/// Unlike the cancelled subscriptions, the subscriptions ending on their own (e.g. the watched
+/// extrinsic is finalized) are not visible to the RPC server, so they are notified to the
+/// `on_end` callback.
+#[derive(Clone)]
+pub struct SubscriptionManager {
+	inner: manager::SubscriptionManager,
+	on_end: Option<OnEnd>,
+}
+
+impl SubscriptionManager {
+	/// Creates a new subscriptions manager.
+	pub fn new(executor: TaskExecutor) -> Self {
+		SubscriptionManager {
+			inner: manager::SubscriptionManager::new(executor),
+			on_end: None,
+		}
+	}
+
+	/// Notify `on_end` of the subscriptions ended by the server.
+	pub fn with_on_end(mut self, on_end: impl Fn(&SubscriptionId) + Send + Sync + 'static) -> Self {
+		self.on_end = Some(Arc::new(on_end));
+		self
+	}
+
+	/// Borrows the internal task executor.
+	pub fn executor(&self) -> &TaskExecutor {
+		self.inner.executor()
+	}
+
+	/// Creates new subscription for given subscriber.
+	///
+	/// Second parameter is a function that converts Subscriber Sink into a Future.
+	/// This future will be driven to completion by the underlying event loop.
+	pub fn add<T, E, G, R, F>(&self, subscriber: Subscriber<T, E>, into_future: G) -> SubscriptionId
+	where
+		G: FnOnce(Sink<T, E>) -> R,
+		R: IntoFuture<Future = F, Item = (), Error = ()>,
+		F: Future<Item = (), Error = ()> + Send + 'static,
+	{
+		let on_end = match self.on_end {
+			Some(ref on_end) => on_end.clone(),
+			None => return self.inner.add(subscriber, into_future),
+		};
+
+		// the subscription may end before its id is known, whichever comes last notifies the end.
+		let state = Arc::new(Mutex::new((None::<SubscriptionId>, false)));
+		let id = self.inner.add(subscriber, {
+			let state = state.clone();
+			let on_end = on_end.clone();
+			move |sink| into_future(sink).into_future().then(move |result| {
+				let mut state = state.lock();
+				state.1 = true;
+				if let Some(ref id) = state.0 {
+					on_end(id);
+				}
+				result
+			})
+		});
+
+		let mut state = state.lock();
+		state.0 = Some(id.clone());
+		if state.1 {
+			on_end(&id);
+		}
+		id
+	}
+
+	/// Cancel subscription.
+	///
+	/// Returns true if subscription existed or false otherwise.
+	pub fn cancel(&self, id: SubscriptionId) -> bool {
+		self.inner.cancel(id)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use jsonrpc_core::futures::future::{self, ExecuteError, Executor};
+
+	/// Executor driving the subscriptions to completion when they are added.
+	struct Immediate;
+
+	impl Executor<Box<dyn Future<Item = (), Error = ()> + Send>> for Immediate {
+		fn execute(
+			&self,
+			future: Box<dyn Future<Item = (), Error = ()> + Send>,
+		) -> Result<(), ExecuteError<Box<dyn Future<Item = (), Error = ()> + Send>>> {
+			let _ = future.wait();
+			Ok(())
+		}
+	}
+
+	#[test]
+	fn subscriptions_ended_by_the_server_are_notified() {
+		let ended = Arc::new(Mutex::new(Vec::new()));
+		let manager = SubscriptionManager::new(Arc::new(Immediate)).with_on_end({
+			let ended = ended.clone();
+			move |id: &SubscriptionId| ended.lock().push(id.clone())
+		});
+
+		let (subscriber, _id, _transport) = Subscriber::<()>::new_test("test_subscribe");
+		let id = manager.add(subscriber, |_| future::ok(()));
+
+		assert_eq!(*ended.lock(), vec![id]);
+	}
+}
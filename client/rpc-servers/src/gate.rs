@@ -0,0 +1,304 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! TCP gate in front of the HTTP and WS servers, applying the per-IP rate limit.
+//!
+//! The servers don't expose the address of the remote socket of a request. When a per-IP rate
+//! limit is configured, they listen on a loopback address and the gate accepts the connections
+//! on the public address instead. The rate limit is keyed on the address of the remote socket,
+//! or on the address forwarded in the `X-Forwarded-For` or `X-Real-IP` headers if the remote
+//! socket is one of the trusted proxies. The accepted connections are relayed to the server.
+//!
+//! The HTTP server doesn't keep connections alive behind the gate, so that every HTTP request
+//! opens a new connection and is counted by the rate limit.
+//!
+//! Every relayed connection is served by two threads. At most `max_connections` connections are
+//! relayed at once, further connections are refused until some of them are closed.
+
+use std::{
+	io::{self, Write},
+	net::{IpAddr, Ipv4Addr, Ipv6Addr, Shutdown, SocketAddr, TcpListener, TcpStream},
+	sync::{Arc, atomic::{AtomicBool, AtomicUsize, Ordering}},
+	thread,
+	time::{Duration, Instant},
+};
+use log::{debug, warn};
+
+use crate::limits::{forwarded_ip, IpRateLimit};
+
+/// Maximum size of the request head read to find the forwarded address.
+const MAX_HEAD_SIZE: usize = 8 * 1024;
+
+/// Time given to a trusted proxy to send the request head.
+const HEAD_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Response to a connection exceeding the rate limit, valid for HTTP requests and WS handshakes.
+const TOO_MANY_REQUESTS: &[u8] = b"HTTP/1.1 429 Too Many Requests\r\n\
+	Content-Type: text/plain; charset=utf-8\r\n\
+	Content-Length: 17\r\n\
+	Connection: close\r\n\r\n\
+	Too many requests";
+
+/// Response to a connection exceeding the number of connections relayed at once.
+const SERVICE_UNAVAILABLE: &[u8] = b"HTTP/1.1 503 Service Unavailable\r\n\
+	Content-Type: text/plain; charset=utf-8\r\n\
+	Content-Length: 20\r\n\
+	Connection: close\r\n\r\n\
+	Too many connections";
+
+/// A listener bound to the public address of a server, not accepting connections yet.
+pub(crate) struct GateListener(TcpListener);
+
+impl GateListener {
+	/// Bind to the public address of the server.
+	pub(crate) fn bind(addr: &SocketAddr) -> io::Result<Self> {
+		TcpListener::bind(addr).map(GateListener)
+	}
+
+	/// The address the gate listens on.
+	pub(crate) fn local_addr(&self) -> io::Result<SocketAddr> {
+		self.0.local_addr()
+	}
+
+	/// Start accepting connections and relaying them to the server listening on `server_addr`,
+	/// at most `max_connections` at once.
+	pub(crate) fn start(
+		self,
+		server_addr: SocketAddr,
+		trusted_proxies: Vec<IpAddr>,
+		rate_limit: IpRateLimit,
+		max_connections: usize,
+	) -> io::Result<Gate> {
+		let local_addr = self.0.local_addr()?;
+		let closed = Arc::new(AtomicBool::new(false));
+		let thread = {
+			let closed = closed.clone();
+			let trusted_proxies = Arc::new(trusted_proxies);
+			let rate_limit = Arc::new(rate_limit);
+			let active = Arc::new(AtomicUsize::new(0));
+			thread::Builder::new()
+				.name(format!("rpc-gate-{}", local_addr))
+				.spawn(move || {
+					for stream in self.0.incoming() {
+						if closed.load(Ordering::SeqCst) {
+							break;
+						}
+						let stream = match stream {
+							Ok(stream) => stream,
+							Err(err) => {
+								debug!(target: "rpc", "Failed to accept connection: {}", err);
+								continue;
+							},
+						};
+						let slot = match ConnectionSlot::take(&active, max_connections) {
+							Some(slot) => slot,
+							None => {
+								debug!(target: "rpc", "Refusing connection, {} are relayed already", max_connections);
+								reject(stream, SERVICE_UNAVAILABLE);
+								continue;
+							},
+						};
+						let trusted_proxies = trusted_proxies.clone();
+						let rate_limit = rate_limit.clone();
+						let spawned = thread::Builder::new()
+							.name("rpc-gate-connection".into())
+							.spawn(move || {
+								serve(stream, server_addr, &trusted_proxies, &rate_limit);
+								drop(slot);
+							});
+						if let Err(err) = spawned {
+							warn!(target: "rpc", "Failed to spawn a thread for a connection: {}", err);
+						}
+					}
+				})?
+		};
+
+		Ok(Gate {
+			local_addr,
+			closed,
+			thread: Some(thread),
+		})
+	}
+}
+
+/// A running gate. It stops accepting connections when it is dropped.
+pub(crate) struct Gate {
+	local_addr: SocketAddr,
+	closed: Arc<AtomicBool>,
+	thread: Option<thread::JoinHandle<()>>,
+}
+
+impl Drop for Gate {
+	fn drop(&mut self) {
+		self.closed.store(true, Ordering::SeqCst);
+		// wake up the accepting thread, so it notices the gate is closed.
+		let mut addr = self.local_addr;
+		match addr.ip() {
+			IpAddr::V4(ip) if ip.is_unspecified() => addr.set_ip(Ipv4Addr::LOCALHOST.into()),
+			IpAddr::V6(ip) if ip.is_unspecified() => addr.set_ip(Ipv6Addr::LOCALHOST.into()),
+			_ => {},
+		}
+		let _ = TcpStream::connect(addr);
+		if let Some(thread) = self.thread.take() {
+			let _ = thread.join();
+		}
+	}
+}
+
+/// A connection counted against the maximum number of connections relayed at once, until it is
+/// dropped.
+struct ConnectionSlot(Arc<AtomicUsize>);
+
+impl ConnectionSlot {
+	/// Take one of the `max` slots, `None` if they are all taken.
+	fn take(active: &Arc<AtomicUsize>, max: usize) -> Option<Self> {
+		if active.fetch_add(1, Ordering::SeqCst) < max {
+			Some(ConnectionSlot(active.clone()))
+		} else {
+			active.fetch_sub(1, Ordering::SeqCst);
+			None
+		}
+	}
+}
+
+impl Drop for ConnectionSlot {
+	fn drop(&mut self) {
+		self.0.fetch_sub(1, Ordering::SeqCst);
+	}
+}
+
+/// The loopback address the server listens on behind a gate listening on `addr`.
+pub(crate) fn server_addr(addr: &SocketAddr) -> SocketAddr {
+	match addr {
+		SocketAddr::V4(_) => (Ipv4Addr::LOCALHOST, 0).into(),
+		SocketAddr::V6(_) => (Ipv6Addr::LOCALHOST, 0).into(),
+	}
+}
+
+/// Apply the rate limit to the connection and relay it to the server if it is allowed.
+fn serve(
+	stream: TcpStream,
+	server_addr: SocketAddr,
+	trusted_proxies: &[IpAddr],
+	rate_limit: &IpRateLimit,
+) {
+	let peer = match stream.peer_addr() {
+		Ok(peer) => peer.ip(),
+		Err(_) => return,
+	};
+	let ip = if trusted_proxies.contains(&peer) {
+		peek_forwarded_ip(&stream).unwrap_or(peer)
+	} else {
+		peer
+	};
+
+	if !rate_limit.check(ip) {
+		reject(stream, TOO_MANY_REQUESTS);
+		return;
+	}
+
+	if let Err(err) = relay(stream, server_addr) {
+		debug!(target: "rpc", "Failed to relay connection from {}: {}", ip, err);
+	}
+}
+
+/// Answer the connection with `response` and close it.
+fn reject(mut stream: TcpStream, response: &[u8]) {
+	let _ = stream.write_all(response);
+	let _ = stream.shutdown(Shutdown::Both);
+}
+
+/// Read the forwarded address from the request head, without consuming it.
+fn peek_forwarded_ip(stream: &TcpStream) -> Option<IpAddr> {
+	let deadline = Instant::now() + HEAD_TIMEOUT;
+	let mut buf = vec![0; MAX_HEAD_SIZE];
+	stream.set_read_timeout(Some(HEAD_TIMEOUT)).ok()?;
+	let head = loop {
+		let len = stream.peek(&mut buf).ok()?;
+		if let Some(end) = buf[..len].windows(4).position(|w| w == b"\r\n\r\n") {
+			break &buf[..end];
+		}
+		if len == 0 || len == buf.len() || Instant::now() >= deadline {
+			return None;
+		}
+		// the head is incomplete, wait for more data.
+		thread::sleep(Duration::from_millis(10));
+	};
+	stream.set_read_timeout(None).ok()?;
+
+	let head = std::str::from_utf8(head).ok()?;
+	let header = |name: &str| head.lines().skip(1).find_map(|line| {
+		let mut parts = line.splitn(2, ':');
+		match (parts.next(), parts.next()) {
+			(Some(key), Some(value)) if key.trim().eq_ignore_ascii_case(name) => Some(value),
+			_ => None,
+		}
+	});
+	forwarded_ip(header("x-forwarded-for"), header("x-real-ip"))
+}
+
+/// Copy the data in both directions between the connection and the server until both are done.
+fn relay(client: TcpStream, server_addr: SocketAddr) -> io::Result<()> {
+	let server = TcpStream::connect(server_addr)?;
+	let (mut client_read, mut server_write) = (client.try_clone()?, server.try_clone()?);
+	let upstream = thread::Builder::new()
+		.name("rpc-gate-relay".into())
+		.spawn(move || {
+			let _ = io::copy(&mut client_read, &mut server_write);
+			let _ = server_write.shutdown(Shutdown::Write);
+		})?;
+
+	let (mut server_read, mut client_write) = (server, client);
+	let result = io::copy(&mut server_read, &mut client_write).map(|_| ());
+	let _ = client_write.shutdown(Shutdown::Both);
+	let _ = upstream.join();
+	result
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::io::Read;
+
+	#[test]
+	fn forwarded_ip_is_peeked_without_consuming_the_request() {
+		let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+		let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+		let request = b"GET / HTTP/1.1\r\nHost: localhost\r\nX-Forwarded-For: 10.0.0.1, 10.0.0.2\r\n\r\n";
+		client.write_all(request).unwrap();
+
+		let (mut stream, _) = listener.accept().unwrap();
+		assert_eq!(peek_forwarded_ip(&stream), Some([10, 0, 0, 2].into()));
+
+		let mut received = vec![0; request.len()];
+		stream.read_exact(&mut received).unwrap();
+		assert_eq!(&received[..], &request[..]);
+	}
+
+	#[test]
+	fn connection_slots_are_bounded() {
+		let active = Arc::new(AtomicUsize::new(0));
+		let first = ConnectionSlot::take(&active, 2).unwrap();
+		let _second = ConnectionSlot::take(&active, 2).unwrap();
+		assert!(ConnectionSlot::take(&active, 2).is_none());
+
+		drop(first);
+		assert!(ConnectionSlot::take(&active, 2).is_some());
+		assert_eq!(active.load(Ordering::SeqCst), 1);
+	}
+}
@@ -20,6 +20,9 @@
 
 #![warn(missing_docs)]
 
+#[cfg(not(target_os = "unknown"))]
+mod gate;
+mod limits;
 mod middleware;
 
 use std::io;
@@ -27,16 +30,20 @@ use jsonrpc_core::{IoHandlerExtension, MetaIoHandler};
 use log::error;
 use pubsub::PubSubMetadata;
 
-/// Maximal payload accepted by RPC servers.
+/// Default maximal payload accepted by RPC servers.
 const MAX_PAYLOAD: usize = 15 * 1024 * 1024;
 
 /// Default maximum number of connections for WS RPC servers.
 const WS_MAX_CONNECTIONS: usize = 100;
 
+/// Maximum number of connections relayed at once to a HTTP RPC server behind a rate limiting gate.
+const HTTP_MAX_CONNECTIONS: usize = 100;
+
 /// The RPC IoHandler containing all requested APIs.
 pub type RpcHandler<T> = pubsub::PubSubHandler<T, RpcMiddleware>;
 
 pub use self::inner::*;
+pub use limits::RpcLimits;
 pub use middleware::{RpcMiddleware, RpcMetrics};
 
 /// Construct rpc `IoHandler`
@@ -70,9 +77,35 @@ mod inner {
 	/// Type alias for ipc server
 	pub type IpcServer = ipc::Server;
 	/// Type alias for http server
-	pub type HttpServer = http::Server;
+	pub type HttpServer = Server<http::Server>;
 	/// Type alias for ws server
-	pub type WsServer = ws::Server;
+	pub type WsServer = Server<ws::Server>;
+
+	/// A HTTP or WS server, behind a gate applying the per-IP rate limit if one is configured.
+	pub struct Server<S> {
+		server: S,
+		gate: Option<gate::Gate>,
+	}
+
+	impl HttpServer {
+		/// Stop accepting connections and wait for the server to shut down.
+		pub fn close(self) {
+			let Server { server, gate } = self;
+			drop(gate);
+			server.close_handle().close();
+			server.wait();
+		}
+	}
+
+	impl WsServer {
+		/// Stop accepting connections and wait for the server to shut down.
+		pub fn close(self) {
+			let Server { server, gate } = self;
+			drop(gate);
+			server.close_handle().close();
+			let _ = server.wait();
+		}
+	}
 
 	/// Start HTTP server listening on given address.
 	///
@@ -80,20 +113,42 @@ mod inner {
 	pub fn start_http<M: pubsub::PubSubMetadata + Default>(
 		addr: &std::net::SocketAddr,
 		cors: Option<&Vec<String>>,
+		limits: &RpcLimits,
+		rpc_metrics: Option<&RpcMetrics>,
 		io: RpcHandler<M>,
-	) -> io::Result<http::Server> {
-		http::ServerBuilder::new(io)
+	) -> io::Result<HttpServer> {
+		let rate_limit = limits::IpRateLimit::new(limits, rpc_metrics.cloned(), "http");
+		let listener = rate_limit.as_ref().map(|_| gate::GateListener::bind(addr)).transpose()?;
+		let public_addr = listener.as_ref().map(|listener| listener.local_addr()).transpose()?;
+		let server_addr = public_addr.as_ref().map_or(*addr, gate::server_addr);
+
+		let server = http::ServerBuilder::new(io)
 			.threads(4)
+			// behind the gate every request has to open a new connection to be rate limited.
+			.keep_alive(public_addr.is_none())
 			.health_api(("/health", "system_health"))
-			.allowed_hosts(hosts_filtering(cors.is_some()))
+			.allowed_hosts(hosts_filtering(cors.is_some(), public_addr.as_ref()))
 			.rest_api(if cors.is_some() {
 				http::RestApi::Secure
 			} else {
 				http::RestApi::Unsecure
 			})
 			.cors(map_cors::<http::AccessControlAllowOrigin>(cors))
-			.max_request_body_size(MAX_PAYLOAD)
-			.start_http(addr)
+			.max_request_body_size(limits.max_request_size)
+			.start_http(&server_addr)?;
+
+		let gate = match (listener, rate_limit) {
+			(Some(listener), Some(rate_limit)) => Some(
+				listener.start(
+					*server.address(),
+					limits.trusted_proxies.clone(),
+					rate_limit,
+					HTTP_MAX_CONNECTIONS,
+				)?
+			),
+			_ => None,
+		};
+		Ok(Server { server, gate })
 	}
 
 	/// Start IPC server listening on given path.
@@ -120,14 +175,23 @@ mod inner {
 		addr: &std::net::SocketAddr,
 		max_connections: Option<usize>,
 		cors: Option<&Vec<String>>,
+		limits: &RpcLimits,
+		rpc_metrics: Option<&RpcMetrics>,
 		io: RpcHandler<M>,
-	) -> io::Result<ws::Server> {
-		ws::ServerBuilder::with_meta_extractor(io, |context: &ws::RequestContext| context.sender().into())
-			.max_payload(MAX_PAYLOAD)
-			.max_connections(max_connections.unwrap_or(WS_MAX_CONNECTIONS))
+	) -> io::Result<WsServer> {
+		let max_connections = max_connections.unwrap_or(WS_MAX_CONNECTIONS);
+		let rate_limit = limits::IpRateLimit::new(limits, rpc_metrics.cloned(), "ws");
+		let listener = rate_limit.as_ref().map(|_| gate::GateListener::bind(addr)).transpose()?;
+		let public_addr = listener.as_ref().map(|listener| listener.local_addr()).transpose()?;
+		let server_addr = public_addr.as_ref().map_or(*addr, gate::server_addr);
+
+		let server = ws::ServerBuilder::with_meta_extractor(io, |context: &ws::RequestContext| context.sender().into())
+			// the payload limit applies to messages in both directions
+			.max_payload(std::cmp::max(limits.max_request_size, limits.max_response_size))
+			.max_connections(max_connections)
 			.allowed_origins(map_cors(cors))
-			.allowed_hosts(hosts_filtering(cors.is_some()))
-			.start(addr)
+			.allowed_hosts(hosts_filtering(cors.is_some(), public_addr.as_ref()))
+			.start(&server_addr)
 			.map_err(|err| match err {
 				ws::Error::Io(io) => io,
 				ws::Error::ConnectionClosed => io::ErrorKind::BrokenPipe.into(),
@@ -135,7 +199,15 @@ mod inner {
 					error!("{}", e);
 					io::ErrorKind::Other.into()
 				}
-			})
+			})?;
+
+		let gate = match (listener, rate_limit) {
+			(Some(listener), Some(rate_limit)) => Some(
+				listener.start(*server.addr(), limits.trusted_proxies.clone(), rate_limit, max_connections)?
+			),
+			_ => None,
+		};
+		Ok(Server { server, gate })
 	}
 
 	fn map_cors<T: for<'a> From<&'a str>>(
@@ -144,16 +216,34 @@ mod inner {
 		cors.map(|x| x.iter().map(AsRef::as_ref).map(Into::into).collect::<Vec<_>>()).into()
 	}
 
-	fn hosts_filtering(enable: bool) -> http::DomainsValidation<http::Host> {
+	fn hosts_filtering(
+		enable: bool,
+		public_addr: Option<&std::net::SocketAddr>,
+	) -> http::DomainsValidation<http::Host> {
 		if enable {
 			// NOTE The listening address is whitelisted by default.
 			// Setting an empty vector here enables the validation
-			// and allows only the listening address.
-			http::DomainsValidation::AllowOnly(vec![])
+			// and allows only the listening address, which is the
+			// loopback address of the server behind a gate.
+			http::DomainsValidation::AllowOnly(public_addr.map_or_else(Vec::new, public_hosts))
 		} else {
 			http::DomainsValidation::Disabled
 		}
 	}
+
+	/// The hosts of the public address of a gate, as whitelisted by the servers for their own address.
+	fn public_hosts(addr: &std::net::SocketAddr) -> Vec<http::Host> {
+		let address = addr.to_string();
+		let mut hosts = Vec::new();
+		if addr.ip() == std::net::IpAddr::from(std::net::Ipv4Addr::UNSPECIFIED) {
+			hosts.push(address.replace("0.0.0.0", "127.0.0.1").into());
+			hosts.push(address.replace("0.0.0.0", "localhost").into());
+		} else if addr.ip() == std::net::IpAddr::from(std::net::Ipv4Addr::LOCALHOST) {
+			hosts.push(address.replace("127.0.0.1", "localhost").into());
+		}
+		hosts.push(address.into());
+		hosts
+	}
 }
 
 #[cfg(target_os = "unknown")]
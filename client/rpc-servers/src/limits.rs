@@ -0,0 +1,383 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Limits applied to the calls of the RPC servers.
+
+use std::{
+	collections::HashMap, hash::Hash, net::{IpAddr, Ipv6Addr}, num::NonZeroU32, sync::Mutex,
+	time::{Duration, Instant},
+};
+use jsonrpc_core::{Error, ErrorCode};
+
+/// Default maximum number of subscriptions on a single connection.
+const DEFAULT_MAX_SUBSCRIPTIONS_PER_CONNECTION: usize = 1024;
+
+/// Interval between the sweeps dropping the rate limiting buckets which are full again, i.e. whose
+/// key has been idle for at least a second.
+const BUCKET_SWEEP_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Limits and filters applied to the calls of the HTTP and WS servers.
+#[derive(Debug, Clone)]
+pub struct RpcLimits {
+	/// Methods that are allowed to be called, `None` if all methods are allowed.
+	///
+	/// A trailing `*` matches any method starting with the given prefix.
+	pub methods_allow: Option<Vec<String>>,
+	/// Methods that are never allowed to be called, in the same format as `methods_allow`.
+	pub methods_deny: Vec<String>,
+	/// Maximum number of requests (HTTP) or new connections (WS) per second from one IP address.
+	pub rate_limit_ip: Option<NonZeroU32>,
+	/// Addresses of the reverse proxies allowed to forward the address of the client in the
+	/// `X-Forwarded-For` or `X-Real-IP` headers, for the per-IP rate limit.
+	pub trusted_proxies: Vec<IpAddr>,
+	/// Maximum number of calls per second on a single WS connection.
+	pub rate_limit_connection: Option<NonZeroU32>,
+	/// Maximum size of the body of a request in bytes.
+	///
+	/// The WS servers limit the messages in both directions to the larger of
+	/// `max_request_size` and `max_response_size`.
+	pub max_request_size: usize,
+	/// Maximum size of a response in bytes.
+	pub max_response_size: usize,
+	/// Maximum number of subscriptions on a single WS connection.
+	pub max_subscriptions_per_connection: usize,
+}
+
+impl Default for RpcLimits {
+	fn default() -> Self {
+		RpcLimits {
+			methods_allow: None,
+			methods_deny: Vec::new(),
+			rate_limit_ip: None,
+			trusted_proxies: Vec::new(),
+			rate_limit_connection: None,
+			max_request_size: crate::MAX_PAYLOAD,
+			max_response_size: crate::MAX_PAYLOAD,
+			max_subscriptions_per_connection: DEFAULT_MAX_SUBSCRIPTIONS_PER_CONNECTION,
+		}
+	}
+}
+
+impl RpcLimits {
+	/// Returns true if the given method may be called.
+	pub fn is_method_allowed(&self, method: &str) -> bool {
+		let matches = |pattern: &String| if pattern.ends_with('*') {
+			method.starts_with(&pattern[..pattern.len() - 1])
+		} else {
+			method == pattern
+		};
+
+		self.methods_allow.as_ref().map_or(true, |allow| allow.iter().any(matches))
+			&& !self.methods_deny.iter().any(matches)
+	}
+}
+
+/// Reason of rejecting a call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Rejection {
+	/// The method is not allowed.
+	Method,
+	/// The rate limit was exceeded.
+	RateLimit,
+	/// The response is too large.
+	ResponseSize,
+	/// The connection has too many subscriptions.
+	Subscriptions,
+}
+
+impl Rejection {
+	/// Label of the Prometheus counter.
+	pub(crate) fn label(&self) -> &'static str {
+		match self {
+			Rejection::Method => "method",
+			Rejection::RateLimit => "rate_limit",
+			Rejection::ResponseSize => "response_size",
+			Rejection::Subscriptions => "subscriptions",
+		}
+	}
+
+	/// Error returned to the caller.
+	pub(crate) fn error(&self) -> Error {
+		let (code, message) = match self {
+			Rejection::Method => return Error::method_not_found(),
+			Rejection::RateLimit => (-32029, "Too many requests"),
+			Rejection::ResponseSize => (-32014, "Response is too large"),
+			Rejection::Subscriptions => (-32015, "Too many subscriptions"),
+		};
+		Error {
+			code: ErrorCode::ServerError(code),
+			message: message.into(),
+			data: None,
+		}
+	}
+}
+
+/// Kind of a call affecting the number of subscriptions of a connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SubscriptionCall {
+	/// Creates a subscription.
+	Subscribe,
+	/// Cancels a subscription.
+	Unsubscribe,
+}
+
+impl SubscriptionCall {
+	/// Classifies the method by its name (e.g. `chain_subscribeNewHeads`, `author_unwatchExtrinsic`).
+	pub(crate) fn from_method(method: &str) -> Option<Self> {
+		let name = method.rsplit('_').next().unwrap_or(method).to_lowercase();
+		if name.contains("unsubscribe") || name.contains("unwatch") {
+			Some(SubscriptionCall::Unsubscribe)
+		} else if name.contains("subscribe") || name.contains("watch") {
+			Some(SubscriptionCall::Subscribe)
+		} else {
+			None
+		}
+	}
+}
+
+/// Token bucket refilled with `rate` tokens per second, holding at most one second worth of tokens.
+#[derive(Debug)]
+pub(crate) struct TokenBucket {
+	rate: f64,
+	tokens: f64,
+	updated: Instant,
+}
+
+impl TokenBucket {
+	/// Create a new full bucket.
+	pub(crate) fn new(rate: NonZeroU32, now: Instant) -> Self {
+		let rate = f64::from(rate.get());
+		TokenBucket {
+			rate,
+			tokens: rate,
+			updated: now,
+		}
+	}
+
+	fn refill(&mut self, now: Instant) {
+		let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+		self.tokens = (self.tokens + elapsed * self.rate).min(self.rate);
+		self.updated = now;
+	}
+
+	/// Take a token from the bucket, returns false if it is empty.
+	pub(crate) fn try_take(&mut self, now: Instant) -> bool {
+		self.refill(now);
+		if self.tokens >= 1.0 {
+			self.tokens -= 1.0;
+			true
+		} else {
+			false
+		}
+	}
+
+	fn is_full(&mut self, now: Instant) -> bool {
+		self.refill(now);
+		self.tokens >= self.rate
+	}
+}
+
+/// Token buckets of a rate limiter.
+#[derive(Debug)]
+struct Buckets<K> {
+	buckets: HashMap<K, TokenBucket>,
+	swept: Instant,
+}
+
+/// Rate limiter keeping a token bucket for each key.
+///
+/// The buckets of idle keys are dropped periodically, so that the memory used is bounded by the
+/// number of keys seen within `BUCKET_SWEEP_INTERVAL`.
+#[derive(Debug)]
+pub(crate) struct RateLimiter<K> {
+	rate: NonZeroU32,
+	buckets: Mutex<Buckets<K>>,
+}
+
+impl<K: Hash + Eq> RateLimiter<K> {
+	/// Create a new rate limiter allowing `rate` events per second for each key.
+	pub(crate) fn new(rate: NonZeroU32) -> Self {
+		RateLimiter {
+			rate,
+			buckets: Mutex::new(Buckets {
+				buckets: HashMap::new(),
+				swept: Instant::now(),
+			}),
+		}
+	}
+
+	/// Record an event for the given key, returns false if the rate limit is exceeded.
+	pub(crate) fn check(&self, key: K) -> bool {
+		self.check_at(key, Instant::now())
+	}
+
+	fn check_at(&self, key: K, now: Instant) -> bool {
+		let mut buckets = self.buckets.lock().expect("Rate limiter lock is never poisoned; qed");
+		if now.saturating_duration_since(buckets.swept) >= BUCKET_SWEEP_INTERVAL {
+			buckets.buckets.retain(|_, bucket| !bucket.is_full(now));
+			buckets.swept = now;
+		}
+		let rate = self.rate;
+		buckets.buckets.entry(key).or_insert_with(|| TokenBucket::new(rate, now)).try_take(now)
+	}
+
+	#[cfg(test)]
+	fn len(&self) -> usize {
+		self.buckets.lock().expect("Rate limiter lock is never poisoned; qed").buckets.len()
+	}
+}
+
+/// The key of the rate limit of `ip`.
+///
+/// IPv6 clients usually get a whole /64 network, so they are limited per /64 network rather than
+/// per address.
+fn rate_limit_key(ip: IpAddr) -> IpAddr {
+	match ip {
+		IpAddr::V4(_) => ip,
+		IpAddr::V6(ip) => {
+			let network = u128::from(ip) & !(u128::from(u64::max_value()));
+			IpAddr::V6(Ipv6Addr::from(network))
+		},
+	}
+}
+
+/// Extract the address of the client from the headers set by a reverse proxy.
+///
+/// The headers can be set by anyone, so they are only read from the requests of trusted proxies.
+/// The proxy appends the address of its client to `X-Forwarded-For`, the entries before it come
+/// from the client and are ignored.
+pub(crate) fn forwarded_ip(forwarded_for: Option<&str>, real_ip: Option<&str>) -> Option<IpAddr> {
+	forwarded_for
+		.and_then(|value| value.rsplit(',').next())
+		.or(real_ip)
+		.and_then(|value| value.trim().parse().ok())
+}
+
+#[cfg(not(target_os = "unknown"))]
+mod transport {
+	use super::*;
+	use crate::middleware::RpcMetrics;
+
+	/// Per-IP rate limiting of the HTTP requests and WS connections.
+	pub struct IpRateLimit {
+		limiter: RateLimiter<IpAddr>,
+		metrics: Option<RpcMetrics>,
+		transport_label: &'static str,
+	}
+
+	impl IpRateLimit {
+		/// Create a new per-IP rate limit, `None` if it is disabled by `limits`.
+		pub fn new(
+			limits: &RpcLimits,
+			metrics: Option<RpcMetrics>,
+			transport_label: &'static str,
+		) -> Option<Self> {
+			limits.rate_limit_ip.map(|rate| IpRateLimit {
+				limiter: RateLimiter::new(rate),
+				metrics,
+				transport_label,
+			})
+		}
+
+		/// Record a request or connection from `ip`, returns false if the rate limit is exceeded.
+		pub fn check(&self, ip: IpAddr) -> bool {
+			let allowed = self.limiter.check(rate_limit_key(ip));
+			if !allowed {
+				if let Some(ref metrics) = self.metrics {
+					metrics.report_rejection(self.transport_label, Rejection::RateLimit);
+				}
+			}
+			allowed
+		}
+	}
+}
+
+#[cfg(not(target_os = "unknown"))]
+pub(crate) use transport::IpRateLimit;
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn method_filters_work() {
+		let limits = RpcLimits {
+			methods_allow: Some(vec!["chain_*".into(), "system_health".into()]),
+			methods_deny: vec!["chain_subscribe*".into()],
+			..Default::default()
+		};
+
+		assert!(limits.is_method_allowed("chain_getBlock"));
+		assert!(limits.is_method_allowed("system_health"));
+		assert!(!limits.is_method_allowed("system_name"));
+		assert!(!limits.is_method_allowed("chain_subscribeNewHeads"));
+		assert!(RpcLimits::default().is_method_allowed("author_submitExtrinsic"));
+	}
+
+	#[test]
+	fn token_bucket_refills() {
+		let now = Instant::now();
+		let mut bucket = TokenBucket::new(NonZeroU32::new(2).unwrap(), now);
+
+		assert!(bucket.try_take(now));
+		assert!(bucket.try_take(now));
+		assert!(!bucket.try_take(now));
+		assert!(bucket.try_take(now + Duration::from_millis(500)));
+		assert!(!bucket.try_take(now + Duration::from_millis(500)));
+		assert!(bucket.is_full(now + Duration::from_secs(10)));
+	}
+
+	#[test]
+	fn idle_buckets_expire() {
+		let limiter = RateLimiter::new(NonZeroU32::new(1).unwrap());
+		let now = Instant::now();
+
+		assert!(limiter.check_at(1, now));
+		assert!(!limiter.check_at(1, now));
+		assert!(limiter.check_at(2, now + Duration::from_secs(1)));
+		assert_eq!(limiter.len(), 2);
+
+		assert!(limiter.check_at(3, now + BUCKET_SWEEP_INTERVAL));
+		assert_eq!(limiter.len(), 1);
+	}
+
+	#[test]
+	fn ipv6_is_limited_per_network() {
+		let ip = |s: &str| s.parse::<IpAddr>().unwrap();
+		assert_eq!(rate_limit_key(ip("2001:db8:1:2:3:4:5:6")), ip("2001:db8:1:2::"));
+		assert_eq!(rate_limit_key(ip("2001:db8:1:2::ffff")), ip("2001:db8:1:2::"));
+		assert_ne!(rate_limit_key(ip("2001:db8:1:3::1")), ip("2001:db8:1:2::"));
+		assert_eq!(rate_limit_key(ip("10.0.0.1")), ip("10.0.0.1"));
+	}
+
+	#[test]
+	fn subscription_calls_are_classified() {
+		assert_eq!(SubscriptionCall::from_method("chain_subscribeNewHeads"), Some(SubscriptionCall::Subscribe));
+		assert_eq!(SubscriptionCall::from_method("author_submitAndWatchExtrinsic"), Some(SubscriptionCall::Subscribe));
+		assert_eq!(SubscriptionCall::from_method("state_unsubscribeStorage"), Some(SubscriptionCall::Unsubscribe));
+		assert_eq!(SubscriptionCall::from_method("author_unwatchExtrinsic"), Some(SubscriptionCall::Unsubscribe));
+		assert_eq!(SubscriptionCall::from_method("chain_getHeader"), None);
+	}
+
+	#[test]
+	fn forwarded_ip_is_parsed() {
+		assert_eq!(forwarded_ip(Some("10.0.0.1, 10.0.0.2"), None), Some([10, 0, 0, 2].into()));
+		assert_eq!(forwarded_ip(None, Some("::1")), Some(std::net::Ipv6Addr::LOCALHOST.into()));
+		assert_eq!(forwarded_ip(None, None), None);
+	}
+}
@@ -18,21 +18,28 @@
 
 //! Middleware for RPC requests.
 
+use std::{collections::{HashMap, HashSet}, io, sync::{Arc, Mutex}, time::Instant};
+
 use jsonrpc_core::{
-	Middleware as RequestMiddleware, Metadata,
-	Request, Response, FutureResponse, FutureOutput
+	Middleware as RequestMiddleware,
+	Request, Response, FutureResponse, FutureOutput,
+	Call, Output, Params,
 };
 use prometheus_endpoint::{
 	Registry, CounterVec, PrometheusError,
 	Opts, register, U64
 };
+use pubsub::{PubSubMetadata, Session, SubscriptionId};
+
+use futures::{future::{self, Either}, Future};
 
-use futures::{future::Either, Future};
+use crate::limits::{Rejection, RpcLimits, SubscriptionCall, TokenBucket};
 
 /// Metrics for RPC middleware
 #[derive(Debug, Clone)]
 pub struct RpcMetrics {
 	rpc_calls: CounterVec<U64>,
+	rpc_calls_rejected: CounterVec<U64>,
 }
 
 impl RpcMetrics {
@@ -47,15 +54,36 @@ impl RpcMetrics {
 					),
 					&["protocol"]
 				).ok()?, r).ok()?,
+				rpc_calls_rejected: register(CounterVec::new(
+					Opts::new(
+						"rpc_calls_rejected_total",
+						"Number of rpc calls rejected because of the configured limits",
+					),
+					&["protocol", "reason"]
+				).ok()?, r).ok()?,
 			})
 		}).ok_or(PrometheusError::Msg("Cannot register metric".to_string()))
 	}
+
+	pub(crate) fn report_rejection(&self, transport_label: &str, rejection: Rejection) {
+		self.rpc_calls_rejected.with_label_values(&[transport_label, rejection.label()]).inc();
+	}
 }
 
+/// State of a connection with a persistent session.
+struct Connection {
+	rate: Option<TokenBucket>,
+	subscriptions: HashSet<SubscriptionId>,
+}
+
+type Connections = Arc<Mutex<HashMap<usize, Connection>>>;
+
 /// Middleware for RPC calls
 pub struct RpcMiddleware {
 	metrics: Option<RpcMetrics>,
 	transport_label: String,
+	limits: Option<Arc<RpcLimits>>,
+	connections: Connections,
 }
 
 impl RpcMiddleware {
@@ -65,11 +93,128 @@ impl RpcMiddleware {
 		RpcMiddleware {
 			metrics,
 			transport_label: String::from(transport_label),
+			limits: None,
+			connections: Default::default(),
+		}
+	}
+
+	/// Apply the given limits to the calls passing through the middleware.
+	pub fn with_limits(mut self, limits: RpcLimits) -> Self {
+		self.limits = Some(Arc::new(limits));
+		self
+	}
+
+	/// Callback to be notified of the subscriptions ended by the server, so that they stop
+	/// counting towards the subscriptions limit of their connection.
+	pub fn subscription_ended(&self) -> impl Fn(&SubscriptionId) + Send + Sync + 'static {
+		let connections = Arc::downgrade(&self.connections);
+		move |id: &SubscriptionId| {
+			if let Some(connections) = connections.upgrade() {
+				let mut connections = connections.lock().expect("Connections lock is never poisoned; qed");
+				for connection in connections.values_mut() {
+					if connection.subscriptions.remove(id) {
+						break
+					}
+				}
+			}
+		}
+	}
+
+	fn reject(&self, rejection: Rejection) -> jsonrpc_core::Error {
+		if let Some(ref metrics) = self.metrics {
+			metrics.report_rejection(&self.transport_label, rejection);
+		}
+		rejection.error()
+	}
+
+	/// Check whether the call may be executed, updating the state of the connection.
+	fn check_call(
+		&self,
+		limits: &RpcLimits,
+		method: &str,
+		session: Option<Arc<Session>>,
+	) -> Result<(), Rejection> {
+		if !limits.is_method_allowed(method) {
+			return Err(Rejection::Method)
+		}
+
+		let session = match session {
+			Some(session) => session,
+			None => return Ok(()),
+		};
+		let mut connections = self.connections.lock().expect("Connections lock is never poisoned; qed");
+		let connection = connections.entry(connection_id(&session)).or_insert_with(|| {
+			let connections = Arc::downgrade(&self.connections);
+			let id = connection_id(&session);
+			session.on_drop(move || {
+				if let Some(connections) = connections.upgrade() {
+					connections.lock().expect("Connections lock is never poisoned; qed").remove(&id);
+				}
+			});
+			Connection {
+				rate: limits.rate_limit_connection.map(|rate| TokenBucket::new(rate, Instant::now())),
+				subscriptions: HashSet::new(),
+			}
+		});
+
+		if let Some(ref mut rate) = connection.rate {
+			if !rate.try_take(Instant::now()) {
+				return Err(Rejection::RateLimit)
+			}
 		}
+		if SubscriptionCall::from_method(method) == Some(SubscriptionCall::Subscribe)
+			&& connection.subscriptions.len() >= limits.max_subscriptions_per_connection
+		{
+			return Err(Rejection::Subscriptions)
+		}
+
+		Ok(())
 	}
 }
 
-impl<M: Metadata> RequestMiddleware<M> for RpcMiddleware {
+/// Identifier of the connection the session belongs to, valid as long as the session is alive.
+fn connection_id(session: &Arc<Session>) -> usize {
+	&**session as *const Session as usize
+}
+
+/// Writer counting the bytes written, failing once more than `limit` bytes are written.
+struct SizeLimit {
+	size: usize,
+	limit: usize,
+}
+
+impl io::Write for SizeLimit {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.size += buf.len();
+		if self.size > self.limit {
+			Err(io::ErrorKind::WriteZero.into())
+		} else {
+			Ok(buf.len())
+		}
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
+
+/// Whether the encoded output is larger than `limit` bytes.
+///
+/// The servers don't expose the encoded response, so the output is encoded without allocating
+/// and the encoding stops as soon as the limit is exceeded.
+fn exceeds_size(output: &Output, limit: usize) -> bool {
+	serde_json::to_writer(&mut SizeLimit { size: 0, limit }, output).is_err()
+}
+
+/// The subscription cancelled by an unsubscribe call.
+fn unsubscribed_id(params: &Params) -> Option<SubscriptionId> {
+	match params {
+		Params::Array(params) => params.first().and_then(SubscriptionId::parse_value),
+		_ => None,
+	}
+}
+
+impl<M: PubSubMetadata> RequestMiddleware<M> for RpcMiddleware {
 	type Future = FutureResponse;
 	type CallFuture = FutureOutput;
 
@@ -82,6 +227,65 @@ impl<M: Metadata> RequestMiddleware<M> for RpcMiddleware {
 			metrics.rpc_calls.with_label_values(&[self.transport_label.as_str()]).inc();
 		}
 
+		// the size of the raw request is limited by the servers.
 		Either::B(next(request, meta))
 	}
+
+	fn on_call<F, X>(&self, call: Call, meta: M, next: F) -> Either<FutureOutput, X>
+	where
+		F: Fn(Call, M) -> X + Send + Sync,
+		X: Future<Item = Option<Output>, Error = ()> + Send + 'static,
+	{
+		let limits = match self.limits {
+			Some(ref limits) => limits.clone(),
+			None => return Either::B(next(call, meta)),
+		};
+		let (method, params, id, jsonrpc) = match call {
+			Call::MethodCall(ref call) =>
+				(call.method.clone(), &call.params, Some(call.id.clone()), call.jsonrpc),
+			Call::Notification(ref notification) =>
+				(notification.method.clone(), &notification.params, None, notification.jsonrpc),
+			Call::Invalid { .. } => return Either::B(next(call, meta)),
+		};
+		let subscription = SubscriptionCall::from_method(&method);
+		let unsubscribed = match subscription {
+			Some(SubscriptionCall::Unsubscribe) => unsubscribed_id(params),
+			_ => None,
+		};
+
+		let session = meta.session();
+		if let Err(rejection) = self.check_call(&limits, &method, session.clone()) {
+			let error = self.reject(rejection);
+			return Either::A(Box::new(future::ok(id.map(|id| Output::from(Err(error), id, jsonrpc)))))
+		}
+
+		let metrics = self.metrics.clone();
+		let transport_label = self.transport_label.clone();
+		let connections = self.connections.clone();
+		Either::A(Box::new(next(call, meta).map(move |output| output.map(|output| {
+			if let (Some(subscription), Some(session), Output::Success(ref success)) = (subscription, session, &output) {
+				let mut connections = connections.lock().expect("Connections lock is never poisoned; qed");
+				if let Some(connection) = connections.get_mut(&connection_id(&session)) {
+					match subscription {
+						SubscriptionCall::Subscribe => if let Some(id) = SubscriptionId::parse_value(&success.result) {
+							connection.subscriptions.insert(id);
+						},
+						// the subscription is gone, whether it was cancelled or had already ended.
+						SubscriptionCall::Unsubscribe => if let Some(ref id) = unsubscribed {
+							connection.subscriptions.remove(id);
+						},
+					}
+				}
+			}
+
+			if exceeds_size(&output, limits.max_response_size) {
+				if let Some(ref metrics) = metrics {
+					metrics.report_rejection(&transport_label, Rejection::ResponseSize);
+				}
+				Output::from(Err(Rejection::ResponseSize.error()), output.id().clone(), output.version())
+			} else {
+				output
+			}
+		}))))
+	}
 }
@@ -18,12 +18,12 @@
 
 use std::sync::Arc;
 use rpc::futures::future::result;
-use jsonrpc_pubsub::manager::SubscriptionManager;
+use sc_rpc_api::SubscriptionManager;
 
 use sc_client_api::{BlockchainEvents, BlockBackend};
 use sp_runtime::{generic::{BlockId, SignedBlock}, traits::{Block as BlockT}};
 
-use super::{ChainBackend, client_err, error::FutureResult};
+use super::{ChainBackend, client_err, error::{Error, FutureResult}};
 use std::marker::PhantomData;
 use sp_blockchain::HeaderBackend;
 
@@ -70,9 +70,17 @@ impl<Block, Client> ChainBackend<Client, Block> for FullChain<Block, Client> whe
 	fn block(&self, hash: Option<Block::Hash>)
 		-> FutureResult<Option<SignedBlock<Block>>>
 	{
-		Box::new(result(self.client
-			.block(&BlockId::Hash(self.unwrap_or_best(hash)))
-			.map_err(client_err)
-		))
+		let hash = self.unwrap_or_best(hash);
+		let block = self.client.block(&BlockId::Hash(hash)).map_err(client_err)
+			.and_then(|block| match block {
+				Some(block) => Ok(Some(block)),
+				// the header is known but not the body, which was pruned.
+				None => match self.client.header(BlockId::Hash(hash)).map_err(client_err)? {
+					Some(_) => Err(Error::BlockBodyPruned(format!("{:?}", hash))),
+					None => Ok(None),
+				},
+			});
+
+		Box::new(result(block))
 	}
 }
@@ -111,6 +111,31 @@ fn should_return_a_block() {
 	);
 }
 
+#[test]
+fn should_return_an_error_for_pruned_block_bodies() {
+	let backend = Arc::new(substrate_test_runtime_client::sc_client_db::Backend::new_test_with_blocks_pruning(
+		substrate_test_runtime_client::sc_client_db::BlocksPruning::Some(1),
+		0,
+	));
+	let mut client = Arc::new(TestClientBuilder::with_backend(backend).build());
+	let api = new_full(client.clone(), SubscriptionManager::new(Arc::new(TaskExecutor)));
+
+	let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let pruned_hash = block.hash();
+	client.import(BlockOrigin::Own, block).unwrap();
+
+	let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let block_hash = block.hash();
+	client.import_as_final(BlockOrigin::Own, block).unwrap();
+
+	assert_matches!(
+		api.block(Some(pruned_hash).into()).wait(),
+		Err(Error::BlockBodyPruned(_))
+	);
+	assert_matches!(api.block(Some(block_hash).into()).wait(), Ok(Some(_)));
+	assert_matches!(api.header(Some(pruned_hash).into()).wait(), Ok(Some(_)));
+}
+
 #[test]
 fn should_return_block_hash() {
 	let mut client = Arc::new(substrate_test_runtime_client::new());
@@ -32,6 +32,8 @@ use sp_core::{
 use parking_lot::RwLock;
 use std::sync::Arc;
 
+const QUERY_INDEXED_MAX_COUNT: u32 = 1000;
+
 /// Offchain API
 #[derive(Debug)]
 pub struct Offchain<T: OffchainStorage> {
@@ -73,4 +75,37 @@ impl<T: OffchainStorage + 'static> OffchainApi for Offchain<T> {
 		};
 		Ok(self.storage.read().get(prefix, &*key).map(Into::into))
 	}
+
+	/// Query entries written through the offchain indexing API.
+	fn query_indexed(
+		&self,
+		prefix: Bytes,
+		count: u32,
+		start_key: Option<Bytes>,
+		end_key: Option<Bytes>,
+	) -> Result<IndexedEntries> {
+		self.deny_unsafe.check_if_safe()?;
+
+		if count > QUERY_INDEXED_MAX_COUNT {
+			return Err(Error::InvalidCount { value: count, max: QUERY_INDEXED_MAX_COUNT });
+		}
+
+		// query one more entry to know whether there is a next page.
+		let mut entries = self.storage.read().indexed_entries(
+			&*prefix,
+			start_key.as_ref().map(|k| &k[..]),
+			end_key.as_ref().map(|k| &k[..]),
+			count as usize + 1,
+		);
+		let next_key = if entries.len() > count as usize {
+			entries.truncate(count as usize);
+			entries.last().map(|(k, _)| k.clone().into())
+		} else {
+			None
+		};
+		Ok(IndexedEntries {
+			entries: entries.into_iter().map(|(k, v)| (k.into(), v.into())).collect(),
+			next_key,
+		})
+	}
 }
@@ -52,4 +52,31 @@ fn offchain_calls_considered_unsafe() {
 		offchain.get_local_storage(StorageKind::PERSISTENT, key),
 		Err(Error::UnsafeRpcCalled(_))
 	);
+	assert_matches!(
+		offchain.query_indexed(Bytes(vec![]), 1, None, None),
+		Err(Error::UnsafeRpcCalled(_))
+	);
+}
+
+#[test]
+fn indexed_entries_should_be_paged() {
+	let mut storage = InMemOffchainStorage::default();
+	for key in &[b"a1", b"a2", b"a3", b"b1"] {
+		storage.set(sp_offchain::STORAGE_PREFIX, &key[..], b"value");
+	}
+	let offchain = Offchain::new(storage, DenyUnsafe::No);
+	let keys = |entries: &IndexedEntries| entries.entries.iter().map(|(k, _)| k.0.clone()).collect::<Vec<_>>();
+
+	let page = offchain.query_indexed(Bytes(b"a".to_vec()), 2, None, None).unwrap();
+	assert_eq!(keys(&page), vec![b"a1".to_vec(), b"a2".to_vec()]);
+	assert_eq!(page.next_key, Some(Bytes(b"a2".to_vec())));
+
+	let page = offchain.query_indexed(Bytes(b"a".to_vec()), 2, page.next_key, None).unwrap();
+	assert_eq!(keys(&page), vec![b"a3".to_vec()]);
+	assert_eq!(page.next_key, None);
+
+	assert_matches!(
+		offchain.query_indexed(Bytes(vec![]), 1001, None, None),
+		Err(Error::InvalidCount { .. })
+	);
 }
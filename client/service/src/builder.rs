@@ -17,7 +17,7 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::{
-	error::Error, DEFAULT_PROTOCOL_ID, MallocSizeOfWasm,
+	error::Error, MallocSizeOfWasm,
 	TelemetryConnectionSinks, RpcHandlers, NetworkStatusSinks,
 	start_rpc_servers, build_network_future, TransactionPoolAdapter, TaskManager, SpawnTaskHandle,
 	metrics::MetricsService,
@@ -34,7 +34,6 @@ use sp_consensus::{
 	import_queue::ImportQueue,
 };
 use futures::{FutureExt, StreamExt, future::ready, channel::oneshot};
-use jsonrpc_pubsub::manager::SubscriptionManager;
 use sc_keystore::Store as Keystore;
 use log::{info, warn};
 use sc_network::config::{Role, FinalityProofProvider, OnDemand, BoxFinalityProofRequestBuilder};
@@ -42,7 +41,7 @@ use sc_network::NetworkService;
 use parking_lot::RwLock;
 use sp_runtime::generic::BlockId;
 use sp_runtime::traits::{
-	Block as BlockT, SaturatedConversion, HashFor, Zero, BlockIdTo,
+	Block as BlockT, SaturatedConversion, HashFor, Zero, BlockIdTo, Hash as HashT,
 };
 use sp_api::{ProvideRuntimeApi, CallApiAt};
 use sc_executor::{NativeExecutor, NativeExecutionDispatch, RuntimeInfo};
@@ -61,6 +60,8 @@ use sc_client_api::{
 	execution_extensions::ExecutionExtensions
 };
 use sp_blockchain::{HeaderMetadata, HeaderBackend};
+use sp_core::storage::{StorageKey, well_known_keys};
+use codec::{Decode, Encode};
 
 /// A utility trait for building an RPC extension given a `DenyUnsafe` instance.
 /// This is useful since at service definition time we don't know whether the
@@ -228,10 +229,11 @@ pub fn new_full_parts<TBl, TRtApi, TExecDisp>(
 		TaskManager::new(config.task_executor.clone(), registry)?
 	};
 
-	let executor = NativeExecutor::<TExecDisp>::new(
+	let executor = NativeExecutor::<TExecDisp>::new_with_cache_path(
 		config.wasm_method,
 		config.default_heap_pages,
 		config.max_runtime_instances,
+		config.base_path.as_ref().map(|base| base.path().join("runtime-cache")),
 	);
 
 	let chain_spec = &config.chain_spec;
@@ -249,6 +251,7 @@ pub fn new_full_parts<TBl, TRtApi, TExecDisp>(
 			state_cache_child_ratio:
 			config.state_cache_child_ratio.map(|v| (v, 100)),
 			pruning: config.pruning.clone(),
+			blocks_pruning: config.blocks_pruning,
 			source: config.database.clone(),
 		};
 
@@ -259,7 +262,7 @@ pub fn new_full_parts<TBl, TRtApi, TExecDisp>(
 
 		new_client(
 			db_config,
-			executor,
+			executor.clone(),
 			chain_spec.as_storage_builder(),
 			fork_blocks,
 			bad_blocks,
@@ -273,9 +276,63 @@ pub fn new_full_parts<TBl, TRtApi, TExecDisp>(
 		)?
 	};
 
+	spawn_runtime_precompiler(&client, executor, task_manager.spawn_handle())?;
+
 	Ok((client, backend, keystore, task_manager))
 }
 
+/// Compile every runtime upgrade imported by the client in the background, so the first block
+/// executed with the new runtime doesn't have to wait for the compilation.
+///
+/// The heap pages are only known if they are changed together with the code, otherwise the
+/// default heap pages are assumed.
+fn spawn_runtime_precompiler<TBl, TRtApi, TExecDisp>(
+	client: &TFullClient<TBl, TRtApi, TExecDisp>,
+	executor: NativeExecutor<TExecDisp>,
+	spawn_handle: SpawnTaskHandle,
+) -> Result<(), Error> where
+	TBl: BlockT,
+	TExecDisp: NativeExecutionDispatch + 'static,
+{
+	let code_key = StorageKey(well_known_keys::CODE.to_vec());
+	let heap_pages_key = StorageKey(well_known_keys::HEAP_PAGES.to_vec());
+	let changes = client.storage_changes_notification_stream(
+		Some(&[code_key.clone(), heap_pages_key.clone()]),
+		None,
+	)?;
+	let task_handle = spawn_handle.clone();
+
+	spawn_handle.spawn(
+		"runtime-precompile-listener",
+		changes.for_each(move |(hash, changes)| {
+			let mut code = None;
+			let mut heap_pages = None;
+			for (child, key, value) in changes.iter() {
+				match value {
+					Some(value) if child.is_none() && key == &code_key => code = Some(value.0.clone()),
+					Some(value) if child.is_none() && key == &heap_pages_key =>
+						heap_pages = u64::decode(&mut &value.0[..]).ok(),
+					_ => {},
+				}
+			}
+
+			if let Some(code) = code {
+				let executor = executor.clone();
+				task_handle.spawn_blocking("runtime-precompile", async move {
+					let code_hash = <HashFor<TBl> as HashT>::hash(&code).encode();
+					if let Err(e) = executor.precompile_runtime(&code, code_hash, heap_pages) {
+						warn!("Failed to precompile runtime upgraded at {:?}: {:?}", hash, e);
+					}
+				});
+			}
+
+			ready(())
+		}),
+	);
+
+	Ok(())
+}
+
 /// Create the initial parts of a light node.
 pub fn new_light_parts<TBl, TRtApi, TExecDisp>(
 	config: &Configuration
@@ -309,6 +366,7 @@ pub fn new_light_parts<TBl, TRtApi, TExecDisp>(
 			state_cache_child_ratio:
 				config.state_cache_child_ratio.map(|v| (v, 100)),
 			pruning: config.pruning.clone(),
+			blocks_pruning: config.blocks_pruning,
 			source: config.database.clone(),
 		};
 		sc_client_db::light::LightStorage::new(db_settings)?
@@ -334,6 +392,9 @@ pub fn new_light_parts<TBl, TRtApi, TExecDisp>(
 	Ok((client, backend, keystore, task_manager, on_demand))
 }
 
+/// Canonicalization delay of the databases of full clients.
+pub(crate) const CANONICALIZATION_DELAY: u64 = 4096;
+
 /// Create an instance of db-backed client.
 pub fn new_client<E, Block, RA>(
 	settings: DatabaseSettings,
@@ -360,8 +421,6 @@ pub fn new_client<E, Block, RA>(
 		Block: BlockT,
 		E: CodeExecutor + RuntimeInfo,
 {
-	const CANONICALIZATION_DELAY: u64 = 4096;
-
 	let backend = Arc::new(Backend::new(settings, CANONICALIZATION_DELAY)?);
 	let executor = crate::client::LocalCallExecutor::new(backend.clone(), executor, spawn_handle, config.clone());
 	Ok((
@@ -704,7 +763,8 @@ fn gen_handler<TBl, TBackend, TExPool, TRpc, TCl>(
 	};
 
 	let task_executor = sc_rpc::SubscriptionTaskExecutor::new(spawn_handle);
-	let subscriptions = SubscriptionManager::new(Arc::new(task_executor.clone()));
+	let subscriptions = sc_rpc::SubscriptionManager::new(Arc::new(task_executor.clone()))
+		.with_on_end(rpc_middleware.subscription_ended());
 
 	let (chain, state, child_state) = if let (Some(remote_blockchain), Some(on_demand)) =
 		(remote_blockchain, on_demand) {
@@ -720,13 +780,18 @@ fn gen_handler<TBl, TBackend, TExPool, TRpc, TCl>(
 			subscriptions.clone(),
 			remote_blockchain.clone(),
 			on_demand,
+			deny_unsafe,
 		);
 		(chain, state, child_state)
 
 	} else {
 		// Full nodes
 		let chain = sc_rpc::chain::new_full(client.clone(), subscriptions.clone());
-		let (state, child_state) = sc_rpc::state::new_full(client.clone(), subscriptions.clone());
+		let (state, child_state) = sc_rpc::state::new_full(
+			client.clone(),
+			subscriptions.clone(),
+			deny_unsafe,
+		);
 		(chain, state, child_state)
 	};
 
@@ -813,18 +878,7 @@ pub fn build_network<TBl, TExPool, TImpQu, TCl>(
 		client: client.clone(),
 	});
 
-	let protocol_id = {
-		let protocol_id_full = match config.chain_spec.protocol_id() {
-			Some(pid) => pid,
-			None => {
-				warn!("Using default protocol ID {:?} because none is configured in the \
-					chain specs", DEFAULT_PROTOCOL_ID
-				);
-				DEFAULT_PROTOCOL_ID
-			}
-		};
-		sc_network::config::ProtocolId::from(protocol_id_full)
-	};
+	let protocol_id = config.protocol_id();
 
 	let block_announce_validator = if let Some(f) = block_announce_validator_builder {
 		f(client.clone())
@@ -832,6 +886,10 @@ pub fn build_network<TBl, TExPool, TImpQu, TCl>(
 		Box::new(DefaultBlockAnnounceValidator)
 	};
 
+	let (state_request_handler, state_request_protocol_config) =
+		sc_network::state_requests::StateRequestHandler::new(&protocol_id, client.clone());
+	spawn_handle.spawn("state-request-handler", state_request_handler.run());
+
 	let network_params = sc_network::config::Params {
 		role: config.role.clone(),
 		executor: {
@@ -849,7 +907,8 @@ pub fn build_network<TBl, TExPool, TImpQu, TCl>(
 		import_queue: Box::new(import_queue),
 		protocol_id,
 		block_announce_validator,
-		metrics_registry: config.prometheus_config.as_ref().map(|config| config.registry.clone())
+		metrics_registry: config.prometheus_config.as_ref().map(|config| config.registry.clone()),
+		state_request_protocol_config,
 	};
 
 	let has_bootnodes = !network_params.network_config.boot_nodes.is_empty();
@@ -0,0 +1,45 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::error::Error;
+use crate::config::{Configuration, DatabaseConfig};
+use sp_runtime::traits::Block as BlockT;
+
+/// Copy the database of `config` into the new database at `target`, column by column, e.g. to
+/// switch from RocksDB to ParityDB.
+///
+/// The target database is created with the other database settings of `config` and must not
+/// exist yet. Everything is copied, including the state of older blocks, the unfinalized blocks
+/// and the offchain storage. ParityDB databases can't be copied, as they can't iterate over
+/// their keys.
+pub fn migrate_database<B: BlockT>(config: &Configuration, target: DatabaseConfig) -> Result<(), Error> {
+	if target.path().map_or(false, |path| path.exists()) {
+		return Err(Error::Other(format!("The target {} database already exists", target)));
+	}
+
+	let settings = |source| sc_client_db::DatabaseSettings {
+		state_cache_size: config.state_cache_size,
+		state_cache_child_ratio: config.state_cache_child_ratio.map(|v| (v, 100)),
+		pruning: config.pruning.clone(),
+		blocks_pruning: config.blocks_pruning,
+		source,
+	};
+	sc_client_db::copy_database::<B>(&settings(config.database.clone()), &settings(target))?;
+
+	Ok(())
+}
@@ -0,0 +1,191 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::builder::CANONICALIZATION_DELAY;
+use crate::error::Error;
+use crate::config::Configuration;
+use crate::chain_ops::export_raw_state;
+use codec::{Decode, Encode};
+use sc_client_api::{BlockBackend, StorageProvider, UsageProvider, backend::AuxStore};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{Justification, Storage, generic::BlockId};
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, One, Zero};
+use sp_core::storage::{ChildInfo, StorageChild};
+use std::{io::{Read, Write}, sync::Arc};
+
+/// Magic bytes every snapshot file starts with.
+pub const SNAPSHOT_MAGIC: [u8; 8] = *b"IDXSNAP\0";
+
+/// Version of the snapshot format written by [`export_snapshot`].
+pub const SNAPSHOT_VERSION: u32 = 2;
+
+/// A finalized block along with everything needed to continue the chain from it.
+///
+/// The file is made of [`SNAPSHOT_MAGIC`], the SCALE-encoded [`SNAPSHOT_VERSION`] and the
+/// SCALE-encoded snapshot.
+#[derive(Debug, Encode, Decode)]
+pub struct Snapshot<B: BlockT> {
+	/// Header of the genesis block.
+	pub genesis: B::Header,
+	/// Headers of the blocks from #1 up to the parent of the snapshot block.
+	pub history: Vec<B::Header>,
+	/// Header of the snapshot block.
+	pub header: B::Header,
+	/// Justification of the snapshot block, if any.
+	pub justification: Option<Justification>,
+	/// Auxiliary data at the snapshot block, e.g. the consensus authority sets.
+	pub aux: Vec<(Vec<u8>, Vec<u8>)>,
+	/// Key/value pairs of the top trie.
+	pub top: Vec<(Vec<u8>, Vec<u8>)>,
+	/// Key/value pairs of every default child trie, by child storage key.
+	pub children: Vec<(Vec<u8>, Vec<(Vec<u8>, Vec<u8>)>)>,
+}
+
+impl<B: BlockT> Snapshot<B> {
+	/// The state of the snapshot block.
+	pub fn storage(&self) -> Storage {
+		Storage {
+			top: self.top.iter().cloned().collect(),
+			children_default: self.children.iter()
+				.map(|(key, data)| {
+					let child_info = ChildInfo::new_default(key);
+					let child = StorageChild { data: data.iter().cloned().collect(), child_info };
+					(key.clone(), child)
+				})
+				.collect(),
+		}
+	}
+}
+
+/// Write a snapshot of the last finalized block to `output`.
+///
+/// Only the last finalized block can be exported, as the auxiliary database only holds the
+/// entries of that block. `aux_keys` returns the keys of the auxiliary database entries to copy
+/// along with the state.
+pub fn export_snapshot<B, BA, C, F>(
+	client: Arc<C>,
+	aux_keys: F,
+	mut output: impl Write,
+) -> Result<B::Header, Error>
+where
+	C: UsageProvider<B> + StorageProvider<B, BA> + BlockBackend<B> + HeaderBackend<B> + AuxStore,
+	B: BlockT,
+	BA: sc_client_api::backend::Backend<B>,
+	F: Fn(&B::Header) -> Vec<Vec<u8>>,
+{
+	let block = BlockId::Hash(client.info().finalized_hash);
+	let header = client.header(block)?
+		.ok_or_else(|| Error::Other(format!("Unknown block {}", block)))?;
+	if header.number().is_zero() {
+		return Err("The genesis block can't be exported as a snapshot".into());
+	}
+	let genesis = client.header(BlockId::Number(Zero::zero()))?
+		.ok_or_else(|| Error::Other("Missing genesis header".into()))?;
+	let mut history = Vec::new();
+	let mut number = One::one();
+	while number < *header.number() {
+		history.push(client.header(BlockId::Number(number))?
+			.ok_or_else(|| Error::Other(format!("Missing header of block #{}", number)))?);
+		number += One::one();
+	}
+	let justification = client.justification(&block)?;
+
+	let mut aux = Vec::new();
+	for key in aux_keys(&header) {
+		if let Some(value) = client.get_aux(&key)? {
+			aux.push((key, value));
+		}
+	}
+
+	let storage = export_raw_state(client, Some(block))?;
+	let snapshot = Snapshot::<B> {
+		genesis,
+		history,
+		header: header.clone(),
+		justification,
+		aux,
+		top: storage.top.into_iter().collect(),
+		children: storage.children_default.into_iter()
+			.map(|(key, child)| (key, child.data.into_iter().collect()))
+			.collect(),
+	};
+
+	output.write_all(&SNAPSHOT_MAGIC)?;
+	output.write_all(&SNAPSHOT_VERSION.encode())?;
+	output.write_all(&snapshot.encode())?;
+	output.flush()?;
+
+	Ok(header)
+}
+
+/// Read a snapshot from `input`.
+pub fn read_snapshot<B: BlockT>(mut input: impl Read) -> Result<Snapshot<B>, Error> {
+	let mut magic = [0u8; 8];
+	input.read_exact(&mut magic)?;
+	if magic != SNAPSHOT_MAGIC {
+		return Err("Input is not a snapshot file".into());
+	}
+
+	let mut data = Vec::new();
+	input.read_to_end(&mut data)?;
+	let mut data = &data[..];
+	let version = u32::decode(&mut data)
+		.map_err(|e| Error::Other(format!("Invalid snapshot version: {}", e.what())))?;
+	if version != SNAPSHOT_VERSION {
+		return Err(Error::Other(format!(
+			"Unsupported snapshot version {}, expected {}",
+			version,
+			SNAPSHOT_VERSION,
+		)));
+	}
+
+	Snapshot::decode(&mut data)
+		.map_err(|e| Error::Other(format!("Invalid snapshot: {}", e.what())))
+}
+
+/// Create the database of `config` from the snapshot read from `input`.
+///
+/// The database must not exist yet. Returns the header of the snapshot block, which becomes
+/// the best and finalized block of the new database.
+pub fn import_snapshot<B: BlockT>(
+	config: &Configuration,
+	input: impl Read,
+) -> Result<B::Header, Error> {
+	let snapshot = read_snapshot::<B>(input)?;
+
+	let db_config = sc_client_db::DatabaseSettings {
+		state_cache_size: config.state_cache_size,
+		state_cache_child_ratio: config.state_cache_child_ratio.map(|v| (v, 100)),
+		pruning: config.pruning.clone(),
+		blocks_pruning: config.blocks_pruning,
+		source: config.database.clone(),
+	};
+	let backend = sc_client_db::Backend::<B>::new(db_config, CANONICALIZATION_DELAY)?;
+
+	let storage = snapshot.storage();
+	backend.import_state_snapshot(
+		snapshot.genesis,
+		snapshot.history,
+		snapshot.header.clone(),
+		snapshot.justification,
+		storage,
+		snapshot.aux,
+	)?;
+
+	Ok(snapshot.header)
+}
@@ -43,13 +43,13 @@ use sp_runtime::{
 };
 use sp_state_machine::{
 	DBValue, Backend as StateBackend, ChangesTrieAnchorBlockId,
-	prove_read, prove_child_read, ChangesTrieRootsStorage, ChangesTrieStorage,
+	prove_read, prove_child_read, prove_range_read_with_size, read_range_proof_check, ChangesTrieRootsStorage, ChangesTrieStorage,
 	ChangesTrieConfigurationRange, key_changes, key_changes_proof,
 };
 use sc_executor::RuntimeVersion;
 use sp_consensus::{
 	Error as ConsensusError, BlockStatus, BlockImportParams, BlockCheckParams,
-	ImportResult, BlockOrigin, ForkChoiceStrategy, RecordProof,
+	ImportResult, BlockOrigin, ForkChoiceStrategy, RecordProof, ImportedState,
 };
 use sp_blockchain::{
 	self as blockchain,
@@ -613,6 +613,7 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 			fork_choice,
 			intermediates,
 			import_existing,
+			imported_state,
 			..
 		} = import_block;
 
@@ -652,6 +653,7 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 			auxiliary,
 			fork_choice,
 			import_existing,
+			imported_state,
 		);
 
 		if let Ok(ImportResult::Imported(ref aux)) = result {
@@ -688,6 +690,7 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 		aux: Vec<(Vec<u8>, Option<Vec<u8>>)>,
 		fork_choice: ForkChoiceStrategy,
 		import_existing: bool,
+		imported_state: Option<ImportedState<Block>>,
 	) -> sp_blockchain::Result<ImportResult> where
 		Self: ProvideRuntimeApi<Block>,
 		<Self as ProvideRuntimeApi<Block>>::Api: CoreApi<Block, Error = Error> +
@@ -706,8 +709,9 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 		let info = self.backend.blockchain().info();
 
 		// the block is lower than our last finalized block so it must revert
-		// finality, refusing import.
-		if *import_headers.post().number() <= info.finalized_number {
+		// finality, refusing import. Existing blocks may be re-imported, e.g. to
+		// add the state of a block that was imported without it.
+		if status == blockchain::BlockStatus::Unknown && *import_headers.post().number() <= info.finalized_number {
 			return Err(sp_blockchain::Error::NotInFinalizedChain);
 		}
 
@@ -719,8 +723,27 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 			BlockOrigin::Genesis | BlockOrigin::NetworkInitialSync | BlockOrigin::File => false,
 		};
 
-		let storage_changes = match storage_changes {
-			Some(storage_changes) => {
+		let storage_changes = match (storage_changes, imported_state) {
+			(_, Some(imported_state)) => {
+				// the downloaded state replaces the parent state, which may be missing.
+				let state_root = operation.op.reset_storage(imported_state.state)?;
+				if state_root != *import_headers.post().state_root() {
+					return Err(Error::InvalidStateRoot);
+				}
+
+				if finalized {
+					self.apply_finality_with_block_hash(
+						operation,
+						parent_hash,
+						None,
+						info.best_hash,
+						make_notifications,
+					)?;
+				}
+
+				None
+			},
+			(Some(storage_changes), None) => {
 				self.backend.begin_state_operation(&mut operation.op, BlockId::Hash(parent_hash))?;
 
 				// ensure parent block is finalized to maintain invariant that
@@ -758,7 +781,7 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 
 				Some((main_sc, child_sc))
 			},
-			None => None,
+			(None, None) => None,
 		};
 
 		let is_new_best = finalized || match fork_choice {
@@ -839,10 +862,12 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 		let at = BlockId::Hash(*parent_hash);
 		let enact_state = match self.block_status(&at)? {
 			BlockStatus::Unknown => return Ok(Some(ImportResult::UnknownParent)),
+			BlockStatus::KnownBad => return Ok(Some(ImportResult::KnownBad)),
+			// a downloaded state replaces the execution of the block.
+			_ if import_block.imported_state.is_some() => false,
 			BlockStatus::InChainWithState | BlockStatus::Queued => true,
 			BlockStatus::InChainPruned if import_block.allow_missing_state => false,
 			BlockStatus::InChainPruned => return Ok(Some(ImportResult::MissingState)),
-			BlockStatus::KnownBad => return Ok(Some(ImportResult::KnownBad)),
 		};
 
 		match (enact_state, &mut import_block.storage_changes, &mut import_block.body) {
@@ -1205,6 +1230,31 @@ impl<B, E, Block, RA> ProofProvider<Block> for Client<B, E, Block, RA> where
 				.map_err(Into::into))
 	}
 
+	fn read_proof_collection(
+		&self,
+		id: &BlockId<Block>,
+		child_info: Option<&ChildInfo>,
+		start_key: &[u8],
+		size_limit: usize,
+	) -> sp_blockchain::Result<(Vec<(Vec<u8>, Vec<u8>)>, bool, StorageProof)> {
+		self.state_at(id)
+			.and_then(|state| prove_range_read_with_size(state, child_info, start_key, size_limit)
+				.map_err(Into::into))
+	}
+
+	fn verify_range_proof(
+		&self,
+		root: Block::Hash,
+		proof: StorageProof,
+		child_info: Option<&ChildInfo>,
+		start_key: &[u8],
+		entries: &[(Vec<u8>, Vec<u8>)],
+		complete: bool,
+	) -> sp_blockchain::Result<()> {
+		read_range_proof_check::<HashFor<Block>>(root, proof, child_info, start_key, entries, complete)
+			.map_err(Into::into)
+	}
+
 	fn execution_proof(
 		&self,
 		id: &BlockId<Block>,
@@ -1746,7 +1796,8 @@ impl<B, E, Block, RA> sp_consensus::BlockImport<Block> for &Client<B, E, Block,
 		{
 			BlockStatus::InChainWithState | BlockStatus::Queued if !import_existing  => return Ok(ImportResult::AlreadyInChain),
 			BlockStatus::InChainWithState | BlockStatus::Queued => {},
-			BlockStatus::InChainPruned => return Ok(ImportResult::AlreadyInChain),
+			BlockStatus::InChainPruned if !import_existing => return Ok(ImportResult::AlreadyInChain),
+			BlockStatus::InChainPruned => {},
 			BlockStatus::Unknown => {},
 			BlockStatus::KnownBad => return Ok(ImportResult::KnownBad),
 		}
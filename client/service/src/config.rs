@@ -18,10 +18,11 @@
 
 //! Service configuration.
 
-pub use sc_client_db::{Database, PruningMode, DatabaseSettingsSrc as DatabaseConfig};
+pub use sc_client_db::{Database, PruningMode, BlocksPruning, DatabaseSettingsSrc as DatabaseConfig};
 pub use sc_network::Multiaddr;
 pub use sc_network::config::{ExtTransport, MultiaddrWithPeerId, NetworkConfiguration, Role, NodeKeyConfig};
 pub use sc_executor::WasmExecutionMethod;
+pub use sc_rpc_server::RpcLimits;
 use sc_client_api::execution_extensions::ExecutionStrategies;
 
 use std::{io, future::Future, path::{PathBuf, Path}, pin::Pin, net::SocketAddr, sync::Arc};
@@ -30,6 +31,7 @@ use sc_chain_spec::ChainSpec;
 use sp_core::crypto::SecretString;
 pub use sc_telemetry::TelemetryEndpoints;
 use prometheus_endpoint::Registry;
+use log::warn;
 #[cfg(not(target_os = "unknown"))]
 use tempfile::TempDir;
 
@@ -58,6 +60,8 @@ pub struct Configuration {
 	pub state_cache_child_ratio: Option<usize>,
 	/// Pruning settings.
 	pub pruning: PruningMode,
+	/// Block body pruning settings.
+	pub blocks_pruning: BlocksPruning,
 	/// Chain configuration.
 	pub chain_spec: Box<dyn ChainSpec>,
 	/// Wasm execution method.
@@ -76,6 +80,8 @@ pub struct Configuration {
 	pub rpc_cors: Option<Vec<String>>,
 	/// RPC methods to expose (by default only a safe subset or all of them).
 	pub rpc_methods: RpcMethods,
+	/// Limits applied to the calls of the HTTP & WS servers.
+	pub rpc_limits: RpcLimits,
 	/// Prometheus endpoint configuration. `None` if disabled.
 	pub prometheus_config: Option<PrometheusConfig>,
 	/// Telemetry service URL. `None` if disabled.
@@ -186,6 +192,20 @@ impl Configuration {
 	pub fn prometheus_registry<'a>(&'a self) -> Option<&'a Registry> {
 		self.prometheus_config.as_ref().map(|config| &config.registry)
 	}
+
+	/// Returns the network protocol id from the chain spec, or the default one.
+	pub fn protocol_id(&self) -> sc_network::config::ProtocolId {
+		let protocol_id_full = match self.chain_spec.protocol_id() {
+			Some(pid) => pid,
+			None => {
+				warn!("Using default protocol ID {:?} because none is configured in the \
+					chain specs", crate::DEFAULT_PROTOCOL_ID
+				);
+				crate::DEFAULT_PROTOCOL_ID
+			}
+		};
+		sc_network::config::ProtocolId::from(protocol_id_full)
+	}
 }
 
 /// Available RPC methods.
@@ -59,7 +59,8 @@ pub use self::builder::{
 	TFullCallExecutor, TLightCallExecutor, RpcExtensionBuilder, NoopRpcExtensionBuilder,
 };
 pub use config::{
-	BasePath, Configuration, DatabaseConfig, PruningMode, Role, RpcMethods, TaskExecutor, TaskType,
+	BasePath, BlocksPruning, Configuration, DatabaseConfig, PruningMode, Role, RpcMethods,
+	TaskExecutor, TaskType,
 };
 pub use sc_chain_spec::{
 	ChainSpec, GenericChainSpec, Properties, RuntimeGenesis, Extension as ChainSpecExtension,
@@ -354,8 +355,7 @@ mod waiting {
 	impl Drop for HttpServer {
 		fn drop(&mut self) {
 			if let Some(server) = self.0.take() {
-				server.close_handle().close();
-				server.wait();
+				server.close();
 			}
 		}
 	}
@@ -374,8 +374,7 @@ mod waiting {
 	impl Drop for WsServer {
 		fn drop(&mut self) {
 			if let Some(server) = self.0.take() {
-				server.close_handle().close();
-				let _ = server.wait();
+				server.close();
 			}
 		}
 	}
@@ -430,9 +429,12 @@ fn start_rpc_servers<
 			|address| sc_rpc_server::start_http(
 				address,
 				config.rpc_cors.as_ref(),
+				&config.rpc_limits,
+				rpc_metrics,
 				gen_handler(
 					deny_unsafe(&address, &config.rpc_methods),
 					sc_rpc_server::RpcMiddleware::new(rpc_metrics.cloned(), "http")
+						.with_limits(config.rpc_limits.clone())
 				),
 			),
 		)?.map(|s| waiting::HttpServer(Some(s))),
@@ -442,9 +444,12 @@ fn start_rpc_servers<
 				address,
 				config.rpc_ws_max_connections,
 				config.rpc_cors.as_ref(),
+				&config.rpc_limits,
+				rpc_metrics,
 				gen_handler(
 					deny_unsafe(&address, &config.rpc_methods),
 					sc_rpc_server::RpcMiddleware::new(rpc_metrics.cloned(), "ws")
+						.with_limits(config.rpc_limits.clone())
 				),
 			),
 		)?.map(|s| waiting::WsServer(Some(s))),
@@ -43,6 +43,7 @@ struct PrometheusMetrics {
 	database_cache: Gauge<U64>,
 	state_cache: Gauge<U64>,
 	state_db: GaugeVec<U64>,
+	database_column_size: GaugeVec<U64>,
 }
 
 impl PrometheusMetrics {
@@ -100,6 +101,10 @@ impl PrometheusMetrics {
 				Opts::new("state_db_cache_bytes", "State DB cache in bytes"),
 				&["subtype"]
 			)?, registry)?,
+			database_column_size: register(GaugeVec::new(
+				Opts::new("database_column_size_bytes", "On-disk size of each database column in bytes, only reported for ParityDB"),
+				&["column"]
+			)?, registry)?,
 		})
 	}
 }
@@ -287,6 +292,10 @@ impl MetricsService {
 				metrics.state_db.with_label_values(&["pinned"]).set(
 					info.memory.state_db.pinned.as_bytes() as u64,
 				);
+
+				for (column, size) in &info.database.column_sizes {
+					metrics.database_column_size.with_label_values(&[column]).set(*size);
+				}
 			}
 		}
 
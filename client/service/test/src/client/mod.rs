@@ -31,7 +31,7 @@ use substrate_test_runtime_client::{
 use sc_client_api::{
 	StorageProvider, BlockBackend, in_mem, BlockchainEvents,
 };
-use sc_client_db::{Backend, DatabaseSettings, DatabaseSettingsSrc, PruningMode};
+use sc_client_db::{Backend, BlocksPruning, DatabaseSettings, DatabaseSettingsSrc, PruningMode};
 use sc_block_builder::BlockBuilderProvider;
 use sc_service::client::{self, Client, LocalCallExecutor, new_in_mem};
 use sp_runtime::traits::{
@@ -1276,6 +1276,7 @@ fn doesnt_import_blocks_that_revert_finality() {
 			state_cache_size: 1 << 20,
 			state_cache_child_ratio: None,
 			pruning: PruningMode::ArchiveAll,
+			blocks_pruning: BlocksPruning::All,
 			source: DatabaseSettingsSrc::RocksDb {
 				path: tmp.path().into(),
 				cache_size: 1024,
@@ -1477,6 +1478,7 @@ fn returns_status_for_pruned_blocks() {
 			state_cache_size: 1 << 20,
 			state_cache_child_ratio: None,
 			pruning: PruningMode::keep_blocks(1),
+			blocks_pruning: BlocksPruning::All,
 			source: DatabaseSettingsSrc::RocksDb {
 				path: tmp.path().into(),
 				cache_size: 1024,
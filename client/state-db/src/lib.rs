@@ -274,6 +274,26 @@ impl<BlockHash: Hash + MallocSizeOf, Key: Hash + MallocSizeOf> StateDbSync<Block
 		}
 	}
 
+	fn insert_detached_block<E: fmt::Debug>(
+		&mut self,
+		hash: &BlockHash,
+		number: u64,
+		parent_hash: &BlockHash,
+		mut changeset: ChangeSet<Key>,
+	) -> Result<CommitSet<Key>, Error<E>> {
+		match self.mode {
+			PruningMode::ArchiveAll => {
+				changeset.deleted.clear();
+				Ok(CommitSet {
+					data: changeset,
+					meta: Default::default(),
+				})
+			},
+			PruningMode::Constrained(_) | PruningMode::ArchiveCanonical =>
+				self.non_canonical.insert_detached(hash, number, parent_hash, changeset),
+		}
+	}
+
 	fn canonicalize_block<E: fmt::Debug>(
 		&mut self,
 		hash: &BlockHash,
@@ -463,6 +483,18 @@ impl<BlockHash: Hash + MallocSizeOf, Key: Hash + MallocSizeOf> StateDb<BlockHash
 		self.db.write().insert_block(hash, number, parent_hash, changeset)
 	}
 
+	/// Add a new non-canonical block with a full state, whose parent state is not in the
+	/// database. There must be no other non-canonical block.
+	pub fn insert_detached_block<E: fmt::Debug>(
+		&self,
+		hash: &BlockHash,
+		number: u64,
+		parent_hash: &BlockHash,
+		changeset: ChangeSet<Key>,
+	) -> Result<CommitSet<Key>, Error<E>> {
+		self.db.write().insert_detached_block(hash, number, parent_hash, changeset)
+	}
+
 	/// Finalize a previously inserted block.
 	pub fn canonicalize_block<E: fmt::Debug>(
 		&self,
@@ -524,6 +556,20 @@ impl<BlockHash: Hash + MallocSizeOf, Key: Hash + MallocSizeOf> StateDb<BlockHash
 	}
 }
 
+/// Rewrite the journals of the state database in `db`, which keys the nodes by prefixed keys and
+/// counts their insertions itself, for a database keying the nodes by `unprefix`ed keys and
+/// counting their references, e.g. to move the state from RocksDB to ParityDB.
+///
+/// Returns the meta values to write to the new database in place of the journal records.
+pub fn unprefix_journals<BlockHash: Hash, Key: Hash, D: MetaDb>(
+	db: &D,
+	unprefix: &dyn Fn(&Key) -> Key,
+) -> Result<Vec<(Vec<u8>, DBValue)>, Error<D::Error>> {
+	let mut records = noncanonical::unprefix_journal::<BlockHash, Key, _>(db, unprefix)?;
+	records.extend(pruning::unprefix_journal::<BlockHash, Key, _>(db, unprefix)?);
+	Ok(records)
+}
+
 #[cfg(test)]
 mod tests {
 	use std::io;
@@ -663,4 +709,42 @@ mod tests {
 		let state_db: Result<StateDb<H256, H256>, _> = StateDb::new(new_mode, false, &db);
 		assert!(state_db.is_err());
 	}
+
+	#[test]
+	fn unprefixed_journals_prune_the_same_nodes() {
+		let h = H256::from_low_u64_be;
+		let unprefix = |key: &H256| H256::from_low_u64_be(key.to_low_u64_be() + 100);
+		let mut db = make_db(&[1, 2]);
+		let state_db: StateDb<H256, H256> = StateDb::new(PruningMode::keep_blocks(2), true, &db).unwrap();
+		// node 1 is deleted by block 1 and inserted again by block 2.
+		for (number, inserted, deleted) in vec![(1, vec![3], vec![1]), (2, vec![1], vec![2]), (3, vec![4], vec![])] {
+			db.commit(&state_db.insert_block::<io::Error>(
+				&h(number),
+				number,
+				&h(number - 1),
+				make_changeset(&inserted, &deleted),
+			).unwrap());
+			state_db.apply_pending();
+		}
+		for number in 1..3 {
+			db.commit(&state_db.canonicalize_block::<io::Error>(&h(number)).unwrap());
+			state_db.apply_pending();
+		}
+
+		let mut unprefixed = db.clone();
+		unprefixed.meta.extend(crate::unprefix_journals::<H256, H256, _>(&db, &unprefix).unwrap());
+		let state_db: StateDb<H256, H256> = StateDb::new(PruningMode::keep_blocks(2), false, &unprefixed).unwrap();
+
+		// pruning block 1 deletes nothing, node 1 is used again by block 2.
+		let commit = state_db.canonicalize_block::<io::Error>(&h(3)).unwrap();
+		assert_eq!(commit.data.inserted.iter().map(|(k, _)| *k).collect::<Vec<_>>(), vec![h(104)]);
+		assert!(commit.data.deleted.is_empty());
+		unprefixed.commit(&commit);
+		state_db.apply_pending();
+
+		unprefixed.commit(&state_db.insert_block::<io::Error>(&h(4), 4, &h(3), make_changeset(&[], &[])).unwrap());
+		state_db.apply_pending();
+		let commit = state_db.canonicalize_block::<io::Error>(&h(4)).unwrap();
+		assert_eq!(commit.data.deleted, vec![h(102)]);
+	}
 }
@@ -57,6 +57,41 @@ fn to_journal_key(block: u64, index: u64) -> Vec<u8> {
 	to_meta_key(NON_CANONICAL_JOURNAL, &(block, index))
 }
 
+/// Rewrite the journal of the overlay for a database keying the nodes by `unprefix`ed keys.
+///
+/// Returns the rewritten records by journal key.
+pub(crate) fn unprefix_journal<BlockHash: Hash, Key: Hash, D: MetaDb>(
+	db: &D,
+	unprefix: &dyn Fn(&Key) -> Key,
+) -> Result<Vec<(Vec<u8>, DBValue)>, Error<D::Error>> {
+	let last_canonicalized = db.get_meta(&to_meta_key(LAST_CANONICAL, &()))
+		.map_err(|e| Error::Db(e))?;
+	let mut block = match last_canonicalized {
+		Some(buffer) => <(BlockHash, u64)>::decode(&mut buffer.as_slice())?.1 + 1,
+		None => return Ok(Vec::new()),
+	};
+	let mut records = Vec::new();
+	loop {
+		let mut index: u64 = 0;
+		while let Some(record) = db.get_meta(&to_journal_key(block, index)).map_err(|e| Error::Db(e))? {
+			let record: JournalRecord<BlockHash, Key> = Decode::decode(&mut record.as_slice())?;
+			let record = JournalRecord {
+				hash: record.hash,
+				parent_hash: record.parent_hash,
+				inserted: record.inserted.into_iter().map(|(k, v)| (unprefix(&k), v)).collect(),
+				deleted: record.deleted.iter().map(|k| unprefix(k)).collect(),
+			};
+			records.push((to_journal_key(block, index), record.encode()));
+			index += 1;
+		}
+		if index == 0 {
+			break;
+		}
+		block += 1;
+	}
+	Ok(records)
+}
+
 #[cfg_attr(test, derive(PartialEq, Debug))]
 #[derive(parity_util_mem_derive::MallocSizeOf)]
 struct BlockOverlay<BlockHash: Hash, Key: Hash> {
@@ -206,6 +241,17 @@ impl<BlockHash: Hash, Key: Hash> NonCanonicalOverlay<BlockHash, Key> {
 		})
 	}
 
+	/// Insert a block whose parent state is not known to the overlay, such as a block with
+	/// a downloaded state. The block becomes the new base of the overlay, which must not
+	/// contain any other block.
+	pub fn insert_detached<E: fmt::Debug>(&mut self, hash: &BlockHash, number: u64, parent_hash: &BlockHash, changeset: ChangeSet<Key>) -> Result<CommitSet<Key>, Error<E>> {
+		if !self.levels.is_empty() || number == 0 {
+			return Err(Error::InvalidBlockNumber);
+		}
+		self.last_canonicalized = None;
+		self.insert(hash, number, parent_hash, changeset)
+	}
+
 	/// Insert a new block into the overlay. If inserted on the second level or lover expects parent to be present in the window.
 	pub fn insert<E: fmt::Debug>(&mut self, hash: &BlockHash, number: u64, parent_hash: &BlockHash, changeset: ChangeSet<Key>) -> Result<CommitSet<Key>, Error<E>> {
 		let mut commit = CommitSet::default();
@@ -605,6 +651,33 @@ mod tests {
 		assert!(db.data_eq(&make_db(&[1, 3, 4])));
 	}
 
+	#[test]
+	fn insert_detached_rebases_overlay() {
+		let h1 = H256::random();
+		let h2 = H256::random();
+		let mut db = make_db(&[1, 2]);
+		let mut overlay = NonCanonicalOverlay::<H256, H256>::new(&db).unwrap();
+		db.commit(&overlay.insert::<io::Error>(&h1, 1, &H256::default(), make_changeset(&[3], &[])).unwrap());
+		let mut commit = CommitSet::default();
+		overlay.canonicalize::<io::Error>(&h1, &mut commit).unwrap();
+		db.commit(&commit);
+		overlay.apply_pending();
+
+		// block 10 can't be attached to the canonical block 1.
+		assert!(overlay.insert::<io::Error>(&h2, 10, &H256::random(), make_changeset(&[4], &[])).is_err());
+		db.commit(&overlay.insert_detached::<io::Error>(&h2, 10, &H256::random(), make_changeset(&[4], &[])).unwrap());
+		assert_eq!(overlay.last_canonicalized_block_number(), Some(9));
+		assert!(contains(&overlay, 4));
+
+		// the new base survives a restart.
+		let overlay2 = NonCanonicalOverlay::<H256, H256>::new(&db).unwrap();
+		assert_eq!(overlay2.last_canonicalized_block_number(), Some(9));
+		assert!(contains(&overlay2, 4));
+
+		// no block may be detached while others are pending.
+		assert!(overlay.insert_detached::<io::Error>(&H256::random(), 20, &H256::random(), make_changeset(&[5], &[])).is_err());
+	}
+
 	#[test]
 	fn restore_from_journal() {
 		let h1 = H256::random();
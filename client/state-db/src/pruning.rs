@@ -26,7 +26,7 @@
 
 use std::collections::{HashMap, HashSet, VecDeque};
 use codec::{Encode, Decode};
-use crate::{CommitSet, Error, MetaDb, to_meta_key, Hash};
+use crate::{CommitSet, DBValue, Error, MetaDb, to_meta_key, Hash};
 use log::{trace, warn};
 
 const LAST_PRUNED: &[u8] = b"last_pruned";
@@ -71,6 +71,27 @@ fn to_journal_key(block: u64) -> Vec<u8> {
 	to_meta_key(PRUNING_JOURNAL, &block)
 }
 
+/// Rewrite the journal of a window counting insertions for a database keying the nodes by
+/// `unprefix`ed keys and counting their references.
+///
+/// The keys inserted again after being deleted are left out of the rewritten records, the
+/// window counting insertions would not delete them.
+/// Returns the rewritten records by journal key.
+pub(crate) fn unprefix_journal<BlockHash: Hash, Key: Hash, D: MetaDb>(
+	db: &D,
+	unprefix: &dyn Fn(&Key) -> Key,
+) -> Result<Vec<(Vec<u8>, DBValue)>, Error<D::Error>> {
+	let window = RefWindow::<BlockHash, Key>::new(db, true)?;
+	Ok(window.death_rows.into_iter().map(|row| {
+		let record = JournalRecord::<BlockHash, Key> {
+			hash: row.hash,
+			inserted: Vec::new(),
+			deleted: row.deleted.iter().map(|k| unprefix(k)).collect(),
+		};
+		(row.journal_key, record.encode())
+	}).collect())
+}
+
 impl<BlockHash: Hash, Key: Hash> RefWindow<BlockHash, Key> {
 	pub fn new<D: MetaDb>(db: &D, count_insertions: bool) -> Result<RefWindow<BlockHash, Key>, Error<D::Error>> {
 		let last_pruned = db.get_meta(&to_meta_key(LAST_PRUNED, &()))
@@ -0,0 +1,345 @@
+// Copyright 2020 Selendra.
+// This file is part of Indracore.
+
+// Indracore is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Indracore is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Indracore.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Utilities for tracing block execution.
+//!
+//! A block is re-executed on top of its parent state with a dedicated `tracing` subscriber
+//! installed as the thread default, so the spans and events emitted by the runtime and the
+//! state machine are collected into a [`BlockTrace`] instead of being sent to the log.
+
+use std::{collections::HashMap, sync::{Arc, atomic::{AtomicU64, Ordering}}, time::Instant};
+
+use parking_lot::Mutex;
+use tracing::{Dispatch, dispatcher, Subscriber, Level, span::{Attributes, Record, Id}};
+use tracing_subscriber::CurrentSpan;
+
+use sc_client_api::BlockBackend;
+use sp_api::{Core, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_core::hexdisplay::HexDisplay;
+use sp_rpc::tracing::{BlockTrace, Span, Event, Data, TraceError, TraceBlockResponse};
+use sp_runtime::{generic::BlockId, traits::{Block as BlockT, Header}};
+use sp_tracing::{WASM_NAME_KEY, WASM_TARGET_KEY, WASM_TRACE_IDENTIFIER};
+
+use crate::{SpanDatum, TraceEvent, Values, ZERO_DURATION, parse_target};
+
+/// Tracing targets recorded when the caller does not specify any.
+pub const DEFAULT_TARGETS: &str = "pallet,frame,state";
+/// Name of the field carrying the storage key of a `state` event.
+const STORAGE_KEY_FIELD: &str = "key";
+/// Target of the storage access events emitted by the state machine.
+const STATE_TARGET: &str = "state";
+
+/// Errors that can occur while tracing a block.
+#[derive(Debug, derive_more::Display, derive_more::From)]
+pub enum Error {
+	/// The block, its header or its body could not be found.
+	#[display(fmt = "Missing block component: {}", _0)]
+	MissingBlockComponent(String),
+	/// Re-executing the block failed.
+	#[display(fmt = "Dispatch error: {}", _0)]
+	Dispatch(String),
+}
+
+impl std::error::Error for Error {}
+
+/// Collects the spans and events emitted while a block is executed.
+struct BlockSubscriber {
+	targets: Vec<(String, Level)>,
+	next_id: AtomicU64,
+	current_span: CurrentSpan,
+	spans: Mutex<HashMap<Id, SpanDatum>>,
+	events: Mutex<Vec<TraceEvent>>,
+}
+
+impl BlockSubscriber {
+	fn new(targets: &str) -> Self {
+		let mut targets: Vec<_> = targets.split(',').map(|s| parse_target(s)).collect();
+		// Wasm spans and events always pass through `enabled`, as their real target is only
+		// known once their values are recorded. They are filtered when the trace is assembled.
+		targets.push((WASM_TRACE_IDENTIFIER.to_owned(), Level::TRACE));
+		BlockSubscriber {
+			targets,
+			next_id: AtomicU64::new(1),
+			current_span: CurrentSpan::default(),
+			spans: Mutex::new(HashMap::new()),
+			events: Mutex::new(Vec::new()),
+		}
+	}
+}
+
+impl Subscriber for BlockSubscriber {
+	fn enabled(&self, metadata: &tracing::Metadata<'_>) -> bool {
+		check_target(&self.targets, metadata.target(), metadata.level())
+	}
+
+	fn new_span(&self, attrs: &Attributes<'_>) -> Id {
+		let id = Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed));
+		let mut values = Values::default();
+		attrs.record(&mut values);
+		let span = SpanDatum {
+			id: id.clone(),
+			parent_id: attrs.parent().cloned().or_else(|| self.current_span.id()),
+			name: attrs.metadata().name().to_owned(),
+			target: attrs.metadata().target().to_owned(),
+			level: attrs.metadata().level().clone(),
+			line: attrs.metadata().line().unwrap_or(0),
+			start_time: Instant::now(),
+			overall_time: ZERO_DURATION,
+			values,
+		};
+		self.spans.lock().insert(id.clone(), span);
+		id
+	}
+
+	fn record(&self, span: &Id, values: &Record<'_>) {
+		if let Some(s) = self.spans.lock().get_mut(span) {
+			values.record(&mut s.values);
+		}
+	}
+
+	fn record_follows_from(&self, _span: &Id, _follows: &Id) {
+		// Not needed for now.
+	}
+
+	fn event(&self, event: &tracing::Event<'_>) {
+		let mut values = Values::default();
+		event.record(&mut values);
+		let trace_event = TraceEvent {
+			name: event.metadata().name(),
+			target: event.metadata().target().to_owned(),
+			level: event.metadata().level().clone(),
+			values,
+			parent_id: event.parent().cloned().or_else(|| self.current_span.id()),
+		};
+		self.events.lock().push(trace_event);
+	}
+
+	fn enter(&self, id: &Id) {
+		self.current_span.enter(id.clone());
+		if let Some(s) = self.spans.lock().get_mut(id) {
+			s.start_time = Instant::now();
+		}
+	}
+
+	fn exit(&self, id: &Id) {
+		self.current_span.exit();
+		let end_time = Instant::now();
+		if let Some(s) = self.spans.lock().get_mut(id) {
+			s.overall_time += end_time - s.start_time;
+		}
+	}
+}
+
+/// Holds a reference to the client in order to re-execute blocks with tracing enabled.
+pub struct BlockExecutor<Block: BlockT, Client> {
+	client: Arc<Client>,
+	block: Block::Hash,
+	targets: Option<String>,
+	storage_keys: Option<String>,
+}
+
+impl<Block, Client> BlockExecutor<Block, Client>
+	where
+		Block: BlockT + 'static,
+		Client: HeaderBackend<Block> + BlockBackend<Block> + ProvideRuntimeApi<Block>
+			+ Send + Sync + 'static,
+		Client::Api: Core<Block, Error = sp_blockchain::Error>,
+{
+	/// Create a new `BlockExecutor`.
+	///
+	/// `targets` is a comma separated list of tracing targets, [`DEFAULT_TARGETS`] if `None`.
+	/// `storage_keys` is a comma separated list of hex encoded storage key prefixes that `state`
+	/// events are restricted to, all storage accesses are recorded if `None`.
+	pub fn new(
+		client: Arc<Client>,
+		block: Block::Hash,
+		targets: Option<String>,
+		storage_keys: Option<String>,
+	) -> Self {
+		Self { client, block, targets, storage_keys }
+	}
+
+	/// Execute the block on top of its parent state and return the recorded spans and events.
+	///
+	/// Failing to find the block is an error, while a failing re-execution is reported as a
+	/// `TraceError` response.
+	pub fn trace_block(&self) -> Result<TraceBlockResponse, Error> {
+		let id = BlockId::Hash(self.block);
+		let mut header = self.client.header(id)
+			.map_err(|e| Error::MissingBlockComponent(e.to_string()))?
+			.ok_or_else(|| Error::MissingBlockComponent("Header not found".to_string()))?;
+		let extrinsics = self.client.block_body(&id)
+			.map_err(|e| Error::MissingBlockComponent(e.to_string()))?
+			.ok_or_else(|| Error::MissingBlockComponent("Extrinsics not found".to_string()))?;
+		let parent_hash = *header.parent_hash();
+		let parent_id = BlockId::Hash(parent_hash);
+		// Seals are added by the consensus engine after the block is built and are stripped
+		// again on import, so the runtime would reject a block that still carries them.
+		header.digest_mut().logs.retain(|d| d.as_seal().is_none());
+		let block = Block::new(header, extrinsics);
+
+		let targets = self.targets.as_deref().unwrap_or(DEFAULT_TARGETS);
+		let dispatch = Dispatch::new(BlockSubscriber::new(targets));
+		let result = dispatcher::with_default(&dispatch, || {
+			self.client.runtime_api().execute_block(&parent_id, block)
+		});
+		if let Err(e) = result {
+			return Ok(TraceBlockResponse::TraceError(TraceError {
+				error: format!("Error executing block: {}", e),
+			}));
+		}
+
+		let subscriber = dispatch.downcast_ref::<BlockSubscriber>()
+			.ok_or_else(|| Error::Dispatch("Cannot downcast Dispatch to BlockSubscriber".to_string()))?;
+		let target_filter = parse_targets(targets);
+		let key_filter = self.storage_keys.as_deref().map(parse_storage_keys);
+
+		let mut spans: Vec<Span> = subscriber.spans.lock()
+			.drain()
+			.map(|(_, s)| normalize_span(s))
+			.filter(|s| check_target(&target_filter, &s.target, &s.level))
+			.map(|s| Span {
+				id: s.id.into_u64(),
+				parent_id: s.parent_id.map(|id| id.into_u64()),
+				wasm: s.values.bool_values.get("wasm").copied().unwrap_or(false),
+				overall_time: s.overall_time.as_nanos() as u64,
+				name: s.name,
+				target: s.target,
+				data: to_data(s.values),
+			})
+			.collect();
+		// Span ids are handed out in creation order.
+		spans.sort_by_key(|s| s.id);
+
+		let events = subscriber.events.lock()
+			.drain(..)
+			.map(normalize_event)
+			.filter(|e| check_target(&target_filter, &e.target, &e.level))
+			.filter(|e| match (&key_filter, e.target.as_str()) {
+				(Some(keys), STATE_TARGET) => storage_key_matches(keys, &e.values),
+				_ => true,
+			})
+			.map(|e| Event {
+				parent_id: e.parent_id.map(|id| id.into_u64()),
+				target: e.target,
+				data: to_data(e.values),
+			})
+			.collect();
+
+		Ok(TraceBlockResponse::BlockTrace(BlockTrace {
+			block_hash: format!("0x{}", HexDisplay::from(&self.block.as_ref())),
+			parent_hash: format!("0x{}", HexDisplay::from(&parent_hash.as_ref())),
+			tracing_targets: targets.to_string(),
+			storage_keys: self.storage_keys.clone().unwrap_or_default(),
+			spans,
+			events,
+		}))
+	}
+}
+
+fn parse_targets(targets: &str) -> Vec<(String, Level)> {
+	targets.split(',').map(|s| parse_target(s)).collect()
+}
+
+fn parse_storage_keys(keys: &str) -> Vec<String> {
+	keys.split(',')
+		.map(|k| k.trim().trim_start_matches("0x").to_lowercase())
+		.filter(|k| !k.is_empty())
+		.collect()
+}
+
+fn check_target(targets: &[(String, Level)], target: &str, level: &Level) -> bool {
+	targets.iter().any(|t| target.starts_with(t.0.as_str()) && level <= &t.1)
+}
+
+fn storage_key_matches(keys: &[String], values: &Values) -> bool {
+	match values.string_values.get(STORAGE_KEY_FIELD) {
+		Some(key) => keys.iter().any(|k| key.starts_with(k.as_str())),
+		None => true,
+	}
+}
+
+/// Replace the generic wasm name and target of a span with the ones recorded from the runtime.
+fn normalize_span(mut span: SpanDatum) -> SpanDatum {
+	if span.name == WASM_TRACE_IDENTIFIER {
+		span.values.bool_values.insert("wasm".to_owned(), true);
+		if let Some(n) = span.values.string_values.remove(WASM_NAME_KEY) {
+			span.name = n;
+		}
+		if let Some(t) = span.values.string_values.remove(WASM_TARGET_KEY) {
+			span.target = t;
+		}
+	}
+	span
+}
+
+/// Replace the generic wasm target of an event with the one recorded from the runtime.
+fn normalize_event(mut event: TraceEvent) -> TraceEvent {
+	if event.target == WASM_TRACE_IDENTIFIER {
+		if let Some(t) = event.values.string_values.remove(WASM_TARGET_KEY) {
+			event.target = t;
+		}
+	}
+	event
+}
+
+/// Flatten all recorded values into their string representation.
+fn to_data(values: Values) -> Data {
+	let Values { bool_values, i64_values, u64_values, string_values } = values;
+	let string_values = bool_values.into_iter().map(|(k, v)| (k, v.to_string()))
+		.chain(i64_values.into_iter().map(|(k, v)| (k, v.to_string())))
+		.chain(u64_values.into_iter().map(|(k, v)| (k, v.to_string())))
+		.chain(string_values.into_iter())
+		.collect();
+	Data { string_values }
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn subscriber_collects_enabled_targets_only() {
+		let dispatch = Dispatch::new(BlockSubscriber::new("pallet,state"));
+		dispatcher::with_default(&dispatch, || {
+			let span = tracing::info_span!(target: "pallet_balances", "transfer");
+			let _guard = span.enter();
+			tracing::trace!(target: "state", key = "0xaabb", "Get");
+			tracing::trace!(target: "sync", "ignored");
+		});
+		let subscriber = dispatch.downcast_ref::<BlockSubscriber>().unwrap();
+		let spans = subscriber.spans.lock();
+		assert_eq!(spans.len(), 1);
+		let span = spans.values().next().unwrap();
+		assert_eq!(span.name, "transfer");
+		let events = subscriber.events.lock();
+		assert_eq!(events.len(), 1);
+		assert_eq!(events[0].target, "state");
+		assert_eq!(events[0].parent_id.as_ref(), Some(&span.id));
+	}
+
+	#[test]
+	fn storage_keys_filter_by_prefix() {
+		let keys = parse_storage_keys("0xAABB, ccdd");
+		assert_eq!(keys, vec!["aabb".to_string(), "ccdd".to_string()]);
+		let mut values = Values::default();
+		values.string_values.insert(STORAGE_KEY_FIELD.to_owned(), "aabb0011".to_owned());
+		assert!(storage_key_matches(&keys, &values));
+		values.string_values.insert(STORAGE_KEY_FIELD.to_owned(), "eeff".to_owned());
+		assert!(!storage_key_matches(&keys, &values));
+	}
+}
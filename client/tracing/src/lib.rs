@@ -23,6 +23,11 @@
 //! See `sp-tracing` for examples on how to use tracing.
 //!
 //! Currently we provide `Log` (default), `Telemetry` variants for `Receiver`
+//!
+//! The [`block`] module re-executes single blocks with tracing enabled, which backs the
+//! `state_traceBlock` RPC.
+
+pub mod block;
 
 use rustc_hash::FxHashMap;
 use std::fmt;
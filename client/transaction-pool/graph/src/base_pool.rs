@@ -21,7 +21,7 @@
 //! For a more full-featured pool, have a look at the `pool` module.
 
 use std::{
-	collections::HashSet,
+	collections::{HashMap, HashSet},
 	fmt,
 	hash,
 	sync::Arc,
@@ -30,7 +30,7 @@ use std::{
 use log::{trace, debug, warn};
 use serde::Serialize;
 use sp_core::hexdisplay::HexDisplay;
-use sp_runtime::traits::Member;
+use sp_runtime::{Percent, traits::Member};
 use sp_runtime::transaction_validity::{
 	TransactionTag as Tag,
 	TransactionLongevity as Longevity,
@@ -261,6 +261,13 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 		return_value
 	}
 
+	/// Sets the minimal priority bump required to replace transactions in the ready queue.
+	///
+	/// See [`ReadyTransactions::set_min_priority_bump`] for details.
+	pub fn set_min_priority_bump(&mut self, bump: Percent) {
+		self.ready.set_min_priority_bump(bump);
+	}
+
 	/// Returns if the transaction for the given hash is already imported.
 	pub fn is_imported(&self, tx_hash: &Hash) -> bool {
 		self.future.contains(tx_hash) || self.ready.contains(tx_hash)
@@ -407,52 +414,115 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 	///
 	/// Removes and returns worst transactions from the queues and all transactions that depend on them.
 	/// Technically the worst transaction should be evaluated by computing the entire pending set.
-	/// We use a simplified approach to remove the transaction that occupies the pool for the longest time.
-	pub fn enforce_limits(&mut self, ready: &Limit, future: &Limit) -> Vec<Arc<Transaction<Hash, Ex>>> {
-		let mut removed = vec![];
+	/// We use a simplified approach: the worst transaction is taken from the sender that holds
+	/// the most transactions in the queue, preferring ones without dependents, then the ones
+	/// with the lowest priority and finally the ones that occupy the pool for the longest time.
+	///
+	/// Senders exceeding the `sender` limit are trimmed first, regardless of the queue limits.
+	pub fn enforce_limits(
+		&mut self,
+		ready: &Limit,
+		future: &Limit,
+		sender: &SenderLimit,
+	) -> EnforcedLimits<Hash, Ex> {
+		let mut enforced = EnforcedLimits::default();
 
-		while ready.is_exceeded(self.ready.len(), self.ready.bytes()) {
-			// find the worst transaction
-			let minimal = self.ready
-				.fold(|minimal, current| {
-					let transaction = &current.transaction;
-					match minimal {
-						None => Some(transaction.clone()),
-						Some(ref tx) if tx.insertion_id > transaction.insertion_id => {
-							Some(transaction.clone())
-						},
-						other => other,
-					}
-				});
+		loop {
+			let worst = worst_of_largest_sender(&self.ready_candidates(sender));
+			match worst {
+				Some((count, hash)) if count > sender.ready => {
+					enforced.sender_limit.append(&mut self.remove_subtree(&[hash]));
+				},
+				Some((_, hash)) if ready.is_exceeded(self.ready.len(), self.ready.bytes()) => {
+					enforced.pool_limit.append(&mut self.remove_subtree(&[hash]));
+				},
+				_ => break,
+			}
+		}
 
-			if let Some(minimal) = minimal {
-				removed.append(&mut self.remove_subtree(&[minimal.transaction.hash.clone()]))
-			} else {
-				break;
+		loop {
+			let worst = worst_of_largest_sender(&self.future_candidates(sender));
+			match worst {
+				Some((count, hash)) if count > sender.future => {
+					enforced.sender_limit.append(&mut self.remove_subtree(&[hash]));
+				},
+				Some((_, hash)) if future.is_exceeded(self.future.len(), self.future.bytes()) => {
+					enforced.pool_limit.append(&mut self.remove_subtree(&[hash]));
+				},
+				_ => break,
 			}
 		}
 
-		while future.is_exceeded(self.future.len(), self.future.bytes()) {
-			// find the worst transaction
-			let minimal = self.future
-				.fold(|minimal, current| {
-					match minimal {
-						None => Some(current.clone()),
-						Some(ref tx) if tx.imported_at > current.imported_at => {
-							Some(current.clone())
-						},
-						other => other,
-					}
-				});
+		enforced
+	}
 
-			if let Some(minimal) = minimal {
-				removed.append(&mut self.remove_subtree(&[minimal.transaction.hash.clone()]))
-			} else {
-				break;
-			}
+	/// Returns true if `enforce_limits` has transactions to remove after importing transactions
+	/// of `senders`.
+	///
+	/// Only the senders of the imported transactions can exceed the sender limit,
+	/// so the transactions of the other senders are not counted.
+	pub fn exceeds_limits(
+		&mut self,
+		ready: &Limit,
+		future: &Limit,
+		sender: &SenderLimit,
+		senders: &HashSet<Vec<u8>>,
+	) -> bool {
+		if ready.is_exceeded(self.ready.len(), self.ready.bytes())
+			|| future.is_exceeded(self.future.len(), self.future.bytes())
+		{
+			return true
+		}
+		if senders.is_empty() {
+			return false
 		}
 
-		removed
+		let mut ready_counts = HashMap::new();
+		self.ready.fold(|_, current| {
+			count_sender(&mut ready_counts, senders, sender, &current.transaction.transaction);
+			None::<()>
+		});
+		let mut future_counts = HashMap::new();
+		for tx in self.future.all() {
+			count_sender(&mut future_counts, senders, sender, tx);
+		}
+
+		ready_counts.values().any(|count| *count > sender.ready)
+			|| future_counts.values().any(|count| *count > sender.future)
+	}
+
+	fn ready_candidates(&mut self, limit: &SenderLimit) -> Vec<EvictionCandidate<Hash, u64>> {
+		self.ready
+			.fold(|candidates, current| {
+				let transaction = &current.transaction;
+				let mut candidates = candidates.unwrap_or_else(Vec::new);
+				candidates.push(EvictionCandidate {
+					hash: transaction.transaction.hash.clone(),
+					sender: limit.sender(&transaction.transaction),
+					has_dependents: !current.unlocks.is_empty(),
+					priority: transaction.transaction.priority,
+					age: transaction.insertion_id,
+				});
+				Some(candidates)
+			})
+			.unwrap_or_default()
+	}
+
+	fn future_candidates(&mut self, limit: &SenderLimit) -> Vec<EvictionCandidate<Hash, wasm_timer::Instant>> {
+		self.future
+			.fold(|candidates, current| {
+				let mut candidates = candidates.unwrap_or_else(Vec::new);
+				candidates.push(EvictionCandidate {
+					hash: current.transaction.hash.clone(),
+					sender: limit.sender(&current.transaction),
+					// Dependencies between future transactions are not tracked.
+					has_dependents: false,
+					priority: current.transaction.priority,
+					age: current.imported_at,
+				});
+				Some(candidates)
+			})
+			.unwrap_or_default()
 	}
 
 	/// Removes all transactions represented by the hashes and all other transactions
@@ -543,6 +613,113 @@ impl Limit {
 	}
 }
 
+/// Per-sender queue limits.
+///
+/// The sender of a transaction is derived from the first tag it provides,
+/// which for FRAME-based runtimes is the encoded `(AccountId, Index)` pair.
+#[derive(Debug, Clone)]
+pub struct SenderLimit {
+	/// Maximal number of ready transactions of a single sender.
+	pub ready: usize,
+	/// Maximal number of future transactions of a single sender.
+	pub future: usize,
+	/// Length of the encoded nonce at the end of the provided tag.
+	pub nonce_len: usize,
+}
+
+impl Default for SenderLimit {
+	fn default() -> Self {
+		SenderLimit {
+			ready: 1024,
+			future: 64,
+			nonce_len: 4,
+		}
+	}
+}
+
+impl SenderLimit {
+	/// Returns the sender identifier of given transaction.
+	///
+	/// Transactions with tags too short to carry a sender are not grouped with any other.
+	pub fn sender<Hash, Ex>(&self, tx: &Transaction<Hash, Ex>) -> Option<Vec<u8>> {
+		self.sender_tag(tx).map(<[u8]>::to_vec)
+	}
+
+	fn sender_tag<'a, Hash, Ex>(&self, tx: &'a Transaction<Hash, Ex>) -> Option<&'a [u8]> {
+		tx.provides.first()
+			.filter(|tag| tag.len() > self.nonce_len)
+			.map(|tag| &tag[..tag.len() - self.nonce_len])
+	}
+}
+
+/// Transactions removed while enforcing limits.
+#[derive(Debug)]
+pub struct EnforcedLimits<Hash, Ex> {
+	/// Transactions removed because their sender exceeded the per-sender limit.
+	pub sender_limit: Vec<Arc<Transaction<Hash, Ex>>>,
+	/// Transactions removed because the queue limits were exceeded.
+	pub pool_limit: Vec<Arc<Transaction<Hash, Ex>>>,
+}
+
+impl<Hash, Ex> Default for EnforcedLimits<Hash, Ex> {
+	fn default() -> Self {
+		EnforcedLimits {
+			sender_limit: Vec::new(),
+			pool_limit: Vec::new(),
+		}
+	}
+}
+
+impl<Hash, Ex> EnforcedLimits<Hash, Ex> {
+	/// Iterates over all removed transactions.
+	pub fn iter(&self) -> impl Iterator<Item=&Arc<Transaction<Hash, Ex>>> {
+		self.sender_limit.iter().chain(self.pool_limit.iter())
+	}
+}
+
+struct EvictionCandidate<Hash, Age> {
+	hash: Hash,
+	sender: Option<Vec<u8>>,
+	has_dependents: bool,
+	priority: Priority,
+	age: Age,
+}
+
+/// Counts the transaction if it belongs to one of `senders`.
+fn count_sender<'a, Hash, Ex>(
+	counts: &mut HashMap<&'a Vec<u8>, usize>,
+	senders: &'a HashSet<Vec<u8>>,
+	limit: &SenderLimit,
+	tx: &Transaction<Hash, Ex>,
+) {
+	if let Some(sender) = limit.sender_tag(tx).and_then(|tag| senders.get(tag)) {
+		*counts.entry(sender).or_default() += 1;
+	}
+}
+
+/// Picks the transaction to evict.
+///
+/// Returns the number of transactions held by the sender of the picked transaction and its hash.
+fn worst_of_largest_sender<Hash: Clone, Age: Ord>(
+	candidates: &[EvictionCandidate<Hash, Age>],
+) -> Option<(usize, Hash)> {
+	let mut counts = HashMap::<&[u8], usize>::new();
+	for sender in candidates.iter().filter_map(|c| c.sender.as_ref()) {
+		*counts.entry(sender.as_slice()).or_default() += 1;
+	}
+	let count = |c: &EvictionCandidate<Hash, Age>| {
+		c.sender.as_ref().map_or(1, |sender| counts[sender.as_slice()])
+	};
+
+	candidates.iter()
+		.max_by(|a, b| count(a).cmp(&count(b))
+			.then_with(|| b.has_dependents.cmp(&a.has_dependents))
+			.then_with(|| b.priority.cmp(&a.priority))
+			.then_with(|| b.age.cmp(&a.age))
+		)
+		.map(|worst| (count(worst), worst.hash.clone()))
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -553,6 +730,58 @@ mod tests {
 		BasePool::default()
 	}
 
+	#[test]
+	fn should_evict_from_sender_holding_most_transactions() {
+		// given
+		let mut pool = pool();
+		let tag = |sender: u8, nonce: u32| {
+			let mut tag = vec![sender; 4];
+			tag.extend_from_slice(&nonce.to_le_bytes());
+			tag
+		};
+		let tx = |hash: u64, sender: u8, nonce: u32| Transaction {
+			data: vec![hash as u8],
+			bytes: 1,
+			hash,
+			priority: 5u64,
+			valid_till: 64u64,
+			requires: if nonce > 0 { vec![tag(sender, nonce - 1)] } else { vec![] },
+			provides: vec![tag(sender, nonce)],
+			propagate: true,
+			source: Source::External,
+		};
+		pool.import(tx(1, 1, 0)).unwrap();
+		pool.import(tx(2, 1, 1)).unwrap();
+		pool.import(tx(3, 1, 2)).unwrap();
+		pool.import(tx(4, 2, 0)).unwrap();
+		assert_eq!(pool.ready().count(), 4);
+		let limit = Limit { count: 100, total_bytes: 1000 };
+		let sender_limit = SenderLimit { ready: 2, future: 2, nonce_len: 4 };
+		let senders = |senders: &[u8]| senders.iter().map(|sender| vec![*sender; 4]).collect::<HashSet<_>>();
+		assert!(!pool.exceeds_limits(&limit, &limit, &sender_limit, &senders(&[2])));
+		assert!(pool.exceeds_limits(&limit, &limit, &sender_limit, &senders(&[1])));
+
+		// when
+		let enforced = pool.enforce_limits(&limit, &limit, &sender_limit);
+
+		// then
+		assert_eq!(enforced.sender_limit.iter().map(|tx| tx.hash).collect::<Vec<_>>(), vec![3]);
+		assert!(enforced.pool_limit.is_empty());
+
+		// when
+		assert!(!pool.exceeds_limits(&limit, &limit, &sender_limit, &senders(&[1, 2])));
+		let limit = Limit { count: 2, total_bytes: 1000 };
+		assert!(pool.exceeds_limits(&limit, &limit, &sender_limit, &senders(&[])));
+		let enforced = pool.enforce_limits(&limit, &limit, &sender_limit);
+
+		// then
+		assert!(enforced.sender_limit.is_empty());
+		assert_eq!(enforced.pool_limit.iter().map(|tx| tx.hash).collect::<Vec<_>>(), vec![2]);
+		let mut remaining = pool.ready().map(|tx| tx.hash).collect::<Vec<_>>();
+		remaining.sort();
+		assert_eq!(remaining, vec![1, 4]);
+	}
+
 	#[test]
 	fn should_import_transaction_to_ready() {
 		// given
@@ -37,7 +37,8 @@ mod tracked_map;
 pub mod base_pool;
 pub mod watcher;
 
-pub use self::base_pool::Transaction;
+pub use self::base_pool::{Transaction, SenderLimit};
+pub use self::validated_pool::EvictionStats;
 pub use self::pool::{
 	Pool, Options, ChainApi, EventStream, ExtrinsicFor, ExtrinsicHash,
 	BlockHash, NumberFor, TransactionFor, ValidatedTransaction,
@@ -18,6 +18,7 @@
 
 use std::{
 	collections::HashMap,
+	path::PathBuf,
 	sync::Arc,
 };
 
@@ -26,6 +27,7 @@ use crate::{base_pool as base, watcher::Watcher};
 use futures::Future;
 use sp_runtime::{
 	generic::BlockId,
+	Percent,
 	traits::{self, SaturatedConversion, Block as BlockT},
 	transaction_validity::{
 		TransactionValidity, TransactionTag as Tag, TransactionValidityError, TransactionSource,
@@ -105,8 +107,14 @@ pub struct Options {
 	pub ready: base::Limit,
 	/// Future queue limits.
 	pub future: base::Limit,
+	/// Limits of transactions held by a single sender.
+	pub sender_limit: base::SenderLimit,
+	/// Minimal priority increase required to replace transactions in the ready queue.
+	pub min_priority_bump: Percent,
 	/// Reject future transactions.
 	pub reject_future_transactions: bool,
+	/// File the transactions are saved to, so that they are restored after a restart.
+	pub persistence_path: Option<PathBuf>,
 }
 
 impl Default for Options {
@@ -120,7 +128,10 @@ impl Default for Options {
 				count: 512,
 				total_bytes: 1 * 1024 * 1024,
 			},
+			sender_limit: Default::default(),
+			min_priority_bump: Percent::from_percent(10),
 			reject_future_transactions: false,
+			persistence_path: None,
 		}
 	}
 }
@@ -25,7 +25,7 @@ use std::{
 
 use serde::Serialize;
 use log::trace;
-use sp_runtime::traits::Member;
+use sp_runtime::{Percent, traits::Member};
 use sp_runtime::transaction_validity::{
 	TransactionTag as Tag,
 };
@@ -118,6 +118,9 @@ pub struct ReadyTransactions<Hash: hash::Hash + Eq, Ex> {
 	ready: TrackedMap<Hash, ReadyTx<Hash, Ex>>,
 	/// Best transactions that are ready to be included to the block without any other previous transaction.
 	best: BTreeSet<TransactionRef<Hash, Ex>>,
+	/// Minimal priority increase required to replace transactions.
+	#[ignore_malloc_size_of = "Copy type"]
+	min_priority_bump: Percent,
 }
 
 impl<Hash, Ex> tracked_map::Size for ReadyTx<Hash, Ex> {
@@ -133,6 +136,7 @@ impl<Hash: hash::Hash + Eq, Ex> Default for ReadyTransactions<Hash, Ex> {
 			provided_tags: Default::default(),
 			ready: Default::default(),
 			best: Default::default(),
+			min_priority_bump: Percent::zero(),
 		}
 	}
 }
@@ -162,6 +166,12 @@ impl<Hash: hash::Hash + Member + Serialize, Ex> ReadyTransactions<Hash, Ex> {
 		}
 	}
 
+	/// Sets the minimal priority increase, relative to the total priority of the replaced
+	/// transactions, that a transaction providing the same tags needs to replace them.
+	pub fn set_min_priority_bump(&mut self, bump: Percent) {
+		self.min_priority_bump = bump;
+	}
+
 	/// Imports transactions to the pool of ready transactions.
 	///
 	/// The transaction needs to have all tags satisfied (be ready) by transactions
@@ -441,7 +451,8 @@ impl<Hash: hash::Hash + Member + Serialize, Ex> ReadyTransactions<Hash, Ex> {
 			};
 
 			// bail - the transaction has too low priority to replace the old ones
-			if old_priority >= tx.priority {
+			let min_priority = old_priority.saturating_add(self.min_priority_bump * old_priority);
+			if tx.priority <= old_priority || tx.priority < min_priority {
 				return Err(error::Error::TooLowPriority { old: old_priority, new: tx.priority })
 			}
 
@@ -605,6 +616,59 @@ mod tests {
 		assert_eq!(ready.get().count(), 1);
 	}
 
+	#[test]
+	fn should_require_min_priority_bump_to_replace() {
+		// given
+		let mut ready = ReadyTransactions::default();
+		ready.set_min_priority_bump(Percent::from_percent(50));
+		let mut tx1 = tx(1);
+		tx1.requires.clear();
+		let mut tx2 = tx(2);
+		tx2.requires.clear();
+		tx2.provides = vec![vec![3]];
+		tx2.priority = 2;
+		let mut tx3 = tx(3);
+		tx3.requires.clear();
+		tx3.provides = vec![vec![4]];
+		tx3.priority = 2;
+		import(&mut ready, tx2).unwrap();
+		import(&mut ready, tx3).unwrap();
+
+		// when
+		tx1.priority = 5;
+		let err = import(&mut ready, tx1.clone()).unwrap_err();
+
+		// then
+		assert!(matches!(err, error::Error::TooLowPriority { old: 4, new: 5 }));
+		// exactly the bumped priority is enough
+		tx1.priority = 6;
+		import(&mut ready, tx1).unwrap();
+		assert_eq!(ready.get().count(), 1);
+	}
+
+	#[test]
+	fn should_require_higher_priority_to_replace_without_bump() {
+		// given
+		let mut ready = ReadyTransactions::default();
+		let mut tx1 = tx(1);
+		tx1.requires.clear();
+		tx1.priority = 2;
+		let mut tx2 = tx(2);
+		tx2.requires.clear();
+		tx2.provides = vec![vec![3]];
+		tx2.priority = 2;
+		import(&mut ready, tx2).unwrap();
+
+		// when
+		let err = import(&mut ready, tx1.clone()).unwrap_err();
+
+		// then
+		assert!(matches!(err, error::Error::TooLowPriority { old: 2, new: 2 }));
+		tx1.priority = 3;
+		import(&mut ready, tx1).unwrap();
+		assert_eq!(ready.get().count(), 1);
+	}
+
 	#[test]
 	fn should_replace_multiple_transactions_correctly() {
 		// given
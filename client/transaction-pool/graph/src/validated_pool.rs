@@ -19,7 +19,7 @@
 use std::{
 	collections::{HashSet, HashMap},
 	hash,
-	sync::Arc,
+	sync::{Arc, atomic::{AtomicU64, Ordering}},
 };
 
 use crate::base_pool as base;
@@ -101,6 +101,23 @@ pub struct ValidatedPool<B: ChainApi> {
 	>>,
 	import_notification_sinks: Mutex<Vec<Sender<ExtrinsicHash<B>>>>,
 	rotator: PoolRotator<ExtrinsicHash<B>>,
+	/// Transactions evicted since the last call to `take_evictions`.
+	evictions: Evictions,
+}
+
+/// Number of transactions evicted while enforcing the pool limits.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct EvictionStats {
+	/// Evicted because their sender exceeded the per-sender limit.
+	pub sender_limit: u64,
+	/// Evicted because the queue limits were exceeded.
+	pub pool_limit: u64,
+}
+
+#[derive(Default)]
+struct Evictions {
+	sender_limit: AtomicU64,
+	pool_limit: AtomicU64,
 }
 
 #[cfg(not(target_os = "unknown"))]
@@ -117,7 +134,8 @@ where
 impl<B: ChainApi> ValidatedPool<B> {
 	/// Create a new transaction pool.
 	pub fn new(options: Options, api: Arc<B>) -> Self {
-		let base_pool = base::BasePool::new(options.reject_future_transactions);
+		let mut base_pool = base::BasePool::new(options.reject_future_transactions);
+		base_pool.set_min_priority_bump(options.min_priority_bump);
 		ValidatedPool {
 			options,
 			listener: Default::default(),
@@ -125,6 +143,15 @@ impl<B: ChainApi> ValidatedPool<B> {
 			pool: RwLock::new(base_pool),
 			import_notification_sinks: Default::default(),
 			rotator: Default::default(),
+			evictions: Default::default(),
+		}
+	}
+
+	/// Returns the number of transactions evicted since the last call and resets the counters.
+	pub fn take_evictions(&self) -> EvictionStats {
+		EvictionStats {
+			sender_limit: self.evictions.sender_limit.swap(0, Ordering::Relaxed),
+			pool_limit: self.evictions.pool_limit.swap(0, Ordering::Relaxed),
 		}
 	}
 
@@ -162,13 +189,25 @@ impl<B: ChainApi> ValidatedPool<B> {
 		&self,
 		txs: impl IntoIterator<Item=ValidatedTransactionFor<B>>,
 	) -> Vec<Result<ExtrinsicHash<B>, B::Error>> {
+		let sender_limit = &self.options.sender_limit;
+		let mut senders = HashSet::new();
 		let results = txs.into_iter()
-			.map(|validated_tx| self.submit_one(validated_tx))
+			.map(|validated_tx| {
+				let sender = match validated_tx {
+					ValidatedTransaction::Valid(ref tx) => sender_limit.sender(tx),
+					_ => None,
+				};
+				let result = self.submit_one(validated_tx);
+				if let (Ok(_), Some(sender)) = (&result, sender) {
+					senders.insert(sender);
+				}
+				result
+			})
 			.collect::<Vec<_>>();
 
 		// only enforce limits if there is at least one imported transaction
 		let removed = if results.iter().any(|res| res.is_ok()) {
-			self.enforce_limits()
+			self.enforce_limits(&senders)
 		} else {
 			Default::default()
 		};
@@ -217,10 +256,12 @@ impl<B: ChainApi> ValidatedPool<B> {
 		}
 	}
 
-	fn enforce_limits(&self) -> HashSet<ExtrinsicHash<B>> {
+	/// Remove the transactions exceeding the limits, after importing transactions of `senders`.
+	fn enforce_limits(&self, senders: &HashSet<Vec<u8>>) -> HashSet<ExtrinsicHash<B>> {
 		let status = self.pool.read().status();
 		let ready_limit = &self.options.ready;
 		let future_limit = &self.options.future;
+		let sender_limit = &self.options.sender_limit;
 
 		log::debug!(target: "txpool", "Pool Status: {:?}", status);
 		if ready_limit.is_exceeded(status.ready, status.ready_bytes)
@@ -232,30 +273,33 @@ impl<B: ChainApi> ValidatedPool<B> {
 				ready_limit.count, ready_limit.total_bytes / 1024,
 				future_limit.count, future_limit.total_bytes / 1024,
 			);
+		}
 
-			// clean up the pool
-			let removed = {
-				let mut pool = self.pool.write();
-				let removed = pool.enforce_limits(ready_limit, future_limit)
-					.into_iter().map(|x| x.hash.clone()).collect::<HashSet<_>>();
-				// ban all removed transactions
-				self.rotator.ban(&Instant::now(), removed.iter().map(|x| x.clone()));
-				removed
-			};
-			if !removed.is_empty() {
-				log::debug!(target: "txpool", "Enforcing limits: {} dropped", removed.len());
-			}
-
-			// run notifications
-			let mut listener = self.listener.write();
-			for h in &removed {
-				listener.dropped(h, None);
+		// clean up the pool
+		let removed = {
+			let mut pool = self.pool.write();
+			if !pool.exceeds_limits(ready_limit, future_limit, sender_limit, senders) {
+				return Default::default()
 			}
-
+			let enforced = pool.enforce_limits(ready_limit, future_limit, sender_limit);
+			self.evictions.sender_limit.fetch_add(enforced.sender_limit.len() as u64, Ordering::Relaxed);
+			self.evictions.pool_limit.fetch_add(enforced.pool_limit.len() as u64, Ordering::Relaxed);
+			let removed = enforced.iter().map(|x| x.hash.clone()).collect::<HashSet<_>>();
+			// ban all removed transactions
+			self.rotator.ban(&Instant::now(), removed.iter().map(|x| x.clone()));
 			removed
-		} else {
-			Default::default()
+		};
+		if !removed.is_empty() {
+			log::debug!(target: "txpool", "Enforcing limits: {} dropped", removed.len());
 		}
+
+		// run notifications
+		let mut listener = self.listener.write();
+		for h in &removed {
+			listener.dropped(h, None);
+		}
+
+		removed
 	}
 
 	/// Import a single extrinsic and starts to watch their progress in the pool.
@@ -591,6 +635,11 @@ impl<B: ChainApi> ValidatedPool<B> {
 		self.pool.read().ready()
 	}
 
+	/// Get a copy of the future transactions.
+	pub fn futures(&self) -> Vec<TransactionFor<B>> {
+		self.pool.read().futures().map(|tx| Arc::new(tx.duplicate())).collect()
+	}
+
 	/// Returns pool status.
 	pub fn status(&self) -> PoolStatus {
 		self.pool.read().status()
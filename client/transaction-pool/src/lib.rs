@@ -25,6 +25,7 @@
 mod api;
 mod revalidation;
 mod metrics;
+mod persistence;
 
 pub mod error;
 
@@ -36,6 +37,7 @@ pub use crate::api::{FullChainApi, LightChainApi};
 
 use std::{collections::{HashMap, HashSet}, sync::Arc, pin::Pin, convert::TryInto};
 use futures::{prelude::*, future::{self, ready}, channel::oneshot};
+use codec::{Decode, Encode};
 use parking_lot::Mutex;
 
 use sp_runtime::{
@@ -53,6 +55,7 @@ use wasm_timer::Instant;
 
 use prometheus_endpoint::Registry as PrometheusRegistry;
 use crate::metrics::MetricsLink as PrometheusMetrics;
+use crate::persistence::Persistence;
 
 type BoxedReadyIterator<Hash, Data> = Box<
 	dyn Iterator<Item=Arc<sc_transaction_graph::base_pool::Transaction<Hash, Data>>> + Send
@@ -81,6 +84,7 @@ pub struct BasicPool<PoolApi, Block>
 	revalidation_queue: Arc<revalidation::RevalidationQueue<PoolApi>>,
 	ready_poll: Arc<Mutex<ReadyPoll<ReadyIteratorFor<PoolApi>, Block>>>,
 	metrics: PrometheusMetrics,
+	persistence: Option<Arc<Persistence<ExtrinsicHash<PoolApi>>>>,
 }
 
 struct ReadyPoll<T, Block: BlockT> {
@@ -163,7 +167,17 @@ impl<PoolApi, Block> BasicPool<PoolApi, Block>
 	pub fn new_test(
 		pool_api: Arc<PoolApi>,
 	) -> (Self, Pin<Box<dyn Future<Output=()> + Send>>, intervalier::BackSignalControl) {
-		let pool = Arc::new(sc_transaction_graph::Pool::new(Default::default(), pool_api.clone()));
+		Self::new_test_with_options(Default::default(), pool_api)
+	}
+
+	/// Create new basic transaction pool with provided options and api, for tests.
+	#[cfg(test)]
+	pub fn new_test_with_options(
+		options: sc_transaction_graph::Options,
+		pool_api: Arc<PoolApi>,
+	) -> (Self, Pin<Box<dyn Future<Output=()> + Send>>, intervalier::BackSignalControl) {
+		let persistence = options.persistence_path.clone().map(|path| Arc::new(Persistence::new(path)));
+		let pool = Arc::new(sc_transaction_graph::Pool::new(options, pool_api.clone()));
 		let (revalidation_queue, background_task, notifier) =
 			revalidation::RevalidationQueue::new_test(pool_api.clone(), pool.clone());
 		(
@@ -174,6 +188,7 @@ impl<PoolApi, Block> BasicPool<PoolApi, Block>
 				revalidation_strategy: Arc::new(Mutex::new(RevalidationStrategy::Always)),
 				ready_poll: Default::default(),
 				metrics: Default::default(),
+				persistence,
 			},
 			background_task,
 			notifier,
@@ -189,6 +204,7 @@ impl<PoolApi, Block> BasicPool<PoolApi, Block>
 		revalidation_type: RevalidationType,
 		spawner: impl SpawnNamed,
 	) -> Self {
+		let persistence = options.persistence_path.clone().map(|path| Arc::new(Persistence::new(path)));
 		let pool = Arc::new(sc_transaction_graph::Pool::new(options, pool_api.clone()));
 		let (revalidation_queue, background_task) = match revalidation_type {
 			RevalidationType::Light => (revalidation::RevalidationQueue::new(pool_api.clone(), pool.clone()), None),
@@ -214,6 +230,7 @@ impl<PoolApi, Block> BasicPool<PoolApi, Block>
 			)),
 			ready_poll: Default::default(),
 			metrics: PrometheusMetrics::new(prometheus),
+			persistence,
 		}
 	}
 
@@ -221,6 +238,111 @@ impl<PoolApi, Block> BasicPool<PoolApi, Block>
 	pub fn pool(&self) -> &Arc<sc_transaction_graph::Pool<PoolApi>> {
 		&self.pool
 	}
+
+	/// Import the transactions saved by a previous run back into the pool, if persistence
+	/// is enabled.
+	///
+	/// The transactions are validated again at the given block, in the order they were
+	/// first submitted, and keep their submission time and local origin. Resolves to the
+	/// number of transactions imported.
+	pub fn restore_persisted(&self, at: &BlockId<Block>) -> impl Future<Output=usize> + Send {
+		let pool = self.pool.clone();
+		let persistence = self.persistence.clone();
+		let at = *at;
+
+		async move {
+			let persistence = match persistence {
+				Some(persistence) => persistence,
+				None => return 0,
+			};
+			let transactions = persistence.load().unwrap_or_else(|e| {
+				log::warn!(target: "txpool", "Failed to load saved transactions: {}", e);
+				Vec::new()
+			});
+			let total = transactions.len();
+
+			let mut restored = 0;
+			let mut transactions = transactions.into_iter().peekable();
+			while let Some(first) = transactions.next() {
+				// submit the consecutive transactions of the same origin together.
+				let local = first.local;
+				let mut batch = vec![first];
+				while transactions.peek().map_or(false, |tx| tx.local == local) {
+					batch.extend(transactions.next());
+				}
+
+				let (times, xts): (Vec<_>, Vec<sc_transaction_graph::ExtrinsicFor<PoolApi>>) = batch.into_iter()
+					.filter_map(|tx| match Decode::decode(&mut &tx.data[..]) {
+						Ok(xt) => Some((tx.submitted_at, xt)),
+						Err(e) => {
+							log::debug!(target: "txpool", "Failed to decode saved transaction: {:?}", e);
+							None
+						},
+					})
+					.unzip();
+				let source = if local { TransactionSource::Local } else { TransactionSource::External };
+				match pool.submit_at(&at, source, xts).await {
+					Ok(results) => for (result, submitted_at) in results.into_iter().zip(times) {
+						if let Ok(hash) = result {
+							persistence.note_restored(hash, submitted_at);
+							restored += 1;
+						}
+					},
+					Err(e) => log::warn!(
+						target: "txpool",
+						"Failed to restore saved transactions at {:?}: {:?}",
+						at,
+						e,
+					),
+				}
+			}
+
+			log::info!(target: "txpool", "Restored {} of {} saved transactions", restored, total);
+			restored
+		}
+	}
+}
+
+/// Report transactions evicted from the pool while enforcing its limits.
+fn report_evictions<PoolApi: ChainApi>(
+	pool: &sc_transaction_graph::Pool<PoolApi>,
+	metrics: &PrometheusMetrics,
+) {
+	let evicted = pool.validated_pool().take_evictions();
+	metrics.report(|metrics| {
+		metrics.evicted_sender_limit.inc_by(evicted.sender_limit);
+		metrics.evicted_pool_limit.inc_by(evicted.pool_limit);
+	});
+}
+
+/// Save the transactions of the pool, ready and future ones.
+fn persist_transactions<PoolApi: ChainApi>(
+	pool: &sc_transaction_graph::Pool<PoolApi>,
+	persistence: &Persistence<ExtrinsicHash<PoolApi>>,
+) {
+	let validated_pool = pool.validated_pool();
+	let transactions = validated_pool.ready()
+		.chain(validated_pool.futures())
+		.map(|tx| (tx.hash.clone(), tx.data.encode(), tx.source == TransactionSource::Local))
+		.collect::<Vec<_>>();
+	let count = transactions.len();
+
+	match persistence.save(transactions) {
+		Ok(()) => log::debug!(target: "txpool", "Saved {} transactions", count),
+		Err(e) => log::warn!(target: "txpool", "Failed to save transactions: {}", e),
+	}
+}
+
+impl<PoolApi, Block> Drop for BasicPool<PoolApi, Block>
+	where
+		Block: BlockT,
+		PoolApi: ChainApi<Block=Block>,
+{
+	fn drop(&mut self) {
+		if let Some(persistence) = &self.persistence {
+			persist_transactions(&self.pool, persistence);
+		}
+	}
 }
 
 impl<PoolApi, Block> TransactionPool for BasicPool<PoolApi, Block>
@@ -244,9 +366,19 @@ impl<PoolApi, Block> TransactionPool for BasicPool<PoolApi, Block>
 		let pool = self.pool.clone();
 		let at = *at;
 
+		let persistence = self.persistence.clone();
+		let metrics = self.metrics.clone();
+
 		self.metrics.report(|metrics| metrics.submitted_transactions.inc_by(xts.len() as u64));
 
-		async move { pool.submit_at(&at, source, xts).await }.boxed()
+		async move {
+			let results = pool.submit_at(&at, source, xts).await?;
+			report_evictions(&pool, &metrics);
+			if let Some(persistence) = persistence {
+				persistence.note_submitted(results.iter().filter_map(|result| result.as_ref().ok().cloned()));
+			}
+			Ok(results)
+		}.boxed()
 	}
 
 	fn submit_one(
@@ -258,9 +390,19 @@ impl<PoolApi, Block> TransactionPool for BasicPool<PoolApi, Block>
 		let pool = self.pool.clone();
 		let at = *at;
 
+		let persistence = self.persistence.clone();
+		let metrics = self.metrics.clone();
+
 		self.metrics.report(|metrics| metrics.submitted_transactions.inc());
 
-		async move { pool.submit_one(&at, source, xt).await }.boxed()
+		async move {
+			let hash = pool.submit_one(&at, source, xt).await?;
+			report_evictions(&pool, &metrics);
+			if let Some(persistence) = persistence {
+				persistence.note_submitted(Some(hash.clone()));
+			}
+			Ok(hash)
+		}.boxed()
 	}
 
 	fn submit_and_watch(
@@ -271,13 +413,18 @@ impl<PoolApi, Block> TransactionPool for BasicPool<PoolApi, Block>
 	) -> PoolFuture<Box<TransactionStatusStreamFor<Self>>, Self::Error> {
 		let at = *at;
 		let pool = self.pool.clone();
+		let persistence = self.persistence.clone();
+		let metrics = self.metrics.clone();
 
 		self.metrics.report(|metrics| metrics.submitted_transactions.inc());
 
 		async move {
-			pool.submit_and_watch(&at, source, xt)
-				.map(|result| result.map(|watcher| Box::new(watcher.into_stream()) as _))
-				.await
+			let watcher = pool.submit_and_watch(&at, source, xt).await?;
+			report_evictions(&pool, &metrics);
+			if let Some(persistence) = persistence {
+				persistence.note_submitted(Some(watcher.hash().clone()));
+			}
+			Ok(Box::new(watcher.into_stream()) as _)
 		}.boxed()
 	}
 
@@ -356,6 +503,7 @@ where
 	Block: BlockT,
 	Client: sp_api::ProvideRuntimeApi<Block>
 		+ sc_client_api::BlockBackend<Block>
+		+ sp_blockchain::HeaderBackend<Block>
 		+ sp_runtime::traits::BlockIdTo<Block>,
 	Client: sc_client_api::ExecutorProvider<Block> + Send + Sync + 'static,
 	Client::Api: sp_transaction_pool::runtime_api::TaggedTransactionQueue<Block>,
@@ -370,9 +518,14 @@ where
 	) -> Arc<Self> {
 		let pool_api = Arc::new(FullChainApi::new(client.clone(), prometheus));
 		let pool = Arc::new(Self::with_revalidation_type(
-			options, pool_api, prometheus, RevalidationType::Full, spawner
+			options, pool_api, prometheus, RevalidationType::Full, spawner.clone(),
 		));
 
+		if pool.persistence.is_some() {
+			let best = BlockId::Hash(sp_blockchain::HeaderBackend::info(&*client).best_hash);
+			spawner.spawn("txpool-restore", pool.restore_persisted(&best).map(drop).boxed());
+		}
+
 		// make transaction pool available for off-chain runtime calls.
 		client.execution_extensions().register_transaction_pool(&pool);
 
@@ -429,7 +582,11 @@ where
 			validity,
 		);
 
-		self.pool.validated_pool().submit(vec![validated]).remove(0)
+		let hash = self.pool.validated_pool().submit(vec![validated]).remove(0)?;
+		if let Some(persistence) = &self.persistence {
+			persistence.note_submitted(Some(hash.clone()));
+		}
+		Ok(hash)
 	}
 }
 
@@ -694,6 +851,7 @@ impl<PoolApi, Block> MaintainedTransactionPool for BasicPool<PoolApi, Block>
 			}
 			ChainEvent::Finalized { hash } => {
 				let pool = self.pool.clone();
+				let persistence = self.persistence.clone();
 				async move {
 					if let Err(e) = pool.validated_pool().on_block_finalized(hash).await {
 						log::warn!(
@@ -702,6 +860,10 @@ impl<PoolApi, Block> MaintainedTransactionPool for BasicPool<PoolApi, Block>
 							e, hash
 						)
 					}
+
+					if let Some(persistence) = persistence.filter(|persistence| persistence.should_save()) {
+						persist_transactions(&pool, &persistence);
+					}
 				}.boxed()
 			}
 		}
@@ -49,6 +49,8 @@ pub struct Metrics {
 	pub validations_invalid: Counter<U64>,
 	pub block_transactions_pruned: Counter<U64>,
 	pub block_transactions_resubmitted: Counter<U64>,
+	pub evicted_sender_limit: Counter<U64>,
+	pub evicted_pool_limit: Counter<U64>,
 }
 
 impl Metrics {
@@ -82,6 +84,20 @@ impl Metrics {
 				)?,
 				registry,
 			)?,
+			evicted_sender_limit: register(
+				Counter::new(
+					"sub_txpool_evicted_sender_limit",
+					"Total number of transactions evicted because their sender exceeded the per-sender limit",
+				)?,
+				registry,
+			)?,
+			evicted_pool_limit: register(
+				Counter::new(
+					"sub_txpool_evicted_pool_limit",
+					"Total number of transactions evicted because the pool limits were exceeded",
+				)?,
+				registry,
+			)?,
 		})
 	}
 }
@@ -0,0 +1,165 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Persistence of the pool contents across restarts.
+
+use codec::{Decode, Encode};
+use parking_lot::Mutex;
+use std::{
+	collections::HashMap, fs, hash::Hash, io, path::PathBuf,
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use wasm_timer::Instant;
+
+/// Version of the format of the file the transactions are saved to.
+const VERSION: u32 = 1;
+
+/// Minimal time between two saves triggered by the pool maintenance.
+const SAVE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// A transaction saved to disk.
+#[derive(Debug, Encode, Decode, PartialEq)]
+pub struct PersistedTransaction {
+	/// SCALE-encoded extrinsic.
+	pub data: Vec<u8>,
+	/// Whether the transaction was submitted by the node itself.
+	pub local: bool,
+	/// Time of the first submission, in milliseconds since the unix epoch.
+	pub submitted_at: u64,
+}
+
+/// Saves the transactions of the pool to a file and loads them back.
+pub struct Persistence<H> {
+	path: PathBuf,
+	submitted_at: Mutex<HashMap<H, u64>>,
+	last_save: Mutex<Option<Instant>>,
+}
+
+impl<H: Hash + Eq> Persistence<H> {
+	/// Create a new instance saving the transactions to the file at `path`.
+	pub fn new(path: PathBuf) -> Self {
+		Persistence {
+			path,
+			submitted_at: Default::default(),
+			last_save: Default::default(),
+		}
+	}
+
+	/// Note that the given transactions were submitted, unless they already were.
+	pub fn note_submitted(&self, hashes: impl IntoIterator<Item=H>) {
+		let now = unix_millis();
+		let mut submitted_at = self.submitted_at.lock();
+		for hash in hashes {
+			submitted_at.entry(hash).or_insert(now);
+		}
+	}
+
+	/// Note that a transaction saved by a previous run was imported again.
+	pub fn note_restored(&self, hash: H, submitted_at: u64) {
+		self.submitted_at.lock().insert(hash, submitted_at);
+	}
+
+	/// Returns true if the transactions weren't saved recently.
+	pub fn should_save(&self) -> bool {
+		self.last_save.lock().map_or(true, |last_save| last_save.elapsed() >= SAVE_INTERVAL)
+	}
+
+	/// Save the given transactions, as `(hash, encoded extrinsic, is local)` tuples, replacing
+	/// the ones saved before.
+	pub fn save(&self, transactions: impl IntoIterator<Item=(H, Vec<u8>, bool)>) -> io::Result<()> {
+		let now = unix_millis();
+		let transactions = {
+			let mut submitted_at = self.submitted_at.lock();
+			let mut retained = HashMap::with_capacity(submitted_at.len());
+			let transactions = transactions.into_iter()
+				.map(|(hash, data, local)| {
+					let time = submitted_at.get(&hash).copied().unwrap_or(now);
+					retained.insert(hash, time);
+					PersistedTransaction { data, local, submitted_at: time }
+				})
+				.collect::<Vec<_>>();
+			// forget the transactions that left the pool.
+			*submitted_at = retained;
+			transactions
+		};
+
+		if let Some(dir) = self.path.parent() {
+			fs::create_dir_all(dir)?;
+		}
+		// write to a temporary file first, so that a crash never leaves a partial file behind.
+		let tmp_path = self.path.with_extension("tmp");
+		fs::write(&tmp_path, (VERSION, transactions).encode())?;
+		fs::rename(&tmp_path, &self.path)?;
+
+		*self.last_save.lock() = Some(Instant::now());
+		Ok(())
+	}
+
+	/// Load the saved transactions, ordered by submission time.
+	pub fn load(&self) -> io::Result<Vec<PersistedTransaction>> {
+		let data = match fs::read(&self.path) {
+			Ok(data) => data,
+			Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+			Err(e) => return Err(e),
+		};
+		let (version, mut transactions) = <(u32, Vec<PersistedTransaction>)>::decode(&mut &data[..])
+			.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.what()))?;
+		if version != VERSION {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidData,
+				format!("Unsupported version {} of the saved transactions", version),
+			));
+		}
+
+		transactions.sort_by_key(|transaction| transaction.submitted_at);
+		Ok(transactions)
+	}
+}
+
+fn unix_millis() -> u64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map(|duration| duration.as_millis() as u64)
+		.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn save_and_load() {
+		let dir = tempfile::tempdir().unwrap();
+		let persistence = Persistence::<u32>::new(dir.path().join("txpool").join("transactions"));
+		assert_eq!(persistence.load().unwrap(), Vec::new());
+		assert!(persistence.should_save());
+
+		persistence.note_restored(1, 20);
+		persistence.note_restored(2, 10);
+		persistence.note_restored(3, 30);
+		persistence.save(vec![(1, vec![1], true), (2, vec![2], false)]).unwrap();
+		assert!(!persistence.should_save());
+
+		assert_eq!(persistence.load().unwrap(), vec![
+			PersistedTransaction { data: vec![2], local: false, submitted_at: 10 },
+			PersistedTransaction { data: vec![1], local: true, submitted_at: 20 },
+		]);
+		// transactions that left the pool are forgotten.
+		assert_eq!(persistence.submitted_at.lock().get(&3), None);
+	}
+}
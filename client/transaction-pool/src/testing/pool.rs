@@ -1038,3 +1038,41 @@ fn only_revalidate_on_best_block() {
 
 	assert_eq!(pool.status().ready, 1);
 }
+
+#[test]
+fn should_restore_persisted_transactions() {
+	let dir = tempfile::tempdir().unwrap();
+	let options = || txpool::Options {
+		persistence_path: Some(dir.path().join("transactions")),
+		..Default::default()
+	};
+	let api = Arc::new(TestApi::with_alice_nonce(209));
+
+	let (pool, _background, _notifier) = BasicPool::new_test_with_options(options(), api.clone());
+	block_on(pool.submit_one(&BlockId::number(0), TransactionSource::Local, uxt(Alice, 209)))
+		.expect("1. Imported");
+	block_on(pool.submit_one(&BlockId::number(0), SOURCE, uxt(Alice, 210))).expect("2. Imported");
+	block_on(pool.submit_one(&BlockId::number(0), SOURCE, uxt(Alice, 212))).expect("3. Imported");
+	assert_eq!(pool.status().ready, 2);
+	assert_eq!(pool.status().future, 1);
+
+	// the transactions are saved when the pool is dropped.
+	drop(pool);
+
+	// a pool without persistence doesn't restore anything.
+	let (pool, _background, _notifier) = BasicPool::new_test(api.clone());
+	assert_eq!(block_on(pool.restore_persisted(&BlockId::number(0))), 0);
+	assert_eq!(pool.status().ready, 0);
+
+	// the transactions are validated again, so the invalid one isn't restored.
+	api.add_invalid(&uxt(Alice, 212));
+	let (pool, _background, _notifier) = BasicPool::new_test_with_options(options(), api);
+	assert_eq!(block_on(pool.restore_persisted(&BlockId::number(0))), 2);
+	assert_eq!(pool.status().future, 0);
+
+	let ready: Vec<_> = pool.ready().map(|tx| (tx.data.transfer().nonce, tx.source)).collect();
+	assert_eq!(ready, vec![
+		(209, TransactionSource::Local),
+		(210, TransactionSource::External),
+	]);
+}
@@ -68,7 +68,7 @@
 //! * `balance` - Get the asset `id` balance of `who`.
 //! * `total_supply` - Get the total supply of an asset `id`.
 //!
-//! Please refer to the [`Module`](./struct.Module.html) struct for details on publicly available functions.
+//! Please refer to the [`Pallet`](./struct.Pallet.html) struct for details on publicly available functions.
 //!
 //! ## Usage
 //!
@@ -133,28 +133,37 @@
 // Ensure we're `no_std` when compiling for Wasm.
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use frame_support::{Parameter, decl_module, decl_event, decl_storage, decl_error, ensure};
-use sp_runtime::traits::{Member, AtLeast32Bit, AtLeast32BitUnsigned, Zero, StaticLookup};
-use frame_system::ensure_signed;
-use sp_runtime::traits::One;
+pub use pallet::*;
 
-/// The module configuration trait.
-pub trait Trait: frame_system::Trait {
-	/// The overarching event type.
-	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+	use sp_runtime::traits::{AtLeast32Bit, AtLeast32BitUnsigned, Zero, StaticLookup, One};
 
-	/// The units in which we record balances.
-	type Balance: Member + Parameter + AtLeast32BitUnsigned + Default + Copy;
+	/// The module configuration trait.
+	#[pallet::config]
+	pub trait Trait: frame_system::Trait {
+		/// The overarching event type.
+		type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
 
-	/// The arithmetic type of asset identifier.
-	type AssetId: Parameter + AtLeast32Bit + Default + Copy;
-}
+		/// The units in which we record balances.
+		type Balance: Member + Parameter + AtLeast32BitUnsigned + Default + Copy;
+
+		/// The arithmetic type of asset identifier.
+		type AssetId: Parameter + AtLeast32Bit + Default + Copy;
+	}
 
-decl_module! {
-	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
-		type Error = Error<T>;
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_prefix(Assets)]
+	pub struct Pallet<T>(PhantomData<T>);
 
-		fn deposit_event() = default;
+	#[pallet::hooks]
+	impl<T: Trait> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Trait> Pallet<T> {
 		/// Issue a new class of fungible assets. There are, and will only ever be, `total`
 		/// such assets and they'll all belong to the `origin` initially. It will have an
 		/// identifier `AssetId` instance: this will be specified in the `Issued` event.
@@ -165,8 +174,11 @@ decl_module! {
 		/// - 2 storage writes (condec `O(1)`).
 		/// - 1 event.
 		/// # </weight>
-		#[weight = 0]
-		fn issue(origin, #[compact] total: T::Balance) {
+		#[pallet::weight(0)]
+		pub fn issue(
+			origin: OriginFor<T>,
+			#[pallet::compact] total: T::Balance,
+		) -> DispatchResultWithPostInfo {
 			let origin = ensure_signed(origin)?;
 
 			let id = Self::next_asset_id();
@@ -175,7 +187,8 @@ decl_module! {
 			<Balances<T>>::insert((id, &origin), total);
 			<TotalSupply<T>>::insert(id, total);
 
-			Self::deposit_event(RawEvent::Issued(id, origin, total));
+			Self::deposit_event(Event::Issued(id, origin, total));
+			Ok(().into())
 		}
 
 		/// Move some assets from one holder to another.
@@ -186,12 +199,13 @@ decl_module! {
 		/// - 2 storage mutations (codec `O(1)`).
 		/// - 1 event.
 		/// # </weight>
-		#[weight = 0]
-		fn transfer(origin,
-			#[compact] id: T::AssetId,
+		#[pallet::weight(0)]
+		pub fn transfer(
+			origin: OriginFor<T>,
+			#[pallet::compact] id: T::AssetId,
 			target: <T::Lookup as StaticLookup>::Source,
-			#[compact] amount: T::Balance
-		) {
+			#[pallet::compact] amount: T::Balance,
+		) -> DispatchResultWithPostInfo {
 			let origin = ensure_signed(origin)?;
 			let origin_account = (id, origin.clone());
 			let origin_balance = <Balances<T>>::get(&origin_account);
@@ -199,9 +213,10 @@ decl_module! {
 			ensure!(!amount.is_zero(), Error::<T>::AmountZero);
 			ensure!(origin_balance >= amount, Error::<T>::BalanceLow);
 
-			Self::deposit_event(RawEvent::Transferred(id, origin, target.clone(), amount));
+			Self::deposit_event(Event::Transferred(id, origin, target.clone(), amount));
 			<Balances<T>>::insert(origin_account, origin_balance - amount);
 			<Balances<T>>::mutate((id, target), |balance| *balance += amount);
+			Ok(().into())
 		}
 
 		/// Destroy any assets of `id` owned by `origin`.
@@ -212,35 +227,35 @@ decl_module! {
 		/// - 1 storage deletion (codec `O(1)`).
 		/// - 1 event.
 		/// # </weight>
-		#[weight = 0]
-		fn destroy(origin, #[compact] id: T::AssetId) {
+		#[pallet::weight(0)]
+		pub fn destroy(
+			origin: OriginFor<T>,
+			#[pallet::compact] id: T::AssetId,
+		) -> DispatchResultWithPostInfo {
 			let origin = ensure_signed(origin)?;
 			let balance = <Balances<T>>::take((id, &origin));
 			ensure!(!balance.is_zero(), Error::<T>::BalanceZero);
 
 			<TotalSupply<T>>::mutate(id, |total_supply| *total_supply -= balance);
-			Self::deposit_event(RawEvent::Destroyed(id, origin, balance));
+			Self::deposit_event(Event::Destroyed(id, origin, balance));
+			Ok(().into())
 		}
 	}
-}
 
-decl_event! {
-	pub enum Event<T> where
-		<T as frame_system::Trait>::AccountId,
-		<T as Trait>::Balance,
-		<T as Trait>::AssetId,
-	{
+	#[pallet::event]
+	#[pallet::metadata(T::AccountId = "AccountId", T::Balance = "Balance", T::AssetId = "AssetId")]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Trait> {
 		/// Some assets were issued. \[asset_id, owner, total_supply\]
-		Issued(AssetId, AccountId, Balance),
+		Issued(T::AssetId, T::AccountId, T::Balance),
 		/// Some assets were transferred. \[asset_id, from, to, amount\]
-		Transferred(AssetId, AccountId, AccountId, Balance),
+		Transferred(T::AssetId, T::AccountId, T::AccountId, T::Balance),
 		/// Some assets were destroyed. \[asset_id, owner, balance\]
-		Destroyed(AssetId, AccountId, Balance),
+		Destroyed(T::AssetId, T::AccountId, T::Balance),
 	}
-}
 
-decl_error! {
-	pub enum Error for Module<T: Trait> {
+	#[pallet::error]
+	pub enum Error<T> {
 		/// Transfer amount should be non-zero
 		AmountZero,
 		/// Account balance must be greater than or equal to the transfer amount
@@ -248,23 +263,27 @@ decl_error! {
 		/// Balance should be non-zero
 		BalanceZero,
 	}
-}
 
-decl_storage! {
-	trait Store for Module<T: Trait> as Assets {
-		/// The number of units of assets held by any given account.
-		Balances: map hasher(blake2_128_concat) (T::AssetId, T::AccountId) => T::Balance;
-		/// The next asset identifier up for grabs.
-		NextAssetId get(fn next_asset_id): T::AssetId;
-		/// The total unit supply of an asset.
-		///
-		/// TWOX-NOTE: `AssetId` is trusted, so this is safe.
-		TotalSupply: map hasher(twox_64_concat) T::AssetId => T::Balance;
-	}
+	/// The number of units of assets held by any given account.
+	#[pallet::storage]
+	pub(super) type Balances<T: Trait> =
+		StorageMap<_, Blake2_128Concat, (T::AssetId, T::AccountId), T::Balance, ValueQuery>;
+
+	/// The next asset identifier up for grabs.
+	#[pallet::storage]
+	#[pallet::getter(fn next_asset_id)]
+	pub(super) type NextAssetId<T: Trait> = StorageValue<_, T::AssetId, ValueQuery>;
+
+	/// The total unit supply of an asset.
+	///
+	/// TWOX-NOTE: `AssetId` is trusted, so this is safe.
+	#[pallet::storage]
+	pub(super) type TotalSupply<T: Trait> =
+		StorageMap<_, Twox64Concat, T::AssetId, T::Balance, ValueQuery>;
 }
 
 // The main implementation block for the module.
-impl<T: Trait> Module<T> {
+impl<T: Trait> Pallet<T> {
 	// Public immutables
 
 	/// Get the asset `id` balance of `who`.
@@ -18,6 +18,8 @@
 //! Interfaces, types and utils for benchmarking a FRAME runtime.
 
 use codec::{Encode, Decode};
+#[cfg(feature = "std")]
+use serde::{Serialize, Deserialize};
 use sp_std::{vec::Vec, prelude::Box};
 use sp_io::hashing::blake2_256;
 use sp_runtime::RuntimeString;
@@ -25,6 +27,7 @@ use sp_storage::TrackedStorageKey;
 
 /// An alphabet of possible parameters to use for benchmarking.
 #[derive(Encode, Decode, Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 #[allow(missing_docs)]
 #[allow(non_camel_case_types)]
 pub enum BenchmarkParameter {
@@ -40,10 +43,13 @@ impl std::fmt::Display for BenchmarkParameter {
 
 /// The results of a single of benchmark.
 #[derive(Encode, Decode, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub struct BenchmarkBatch {
 	/// The pallet containing this benchmark.
+	#[cfg_attr(feature = "std", serde(with = "serde_as_str"))]
 	pub pallet: Vec<u8>,
 	/// The extrinsic (or benchmark name) of this benchmark.
+	#[cfg_attr(feature = "std", serde(with = "serde_as_str"))]
 	pub benchmark: Vec<u8>,
 	/// The results from this benchmark.
 	pub results: Vec<BenchmarkResults>,
@@ -53,6 +59,7 @@ pub struct BenchmarkBatch {
 /// Contains duration of the function call in nanoseconds along with the benchmark parameters
 /// used for that benchmark result.
 #[derive(Encode, Decode, Default, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub struct BenchmarkResults {
 	pub components: Vec<(BenchmarkParameter, u32)>,
 	pub extrinsic_time: u128,
@@ -63,6 +70,21 @@ pub struct BenchmarkResults {
 	pub repeat_writes: u32,
 }
 
+/// (De)serializes the UTF-8 names of pallets and benchmarks as strings.
+#[cfg(feature = "std")]
+mod serde_as_str {
+	use serde::{Deserialize, Deserializer, Serializer, ser::Error};
+
+	pub fn serialize<S: Serializer>(value: &Vec<u8>, serializer: S) -> Result<S::Ok, S::Error> {
+		let s = std::str::from_utf8(value).map_err(S::Error::custom)?;
+		serializer.serialize_str(s)
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+		Ok(String::deserialize(deserializer)?.into_bytes())
+	}
+}
+
 /// Configuration used to setup and run runtime benchmarks.
 #[derive(Encode, Decode, Default, Clone, PartialEq, Debug)]
 pub struct BenchmarkConfig {
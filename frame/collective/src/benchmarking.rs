@@ -633,6 +633,30 @@ benchmarks_instance! {
 		assert_eq!(Collective::<T, _>::proposals().len(), (p - 1) as usize);
 		assert_last_event::<T, I>(RawEvent::Disapproved(last_hash).into());
 	}
+
+	// Cost of tallying `m` voters with the configured `VoteCounting`.
+	count_votes {
+		let m in 1 .. T::MaxMembers::get();
+
+		// Construct `members`, the last one being prime. Weighted modes look up the weight of
+		// every member, so each is given the most expensive weight to look up.
+		let mut members = vec![];
+		for i in 0 .. m {
+			let member = account("member", i, SEED);
+			T::VoteCounting::prepare_voter(&member);
+			members.push(member);
+		}
+		let prime = members.last().cloned();
+		Collective::<T, _>::set_members(
+			SystemOrigin::Root.into(),
+			members.clone(),
+			prime.clone(),
+			T::MaxMembers::get(),
+		)?;
+
+	}: {
+		T::VoteCounting::count(&members, prime.as_ref());
+	}
 }
 
 #[cfg(test)]
@@ -710,4 +734,11 @@ mod tests {
 			assert_ok!(test_benchmark_disapprove_proposal::<Test>());
 		});
 	}
+
+	#[test]
+	fn count_votes() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_count_votes::<Test>());
+		});
+	}
 }
@@ -94,4 +94,8 @@ impl crate::WeightInfo for () {
 			.saturating_add(DbWeight::get().reads(1 as Weight))
 			.saturating_add(DbWeight::get().writes(3 as Weight))
 	}
+	fn count_votes(m: u32, ) -> Weight {
+		(1265000 as Weight)
+			.saturating_add((97000 as Weight).saturating_mul(m as Weight))
+	}
 }
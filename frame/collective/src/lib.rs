@@ -38,14 +38,26 @@
 //! abstentions and the proposal is executed if there are enough approvals counting the new votes.
 //!
 //! If there are not, or if no prime is set, then the motion is dropped without being executed.
+//!
+//! How votes are counted is set through `VoteCounting`. With `OneMemberOneVote`, the default,
+//! every member has one vote and thresholds are numbers of members. The weighted modes instead
+//! give each member a vote weight, and thresholds and tallies are expressed in vote weight:
+//!
+//! - `PrimeWeighted` counts the vote of the prime member a configurable number of times.
+//! - `StakeWeighted` weighs votes by a `MemberWeights` source, such as the backing stake of each
+//!   member.
+//! - `Quadratic` weighs votes by the square root of a `MemberWeights` source.
+//!
+//! Motions of a collective using a weighted mode are dispatched from the `Weighted` origin, which
+//! can be checked with `EnsureWeightedProportionMoreThan` and `EnsureWeightedProportionAtLeast`.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![recursion_limit="128"]
 
-use sp_std::{prelude::*, result};
-use sp_core::u32_trait::Value as U32;
+use sp_std::{cmp::Ordering, prelude::*, result};
+use sp_core::{U256, u32_trait::Value as U32};
 use sp_io::storage;
-use sp_runtime::{RuntimeDebug, traits::Hash};
+use sp_runtime::{RuntimeDebug, traits::{Hash, IntegerSquareRoot, SaturatedConversion}};
 
 use frame_support::{
 	codec::{Decode, Encode},
@@ -55,6 +67,7 @@ use frame_support::{
 		PostDispatchInfo,
 	},
 	ensure,
+	storage::StoragePrefixedMap,
 	traits::{ChangeMembers, EnsureOrigin, Get, InitializeMembers},
 	weights::{DispatchClass, GetDispatchInfo, Weight},
 };
@@ -74,19 +87,120 @@ pub type ProposalIndex = u32;
 /// vote exactly once, therefore also the number of votes for any given motion.
 pub type MemberCount = u32;
 
+/// The weight of a vote, or of a number of votes.
+///
+/// With `OneMemberOneVote` this is a number of members. It is balance-sized so that votes can be
+/// weighted by stake.
+pub type MemberWeight = u128;
+
+/// Source of the vote weight of the members of a collective.
+pub trait MemberWeights<AccountId> {
+	/// The vote weight of `who`.
+	fn member_weight(who: &AccountId) -> MemberWeight;
+
+	/// Give `who` the most expensive vote weight to look up, for benchmarking.
+	#[cfg(feature = "runtime-benchmarks")]
+	fn set_member_weight(_who: &AccountId) {}
+}
+
+/// The way in which the votes of the members of a collective are counted.
+pub trait CountVotes<AccountId> {
+	/// Whether the votes are weighted.
+	///
+	/// Motions of a collective with weighted votes are dispatched from the `Weighted` origin
+	/// rather than the `Members` origin.
+	fn is_weighted() -> bool;
+
+	/// The total vote weight of `voters`, given the current prime member.
+	fn count(voters: &[AccountId], prime: Option<&AccountId>) -> MemberWeight;
+
+	/// Make counting the vote of `who` as expensive as possible, for benchmarking.
+	#[cfg(feature = "runtime-benchmarks")]
+	fn prepare_voter(_who: &AccountId) {}
+}
+
+/// Every member has exactly one vote. This is the classic mode of the collective.
+pub struct OneMemberOneVote;
+
+impl<AccountId> CountVotes<AccountId> for OneMemberOneVote {
+	fn is_weighted() -> bool {
+		false
+	}
+
+	fn count(voters: &[AccountId], _prime: Option<&AccountId>) -> MemberWeight {
+		voters.len() as MemberWeight
+	}
+}
+
+/// Every member has one vote, except for the prime member whose vote counts `W` times.
+pub struct PrimeWeighted<W>(sp_std::marker::PhantomData<W>);
+
+impl<AccountId: PartialEq, W: Get<MemberWeight>> CountVotes<AccountId> for PrimeWeighted<W> {
+	fn is_weighted() -> bool {
+		true
+	}
+
+	fn count(voters: &[AccountId], prime: Option<&AccountId>) -> MemberWeight {
+		voters.iter()
+			.map(|v| if Some(v) == prime { W::get() } else { 1 })
+			.fold(0, |acc: MemberWeight, w| acc.saturating_add(w))
+	}
+}
+
+/// The vote of every member is weighted by `S`, e.g. by their backing stake.
+pub struct StakeWeighted<S>(sp_std::marker::PhantomData<S>);
+
+impl<AccountId, S: MemberWeights<AccountId>> CountVotes<AccountId> for StakeWeighted<S> {
+	fn is_weighted() -> bool {
+		true
+	}
+
+	fn count(voters: &[AccountId], _prime: Option<&AccountId>) -> MemberWeight {
+		voters.iter()
+			.map(S::member_weight)
+			.fold(0, |acc: MemberWeight, w| acc.saturating_add(w))
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn prepare_voter(who: &AccountId) {
+		S::set_member_weight(who);
+	}
+}
+
+/// The vote of every member is weighted by the square root of their weight given by `S`. This
+/// dampens the influence of the most heavily weighted members.
+pub struct Quadratic<S>(sp_std::marker::PhantomData<S>);
+
+impl<AccountId, S: MemberWeights<AccountId>> CountVotes<AccountId> for Quadratic<S> {
+	fn is_weighted() -> bool {
+		true
+	}
+
+	fn count(voters: &[AccountId], _prime: Option<&AccountId>) -> MemberWeight {
+		voters.iter()
+			.map(|v| S::member_weight(v).integer_sqrt())
+			.fold(0, |acc: MemberWeight, w| acc.saturating_add(w))
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn prepare_voter(who: &AccountId) {
+		S::set_member_weight(who);
+	}
+}
+
 /// Default voting strategy when a member is inactive.
 pub trait DefaultVote {
 	/// Get the default voting strategy, given:
 	///
 	/// - Whether the prime member voted Aye.
-	/// - Raw number of yes votes.
-	/// - Raw number of no votes.
-	/// - Total number of member count.
+	/// - Yes votes, as counted by `VoteCounting`.
+	/// - No votes, as counted by `VoteCounting`.
+	/// - Total votes of all members, as counted by `VoteCounting`.
 	fn default_vote(
 		prime_vote: Option<bool>,
-		yes_votes: MemberCount,
-		no_votes: MemberCount,
-		len: MemberCount,
+		yes_votes: MemberWeight,
+		no_votes: MemberWeight,
+		len: MemberWeight,
 	) -> bool;
 }
 
@@ -96,9 +210,9 @@ pub struct PrimeDefaultVote;
 impl DefaultVote for PrimeDefaultVote {
 	fn default_vote(
 		prime_vote: Option<bool>,
-		_yes_votes: MemberCount,
-		_no_votes: MemberCount,
-		_len: MemberCount,
+		_yes_votes: MemberWeight,
+		_no_votes: MemberWeight,
+		_len: MemberWeight,
 	) -> bool {
 		prime_vote.unwrap_or(false)
 	}
@@ -111,11 +225,11 @@ pub struct MoreThanMajorityThenPrimeDefaultVote;
 impl DefaultVote for MoreThanMajorityThenPrimeDefaultVote {
 	fn default_vote(
 		prime_vote: Option<bool>,
-		yes_votes: MemberCount,
-		_no_votes: MemberCount,
-		len: MemberCount,
+		yes_votes: MemberWeight,
+		_no_votes: MemberWeight,
+		len: MemberWeight,
 	) -> bool {
-		let more_than_majority = yes_votes * 2 > len;
+		let more_than_majority = yes_votes.saturating_mul(2) > len;
 		more_than_majority || prime_vote.unwrap_or(false)
 	}
 }
@@ -131,6 +245,7 @@ pub trait WeightInfo {
 	fn close_disapproved(m: u32, p: u32, ) -> Weight;
 	fn close_approved(b: u32, m: u32, p: u32, ) -> Weight;
 	fn disapprove_proposal(p: u32, ) -> Weight;
+	fn count_votes(m: u32, ) -> Weight;
 }
 
 pub trait Trait<I: Instance=DefaultInstance>: frame_system::Trait {
@@ -162,6 +277,9 @@ pub trait Trait<I: Instance=DefaultInstance>: frame_system::Trait {
 	/// Default vote strategy of this collective.
 	type DefaultVote: DefaultVote;
 
+	/// The way votes are counted. Use `OneMemberOneVote` for one vote per member.
+	type VoteCounting: CountVotes<Self::AccountId>;
+
 	/// Weight information for extrinsics in this pallet.
 	type WeightInfo: WeightInfo;
 }
@@ -173,6 +291,9 @@ pub enum RawOrigin<AccountId, I> {
 	Members(MemberCount, MemberCount),
 	/// It has been condoned by a single member of the collective.
 	Member(AccountId),
+	/// It has been condoned by a given vote weight of the collective from a given total vote
+	/// weight. Only used by collectives counting weighted votes.
+	Weighted(MemberWeight, MemberWeight),
 	/// Dummy to manage the fact we have instancing.
 	_Phantom(sp_std::marker::PhantomData<I>),
 }
@@ -185,8 +306,9 @@ pub type Origin<T, I=DefaultInstance> = RawOrigin<<T as frame_system::Trait>::Ac
 pub struct Votes<AccountId, BlockNumber> {
 	/// The proposal's unique index.
 	index: ProposalIndex,
-	/// The number of approval votes that are needed to pass the motion.
-	threshold: MemberCount,
+	/// The number of approval votes that are needed to pass the motion, as counted by
+	/// `VoteCounting`.
+	threshold: MemberWeight,
 	/// The current set of voters that approved it.
 	ayes: Vec<AccountId>,
 	/// The current set of voters that rejected it.
@@ -195,6 +317,31 @@ pub struct Votes<AccountId, BlockNumber> {
 	end: BlockNumber,
 }
 
+/// `Votes` as stored before `MemberWeight` was balance-sized.
+#[derive(Decode)]
+struct VotesV1<AccountId, BlockNumber> {
+	index: ProposalIndex,
+	threshold: MemberCount,
+	ayes: Vec<AccountId>,
+	nays: Vec<AccountId>,
+	end: BlockNumber,
+}
+
+// A value placed in storage that represents the current version of the Collective storage.
+// This value is used by the `on_runtime_upgrade` logic to determine whether we run
+// storage migration logic.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+enum Releases {
+	V1,
+	V2,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		Releases::V1
+	}
+}
+
 decl_storage! {
 	trait Store for Module<T: Trait<I>, I: Instance=DefaultInstance> as Collective {
 		/// The hashes of the active proposals.
@@ -211,6 +358,11 @@ decl_storage! {
 		pub Members get(fn members): Vec<T::AccountId>;
 		/// The prime member that helps determine the default vote behavior in case of absentations.
 		pub Prime get(fn prime): Option<T::AccountId>;
+
+		/// Storage version of the pallet.
+		///
+		/// New networks start with last version.
+		StorageVersion build(|_: &GenesisConfig<T, I>| Releases::V2): Releases;
 	}
 	add_extra_genesis {
 		config(phantom): sp_std::marker::PhantomData<I>;
@@ -225,13 +377,13 @@ decl_event! {
 		<T as frame_system::Trait>::AccountId,
 	{
 		/// A motion (given hash) has been proposed (by given account) with a threshold (given
-		/// `MemberCount`).
+		/// `MemberWeight`).
 		/// \[account, proposal_index, proposal_hash, threshold\]
-		Proposed(AccountId, ProposalIndex, Hash, MemberCount),
+		Proposed(AccountId, ProposalIndex, Hash, MemberWeight),
 		/// A motion (given hash) has been voted on by given account, leaving
-		/// a tally (yes votes and no votes given respectively as `MemberCount`).
+		/// a tally (yes votes and no votes given respectively as `MemberWeight`).
 		/// \[account, proposal_hash, voted, yes, no\]
-		Voted(AccountId, Hash, bool, MemberCount, MemberCount),
+		Voted(AccountId, Hash, bool, MemberWeight, MemberWeight),
 		/// A motion was approved by the required threshold.
 		/// \[proposal_hash\]
 		Approved(Hash),
@@ -246,7 +398,7 @@ decl_event! {
 		MemberExecuted(Hash, DispatchResult),
 		/// A proposal was closed because its threshold was reached or after its duration was up.
 		/// \[proposal_hash, yes, no\]
-		Closed(Hash, MemberCount, MemberCount),
+		Closed(Hash, MemberWeight, MemberWeight),
 	}
 }
 
@@ -293,6 +445,13 @@ decl_module! {
 
 		fn deposit_event() = default;
 
+		fn on_runtime_upgrade() -> Weight {
+			match Self::migrate_v1_to_v2() {
+				Some(motions) => T::DbWeight::get().reads_writes(motions + 2, motions + 1),
+				None => T::DbWeight::get().reads(1),
+			}
+		}
+
 		/// Set the collective's membership.
 		///
 		/// - `new_members`: The new member list. Be nice to the chain and provide it sorted.
@@ -405,7 +564,7 @@ decl_module! {
 		/// Requires the sender to be member.
 		///
 		/// `threshold` determines whether `proposal` is executed directly (`threshold < 2`)
-		/// or put up for voting.
+		/// or put up for voting. It is expressed in the units of `VoteCounting`.
 		///
 		/// # <weight>
 		/// ## Weight
@@ -432,7 +591,9 @@ decl_module! {
 				T::WeightInfo::propose_execute(
 					*length_bound, // B
 					T::MaxMembers::get(), // M
-				).saturating_add(proposal.get_dispatch_info().weight) // P1
+				)
+				.saturating_add(T::WeightInfo::count_votes(T::MaxMembers::get()))
+				.saturating_add(proposal.get_dispatch_info().weight) // P1
 			} else {
 				T::WeightInfo::propose_proposed(
 					*length_bound, // B
@@ -443,7 +604,7 @@ decl_module! {
 			DispatchClass::Operational
 		)]
 		fn propose(origin,
-			#[compact] threshold: MemberCount,
+			#[compact] threshold: MemberWeight,
 			proposal: Box<<T as Trait<I>>::Proposal>,
 			#[compact] length_bound: u32
 		) -> DispatchResultWithPostInfo {
@@ -457,8 +618,10 @@ decl_module! {
 			ensure!(!<ProposalOf<T, I>>::contains_key(proposal_hash), Error::<T, I>::DuplicateProposal);
 
 			if threshold < 2 {
-				let seats = Self::members().len() as MemberCount;
-				let result = proposal.dispatch(RawOrigin::Members(1, seats).into());
+				let prime = Self::prime();
+				let approvals = T::VoteCounting::count(sp_std::slice::from_ref(&who), prime.as_ref());
+				let total = T::VoteCounting::count(&members, prime.as_ref());
+				let result = proposal.dispatch(Self::approval_origin(approvals, total).into());
 				Self::deposit_event(
 					RawEvent::Executed(proposal_hash, result.map(|_| ()).map_err(|e| e.error))
 				);
@@ -467,7 +630,9 @@ decl_module! {
 					T::WeightInfo::propose_execute(
 						proposal_len as u32, // B
 						members.len() as u32, // M
-					).saturating_add(w) // P1
+					)
+					.saturating_add(T::WeightInfo::count_votes(members.len() as u32))
+					.saturating_add(w) // P1
 				}).into())
 			} else {
 				let active_proposals =
@@ -505,11 +670,14 @@ decl_module! {
 		/// - `O(M)` where `M` is members-count (code- and governance-bounded)
 		/// - DB:
 		///   - 1 storage read `Members` (codec `O(M)`)
+		///   - 1 storage read `Prime` (codec `O(1)`)
 		///   - 1 storage mutation `Voting` (codec `O(M)`)
+		///   - any reads done by `VoteCounting` for the voters (`O(M)`)
 		/// - 1 event
 		/// # </weight>
 		#[weight = (
-			T::WeightInfo::vote(T::MaxMembers::get()),
+			T::WeightInfo::vote(T::MaxMembers::get())
+				.saturating_add(T::WeightInfo::count_votes(T::MaxMembers::get())),
 			DispatchClass::Operational
 		)]
 		fn vote(origin,
@@ -547,13 +715,18 @@ decl_module! {
 				}
 			}
 
-			let yes_votes = voting.ayes.len() as MemberCount;
-			let no_votes = voting.nays.len() as MemberCount;
+			let prime = Self::prime();
+			let yes_votes = T::VoteCounting::count(&voting.ayes, prime.as_ref());
+			let no_votes = T::VoteCounting::count(&voting.nays, prime.as_ref());
+			let voters = (voting.ayes.len() + voting.nays.len()) as u32;
 			Self::deposit_event(RawEvent::Voted(who, proposal, approve, yes_votes, no_votes));
 
 			Voting::<T, I>::insert(&proposal, voting);
 
-			Ok(Some(T::WeightInfo::vote(members.len() as u32)).into())
+			Ok(Some(
+				T::WeightInfo::vote(members.len() as u32)
+					.saturating_add(T::WeightInfo::count_votes(voters))
+			).into())
 		}
 
 		/// Close a vote that is either approved, disapproved or whose voting period has ended.
@@ -580,6 +753,7 @@ decl_module! {
 		/// - DB:
 		///  - 2 storage reads (`Members`: codec `O(M)`, `Prime`: codec `O(1)`)
 		///  - 3 mutations (`Voting`: codec `O(M)`, `ProposalOf`: codec `O(B)`, `Proposals`: codec `O(P2)`)
+		///  - any reads done by `VoteCounting` for the voters and the members (`O(M)`)
 		///  - any mutations done while executing `proposal` (`P1`)
 		/// - up to 3 events
 		/// # </weight>
@@ -593,6 +767,7 @@ decl_module! {
 					.max(T::WeightInfo::close_early_disapproved(m, p2))
 					.max(T::WeightInfo::close_approved(b, m, p2))
 					.max(T::WeightInfo::close_disapproved(m, p2))
+					.saturating_add(T::WeightInfo::count_votes(m.saturating_mul(2)))
 					.saturating_add(p1)
 			},
 			DispatchClass::Operational
@@ -608,11 +783,17 @@ decl_module! {
 			let voting = Self::voting(&proposal_hash).ok_or(Error::<T, I>::ProposalMissing)?;
 			ensure!(voting.index == index, Error::<T, I>::WrongIndex);
 
-			let mut no_votes = voting.nays.len() as MemberCount;
-			let mut yes_votes = voting.ayes.len() as MemberCount;
-			let seats = Self::members().len() as MemberCount;
+			let prime = Self::prime();
+			let members = Self::members();
+			let mut no_votes = T::VoteCounting::count(&voting.nays, prime.as_ref());
+			let mut yes_votes = T::VoteCounting::count(&voting.ayes, prime.as_ref());
+			let total = T::VoteCounting::count(&members, prime.as_ref());
+			let seats = members.len() as MemberCount;
+			let count_weight = T::WeightInfo::count_votes(
+				(voting.ayes.len() + voting.nays.len() + members.len()) as u32
+			);
 			let approved = yes_votes >= voting.threshold;
-			let disapproved = seats.saturating_sub(no_votes) < voting.threshold;
+			let disapproved = total.saturating_sub(no_votes) < voting.threshold;
 			// Allow (dis-)approving the proposal as soon as there are enough votes.
 			if approved {
 				let (proposal, len) = Self::validate_and_get_proposal(
@@ -622,9 +803,10 @@ decl_module! {
 				)?;
 				Self::deposit_event(RawEvent::Closed(proposal_hash, yes_votes, no_votes));
 				let (proposal_weight, proposal_count) =
-					Self::do_approve_proposal(seats, voting, proposal_hash, proposal);
+					Self::do_approve_proposal(total, voting, proposal_hash, proposal);
 				return Ok(Some(
 					T::WeightInfo::close_early_approved(len as u32, seats, proposal_count)
+						.saturating_add(count_weight)
 						.saturating_add(proposal_weight)
 				).into());
 			} else if disapproved {
@@ -632,18 +814,19 @@ decl_module! {
 				let proposal_count = Self::do_disapprove_proposal(proposal_hash);
 				return Ok(Some(
 					T::WeightInfo::close_early_disapproved(seats, proposal_count)
+						.saturating_add(count_weight)
 				).into());
 			}
 
 			// Only allow actual closing of the proposal after the voting period has ended.
 			ensure!(system::Module::<T>::block_number() >= voting.end, Error::<T, I>::TooEarly);
 
-			let prime_vote = Self::prime().map(|who| voting.ayes.iter().any(|a| a == &who));
+			let prime_vote = prime.map(|who| voting.ayes.iter().any(|a| a == &who));
 
 			// default voting strategy.
-			let default = T::DefaultVote::default_vote(prime_vote, yes_votes, no_votes, seats);
+			let default = T::DefaultVote::default_vote(prime_vote, yes_votes, no_votes, total);
 
-			let abstentions = seats - (yes_votes + no_votes);
+			let abstentions = total.saturating_sub(yes_votes.saturating_add(no_votes));
 			match default {
 				true => yes_votes += abstentions,
 				false => no_votes += abstentions,
@@ -658,9 +841,10 @@ decl_module! {
 				)?;
 				Self::deposit_event(RawEvent::Closed(proposal_hash, yes_votes, no_votes));
 				let (proposal_weight, proposal_count) =
-					Self::do_approve_proposal(seats, voting, proposal_hash, proposal);
+					Self::do_approve_proposal(total, voting, proposal_hash, proposal);
 				return Ok(Some(
 					T::WeightInfo::close_approved(len as u32, seats, proposal_count)
+						.saturating_add(count_weight)
 						.saturating_add(proposal_weight)
 				).into());
 			} else {
@@ -668,6 +852,7 @@ decl_module! {
 				let proposal_count = Self::do_disapprove_proposal(proposal_hash);
 				return Ok(Some(
 					T::WeightInfo::close_disapproved(seats, proposal_count)
+						.saturating_add(count_weight)
 				).into());
 			}
 		}
@@ -702,6 +887,44 @@ impl<T: Trait<I>, I: Instance> Module<T, I> {
 		Self::members().contains(who)
 	}
 
+	/// The origin of a motion passed with `approvals` out of `total` votes, as counted by
+	/// `VoteCounting`.
+	fn approval_origin(approvals: MemberWeight, total: MemberWeight) -> RawOrigin<T::AccountId, I> {
+		if T::VoteCounting::is_weighted() {
+			RawOrigin::Weighted(approvals, total)
+		} else {
+			RawOrigin::Members(approvals.saturated_into(), total.saturated_into())
+		}
+	}
+
+	/// Migrate storage format from V1 to V2, widening the thresholds of the ongoing motions to
+	/// `MemberWeight`.
+	///
+	/// The thresholds were numbers of members, they are rescaled to the same proportion of the
+	/// total vote weight of the members, as counted by `VoteCounting`.
+	/// Return the number of migrated motions if migration is performed.
+	pub fn migrate_v1_to_v2() -> Option<u64> {
+		if StorageVersion::<I>::get() != Releases::V1 {
+			return None;
+		}
+		StorageVersion::<I>::put(Releases::V2);
+
+		let members = Self::members();
+		let total = T::VoteCounting::count(&members, Self::prime().as_ref());
+		let seats = members.len() as MemberWeight;
+		let motions = Self::proposals().len() as u64;
+		Voting::<T, I>::translate_values::<VotesV1<T::AccountId, T::BlockNumber>, _>(|votes| {
+			Some(Votes {
+				index: votes.index,
+				threshold: rescale_threshold(votes.threshold, total, seats),
+				ayes: votes.ayes,
+				nays: votes.nays,
+				end: votes.end,
+			})
+		});
+		Some(motions)
+	}
+
 	/// Ensure that the right proposal bounds were passed and get the proposal from storage.
 	///
 	/// Checks the length in storage via `storage::read` which adds an extra `size_of::<u32>() == 4`
@@ -737,7 +960,7 @@ impl<T: Trait<I>, I: Instance> Module<T, I> {
 	/// Computation and i/o `O(P)` where:
 	/// - `P` is number of active proposals
 	fn do_approve_proposal(
-		seats: MemberCount,
+		total: MemberWeight,
 		voting: Votes<T::AccountId, T::BlockNumber>,
 		proposal_hash: T::Hash,
 		proposal: <T as Trait<I>>::Proposal,
@@ -745,7 +968,7 @@ impl<T: Trait<I>, I: Instance> Module<T, I> {
 		Self::deposit_event(RawEvent::Approved(proposal_hash));
 
 		let dispatch_weight = proposal.get_dispatch_info().weight;
-		let origin = RawOrigin::Members(voting.threshold, seats).into();
+		let origin = Self::approval_origin(voting.threshold, total).into();
 		let result = proposal.dispatch(origin);
 		Self::deposit_event(
 			RawEvent::Executed(proposal_hash, result.map(|_| ()).map_err(|e| e.error))
@@ -942,6 +1165,79 @@ impl<
 	}
 }
 
+/// The approvals and the total of a motion dispatched from `o`, as vote weights.
+fn approvals_of<AccountId, I>(o: &RawOrigin<AccountId, I>) -> Option<(MemberWeight, MemberWeight)> {
+	match *o {
+		RawOrigin::Members(n, m) => Some((n.into(), m.into())),
+		RawOrigin::Weighted(n, m) => Some((n, m)),
+		_ => None,
+	}
+}
+
+/// The vote weight needed to reach the same proportion of `total` as `threshold` members out of
+/// `seats`, rounded up. The threshold is kept as is without members.
+fn rescale_threshold(threshold: MemberCount, total: MemberWeight, seats: MemberWeight) -> MemberWeight {
+	if seats == 0 {
+		return threshold.into();
+	}
+	let seats = U256::from(seats);
+	let rescaled = (U256::from(threshold) * U256::from(total) + seats - 1) / seats;
+	rescaled.min(U256::from(MemberWeight::max_value())).low_u128()
+}
+
+/// Compare the proportion `n / m` to `num / den` without overflowing.
+fn cmp_proportion(n: MemberWeight, m: MemberWeight, num: u32, den: u32) -> Ordering {
+	(U256::from(n) * U256::from(den)).cmp(&(U256::from(m) * U256::from(num)))
+}
+
+pub struct EnsureWeightedProportionMoreThan<N: U32, D: U32, AccountId, I=DefaultInstance>(
+	sp_std::marker::PhantomData<(N, D, AccountId, I)>
+);
+impl<
+	O: Into<Result<RawOrigin<AccountId, I>, O>> + From<RawOrigin<AccountId, I>>,
+	N: U32,
+	D: U32,
+	AccountId,
+	I,
+> EnsureOrigin<O> for EnsureWeightedProportionMoreThan<N, D, AccountId, I> {
+	type Success = ();
+	fn try_origin(o: O) -> Result<Self::Success, O> {
+		o.into().and_then(|o| match approvals_of(&o) {
+			Some((n, m)) if cmp_proportion(n, m, N::VALUE, D::VALUE) == Ordering::Greater => Ok(()),
+			_ => Err(O::from(o)),
+		})
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn successful_origin() -> O {
+		O::from(RawOrigin::Weighted(1, 0))
+	}
+}
+
+pub struct EnsureWeightedProportionAtLeast<N: U32, D: U32, AccountId, I=DefaultInstance>(
+	sp_std::marker::PhantomData<(N, D, AccountId, I)>
+);
+impl<
+	O: Into<Result<RawOrigin<AccountId, I>, O>> + From<RawOrigin<AccountId, I>>,
+	N: U32,
+	D: U32,
+	AccountId,
+	I,
+> EnsureOrigin<O> for EnsureWeightedProportionAtLeast<N, D, AccountId, I> {
+	type Success = ();
+	fn try_origin(o: O) -> Result<Self::Success, O> {
+		o.into().and_then(|o| match approvals_of(&o) {
+			Some((n, m)) if cmp_proportion(n, m, N::VALUE, D::VALUE) != Ordering::Less => Ok(()),
+			_ => Err(O::from(o)),
+		})
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn successful_origin() -> O {
+		O::from(RawOrigin::Weighted(0, 0))
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -963,6 +1259,7 @@ mod tests {
 		pub const MotionDuration: u64 = 3;
 		pub const MaxProposals: u32 = 100;
 		pub const MaxMembers: u32 = 100;
+		pub const PrimeWeight: MemberWeight = 3;
 	}
 	impl frame_system::Trait for Test {
 		type BaseCallFilter = ();
@@ -999,6 +1296,7 @@ mod tests {
 		type MaxProposals = MaxProposals;
 		type MaxMembers = MaxMembers;
 		type DefaultVote = PrimeDefaultVote;
+		type VoteCounting = OneMemberOneVote;
 		type WeightInfo = ();
 	}
 	impl Trait<Instance2> for Test {
@@ -1009,6 +1307,48 @@ mod tests {
 		type MaxProposals = MaxProposals;
 		type MaxMembers = MaxMembers;
 		type DefaultVote = MoreThanMajorityThenPrimeDefaultVote;
+		type VoteCounting = OneMemberOneVote;
+		type WeightInfo = ();
+	}
+
+	/// Weighs each member by the square of their account id.
+	pub struct TestStakes;
+	impl MemberWeights<u64> for TestStakes {
+		fn member_weight(who: &u64) -> MemberWeight {
+			(who * who) as MemberWeight
+		}
+	}
+	impl Trait<Instance3> for Test {
+		type Origin = Origin;
+		type Proposal = Call;
+		type Event = Event;
+		type MotionDuration = MotionDuration;
+		type MaxProposals = MaxProposals;
+		type MaxMembers = MaxMembers;
+		type DefaultVote = PrimeDefaultVote;
+		type VoteCounting = StakeWeighted<TestStakes>;
+		type WeightInfo = ();
+	}
+	impl Trait<Instance4> for Test {
+		type Origin = Origin;
+		type Proposal = Call;
+		type Event = Event;
+		type MotionDuration = MotionDuration;
+		type MaxProposals = MaxProposals;
+		type MaxMembers = MaxMembers;
+		type DefaultVote = PrimeDefaultVote;
+		type VoteCounting = Quadratic<TestStakes>;
+		type WeightInfo = ();
+	}
+	impl Trait<Instance5> for Test {
+		type Origin = Origin;
+		type Proposal = Call;
+		type Event = Event;
+		type MotionDuration = MotionDuration;
+		type MaxProposals = MaxProposals;
+		type MaxMembers = MaxMembers;
+		type DefaultVote = PrimeDefaultVote;
+		type VoteCounting = PrimeWeighted<PrimeWeight>;
 		type WeightInfo = ();
 	}
 	impl Trait for Test {
@@ -1019,6 +1359,7 @@ mod tests {
 		type MaxProposals = MaxProposals;
 		type MaxMembers = MaxMembers;
 		type DefaultVote = PrimeDefaultVote;
+		type VoteCounting = OneMemberOneVote;
 		type WeightInfo = ();
 	}
 
@@ -1034,6 +1375,9 @@ mod tests {
 			System: system::{Module, Call, Event<T>},
 			Collective: collective::<Instance1>::{Module, Call, Event<T>, Origin<T>, Config<T>},
 			CollectiveMajority: collective::<Instance2>::{Module, Call, Event<T>, Origin<T>, Config<T>},
+			CollectiveStake: collective::<Instance3>::{Module, Call, Event<T>, Origin<T>, Config<T>},
+			CollectiveQuadratic: collective::<Instance4>::{Module, Call, Event<T>, Origin<T>, Config<T>},
+			CollectivePrime: collective::<Instance5>::{Module, Call, Event<T>, Origin<T>, Config<T>},
 			DefaultCollective: collective::{Module, Call, Event<T>, Origin<T>, Config<T>},
 		}
 	);
@@ -1048,6 +1392,18 @@ mod tests {
 				members: vec![1, 2, 3, 4, 5],
 				phantom: Default::default(),
 			}),
+			collective_Instance3: Some(collective::GenesisConfig {
+				members: vec![1, 2, 3, 4],
+				phantom: Default::default(),
+			}),
+			collective_Instance4: Some(collective::GenesisConfig {
+				members: vec![1, 2, 3, 4],
+				phantom: Default::default(),
+			}),
+			collective_Instance5: Some(collective::GenesisConfig {
+				members: vec![1, 2, 3, 4],
+				phantom: Default::default(),
+			}),
 			collective: None,
 		}.build_storage().unwrap().into();
 		ext.execute_with(|| System::set_block_number(1));
@@ -1096,6 +1452,202 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn stake_weighted_votes_are_counted() {
+		new_test_ext().execute_with(|| {
+			let proposal = make_proposal(42);
+			let proposal_len: u32 = proposal.using_encoded(|p| p.len() as u32);
+			let proposal_weight = proposal.get_dispatch_info().weight;
+			let hash = BlakeTwo256::hash_of(&proposal);
+
+			// Weights are 1, 4, 9 and 16, for a total of 30.
+			assert_ok!(CollectiveStake::propose(Origin::signed(1), 16, Box::new(proposal.clone()), proposal_len));
+			assert_ok!(CollectiveStake::vote(Origin::signed(4), hash.clone(), 0, true));
+			assert_ok!(CollectiveStake::close(Origin::signed(2), hash.clone(), 0, proposal_weight, proposal_len));
+
+			let record = |event| EventRecord { phase: Phase::Initialization, event, topics: vec![] };
+			assert_eq!(System::events(), vec![
+				record(Event::collective_Instance3(RawEvent::Proposed(1, 0, hash.clone(), 16))),
+				record(Event::collective_Instance3(RawEvent::Voted(4, hash.clone(), true, 17, 0))),
+				record(Event::collective_Instance3(RawEvent::Closed(hash.clone(), 17, 0))),
+				record(Event::collective_Instance3(RawEvent::Approved(hash.clone()))),
+				record(Event::collective_Instance3(RawEvent::Executed(hash.clone(), Err(DispatchError::BadOrigin))))
+			]);
+		});
+	}
+
+	#[test]
+	fn stake_weighted_nays_disapprove_early() {
+		new_test_ext().execute_with(|| {
+			let proposal = make_proposal(42);
+			let proposal_len: u32 = proposal.using_encoded(|p| p.len() as u32);
+			let proposal_weight = proposal.get_dispatch_info().weight;
+			let hash = BlakeTwo256::hash_of(&proposal);
+
+			assert_ok!(CollectiveStake::propose(Origin::signed(1), 16, Box::new(proposal.clone()), proposal_len));
+			// A single heavy nay leaves only 14 of the 30 votes to approve.
+			assert_ok!(CollectiveStake::vote(Origin::signed(4), hash.clone(), 0, false));
+			assert_ok!(CollectiveStake::close(Origin::signed(2), hash.clone(), 0, proposal_weight, proposal_len));
+
+			let record = |event| EventRecord { phase: Phase::Initialization, event, topics: vec![] };
+			assert_eq!(System::events(), vec![
+				record(Event::collective_Instance3(RawEvent::Proposed(1, 0, hash.clone(), 16))),
+				record(Event::collective_Instance3(RawEvent::Voted(4, hash.clone(), false, 1, 16))),
+				record(Event::collective_Instance3(RawEvent::Closed(hash.clone(), 1, 16))),
+				record(Event::collective_Instance3(RawEvent::Disapproved(hash.clone())))
+			]);
+		});
+	}
+
+	#[test]
+	fn quadratic_votes_are_counted() {
+		new_test_ext().execute_with(|| {
+			let proposal = make_proposal(42);
+			let proposal_len: u32 = proposal.using_encoded(|p| p.len() as u32);
+			let proposal_weight = proposal.get_dispatch_info().weight;
+			let hash = BlakeTwo256::hash_of(&proposal);
+
+			// Weights are 1, 2, 3 and 4, for a total of 10.
+			assert_ok!(CollectiveQuadratic::propose(Origin::signed(4), 7, Box::new(proposal.clone()), proposal_len));
+			assert_ok!(CollectiveQuadratic::vote(Origin::signed(2), hash.clone(), 0, true));
+			assert_noop!(
+				CollectiveQuadratic::close(Origin::signed(2), hash.clone(), 0, proposal_weight, proposal_len),
+				Error::<Test, Instance4>::TooEarly
+			);
+			assert_ok!(CollectiveQuadratic::vote(Origin::signed(1), hash.clone(), 0, true));
+			assert_ok!(CollectiveQuadratic::close(Origin::signed(2), hash.clone(), 0, proposal_weight, proposal_len));
+
+			let record = |event| EventRecord { phase: Phase::Initialization, event, topics: vec![] };
+			assert_eq!(System::events(), vec![
+				record(Event::collective_Instance4(RawEvent::Proposed(4, 0, hash.clone(), 7))),
+				record(Event::collective_Instance4(RawEvent::Voted(2, hash.clone(), true, 6, 0))),
+				record(Event::collective_Instance4(RawEvent::Voted(1, hash.clone(), true, 7, 0))),
+				record(Event::collective_Instance4(RawEvent::Closed(hash.clone(), 7, 0))),
+				record(Event::collective_Instance4(RawEvent::Approved(hash.clone()))),
+				record(Event::collective_Instance4(RawEvent::Executed(hash.clone(), Err(DispatchError::BadOrigin))))
+			]);
+		});
+	}
+
+	#[test]
+	fn weighted_abstentions_follow_default_vote() {
+		new_test_ext().execute_with(|| {
+			let proposal = make_proposal(42);
+			let proposal_len: u32 = proposal.using_encoded(|p| p.len() as u32);
+			let proposal_weight = proposal.get_dispatch_info().weight;
+			let hash = BlakeTwo256::hash_of(&proposal);
+
+			Prime::<Test, Instance4>::set(Some(4));
+			assert_ok!(CollectiveQuadratic::propose(Origin::signed(4), 8, Box::new(proposal.clone()), proposal_len));
+			assert_ok!(CollectiveQuadratic::vote(Origin::signed(1), hash.clone(), 0, false));
+
+			System::set_block_number(4);
+			assert_ok!(CollectiveQuadratic::close(Origin::signed(2), hash.clone(), 0, proposal_weight, proposal_len));
+
+			// The abstaining weight of 2 and 3 follows the prime.
+			let record = |event| EventRecord { phase: Phase::Initialization, event, topics: vec![] };
+			assert_eq!(System::events(), vec![
+				record(Event::collective_Instance4(RawEvent::Proposed(4, 0, hash.clone(), 8))),
+				record(Event::collective_Instance4(RawEvent::Voted(1, hash.clone(), false, 4, 1))),
+				record(Event::collective_Instance4(RawEvent::Closed(hash.clone(), 9, 1))),
+				record(Event::collective_Instance4(RawEvent::Approved(hash.clone()))),
+				record(Event::collective_Instance4(RawEvent::Executed(hash.clone(), Err(DispatchError::BadOrigin))))
+			]);
+		});
+	}
+
+	#[test]
+	fn prime_weighted_votes_are_counted() {
+		new_test_ext().execute_with(|| {
+			let proposal = make_proposal(42);
+			let proposal_len: u32 = proposal.using_encoded(|p| p.len() as u32);
+			let proposal_weight = proposal.get_dispatch_info().weight;
+			let hash = BlakeTwo256::hash_of(&proposal);
+
+			// The prime counts three times, for a total of 6.
+			Prime::<Test, Instance5>::set(Some(1));
+			assert_ok!(CollectivePrime::propose(Origin::signed(2), 4, Box::new(proposal.clone()), proposal_len));
+			assert_ok!(CollectivePrime::vote(Origin::signed(1), hash.clone(), 0, true));
+			assert_ok!(CollectivePrime::close(Origin::signed(2), hash.clone(), 0, proposal_weight, proposal_len));
+
+			let record = |event| EventRecord { phase: Phase::Initialization, event, topics: vec![] };
+			assert_eq!(System::events(), vec![
+				record(Event::collective_Instance5(RawEvent::Proposed(2, 0, hash.clone(), 4))),
+				record(Event::collective_Instance5(RawEvent::Voted(1, hash.clone(), true, 4, 0))),
+				record(Event::collective_Instance5(RawEvent::Closed(hash.clone(), 4, 0))),
+				record(Event::collective_Instance5(RawEvent::Approved(hash.clone()))),
+				record(Event::collective_Instance5(RawEvent::Executed(hash.clone(), Err(DispatchError::BadOrigin))))
+			]);
+		});
+	}
+
+	#[test]
+	fn weighted_origins_work() {
+		use sp_core::u32_trait::{_1, _2};
+		type MoreThanHalf = EnsureWeightedProportionMoreThan<_1, _2, u64, Instance3>;
+		type AtLeastHalf = EnsureWeightedProportionAtLeast<_1, _2, u64, Instance3>;
+		let weighted = |n, m| Origin::from(RawOrigin::<u64, Instance3>::Weighted(n, m));
+		let members = |n, m| Origin::from(RawOrigin::<u64, Instance3>::Members(n, m));
+
+		assert!(MoreThanHalf::try_origin(weighted(16, 30)).is_ok());
+		assert!(MoreThanHalf::try_origin(weighted(15, 30)).is_err());
+		assert!(AtLeastHalf::try_origin(weighted(15, 30)).is_ok());
+		assert!(AtLeastHalf::try_origin(weighted(14, 30)).is_err());
+		// Unweighted approvals of the same instance are accepted too.
+		assert!(MoreThanHalf::try_origin(members(2, 3)).is_ok());
+		assert!(AtLeastHalf::try_origin(members(1, 3)).is_err());
+		// Plain proportions never accept weighted approvals.
+		assert!(
+			EnsureProportionAtLeast::<_1, _2, u64, Instance3>::try_origin(weighted(30, 30)).is_err()
+		);
+		// Stake-sized weights don't overflow.
+		let total = MemberWeight::max_value();
+		assert!(MoreThanHalf::try_origin(weighted(total / 2 + 1, total)).is_ok());
+		assert!(MoreThanHalf::try_origin(weighted(total / 2, total)).is_err());
+	}
+
+	#[test]
+	fn migration_v1_to_v2_widens_thresholds() {
+		new_test_ext().execute_with(|| {
+			let hash = H256::repeat_byte(1);
+			Proposals::<Test, Instance1>::put(vec![hash]);
+			// `VotesV1 { index: 0, threshold: 3, ayes: vec![1], nays: vec![], end: 3 }`
+			frame_support::storage::unhashed::put(
+				&Voting::<Test, Instance1>::hashed_key_for(hash),
+				&(0u32, 3u32, vec![1u64], Vec::<u64>::new(), 3u64),
+			);
+			StorageVersion::<Instance1>::put(Releases::V1);
+
+			assert_eq!(Collective::migrate_v1_to_v2(), Some(1));
+			assert_eq!(
+				Collective::voting(&hash),
+				Some(Votes { index: 0, threshold: 3, ayes: vec![1], nays: vec![], end: 3 })
+			);
+			assert_eq!(Collective::migrate_v1_to_v2(), None);
+		});
+	}
+
+	#[test]
+	fn migration_v1_to_v2_rescales_thresholds_to_stake() {
+		new_test_ext().execute_with(|| {
+			let hash = H256::repeat_byte(1);
+			Proposals::<Test, Instance3>::put(vec![hash]);
+			// 3 out of 4 members.
+			frame_support::storage::unhashed::put(
+				&Voting::<Test, Instance3>::hashed_key_for(hash),
+				&(0u32, 3u32, vec![1u64], Vec::<u64>::new(), 3u64),
+			);
+			StorageVersion::<Instance3>::put(Releases::V1);
+
+			assert_eq!(CollectiveStake::migrate_v1_to_v2(), Some(1));
+			// 3/4 of the stake of 1 + 4 + 9 + 16, rounded up.
+			assert_eq!(
+				CollectiveStake::voting(&hash),
+				Some(Votes { index: 0, threshold: 23, ayes: vec![1], nays: vec![], end: 3 })
+			);
+		});
+	}
+
 	#[test]
 	fn proposal_weight_limit_works_on_approve() {
 		new_test_ext().execute_with(|| {
@@ -0,0 +1,195 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Conviction voting pallet benchmarking.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use sp_runtime::traits::Bounded;
+use frame_system::RawOrigin;
+use frame_benchmarking::{benchmarks, account, whitelisted_caller};
+
+use crate::Module as ConvictionVoting;
+
+const SEED: u32 = 0;
+
+fn funded_account<T: Trait>(name: &'static str, index: u32) -> T::AccountId {
+	let caller: T::AccountId = account(name, index, SEED);
+	T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+	caller
+}
+
+fn account_vote<T: Trait>(aye: bool) -> AccountVote<BalanceOf<T>> {
+	AccountVote::Standard {
+		vote: Vote { aye, conviction: Conviction::Locked1x },
+		balance: 100u32.into(),
+	}
+}
+
+fn first_class<T: Trait>() -> Result<ClassOf<T>, &'static str> {
+	T::Polls::classes().into_iter().next().ok_or("no poll classes")
+}
+
+/// Create `n` ongoing polls of `class`.
+fn create_polls<T: Trait>(class: &ClassOf<T>, n: u32) -> Result<Vec<PollIndexOf<T>>, &'static str> {
+	(0..n).map(|_| T::Polls::create_ongoing(class.clone()).map_err(|_| "failed to create poll"))
+		.collect()
+}
+
+/// Vote aye on each of `polls` from `who`.
+fn vote_on<T: Trait>(who: &T::AccountId, polls: &[PollIndexOf<T>]) -> Result<(), &'static str> {
+	for &index in polls {
+		ConvictionVoting::<T>::vote(RawOrigin::Signed(who.clone()).into(), index, account_vote::<T>(true))?;
+	}
+	Ok(())
+}
+
+fn votes_of<T: Trait>(who: &T::AccountId, class: &ClassOf<T>) -> Result<usize, &'static str> {
+	match VotingFor::<T>::get(who, class) {
+		Voting::Casting(Casting { votes, .. }) => Ok(votes.len()),
+		_ => Err("account is not casting votes"),
+	}
+}
+
+benchmarks! {
+	_ { }
+
+	vote_new {
+		let r in 0 .. T::MaxVotes::get() - 1;
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		let class = first_class::<T>()?;
+		let mut polls = create_polls::<T>(&class, r + 1)?;
+		let index = polls.pop().ok_or("no poll")?;
+		vote_on::<T>(&caller, &polls)?;
+	}: vote(RawOrigin::Signed(caller.clone()), index, account_vote::<T>(true))
+	verify {
+		assert_eq!(votes_of::<T>(&caller, &class)?, r as usize + 1);
+	}
+
+	vote_existing {
+		let r in 1 .. T::MaxVotes::get();
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		let class = first_class::<T>()?;
+		let polls = create_polls::<T>(&class, r)?;
+		vote_on::<T>(&caller, &polls)?;
+		let index = *polls.last().ok_or("no poll")?;
+	}: vote(RawOrigin::Signed(caller.clone()), index, account_vote::<T>(false))
+	verify {
+		assert_eq!(votes_of::<T>(&caller, &class)?, r as usize);
+	}
+
+	remove_vote {
+		let r in 1 .. T::MaxVotes::get();
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		let class = first_class::<T>()?;
+		let polls = create_polls::<T>(&class, r)?;
+		vote_on::<T>(&caller, &polls)?;
+		let index = *polls.last().ok_or("no poll")?;
+	}: _(RawOrigin::Signed(caller.clone()), Some(class.clone()), index)
+	verify {
+		assert_eq!(votes_of::<T>(&caller, &class)?, r as usize - 1);
+	}
+
+	// The poll was rejected, so the aye vote of the voter has expired.
+	remove_other_vote {
+		let r in 1 .. T::MaxVotes::get();
+		let caller: T::AccountId = whitelisted_caller();
+		let voter = funded_account::<T>("voter", 0);
+		let class = first_class::<T>()?;
+		let polls = create_polls::<T>(&class, r)?;
+		vote_on::<T>(&voter, &polls)?;
+		let index = *polls.last().ok_or("no poll")?;
+		T::Polls::end_ongoing(index, false).map_err(|_| "failed to end poll")?;
+	}: _(RawOrigin::Signed(caller), voter.clone(), class.clone(), index)
+	verify {
+		assert_eq!(votes_of::<T>(&voter, &class)?, r as usize - 1);
+	}
+
+	// The delegate voted on `r` ongoing polls, whose tallies are updated.
+	delegate {
+		let r in 0 .. T::MaxVotes::get();
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		let delegate = funded_account::<T>("delegate", 0);
+		let class = first_class::<T>()?;
+		let polls = create_polls::<T>(&class, r)?;
+		vote_on::<T>(&delegate, &polls)?;
+	}: _(RawOrigin::Signed(caller.clone()), class.clone(), delegate, Conviction::Locked1x, 100u32.into())
+	verify {
+		assert!(matches!(VotingFor::<T>::get(&caller, &class), Voting::Delegating(_)));
+	}
+
+	undelegate {
+		let r in 0 .. T::MaxVotes::get();
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		let delegate = funded_account::<T>("delegate", 0);
+		let class = first_class::<T>()?;
+		let polls = create_polls::<T>(&class, r)?;
+		vote_on::<T>(&delegate, &polls)?;
+		ConvictionVoting::<T>::delegate(
+			RawOrigin::Signed(caller.clone()).into(),
+			class.clone(),
+			delegate,
+			Conviction::Locked1x,
+			100u32.into(),
+		)?;
+	}: _(RawOrigin::Signed(caller.clone()), class.clone())
+	verify {
+		assert!(matches!(VotingFor::<T>::get(&caller, &class), Voting::Casting(_)));
+	}
+
+	// The prior lock of a winning vote has expired and is removed.
+	unlock {
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		let class = first_class::<T>()?;
+		let index = create_polls::<T>(&class, 1)?[0];
+		vote_on::<T>(&caller, &[index])?;
+		T::Polls::end_ongoing(index, true).map_err(|_| "failed to end poll")?;
+		ConvictionVoting::<T>::remove_vote(RawOrigin::Signed(caller.clone()).into(), Some(class.clone()), index)?;
+		let now = system::Module::<T>::block_number();
+		system::Module::<T>::set_block_number(now + T::VoteLockingPeriod::get());
+	}: _(RawOrigin::Signed(caller.clone()), class, caller.clone())
+	verify {
+		assert!(ClassLocksFor::<T>::get(&caller).is_empty());
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{new_test_ext, Test};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn test_benchmarks() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_vote_new::<Test>());
+			assert_ok!(test_benchmark_vote_existing::<Test>());
+			assert_ok!(test_benchmark_remove_vote::<Test>());
+			assert_ok!(test_benchmark_remove_other_vote::<Test>());
+			assert_ok!(test_benchmark_delegate::<Test>());
+			assert_ok!(test_benchmark_undelegate::<Test>());
+			assert_ok!(test_benchmark_unlock::<Test>());
+		});
+	}
+}
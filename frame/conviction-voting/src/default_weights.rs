@@ -0,0 +1,71 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Default weights for the Conviction Voting Pallet.
+//! The storage reads and writes are those of the worst cases in `benchmarking.rs`.
+
+use frame_support::weights::{Weight, constants::RocksDbWeight as DbWeight};
+
+/// Default implementation of weight, this is just from an example return, values may change
+/// depending on the runtime. This is not meant to be used in production.
+impl crate::WeightInfo for () {
+	fn vote_new(r: u32, ) -> Weight {
+		(54_159_000 as Weight)
+			.saturating_add((252_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(DbWeight::get().reads(5 as Weight))
+			.saturating_add(DbWeight::get().writes(5 as Weight))
+	}
+	fn vote_existing(r: u32, ) -> Weight {
+		(54_145_000 as Weight)
+			.saturating_add((262_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(DbWeight::get().reads(5 as Weight))
+			.saturating_add(DbWeight::get().writes(5 as Weight))
+	}
+	fn remove_vote(r: u32, ) -> Weight {
+		(28_250_000 as Weight)
+			.saturating_add((283_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(DbWeight::get().reads(2 as Weight))
+			.saturating_add(DbWeight::get().writes(2 as Weight))
+	}
+	fn remove_other_vote(r: u32, ) -> Weight {
+		(28_250_000 as Weight)
+			.saturating_add((283_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(DbWeight::get().reads(2 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn delegate(r: u32, ) -> Weight {
+		(72_046_000 as Weight)
+			.saturating_add((7_837_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(DbWeight::get().reads(5 as Weight))
+			.saturating_add(DbWeight::get().reads((1 as Weight).saturating_mul(r as Weight)))
+			.saturating_add(DbWeight::get().writes(5 as Weight))
+			.saturating_add(DbWeight::get().writes((1 as Weight).saturating_mul(r as Weight)))
+	}
+	fn undelegate(r: u32, ) -> Weight {
+		(41_028_000 as Weight)
+			.saturating_add((7_810_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(DbWeight::get().reads(2 as Weight))
+			.saturating_add(DbWeight::get().reads((1 as Weight).saturating_mul(r as Weight)))
+			.saturating_add(DbWeight::get().writes(2 as Weight))
+			.saturating_add(DbWeight::get().writes((1 as Weight).saturating_mul(r as Weight)))
+	}
+	fn unlock() -> Weight {
+		(45_333_000 as Weight)
+			.saturating_add(DbWeight::get().reads(4 as Weight))
+			.saturating_add(DbWeight::get().writes(4 as Weight))
+	}
+}
@@ -0,0 +1,629 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Conviction Voting Pallet
+//!
+//! - [`conviction_voting::Trait`](./trait.Trait.html)
+//! - [`Call`](./enum.Call.html)
+//!
+//! ## Overview
+//!
+//! The Conviction Voting pallet manages the time-locked votes of token holders on the polls of
+//! another pallet, accessed through the [`Polling`] trait.
+//!
+//! Every poll belongs to a class, and an account is either voting directly or delegating its
+//! voting power, separately for each class. The balance behind votes and delegations is locked
+//! for as long as the greatest lock of any class requires.
+//!
+//! ### Terminology
+//!
+//! - **Vote Locking Period:** The minimum period for which the balance of a winning vote is
+//!   locked after its poll has completed.
+//! - **Conviction:** An indication of a voter's strength of belief in their vote. An increase
+//!   of one in conviction indicates that a token holder is willing to lock their tokens for twice
+//!   as many lock periods after enactment.
+//! - **Class:** The category of a poll, e.g. the track of a referendum. Delegations apply to a
+//!   single class.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! - `vote` - Vote on an ongoing poll.
+//! - `delegate` - Delegate voting power on a class to another account.
+//! - `undelegate` - Stop delegating voting power on a class.
+//! - `unlock` - Redetermine the voting lock of an account for a class.
+//! - `remove_vote` - Remove a vote on a poll.
+//! - `remove_other_vote` - Remove the expired vote of another account.
+
+#![recursion_limit="128"]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_std::prelude::*;
+use sp_runtime::{
+	DispatchResult, DispatchError,
+	traits::{Zero, Saturating},
+};
+use frame_support::{
+	decl_module, decl_storage, decl_event, decl_error, ensure,
+	dispatch::DispatchResultWithPostInfo,
+	weights::Weight,
+	traits::{
+		Currency, Get, LockableCurrency, LockIdentifier, WithdrawReason,
+		voting::{Polling, PollStatus},
+	},
+};
+use frame_system::{self as system, ensure_signed};
+
+mod conviction;
+mod types;
+mod vote;
+mod benchmarking;
+mod default_weights;
+
+pub use conviction::Conviction;
+pub use types::{Tally, Delegations, UnvoteScope};
+pub use vote::{Vote, AccountVote, Voting, Casting, Delegating, PriorLock};
+
+#[cfg(test)]
+mod tests;
+
+const CONVICTION_VOTING_ID: LockIdentifier = *b"pyconvot";
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
+/// The tally of a poll.
+pub type TallyOf<T> = Tally<BalanceOf<T>>;
+/// The index of a poll.
+pub type PollIndexOf<T> = <<T as Trait>::Polls as Polling<TallyOf<T>>>::Index;
+/// The class of a poll.
+pub type ClassOf<T> = <<T as Trait>::Polls as Polling<TallyOf<T>>>::Class;
+/// The voting state of an account on a class of polls.
+pub type VotingOf<T> = Voting<
+	BalanceOf<T>,
+	<T as frame_system::Trait>::AccountId,
+	<T as frame_system::Trait>::BlockNumber,
+	PollIndexOf<T>,
+>;
+
+pub trait WeightInfo {
+	fn vote_new(r: u32, ) -> Weight;
+	fn vote_existing(r: u32, ) -> Weight;
+	fn remove_vote(r: u32, ) -> Weight;
+	fn remove_other_vote(r: u32, ) -> Weight;
+	fn delegate(r: u32, ) -> Weight;
+	fn undelegate(r: u32, ) -> Weight;
+	fn unlock() -> Weight;
+}
+
+pub trait Trait: frame_system::Trait + Sized {
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+
+	/// Currency type with which voting happens.
+	type Currency: LockableCurrency<Self::AccountId, Moment=Self::BlockNumber>;
+
+	/// The polls on which voting happens, e.g. the referenda of the Referenda pallet.
+	type Polls: Polling<TallyOf<Self>, Votes = BalanceOf<Self>, Moment = Self::BlockNumber>;
+
+	/// The maximum number of concurrent votes an account may have, per class.
+	///
+	/// Also used to compute weight, an overly large value can lead to extrinsics with large
+	/// weight estimation: see `delegate` for instance.
+	type MaxVotes: Get<u32>;
+
+	/// The minimum period of vote locking.
+	///
+	/// It should be no shorter than enactment period to ensure that in the case of an approval,
+	/// those successful voters are locked into the consequences that their votes entail.
+	type VoteLockingPeriod: Get<Self::BlockNumber>;
+
+	/// Weight information for extrinsics in this pallet.
+	type WeightInfo: WeightInfo;
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as ConvictionVoting {
+		/// All voting for a particular voter in a particular voting class. We store the balance
+		/// for the number of votes that we have recorded.
+		pub VotingFor get(fn voting_for):
+			double_map hasher(twox_64_concat) T::AccountId, hasher(twox_64_concat) ClassOf<T>
+			=> VotingOf<T>;
+
+		/// The voting classes which have a non-zero lock requirement and the lock amounts which
+		/// they require. The actual amount locked on behalf of this pallet should always be the
+		/// maximum of this list.
+		pub ClassLocksFor get(fn class_locks_for):
+			map hasher(twox_64_concat) T::AccountId => Vec<(ClassOf<T>, BalanceOf<T>)>;
+	}
+}
+
+decl_event! {
+	pub enum Event<T> where
+		<T as frame_system::Trait>::AccountId,
+	{
+		/// An account has delegated their vote to another account. \[who, target\]
+		Delegated(AccountId, AccountId),
+		/// An \[account\] has cancelled a previous delegation operation.
+		Undelegated(AccountId),
+	}
+}
+
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// Poll is not ongoing.
+		NotOngoing,
+		/// The given account did not vote on the poll.
+		NotVoter,
+		/// The actor has no permission to conduct the action.
+		NoPermission,
+		/// The actor has no permission to conduct the action right now but will do in the future.
+		NoPermissionYet,
+		/// The account is already delegating.
+		AlreadyDelegating,
+		/// The account currently has votes attached to it and the operation cannot succeed until
+		/// these are removed, either through `unvote` or `reap_vote`.
+		AlreadyVoting,
+		/// Too high a balance was provided that the account cannot afford.
+		InsufficientFunds,
+		/// The account is not currently delegating.
+		NotDelegating,
+		/// Delegation to oneself makes no sense.
+		Nonsense,
+		/// Maximum number of votes reached.
+		MaxVotesReached,
+		/// The class must be supplied since it is not easily determinable from the state.
+		ClassNeeded,
+		/// The class ID supplied is invalid.
+		BadClass,
+		/// Unexpected error: the tally would overflow.
+		Overflow,
+		/// Unexpected error: the tally would underflow.
+		Underflow,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		/// The maximum number of concurrent votes an account may have, per class.
+		const MaxVotes: u32 = T::MaxVotes::get();
+
+		/// The minimum period of vote locking.
+		const VoteLockingPeriod: T::BlockNumber = T::VoteLockingPeriod::get();
+
+		fn deposit_event() = default;
+
+		/// Vote in a poll. If `vote.is_aye()`, the vote is to enact the proposal;
+		/// otherwise it is a vote to keep the status quo.
+		///
+		/// The dispatch origin of this call must be _Signed_.
+		///
+		/// - `poll_index`: The index of the poll to vote for.
+		/// - `vote`: The vote configuration.
+		///
+		/// Weight: `O(R)` where R is the number of polls the voter has voted on.
+		#[weight = T::WeightInfo::vote_new(T::MaxVotes::get())
+			.max(T::WeightInfo::vote_existing(T::MaxVotes::get()))]
+		fn vote(origin, poll_index: PollIndexOf<T>, vote: AccountVote<BalanceOf<T>>) {
+			let who = ensure_signed(origin)?;
+			Self::try_vote(&who, poll_index, vote)?;
+		}
+
+		/// Delegate the voting power (with some given conviction) of the sending account for a
+		/// particular class of polls.
+		///
+		/// The balance delegated is locked for as long as it's delegated, and thereafter for the
+		/// time appropriate for the conviction's lock period.
+		///
+		/// The dispatch origin of this call must be _Signed_, and the signing account must either:
+		///   - be delegating already; or
+		///   - have no voting activity (if there is, then it will need to be removed through
+		///     `remove_vote`).
+		///
+		/// - `class`: The class of polls to delegate. To delegate multiple classes, multiple
+		///   calls to this function are required.
+		/// - `to`: The account whose voting the `target` account's voting power will follow.
+		/// - `conviction`: The conviction that will be attached to the delegated votes. When the
+		///   account is undelegated, the funds will be locked for the corresponding period.
+		/// - `balance`: The amount of the account's balance to be used in delegating. This must
+		///   not be more than the account's current balance.
+		///
+		/// Emits `Delegated`.
+		///
+		/// Weight: `O(R)` where R is the number of polls the voter delegating to has
+		///   voted on. Weight is initially charged as if maximum votes, but is refunded later.
+		// NOTE: weight must cover an incorrect voting of origin with max votes, this is ensure
+		// because a valid delegation cover decoding a direct voting with max votes.
+		#[weight = T::WeightInfo::delegate(T::MaxVotes::get())]
+		fn delegate(origin,
+			class: ClassOf<T>,
+			to: T::AccountId,
+			conviction: Conviction,
+			balance: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let votes = Self::try_delegate(who, class, to, conviction, balance)?;
+
+			Ok(Some(T::WeightInfo::delegate(votes)).into())
+		}
+
+		/// Undelegate the voting power of the sending account for a particular class of polls.
+		///
+		/// Tokens may be unlocked following once an amount of time consistent with the lock
+		/// period of the conviction with which the delegation was issued.
+		///
+		/// The dispatch origin of this call must be _Signed_ and the signing account must be
+		/// currently delegating.
+		///
+		/// - `class`: The class of polls to remove the delegation from.
+		///
+		/// Emits `Undelegated`.
+		///
+		/// Weight: `O(R)` where R is the number of polls the voter delegating to has
+		///   voted on. Weight is initially charged as if maximum votes, but is refunded later.
+		// NOTE: weight must cover an incorrect voting of origin with max votes, this is ensure
+		// because a valid delegation cover decoding a direct voting with max votes.
+		#[weight = T::WeightInfo::undelegate(T::MaxVotes::get())]
+		fn undelegate(origin, class: ClassOf<T>) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let votes = Self::try_undelegate(who, class)?;
+			Ok(Some(T::WeightInfo::undelegate(votes)).into())
+		}
+
+		/// Remove the lock caused by prior voting/delegating which has expired within a
+		/// particular class.
+		///
+		/// The dispatch origin of this call must be _Signed_.
+		///
+		/// - `class`: The class of polls to unlock.
+		/// - `target`: The account to remove the lock on.
+		///
+		/// Weight: `O(R)` with R number of vote of target.
+		#[weight = T::WeightInfo::unlock()]
+		fn unlock(origin, class: ClassOf<T>, target: T::AccountId) {
+			ensure_signed(origin)?;
+			Self::update_lock(&class, &target);
+		}
+
+		/// Remove a vote for a poll.
+		///
+		/// If:
+		/// - the poll was cancelled, or
+		/// - the poll is ongoing, or
+		/// - the poll has ended such that
+		///   - the vote of the account was in opposition to the result; or
+		///   - there was no conviction to the account's vote; or
+		///   - the account made a split vote
+		/// ...then the vote is removed cleanly and a following call to `unlock` may result in
+		/// more funds being available.
+		///
+		/// If, however, the poll has ended and:
+		/// - it finished corresponding to the vote of the account, and
+		/// - the account made a standard vote with conviction, and
+		/// - the lock period of the conviction is not over
+		/// ...then the lock will be aggregated into the overall account's lock, which may involve
+		/// *overlocking* (where the two locks are combined into a single lock that is the maximum
+		/// of both the amount locked and the time is it locked for).
+		///
+		/// The dispatch origin of this call must be _Signed_, and the signer must have a vote
+		/// registered for poll `index`.
+		///
+		/// - `index`: The index of poll of the vote to be removed.
+		/// - `class`: Optional parameter, if given it indicates the class of the poll. For polls
+		///   which have finished or are cancelled, this must be `Some`.
+		///
+		/// Weight: `O(R + log R)` where R is the number of polls that `target` has voted on.
+		///   Weight is calculated for the maximum number of vote.
+		#[weight = T::WeightInfo::remove_vote(T::MaxVotes::get())]
+		fn remove_vote(origin, class: Option<ClassOf<T>>, index: PollIndexOf<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::try_remove_vote(&who, index, class, UnvoteScope::Any)
+		}
+
+		/// Remove a vote for a poll.
+		///
+		/// If the `target` is equal to the signer, then this function is exactly equivalent to
+		/// `remove_vote`. If not equal to the signer, then the vote must have expired,
+		/// either because the poll was cancelled, because the voter lost the poll or
+		/// because the conviction period is over.
+		///
+		/// The dispatch origin of this call must be _Signed_.
+		///
+		/// - `target`: The account of the vote to be removed; this account must have voted for poll
+		///   `index`.
+		/// - `class`: The class of the poll.
+		/// - `index`: The index of poll of the vote to be removed.
+		///
+		/// Weight: `O(R + log R)` where R is the number of polls that `target` has voted on.
+		///   Weight is calculated for the maximum number of vote.
+		#[weight = T::WeightInfo::remove_other_vote(T::MaxVotes::get())]
+		fn remove_other_vote(origin,
+			target: T::AccountId,
+			class: ClassOf<T>,
+			index: PollIndexOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let scope = if target == who { UnvoteScope::Any } else { UnvoteScope::OnlyExpired };
+			Self::try_remove_vote(&target, index, Some(class), scope)?;
+			Ok(())
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Actually enact a vote, if legit.
+	fn try_vote(
+		who: &T::AccountId,
+		poll_index: PollIndexOf<T>,
+		vote: AccountVote<BalanceOf<T>>,
+	) -> DispatchResult {
+		ensure!(vote.balance() <= T::Currency::free_balance(who), Error::<T>::InsufficientFunds);
+		T::Polls::try_access_poll(poll_index, |poll_status| {
+			let (tally, class) = poll_status.ensure_ongoing().ok_or(Error::<T>::NotOngoing)?;
+			VotingFor::<T>::try_mutate(who, &class, |voting| -> DispatchResult {
+				if let Voting::Casting(Casting { ref mut votes, delegations, .. }) = voting {
+					match votes.binary_search_by_key(&poll_index, |i| i.0) {
+						Ok(i) => {
+							// Shouldn't be possible to fail, but we handle it gracefully.
+							tally.remove(votes[i].1).ok_or(Error::<T>::Underflow)?;
+							if let Some(approve) = votes[i].1.as_standard() {
+								tally.reduce(approve, *delegations);
+							}
+							votes[i].1 = vote;
+						}
+						Err(i) => {
+							ensure!(
+								(votes.len() as u32) < T::MaxVotes::get(),
+								Error::<T>::MaxVotesReached
+							);
+							votes.insert(i, (poll_index, vote));
+						}
+					}
+					// Shouldn't be possible to fail, but we handle it gracefully.
+					tally.add(vote).ok_or(Error::<T>::Overflow)?;
+					if let Some(approve) = vote.as_standard() {
+						tally.increase(approve, *delegations);
+					}
+					Ok(())
+				} else {
+					Err(Error::<T>::AlreadyDelegating.into())
+				}
+			})?;
+			// Extend the lock to `balance` (rather than setting it) since we don't know what
+			// other votes are in place.
+			Self::extend_lock(who, &class, vote.balance());
+			Ok(())
+		})
+	}
+
+	/// Remove the account's vote for the given poll if possible. This is possible when:
+	/// - The poll has not finished.
+	/// - The poll has finished and the voter lost their direction.
+	/// - The poll has finished and the voter's lock period is up.
+	///
+	/// This will generally be combined with a call to `unlock`.
+	fn try_remove_vote(
+		who: &T::AccountId,
+		poll_index: PollIndexOf<T>,
+		class_hint: Option<ClassOf<T>>,
+		scope: UnvoteScope,
+	) -> DispatchResult {
+		let class = class_hint
+			.or_else(|| Some(T::Polls::as_ongoing(poll_index)?.1))
+			.ok_or(Error::<T>::ClassNeeded)?;
+		VotingFor::<T>::try_mutate(who, class, |voting| -> DispatchResult {
+			if let Voting::Casting(Casting { ref mut votes, delegations, ref mut prior }) = voting {
+				let i = votes.binary_search_by_key(&poll_index, |i| i.0)
+					.map_err(|_| Error::<T>::NotVoter)?;
+				let v = votes.remove(i);
+
+				T::Polls::try_access_poll(poll_index, |poll_status| match poll_status {
+					PollStatus::Ongoing(tally, _) => {
+						ensure!(matches!(scope, UnvoteScope::Any), Error::<T>::NoPermission);
+						// Shouldn't be possible to fail, but we handle it gracefully.
+						tally.remove(v.1).ok_or(Error::<T>::Underflow)?;
+						if let Some(approve) = v.1.as_standard() {
+							tally.reduce(approve, *delegations);
+						}
+						Ok(())
+					},
+					PollStatus::Completed(end, approved) => {
+						if let Some((lock_periods, balance)) = v.1.locked_if(approved) {
+							let unlock_at = end.saturating_add(
+								T::VoteLockingPeriod::get().saturating_mul(lock_periods.into())
+							);
+							let now = system::Module::<T>::block_number();
+							if now < unlock_at {
+								ensure!(
+									matches!(scope, UnvoteScope::Any),
+									Error::<T>::NoPermissionYet
+								);
+								prior.accumulate(unlock_at, balance)
+							}
+						}
+						Ok(())
+					},
+					// Poll was cancelled.
+					PollStatus::None => Ok(()),
+				})
+			} else {
+				Ok(())
+			}
+		})
+	}
+
+	/// Return the number of votes for `who`
+	fn increase_upstream_delegation(
+		who: &T::AccountId,
+		class: &ClassOf<T>,
+		amount: Delegations<BalanceOf<T>>,
+	) -> u32 {
+		VotingFor::<T>::mutate(who, class, |voting| match voting {
+			Voting::Delegating(Delegating { delegations, .. }) => {
+				// We don't support second level delegating, so we don't need to do anything more.
+				*delegations = delegations.saturating_add(amount);
+				1
+			},
+			Voting::Casting(Casting { votes, delegations, .. }) => {
+				*delegations = delegations.saturating_add(amount);
+				for &(poll_index, account_vote) in votes.iter() {
+					if let AccountVote::Standard { vote, .. } = account_vote {
+						T::Polls::access_poll(poll_index, |poll_status| {
+							if let PollStatus::Ongoing(tally, _) = poll_status {
+								tally.increase(vote.aye, amount);
+							}
+						});
+					}
+				}
+				votes.len() as u32
+			}
+		})
+	}
+
+	/// Return the number of votes for `who`
+	fn reduce_upstream_delegation(
+		who: &T::AccountId,
+		class: &ClassOf<T>,
+		amount: Delegations<BalanceOf<T>>,
+	) -> u32 {
+		VotingFor::<T>::mutate(who, class, |voting| match voting {
+			Voting::Delegating(Delegating { delegations, .. }) => {
+				// We don't support second level delegating, so we don't need to do anything more.
+				*delegations = delegations.saturating_sub(amount);
+				1
+			},
+			Voting::Casting(Casting { votes, delegations, .. }) => {
+				*delegations = delegations.saturating_sub(amount);
+				for &(poll_index, account_vote) in votes.iter() {
+					if let AccountVote::Standard { vote, .. } = account_vote {
+						T::Polls::access_poll(poll_index, |poll_status| {
+							if let PollStatus::Ongoing(tally, _) = poll_status {
+								tally.reduce(vote.aye, amount);
+							}
+						});
+					}
+				}
+				votes.len() as u32
+			}
+		})
+	}
+
+	/// Attempt to delegate `balance` times `conviction` of voting power from `who` to `target`.
+	///
+	/// Return the upstream number of votes.
+	fn try_delegate(
+		who: T::AccountId,
+		class: ClassOf<T>,
+		target: T::AccountId,
+		conviction: Conviction,
+		balance: BalanceOf<T>,
+	) -> Result<u32, DispatchError> {
+		ensure!(who != target, Error::<T>::Nonsense);
+		ensure!(T::Polls::classes().contains(&class), Error::<T>::BadClass);
+		ensure!(balance <= T::Currency::free_balance(&who), Error::<T>::InsufficientFunds);
+		let votes = VotingFor::<T>::try_mutate(&who, &class, |voting| -> Result<u32, DispatchError> {
+			let old = sp_std::mem::replace(voting, Voting::Delegating(Delegating {
+				balance,
+				target: target.clone(),
+				conviction,
+				delegations: Default::default(),
+				prior: Default::default(),
+			}));
+			match old {
+				Voting::Delegating(Delegating { .. }) =>
+					Err(Error::<T>::AlreadyDelegating)?,
+				Voting::Casting(Casting { votes, delegations, prior }) => {
+					// here we just ensure that we're currently idling with no votes recorded.
+					ensure!(votes.is_empty(), Error::<T>::AlreadyVoting);
+					voting.set_common(delegations, prior);
+				}
+			}
+			let votes = Self::increase_upstream_delegation(&target, &class, conviction.votes(balance));
+			// Extend the lock to `balance` (rather than setting it) since we don't know what other
+			// votes are in place.
+			Self::extend_lock(&who, &class, balance);
+			Ok(votes)
+		})?;
+		Self::deposit_event(RawEvent::Delegated(who, target));
+		Ok(votes)
+	}
+
+	/// Attempt to end the current delegation.
+	///
+	/// Return the number of votes of upstream.
+	fn try_undelegate(who: T::AccountId, class: ClassOf<T>) -> Result<u32, DispatchError> {
+		let votes = VotingFor::<T>::try_mutate(&who, &class, |voting| -> Result<u32, DispatchError> {
+			match sp_std::mem::replace(voting, Voting::default()) {
+				Voting::Delegating(Delegating {
+					balance,
+					target,
+					conviction,
+					delegations,
+					mut prior,
+				}) => {
+					// remove any delegation votes to our current target.
+					let votes = Self::reduce_upstream_delegation(&target, &class, conviction.votes(balance));
+					let now = system::Module::<T>::block_number();
+					let lock_periods = conviction.lock_periods().into();
+					prior.accumulate(
+						now.saturating_add(T::VoteLockingPeriod::get().saturating_mul(lock_periods)),
+						balance,
+					);
+					voting.set_common(delegations, prior);
+
+					Ok(votes)
+				}
+				Voting::Casting(_) => Err(Error::<T>::NotDelegating.into()),
+			}
+		})?;
+		Self::deposit_event(RawEvent::Undelegated(who));
+		Ok(votes)
+	}
+
+	/// Extend the lock of `who` for `class` to at least `amount`.
+	fn extend_lock(who: &T::AccountId, class: &ClassOf<T>, amount: BalanceOf<T>) {
+		ClassLocksFor::<T>::mutate(who, |locks| {
+			match locks.iter().position(|x| &x.0 == class) {
+				Some(i) => locks[i].1 = locks[i].1.max(amount),
+				None => locks.push((class.clone(), amount)),
+			}
+		});
+		T::Currency::extend_lock(CONVICTION_VOTING_ID, who, amount, WithdrawReason::Transfer.into());
+	}
+
+	/// Rejig the lock on an account. It will never get more stringent (since that would indicate
+	/// a security hole) but may be reduced from what they are currently.
+	fn update_lock(class: &ClassOf<T>, who: &T::AccountId) {
+		let class_lock_needed = VotingFor::<T>::mutate(who, class, |voting| {
+			voting.rejig(system::Module::<T>::block_number());
+			voting.locked_balance()
+		});
+		let lock_needed = ClassLocksFor::<T>::mutate(who, |locks| {
+			locks.retain(|x| &x.0 != class);
+			if !class_lock_needed.is_zero() {
+				locks.push((class.clone(), class_lock_needed));
+			}
+			locks.iter().map(|x| x.1).max().unwrap_or(Zero::zero())
+		});
+		if lock_needed.is_zero() {
+			T::Currency::remove_lock(CONVICTION_VOTING_ID, who);
+		} else {
+			T::Currency::set_lock(CONVICTION_VOTING_ID, who, lock_needed, WithdrawReason::Transfer.into());
+		}
+	}
+}
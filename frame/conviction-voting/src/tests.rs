@@ -0,0 +1,477 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The crate's tests.
+
+use super::*;
+use std::{cell::RefCell, collections::BTreeMap};
+use frame_support::{
+	impl_outer_origin, impl_outer_dispatch, impl_outer_event, assert_noop, assert_ok,
+	parameter_types, weights::Weight,
+};
+use sp_core::H256;
+use sp_runtime::{
+	traits::{BlakeTwo256, IdentityLookup},
+	testing::Header, Perbill,
+};
+use pallet_balances::{BalanceLock, Reasons};
+
+impl_outer_origin! {
+	pub enum Origin for Test where system = frame_system {}
+}
+
+impl_outer_dispatch! {
+	pub enum Call for Test where origin: Origin {
+		frame_system::System,
+		pallet_balances::Balances,
+		conviction_voting::ConvictionVoting,
+	}
+}
+
+mod conviction_voting {
+	pub use crate::Event;
+}
+
+impl_outer_event! {
+	pub enum Event for Test {
+		system<T>,
+		pallet_balances<T>,
+		conviction_voting<T>,
+	}
+}
+
+// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Test;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1_000_000;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+impl frame_system::Trait for Test {
+	type BaseCallFilter = ();
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Call = Call;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type PalletInfo = ();
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+}
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+impl pallet_balances::Trait for Test {
+	type MaxLocks = ();
+	type Balance = u64;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum TestPollState {
+	Ongoing(TallyOf<Test>, u8),
+	Completed(u64, bool),
+}
+use TestPollState::*;
+
+thread_local! {
+	static POLLS: RefCell<BTreeMap<u8, TestPollState>> = RefCell::new(vec![
+		(1, Completed(1, true)),
+		(2, Completed(2, false)),
+		(3, Ongoing(Tally::from_parts(0, 0, 0), 0)),
+	].into_iter().collect());
+}
+
+pub struct TestPolls;
+impl TestPolls {
+	fn set(index: u8, state: TestPollState) {
+		POLLS.with(|p| p.borrow_mut().insert(index, state));
+	}
+}
+
+impl Polling<TallyOf<Test>> for TestPolls {
+	type Index = u8;
+	type Votes = u64;
+	type Moment = u64;
+	type Class = u8;
+	fn classes() -> Vec<u8> {
+		vec![0, 1, 2]
+	}
+	fn as_ongoing(index: u8) -> Option<(TallyOf<Test>, Self::Class)> {
+		POLLS.with(|p| p.borrow().get(&index).and_then(|x| match x {
+			Ongoing(t, c) => Some((t.clone(), *c)),
+			_ => None,
+		}))
+	}
+	fn access_poll<R>(
+		index: Self::Index,
+		f: impl FnOnce(PollStatus<&mut TallyOf<Test>, u64, u8>) -> R,
+	) -> R {
+		let mut polls = POLLS.with(|p| p.borrow().clone());
+		let r = match polls.get_mut(&index) {
+			Some(Ongoing(ref mut tally_mut_ref, class)) =>
+				f(PollStatus::Ongoing(tally_mut_ref, *class)),
+			Some(Completed(when, succeeded)) => f(PollStatus::Completed(*when, *succeeded)),
+			None => f(PollStatus::None),
+		};
+		POLLS.with(|p| *p.borrow_mut() = polls);
+		r
+	}
+	fn try_access_poll<R>(
+		index: Self::Index,
+		f: impl FnOnce(PollStatus<&mut TallyOf<Test>, u64, u8>) -> Result<R, DispatchError>,
+	) -> Result<R, DispatchError> {
+		let mut polls = POLLS.with(|p| p.borrow().clone());
+		let r = match polls.get_mut(&index) {
+			Some(Ongoing(ref mut tally_mut_ref, class)) =>
+				f(PollStatus::Ongoing(tally_mut_ref, *class)),
+			Some(Completed(when, succeeded)) => f(PollStatus::Completed(*when, *succeeded)),
+			None => f(PollStatus::None),
+		}?;
+		POLLS.with(|p| *p.borrow_mut() = polls);
+		Ok(r)
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn create_ongoing(class: u8) -> Result<u8, ()> {
+		POLLS.with(|p| {
+			let mut polls = p.borrow_mut();
+			let index = polls.keys().rev().next().map_or(0, |i| i + 1);
+			polls.insert(index, Ongoing(Tally::default(), class));
+			Ok(index)
+		})
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn end_ongoing(index: u8, approved: bool) -> Result<(), ()> {
+		let now = frame_system::Module::<Test>::block_number();
+		POLLS.with(|p| match p.borrow_mut().get_mut(&index) {
+			Some(poll @ Ongoing(..)) => {
+				*poll = Completed(now, approved);
+				Ok(())
+			},
+			_ => Err(()),
+		})
+	}
+}
+
+parameter_types! {
+	pub const MaxVotes: u32 = 3;
+	pub const VoteLockingPeriod: u64 = 3;
+}
+impl super::Trait for Test {
+	type Event = Event;
+	type Currency = pallet_balances::Module<Self>;
+	type Polls = TestPolls;
+	type MaxVotes = MaxVotes;
+	type VoteLockingPeriod = VoteLockingPeriod;
+	type WeightInfo = ();
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test>{
+		balances: vec![(1, 10), (2, 20), (3, 30), (4, 40), (5, 50), (6, 60)],
+	}.assimilate_storage(&mut t).unwrap();
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+type System = frame_system::Module<Test>;
+type Balances = pallet_balances::Module<Test>;
+type ConvictionVoting = Module<Test>;
+
+fn next_block() {
+	System::set_block_number(System::block_number() + 1);
+}
+
+fn run_to(n: u64) {
+	while System::block_number() < n {
+		next_block();
+	}
+}
+
+fn aye(amount: u64) -> AccountVote<u64> {
+	let vote = Vote { aye: true, conviction: Conviction::None };
+	AccountVote::Standard { vote, balance: amount }
+}
+
+fn nay(amount: u64) -> AccountVote<u64> {
+	let vote = Vote { aye: false, conviction: Conviction::None };
+	AccountVote::Standard { vote, balance: amount }
+}
+
+fn big_aye(amount: u64) -> AccountVote<u64> {
+	let vote = Vote { aye: true, conviction: Conviction::Locked1x };
+	AccountVote::Standard { vote, balance: amount }
+}
+
+fn tally(index: u8) -> TallyOf<Test> {
+	<TestPolls as Polling<TallyOf<Test>>>::as_ongoing(index).expect("No poll").0
+}
+
+fn class(index: u8) -> u8 {
+	<TestPolls as Polling<TallyOf<Test>>>::as_ongoing(index).expect("No poll").1
+}
+
+fn locks(who: u64) -> Vec<BalanceLock<u64>> {
+	Balances::locks(who)
+}
+
+fn voting_lock(amount: u64) -> BalanceLock<u64> {
+	BalanceLock { id: CONVICTION_VOTING_ID, amount, reasons: Reasons::Misc }
+}
+
+#[test]
+fn params_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Balances::free_balance(42), 0);
+		assert_eq!(Balances::total_issuance(), 210);
+	});
+}
+
+#[test]
+fn basic_stuff() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ConvictionVoting::vote(Origin::signed(1), 3, aye(2)));
+		assert_ok!(ConvictionVoting::vote(Origin::signed(2), 3, nay(4)));
+		assert_eq!(tally(3), Tally::from_parts(2, 4, 2));
+		assert_eq!(locks(1), vec![voting_lock(2)]);
+
+		// Changing the vote removes the old one from the tally.
+		assert_ok!(ConvictionVoting::vote(Origin::signed(1), 3, big_aye(5)));
+		assert_eq!(tally(3), Tally::from_parts(5, 4, 5));
+		assert_eq!(locks(1), vec![voting_lock(5)]);
+
+		assert_ok!(ConvictionVoting::remove_vote(Origin::signed(1), None, 3));
+		assert_eq!(tally(3), Tally::from_parts(0, 4, 0));
+		assert_ok!(ConvictionVoting::unlock(Origin::signed(1), class(3), 1));
+		assert!(locks(1).is_empty());
+	});
+}
+
+#[test]
+fn voting_on_completed_poll_is_not_allowed() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ConvictionVoting::vote(Origin::signed(1), 1, aye(2)),
+			Error::<Test>::NotOngoing,
+		);
+		assert_noop!(
+			ConvictionVoting::vote(Origin::signed(1), 4, aye(2)),
+			Error::<Test>::NotOngoing,
+		);
+		assert_noop!(
+			ConvictionVoting::vote(Origin::signed(1), 3, aye(11)),
+			Error::<Test>::InsufficientFunds,
+		);
+	});
+}
+
+#[test]
+fn max_votes_per_class_is_enforced() {
+	new_test_ext().execute_with(|| {
+		for i in 10..13 {
+			TestPolls::set(i, Ongoing(Tally::default(), 0));
+			assert_ok!(ConvictionVoting::vote(Origin::signed(1), i, aye(1)));
+		}
+		TestPolls::set(13, Ongoing(Tally::default(), 0));
+		assert_noop!(
+			ConvictionVoting::vote(Origin::signed(1), 13, aye(1)),
+			Error::<Test>::MaxVotesReached,
+		);
+		// Votes on another class are counted separately.
+		TestPolls::set(14, Ongoing(Tally::default(), 1));
+		assert_ok!(ConvictionVoting::vote(Origin::signed(1), 14, aye(1)));
+	});
+}
+
+#[test]
+fn delegation_is_per_class() {
+	new_test_ext().execute_with(|| {
+		TestPolls::set(10, Ongoing(Tally::default(), 1));
+		assert_ok!(ConvictionVoting::vote(Origin::signed(1), 3, aye(1)));
+		assert_ok!(ConvictionVoting::vote(Origin::signed(1), 10, aye(1)));
+
+		// Delegating on class 0 only affects the poll of class 0.
+		assert_ok!(ConvictionVoting::delegate(Origin::signed(2), 0, 1, Conviction::Locked1x, 5));
+		assert_eq!(tally(3), Tally::from_parts(6, 0, 6));
+		assert_eq!(tally(10), Tally::from_parts(1, 0, 1));
+
+		// The delegator may still vote directly on other classes.
+		assert_ok!(ConvictionVoting::vote(Origin::signed(2), 10, nay(3)));
+		assert_eq!(tally(10), Tally::from_parts(1, 3, 1));
+
+		// But not on the delegated class.
+		assert_noop!(
+			ConvictionVoting::vote(Origin::signed(2), 3, aye(3)),
+			Error::<Test>::AlreadyDelegating,
+		);
+		assert_noop!(
+			ConvictionVoting::delegate(Origin::signed(2), 0, 3, Conviction::None, 5),
+			Error::<Test>::AlreadyDelegating,
+		);
+
+		assert_ok!(ConvictionVoting::undelegate(Origin::signed(2), 0));
+		assert_eq!(tally(3), Tally::from_parts(1, 0, 1));
+		assert_noop!(
+			ConvictionVoting::undelegate(Origin::signed(2), 0),
+			Error::<Test>::NotDelegating,
+		);
+	});
+}
+
+#[test]
+fn delegation_checks_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ConvictionVoting::delegate(Origin::signed(1), 0, 1, Conviction::None, 5),
+			Error::<Test>::Nonsense,
+		);
+		assert_noop!(
+			ConvictionVoting::delegate(Origin::signed(1), 3, 2, Conviction::None, 5),
+			Error::<Test>::BadClass,
+		);
+		assert_noop!(
+			ConvictionVoting::delegate(Origin::signed(1), 0, 2, Conviction::None, 11),
+			Error::<Test>::InsufficientFunds,
+		);
+		assert_ok!(ConvictionVoting::vote(Origin::signed(1), 3, aye(1)));
+		assert_noop!(
+			ConvictionVoting::delegate(Origin::signed(1), 0, 2, Conviction::None, 5),
+			Error::<Test>::AlreadyVoting,
+		);
+	});
+}
+
+#[test]
+fn undelegation_locks_for_conviction_period() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ConvictionVoting::delegate(Origin::signed(2), 0, 1, Conviction::Locked2x, 10));
+		assert_eq!(locks(2), vec![voting_lock(10)]);
+		assert_ok!(ConvictionVoting::undelegate(Origin::signed(2), 0));
+
+		// Locked for two lock periods of three blocks from block 1.
+		run_to(6);
+		assert_ok!(ConvictionVoting::unlock(Origin::signed(2), 0, 2));
+		assert_eq!(locks(2), vec![voting_lock(10)]);
+		run_to(7);
+		assert_ok!(ConvictionVoting::unlock(Origin::signed(2), 0, 2));
+		assert!(locks(2).is_empty());
+	});
+}
+
+#[test]
+fn lock_is_the_maximum_over_classes() {
+	new_test_ext().execute_with(|| {
+		TestPolls::set(10, Ongoing(Tally::default(), 1));
+		assert_ok!(ConvictionVoting::vote(Origin::signed(1), 3, aye(3)));
+		assert_ok!(ConvictionVoting::vote(Origin::signed(1), 10, aye(7)));
+		assert_eq!(locks(1), vec![voting_lock(7)]);
+
+		assert_ok!(ConvictionVoting::remove_vote(Origin::signed(1), None, 10));
+		assert_ok!(ConvictionVoting::unlock(Origin::signed(1), 1, 1));
+		assert_eq!(locks(1), vec![voting_lock(3)]);
+		assert_eq!(ConvictionVoting::class_locks_for(1), vec![(0, 3)]);
+
+		assert_ok!(ConvictionVoting::remove_vote(Origin::signed(1), None, 3));
+		assert_ok!(ConvictionVoting::unlock(Origin::signed(1), 0, 1));
+		assert!(locks(1).is_empty());
+		assert!(ConvictionVoting::class_locks_for(1).is_empty());
+	});
+}
+
+#[test]
+fn removing_winning_vote_with_conviction_keeps_lock() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ConvictionVoting::vote(Origin::signed(1), 3, big_aye(5)));
+		assert_ok!(ConvictionVoting::vote(Origin::signed(2), 3, big_aye(5)));
+		TestPolls::set(3, Completed(2, true));
+
+		// The poll is completed, so the class must be given.
+		assert_noop!(
+			ConvictionVoting::remove_vote(Origin::signed(1), None, 3),
+			Error::<Test>::ClassNeeded,
+		);
+		// Others may not remove a winning vote before the lock period is over.
+		assert_noop!(
+			ConvictionVoting::remove_other_vote(Origin::signed(3), 1, 0, 3),
+			Error::<Test>::NoPermissionYet,
+		);
+
+		assert_ok!(ConvictionVoting::remove_vote(Origin::signed(1), Some(0), 3));
+		assert_ok!(ConvictionVoting::unlock(Origin::signed(1), 0, 1));
+		assert_eq!(locks(1), vec![voting_lock(5)]);
+
+		// Lock period ends three blocks after completion.
+		run_to(5);
+		assert_ok!(ConvictionVoting::unlock(Origin::signed(1), 0, 1));
+		assert!(locks(1).is_empty());
+		assert_ok!(ConvictionVoting::remove_other_vote(Origin::signed(3), 2, 0, 3));
+		assert_ok!(ConvictionVoting::unlock(Origin::signed(3), 0, 2));
+		assert!(locks(2).is_empty());
+	});
+}
+
+#[test]
+fn losing_vote_can_be_removed_by_anyone() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ConvictionVoting::vote(Origin::signed(1), 3, big_aye(5)));
+		TestPolls::set(3, Completed(2, false));
+		assert_ok!(ConvictionVoting::remove_other_vote(Origin::signed(3), 1, 0, 3));
+		assert_ok!(ConvictionVoting::unlock(Origin::signed(3), 0, 1));
+		assert!(locks(1).is_empty());
+	});
+}
+
+#[test]
+fn others_cannot_remove_ongoing_vote() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ConvictionVoting::vote(Origin::signed(1), 3, aye(5)));
+		assert_noop!(
+			ConvictionVoting::remove_other_vote(Origin::signed(2), 1, 0, 3),
+			Error::<Test>::NoPermission,
+		);
+		assert_noop!(
+			ConvictionVoting::remove_other_vote(Origin::signed(2), 2, 0, 3),
+			Error::<Test>::NotVoter,
+		);
+	});
+}
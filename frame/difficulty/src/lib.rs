@@ -0,0 +1,159 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Difficulty Pallet
+//!
+//! - [`difficulty::Trait`](./trait.Trait.html)
+//!
+//! ## Overview
+//!
+//! The Difficulty pallet keeps the proof-of-work difficulty of the next block on chain, and
+//! retargets it after every block from the timestamps of recent blocks, aiming for a configured
+//! `TargetBlockTime`.
+//!
+//! Retargeting compares the time taken by the last `AdjustmentWindow` blocks against the
+//! expected time. The observed time is damped towards the expected one and clamped to a bounded
+//! range before the difficulty is scaled, so that a few unusually fast or slow blocks cannot swing
+//! the difficulty sharply. The result is kept between `MinDifficulty` and `MaxDifficulty`.
+//!
+//! The pallet must be registered as (part of) the timestamp pallet's `OnTimestampSet` handler.
+//! The client reads the difficulty through the `sp_consensus_pow::DifficultyApi` runtime API,
+//! which can be implemented with [`Module::difficulty`].
+//!
+//! ## Interface
+//!
+//! ### Public Functions
+//!
+//! - `difficulty` - The difficulty the next block must be mined at.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_std::prelude::*;
+use sp_core::U256;
+use sp_runtime::traits::SaturatedConversion;
+use frame_support::{decl_module, decl_storage, traits::Get, weights::Weight};
+
+#[cfg(test)]
+mod tests;
+
+/// Weight of the observed block time relative to the expected one is `1 / DAMP_FACTOR`.
+pub const DAMP_FACTOR: u128 = 3;
+
+/// The damped block time is clamped to within this factor of the expected block time.
+pub const CLAMP_FACTOR: u128 = 2;
+
+pub trait Trait: pallet_timestamp::Trait {
+	/// The block time the difficulty is retargeted towards, in the unit of the timestamp.
+	type TargetBlockTime: Get<Self::Moment>;
+
+	/// The number of most recent blocks considered when retargeting.
+	type AdjustmentWindow: Get<u32>;
+
+	/// The lowest difficulty retargeting may reach.
+	type MinDifficulty: Get<U256>;
+
+	/// The highest difficulty retargeting may reach.
+	type MaxDifficulty: Get<U256>;
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Difficulty {
+		/// The difficulty of the next block, before bounds are applied.
+		CurrentDifficulty config(initial_difficulty): U256;
+
+		/// Timestamps of the most recent blocks, oldest first. At most `AdjustmentWindow + 1`
+		/// entries are kept, spanning `AdjustmentWindow` block intervals.
+		pub PastTimestamps get(fn past_timestamps): Vec<T::Moment>;
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		/// The block time the difficulty is retargeted towards.
+		const TargetBlockTime: T::Moment = T::TargetBlockTime::get();
+
+		/// The number of most recent blocks considered when retargeting.
+		const AdjustmentWindow: u32 = T::AdjustmentWindow::get();
+
+		/// The lowest difficulty retargeting may reach.
+		const MinDifficulty: U256 = T::MinDifficulty::get();
+
+		/// The highest difficulty retargeting may reach.
+		const MaxDifficulty: U256 = T::MaxDifficulty::get();
+
+		fn on_initialize() -> Weight {
+			// Accounts for the retarget done once the timestamp is set.
+			T::DbWeight::get().reads_writes(2, 2)
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// The difficulty the next block must be mined at.
+	pub fn difficulty() -> U256 {
+		CurrentDifficulty::get()
+			.max(T::MinDifficulty::get())
+			.min(T::MaxDifficulty::get())
+	}
+
+	/// Record the timestamp of the current block and retarget the difficulty for the next one.
+	fn note_timestamp(now: T::Moment) {
+		let window = T::AdjustmentWindow::get() as usize;
+		let mut timestamps = PastTimestamps::<T>::get();
+		timestamps.push(now);
+		if timestamps.len() > window + 1 {
+			let excess = timestamps.len() - (window + 1);
+			timestamps.drain(..excess);
+		}
+
+		let target = T::TargetBlockTime::get().saturated_into::<u128>();
+		let first = timestamps.first().copied().unwrap_or(now).saturated_into::<u128>();
+		let last = now.saturated_into::<u128>();
+		let intervals = (timestamps.len() - 1) as u128;
+
+		if let Some(next) = retarget(Self::difficulty(), last.saturating_sub(first), intervals, target) {
+			let next = next.max(T::MinDifficulty::get()).min(T::MaxDifficulty::get());
+			CurrentDifficulty::put(next);
+		}
+		PastTimestamps::<T>::put(timestamps);
+	}
+}
+
+/// Compute the difficulty after `intervals` blocks took `elapsed` in total, for a `target` block
+/// time. Returns `None` if there is nothing to retarget from.
+pub fn retarget(current: U256, elapsed: u128, intervals: u128, target: u128) -> Option<U256> {
+	let expected = target.saturating_mul(intervals);
+	if expected == 0 {
+		return None
+	}
+
+	let damped = elapsed
+		.saturating_add(expected.saturating_mul(DAMP_FACTOR - 1))
+		/ DAMP_FACTOR;
+	let clamped = damped
+		.max(expected / CLAMP_FACTOR)
+		.min(expected.saturating_mul(CLAMP_FACTOR))
+		.max(1);
+
+	Some(current.saturating_mul(U256::from(expected)) / U256::from(clamped))
+}
+
+impl<T: Trait> sp_timestamp::OnTimestampSet<T::Moment> for Module<T> {
+	fn on_timestamp_set(moment: T::Moment) {
+		Self::note_timestamp(moment)
+	}
+}
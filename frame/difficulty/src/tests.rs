@@ -0,0 +1,190 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The crate's tests.
+
+use super::*;
+use frame_support::{impl_outer_origin, parameter_types, weights::Weight};
+use sp_core::H256;
+use sp_runtime::{
+	Perbill,
+	traits::{BlakeTwo256, IdentityLookup},
+	testing::Header,
+};
+use sp_timestamp::OnTimestampSet;
+
+impl_outer_origin! {
+	pub enum Origin for Test where system = frame_system {}
+}
+
+// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Test;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1_000_000;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+impl frame_system::Trait for Test {
+	type BaseCallFilter = ();
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Call = ();
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = ();
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type PalletInfo = ();
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+}
+parameter_types! {
+	pub const MinimumPeriod: u64 = 1;
+}
+impl pallet_timestamp::Trait for Test {
+	type Moment = u64;
+	type OnTimestampSet = Difficulty;
+	type MinimumPeriod = MinimumPeriod;
+	type WeightInfo = ();
+}
+parameter_types! {
+	pub const TargetBlockTime: u64 = 6_000;
+	pub const AdjustmentWindow: u32 = 4;
+	pub MinDifficulty: U256 = U256::from(100);
+	pub MaxDifficulty: U256 = U256::from(1_000_000);
+}
+impl Trait for Test {
+	type TargetBlockTime = TargetBlockTime;
+	type AdjustmentWindow = AdjustmentWindow;
+	type MinDifficulty = MinDifficulty;
+	type MaxDifficulty = MaxDifficulty;
+}
+
+type Difficulty = Module<Test>;
+
+fn new_test_ext(initial_difficulty: u64) -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	GenesisConfig {
+		initial_difficulty: U256::from(initial_difficulty),
+	}.assimilate_storage::<Test>(&mut t).unwrap();
+	t.into()
+}
+
+/// Note blocks produced `interval` apart, starting right after `start`.
+fn produce_blocks(start: u64, interval: u64, count: u64) -> u64 {
+	let mut now = start;
+	for _ in 0..count {
+		now += interval;
+		Difficulty::on_timestamp_set(now);
+	}
+	now
+}
+
+#[test]
+fn first_block_does_not_retarget() {
+	new_test_ext(10_000).execute_with(|| {
+		Difficulty::on_timestamp_set(1_000);
+		assert_eq!(Difficulty::difficulty(), U256::from(10_000));
+		assert_eq!(Difficulty::past_timestamps(), vec![1_000]);
+	});
+}
+
+#[test]
+fn difficulty_is_stable_on_target() {
+	new_test_ext(10_000).execute_with(|| {
+		produce_blocks(0, 6_000, 20);
+		assert_eq!(Difficulty::difficulty(), U256::from(10_000));
+	});
+}
+
+#[test]
+fn fast_blocks_raise_difficulty() {
+	new_test_ext(10_000).execute_with(|| {
+		let now = produce_blocks(0, 6_000, 5);
+		produce_blocks(now, 2_000, 1);
+		let raised = Difficulty::difficulty();
+		assert!(raised > U256::from(10_000));
+
+		// The change is bounded by the damping and clamping of a single fast interval.
+		assert!(raised < U256::from(20_000));
+	});
+}
+
+#[test]
+fn slow_blocks_lower_difficulty() {
+	new_test_ext(10_000).execute_with(|| {
+		let now = produce_blocks(0, 6_000, 5);
+		produce_blocks(now, 60_000, 1);
+		let lowered = Difficulty::difficulty();
+		assert!(lowered < U256::from(10_000));
+		assert!(lowered >= U256::from(5_000));
+	});
+}
+
+#[test]
+fn only_window_is_kept() {
+	new_test_ext(10_000).execute_with(|| {
+		produce_blocks(0, 6_000, 10);
+		assert_eq!(
+			Difficulty::past_timestamps(),
+			vec![36_000, 42_000, 48_000, 54_000, 60_000],
+		);
+	});
+}
+
+#[test]
+fn difficulty_respects_bounds() {
+	new_test_ext(0).execute_with(|| {
+		assert_eq!(Difficulty::difficulty(), U256::from(100));
+		produce_blocks(0, 600_000, 10);
+		assert_eq!(Difficulty::difficulty(), U256::from(100));
+	});
+
+	new_test_ext(900_000).execute_with(|| {
+		produce_blocks(0, 1, 10);
+		assert_eq!(Difficulty::difficulty(), U256::from(1_000_000));
+	});
+}
+
+#[test]
+fn retarget_works() {
+	// Exactly on target.
+	assert_eq!(retarget(U256::from(1_000), 60, 10, 6), Some(U256::from(1_000)));
+	// Twice as slow: damped to 80, difficulty scaled by 60 / 80.
+	assert_eq!(retarget(U256::from(1_000), 120, 10, 6), Some(U256::from(750)));
+	// Instant blocks are damped to two thirds of the expected time.
+	assert_eq!(retarget(U256::from(1_000), 0, 10, 6), Some(U256::from(1_500)));
+	// Very slow blocks are clamped to twice the expected time.
+	assert_eq!(retarget(U256::from(1_000), 6_000, 10, 6), Some(U256::from(500)));
+	// Nothing to retarget from.
+	assert_eq!(retarget(U256::from(1_000), 60, 0, 6), None);
+}
@@ -117,7 +117,7 @@
 
 use sp_std::{prelude::*, marker::PhantomData};
 use frame_support::{
-	storage::StorageValue, weights::{GetDispatchInfo, DispatchInfo, DispatchClass},
+	storage::StorageValue, weights::{GetDispatchInfo, DispatchInfo, DispatchClass, Weight},
 	traits::{OnInitialize, OnFinalize, OnRuntimeUpgrade, OffchainWorker},
 	dispatch::PostDispatchInfo,
 };
@@ -205,6 +205,16 @@ where
 	OriginOf<Block::Extrinsic, Context>: From<Option<System::AccountId>>,
 	UnsignedValidator: ValidateUnsigned<Call=CallOf<Block::Extrinsic, Context>>,
 {
+	/// Execute all `OnRuntimeUpgrade` hooks of this runtime and return the aggregate weight.
+	///
+	/// This does not check nor record whether the runtime was actually upgraded.
+	pub fn execute_on_runtime_upgrade() -> Weight {
+		// System is not part of `AllModules`, so we need to call this manually.
+		let mut weight = <frame_system::Module::<System> as OnRuntimeUpgrade>::on_runtime_upgrade();
+		weight = weight.saturating_add(COnRuntimeUpgrade::on_runtime_upgrade());
+		weight.saturating_add(<AllModules as OnRuntimeUpgrade>::on_runtime_upgrade())
+	}
+
 	/// Start the execution of a particular block.
 	pub fn initialize_block(header: &System::Header) {
 		sp_io::init_tracing();
@@ -235,10 +245,7 @@ where
 		digest: &Digest<System::Hash>,
 	) {
 		if Self::runtime_upgraded() {
-			// System is not part of `AllModules`, so we need to call this manually.
-			let mut weight = <frame_system::Module::<System> as OnRuntimeUpgrade>::on_runtime_upgrade();
-			weight = weight.saturating_add(COnRuntimeUpgrade::on_runtime_upgrade());
-			weight = weight.saturating_add(<AllModules as OnRuntimeUpgrade>::on_runtime_upgrade());
+			let weight = Self::execute_on_runtime_upgrade();
 			<frame_system::Module<System>>::register_extra_weight_unchecked(weight, DispatchClass::Mandatory);
 		}
 		<frame_system::Module<System>>::initialize(
@@ -315,6 +322,25 @@ where
 		} };
 	}
 
+	/// Execute all transitions for `block` without checking the resulting digest and state root,
+	/// returning the weight the block consumed.
+	///
+	/// Meant to replay blocks against a state that was altered after they were authored, e.g. by
+	/// a runtime upgrade that was not enacted yet.
+	#[cfg(feature = "try-runtime")]
+	pub fn execute_block_no_check(block: Block) -> Weight {
+		Self::initialize_block(block.header());
+		Self::initial_checks(&block);
+
+		let (header, extrinsics) = block.deconstruct();
+		Self::execute_extrinsics_with_book_keeping(extrinsics, *header.number());
+
+		let weight = <frame_system::Module<System>>::block_weight().total();
+		// remove temporaries
+		<frame_system::Module<System>>::finalize();
+		weight
+	}
+
 	/// Execute given extrinsics and take care of post-extrinsics book-keeping.
 	fn execute_extrinsics_with_book_keeping(extrinsics: Vec<Block::Extrinsic>, block_number: NumberFor<Block>) {
 		extrinsics.into_iter().for_each(Self::apply_extrinsic_no_note);
@@ -1058,4 +1084,17 @@ mod tests {
 			assert_eq!(sp_io::storage::get(CUSTOM_ON_RUNTIME_KEY).unwrap(), true.encode());
 		});
 	}
+
+	#[test]
+	fn execute_on_runtime_upgrade_calls_hooks_without_noting_upgrade() {
+		new_test_ext(1).execute_with(|| {
+			let last = frame_system::LastRuntimeUpgrade::get();
+
+			assert_eq!(Executive::execute_on_runtime_upgrade(), 0);
+
+			assert_eq!(&sp_io::storage::get(TEST_KEY).unwrap()[..], *b"module");
+			assert_eq!(sp_io::storage::get(CUSTOM_ON_RUNTIME_KEY).unwrap(), true.encode());
+			assert_eq!(frame_system::LastRuntimeUpgrade::get(), last);
+		});
+	}
 }
@@ -227,6 +227,9 @@ impl pallet_staking::Trait for Test {
 	type UnixTime = pallet_timestamp::Module<Test>;
 	type RewardCurve = RewardCurve;
 	type MaxNominatorRewardedPerValidator = MaxNominatorRewardedPerValidator;
+	type Scheduler = ();
+	type PalletsOrigin = frame_system::RawOrigin<Self::AccountId>;
+	type MaxAutoPayoutsPerBlock = ();
 	type NextNewSession = Session;
 	type ElectionLookahead = ElectionLookahead;
 	type Call = Call;
@@ -0,0 +1,43 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime API definition for the identity pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_std::{prelude::*, fmt::Debug};
+use codec::Codec;
+
+pub use pallet_identity::{IdentityView, RegistrarIndex, RegistrarInfo, RegistrarProfile};
+
+sp_api::decl_runtime_apis! {
+	pub trait IdentityApi<AccountId, Balance, BlockNumber> where
+		AccountId: Codec + Clone + Debug + Eq + PartialEq,
+		Balance: Codec + Copy + Clone + Debug + Eq + PartialEq,
+		BlockNumber: Codec,
+	{
+		/// Everything known about the identity of `who`.
+		fn identity(who: AccountId) -> IdentityView<AccountId, Balance, BlockNumber>;
+
+		/// The registrars together with their profiles.
+		fn registrars() -> Vec<(
+			RegistrarIndex,
+			RegistrarInfo<Balance, AccountId>,
+			Option<RegistrarProfile>,
+		)>;
+	}
+}
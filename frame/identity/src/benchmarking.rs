@@ -106,6 +106,23 @@ fn create_identity_info<T: Trait>(num_fields: u32) -> IdentityInfo {
 	return info
 }
 
+// All the fields of an identity.
+fn all_fields() -> IdentityFields {
+	IdentityFields(
+		IdentityField::Display | IdentityField::Legal | IdentityField::Web | IdentityField::Riot
+		| IdentityField::Email | IdentityField::PgpFingerprint | IdentityField::Image | IdentityField::Twitter
+	)
+}
+
+// This creates a `RegistrarProfile` checking every field.
+// All data is pre-populated with some arbitrary bytes.
+fn create_registrar_profile() -> RegistrarProfile {
+	let data = Data::Raw(vec![0; 32]);
+	let checks = all_fields().0.iter().map(|f| (f, data.clone())).collect();
+
+	RegistrarProfile { display: data.clone(), web: data, checks }
+}
+
 benchmarks! {
 	// These are the common parameters along with their instancing.
 	_ {
@@ -402,6 +419,124 @@ benchmarks! {
 		ensure!(!SuperOf::<T>::contains_key(&caller), "Sub not removed");
 	}
 
+	set_profile {
+		let caller: T::AccountId = whitelisted_caller();
+
+		let r in 1 .. T::MaxRegistrars::get() - 1 => add_registrars::<T>(r)?;
+
+		Identity::<T>::add_registrar(RawOrigin::Root.into(), caller.clone())?;
+		ensure!(!RegistrarProfiles::contains_key(r), "profile already set.");
+	}: _(RawOrigin::Signed(caller), r, create_registrar_profile())
+	verify {
+		ensure!(RegistrarProfiles::contains_key(r), "profile not set.");
+	}
+
+	request_bids {
+		let caller: T::AccountId = whitelisted_caller();
+		let caller_origin = <T as frame_system::Trait>::Origin::from(RawOrigin::Signed(caller.clone()));
+		let _ = T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		Identity::<T>::set_identity(caller_origin, create_identity_info::<T>(1))?;
+	}: _(RawOrigin::Signed(caller.clone()), 10.into(), all_fields())
+	verify {
+		assert_last_event::<T>(Event::<T>::JudgementBidsRequested(caller, 10.into()).into());
+	}
+
+	bid_judgement {
+		// The user
+		let user: T::AccountId = account("user", 0, SEED);
+		let user_origin = <T as frame_system::Trait>::Origin::from(RawOrigin::Signed(user.clone()));
+		let user_lookup = <T::Lookup as StaticLookup>::unlookup(user.clone());
+		let _ = T::Currency::make_free_balance_be(&user, BalanceOf::<T>::max_value());
+		Identity::<T>::set_identity(user_origin.clone(), create_identity_info::<T>(1))?;
+		Identity::<T>::request_bids(user_origin, 10.into(), all_fields())?;
+
+		let caller: T::AccountId = whitelisted_caller();
+
+		let r in 1 .. T::MaxRegistrars::get() - 1 => add_registrars::<T>(r)?;
+
+		// Every other registrar has already bid.
+		for i in 0..r {
+			Identity::<T>::bid_judgement(
+				RawOrigin::Signed(account("registrar", i, SEED)).into(),
+				i,
+				user_lookup.clone(),
+				10.into(),
+			)?;
+		}
+		Identity::<T>::add_registrar(RawOrigin::Root.into(), caller.clone())?;
+		Identity::<T>::set_fields(RawOrigin::Signed(caller.clone()).into(), r, all_fields())?;
+	}: _(RawOrigin::Signed(caller), r, user_lookup, 5.into())
+	verify {
+		assert_last_event::<T>(Event::<T>::JudgementBid(user, r, 5.into()).into());
+	}
+
+	accept_bid {
+		let caller: T::AccountId = whitelisted_caller();
+		let caller_origin = <T as frame_system::Trait>::Origin::from(RawOrigin::Signed(caller.clone()));
+		let caller_lookup = <T::Lookup as StaticLookup>::unlookup(caller.clone());
+		let _ = T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+
+		let r in ...;
+		let x in ...;
+
+		// Every registrar has bid.
+		Identity::<T>::request_bids(caller_origin, 10.into(), all_fields())?;
+		for i in 0..r {
+			Identity::<T>::bid_judgement(
+				RawOrigin::Signed(account("registrar", i, SEED)).into(),
+				i,
+				caller_lookup.clone(),
+				10.into(),
+			)?;
+		}
+	}: _(RawOrigin::Signed(caller.clone()), r - 1)
+	verify {
+		assert_last_event::<T>(Event::<T>::JudgementRequested(caller, r - 1).into());
+	}
+
+	cancel_bids {
+		let caller: T::AccountId = whitelisted_caller();
+		let caller_origin = <T as frame_system::Trait>::Origin::from(RawOrigin::Signed(caller.clone()));
+		let _ = T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		Identity::<T>::set_identity(caller_origin.clone(), create_identity_info::<T>(1))?;
+		Identity::<T>::request_bids(caller_origin, 10.into(), all_fields())?;
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert_last_event::<T>(Event::<T>::JudgementBidsCancelled(caller).into());
+	}
+
+	expire_judgement {
+		// The user
+		let user: T::AccountId = account("user", 0, SEED);
+		let user_origin = <T as frame_system::Trait>::Origin::from(RawOrigin::Signed(user.clone()));
+		let user_lookup = <T::Lookup as StaticLookup>::unlookup(user.clone());
+		let _ = T::Currency::make_free_balance_be(&user, BalanceOf::<T>::max_value());
+
+		let caller: T::AccountId = whitelisted_caller();
+
+		let r in ...;
+		// For this x, it's the user identity that gets the fields, not the caller.
+		let x in _ .. _ => {
+			let info = create_identity_info::<T>(x);
+			Identity::<T>::set_identity(user_origin.clone(), info)?;
+		};
+
+		// User requests judgement from all the registrars, and they approve
+		for i in 0..r {
+			Identity::<T>::request_judgement(user_origin.clone(), i, 10.into())?;
+			Identity::<T>::provide_judgement(
+				RawOrigin::Signed(account("registrar", i, SEED)).into(),
+				i,
+				user_lookup.clone(),
+				Judgement::Reasonable
+			)?;
+		}
+		let now = frame_system::Module::<T>::block_number();
+		frame_system::Module::<T>::set_block_number(now + T::JudgementExpiry::get());
+	}: _(RawOrigin::Signed(caller), user_lookup, r - 1)
+	verify {
+		assert_last_event::<T>(Event::<T>::JudgementExpired(user, r - 1).into());
+	}
 }
 
 #[cfg(test)]
@@ -429,6 +564,12 @@ mod tests {
 			assert_ok!(test_benchmark_rename_sub::<Test>());
 			assert_ok!(test_benchmark_remove_sub::<Test>());
 			assert_ok!(test_benchmark_quit_sub::<Test>());
+			assert_ok!(test_benchmark_set_profile::<Test>());
+			assert_ok!(test_benchmark_request_bids::<Test>());
+			assert_ok!(test_benchmark_bid_judgement::<Test>());
+			assert_ok!(test_benchmark_accept_bid::<Test>());
+			assert_ok!(test_benchmark_cancel_bids::<Test>());
+			assert_ok!(test_benchmark_expire_judgement::<Test>());
 		});
 	}
 }
@@ -40,6 +40,14 @@
 //! The number of registrars should be limited, and the deposit made sufficiently large, to ensure
 //! no state-bloat attack is viable.
 //!
+//! Registrars may publish a profile describing their service and how they check each identity
+//! field. Rather than picking a registrar up front, an account may open its judgement to bids
+//! from any registrar checking the fields it cares about, paying no more than a maximum fee, and
+//! accept whichever bid it prefers.
+//!
+//! Judgements other than sticky ones expire after `JudgementExpiry` blocks, after which anyone may
+//! remove them.
+//!
 //! ## Interface
 //!
 //! ### Dispatchable Functions
@@ -50,6 +58,10 @@
 //! * `clear_identity` - Remove an account's associated identity; the deposit is returned.
 //! * `request_judgement` - Request a judgement from a registrar, paying a fee.
 //! * `cancel_request` - Cancel the previous request for a judgement.
+//! * `request_bids` - Open a judgement request to bids from registrars, up to a maximum fee.
+//! * `accept_bid` - Accept a registrar's bid, turning it into a judgement request.
+//! * `cancel_bids` - Close an open request for bids.
+//! * `expire_judgement` - Remove a judgement which has expired.
 //!
 //! #### For general users with sub-identities
 //! * `set_subs` - Set the sub-accounts of an identity.
@@ -61,6 +73,8 @@
 //! #### For registrars
 //! * `set_fee` - Set the fee required to be paid for a judgement to be given by the registrar.
 //! * `set_fields` - Set the fields that a registrar cares about in their judgements.
+//! * `set_profile` - Publish a profile of the registrar and the fields it checks.
+//! * `bid_judgement` - Bid to provide a judgement for an account that requested bids.
 //! * `provide_judgement` - Provide a judgement to an identity.
 //!
 //! #### For super-users
@@ -111,6 +125,12 @@ pub trait WeightInfo {
 	fn set_fields(r: u32, ) -> Weight;
 	fn provide_judgement(r: u32, x: u32, ) -> Weight;
 	fn kill_identity(r: u32, s: u32, x: u32, ) -> Weight;
+	fn set_profile(r: u32, ) -> Weight;
+	fn request_bids() -> Weight;
+	fn bid_judgement(r: u32, ) -> Weight;
+	fn accept_bid(r: u32, x: u32, ) -> Weight;
+	fn cancel_bids() -> Weight;
+	fn expire_judgement(r: u32, x: u32, ) -> Weight;
 }
 
 pub trait Trait: frame_system::Trait {
@@ -151,6 +171,13 @@ pub trait Trait: frame_system::Trait {
 	/// The origin which may add or remove registrars. Root can always do this.
 	type RegistrarOrigin: EnsureOrigin<Self::Origin>;
 
+	/// The number of blocks for which registrars may bid on a request for bids.
+	type BidPeriod: Get<Self::BlockNumber>;
+
+	/// The number of blocks after which a judgement that is not sticky expires. Zero means that
+	/// judgements never expire.
+	type JudgementExpiry: Get<Self::BlockNumber>;
+
 	/// Weight information for extrinsics in this pallet.
 	type WeightInfo: WeightInfo;
 }
@@ -417,6 +444,81 @@ pub struct RegistrarInfo<
 	pub fields: IdentityFields,
 }
 
+/// The public profile of a registrar.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+pub struct RegistrarProfile {
+	/// The name under which the registrar offers its service.
+	pub display: Data,
+
+	/// A website describing the service of the registrar.
+	pub web: Data,
+
+	/// The fields checked by the registrar, each with a description of how it is checked.
+	pub checks: Vec<(IdentityField, Data)>,
+}
+
+impl RegistrarProfile {
+	/// The fields checked by the registrar.
+	pub fn fields(&self) -> IdentityFields {
+		IdentityFields(self.checks.iter().fold(BitFlags::empty(), |acc, (f, _)| acc | *f))
+	}
+}
+
+/// An open request from an account for registrars to bid on judging its identity.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+pub struct JudgementBids<Balance, BlockNumber> {
+	/// The most the account is willing to pay. This is held on deposit while bids are open.
+	pub max_fee: Balance,
+
+	/// The fields which a registrar must check in order to bid.
+	pub fields: IdentityFields,
+
+	/// The last block at which bids are accepted.
+	pub end: BlockNumber,
+
+	/// The bids so far, as the fee asked by each registrar. Stored ordered by `RegistrarIndex`.
+	pub bids: Vec<(RegistrarIndex, Balance)>,
+}
+
+/// Everything known about the identity of an account.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+pub struct IdentityView<
+	AccountId,
+	Balance: Encode + Decode + Copy + Clone + Debug + Eq + PartialEq,
+	BlockNumber,
+> {
+	/// The identity of the account, if any.
+	pub registration: Option<Registration<Balance>>,
+
+	/// When each judgement of the identity was given and whether it has expired since.
+	pub judged_at: Vec<(RegistrarIndex, BlockNumber, bool)>,
+
+	/// The open request for judgement bids of the account, if any.
+	pub bids: Option<JudgementBids<Balance, BlockNumber>>,
+
+	/// The super-identity of the account together with its name within it, if it is a
+	/// sub-identity.
+	pub super_of: Option<(AccountId, Data)>,
+
+	/// The sub-identities of the account together with their names.
+	pub subs: Vec<(AccountId, Data)>,
+}
+
+// A value placed in storage that represents the current version of the Identity storage.
+// This value is used by the `on_runtime_upgrade` logic to determine whether we run
+// storage migration logic.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+enum Releases {
+	V1,
+	V2,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		Releases::V1
+	}
+}
+
 decl_storage! {
 	trait Store for Module<T: Trait> as Identity {
 		/// Information that is pertinent to identify the entity behind an account.
@@ -443,6 +545,28 @@ decl_storage! {
 		///
 		/// The index into this can be cast to `RegistrarIndex` to get a valid value.
 		pub Registrars get(fn registrars): Vec<Option<RegistrarInfo<BalanceOf<T>, T::AccountId>>>;
+
+		/// The public profiles of the registrars.
+		pub RegistrarProfiles get(fn registrar_profile):
+			map hasher(twox_64_concat) RegistrarIndex => Option<RegistrarProfile>;
+
+		/// The open requests for judgement bids.
+		///
+		/// TWOX-NOTE: OK ― `AccountId` is a secure hash.
+		pub BidsFor get(fn bids_for):
+			map hasher(twox_64_concat) T::AccountId => Option<JudgementBids<BalanceOf<T>, T::BlockNumber>>;
+
+		/// The block at which each judgement was given, by account and registrar.
+		///
+		/// TWOX-NOTE: OK ― `AccountId` is a secure hash.
+		pub JudgedAt get(fn judged_at):
+			double_map hasher(twox_64_concat) T::AccountId, hasher(twox_64_concat) RegistrarIndex
+			=> Option<T::BlockNumber>;
+
+		/// Storage version of the pallet.
+		///
+		/// New networks start with V1 too, the migration then has nothing to do.
+		StorageVersion: Releases;
 	}
 }
 
@@ -470,6 +594,16 @@ decl_event!(
 		/// A sub-identity was cleared, and the given deposit repatriated from the
 		/// main identity account to the sub-identity account. \[sub, main, deposit\]
 		SubIdentityRevoked(AccountId, AccountId, Balance),
+		/// A registrar published its profile. \[registrar_index\]
+		RegistrarProfileSet(RegistrarIndex),
+		/// Bids for a judgement were requested, reserving the maximum fee. \[who, max_fee\]
+		JudgementBidsRequested(AccountId, Balance),
+		/// A registrar bid to provide a judgement. \[target, registrar_index, fee\]
+		JudgementBid(AccountId, RegistrarIndex, Balance),
+		/// A request for judgement bids was closed without accepting a bid. \[who\]
+		JudgementBidsCancelled(AccountId),
+		/// A judgement expired and was removed. \[target, registrar_index\]
+		JudgementExpired(AccountId, RegistrarIndex),
 	}
 );
 
@@ -507,7 +641,23 @@ decl_error! {
 		/// Sender is not a sub-account.
 		NotSub,
 		/// Sub-account isn't owned by sender.
-		NotOwned
+		NotOwned,
+		/// A field is checked more than once in a registrar profile.
+		DuplicateCheck,
+		/// Bids for a judgement are already open.
+		BidsAlreadyOpen,
+		/// There is no open request for judgement bids.
+		NoBids,
+		/// The bidding period is over.
+		BiddingClosed,
+		/// The fee exceeds the maximum fee of the request.
+		FeeTooHigh,
+		/// The registrar does not check all of the requested fields.
+		FieldsNotChecked,
+		/// No bid from the registrar.
+		BidNotFound,
+		/// The judgement has not expired.
+		NotExpired
 	}
 }
 
@@ -536,10 +686,24 @@ decl_module! {
 		/// of, e.g., updating judgements.
 		const MaxRegistrars: u32 = T::MaxRegistrars::get();
 
+		/// The number of blocks for which registrars may bid on a request for bids.
+		const BidPeriod: T::BlockNumber = T::BidPeriod::get();
+
+		/// The number of blocks after which a judgement that is not sticky expires. Zero means that
+		/// judgements never expire.
+		const JudgementExpiry: T::BlockNumber = T::JudgementExpiry::get();
+
 		type Error = Error<T>;
 
 		fn deposit_event() = default;
 
+		fn on_runtime_upgrade() -> Weight {
+			match Self::migrate_v1_to_v2() {
+				Some((reads, writes)) => T::DbWeight::get().reads_writes(reads + 1, writes + 1),
+				None => T::DbWeight::get().reads(1),
+			}
+		}
+
 		/// Add a registrar to the system.
 		///
 		/// The dispatch origin for this call must be `T::RegistrarOrigin`.
@@ -601,9 +765,14 @@ decl_module! {
 			ensure!(extra_fields <= T::MaxAdditionalFields::get(), Error::<T>::TooManyFields);
 			let fd = <BalanceOf<T>>::from(extra_fields) * T::FieldDeposit::get();
 
+			let mut dropped = Vec::new();
 			let mut id = match <IdentityOf<T>>::get(&sender) {
 				Some(mut id) => {
 					// Only keep non-positive judgements.
+					dropped = id.judgements.iter()
+						.filter(|j| !j.1.is_sticky())
+						.map(|j| j.0)
+						.collect();
 					id.judgements.retain(|j| j.1.is_sticky());
 					id.info = info;
 					id
@@ -622,6 +791,9 @@ decl_module! {
 
 			let judgements = id.judgements.len();
 			<IdentityOf<T>>::insert(&sender, id);
+			for i in dropped {
+				<JudgedAt<T>>::remove(&sender, i);
+			}
 			Self::deposit_event(RawEvent::IdentitySet(sender));
 
 			Ok(Some(T::WeightInfo::set_identity(
@@ -731,6 +903,7 @@ decl_module! {
 			for sub in sub_ids.iter() {
 				<SuperOf<T>>::remove(sub);
 			}
+			Self::forget_judgements(&sender);
 
 			let _ = T::Currency::unreserve(&sender, deposit.clone());
 
@@ -781,17 +954,10 @@ decl_module! {
 			ensure!(max_fee >= registrar.fee, Error::<T>::FeeChanged);
 			let mut id = <IdentityOf<T>>::get(&sender).ok_or(Error::<T>::NoIdentity)?;
 
-			let item = (reg_index, Judgement::FeePaid(registrar.fee));
-			match id.judgements.binary_search_by_key(&reg_index, |x| x.0) {
-				Ok(i) => if id.judgements[i].1.is_sticky() {
-					Err(Error::<T>::StickyJudgement)?
-				} else {
-					id.judgements[i] = item
-				},
-				Err(i) => id.judgements.insert(i, item),
-			}
+			Self::insert_fee_paid(&mut id, reg_index, registrar.fee)?;
 
 			T::Currency::reserve(&sender, registrar.fee)?;
+			<JudgedAt<T>>::remove(&sender, reg_index);
 
 			let judgements = id.judgements.len();
 			let extra_fields = id.info.additional.len();
@@ -995,6 +1161,7 @@ decl_module! {
 			let judgements = id.judgements.len();
 			let extra_fields = id.info.additional.len();
 			<IdentityOf<T>>::insert(&target, id);
+			<JudgedAt<T>>::insert(&target, reg_index, frame_system::Module::<T>::block_number());
 			Self::deposit_event(RawEvent::JudgementGiven(target, reg_index));
 
 			Ok(Some(T::WeightInfo::provide_judgement(
@@ -1039,6 +1206,7 @@ decl_module! {
 			for sub in sub_ids.iter() {
 				<SuperOf<T>>::remove(sub);
 			}
+			Self::forget_judgements(&target);
 			// Slash their deposit from them.
 			T::Slashed::on_unbalanced(T::Currency::slash_reserved(&target, deposit).0);
 
@@ -1141,6 +1309,250 @@ decl_module! {
 				Self::deposit_event(RawEvent::SubIdentityRevoked(sender, sup.clone(), deposit));
 			});
 		}
+
+		/// Publish the profile of a registrar.
+		///
+		/// The fields of the registrar are set to those listed in the checks of the profile.
+		///
+		/// The dispatch origin for this call must be _Signed_ and the sender must be the account
+		/// of the registrar whose index is `index`.
+		///
+		/// - `index`: the index of the registrar whose profile is to be set.
+		/// - `profile`: the profile of the registrar. Each field may be checked at most once.
+		///
+		/// Emits `RegistrarProfileSet` if successful.
+		///
+		/// # <weight>
+		/// - `O(R)`.
+		/// - One storage mutation `O(R)`.
+		/// - One storage write.
+		/// - One event.
+		/// # </weight>
+		#[weight = T::WeightInfo::set_profile(T::MaxRegistrars::get())] // R
+		fn set_profile(origin,
+			#[compact] index: RegistrarIndex,
+			profile: RegistrarProfile,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let fields = profile.fields();
+			ensure!(fields.0.bits().count_ones() as usize == profile.checks.len(), Error::<T>::DuplicateCheck);
+
+			let registrars = <Registrars<T>>::mutate(|rs| -> Result<usize, DispatchError> {
+				rs.get_mut(index as usize)
+					.and_then(|x| x.as_mut())
+					.and_then(|r| if r.account == who { r.fields = fields; Some(()) } else { None })
+					.ok_or_else(|| DispatchError::from(Error::<T>::InvalidIndex))?;
+				Ok(rs.len())
+			})?;
+			<RegistrarProfiles>::insert(index, profile);
+			Self::deposit_event(RawEvent::RegistrarProfileSet(index));
+
+			Ok(Some(T::WeightInfo::set_profile(registrars as u32)).into()) // R
+		}
+
+		/// Open a request for registrars to bid on providing a judgement.
+		///
+		/// Payment: `max_fee` is reserved until a bid is accepted or the request is cancelled.
+		///
+		/// The dispatch origin for this call must be _Signed_ and the sender must have a
+		/// registered identity and no open request for bids.
+		///
+		/// - `max_fee`: The maximum fee that the sender is willing to pay.
+		/// - `fields`: The fields that a registrar must check in order to bid.
+		///
+		/// Emits `JudgementBidsRequested` if successful.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - One balance-reserve operation.
+		/// - Storage: 1 read, 1 write.
+		/// - One event.
+		/// # </weight>
+		#[weight = T::WeightInfo::request_bids()]
+		fn request_bids(origin,
+			#[compact] max_fee: BalanceOf<T>,
+			fields: IdentityFields,
+		) {
+			let sender = ensure_signed(origin)?;
+			ensure!(<IdentityOf<T>>::contains_key(&sender), Error::<T>::NoIdentity);
+			ensure!(!<BidsFor<T>>::contains_key(&sender), Error::<T>::BidsAlreadyOpen);
+
+			T::Currency::reserve(&sender, max_fee)?;
+
+			let end = frame_system::Module::<T>::block_number() + T::BidPeriod::get();
+			<BidsFor<T>>::insert(&sender, JudgementBids { max_fee, fields, end, bids: Vec::new() });
+			Self::deposit_event(RawEvent::JudgementBidsRequested(sender, max_fee));
+		}
+
+		/// Bid to provide a judgement for an account which requested bids.
+		///
+		/// A new bid from a registrar replaces its previous one.
+		///
+		/// The dispatch origin for this call must be _Signed_ and the sender must be the account
+		/// of the registrar whose index is `reg_index`. The registrar must check all the fields
+		/// requested.
+		///
+		/// - `reg_index`: the index of the registrar bidding.
+		/// - `target`: the account which requested bids.
+		/// - `fee`: the fee asked by the registrar. This must not exceed the maximum fee of the
+		///   request.
+		///
+		/// Emits `JudgementBid` if successful.
+		///
+		/// # <weight>
+		/// - `O(R)`.
+		/// - Storage: 1 read `O(R)`, 1 mutate `O(R)`.
+		/// - One event.
+		/// # </weight>
+		#[weight = T::WeightInfo::bid_judgement(T::MaxRegistrars::get())] // R
+		fn bid_judgement(origin,
+			#[compact] reg_index: RegistrarIndex,
+			target: <T::Lookup as StaticLookup>::Source,
+			#[compact] fee: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+			let target = T::Lookup::lookup(target)?;
+			let registrar = <Registrars<T>>::get()
+				.get(reg_index as usize)
+				.and_then(Option::as_ref)
+				.and_then(|r| if r.account == sender { Some(r.clone()) } else { None })
+				.ok_or(Error::<T>::InvalidIndex)?;
+			let mut request = <BidsFor<T>>::get(&target).ok_or(Error::<T>::NoBids)?;
+			ensure!(frame_system::Module::<T>::block_number() <= request.end, Error::<T>::BiddingClosed);
+			ensure!(fee <= request.max_fee, Error::<T>::FeeTooHigh);
+			ensure!(registrar.fields.0.contains(request.fields.0), Error::<T>::FieldsNotChecked);
+
+			match request.bids.binary_search_by_key(&reg_index, |x| x.0) {
+				Ok(i) => request.bids[i].1 = fee,
+				Err(i) => request.bids.insert(i, (reg_index, fee)),
+			}
+
+			let bids = request.bids.len();
+			<BidsFor<T>>::insert(&target, request);
+			Self::deposit_event(RawEvent::JudgementBid(target, reg_index, fee));
+
+			Ok(Some(T::WeightInfo::bid_judgement(bids as u32)).into()) // R
+		}
+
+		/// Accept the bid of a registrar, requesting its judgement for the fee it asked.
+		///
+		/// Payment: The fee of the bid stays reserved for payment to the registrar when judgement
+		/// is given. The rest of the maximum fee is returned.
+		///
+		/// The dispatch origin for this call must be _Signed_ and the sender must have an open
+		/// request for bids.
+		///
+		/// - `reg_index`: The index of the registrar whose bid is accepted.
+		///
+		/// Emits `JudgementRequested` if successful.
+		///
+		/// # <weight>
+		/// - `O(R + X)`.
+		/// - One balance-unreserve operation.
+		/// - Storage: 1 take `O(R)`, 1 mutate `O(X + R)`.
+		/// - One event.
+		/// # </weight>
+		#[weight = T::WeightInfo::accept_bid(
+			T::MaxRegistrars::get().into(), // R
+			T::MaxAdditionalFields::get().into(), // X
+		)]
+		fn accept_bid(origin, #[compact] reg_index: RegistrarIndex) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+			let request = <BidsFor<T>>::get(&sender).ok_or(Error::<T>::NoBids)?;
+			let fee = request.bids.binary_search_by_key(&reg_index, |x| x.0)
+				.map(|i| request.bids[i].1)
+				.map_err(|_| Error::<T>::BidNotFound)?;
+			let mut id = <IdentityOf<T>>::get(&sender).ok_or(Error::<T>::NoIdentity)?;
+
+			Self::insert_fee_paid(&mut id, reg_index, fee)?;
+
+			let _ = T::Currency::unreserve(&sender, request.max_fee.saturating_sub(fee));
+			<BidsFor<T>>::remove(&sender);
+			<JudgedAt<T>>::remove(&sender, reg_index);
+
+			let judgements = id.judgements.len();
+			let extra_fields = id.info.additional.len();
+			<IdentityOf<T>>::insert(&sender, id);
+
+			Self::deposit_event(RawEvent::JudgementRequested(sender, reg_index));
+
+			Ok(Some(T::WeightInfo::accept_bid(
+				judgements as u32,
+				extra_fields as u32,
+			)).into())
+		}
+
+		/// Close the open request for bids of the sender without accepting any bid.
+		///
+		/// Payment: The reserved maximum fee is returned.
+		///
+		/// The dispatch origin for this call must be _Signed_ and the sender must have an open
+		/// request for bids.
+		///
+		/// Emits `JudgementBidsCancelled` if successful.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - One balance-unreserve operation.
+		/// - One storage take.
+		/// - One event.
+		/// # </weight>
+		#[weight = T::WeightInfo::cancel_bids()]
+		fn cancel_bids(origin) {
+			let sender = ensure_signed(origin)?;
+			let request = <BidsFor<T>>::take(&sender).ok_or(Error::<T>::NoBids)?;
+			let _ = T::Currency::unreserve(&sender, request.max_fee);
+			Self::deposit_event(RawEvent::JudgementBidsCancelled(sender));
+		}
+
+		/// Remove a judgement which has expired.
+		///
+		/// Sticky judgements never expire.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `target`: the account whose identity the judgement is upon.
+		/// - `reg_index`: the index of the registrar who gave the judgement.
+		///
+		/// Emits `JudgementExpired` if successful.
+		///
+		/// # <weight>
+		/// - `O(R + X)`.
+		/// - Storage: 1 read, 1 deletion, 1 mutate `O(R + X)`.
+		/// - One event.
+		/// # </weight>
+		#[weight = T::WeightInfo::expire_judgement(
+			T::MaxRegistrars::get().into(), // R
+			T::MaxAdditionalFields::get().into(), // X
+		)]
+		fn expire_judgement(origin,
+			target: <T::Lookup as StaticLookup>::Source,
+			#[compact] reg_index: RegistrarIndex,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+			let target = T::Lookup::lookup(target)?;
+			let judged_at = <JudgedAt<T>>::get(&target, reg_index).ok_or(Error::<T>::NotFound)?;
+			ensure!(
+				Self::has_expired(judged_at, frame_system::Module::<T>::block_number()),
+				Error::<T>::NotExpired
+			);
+			let mut id = <IdentityOf<T>>::get(&target).ok_or(Error::<T>::InvalidTarget)?;
+			let pos = id.judgements.binary_search_by_key(&reg_index, |x| x.0)
+				.map_err(|_| Error::<T>::NotFound)?;
+			ensure!(!id.judgements[pos].1.is_sticky(), Error::<T>::StickyJudgement);
+
+			id.judgements.remove(pos);
+			<JudgedAt<T>>::remove(&target, reg_index);
+			let judgements = id.judgements.len();
+			let extra_fields = id.info.additional.len();
+			<IdentityOf<T>>::insert(&target, id);
+			Self::deposit_event(RawEvent::JudgementExpired(target, reg_index));
+
+			Ok(Some(T::WeightInfo::expire_judgement(
+				judgements as u32,
+				extra_fields as u32,
+			)).into())
+		}
 	}
 }
 
@@ -1152,4 +1564,107 @@ impl<T: Trait> Module<T> {
 			.filter_map(|a| SuperOf::<T>::get(&a).map(|x| (a, x.1)))
 			.collect()
 	}
+
+	/// Get everything known about the identity of an account.
+	pub fn identity_view(
+		who: &T::AccountId,
+	) -> IdentityView<T::AccountId, BalanceOf<T>, T::BlockNumber> {
+		let now = frame_system::Module::<T>::block_number();
+		let registration = Self::identity(who);
+		let judged_at = registration.iter()
+			.flat_map(|r| r.judgements.iter())
+			.filter_map(|(i, j)| Self::judged_at(who, i).map(|at| {
+				(*i, at, !j.is_sticky() && Self::has_expired(at, now))
+			}))
+			.collect();
+
+		IdentityView {
+			registration,
+			judged_at,
+			bids: Self::bids_for(who),
+			super_of: Self::super_of(who),
+			subs: Self::subs(who),
+		}
+	}
+
+	/// Get the registrars together with their profiles.
+	pub fn registrars_with_profiles() -> Vec<(
+		RegistrarIndex,
+		RegistrarInfo<BalanceOf<T>, T::AccountId>,
+		Option<RegistrarProfile>,
+	)> {
+		Self::registrars()
+			.into_iter()
+			.enumerate()
+			.filter_map(|(i, r)| r.map(|r| {
+				let i = i as RegistrarIndex;
+				(i, r, Self::registrar_profile(i))
+			}))
+			.collect()
+	}
+
+	/// Whether a judgement given at `judged_at` has expired at `now`, assuming it is not sticky.
+	/// Migrate storage format from V1 to V2, recording when the judgements given before they
+	/// could expire were given.
+	///
+	/// They are considered given at the block of the upgrade, so they expire a full
+	/// `JudgementExpiry` later. Return the number of storage reads and writes if migration is
+	/// performed.
+	pub fn migrate_v1_to_v2() -> Option<(u64, u64)> {
+		if StorageVersion::get() != Releases::V1 {
+			return None;
+		}
+		StorageVersion::put(Releases::V2);
+
+		let now = frame_system::Module::<T>::block_number();
+		let (mut reads, mut writes) = (0u64, 0u64);
+		for (who, id) in <IdentityOf<T>>::iter() {
+			reads += 1;
+			for (reg_index, judgement) in id.judgements.iter() {
+				if let Judgement::FeePaid(_) = judgement {
+					continue;
+				}
+				reads += 1;
+				if !<JudgedAt<T>>::contains_key(&who, reg_index) {
+					<JudgedAt<T>>::insert(&who, reg_index, now);
+					writes += 1;
+				}
+			}
+		}
+		Some((reads, writes))
+	}
+
+	fn has_expired(judged_at: T::BlockNumber, now: T::BlockNumber) -> bool {
+		let expiry = T::JudgementExpiry::get();
+		!expiry.is_zero() && now >= judged_at.saturating_add(expiry)
+	}
+
+	/// Record in `id` that `fee` is paid to registrar `reg_index` for a judgement.
+	///
+	/// Fails if the registrar already gave a sticky judgement.
+	fn insert_fee_paid(
+		id: &mut Registration<BalanceOf<T>>,
+		reg_index: RegistrarIndex,
+		fee: BalanceOf<T>,
+	) -> DispatchResult {
+		let item = (reg_index, Judgement::FeePaid(fee));
+		match id.judgements.binary_search_by_key(&reg_index, |x| x.0) {
+			Ok(i) => if id.judgements[i].1.is_sticky() {
+				Err(Error::<T>::StickyJudgement)?
+			} else {
+				id.judgements[i] = item
+			},
+			Err(i) => id.judgements.insert(i, item),
+		}
+		Ok(())
+	}
+
+	/// Forget the judgements of `who` and close their request for bids, if any, returning its
+	/// deposit.
+	fn forget_judgements(who: &T::AccountId) {
+		<JudgedAt<T>>::remove_prefix(who);
+		if let Some(request) = <BidsFor<T>>::take(who) {
+			let _ = T::Currency::unreserve(who, request.max_fee);
+		}
+	}
 }
@@ -88,6 +88,8 @@ parameter_types! {
 	pub const MaxSubAccounts: u32 = 2;
 	pub const MaxAdditionalFields: u32 = 2;
 	pub const MaxRegistrars: u32 = 20;
+	pub const BidPeriod: u64 = 10;
+	pub const JudgementExpiry: u64 = 100;
 }
 ord_parameter_types! {
 	pub const One: u64 = 1;
@@ -115,6 +117,8 @@ impl Trait for Test {
 	type MaxRegistrars = MaxRegistrars;
 	type RegistrarOrigin = EnsureOneOrRoot;
 	type ForceOrigin = EnsureTwoOrRoot;
+	type BidPeriod = BidPeriod;
+	type JudgementExpiry = JudgementExpiry;
 	type WeightInfo = ();
 }
 type System = frame_system::Module<Test>;
@@ -470,3 +474,191 @@ fn setting_account_id_should_work() {
 		assert_ok!(Identity::set_account_id(Origin::signed(4), 0, 3));
 	});
 }
+
+fn display_checker() -> RegistrarProfile {
+	RegistrarProfile {
+		display: Data::Raw(b"checker".to_vec()),
+		web: Data::Raw(b"checker.example".to_vec()),
+		checks: vec![(IdentityField::Display, Data::Raw(b"video call".to_vec()))],
+	}
+}
+
+#[test]
+fn setting_profile_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Identity::add_registrar(Origin::signed(1), 3));
+		assert_noop!(Identity::set_profile(Origin::signed(4), 0, display_checker()), Error::<Test>::InvalidIndex);
+
+		let mut profile = display_checker();
+		profile.checks.push((IdentityField::Display, Data::Raw(b"again".to_vec())));
+		assert_noop!(Identity::set_profile(Origin::signed(3), 0, profile), Error::<Test>::DuplicateCheck);
+
+		let mut profile = display_checker();
+		profile.checks.push((IdentityField::Email, Data::Raw(b"confirmation link".to_vec())));
+		assert_ok!(Identity::set_profile(Origin::signed(3), 0, profile.clone()));
+		assert_eq!(Identity::registrar_profile(0), Some(profile));
+		assert_eq!(
+			Identity::registrars()[0].as_ref().unwrap().fields,
+			IdentityFields(IdentityField::Display | IdentityField::Email),
+		);
+	});
+}
+
+#[test]
+fn judgement_bids_should_work() {
+	new_test_ext().execute_with(|| {
+		let display = IdentityFields(IdentityField::Display.into());
+		assert_ok!(Identity::add_registrar(Origin::signed(1), 3));
+		assert_ok!(Identity::set_profile(Origin::signed(3), 0, display_checker()));
+		assert_ok!(Identity::add_registrar(Origin::signed(1), 4));
+
+		assert_noop!(Identity::request_bids(Origin::signed(10), 10, display), Error::<Test>::NoIdentity);
+		assert_ok!(Identity::set_identity(Origin::signed(10), ten()));
+		assert_noop!(Identity::bid_judgement(Origin::signed(3), 0, 10, 5), Error::<Test>::NoBids);
+		assert_ok!(Identity::request_bids(Origin::signed(10), 10, display));
+		// 10 for the maximum fee, 10 for the identity.
+		assert_eq!(Balances::free_balance(10), 80);
+		assert_noop!(Identity::request_bids(Origin::signed(10), 10, display), Error::<Test>::BidsAlreadyOpen);
+
+		assert_noop!(Identity::bid_judgement(Origin::signed(4), 1, 10, 5), Error::<Test>::FieldsNotChecked);
+		assert_noop!(Identity::bid_judgement(Origin::signed(4), 0, 10, 5), Error::<Test>::InvalidIndex);
+		assert_noop!(Identity::bid_judgement(Origin::signed(3), 0, 10, 11), Error::<Test>::FeeTooHigh);
+		assert_ok!(Identity::bid_judgement(Origin::signed(3), 0, 10, 8));
+		// A new bid replaces the previous one.
+		assert_ok!(Identity::bid_judgement(Origin::signed(3), 0, 10, 6));
+		assert_eq!(Identity::bids_for(10).unwrap().bids, vec![(0, 6)]);
+
+		assert_noop!(Identity::accept_bid(Origin::signed(10), 1), Error::<Test>::BidNotFound);
+		assert_ok!(Identity::accept_bid(Origin::signed(10), 0));
+		// Only the fee of the accepted bid stays reserved.
+		assert_eq!(Balances::free_balance(10), 84);
+		assert_eq!(Identity::bids_for(10), None);
+		assert_eq!(Identity::identity(10).unwrap().judgements, vec![(0, Judgement::FeePaid(6))]);
+
+		assert_ok!(Identity::provide_judgement(Origin::signed(3), 0, 10, Judgement::Reasonable));
+		assert_eq!(Balances::free_balance(3), 16);
+	});
+}
+
+#[test]
+fn bidding_closes_after_bid_period() {
+	new_test_ext().execute_with(|| {
+		let display = IdentityFields(IdentityField::Display.into());
+		System::set_block_number(1);
+		assert_ok!(Identity::add_registrar(Origin::signed(1), 3));
+		assert_ok!(Identity::set_profile(Origin::signed(3), 0, display_checker()));
+		assert_ok!(Identity::set_identity(Origin::signed(10), ten()));
+		assert_ok!(Identity::request_bids(Origin::signed(10), 10, display));
+
+		System::set_block_number(12);
+		assert_noop!(Identity::bid_judgement(Origin::signed(3), 0, 10, 5), Error::<Test>::BiddingClosed);
+
+		assert_ok!(Identity::cancel_bids(Origin::signed(10)));
+		assert_eq!(Balances::free_balance(10), 90);
+		assert_noop!(Identity::cancel_bids(Origin::signed(10)), Error::<Test>::NoBids);
+	});
+}
+
+#[test]
+fn clearing_identity_should_close_bids() {
+	new_test_ext().execute_with(|| {
+		let display = IdentityFields(IdentityField::Display.into());
+		assert_ok!(Identity::set_identity(Origin::signed(10), ten()));
+		assert_ok!(Identity::request_bids(Origin::signed(10), 10, display));
+		assert_ok!(Identity::clear_identity(Origin::signed(10)));
+		assert_eq!(Balances::free_balance(10), 100);
+		assert_eq!(Identity::bids_for(10), None);
+	});
+}
+
+#[test]
+fn judgements_should_expire() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Identity::add_registrar(Origin::signed(1), 3));
+		assert_ok!(Identity::add_registrar(Origin::signed(1), 4));
+		assert_ok!(Identity::set_identity(Origin::signed(10), ten()));
+		assert_ok!(Identity::provide_judgement(Origin::signed(3), 0, 10, Judgement::Reasonable));
+		assert_ok!(Identity::provide_judgement(Origin::signed(4), 1, 10, Judgement::Erroneous));
+		assert_eq!(Identity::judged_at(10, 0), Some(1));
+
+		System::set_block_number(100);
+		assert_noop!(Identity::expire_judgement(Origin::signed(20), 10, 0), Error::<Test>::NotExpired);
+
+		System::set_block_number(101);
+		assert_ok!(Identity::expire_judgement(Origin::signed(20), 10, 0));
+		assert_eq!(Identity::identity(10).unwrap().judgements, vec![(1, Judgement::Erroneous)]);
+		assert_eq!(Identity::judged_at(10, 0), None);
+		assert_noop!(Identity::expire_judgement(Origin::signed(20), 10, 0), Error::<Test>::NotFound);
+
+		// Sticky judgements never expire.
+		assert_noop!(Identity::expire_judgement(Origin::signed(20), 10, 1), Error::<Test>::StickyJudgement);
+	});
+}
+
+#[test]
+fn migration_v1_to_v2_dates_existing_judgements() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Identity::add_registrar(Origin::signed(1), 3));
+		assert_ok!(Identity::add_registrar(Origin::signed(1), 4));
+		assert_ok!(Identity::set_identity(Origin::signed(10), ten()));
+		assert_ok!(Identity::set_identity(Origin::signed(20), twenty()));
+		assert_ok!(Identity::provide_judgement(Origin::signed(3), 0, 10, Judgement::Reasonable));
+		assert_ok!(Identity::provide_judgement(Origin::signed(4), 1, 10, Judgement::Reasonable));
+		assert_ok!(Identity::request_judgement(Origin::signed(20), 0, 10));
+		// the judgement of registrar 0 was given before the dates were recorded.
+		<JudgedAt<Test>>::remove(10, 0);
+
+		System::set_block_number(50);
+		assert_eq!(Identity::migrate_v1_to_v2(), Some((4, 1)));
+		assert_eq!(Identity::judged_at(10, 0), Some(50));
+		assert_eq!(Identity::judged_at(10, 1), Some(1));
+		assert_eq!(Identity::judged_at(20, 0), None);
+		assert_eq!(Identity::migrate_v1_to_v2(), None);
+
+		// the judgement expires a full lifetime after the upgrade.
+		System::set_block_number(149);
+		assert_noop!(Identity::expire_judgement(Origin::signed(20), 10, 0), Error::<Test>::NotExpired);
+		System::set_block_number(150);
+		assert_ok!(Identity::expire_judgement(Origin::signed(20), 10, 0));
+	});
+}
+
+#[test]
+fn resetting_identity_should_forget_judgement_dates() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Identity::add_registrar(Origin::signed(1), 3));
+		assert_ok!(Identity::set_identity(Origin::signed(10), ten()));
+		assert_ok!(Identity::provide_judgement(Origin::signed(3), 0, 10, Judgement::Reasonable));
+		assert_ok!(Identity::set_identity(Origin::signed(10), twenty()));
+		assert_eq!(Identity::judged_at(10, 0), None);
+	});
+}
+
+#[test]
+fn identity_view_should_work() {
+	new_test_ext().execute_with(|| {
+		let data = Data::Raw(b"sub".to_vec());
+		System::set_block_number(1);
+		assert_ok!(Identity::add_registrar(Origin::signed(1), 3));
+		assert_ok!(Identity::set_identity(Origin::signed(10), ten()));
+		assert_ok!(Identity::set_subs(Origin::signed(10), vec![(20, data.clone())]));
+		assert_ok!(Identity::provide_judgement(Origin::signed(3), 0, 10, Judgement::KnownGood));
+
+		let view = Identity::identity_view(&10);
+		assert_eq!(view.registration.unwrap().info, ten());
+		assert_eq!(view.judged_at, vec![(0, 1, false)]);
+		assert_eq!(view.bids, None);
+		assert_eq!(view.super_of, None);
+		assert_eq!(view.subs, vec![(20, data.clone())]);
+
+		let view = Identity::identity_view(&20);
+		assert_eq!(view.registration, None);
+		assert_eq!(view.super_of, Some((10, data)));
+
+		System::set_block_number(101);
+		assert_eq!(Identity::identity_view(&10).judged_at, vec![(0, 1, true)]);
+	});
+}
@@ -180,6 +180,9 @@ impl pallet_staking::Trait for Test {
 	type ElectionLookahead = ();
 	type Call = Call;
 	type MaxNominatorRewardedPerValidator = MaxNominatorRewardedPerValidator;
+	type Scheduler = ();
+	type PalletsOrigin = frame_system::RawOrigin<Self::AccountId>;
+	type MaxAutoPayoutsPerBlock = ();
 	type UnsignedPriority = ();
 	type MaxIterations = ();
 	type MinSolutionScoreBump = ();
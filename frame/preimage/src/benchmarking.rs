@@ -0,0 +1,106 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Preimage pallet benchmarking.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use sp_std::{vec, prelude::*};
+use sp_runtime::traits::Bounded;
+use frame_system::RawOrigin;
+use frame_support::traits::UnfilteredDispatchable;
+use frame_benchmarking::{benchmarks, account, whitelisted_caller};
+
+use crate::Module as Preimage;
+
+const SEED: u32 = 0;
+
+fn funded_account<T: Trait>(name: &'static str, index: u32) -> T::AccountId {
+	let caller: T::AccountId = account(name, index, SEED);
+	T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+	caller
+}
+
+fn preimage_and_hash<T: Trait>(size: u32) -> (Vec<u8>, T::Hash) {
+	let preimage = vec![1u8; size as usize];
+	let hash = T::Hashing::hash(&preimage[..]);
+	(preimage, hash)
+}
+
+benchmarks! {
+	_ { }
+
+	note_preimage {
+		let s in 0 .. T::MaxSize::get();
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		let (preimage, hash) = preimage_and_hash::<T>(s);
+	}: _(RawOrigin::Signed(caller), preimage)
+	verify {
+		assert!(Preimage::<T>::have_preimage(&hash));
+	}
+
+	unnote_preimage {
+		let caller = funded_account::<T>("caller", 0);
+		let (preimage, hash) = preimage_and_hash::<T>(T::MaxSize::get());
+		Preimage::<T>::note_preimage(RawOrigin::Signed(caller.clone()).into(), preimage)?;
+	}: _(RawOrigin::Signed(caller), hash)
+	verify {
+		assert!(!Preimage::<T>::have_preimage(&hash));
+	}
+
+	request_preimage {
+		let noter = funded_account::<T>("noter", 0);
+		let (preimage, hash) = preimage_and_hash::<T>(T::MaxSize::get());
+		Preimage::<T>::note_preimage(RawOrigin::Signed(noter).into(), preimage)?;
+		let origin = T::ManagerOrigin::successful_origin();
+		let call = Call::<T>::request_preimage(hash);
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert_eq!(StatusFor::<T>::get(&hash), Some(RequestStatus::Requested(1)));
+	}
+
+	unrequest_preimage {
+		let (preimage, hash) = preimage_and_hash::<T>(T::MaxSize::get());
+		let origin = T::ManagerOrigin::successful_origin();
+		Preimage::<T>::request_preimage(origin.clone(), hash)?;
+		Preimage::<T>::note_preimage(origin.clone(), preimage)?;
+		let call = Call::<T>::unrequest_preimage(hash);
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert!(!Preimage::<T>::have_preimage(&hash));
+		assert_eq!(StatusFor::<T>::get(&hash), None);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{new_test_ext, Test};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn test_benchmarks() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_note_preimage::<Test>());
+			assert_ok!(test_benchmark_unnote_preimage::<Test>());
+			assert_ok!(test_benchmark_request_preimage::<Test>());
+			assert_ok!(test_benchmark_unrequest_preimage::<Test>());
+		});
+	}
+}
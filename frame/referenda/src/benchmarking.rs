@@ -0,0 +1,172 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Referenda pallet benchmarking.
+//!
+//! Referenda are submitted for the _Root_ origin, which must have a track. An empty tally is
+//! assumed not to meet the curves of that track.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use sp_runtime::traits::Bounded;
+use frame_system::RawOrigin;
+use frame_support::traits::UnfilteredDispatchable;
+use frame_benchmarking::{benchmarks, account, whitelisted_caller};
+
+use crate::Module as Referenda;
+
+const SEED: u32 = 0;
+
+fn funded_account<T: Trait>(name: &'static str, index: u32) -> T::AccountId {
+	let caller: T::AccountId = account(name, index, SEED);
+	T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+	caller
+}
+
+fn root_track<T: Trait>() -> Result<&'static TrackInfoOf<T>, &'static str> {
+	let track = T::Tracks::track_for(&RawOrigin::Root.into()).map_err(|_| "no track for root")?;
+	T::Tracks::info(track).ok_or("unknown root track")
+}
+
+/// Submit `n` referenda and let them through the preparation period of their track.
+fn create_referenda<T: Trait>(n: u32) -> Result<Vec<ReferendumIndex>, &'static str> {
+	let mut indices = Vec::new();
+	for i in 0..n {
+		let caller = funded_account::<T>("submitter", i);
+		let index = Referenda::<T>::referendum_count();
+		Referenda::<T>::submit(
+			RawOrigin::Signed(caller).into(),
+			Box::new(RawOrigin::Root.into()),
+			Box::new(Call::<T>::nudge_referendum(index).into()),
+			DispatchTime::After(Zero::zero()),
+		)?;
+		indices.push(index);
+	}
+	let now = system::Module::<T>::block_number();
+	system::Module::<T>::set_block_number(now + root_track::<T>()?.prepare_period);
+	Ok(indices)
+}
+
+fn place_deposit<T: Trait>(index: ReferendumIndex) -> Result<(), &'static str> {
+	let depositor = funded_account::<T>("depositor", index);
+	Referenda::<T>::place_decision_deposit(RawOrigin::Signed(depositor).into(), index)?;
+	Ok(())
+}
+
+/// Fill the decision slots and the queue of the root track. The first referendum is deciding.
+fn fill_root_track<T: Trait>() -> Result<Vec<ReferendumIndex>, &'static str> {
+	let indices = create_referenda::<T>(root_track::<T>()?.max_deciding + T::MaxQueued::get())?;
+	for &index in &indices {
+		place_deposit::<T>(index)?;
+	}
+	Ok(indices)
+}
+
+benchmarks! {
+	_ { }
+
+	submit {
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		let index = Referenda::<T>::referendum_count();
+		let proposal: <T as Trait>::Call = Call::<T>::nudge_referendum(index).into();
+	}: _(
+		RawOrigin::Signed(caller),
+		Box::new(RawOrigin::Root.into()),
+		Box::new(proposal),
+		DispatchTime::After(Zero::zero())
+	)
+	verify {
+		assert!(Referenda::<T>::ensure_ongoing(index).is_ok());
+	}
+
+	// The decision slots are taken, so the referendum is inserted into the queue.
+	place_decision_deposit {
+		let mut indices = create_referenda::<T>(
+			root_track::<T>()?.max_deciding + T::MaxQueued::get()
+		)?;
+		let index = indices.pop().ok_or("no referendum")?;
+		for &other in &indices {
+			place_deposit::<T>(other)?;
+		}
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+	}: _(RawOrigin::Signed(caller), index)
+	verify {
+		assert!(Referenda::<T>::ensure_ongoing(index)?.decision_deposit.is_some());
+	}
+
+	refund_decision_deposit {
+		let index = create_referenda::<T>(1)?[0];
+		place_deposit::<T>(index)?;
+		Referenda::<T>::cancel(T::CancelOrigin::successful_origin(), index)?;
+		let caller: T::AccountId = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller), index)
+	verify {
+		assert!(matches!(Referenda::<T>::referendum_info(index), Some(ReferendumInfo::Cancelled(_, None))));
+	}
+
+	// The referendum is deciding, so the head of the queue takes its decision slot.
+	cancel {
+		let index = fill_root_track::<T>()?[0];
+		let origin = T::CancelOrigin::successful_origin();
+		let call = Call::<T>::cancel(index);
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert!(matches!(Referenda::<T>::referendum_info(index), Some(ReferendumInfo::Cancelled(..))));
+	}
+
+	kill {
+		let index = fill_root_track::<T>()?[0];
+		let origin = T::KillOrigin::successful_origin();
+		let call = Call::<T>::kill(index);
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert!(matches!(Referenda::<T>::referendum_info(index), Some(ReferendumInfo::Killed(..))));
+	}
+
+	// The decision period of a deciding referendum is over, so it is rejected and the head of
+	// the queue takes its decision slot.
+	nudge_referendum {
+		let index = fill_root_track::<T>()?[0];
+		let now = system::Module::<T>::block_number();
+		system::Module::<T>::set_block_number(now + root_track::<T>()?.decision_period);
+	}: _(RawOrigin::Root, index)
+	verify {
+		assert!(matches!(Referenda::<T>::referendum_info(index), Some(ReferendumInfo::Rejected(..))));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{new_test_ext, Test};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn test_benchmarks() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_submit::<Test>());
+			assert_ok!(test_benchmark_place_decision_deposit::<Test>());
+			assert_ok!(test_benchmark_refund_decision_deposit::<Test>());
+			assert_ok!(test_benchmark_cancel::<Test>());
+			assert_ok!(test_benchmark_kill::<Test>());
+			assert_ok!(test_benchmark_nudge_referendum::<Test>());
+		});
+	}
+}
@@ -0,0 +1,807 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Referenda Pallet
+//!
+//! - [`referenda::Trait`](./trait.Trait.html)
+//! - [`Call`](./enum.Call.html)
+//!
+//! ## Overview
+//!
+//! The Referenda pallet runs referenda on a number of tracks, each with its own parameters. The
+//! track of a referendum is determined by the origin with which its proposal is to be dispatched,
+//! through [`TracksInfo`].
+//!
+//! A submitted referendum is in its preparation stage until the decision deposit of its track
+//! has been placed and the preparation period of the track has passed. It then enters the
+//! decision stage, unless the track already has its maximum number of referenda being decided,
+//! in which case it waits in the queue of the track, ordered by aye votes.
+//!
+//! A referendum in the decision stage starts confirming as soon as its tally meets both the
+//! approval and the support curves of its track. If it keeps passing for the whole confirmation
+//! period, it is approved and its proposal is scheduled for enactment with the origin it was
+//! submitted for. If the decision period ends while it is not confirming, it is rejected. A
+//! referendum which has not entered the decision stage within `UndecidingTimeout` times out.
+//!
+//! Votes are not counted by this pallet. Instead it implements [`Polling`] so that a voting
+//! pallet, such as the Conviction Voting pallet, can alter the tally of ongoing referenda.
+//!
+//! Referenda are advanced by alarms, which are calls to `nudge_referendum` dispatched by
+//! [`Trait::Scheduler`].
+//!
+//! ### Deposits
+//!
+//! - **Submission deposit:** Placed on submission. It is returned when the referendum
+//!   completes, unless the referendum is killed.
+//! - **Decision deposit:** Placed by anyone for the referendum to enter the decision stage. It
+//!   may be refunded through `refund_decision_deposit` once the referendum has completed, unless
+//!   the referendum is killed.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! - `submit` - Submit a proposal for a referendum.
+//! - `place_decision_deposit` - Place the decision deposit of a referendum.
+//! - `refund_decision_deposit` - Refund the decision deposit of a completed referendum.
+//!
+//! Cancellation origin functions:
+//!
+//! - `cancel` - Cancel an ongoing referendum, returning its deposits.
+//!
+//! Kill origin functions:
+//!
+//! - `kill` - Cancel an ongoing referendum, slashing its deposits.
+//!
+//! Root origin functions:
+//!
+//! - `nudge_referendum` - Advance a referendum to its next stage.
+
+#![recursion_limit="128"]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_std::prelude::*;
+use codec::{Encode, Decode};
+use sp_runtime::{
+	DispatchError, Perbill, RuntimeDebug,
+	traits::{Saturating, Zero, One},
+};
+use frame_support::{
+	decl_module, decl_storage, decl_event, decl_error, ensure, Parameter,
+	weights::Weight,
+	traits::{
+		Currency, ReservableCurrency, Get, EnsureOrigin, OnUnbalanced,
+		schedule::{Named as ScheduleNamed, DispatchTime, HARD_DEADLINE},
+		voting::{Polling, PollStatus, VoteTally},
+	},
+};
+use frame_system::{self as system, ensure_signed, ensure_root};
+
+mod types;
+mod benchmarking;
+mod default_weights;
+
+pub use types::{
+	TrackId, ReferendumIndex, Curve, TrackInfo, TracksInfo, Deposit, DecidingStatus,
+	ReferendumStatus, ReferendumInfo,
+};
+
+#[cfg(test)]
+mod tests;
+
+const ALARM_ID: [u8; 8] = *b"rfalarm ";
+const ENACTMENT_ID: [u8; 8] = *b"rfenact ";
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
+type NegativeImbalanceOf<T> =
+	<<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::NegativeImbalance;
+/// Information on a referendum, present or past.
+pub type ReferendumInfoOf<T> = ReferendumInfo<
+	<T as frame_system::Trait>::AccountId,
+	BalanceOf<T>,
+	<T as frame_system::Trait>::BlockNumber,
+	<T as Trait>::PalletsOrigin,
+	<T as Trait>::Call,
+	<T as Trait>::Tally,
+>;
+/// Information on an ongoing referendum.
+pub type ReferendumStatusOf<T> = ReferendumStatus<
+	<T as frame_system::Trait>::AccountId,
+	BalanceOf<T>,
+	<T as frame_system::Trait>::BlockNumber,
+	<T as Trait>::PalletsOrigin,
+	<T as Trait>::Call,
+	<T as Trait>::Tally,
+>;
+/// Information on a track.
+pub type TrackInfoOf<T> = TrackInfo<BalanceOf<T>, <T as frame_system::Trait>::BlockNumber>;
+
+/// Origin for the referenda module.
+#[derive(PartialEq, Eq, Clone, RuntimeDebug, Encode, Decode)]
+pub enum Origin {
+	/// The proposal was approved by a referendum on the given track.
+	Track(TrackId),
+}
+
+/// Ensure that the origin is the approval of a referendum on track `Id`.
+pub struct EnsureTrack<Id>(sp_std::marker::PhantomData<Id>);
+impl<
+	O: Into<Result<Origin, O>> + From<Origin>,
+	Id: Get<TrackId>,
+> EnsureOrigin<O> for EnsureTrack<Id> {
+	type Success = ();
+	fn try_origin(o: O) -> Result<Self::Success, O> {
+		o.into().and_then(|o| match o {
+			Origin::Track(id) if id == Id::get() => Ok(()),
+			r => Err(O::from(r)),
+		})
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn successful_origin() -> O {
+		O::from(Origin::Track(Id::get()))
+	}
+}
+
+pub trait WeightInfo {
+	fn submit() -> Weight;
+	fn place_decision_deposit() -> Weight;
+	fn refund_decision_deposit() -> Weight;
+	fn cancel() -> Weight;
+	fn kill() -> Weight;
+	fn nudge_referendum() -> Weight;
+}
+
+pub trait Trait: frame_system::Trait + Sized {
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+
+	/// The aggregated call type, of which proposals are made.
+	type Call: Parameter + From<Call<Self>>;
+
+	/// Currency type for the deposits.
+	type Currency: ReservableCurrency<Self::AccountId>;
+
+	/// The scheduler used for alarms and for the enactment of approved proposals.
+	type Scheduler: ScheduleNamed<Self::BlockNumber, <Self as Trait>::Call, Self::PalletsOrigin>;
+
+	/// Overarching type of all pallets origins.
+	type PalletsOrigin: Parameter + From<system::RawOrigin<Self::AccountId>>;
+
+	/// Origin from which any ongoing referendum may be cancelled.
+	type CancelOrigin: EnsureOrigin<Self::Origin>;
+
+	/// Origin from which any ongoing referendum may be killed.
+	type KillOrigin: EnsureOrigin<Self::Origin>;
+
+	/// Handler for the unbalanced reduction when slashing the deposits of a killed referendum.
+	type Slash: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+	/// The tally of a referendum, altered by a voting pallet through [`Polling`].
+	type Tally: VoteTally<BalanceOf<Self>> + Parameter + Default;
+
+	/// The amount reserved on submission of a referendum.
+	type SubmissionDeposit: Get<BalanceOf<Self>>;
+
+	/// The maximum number of referenda which may wait in the queue of a single track.
+	type MaxQueued: Get<u32>;
+
+	/// The number of blocks after submission within which a referendum must enter the decision
+	/// stage, or time out.
+	type UndecidingTimeout: Get<Self::BlockNumber>;
+
+	/// The maximum number of blocks between two nudges of a referendum which is waiting for a
+	/// place in the queue or for its tally to meet the curves of its track.
+	type AlarmInterval: Get<Self::BlockNumber>;
+
+	/// Information on the tracks referenda may be submitted on.
+	type Tracks: TracksInfo<BalanceOf<Self>, Self::BlockNumber, Origin = Self::PalletsOrigin>;
+
+	/// Weight information for extrinsics in this pallet.
+	type WeightInfo: WeightInfo;
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Referenda {
+		/// The next free referendum index, aka the number of referenda started so far.
+		pub ReferendumCount get(fn referendum_count): ReferendumIndex;
+
+		/// Information concerning any given referendum.
+		pub ReferendumInfoFor get(fn referendum_info):
+			map hasher(twox_64_concat) ReferendumIndex => Option<ReferendumInfoOf<T>>;
+
+		/// The referenda of a track waiting for a decision slot, with their aye votes. Sorted by
+		/// aye votes, ascending; the last one is the next to enter the decision stage.
+		pub TrackQueue get(fn track_queue):
+			map hasher(twox_64_concat) TrackId => Vec<(ReferendumIndex, BalanceOf<T>)>;
+
+		/// The number of referenda of a track in the decision stage.
+		pub DecidingCount get(fn deciding_count): map hasher(twox_64_concat) TrackId => u32;
+	}
+}
+
+decl_event! {
+	pub enum Event<T> where
+		Balance = BalanceOf<T>,
+		<T as frame_system::Trait>::AccountId,
+	{
+		/// A referendum has been submitted. \[ref_index, track\]
+		Submitted(ReferendumIndex, TrackId),
+		/// The decision deposit of a referendum has been placed. \[ref_index, who, amount\]
+		DecisionDepositPlaced(ReferendumIndex, AccountId, Balance),
+		/// The decision deposit of a referendum has been refunded. \[ref_index, who, amount\]
+		DecisionDepositRefunded(ReferendumIndex, AccountId, Balance),
+		/// A referendum has entered the decision stage. \[ref_index, track\]
+		DecisionStarted(ReferendumIndex, TrackId),
+		/// A referendum has started confirming. \[ref_index\]
+		ConfirmStarted(ReferendumIndex),
+		/// A referendum has stopped passing and is no longer confirming. \[ref_index\]
+		ConfirmAborted(ReferendumIndex),
+		/// A referendum has been approved and its proposal scheduled. \[ref_index\]
+		Approved(ReferendumIndex),
+		/// A referendum has been rejected. \[ref_index\]
+		Rejected(ReferendumIndex),
+		/// A referendum has timed out before entering the decision stage. \[ref_index\]
+		TimedOut(ReferendumIndex),
+		/// A referendum has been cancelled. \[ref_index\]
+		Cancelled(ReferendumIndex),
+		/// A referendum has been killed. \[ref_index\]
+		Killed(ReferendumIndex),
+	}
+}
+
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// Referendum is not ongoing.
+		NotOngoing,
+		/// Referendum does not exist.
+		BadReferendum,
+		/// Referendum has not completed yet.
+		Unfinished,
+		/// There is no track for the origin of the proposal.
+		NoTrack,
+		/// The decision deposit has already been placed.
+		HasDeposit,
+		/// There is no decision deposit to refund.
+		NoDeposit,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		/// The amount reserved on submission of a referendum.
+		const SubmissionDeposit: BalanceOf<T> = T::SubmissionDeposit::get();
+
+		/// The maximum number of referenda which may wait in the queue of a single track.
+		const MaxQueued: u32 = T::MaxQueued::get();
+
+		/// The number of blocks after submission within which a referendum must enter the
+		/// decision stage, or time out.
+		const UndecidingTimeout: T::BlockNumber = T::UndecidingTimeout::get();
+
+		/// The maximum number of blocks between two nudges of a waiting referendum.
+		const AlarmInterval: T::BlockNumber = T::AlarmInterval::get();
+
+		fn deposit_event() = default;
+
+		/// Submit a proposal for a referendum on the track of `proposal_origin`.
+		///
+		/// The dispatch origin of this call must be _Signed_ and the sender must
+		/// have funds to cover the submission deposit.
+		///
+		/// - `proposal_origin`: The origin with which the proposal is dispatched if approved.
+		/// - `proposal`: The proposal.
+		/// - `enactment_moment`: When the proposal is enacted once approved. It is never earlier
+		///   than the minimum enactment period of the track.
+		///
+		/// Emits `Submitted`.
+		#[weight = T::WeightInfo::submit()]
+		fn submit(origin,
+			proposal_origin: Box<T::PalletsOrigin>,
+			proposal: Box<<T as Trait>::Call>,
+			enactment_moment: DispatchTime<T::BlockNumber>,
+		) {
+			let who = ensure_signed(origin)?;
+			let track = T::Tracks::track_for(&proposal_origin).map_err(|_| Error::<T>::NoTrack)?;
+			let submission_deposit = Self::take_deposit(who, T::SubmissionDeposit::get())?;
+
+			let index = Self::referendum_count();
+			ReferendumCount::put(index + 1);
+
+			let now = system::Module::<T>::block_number();
+			let mut status = ReferendumStatus {
+				track,
+				origin: *proposal_origin,
+				proposal: *proposal,
+				enactment: enactment_moment,
+				submitted: now,
+				submission_deposit,
+				decision_deposit: None,
+				deciding: None,
+				tally: Default::default(),
+				in_queue: false,
+				alarm: None,
+			};
+			Self::set_alarm(index, &mut status, now.saturating_add(T::UndecidingTimeout::get()));
+			<ReferendumInfoFor<T>>::insert(index, ReferendumInfo::Ongoing(status));
+
+			Self::deposit_event(RawEvent::Submitted(index, track));
+		}
+
+		/// Place the decision deposit of a referendum.
+		///
+		/// The dispatch origin of this call must be _Signed_ and the sender must
+		/// have funds to cover the decision deposit of the track of the referendum.
+		///
+		/// - `index`: The index of the referendum.
+		///
+		/// Emits `DecisionDepositPlaced`, and `DecisionStarted` if the referendum enters the
+		/// decision stage right away.
+		#[weight = T::WeightInfo::place_decision_deposit()]
+		fn place_decision_deposit(origin, index: ReferendumIndex) {
+			let who = ensure_signed(origin)?;
+			let mut status = Self::ensure_ongoing(index)?;
+			ensure!(status.decision_deposit.is_none(), Error::<T>::HasDeposit);
+			let track = T::Tracks::info(status.track).ok_or(Error::<T>::NoTrack)?;
+			let amount = track.decision_deposit;
+			status.decision_deposit = Some(Self::take_deposit(who.clone(), amount)?);
+			Self::deposit_event(RawEvent::DecisionDepositPlaced(index, who, amount));
+
+			let now = system::Module::<T>::block_number();
+			let info = Self::service_referendum(now, index, status);
+			<ReferendumInfoFor<T>>::insert(index, info);
+		}
+
+		/// Refund the decision deposit of a completed referendum.
+		///
+		/// The dispatch origin of this call must be _Signed_. The deposit is returned to the
+		/// account which placed it.
+		///
+		/// - `index`: The index of the referendum.
+		///
+		/// Emits `DecisionDepositRefunded`.
+		#[weight = T::WeightInfo::refund_decision_deposit()]
+		fn refund_decision_deposit(origin, index: ReferendumIndex) {
+			ensure_signed(origin)?;
+			let mut info = Self::referendum_info(index).ok_or(Error::<T>::BadReferendum)?;
+			let deposit = info.take_decision_deposit()
+				.map_err(|_| Error::<T>::Unfinished)?
+				.ok_or(Error::<T>::NoDeposit)?;
+			T::Currency::unreserve(&deposit.who, deposit.amount);
+			<ReferendumInfoFor<T>>::insert(index, info);
+
+			Self::deposit_event(RawEvent::DecisionDepositRefunded(index, deposit.who, deposit.amount));
+		}
+
+		/// Cancel an ongoing referendum. Its deposits are returned.
+		///
+		/// The dispatch origin of this call must be `CancelOrigin`.
+		///
+		/// - `index`: The index of the referendum.
+		///
+		/// Emits `Cancelled`.
+		#[weight = T::WeightInfo::cancel()]
+		fn cancel(origin, index: ReferendumIndex) {
+			T::CancelOrigin::ensure_origin(origin)?;
+			let status = Self::ensure_ongoing(index)?;
+			let now = system::Module::<T>::block_number();
+
+			Self::deposit_event(RawEvent::Cancelled(index));
+			Self::end_referendum(now, index, &status);
+			Self::refund_deposit(Some(status.submission_deposit));
+			<ReferendumInfoFor<T>>::insert(index, ReferendumInfo::Cancelled(now, status.decision_deposit));
+		}
+
+		/// Cancel an ongoing referendum and slash its deposits.
+		///
+		/// The dispatch origin of this call must be `KillOrigin`.
+		///
+		/// - `index`: The index of the referendum.
+		///
+		/// Emits `Killed`.
+		#[weight = T::WeightInfo::kill()]
+		fn kill(origin, index: ReferendumIndex) {
+			T::KillOrigin::ensure_origin(origin)?;
+			let status = Self::ensure_ongoing(index)?;
+			let now = system::Module::<T>::block_number();
+
+			Self::deposit_event(RawEvent::Killed(index));
+			Self::end_referendum(now, index, &status);
+			Self::slash_deposit(Some(status.submission_deposit));
+			Self::slash_deposit(status.decision_deposit);
+			<ReferendumInfoFor<T>>::insert(index, ReferendumInfo::Killed(now));
+		}
+
+		/// Advance a referendum onto its next logical state. This is the call dispatched by the
+		/// alarms of the referenda.
+		///
+		/// The dispatch origin of this call must be _Root_.
+		///
+		/// - `index`: The index of the referendum.
+		#[weight = T::WeightInfo::nudge_referendum()]
+		fn nudge_referendum(origin, index: ReferendumIndex) {
+			ensure_root(origin)?;
+			let mut status = Self::ensure_ongoing(index)?;
+			let now = system::Module::<T>::block_number();
+			// The alarm which is dispatching this call is spent.
+			if status.alarm == Some(now) {
+				status.alarm = None;
+			}
+			let info = Self::service_referendum(now, index, status);
+			<ReferendumInfoFor<T>>::insert(index, info);
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	// exposed immutables.
+
+	/// Get the status of an ongoing referendum.
+	pub fn ensure_ongoing(index: ReferendumIndex) -> Result<ReferendumStatusOf<T>, DispatchError> {
+		match Self::referendum_info(index) {
+			Some(ReferendumInfo::Ongoing(status)) => Ok(status),
+			_ => Err(Error::<T>::NotOngoing.into()),
+		}
+	}
+
+	/// Whether `tally` meets the curves of `track`, for a referendum in the decision stage
+	/// since `since`.
+	pub fn is_passing(
+		tally: &T::Tally,
+		now: T::BlockNumber,
+		since: T::BlockNumber,
+		track: &TrackInfoOf<T>,
+	) -> bool {
+		let x = if track.decision_period.is_zero() {
+			Perbill::one()
+		} else {
+			let elapsed = now.saturating_sub(since).min(track.decision_period);
+			Perbill::from_rational_approximation(elapsed, track.decision_period)
+		};
+		tally.approval() >= track.min_approval.threshold(x)
+			&& tally.support(T::Currency::total_issuance()) >= track.min_support.threshold(x)
+	}
+
+	// private.
+
+	/// Advance the referendum `index` as far as it can be at `now`, and set its next alarm.
+	///
+	/// Returns the new information of the referendum, which the caller must store.
+	fn service_referendum(
+		now: T::BlockNumber,
+		index: ReferendumIndex,
+		mut status: ReferendumStatusOf<T>,
+	) -> ReferendumInfoOf<T> {
+		let track = match T::Tracks::info(status.track) {
+			Some(x) => x,
+			None => return ReferendumInfo::Ongoing(status),
+		};
+
+		// Enter the decision stage, or the queue, once the decision deposit is placed and the
+		// preparation period is over.
+		let prepared = status.submitted.saturating_add(track.prepare_period);
+		if status.deciding.is_none() && !status.in_queue && status.decision_deposit.is_some()
+			&& now >= prepared
+		{
+			if Self::deciding_count(status.track) < track.max_deciding {
+				Self::begin_deciding(now, index, &mut status);
+			} else {
+				Self::try_enqueue(index, &mut status);
+			}
+		}
+
+		let alarm = match status.deciding.clone() {
+			None => {
+				let timeout = status.submitted.saturating_add(T::UndecidingTimeout::get());
+				if now >= timeout {
+					Self::deposit_event(RawEvent::TimedOut(index));
+					Self::end_referendum(now, index, &status);
+					Self::refund_deposit(Some(status.submission_deposit));
+					return ReferendumInfo::TimedOut(now, status.decision_deposit);
+				}
+				if status.decision_deposit.is_some() && !status.in_queue {
+					// Waiting for the preparation period to end or for a place in the queue.
+					let retry = now.saturating_add(Self::alarm_interval());
+					if now < prepared { prepared } else { retry }.min(timeout)
+				} else {
+					timeout
+				}
+			}
+			Some(mut deciding) => {
+				if Self::is_passing(&status.tally, now, deciding.since, track) {
+					if deciding.confirming.is_none() {
+						deciding.confirming = Some(now.saturating_add(track.confirm_period));
+						Self::deposit_event(RawEvent::ConfirmStarted(index));
+					}
+				} else if deciding.confirming.take().is_some() {
+					Self::deposit_event(RawEvent::ConfirmAborted(index));
+				}
+
+				let decision_end = deciding.since.saturating_add(track.decision_period);
+				match deciding.confirming {
+					Some(confirmed) if now >= confirmed => {
+						Self::deposit_event(RawEvent::Approved(index));
+						Self::end_referendum(now, index, &status);
+						Self::schedule_enactment(now, index, track, status.enactment, status.origin, status.proposal);
+						Self::refund_deposit(Some(status.submission_deposit));
+						return ReferendumInfo::Approved(now, status.decision_deposit);
+					}
+					None if now >= decision_end => {
+						Self::deposit_event(RawEvent::Rejected(index));
+						Self::end_referendum(now, index, &status);
+						Self::refund_deposit(Some(status.submission_deposit));
+						return ReferendumInfo::Rejected(now, status.decision_deposit);
+					}
+					_ => {}
+				}
+				status.deciding = Some(deciding.clone());
+
+				// While not confirming, the curves keep decreasing, so check back regularly.
+				deciding.confirming.unwrap_or_else(||
+					now.saturating_add(Self::alarm_interval()).min(decision_end)
+				)
+			}
+		};
+		Self::set_alarm(index, &mut status, alarm);
+		ReferendumInfo::Ongoing(status)
+	}
+
+	/// Move a referendum into the decision stage.
+	fn begin_deciding(now: T::BlockNumber, index: ReferendumIndex, status: &mut ReferendumStatusOf<T>) {
+		DecidingCount::mutate(status.track, |x| *x = x.saturating_add(1));
+		status.deciding = Some(DecidingStatus { since: now, confirming: None });
+		Self::deposit_event(RawEvent::DecisionStarted(index, status.track));
+	}
+
+	/// Place a referendum in the queue of its track, if the queue is not full.
+	fn try_enqueue(index: ReferendumIndex, status: &mut ReferendumStatusOf<T>) {
+		let ayes = status.tally.ayes();
+		<TrackQueue<T>>::mutate(status.track, |queue| {
+			if (queue.len() as u32) < T::MaxQueued::get() {
+				let i = queue.binary_search_by_key(&ayes, |x| x.1).unwrap_or_else(|x| x);
+				queue.insert(i, (index, ayes));
+				status.in_queue = true;
+			}
+		});
+	}
+
+	/// Clean up after a referendum which is no longer ongoing: its alarm, its place in the queue
+	/// and its decision slot, which is handed over to the next queued referendum.
+	fn end_referendum(now: T::BlockNumber, index: ReferendumIndex, status: &ReferendumStatusOf<T>) {
+		Self::cancel_alarm(index, status.alarm);
+		if status.in_queue {
+			<TrackQueue<T>>::mutate(status.track, |queue| queue.retain(|x| x.0 != index));
+		}
+		if status.deciding.is_some() {
+			Self::note_one_fewer_deciding(now, status.track);
+		}
+	}
+
+	/// A referendum of `track` has left the decision stage. The queued referendum of the track
+	/// with the most aye votes takes its place.
+	fn note_one_fewer_deciding(now: T::BlockNumber, track: TrackId) {
+		DecidingCount::mutate(track, |x| *x = x.saturating_sub(1));
+		if let Some((index, _)) = <TrackQueue<T>>::mutate(track, |queue| queue.pop()) {
+			if let Ok(mut status) = Self::ensure_ongoing(index) {
+				status.in_queue = false;
+				let info = Self::service_referendum(now, index, status);
+				<ReferendumInfoFor<T>>::insert(index, info);
+			}
+		}
+	}
+
+	/// The tally of an ongoing referendum has changed.
+	fn note_tally_change(index: ReferendumIndex, status: &mut ReferendumStatusOf<T>) {
+		if status.in_queue {
+			let ayes = status.tally.ayes();
+			<TrackQueue<T>>::mutate(status.track, |queue| {
+				queue.retain(|x| x.0 != index);
+				let i = queue.binary_search_by_key(&ayes, |x| x.1).unwrap_or_else(|x| x);
+				queue.insert(i, (index, ayes));
+			});
+		}
+
+		let now = system::Module::<T>::block_number();
+		let needs_nudge = match (&status.deciding, T::Tracks::info(status.track)) {
+			(Some(deciding), Some(track)) =>
+				Self::is_passing(&status.tally, now, deciding.since, track)
+					!= deciding.confirming.is_some(),
+			_ => false,
+		};
+		if needs_nudge {
+			// Start or abort confirming in the next block.
+			Self::set_alarm(index, status, now.saturating_add(One::one()));
+		}
+	}
+
+	/// Schedule the enactment of the proposal of an approved referendum.
+	fn schedule_enactment(
+		now: T::BlockNumber,
+		index: ReferendumIndex,
+		track: &TrackInfoOf<T>,
+		enactment: DispatchTime<T::BlockNumber>,
+		origin: T::PalletsOrigin,
+		proposal: <T as Trait>::Call,
+	) {
+		let earliest = now.saturating_add(track.min_enactment_period.max(One::one()));
+		let when = match enactment {
+			DispatchTime::At(when) => when,
+			DispatchTime::After(delay) => now.saturating_add(delay),
+		}.max(earliest);
+		if T::Scheduler::schedule_named(
+			(ENACTMENT_ID, index).encode(),
+			DispatchTime::At(when),
+			None,
+			63,
+			origin,
+			proposal,
+		).is_err() {
+			frame_support::print("LOGIC ERROR: schedule_enactment/schedule_named failed");
+		}
+	}
+
+	/// Set the next alarm of a referendum, replacing the current one.
+	fn set_alarm(index: ReferendumIndex, status: &mut ReferendumStatusOf<T>, when: T::BlockNumber) {
+		if status.alarm == Some(when) {
+			return;
+		}
+		Self::cancel_alarm(index, status.alarm.take());
+		status.alarm = T::Scheduler::schedule_named(
+			Self::alarm_id(index, when),
+			DispatchTime::At(when),
+			None,
+			HARD_DEADLINE,
+			system::RawOrigin::Root.into(),
+			Call::nudge_referendum(index).into(),
+		).ok().map(|_| when);
+	}
+
+	/// Cancel an alarm of a referendum, if any.
+	fn cancel_alarm(index: ReferendumIndex, alarm: Option<T::BlockNumber>) {
+		if let Some(when) = alarm {
+			let _ = T::Scheduler::cancel_named(Self::alarm_id(index, when));
+		}
+	}
+
+	/// The name of the scheduled task nudging referendum `index` at `when`.
+	///
+	/// The block is part of the name so that a new alarm can be set from within the nudge
+	/// dispatched by the previous one.
+	fn alarm_id(index: ReferendumIndex, when: T::BlockNumber) -> Vec<u8> {
+		(ALARM_ID, index, when).encode()
+	}
+
+	/// The interval between two nudges of a waiting referendum; at least one block.
+	fn alarm_interval() -> T::BlockNumber {
+		T::AlarmInterval::get().max(One::one())
+	}
+
+	/// Reserve a deposit of `amount` from `who`.
+	fn take_deposit(
+		who: T::AccountId,
+		amount: BalanceOf<T>,
+	) -> Result<Deposit<T::AccountId, BalanceOf<T>>, DispatchError> {
+		T::Currency::reserve(&who, amount)?;
+		Ok(Deposit { who, amount })
+	}
+
+	/// Return a deposit to the account which placed it.
+	fn refund_deposit(deposit: Option<Deposit<T::AccountId, BalanceOf<T>>>) {
+		if let Some(Deposit { who, amount }) = deposit {
+			T::Currency::unreserve(&who, amount);
+		}
+	}
+
+	/// Slash a deposit.
+	fn slash_deposit(deposit: Option<Deposit<T::AccountId, BalanceOf<T>>>) {
+		if let Some(Deposit { who, amount }) = deposit {
+			T::Slash::on_unbalanced(T::Currency::slash_reserved(&who, amount).0);
+		}
+	}
+}
+
+impl<T: Trait> Polling<T::Tally> for Module<T> {
+	type Index = ReferendumIndex;
+	type Votes = BalanceOf<T>;
+	type Class = TrackId;
+	type Moment = T::BlockNumber;
+
+	fn classes() -> Vec<TrackId> {
+		T::Tracks::tracks().iter().map(|x| x.0).collect()
+	}
+
+	fn as_ongoing(index: ReferendumIndex) -> Option<(T::Tally, TrackId)> {
+		Self::ensure_ongoing(index).ok().map(|status| (status.tally, status.track))
+	}
+
+	fn access_poll<R>(
+		index: ReferendumIndex,
+		f: impl FnOnce(PollStatus<&mut T::Tally, T::BlockNumber, TrackId>) -> R,
+	) -> R {
+		match Self::referendum_info(index) {
+			Some(ReferendumInfo::Ongoing(mut status)) => {
+				let result = f(PollStatus::Ongoing(&mut status.tally, status.track));
+				Self::note_tally_change(index, &mut status);
+				<ReferendumInfoFor<T>>::insert(index, ReferendumInfo::Ongoing(status));
+				result
+			},
+			Some(ReferendumInfo::Approved(end, ..)) => f(PollStatus::Completed(end, true)),
+			Some(ReferendumInfo::Rejected(end, ..)) => f(PollStatus::Completed(end, false)),
+			_ => f(PollStatus::None),
+		}
+	}
+
+	fn try_access_poll<R>(
+		index: ReferendumIndex,
+		f: impl FnOnce(PollStatus<&mut T::Tally, T::BlockNumber, TrackId>) -> Result<R, DispatchError>,
+	) -> Result<R, DispatchError> {
+		match Self::referendum_info(index) {
+			Some(ReferendumInfo::Ongoing(mut status)) => {
+				let result = f(PollStatus::Ongoing(&mut status.tally, status.track))?;
+				Self::note_tally_change(index, &mut status);
+				<ReferendumInfoFor<T>>::insert(index, ReferendumInfo::Ongoing(status));
+				Ok(result)
+			},
+			Some(ReferendumInfo::Approved(end, ..)) => f(PollStatus::Completed(end, true)),
+			Some(ReferendumInfo::Rejected(end, ..)) => f(PollStatus::Completed(end, false)),
+			_ => f(PollStatus::None),
+		}
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn create_ongoing(class: TrackId) -> Result<ReferendumIndex, ()> {
+		T::Tracks::info(class).ok_or(())?;
+		let index = Self::referendum_count();
+		ReferendumCount::put(index + 1);
+
+		// A referendum in the decision stage, without deposits nor alarm, which only a voting
+		// benchmark is going to look at.
+		let now = system::Module::<T>::block_number();
+		let status = ReferendumStatus {
+			track: class,
+			origin: system::RawOrigin::Root.into(),
+			proposal: Call::nudge_referendum(index).into(),
+			enactment: DispatchTime::After(Zero::zero()),
+			submitted: now,
+			submission_deposit: Deposit { who: Default::default(), amount: Zero::zero() },
+			decision_deposit: None,
+			deciding: Some(DecidingStatus { since: now, confirming: None }),
+			tally: Default::default(),
+			in_queue: false,
+			alarm: None,
+		};
+		DecidingCount::mutate(class, |x| *x = x.saturating_add(1));
+		<ReferendumInfoFor<T>>::insert(index, ReferendumInfo::Ongoing(status));
+		Ok(index)
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn end_ongoing(index: ReferendumIndex, approved: bool) -> Result<(), ()> {
+		let status = Self::ensure_ongoing(index).map_err(|_| ())?;
+		let now = system::Module::<T>::block_number();
+		Self::end_referendum(now, index, &status);
+		let info = if approved {
+			ReferendumInfo::Approved(now, status.decision_deposit)
+		} else {
+			ReferendumInfo::Rejected(now, status.decision_deposit)
+		};
+		<ReferendumInfoFor<T>>::insert(index, info);
+		Ok(())
+	}
+}
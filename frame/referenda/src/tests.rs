@@ -0,0 +1,504 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The crate's tests.
+
+use super::*;
+use codec::{Encode, Decode};
+use frame_support::{
+	impl_outer_origin, impl_outer_dispatch, impl_outer_event, assert_noop, assert_ok,
+	parameter_types, traits::OnInitialize,
+};
+use sp_core::H256;
+use sp_runtime::{
+	traits::{BlakeTwo256, IdentityLookup, BadOrigin},
+	testing::Header,
+};
+use frame_system::EnsureRoot;
+
+impl_outer_origin! {
+	pub enum Origin for Test where system = frame_system {
+		referenda,
+	}
+}
+
+impl_outer_dispatch! {
+	pub enum Call for Test where origin: Origin {
+		frame_system::System,
+		pallet_balances::Balances,
+		referenda::Referenda,
+	}
+}
+
+mod referenda {
+	pub use crate::{Origin, Event};
+}
+
+impl_outer_event! {
+	pub enum Event for Test {
+		system<T>,
+		pallet_balances<T>,
+		pallet_scheduler<T>,
+		referenda<T>,
+	}
+}
+
+// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Test;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1_000_000;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+impl frame_system::Trait for Test {
+	type BaseCallFilter = ();
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Call = Call;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type PalletInfo = ();
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+}
+parameter_types! {
+	pub MaximumSchedulerWeight: Weight = Perbill::from_percent(80) * MaximumBlockWeight::get();
+}
+impl pallet_scheduler::Trait for Test {
+	type Event = Event;
+	type Origin = Origin;
+	type PalletsOrigin = OriginCaller;
+	type Call = Call;
+	type MaximumWeight = MaximumSchedulerWeight;
+	type ScheduleOrigin = EnsureRoot<u64>;
+	type MaxScheduledPerBlock = ();
+	type WeightInfo = ();
+	type PreimageProvider = ();
+	type NoPreimagePostponement = ();
+}
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+impl pallet_balances::Trait for Test {
+	type MaxLocks = ();
+	type Balance = u64;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+/// A tally in which aye votes are directly the support, in percent of the total issuance.
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, Debug)]
+pub struct Tally {
+	pub ayes: u32,
+	pub nays: u32,
+}
+
+impl VoteTally<u64> for Tally {
+	fn ayes(&self) -> u64 {
+		self.ayes as u64
+	}
+
+	fn support(&self, _total: u64) -> Perbill {
+		Perbill::from_percent(self.ayes)
+	}
+
+	fn approval(&self) -> Perbill {
+		if self.ayes + self.nays == 0 {
+			return Perbill::zero();
+		}
+		Perbill::from_rational_approximation(self.ayes, self.ayes + self.nays)
+	}
+}
+
+pub struct TestTracksInfo;
+impl TracksInfo<u64, u64> for TestTracksInfo {
+	type Origin = OriginCaller;
+
+	fn tracks() -> &'static [(TrackId, TrackInfo<u64, u64>)] {
+		static DATA: [(TrackId, TrackInfo<u64, u64>); 2] = [
+			(0, TrackInfo {
+				name: "root",
+				max_deciding: 1,
+				decision_deposit: 10,
+				prepare_period: 4,
+				decision_period: 4,
+				confirm_period: 2,
+				min_enactment_period: 4,
+				min_approval: Curve::LinearDecreasing {
+					begin: Perbill::from_percent(100),
+					delta: Perbill::from_percent(50),
+				},
+				min_support: Curve::LinearDecreasing {
+					begin: Perbill::from_percent(100),
+					delta: Perbill::from_percent(100),
+				},
+			}),
+			(1, TrackInfo {
+				name: "none",
+				max_deciding: 3,
+				decision_deposit: 1,
+				prepare_period: 2,
+				decision_period: 2,
+				confirm_period: 1,
+				min_enactment_period: 2,
+				min_approval: Curve::SteppedDecreasing {
+					begin: Perbill::from_percent(100),
+					end: Perbill::from_percent(50),
+					step: Perbill::from_percent(10),
+					period: Perbill::from_percent(10),
+				},
+				min_support: Curve::SteppedDecreasing {
+					begin: Perbill::from_percent(100),
+					end: Perbill::from_percent(0),
+					step: Perbill::from_percent(10),
+					period: Perbill::from_percent(10),
+				},
+			}),
+		];
+		&DATA[..]
+	}
+
+	fn track_for(origin: &OriginCaller) -> Result<TrackId, ()> {
+		match origin {
+			OriginCaller::system(frame_system::RawOrigin::Root) => Ok(0),
+			OriginCaller::system(frame_system::RawOrigin::None) => Ok(1),
+			_ => Err(()),
+		}
+	}
+}
+
+parameter_types! {
+	pub const SubmissionDeposit: u64 = 2;
+	pub const MaxQueued: u32 = 3;
+	pub const UndecidingTimeout: u64 = 20;
+	pub const AlarmInterval: u64 = 1;
+}
+impl super::Trait for Test {
+	type Event = Event;
+	type Call = Call;
+	type Currency = pallet_balances::Module<Self>;
+	type Scheduler = Scheduler;
+	type PalletsOrigin = OriginCaller;
+	type CancelOrigin = EnsureRoot<u64>;
+	type KillOrigin = EnsureRoot<u64>;
+	type Slash = ();
+	type Tally = Tally;
+	type SubmissionDeposit = SubmissionDeposit;
+	type MaxQueued = MaxQueued;
+	type UndecidingTimeout = UndecidingTimeout;
+	type AlarmInterval = AlarmInterval;
+	type Tracks = TestTracksInfo;
+	type WeightInfo = ();
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test>{
+		balances: vec![(1, 100), (2, 100), (3, 100), (4, 100), (5, 100), (6, 100)],
+	}.assimilate_storage(&mut t).unwrap();
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+type System = frame_system::Module<Test>;
+type Balances = pallet_balances::Module<Test>;
+type Scheduler = pallet_scheduler::Module<Test>;
+type Referenda = Module<Test>;
+
+fn next_block() {
+	System::set_block_number(System::block_number() + 1);
+	Scheduler::on_initialize(System::block_number());
+}
+
+fn run_to(n: u64) {
+	while System::block_number() < n {
+		next_block();
+	}
+}
+
+fn set_balance_proposal(value: u64) -> Box<Call> {
+	Box::new(Call::Balances(pallet_balances::Call::set_balance(42, value, 0)))
+}
+
+fn submit_root(who: u64, value: u64) -> ReferendumIndex {
+	let index = Referenda::referendum_count();
+	assert_ok!(Referenda::submit(
+		Origin::signed(who),
+		Box::new(frame_system::RawOrigin::Root.into()),
+		set_balance_proposal(value),
+		DispatchTime::At(10),
+	));
+	index
+}
+
+fn set_tally(index: ReferendumIndex, ayes: u32, nays: u32) {
+	Referenda::access_poll(index, |status| {
+		if let PollStatus::Ongoing(tally, _) = status {
+			*tally = Tally { ayes, nays };
+		}
+	});
+}
+
+fn deciding_since(index: ReferendumIndex) -> Option<u64> {
+	Referenda::ensure_ongoing(index).ok()?.deciding.map(|d| d.since)
+}
+
+fn confirming(index: ReferendumIndex) -> Option<u64> {
+	Referenda::ensure_ongoing(index).ok()?.deciding?.confirming
+}
+
+#[test]
+fn params_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Referenda::referendum_count(), 0);
+		assert_eq!(Balances::free_balance(42), 0);
+		assert_eq!(Balances::total_issuance(), 600);
+		assert_eq!(<Referenda as Polling<Tally>>::classes(), vec![0, 1]);
+	});
+}
+
+#[test]
+fn basic_happy_path_works() {
+	new_test_ext().execute_with(|| {
+		let r = submit_root(1, 20);
+		assert_eq!(Balances::reserved_balance(1), 2);
+		assert_eq!(<Referenda as Polling<Tally>>::as_ongoing(r), Some((Tally::default(), 0)));
+
+		assert_ok!(Referenda::place_decision_deposit(Origin::signed(2), r));
+		assert_eq!(Balances::reserved_balance(2), 10);
+		assert_eq!(deciding_since(r), None);
+
+		// The preparation period ends at block 5.
+		run_to(5);
+		assert_eq!(deciding_since(r), Some(5));
+		assert_eq!(Referenda::deciding_count(0), 1);
+
+		set_tally(r, 100, 0);
+		run_to(6);
+		assert_eq!(confirming(r), Some(8));
+
+		run_to(8);
+		assert_eq!(
+			Referenda::referendum_info(r),
+			Some(ReferendumInfo::Approved(8, Some(Deposit { who: 2, amount: 10 }))),
+		);
+		assert_eq!(Referenda::deciding_count(0), 0);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert!(<Referenda as Polling<Tally>>::access_poll(r, |s| s == PollStatus::Completed(8, true)));
+
+		// Enactment is no earlier than the minimum enactment period after approval.
+		run_to(11);
+		assert_eq!(Balances::free_balance(42), 0);
+		run_to(12);
+		assert_eq!(Balances::free_balance(42), 20);
+
+		assert_ok!(Referenda::refund_decision_deposit(Origin::signed(3), r));
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_noop!(
+			Referenda::refund_decision_deposit(Origin::signed(3), r),
+			Error::<Test>::NoDeposit,
+		);
+	});
+}
+
+#[test]
+fn referendum_not_passing_is_rejected() {
+	new_test_ext().execute_with(|| {
+		let r = submit_root(1, 20);
+		assert_ok!(Referenda::place_decision_deposit(Origin::signed(2), r));
+		run_to(8);
+		assert_eq!(deciding_since(r), Some(5));
+
+		run_to(9);
+		assert_eq!(
+			Referenda::referendum_info(r),
+			Some(ReferendumInfo::Rejected(9, Some(Deposit { who: 2, amount: 10 }))),
+		);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		run_to(12);
+		assert_eq!(Balances::free_balance(42), 0);
+	});
+}
+
+#[test]
+fn confirmation_is_aborted_when_tally_stops_passing() {
+	new_test_ext().execute_with(|| {
+		let r = submit_root(1, 20);
+		assert_ok!(Referenda::place_decision_deposit(Origin::signed(2), r));
+		run_to(5);
+		set_tally(r, 100, 0);
+		run_to(6);
+		assert_eq!(confirming(r), Some(8));
+
+		set_tally(r, 100, 200);
+		run_to(7);
+		assert_eq!(confirming(r), None);
+
+		run_to(9);
+		assert!(matches!(Referenda::referendum_info(r), Some(ReferendumInfo::Rejected(9, _))));
+	});
+}
+
+#[test]
+fn decreasing_curves_are_followed() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Referenda::submit(
+			Origin::signed(1),
+			Box::new(frame_system::RawOrigin::None.into()),
+			set_balance_proposal(20),
+			DispatchTime::After(0),
+		));
+		assert_ok!(Referenda::place_decision_deposit(Origin::signed(2), 0));
+		run_to(3);
+		assert_eq!(deciding_since(0), Some(3));
+
+		// Both curves start at 100%, so 60% approval with 60% support is not yet enough.
+		set_tally(0, 60, 40);
+		assert_eq!(confirming(0), None);
+
+		// Half of the decision period later, both curves have gone down five steps to 50%.
+		run_to(4);
+		assert_eq!(confirming(0), Some(5));
+		run_to(5);
+		assert!(matches!(Referenda::referendum_info(0), Some(ReferendumInfo::Approved(5, _))));
+		run_to(7);
+		assert_eq!(Balances::free_balance(42), 20);
+	});
+}
+
+#[test]
+fn decision_slots_are_handed_over_to_queued_referenda() {
+	new_test_ext().execute_with(|| {
+		let r0 = submit_root(1, 20);
+		let r1 = submit_root(3, 30);
+		let r2 = submit_root(4, 40);
+		assert_ok!(Referenda::place_decision_deposit(Origin::signed(2), r0));
+		assert_ok!(Referenda::place_decision_deposit(Origin::signed(2), r1));
+		assert_ok!(Referenda::place_decision_deposit(Origin::signed(2), r2));
+		run_to(5);
+		assert_eq!(deciding_since(r0), Some(5));
+		assert_eq!(Referenda::deciding_count(0), 1);
+		assert_eq!(Referenda::track_queue(0), vec![(r2, 0), (r1, 0)]);
+
+		// The queue is ordered by aye votes.
+		set_tally(r1, 10, 0);
+		assert_eq!(Referenda::track_queue(0), vec![(r2, 0), (r1, 10)]);
+
+		run_to(6);
+		assert_ok!(Referenda::cancel(Origin::root(), r0));
+		assert_eq!(
+			Referenda::referendum_info(r0),
+			Some(ReferendumInfo::Cancelled(6, Some(Deposit { who: 2, amount: 10 }))),
+		);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(deciding_since(r1), Some(6));
+		assert_eq!(Referenda::deciding_count(0), 1);
+		assert_eq!(Referenda::track_queue(0), vec![(r2, 0)]);
+	});
+}
+
+#[test]
+fn referendum_without_decision_deposit_times_out() {
+	new_test_ext().execute_with(|| {
+		let r = submit_root(1, 20);
+		run_to(20);
+		assert!(Referenda::ensure_ongoing(r).is_ok());
+		run_to(21);
+		assert_eq!(Referenda::referendum_info(r), Some(ReferendumInfo::TimedOut(21, None)));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_noop!(
+			Referenda::refund_decision_deposit(Origin::signed(1), r),
+			Error::<Test>::NoDeposit,
+		);
+	});
+}
+
+#[test]
+fn kill_slashes_deposits() {
+	new_test_ext().execute_with(|| {
+		let r = submit_root(1, 20);
+		assert_ok!(Referenda::place_decision_deposit(Origin::signed(2), r));
+		assert_noop!(Referenda::kill(Origin::signed(1), r), BadOrigin);
+		assert_ok!(Referenda::kill(Origin::root(), r));
+		assert_eq!(Referenda::referendum_info(r), Some(ReferendumInfo::Killed(1)));
+		assert_eq!(Balances::free_balance(1), 98);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(2), 90);
+		assert_eq!(Balances::reserved_balance(2), 0);
+
+		// The alarm was cancelled along with the referendum.
+		run_to(21);
+		assert_eq!(Referenda::referendum_info(r), Some(ReferendumInfo::Killed(1)));
+	});
+}
+
+#[test]
+fn submission_and_deposit_checks_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Referenda::submit(
+				Origin::signed(1),
+				Box::new(frame_system::RawOrigin::Signed(1).into()),
+				set_balance_proposal(20),
+				DispatchTime::At(10),
+			),
+			Error::<Test>::NoTrack,
+		);
+		assert_noop!(
+			Referenda::place_decision_deposit(Origin::signed(2), 0),
+			Error::<Test>::NotOngoing,
+		);
+
+		let r = submit_root(1, 20);
+		assert_noop!(
+			Referenda::refund_decision_deposit(Origin::signed(2), r),
+			Error::<Test>::Unfinished,
+		);
+		assert_noop!(
+			Referenda::refund_decision_deposit(Origin::signed(2), r + 1),
+			Error::<Test>::BadReferendum,
+		);
+		assert_ok!(Referenda::place_decision_deposit(Origin::signed(2), r));
+		assert_noop!(
+			Referenda::place_decision_deposit(Origin::signed(3), r),
+			Error::<Test>::HasDeposit,
+		);
+		assert_noop!(Referenda::nudge_referendum(Origin::signed(1), r), BadOrigin);
+	});
+}
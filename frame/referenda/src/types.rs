@@ -0,0 +1,207 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Miscellaneous additional datatypes.
+
+use codec::{Encode, Decode};
+use sp_runtime::{Perbill, RuntimeDebug, traits::Saturating};
+use frame_support::traits::schedule::DispatchTime;
+
+/// The identifier of a track.
+pub type TrackId = u16;
+
+/// A referendum index.
+pub type ReferendumIndex = u32;
+
+/// A curve giving a threshold as a function of the proportion of the decision period elapsed.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum Curve {
+	/// Decreases linearly from `begin` at the start of the decision period to `begin - delta` at
+	/// its end.
+	LinearDecreasing { begin: Perbill, delta: Perbill },
+	/// Starts at `begin` and decreases by `step` each time a further `period` of the decision
+	/// period has elapsed, never going below `end`.
+	SteppedDecreasing { begin: Perbill, end: Perbill, step: Perbill, period: Perbill },
+}
+
+impl Curve {
+	/// The threshold once the proportion `x` of the decision period has elapsed.
+	pub fn threshold(&self, x: Perbill) -> Perbill {
+		match self {
+			Curve::LinearDecreasing { begin, delta } =>
+				begin.saturating_sub(delta.saturating_mul(x)),
+			Curve::SteppedDecreasing { begin, end, step, period } => {
+				let steps = x.deconstruct().checked_div(period.deconstruct()).unwrap_or(0);
+				let decrease = Perbill::from_parts(step.deconstruct().saturating_mul(steps));
+				begin.saturating_sub(decrease).max(*end)
+			}
+		}
+	}
+}
+
+/// Information about a track.
+#[derive(Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct TrackInfo<Balance, Moment> {
+	/// Name of this track.
+	pub name: &'static str,
+	/// The maximum number of referenda which may be in the decision stage at the same time.
+	pub max_deciding: u32,
+	/// Amount that must be placed on deposit before a decision can be made.
+	pub decision_deposit: Balance,
+	/// Minimum amount of time between submission and the start of the decision stage.
+	pub prepare_period: Moment,
+	/// Amount of time a decision may take to be made. A referendum which is not confirming at
+	/// the end of this period is rejected.
+	pub decision_period: Moment,
+	/// Amount of time that a referendum must keep passing before it is approved.
+	pub confirm_period: Moment,
+	/// Minimum amount of time between approval and enactment.
+	pub min_enactment_period: Moment,
+	/// Minimum proportion of aye votes among all votes, as a function of the proportion of the
+	/// decision period elapsed.
+	pub min_approval: Curve,
+	/// Minimum proportion of the total issuance voting aye, as a function of the proportion of
+	/// the decision period elapsed.
+	pub min_support: Curve,
+}
+
+/// Information on the tracks referenda may be submitted on.
+pub trait TracksInfo<Balance, Moment> {
+	/// The origin type from which a track is derived.
+	type Origin;
+
+	/// All known tracks, sorted by identifier.
+	fn tracks() -> &'static [(TrackId, TrackInfo<Balance, Moment>)];
+
+	/// Determine the track of a referendum whose proposal would be dispatched with `origin`.
+	fn track_for(origin: &Self::Origin) -> Result<TrackId, ()>;
+
+	/// Return the information of track `id`, if it exists.
+	fn info(id: TrackId) -> Option<&'static TrackInfo<Balance, Moment>> {
+		Self::tracks().iter().find(|x| x.0 == id).map(|x| &x.1)
+	}
+}
+
+/// A deposit held on behalf of an account.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Deposit<AccountId, Balance> {
+	/// The account which placed the deposit.
+	pub who: AccountId,
+	/// The amount reserved.
+	pub amount: Balance,
+}
+
+/// The status of a referendum in the decision stage.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct DecidingStatus<Moment> {
+	/// When the decision stage began.
+	pub since: Moment,
+	/// If `Some`, the referendum is passing and will be approved at the given moment unless it
+	/// stops passing before.
+	pub confirming: Option<Moment>,
+}
+
+/// Info regarding an ongoing referendum.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct ReferendumStatus<AccountId, Balance, Moment, Origin, Call, Tally> {
+	/// The track of the referendum.
+	pub track: TrackId,
+	/// The origin with which the proposal is dispatched if approved.
+	pub origin: Origin,
+	/// The proposal.
+	pub proposal: Call,
+	/// When the proposal should be enacted once approved.
+	pub enactment: DispatchTime<Moment>,
+	/// When the referendum was submitted.
+	pub submitted: Moment,
+	/// The deposit placed on submission.
+	pub submission_deposit: Deposit<AccountId, Balance>,
+	/// The deposit needed for the referendum to enter the decision stage, if placed.
+	pub decision_deposit: Option<Deposit<AccountId, Balance>>,
+	/// The decision stage status, if the referendum is being decided.
+	pub deciding: Option<DecidingStatus<Moment>>,
+	/// The current tally of votes.
+	pub tally: Tally,
+	/// Whether the referendum is waiting in the queue of its track for a decision slot.
+	pub in_queue: bool,
+	/// The next block at which the referendum is nudged by the scheduler, if any.
+	pub alarm: Option<Moment>,
+}
+
+/// Info regarding a referendum, present or past.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum ReferendumInfo<AccountId, Balance, Moment, Origin, Call, Tally> {
+	/// Referendum has been submitted and is being voted on.
+	Ongoing(ReferendumStatus<AccountId, Balance, Moment, Origin, Call, Tally>),
+	/// Referendum was approved at the given moment.
+	Approved(Moment, Option<Deposit<AccountId, Balance>>),
+	/// Referendum was rejected at the given moment.
+	Rejected(Moment, Option<Deposit<AccountId, Balance>>),
+	/// Referendum was cancelled at the given moment.
+	Cancelled(Moment, Option<Deposit<AccountId, Balance>>),
+	/// Referendum never entered the decision stage and timed out at the given moment.
+	TimedOut(Moment, Option<Deposit<AccountId, Balance>>),
+	/// Referendum was killed at the given moment; its deposits were slashed.
+	Killed(Moment),
+}
+
+impl<AccountId, Balance, Moment, Origin, Call, Tally>
+	ReferendumInfo<AccountId, Balance, Moment, Origin, Call, Tally>
+{
+	/// Take the decision deposit of a completed referendum, if any remains to be refunded.
+	pub fn take_decision_deposit(&mut self) -> Result<Option<Deposit<AccountId, Balance>>, ()> {
+		match self {
+			ReferendumInfo::Ongoing(_) => Err(()),
+			ReferendumInfo::Approved(_, d) |
+			ReferendumInfo::Rejected(_, d) |
+			ReferendumInfo::Cancelled(_, d) |
+			ReferendumInfo::TimedOut(_, d) => Ok(d.take()),
+			ReferendumInfo::Killed(_) => Ok(None),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn linear_curve_works() {
+		let c = Curve::LinearDecreasing {
+			begin: Perbill::from_percent(100),
+			delta: Perbill::from_percent(50),
+		};
+		assert_eq!(c.threshold(Perbill::zero()), Perbill::from_percent(100));
+		assert_eq!(c.threshold(Perbill::from_percent(50)), Perbill::from_percent(75));
+		assert_eq!(c.threshold(Perbill::one()), Perbill::from_percent(50));
+	}
+
+	#[test]
+	fn stepped_curve_works() {
+		let c = Curve::SteppedDecreasing {
+			begin: Perbill::from_percent(80),
+			end: Perbill::from_percent(30),
+			step: Perbill::from_percent(10),
+			period: Perbill::from_percent(15),
+		};
+		assert_eq!(c.threshold(Perbill::zero()), Perbill::from_percent(80));
+		assert_eq!(c.threshold(Perbill::from_percent(14)), Perbill::from_percent(80));
+		assert_eq!(c.threshold(Perbill::from_percent(15)), Perbill::from_percent(70));
+		assert_eq!(c.threshold(Perbill::from_percent(45)), Perbill::from_percent(50));
+		assert_eq!(c.threshold(Perbill::one()), Perbill::from_percent(30));
+	}
+}
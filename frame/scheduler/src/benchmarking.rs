@@ -44,7 +44,7 @@ fn fill_schedule<T: Trait> (when: T::BlockNumber, n: u32) -> Result<(), &'static
 			// HARD_DEADLINE priority means it gets executed no matter what
 			0,
 			frame_system::RawOrigin::Root.into(),
-			call.clone().into(),
+			MaybeHashed::Value(call.clone().into()),
 		)?;
 	}
 	ensure!(Agenda::<T>::get(when).len() == n as usize, "didn't fill schedule");
@@ -60,7 +60,7 @@ benchmarks! {
 		let periodic = Some((T::BlockNumber::one(), 100));
 		let priority = 0;
 		// Essentially a no-op call.
-		let call = Box::new(frame_system::Call::set_storage(vec![]).into());
+		let call = Box::new(MaybeHashed::Value(frame_system::Call::set_storage(vec![]).into()));
 
 		fill_schedule::<T>(when, s)?;
 	}: _(RawOrigin::Root, when, periodic, priority, call)
@@ -97,7 +97,7 @@ benchmarks! {
 		let periodic = Some((T::BlockNumber::one(), 100));
 		let priority = 0;
 		// Essentially a no-op call.
-		let call = Box::new(frame_system::Call::set_storage(vec![]).into());
+		let call = Box::new(MaybeHashed::Value(frame_system::Call::set_storage(vec![]).into()));
 
 		fill_schedule::<T>(when, s)?;
 	}: _(RawOrigin::Root, id, when, periodic, priority, call)
@@ -28,6 +28,12 @@
 //! specified block number or at a specified period. These scheduled dispatches
 //! may be named or anonymous and may be canceled.
 //!
+//! Instead of the call itself, a task may be given the hash of the encoded call.
+//! The preimage is requested from the configured `PreimageProvider` when the
+//! task is scheduled and looked up when the task is due. If the preimage is not
+//! available by then, the task is postponed by `NoPreimagePostponement` blocks,
+//! or dropped if no postponement is configured.
+//!
 //! **NOTE:** The scheduled calls will be dispatched with the default filter
 //! for the origin: namely `frame_system::Trait::BaseCallFilter` for all origin
 //! except root which will get no filter. And not the filter contained in origin
@@ -60,7 +66,10 @@ use sp_runtime::{RuntimeDebug, traits::{Zero, One, BadOrigin, Saturating}};
 use frame_support::{
 	decl_module, decl_storage, decl_event, decl_error, IterableStorageMap,
 	dispatch::{Dispatchable, DispatchError, DispatchResult, Parameter},
-	traits::{Get, schedule::{self, DispatchTime}, OriginTrait, EnsureOrigin, IsType},
+	traits::{
+		Get, schedule::{self, DispatchTime, MaybeHashed, LookupError}, OriginTrait, EnsureOrigin, IsType,
+		PreimageProvider,
+	},
 	weights::{GetDispatchInfo, Weight},
 };
 use frame_system::{self as system};
@@ -104,12 +113,21 @@ pub trait Trait: system::Trait {
 
 	/// Weight information for extrinsics in this pallet.
 	type WeightInfo: WeightInfo;
+
+	/// The source of preimages for calls which are scheduled by hash.
+	type PreimageProvider: PreimageProvider<Self::Hash>;
+
+	/// If `Some` then the number of blocks to postpone execution for when the item is delayed
+	/// because its preimage is not yet available.
+	type NoPreimagePostponement: Get<Option<Self::BlockNumber>>;
 }
 
 /// Just a simple index for naming period tasks.
 pub type PeriodicIndex = u32;
 /// The location of a scheduled task that can be used to remove it.
 pub type TaskAddress<BlockNumber> = (BlockNumber, u32);
+/// A call which is either given in full or by the hash of its encoding.
+pub type CallOrHashOf<T> = MaybeHashed<<T as Trait>::Call, <T as system::Trait>::Hash>;
 
 #[cfg_attr(any(feature = "std", test), derive(PartialEq, Eq))]
 #[derive(Clone, RuntimeDebug, Encode, Decode)]
@@ -140,6 +158,14 @@ pub struct ScheduledV2<Call, BlockNumber, PalletsOrigin, AccountId> {
 /// The current version of Scheduled struct.
 pub type Scheduled<Call, BlockNumber, PalletsOrigin, AccountId> = ScheduledV2<Call, BlockNumber, PalletsOrigin, AccountId>;
 
+/// The scheduled item as kept in `Agenda`, whose call may be given by hash.
+pub type ScheduledOf<T> = Scheduled<
+	CallOrHashOf<T>,
+	<T as system::Trait>::BlockNumber,
+	<T as Trait>::PalletsOrigin,
+	<T as system::Trait>::AccountId,
+>;
+
 // A value placed in storage that represents the current version of the Scheduler storage.
 // This value is used by the `on_runtime_upgrade` logic to determine whether we run
 // storage migration logic.
@@ -147,6 +173,7 @@ pub type Scheduled<Call, BlockNumber, PalletsOrigin, AccountId> = ScheduledV2<Ca
 enum Releases {
 	V1,
 	V2,
+	V3,
 }
 
 impl Default for Releases {
@@ -159,7 +186,7 @@ decl_storage! {
 	trait Store for Module<T: Trait> as Scheduler {
 		/// Items to be executed, indexed by the block number that they should be executed on.
 		pub Agenda: map hasher(twox_64_concat) T::BlockNumber
-			=> Vec<Option<Scheduled<<T as Trait>::Call, T::BlockNumber, T::PalletsOrigin, T::AccountId>>>;
+			=> Vec<Option<ScheduledOf<T>>>;
 
 		/// Lookup from identity to the block number and index of the task.
 		Lookup: map hasher(twox_64_concat) Vec<u8> => Option<TaskAddress<T::BlockNumber>>;
@@ -167,7 +194,7 @@ decl_storage! {
 		/// Storage version of the pallet.
 		///
 		/// New networks start with last version.
-		StorageVersion build(|_| Releases::V2): Releases;
+		StorageVersion build(|_| Releases::V3): Releases;
 	}
 }
 
@@ -179,6 +206,9 @@ decl_event!(
 		Canceled(BlockNumber, u32),
 		/// Dispatched some task. \[task, id, result\]
 		Dispatched(TaskAddress<BlockNumber>, Option<Vec<u8>>, DispatchResult),
+		/// The call for the provided hash was not found so the task has been postponed or
+		/// dropped. \[task, id, error\]
+		CallLookupFailed(TaskAddress<BlockNumber>, Option<Vec<u8>>, LookupError),
 	}
 );
 
@@ -199,6 +229,14 @@ decl_module! {
 		type Error = Error<T>;
 		fn deposit_event() = default;
 
+		fn on_runtime_upgrade() -> Weight {
+			if Self::migrate_v1_to_v3() || Self::migrate_v2_to_v3() {
+				T::MaximumBlockWeight::get()
+			} else {
+				T::DbWeight::get().reads(1)
+			}
+		}
+
 		/// Anonymously schedule a task.
 		///
 		/// # <weight>
@@ -214,7 +252,7 @@ decl_module! {
 			when: T::BlockNumber,
 			maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
 			priority: schedule::Priority,
-			call: Box<<T as Trait>::Call>,
+			call: Box<CallOrHashOf<T>>,
 		) {
 			T::ScheduleOrigin::ensure_origin(origin.clone())?;
 			let origin = <T as Trait>::Origin::from(origin);
@@ -254,7 +292,7 @@ decl_module! {
 			when: T::BlockNumber,
 			maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
 			priority: schedule::Priority,
-			call: Box<<T as Trait>::Call>,
+			call: Box<CallOrHashOf<T>>,
 		) {
 			T::ScheduleOrigin::ensure_origin(origin.clone())?;
 			let origin = <T as Trait>::Origin::from(origin);
@@ -290,7 +328,7 @@ decl_module! {
 			after: T::BlockNumber,
 			maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
 			priority: schedule::Priority,
-			call: Box<<T as Trait>::Call>,
+			call: Box<CallOrHashOf<T>>,
 		) {
 			T::ScheduleOrigin::ensure_origin(origin.clone())?;
 			let origin = <T as Trait>::Origin::from(origin);
@@ -310,7 +348,7 @@ decl_module! {
 			after: T::BlockNumber,
 			maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
 			priority: schedule::Priority,
-			call: Box<<T as Trait>::Call>,
+			call: Box<CallOrHashOf<T>>,
 		) {
 			T::ScheduleOrigin::ensure_origin(origin.clone())?;
 			let origin = <T as Trait>::Origin::from(origin);
@@ -325,10 +363,11 @@ decl_module! {
 		/// - S = Number of already scheduled calls
 		/// - N = Named scheduled calls
 		/// - P = Periodic Calls
+		/// - H = Calls scheduled by hash
 		/// - Base Weight: 9.243 + 23.45 * S µs
 		/// - DB Weight:
-		///     - Read: Agenda + Lookup * N + Agenda(Future) * P
-		///     - Write: Agenda + Lookup * N  + Agenda(future) * P
+		///     - Read: Agenda + Lookup * N + Agenda(Future) * P + Preimage * 2H
+		///     - Write: Agenda + Lookup * N  + Agenda(future) * P + Preimage * H
 		/// # </weight>
 		fn on_initialize(now: T::BlockNumber) -> Weight {
 			let limit = T::MaximumWeight::get();
@@ -343,13 +382,24 @@ decl_module! {
 				);
 			}
 			queued.sort_by_key(|(_, s)| s.priority);
-			let base_weight: Weight = T::DbWeight::get().reads_writes(1, 2); // Agenda + Agenda(next)
+			let mut lookup_weight: Weight = 0;
+			let queued = queued.into_iter()
+				.filter_map(|(index, s)| {
+					if s.call.as_hash().is_some() {
+						// Read preimage and its status, write the status
+						lookup_weight = lookup_weight.saturating_add(T::DbWeight::get().reads_writes(2, 1));
+					}
+					Self::resolve_call(now, index, s)
+				})
+				.collect::<Vec<_>>();
+			// Agenda + Agenda(next) + preimage lookups
+			let base_weight: Weight = T::DbWeight::get().reads_writes(1, 2).saturating_add(lookup_weight);
 			let mut total_weight: Weight = 0;
 			queued.into_iter()
 				.enumerate()
-				.scan(base_weight, |cumulative_weight, (order, (index, s))| {
+				.scan(base_weight, |cumulative_weight, (order, (index, call, s))| {
 					*cumulative_weight = cumulative_weight
-						.saturating_add(s.call.get_dispatch_info().weight);
+						.saturating_add(call.get_dispatch_info().weight);
 
 					if s.maybe_id.is_some() {
 						// Remove/Modify Lookup
@@ -360,15 +410,15 @@ decl_module! {
 						*cumulative_weight = cumulative_weight.saturating_add(T::DbWeight::get().reads_writes(1, 1));
 					}
 
-					Some((order, index, *cumulative_weight, s))
+					Some((order, index, *cumulative_weight, call, s))
 				})
-				.filter_map(|(order, index, cumulative_weight, mut s)| {
+				.filter_map(|(order, index, cumulative_weight, call, mut s)| {
 					// We allow a scheduled call if any is true:
 					// - It's priority is `HARD_DEADLINE`
 					// - It does not push the weight past the limit.
 					// - It is the first item in the schedule
 					if s.priority <= schedule::HARD_DEADLINE || cumulative_weight <= limit || order == 0 {
-						let r = s.call.clone().dispatch(s.origin.clone().into());
+						let r = call.dispatch(s.origin.clone().into());
 						let maybe_id = s.maybe_id.clone();
 						if let &Some((period, count)) = &s.maybe_periodic {
 							if count > 1 {
@@ -404,17 +454,17 @@ decl_module! {
 					Agenda::<T>::append(next, unused);
 				});
 
-			total_weight
+			total_weight.max(lookup_weight)
 		}
 	}
 }
 
 impl<T: Trait> Module<T> {
-	/// Migrate storage format from V1 to V2.
+	/// Migrate storage format from V1 to V3.
 	/// Return true if migration is performed.
-	pub fn migrate_v1_to_t2() -> bool {
+	pub fn migrate_v1_to_v3() -> bool {
 		if StorageVersion::get() == Releases::V1 {
-			StorageVersion::put(Releases::V2);
+			StorageVersion::put(Releases::V3);
 
 			Agenda::<T>::translate::<
 				Vec<Option<ScheduledV1<<T as Trait>::Call, T::BlockNumber>>>, _
@@ -424,7 +474,7 @@ impl<T: Trait> Module<T> {
 					.map(|schedule| schedule.map(|schedule| ScheduledV2 {
 						maybe_id: schedule.maybe_id,
 						priority: schedule.priority,
-						call: schedule.call,
+						call: schedule.call.into(),
 						maybe_periodic: schedule.maybe_periodic,
 						origin: system::RawOrigin::Root.into(),
 						_phantom: Default::default(),
@@ -438,10 +488,38 @@ impl<T: Trait> Module<T> {
 		}
 	}
 
+	/// Migrate storage format from V2 to V3, keeping every stored call as a plain value.
+	/// Return true if migration is performed.
+	pub fn migrate_v2_to_v3() -> bool {
+		if StorageVersion::get() == Releases::V2 {
+			StorageVersion::put(Releases::V3);
+
+			Agenda::<T>::translate::<
+				Vec<Option<ScheduledV2<<T as Trait>::Call, T::BlockNumber, T::PalletsOrigin, T::AccountId>>>, _
+			>(|_, agenda| Some(
+				agenda
+					.into_iter()
+					.map(|schedule| schedule.map(|schedule| ScheduledV2 {
+						maybe_id: schedule.maybe_id,
+						priority: schedule.priority,
+						call: schedule.call.into(),
+						maybe_periodic: schedule.maybe_periodic,
+						origin: schedule.origin,
+						_phantom: Default::default(),
+					}))
+					.collect::<Vec<_>>()
+			));
+
+			true
+		} else {
+			false
+		}
+	}
+
 	/// Helper to migrate scheduler when the pallet origin type has changed.
 	pub fn migrate_origin<OldOrigin: Into<T::PalletsOrigin> + codec::Decode>() {
 		Agenda::<T>::translate::<
-			Vec<Option<Scheduled<<T as Trait>::Call, T::BlockNumber, OldOrigin, T::AccountId>>>, _
+			Vec<Option<Scheduled<CallOrHashOf<T>, T::BlockNumber, OldOrigin, T::AccountId>>>, _
 		>(|_, agenda| Some(
 			agenda
 				.into_iter()
@@ -462,7 +540,7 @@ impl<T: Trait> Module<T> {
 		maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
 		priority: schedule::Priority,
 		origin: T::PalletsOrigin,
-		call: <T as Trait>::Call
+		call: CallOrHashOf<T>,
 	) -> Result<TaskAddress<T::BlockNumber>, DispatchError> {
 		let now = frame_system::Module::<T>::block_number();
 
@@ -480,6 +558,9 @@ impl<T: Trait> Module<T> {
 			.filter(|p| p.1 > 1 && !p.0.is_zero())
 			// Remove one from the number of repetitions since we will schedule one now.
 			.map(|(p, c)| (p, c - 1));
+		if let Some(hash) = call.as_hash() {
+			T::PreimageProvider::request_preimage(hash);
+		}
 		let s = Some(Scheduled {
 			maybe_id: None, priority, call, maybe_periodic, origin, _phantom: PhantomData::<T::AccountId>::default(),
 		});
@@ -515,6 +596,9 @@ impl<T: Trait> Module<T> {
 			},
 		)?;
 		if let Some(s) = scheduled {
+			if let Some(hash) = s.call.as_hash() {
+				T::PreimageProvider::unrequest_preimage(hash);
+			}
 			if let Some(id) = s.maybe_id {
 				Lookup::<T>::remove(id);
 			}
@@ -531,7 +615,7 @@ impl<T: Trait> Module<T> {
 		maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
 		priority: schedule::Priority,
 		origin: T::PalletsOrigin,
-		call: <T as Trait>::Call,
+		call: CallOrHashOf<T>,
 	) -> Result<TaskAddress<T::BlockNumber>, DispatchError> {
 		// ensure id it is unique
 		if Lookup::<T>::contains_key(&id) {
@@ -554,6 +638,9 @@ impl<T: Trait> Module<T> {
 			.filter(|p| p.1 > 1 && !p.0.is_zero())
 			// Remove one from the number of repetitions since we will schedule one now.
 			.map(|(p, c)| (p, c - 1));
+		if let Some(hash) = call.as_hash() {
+			T::PreimageProvider::request_preimage(hash);
+		}
 
 		let s = Scheduled {
 			maybe_id: Some(id.clone()), priority, call, maybe_periodic, origin, _phantom: Default::default()
@@ -584,7 +671,9 @@ impl<T: Trait> Module<T> {
 								return Err(BadOrigin.into());
 							}
 						}
-						*s = None;
+						if let Some(hash) = s.take().as_ref().and_then(|s| s.call.as_hash()) {
+							T::PreimageProvider::unrequest_preimage(hash);
+						}
 					}
 					Ok(())
 				})?;
@@ -595,6 +684,55 @@ impl<T: Trait> Module<T> {
 			}
 		})
 	}
+
+	/// Resolve the call of a due task, should it be given by hash.
+	///
+	/// Returns the task together with its call if it can be dispatched. Otherwise the task is
+	/// postponed by `NoPreimagePostponement` blocks if its preimage is unknown, or dropped.
+	fn resolve_call(
+		now: T::BlockNumber,
+		index: u32,
+		mut s: ScheduledOf<T>,
+	) -> Option<(u32, <T as Trait>::Call, ScheduledOf<T>)> {
+		let (call, maybe_resolved) = s.call.resolved::<T::PreimageProvider>();
+		s.call = call;
+		if let Some(hash) = maybe_resolved {
+			// The call is kept in full from now on, so the preimage is no longer needed.
+			T::PreimageProvider::unrequest_preimage(&hash);
+		}
+		let hash = match s.call {
+			MaybeHashed::Value(ref call) => {
+				let call = call.clone();
+				return Some((index, call, s))
+			},
+			MaybeHashed::Hash(hash) => hash,
+		};
+
+		let error = if T::PreimageProvider::have_preimage(&hash) {
+			LookupError::BadFormat
+		} else {
+			LookupError::Unknown
+		};
+		Self::deposit_event(RawEvent::CallLookupFailed((now, index), s.maybe_id.clone(), error));
+
+		match (error, T::NoPreimagePostponement::get()) {
+			(LookupError::Unknown, Some(delay)) => {
+				let until = now.saturating_add(delay.max(One::one()));
+				if let Some(ref id) = s.maybe_id {
+					let next_index = Agenda::<T>::decode_len(until).unwrap_or(0);
+					Lookup::<T>::insert(id, (until, next_index as u32));
+				}
+				Agenda::<T>::append(until, Some(s));
+			},
+			_ => {
+				T::PreimageProvider::unrequest_preimage(&hash);
+				if let Some(ref id) = s.maybe_id {
+					Lookup::<T>::remove(id);
+				}
+			},
+		}
+		None
+	}
 }
 
 impl<T: Trait> schedule::Anon<T::BlockNumber, <T as Trait>::Call, T::PalletsOrigin> for Module<T> {
@@ -607,7 +745,7 @@ impl<T: Trait> schedule::Anon<T::BlockNumber, <T as Trait>::Call, T::PalletsOrig
 		origin: T::PalletsOrigin,
 		call: <T as Trait>::Call
 	) -> Result<Self::Address, DispatchError> {
-		Self::do_schedule(when, maybe_periodic, priority, origin, call)
+		Self::do_schedule(when, maybe_periodic, priority, origin, call.into())
 	}
 
 	fn cancel((when, index): Self::Address) -> Result<(), ()> {
@@ -626,7 +764,7 @@ impl<T: Trait> schedule::Named<T::BlockNumber, <T as Trait>::Call, T::PalletsOri
 		origin: T::PalletsOrigin,
 		call: <T as Trait>::Call,
 	) -> Result<Self::Address, ()> {
-		Self::do_schedule_named(id, when, maybe_periodic, priority, origin, call).map_err(|_| ())
+		Self::do_schedule_named(id, when, maybe_periodic, priority, origin, call.into()).map_err(|_| ())
 	}
 
 	fn cancel_named(id: Vec<u8>) -> Result<(), ()> {
@@ -641,22 +779,22 @@ mod tests {
 	use frame_support::{
 		impl_outer_event, impl_outer_origin, impl_outer_dispatch, parameter_types, assert_ok, ord_parameter_types,
 		assert_noop, assert_err, Hashable,
-		traits::{OnInitialize, OnFinalize, Filter},
+		traits::{OnInitialize, OnFinalize, OnRuntimeUpgrade, Filter},
 		weights::constants::RocksDbWeight,
 	};
 	use sp_core::H256;
 	use sp_runtime::{
 		Perbill,
 		testing::Header,
-		traits::{BlakeTwo256, IdentityLookup},
+		traits::{BlakeTwo256, Hash, IdentityLookup},
 	};
 	use frame_system::{EnsureOneOf, EnsureRoot, EnsureSignedBy};
 	use substrate_test_utils::assert_eq_uvec;
+	use std::{cell::RefCell, collections::BTreeMap};
 	use crate as scheduler;
 
 	mod logger {
 		use super::*;
-		use std::cell::RefCell;
 
 		thread_local! {
 			static LOG: RefCell<Vec<(OriginCaller, u32)>> = RefCell::new(Vec::new());
@@ -768,6 +906,58 @@ mod tests {
 		pub MaximumSchedulerWeight: Weight = Perbill::from_percent(80) * MaximumBlockWeight::get();
 		pub const MaxScheduledPerBlock: u32 = 10;
 	}
+
+	thread_local! {
+		static POSTPONEMENT: RefCell<Option<u64>> = RefCell::new(Some(2));
+		static PREIMAGES: RefCell<BTreeMap<H256, Vec<u8>>> = RefCell::new(BTreeMap::new());
+		static REQUESTS: RefCell<BTreeMap<H256, u32>> = RefCell::new(BTreeMap::new());
+	}
+
+	pub struct NoPreimagePostponement;
+	impl Get<Option<u64>> for NoPreimagePostponement {
+		fn get() -> Option<u64> {
+			POSTPONEMENT.with(|v| *v.borrow())
+		}
+	}
+
+	/// A preimage store which keeps a count of the outstanding requests for each hash.
+	pub struct TestPreimages;
+	impl TestPreimages {
+		fn note(bytes: Vec<u8>) -> H256 {
+			let hash = BlakeTwo256::hash(&bytes[..]);
+			PREIMAGES.with(|p| p.borrow_mut().insert(hash, bytes));
+			hash
+		}
+		fn requests(hash: &H256) -> u32 {
+			REQUESTS.with(|r| r.borrow().get(hash).cloned().unwrap_or(0))
+		}
+	}
+	impl PreimageProvider<H256> for TestPreimages {
+		fn have_preimage(hash: &H256) -> bool {
+			PREIMAGES.with(|p| p.borrow().contains_key(hash))
+		}
+		fn get_preimage(hash: &H256) -> Option<Vec<u8>> {
+			PREIMAGES.with(|p| p.borrow().get(hash).cloned())
+		}
+		fn preimage_requested(hash: &H256) -> bool {
+			Self::requests(hash) > 0
+		}
+		fn request_preimage(hash: &H256) {
+			REQUESTS.with(|r| *r.borrow_mut().entry(*hash).or_default() += 1);
+		}
+		fn unrequest_preimage(hash: &H256) {
+			REQUESTS.with(|r| {
+				let mut r = r.borrow_mut();
+				let count = r.get(hash).cloned().unwrap_or(0);
+				assert!(count > 0, "unrequested a preimage which was not requested");
+				if count == 1 {
+					r.remove(hash);
+				} else {
+					r.insert(*hash, count - 1);
+				}
+			});
+		}
+	}
 	ord_parameter_types! {
 		pub const One: u64 = 1;
 	}
@@ -781,6 +971,8 @@ mod tests {
 		type ScheduleOrigin = EnsureOneOf<u64, EnsureRoot<u64>, EnsureSignedBy<One, u64>>;
 		type MaxScheduledPerBlock = MaxScheduledPerBlock;
 		type WeightInfo = ();
+		type PreimageProvider = TestPreimages;
+		type NoPreimagePostponement = NoPreimagePostponement;
 	}
 	type System = system::Module<Test>;
 	type Logger = logger::Module<Test>;
@@ -808,7 +1000,7 @@ mod tests {
 		new_test_ext().execute_with(|| {
 			let call = Call::Logger(logger::Call::log(42, 1000));
 			assert!(!<Test as frame_system::Trait>::BaseCallFilter::filter(&call));
-			let _ = Scheduler::do_schedule(DispatchTime::At(4), None, 127, root(), call);
+			let _ = Scheduler::do_schedule(DispatchTime::At(4), None, 127, root(), call.into());
 			run_to_block(3);
 			assert!(logger::log().is_empty());
 			run_to_block(4);
@@ -824,7 +1016,7 @@ mod tests {
 			run_to_block(2);
 			let call = Call::Logger(logger::Call::log(42, 1000));
 			assert!(!<Test as frame_system::Trait>::BaseCallFilter::filter(&call));
-			let _ = Scheduler::do_schedule(DispatchTime::After(3), None, 127, root(), call);
+			let _ = Scheduler::do_schedule(DispatchTime::After(3), None, 127, root(), call.into());
 			run_to_block(4);
 			assert!(logger::log().is_empty());
 			run_to_block(5);
@@ -839,7 +1031,7 @@ mod tests {
 		new_test_ext().execute_with(|| {
 			// at #4, every 3 blocks, 3 times.
 			let _ = Scheduler::do_schedule(
-				DispatchTime::At(4), Some((3, 3)), 127, root(), Call::Logger(logger::Call::log(42, 1000))
+				DispatchTime::At(4), Some((3, 3)), 127, root(), Call::Logger(logger::Call::log(42, 1000)).into()
 			);
 			run_to_block(3);
 			assert!(logger::log().is_empty());
@@ -863,10 +1055,10 @@ mod tests {
 		new_test_ext().execute_with(|| {
 			// at #4.
 			Scheduler::do_schedule_named(
-				1u32.encode(), DispatchTime::At(4), None, 127, root(), Call::Logger(logger::Call::log(69, 1000))
+				1u32.encode(), DispatchTime::At(4), None, 127, root(), Call::Logger(logger::Call::log(69, 1000)).into()
 			).unwrap();
 			let i = Scheduler::do_schedule(
-				DispatchTime::At(4), None, 127, root(), Call::Logger(logger::Call::log(42, 1000))
+				DispatchTime::At(4), None, 127, root(), Call::Logger(logger::Call::log(42, 1000)).into()
 			).unwrap();
 			run_to_block(3);
 			assert!(logger::log().is_empty());
@@ -887,7 +1079,7 @@ mod tests {
 				Some((3, 3)),
 				127,
 				root(),
-				Call::Logger(logger::Call::log(42, 1000))
+				Call::Logger(logger::Call::log(42, 1000)).into()
 			).unwrap();
 			// same id results in error.
 			assert!(Scheduler::do_schedule_named(
@@ -896,11 +1088,11 @@ mod tests {
 				None,
 				127,
 				root(),
-				Call::Logger(logger::Call::log(69, 1000))
+				Call::Logger(logger::Call::log(69, 1000)).into()
 			).is_err());
 			// different id is ok.
 			Scheduler::do_schedule_named(
-				2u32.encode(), DispatchTime::At(8), None, 127, root(), Call::Logger(logger::Call::log(69, 1000))
+				2u32.encode(), DispatchTime::At(8), None, 127, root(), Call::Logger(logger::Call::log(69, 1000)).into()
 			).unwrap();
 			run_to_block(3);
 			assert!(logger::log().is_empty());
@@ -921,13 +1113,13 @@ mod tests {
 				None,
 				127,
 				root(),
-				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 2))
+				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 2)).into()
 			);
 			let _ = Scheduler::do_schedule(
 				DispatchTime::At(4),
 				None,
 				127,
-				root(), Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2))
+				root(), Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2)).into()
 			);
 			// 69 and 42 do not fit together
 			run_to_block(4);
@@ -945,14 +1137,14 @@ mod tests {
 				None,
 				0,
 				root(),
-				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 2))
+				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 2)).into()
 			);
 			let _ = Scheduler::do_schedule(
 				DispatchTime::At(4),
 				None,
 				0,
 				root(),
-				Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2))
+				Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2)).into()
 			);
 			// With base weights, 69 and 42 should not fit together, but do because of hard deadlines
 			run_to_block(4);
@@ -968,14 +1160,14 @@ mod tests {
 				None,
 				1,
 				root(),
-				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 2))
+				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 2)).into()
 			);
 			let _ = Scheduler::do_schedule(
 				DispatchTime::At(4),
 				None,
 				0,
 				root(),
-				Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2))
+				Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2)).into()
 			);
 			run_to_block(4);
 			assert_eq!(logger::log(), vec![(root(), 69u32), (root(), 42u32)]);
@@ -989,19 +1181,19 @@ mod tests {
 				DispatchTime::At(4),
 				None,
 				255,
-				root(), Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 3))
+				root(), Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 3)).into()
 			);
 			let _ = Scheduler::do_schedule(
 				DispatchTime::At(4),
 				None,
 				127,
-				root(), Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2))
+				root(), Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2)).into()
 			);
 			let _ = Scheduler::do_schedule(
 				DispatchTime::At(4),
 				None,
 				126,
-				root(), Call::Logger(logger::Call::log(2600, MaximumSchedulerWeight::get() / 2))
+				root(), Call::Logger(logger::Call::log(2600, MaximumSchedulerWeight::get() / 2)).into()
 			);
 
 			// 2600 does not fit with 69 or 42, but has higher priority, so will go through
@@ -1025,7 +1217,7 @@ mod tests {
 			assert_ok!(
 				Scheduler::do_schedule_named(
 					1u32.encode(), DispatchTime::At(1), None, 255, root(),
-					Call::Logger(logger::Call::log(3, MaximumSchedulerWeight::get() / 3))
+					Call::Logger(logger::Call::log(3, MaximumSchedulerWeight::get() / 3)).into()
 				)
 			);
 			// Anon Periodic
@@ -1034,7 +1226,7 @@ mod tests {
 				Some((1000, 3)),
 				128,
 				root(),
-				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 3))
+				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 3)).into()
 			);
 			// Anon
 			let _ = Scheduler::do_schedule(
@@ -1042,12 +1234,12 @@ mod tests {
 				None,
 				127,
 				root(),
-				Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2))
+				Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2)).into()
 			);
 			// Named Periodic
 			assert_ok!(Scheduler::do_schedule_named(
 				2u32.encode(), DispatchTime::At(1), Some((1000, 3)), 126, root(),
-				Call::Logger(logger::Call::log(2600, MaximumSchedulerWeight::get() / 2)))
+				Call::Logger(logger::Call::log(2600, MaximumSchedulerWeight::get() / 2)).into())
 			);
 
 			// Will include the named periodic only
@@ -1079,8 +1271,8 @@ mod tests {
 	#[test]
 	fn root_calls_works() {
 		new_test_ext().execute_with(|| {
-			let call = Box::new(Call::Logger(logger::Call::log(69, 1000)));
-			let call2 = Box::new(Call::Logger(logger::Call::log(42, 1000)));
+			let call = Box::new(Call::Logger(logger::Call::log(69, 1000)).into());
+			let call2 = Box::new(Call::Logger(logger::Call::log(42, 1000)).into());
 			assert_ok!(Scheduler::schedule_named(Origin::root(), 1u32.encode(), 4, None, 127, call));
 			assert_ok!(Scheduler::schedule(Origin::root(), 4, None, 127, call2));
 			run_to_block(3);
@@ -1100,8 +1292,8 @@ mod tests {
 		new_test_ext().execute_with(|| {
 			run_to_block(3);
 
-			let call = Box::new(Call::Logger(logger::Call::log(69, 1000)));
-			let call2 = Box::new(Call::Logger(logger::Call::log(42, 1000)));
+			let call = Box::new(Call::Logger(logger::Call::log(69, 1000)).into());
+			let call2 = Box::new(Call::Logger(logger::Call::log(42, 1000)).into());
 
 			assert_err!(
 				Scheduler::schedule_named(Origin::root(), 1u32.encode(), 2, None, 127, call),
@@ -1123,8 +1315,8 @@ mod tests {
 	#[test]
 	fn should_use_orign() {
 		new_test_ext().execute_with(|| {
-			let call = Box::new(Call::Logger(logger::Call::log(69, 1000)));
-			let call2 = Box::new(Call::Logger(logger::Call::log(42, 1000)));
+			let call = Box::new(Call::Logger(logger::Call::log(69, 1000)).into());
+			let call2 = Box::new(Call::Logger(logger::Call::log(42, 1000)).into());
 			assert_ok!(
 				Scheduler::schedule_named(system::RawOrigin::Signed(1).into(), 1u32.encode(), 4, None, 127, call)
 			);
@@ -1144,8 +1336,8 @@ mod tests {
 	#[test]
 	fn should_check_orign() {
 		new_test_ext().execute_with(|| {
-			let call = Box::new(Call::Logger(logger::Call::log(69, 1000)));
-			let call2 = Box::new(Call::Logger(logger::Call::log(42, 1000)));
+			let call = Box::new(Call::Logger(logger::Call::log(69, 1000)).into());
+			let call2 = Box::new(Call::Logger(logger::Call::log(42, 1000)).into());
 			assert_noop!(
 				Scheduler::schedule_named(system::RawOrigin::Signed(2).into(), 1u32.encode(), 4, None, 127, call),
 				BadOrigin
@@ -1157,8 +1349,8 @@ mod tests {
 	#[test]
 	fn should_check_orign_for_cancel() {
 		new_test_ext().execute_with(|| {
-			let call = Box::new(Call::Logger(logger::Call::log_without_filter(69, 1000)));
-			let call2 = Box::new(Call::Logger(logger::Call::log_without_filter(42, 1000)));
+			let call = Box::new(Call::Logger(logger::Call::log_without_filter(69, 1000)).into());
+			let call2 = Box::new(Call::Logger(logger::Call::log_without_filter(42, 1000)).into());
 			assert_ok!(
 				Scheduler::schedule_named(system::RawOrigin::Signed(1).into(), 1u32.encode(), 4, None, 127, call)
 			);
@@ -1180,7 +1372,90 @@ mod tests {
 	}
 
 	#[test]
-	fn migration_to_v2_works() {
+	fn scheduling_with_preimage_works() {
+		new_test_ext().execute_with(|| {
+			let call = Call::Logger(logger::Call::log(42, 1000));
+			let hash = TestPreimages::note(call.encode());
+			assert_ok!(Scheduler::do_schedule(DispatchTime::At(4), None, 127, root(), MaybeHashed::Hash(hash)));
+			assert_eq!(TestPreimages::requests(&hash), 1);
+			run_to_block(3);
+			assert!(logger::log().is_empty());
+			run_to_block(4);
+			assert_eq!(logger::log(), vec![(root(), 42u32)]);
+			assert_eq!(TestPreimages::requests(&hash), 0);
+		});
+	}
+
+	#[test]
+	fn scheduling_with_preimage_postpones_correctly() {
+		new_test_ext().execute_with(|| {
+			let call = Call::Logger(logger::Call::log(42, 1000));
+			let hash = BlakeTwo256::hash(&call.encode()[..]);
+			assert_ok!(Scheduler::do_schedule_named(
+				1u32.encode(), DispatchTime::At(4), None, 127, root(), MaybeHashed::Hash(hash)
+			));
+			assert_eq!(TestPreimages::requests(&hash), 1);
+
+			run_to_block(4);
+			// Preimage not known, so the call is postponed.
+			assert!(logger::log().is_empty());
+			assert_eq!(Lookup::<Test>::get(1u32.encode()), Some((6, 0)));
+			assert_eq!(Agenda::<Test>::get(6).len(), 1);
+
+			TestPreimages::note(call.encode());
+			run_to_block(6);
+			assert_eq!(logger::log(), vec![(root(), 42u32)]);
+			assert_eq!(TestPreimages::requests(&hash), 0);
+			assert!(Lookup::<Test>::get(1u32.encode()).is_none());
+		});
+	}
+
+	#[test]
+	fn scheduling_with_unknown_preimage_is_dropped_without_postponement() {
+		new_test_ext().execute_with(|| {
+			POSTPONEMENT.with(|v| *v.borrow_mut() = None);
+			let hash = BlakeTwo256::hash(&b"unknown"[..]);
+			assert_ok!(Scheduler::do_schedule_named(
+				1u32.encode(), DispatchTime::At(4), None, 127, root(), MaybeHashed::Hash(hash)
+			));
+			run_to_block(4);
+			assert!(logger::log().is_empty());
+			assert!(Lookup::<Test>::get(1u32.encode()).is_none());
+			assert_eq!(TestPreimages::requests(&hash), 0);
+			run_to_block(100);
+			assert!(logger::log().is_empty());
+		});
+	}
+
+	#[test]
+	fn scheduling_with_badly_formed_preimage_is_dropped() {
+		new_test_ext().execute_with(|| {
+			let hash = TestPreimages::note(b"not a call".to_vec());
+			assert_ok!(Scheduler::do_schedule(DispatchTime::At(4), None, 127, root(), MaybeHashed::Hash(hash)));
+			run_to_block(4);
+			assert!(logger::log().is_empty());
+			assert!(Agenda::<Test>::iter().all(|(_, agenda)| agenda.iter().all(Option::is_none)));
+			assert_eq!(TestPreimages::requests(&hash), 0);
+		});
+	}
+
+	#[test]
+	fn cancelling_hashed_call_unrequests_preimage() {
+		new_test_ext().execute_with(|| {
+			let hash = BlakeTwo256::hash(&b"whatever"[..]);
+			assert_ok!(Scheduler::schedule_named(
+				Origin::root(), 1u32.encode(), 4, None, 127, Box::new(MaybeHashed::Hash(hash))
+			));
+			assert_ok!(Scheduler::schedule(Origin::root(), 4, None, 127, Box::new(MaybeHashed::Hash(hash))));
+			assert_eq!(TestPreimages::requests(&hash), 2);
+			assert_ok!(Scheduler::cancel_named(Origin::root(), 1u32.encode()));
+			assert_ok!(Scheduler::cancel(Origin::root(), 4, 1));
+			assert_eq!(TestPreimages::requests(&hash), 0);
+		});
+	}
+
+	#[test]
+	fn migration_to_v3_works() {
 		new_test_ext().execute_with(|| {
 			for i in 0..3u64 {
 				let k = i.twox_64_concat();
@@ -1209,7 +1484,7 @@ mod tests {
 
 			assert_eq!(StorageVersion::get(), Releases::V1);
 
-			assert!(Scheduler::migrate_v1_to_t2());
+			assert!(Scheduler::migrate_v1_to_v3());
 
 			assert_eq_uvec!(Agenda::<Test>::iter().collect::<Vec<_>>(), vec![
 				(
@@ -1218,7 +1493,7 @@ mod tests {
 					Some(ScheduledV2 {
 						maybe_id: None,
 						priority: 10,
-						call: Call::Logger(logger::Call::log(96, 100)),
+						call: Call::Logger(logger::Call::log(96, 100)).into(),
 						maybe_periodic: None,
 						origin: root(),
 						_phantom: PhantomData::<u64>::default(),
@@ -1227,7 +1502,7 @@ mod tests {
 					Some(ScheduledV2 {
 						maybe_id: Some(b"test".to_vec()),
 						priority: 123,
-						call: Call::Logger(logger::Call::log(69, 1000)),
+						call: Call::Logger(logger::Call::log(69, 1000)).into(),
 						maybe_periodic: Some((456u64, 10)),
 						origin: root(),
 						_phantom: PhantomData::<u64>::default(),
@@ -1239,7 +1514,7 @@ mod tests {
 						Some(ScheduledV2 {
 							maybe_id: None,
 							priority: 11,
-							call: Call::Logger(logger::Call::log(96, 100)),
+							call: Call::Logger(logger::Call::log(96, 100)).into(),
 							maybe_periodic: None,
 							origin: root(),
 							_phantom: PhantomData::<u64>::default(),
@@ -1248,7 +1523,7 @@ mod tests {
 						Some(ScheduledV2 {
 							maybe_id: Some(b"test".to_vec()),
 							priority: 123,
-							call: Call::Logger(logger::Call::log(69, 1000)),
+							call: Call::Logger(logger::Call::log(69, 1000)).into(),
 							maybe_periodic: Some((456u64, 10)),
 							origin: root(),
 							_phantom: PhantomData::<u64>::default(),
@@ -1261,7 +1536,7 @@ mod tests {
 						Some(ScheduledV2 {
 							maybe_id: None,
 							priority: 12,
-							call: Call::Logger(logger::Call::log(96, 100)),
+							call: Call::Logger(logger::Call::log(96, 100)).into(),
 							maybe_periodic: None,
 							origin: root(),
 							_phantom: PhantomData::<u64>::default(),
@@ -1270,7 +1545,7 @@ mod tests {
 						Some(ScheduledV2 {
 							maybe_id: Some(b"test".to_vec()),
 							priority: 123,
-							call: Call::Logger(logger::Call::log(69, 1000)),
+							call: Call::Logger(logger::Call::log(69, 1000)).into(),
 							maybe_periodic: Some((456u64, 10)),
 							origin: root(),
 							_phantom: PhantomData::<u64>::default(),
@@ -1279,7 +1554,43 @@ mod tests {
 				)
 			]);
 
-			assert_eq!(StorageVersion::get(), Releases::V2);
+			assert_eq!(StorageVersion::get(), Releases::V3);
+		});
+	}
+
+	#[test]
+	fn migration_from_v2_works() {
+		new_test_ext().execute_with(|| {
+			StorageVersion::put(Releases::V2);
+			let k = 1u64.twox_64_concat();
+			let old: Vec<Option<ScheduledV2<Call, u64, OriginCaller, u64>>> = vec![
+				None,
+				Some(ScheduledV2 {
+					maybe_id: Some(b"test".to_vec()),
+					priority: 123,
+					call: Call::Logger(logger::Call::log(69, 1000)),
+					maybe_periodic: Some((456u64, 10)),
+					origin: system::RawOrigin::Signed(1).into(),
+					_phantom: Default::default(),
+				}),
+			];
+			frame_support::migration::put_storage_value(b"Scheduler", b"Agenda", &k, old);
+
+			assert_eq!(Scheduler::on_runtime_upgrade(), MaximumBlockWeight::get());
+			assert!(!Scheduler::migrate_v2_to_v3());
+
+			assert_eq!(Agenda::<Test>::get(1), vec![
+				None,
+				Some(ScheduledV2 {
+					maybe_id: Some(b"test".to_vec()),
+					priority: 123,
+					call: MaybeHashed::Value(Call::Logger(logger::Call::log(69, 1000))),
+					maybe_periodic: Some((456u64, 10)),
+					origin: system::RawOrigin::Signed(1).into(),
+					_phantom: Default::default(),
+				}),
+			]);
+			assert_eq!(StorageVersion::get(), Releases::V3);
 		});
 	}
 
@@ -1288,11 +1599,11 @@ mod tests {
 		new_test_ext().execute_with(|| {
 			for i in 0..3u64 {
 				let k = i.twox_64_concat();
-				let old: Vec<Option<Scheduled<_, _, u32, u64>>> = vec![
+				let old: Vec<Option<Scheduled<CallOrHashOf<Test>, u64, u32, u64>>> = vec![
 					Some(Scheduled {
 						maybe_id: None,
 						priority: i as u8 + 10,
-						call: Call::Logger(logger::Call::log(96, 100)),
+						call: Call::Logger(logger::Call::log(96, 100)).into(),
 						origin: 3u32,
 						maybe_periodic: None,
 						_phantom: Default::default(),
@@ -1302,7 +1613,7 @@ mod tests {
 						maybe_id: Some(b"test".to_vec()),
 						priority: 123,
 						origin: 2u32,
-						call: Call::Logger(logger::Call::log(69, 1000)),
+						call: Call::Logger(logger::Call::log(69, 1000)).into(),
 						maybe_periodic: Some((456u64, 10)),
 						_phantom: Default::default(),
 					}),
@@ -1334,7 +1645,7 @@ mod tests {
 					Some(ScheduledV2::<_, _, OriginCaller, u64> {
 						maybe_id: None,
 						priority: 10,
-						call: Call::Logger(logger::Call::log(96, 100)),
+						call: Call::Logger(logger::Call::log(96, 100)).into(),
 						maybe_periodic: None,
 						origin: system::RawOrigin::Root.into(),
 						_phantom: PhantomData::<u64>::default(),
@@ -1343,7 +1654,7 @@ mod tests {
 					Some(ScheduledV2 {
 						maybe_id: Some(b"test".to_vec()),
 						priority: 123,
-						call: Call::Logger(logger::Call::log(69, 1000)),
+						call: Call::Logger(logger::Call::log(69, 1000)).into(),
 						maybe_periodic: Some((456u64, 10)),
 						origin: system::RawOrigin::None.into(),
 						_phantom: PhantomData::<u64>::default(),
@@ -1355,7 +1666,7 @@ mod tests {
 						Some(ScheduledV2 {
 							maybe_id: None,
 							priority: 11,
-							call: Call::Logger(logger::Call::log(96, 100)),
+							call: Call::Logger(logger::Call::log(96, 100)).into(),
 							maybe_periodic: None,
 							origin: system::RawOrigin::Root.into(),
 							_phantom: PhantomData::<u64>::default(),
@@ -1364,7 +1675,7 @@ mod tests {
 						Some(ScheduledV2 {
 							maybe_id: Some(b"test".to_vec()),
 							priority: 123,
-							call: Call::Logger(logger::Call::log(69, 1000)),
+							call: Call::Logger(logger::Call::log(69, 1000)).into(),
 							maybe_periodic: Some((456u64, 10)),
 							origin: system::RawOrigin::None.into(),
 							_phantom: PhantomData::<u64>::default(),
@@ -1377,7 +1688,7 @@ mod tests {
 						Some(ScheduledV2 {
 							maybe_id: None,
 							priority: 12,
-							call: Call::Logger(logger::Call::log(96, 100)),
+							call: Call::Logger(logger::Call::log(96, 100)).into(),
 							maybe_periodic: None,
 							origin: system::RawOrigin::Root.into(),
 							_phantom: PhantomData::<u64>::default(),
@@ -1386,7 +1697,7 @@ mod tests {
 						Some(ScheduledV2 {
 							maybe_id: Some(b"test".to_vec()),
 							priority: 123,
-							call: Call::Logger(logger::Call::log(69, 1000)),
+							call: Call::Logger(logger::Call::log(69, 1000)).into(),
 							maybe_periodic: Some((456u64, 10)),
 							origin: system::RawOrigin::None.into(),
 							_phantom: PhantomData::<u64>::default(),
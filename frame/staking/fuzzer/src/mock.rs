@@ -194,6 +194,9 @@ impl pallet_staking::Trait for Test {
 	type MaxIterations = MaxIterations;
 	type MinSolutionScoreBump = ();
 	type MaxNominatorRewardedPerValidator = MaxNominatorRewardedPerValidator;
+	type Scheduler = ();
+	type PalletsOrigin = frame_system::RawOrigin<Self::AccountId>;
+	type MaxAutoPayoutsPerBlock = ();
 	type UnsignedPriority = ();
 	type WeightInfo = ();
 }
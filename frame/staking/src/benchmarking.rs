@@ -214,6 +214,15 @@ benchmarks! {
 		do_whitelist!(controller);
 	}: _(RawOrigin::Signed(controller))
 
+	set_auto_payout {
+		let (stash, controller) = create_stash_controller::<T>(USER_SEED, 100, Default::default())?;
+		Validators::<T>::insert(&stash, ValidatorPrefs::default());
+		do_whitelist!(controller);
+	}: _(RawOrigin::Signed(controller), true)
+	verify {
+		assert!(AutoPayout::<T>::get(&stash));
+	}
+
 	set_payee {
 		let (stash, controller) = create_stash_controller::<T>(USER_SEED, 100, Default::default())?;
 		assert_eq!(Payee::<T>::get(&stash), RewardDestination::Staked);
@@ -786,6 +795,7 @@ mod tests {
 			assert_ok!(test_benchmark_validate::<Test>());
 			assert_ok!(test_benchmark_nominate::<Test>());
 			assert_ok!(test_benchmark_chill::<Test>());
+			assert_ok!(test_benchmark_set_auto_payout::<Test>());
 			assert_ok!(test_benchmark_set_payee::<Test>());
 			assert_ok!(test_benchmark_set_controller::<Test>());
 			assert_ok!(test_benchmark_set_validator_count::<Test>());
@@ -111,6 +111,11 @@
 //! biggest stakers can claim their reward. This is to limit the i/o cost to mutate storage for each
 //! nominator's account.
 //!
+//! Validators may opt in to automatic payouts via the
+//! [`set_auto_payout`](enum.Call.html#variant.set_auto_payout) call. At the end of each era, a
+//! `payout_stakers` for every opted-in validator is scheduled through [`Trait::Scheduler`],
+//! spread over the following blocks at most [`Trait::MaxAutoPayoutsPerBlock`] at a time.
+//!
 //! Slashing can occur at any point in time, once misbehavior is reported. Once slashing is
 //! determined, a value is deducted from the balance of the validator and all the nominators who
 //! voted for this validator (values are deducted from the _stash_ account of the slashed entity).
@@ -300,6 +305,7 @@ use frame_support::{
 	traits::{
 		Currency, LockIdentifier, LockableCurrency, WithdrawReasons, OnUnbalanced, Imbalance, Get,
 		UnixTime, EstimateNextNewSession, EnsureOrigin,
+		schedule::{Named as ScheduleNamed, DispatchTime, LOWEST_PRIORITY},
 	}
 };
 use pallet_session::historical;
@@ -332,6 +338,7 @@ use sp_npos_elections::{
 };
 
 const STAKING_ID: LockIdentifier = *b"staking ";
+const AUTO_PAYOUT_ID: [u8; 8] = *b"autopay ";
 pub const MAX_UNLOCKING_CHUNKS: usize = 32;
 pub const MAX_NOMINATIONS: usize = <CompactAssignments as VotingLimit>::LIMIT;
 
@@ -794,6 +801,7 @@ pub trait WeightInfo {
 	fn reap_stash(s: u32, ) -> Weight;
 	fn new_era(v: u32, n: u32, ) -> Weight;
 	fn submit_solution_better(v: u32, n: u32, a: u32, w: u32, ) -> Weight;
+	fn set_auto_payout() -> Weight;
 }
 
 pub trait Trait: frame_system::Trait + SendTransactionTypes<Call<Self>> {
@@ -883,6 +891,18 @@ pub trait Trait: frame_system::Trait + SendTransactionTypes<Call<Self>> {
 	/// multiple pallets send unsigned transactions.
 	type UnsignedPriority: Get<TransactionPriority>;
 
+	/// The scheduler used to dispatch automatic era payouts.
+	type Scheduler: ScheduleNamed<Self::BlockNumber, <Self as Trait>::Call, Self::PalletsOrigin>;
+
+	/// Overarching type of all pallets origins.
+	type PalletsOrigin: From<frame_system::RawOrigin<Self::AccountId>>;
+
+	/// The maximum number of automatic payouts dispatched in a single block.
+	///
+	/// Automatic payouts of an era are spread over as many blocks as needed, starting from the
+	/// block after the era has ended.
+	type MaxAutoPayoutsPerBlock: Get<u32>;
+
 	/// Weight information for extrinsics in this pallet.
 	type WeightInfo: WeightInfo;
 }
@@ -959,6 +979,10 @@ decl_storage! {
 		pub Validators get(fn validators):
 			map hasher(twox_64_concat) T::AccountId => ValidatorPrefs;
 
+		/// Validator stashes which opted in to have their era rewards paid out automatically.
+		pub AutoPayout get(fn auto_payout):
+			map hasher(twox_64_concat) T::AccountId => bool;
+
 		/// The map from nominator stash key to the set of stash keys of all validators to nominate.
 		pub Nominators get(fn nominators):
 			map hasher(twox_64_concat) T::AccountId => Option<Nominations<T::AccountId>>;
@@ -1167,6 +1191,13 @@ decl_event!(
 		/// An account has called `withdraw_unbonded` and removed unbonding chunks worth `Balance`
 		/// from the unlocking queue. \[stash, amount\]
 		Withdrawn(AccountId, Balance),
+		/// A validator has changed its automatic payout preference. \[stash, enabled\]
+		AutoPayoutSet(AccountId, bool),
+		/// An automatic payout was not performed, either because it could not be scheduled or
+		/// because the validator opted out in the meantime. \[stash, era_index\]
+		AutoPayoutSkipped(AccountId, EraIndex),
+		/// An automatic payout was dispatched but failed. \[stash, era_index, error\]
+		AutoPayoutFailed(AccountId, EraIndex, DispatchError),
 	}
 );
 
@@ -1238,6 +1269,8 @@ decl_error! {
 		IncorrectHistoryDepth,
 		/// Incorrect number of slashing spans provided.
 		IncorrectSlashingSpans,
+		/// The stash is not a validator.
+		NotValidator,
 	}
 }
 
@@ -1279,6 +1312,9 @@ decl_module! {
 		/// their reward. This used to limit the i/o cost for the nominator payout.
 		const MaxNominatorRewardedPerValidator: u32 = T::MaxNominatorRewardedPerValidator::get();
 
+		/// The maximum number of automatic payouts dispatched in a single block.
+		const MaxAutoPayoutsPerBlock: u32 = T::MaxAutoPayoutsPerBlock::get();
+
 		type Error = Error<T>;
 
 		fn deposit_event() = default;
@@ -1950,6 +1986,67 @@ decl_module! {
 			Self::do_payout_stakers(validator_stash, era)
 		}
 
+		/// Opt in or out of automatic era payouts for the validator of the origin controller.
+		///
+		/// When enabled, a `payout_stakers` for the validator is scheduled at the end of every era
+		/// in which it earned reward points. Effects will be felt at the end of the current era.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the controller, not the stash.
+		///
+		/// Emits `AutoPayoutSet`.
+		///
+		/// # <weight>
+		/// - Independent of the arguments. Insignificant complexity.
+		/// ---------
+		/// - Weight: O(1)
+		/// - DB Weight:
+		///     - Read: Ledger, Validators
+		///     - Write: AutoPayout
+		/// # </weight>
+		#[weight = T::WeightInfo::set_auto_payout()]
+		fn set_auto_payout(origin, enabled: bool) {
+			let controller = ensure_signed(origin)?;
+			let ledger = Self::ledger(&controller).ok_or(Error::<T>::NotController)?;
+			let stash = ledger.stash;
+			if enabled {
+				ensure!(<Validators<T>>::contains_key(&stash), Error::<T>::NotValidator);
+				<AutoPayout<T>>::insert(&stash, true);
+			} else {
+				<AutoPayout<T>>::remove(&stash);
+			}
+			Self::deposit_event(RawEvent::AutoPayoutSet(stash, enabled));
+		}
+
+		/// Pay out the stakers of `validator_stash` for `era` on behalf of the scheduler.
+		///
+		/// This is the call scheduled at the end of each era for validators that opted in to
+		/// automatic payouts. Instead of failing, it emits `AutoPayoutSkipped` if the validator
+		/// has opted out since, or `AutoPayoutFailed` if the payout itself fails.
+		///
+		/// The dispatch origin for this call must be _Root_.
+		///
+		/// # <weight>
+		/// Same as [`payout_stakers`], plus a read of `AutoPayout`.
+		/// # </weight>
+		#[weight = T::WeightInfo::payout_stakers_alive_staked(T::MaxNominatorRewardedPerValidator::get())
+			.saturating_add(T::DbWeight::get().reads(1))]
+		fn auto_payout_stakers(origin, validator_stash: T::AccountId, era: EraIndex) {
+			ensure_root(origin)?;
+			if !Self::auto_payout(&validator_stash) {
+				Self::deposit_event(RawEvent::AutoPayoutSkipped(validator_stash, era));
+				return Ok(());
+			}
+
+			let result = if Self::era_election_status().is_closed() {
+				Self::do_payout_stakers(validator_stash.clone(), era)
+			} else {
+				Err(Error::<T>::CallNotAllowed.into())
+			};
+			if let Err(e) = result {
+				Self::deposit_event(RawEvent::AutoPayoutFailed(validator_stash, era, e));
+			}
+		}
+
 		/// Rebond a portion of the stash scheduled to be unlocked.
 		///
 		/// The dispatch origin must be signed by the controller, and it can be only called when
@@ -2705,9 +2802,45 @@ impl<T: Trait> Module<T> {
 			// Set ending era reward.
 			<ErasValidatorReward<T>>::insert(&active_era.index, validator_payout);
 			T::RewardRemainder::on_unbalanced(T::Currency::issue(rest));
+
+			Self::schedule_auto_payouts(active_era.index);
 		}
 	}
 
+	/// Schedule a payout for every validator of `era` that has opted in to automatic payouts.
+	///
+	/// Payouts are dispatched by the scheduler with the lowest priority, starting from the next
+	/// block and spreading over as many blocks as needed so that no more than
+	/// `T::MaxAutoPayoutsPerBlock` payouts are dispatched in one block.
+	fn schedule_auto_payouts(era: EraIndex) {
+		let per_block = T::MaxAutoPayoutsPerBlock::get().max(1);
+		let now = <frame_system::Module<T>>::block_number();
+		let era_reward_points = <ErasRewardPoints<T>>::get(&era);
+
+		era_reward_points.individual.into_iter()
+			.filter(|(stash, points)| !points.is_zero() && Self::auto_payout(stash))
+			.enumerate()
+			.for_each(|(index, (stash, _))| {
+				let offset: T::BlockNumber = (1 + index as u32 / per_block).into();
+				if T::Scheduler::schedule_named(
+					Self::auto_payout_task_id(era, &stash),
+					DispatchTime::At(now.saturating_add(offset)),
+					None,
+					LOWEST_PRIORITY,
+					system::RawOrigin::Root.into(),
+					Call::auto_payout_stakers(stash.clone(), era).into(),
+				).is_err() {
+					log!(warn, "💸 Failed to schedule automatic payout of {:?} for era {}.", stash, era);
+					Self::deposit_event(RawEvent::AutoPayoutSkipped(stash, era));
+				}
+			});
+	}
+
+	/// The name of the scheduled task paying out `stash` for `era`.
+	fn auto_payout_task_id(era: EraIndex, stash: &T::AccountId) -> Vec<u8> {
+		(AUTO_PAYOUT_ID, era, stash).encode()
+	}
+
 	/// Plan a new era. Return the potential new staking set.
 	fn new_era(start_session_index: SessionIndex) -> Option<Vec<T::AccountId>> {
 		// Increment or set current era.
@@ -2964,6 +3097,7 @@ impl<T: Trait> Module<T> {
 		<Payee<T>>::remove(stash);
 		<Validators<T>>::remove(stash);
 		<Nominators<T>>::remove(stash);
+		<AutoPayout<T>>::remove(stash);
 
 		system::Module::<T>::dec_ref(stash);
 
@@ -27,7 +27,10 @@ use sp_core::H256;
 use frame_support::{
 	assert_ok, impl_outer_origin, parameter_types, impl_outer_dispatch, impl_outer_event,
 	StorageValue, StorageMap, StorageDoubleMap, IterableStorageMap,
-	traits::{Currency, Get, FindAuthor, OnFinalize, OnInitialize},
+	traits::{
+		Currency, Get, FindAuthor, OnFinalize, OnInitialize,
+		schedule::{Named as ScheduleNamed, DispatchTime, Period, Priority},
+	},
 	weights::{Weight, constants::RocksDbWeight},
 };
 use sp_io;
@@ -310,6 +313,58 @@ impl OnUnbalanced<NegativeImbalanceOf<Test>> for RewardRemainderMock {
 	}
 }
 
+parameter_types! {
+	pub const MaxAutoPayoutsPerBlock: u32 = 1;
+}
+
+thread_local! {
+	pub static SCHEDULED: RefCell<Vec<(Vec<u8>, BlockNumber, Call)>> = RefCell::new(vec![]);
+}
+
+/// Scheduler which only records the named tasks it is given.
+pub struct MockScheduler;
+impl ScheduleNamed<BlockNumber, Call, frame_system::RawOrigin<AccountId>> for MockScheduler {
+	type Address = (BlockNumber, u32);
+
+	fn schedule_named(
+		id: Vec<u8>,
+		when: DispatchTime<BlockNumber>,
+		_maybe_periodic: Option<Period<BlockNumber>>,
+		_priority: Priority,
+		_origin: frame_system::RawOrigin<AccountId>,
+		call: Call,
+	) -> Result<Self::Address, ()> {
+		let when = match when {
+			DispatchTime::At(when) => when,
+			DispatchTime::After(after) => System::block_number() + after,
+		};
+		SCHEDULED.with(|scheduled| {
+			let mut scheduled = scheduled.borrow_mut();
+			if scheduled.iter().any(|(other, _, _)| *other == id) {
+				return Err(());
+			}
+			scheduled.push((id, when, call));
+			Ok((when, scheduled.len() as u32 - 1))
+		})
+	}
+
+	fn cancel_named(id: Vec<u8>) -> Result<(), ()> {
+		SCHEDULED.with(|scheduled| {
+			let mut scheduled = scheduled.borrow_mut();
+			let len = scheduled.len();
+			scheduled.retain(|(other, _, _)| *other != id);
+			if scheduled.len() < len { Ok(()) } else { Err(()) }
+		})
+	}
+}
+
+/// Take all the tasks scheduled so far, as `(when, call)`.
+pub(crate) fn take_scheduled() -> Vec<(BlockNumber, Call)> {
+	SCHEDULED.with(|scheduled| {
+		scheduled.borrow_mut().drain(..).map(|(_, when, call)| (when, call)).collect()
+	})
+}
+
 impl Trait for Test {
 	type Currency = Balances;
 	type UnixTime = Timestamp;
@@ -331,6 +386,9 @@ impl Trait for Test {
 	type MinSolutionScoreBump = MinSolutionScoreBump;
 	type MaxNominatorRewardedPerValidator = MaxNominatorRewardedPerValidator;
 	type UnsignedPriority = UnsignedPriority;
+	type Scheduler = MockScheduler;
+	type PalletsOrigin = frame_system::RawOrigin<AccountId>;
+	type MaxAutoPayoutsPerBlock = MaxAutoPayoutsPerBlock;
 	type WeightInfo = ();
 }
 
@@ -454,6 +512,7 @@ impl ExtBuilder {
 	pub fn build(self) -> sp_io::TestExternalities {
 		sp_tracing::try_init_simple();
 		self.set_associated_constants();
+		SCHEDULED.with(|scheduled| scheduled.borrow_mut().clear());
 		let mut storage = frame_system::GenesisConfig::default()
 			.build_storage::<Test>()
 			.unwrap();
@@ -4697,3 +4697,114 @@ fn payout_to_any_account_works() {
 		assert!(Balances::free_balance(42) > 0);
 	})
 }
+
+#[test]
+fn set_auto_payout_requires_validator() {
+	ExtBuilder::default().build_and_execute(|| {
+		// 100 is the controller of nominator 101.
+		assert_noop!(
+			Staking::set_auto_payout(Origin::signed(100), true),
+			Error::<Test>::NotValidator,
+		);
+		assert_noop!(
+			Staking::set_auto_payout(Origin::signed(11), true),
+			Error::<Test>::NotController,
+		);
+
+		assert_ok!(Staking::set_auto_payout(Origin::signed(10), true));
+		assert!(Staking::auto_payout(11));
+		assert_eq!(
+			*staking_events().last().unwrap(),
+			RawEvent::AutoPayoutSet(11, true),
+		);
+
+		// opting out is always allowed.
+		assert_ok!(Staking::set_auto_payout(Origin::signed(10), false));
+		assert!(!Staking::auto_payout(11));
+	})
+}
+
+#[test]
+fn auto_payouts_are_scheduled_at_era_end() {
+	ExtBuilder::default().build_and_execute(|| {
+		assert_ok!(Staking::set_auto_payout(Origin::signed(10), true));
+		assert_ok!(Staking::set_auto_payout(Origin::signed(20), true));
+
+		Staking::reward_by_ids(vec![(11, 1), (21, 1)]);
+		mock::start_era(1);
+
+		// `MaxAutoPayoutsPerBlock` is one, so both payouts are spread over two blocks.
+		let now = System::block_number();
+		let scheduled = take_scheduled();
+		assert_eq!(
+			scheduled.iter().map(|(when, _)| *when).collect::<Vec<_>>(),
+			vec![now + 1, now + 2],
+		);
+
+		for (_, call) in scheduled {
+			assert_ok!(call.dispatch(Origin::root()));
+		}
+		assert_eq!(Staking::ledger(&10).unwrap().claimed_rewards, vec![0]);
+		assert_eq!(Staking::ledger(&20).unwrap().claimed_rewards, vec![0]);
+	})
+}
+
+#[test]
+fn auto_payouts_only_for_opted_in_validators_with_points() {
+	ExtBuilder::default().build_and_execute(|| {
+		assert_ok!(Staking::set_auto_payout(Origin::signed(10), true));
+		assert_ok!(Staking::set_auto_payout(Origin::signed(20), true));
+
+		// 21 earns no points, 31 is not a validator and has not opted in.
+		Staking::reward_by_ids(vec![(11, 1)]);
+		mock::start_era(1);
+
+		let scheduled = take_scheduled();
+		assert_eq!(scheduled.len(), 1);
+		assert_eq!(
+			scheduled[0].1,
+			mock::Call::Staking(crate::Call::auto_payout_stakers(11, 0)),
+		);
+	})
+}
+
+#[test]
+fn auto_payout_emits_events_instead_of_failing() {
+	ExtBuilder::default().build_and_execute(|| {
+		assert_ok!(Staking::set_auto_payout(Origin::signed(10), true));
+		Staking::reward_by_ids(vec![(11, 1)]);
+		mock::start_era(1);
+		let (_, call) = take_scheduled().pop().unwrap();
+
+		// only root can dispatch it.
+		assert_noop!(
+			Staking::auto_payout_stakers(Origin::signed(10), 11, 0),
+			BadOrigin,
+		);
+
+		// already claimed manually: the automatic payout fails.
+		assert_ok!(Staking::payout_stakers(Origin::signed(1337), 11, 0));
+		assert_ok!(call.clone().dispatch(Origin::root()));
+		assert_eq!(
+			*staking_events().last().unwrap(),
+			RawEvent::AutoPayoutFailed(11, 0, Error::<Test>::AlreadyClaimed.into()),
+		);
+
+		// opted out in the meantime: the automatic payout is skipped.
+		assert_ok!(Staking::set_auto_payout(Origin::signed(10), false));
+		assert_ok!(call.dispatch(Origin::root()));
+		assert_eq!(
+			*staking_events().last().unwrap(),
+			RawEvent::AutoPayoutSkipped(11, 0),
+		);
+	})
+}
+
+#[test]
+fn auto_payout_is_cleared_when_stash_is_killed() {
+	ExtBuilder::default().build_and_execute(|| {
+		assert_ok!(Staking::set_auto_payout(Origin::signed(10), true));
+		assert_ok!(Staking::force_unstake(Origin::root(), 11, 0));
+		assert!(!Staking::auto_payout(11));
+	})
+}
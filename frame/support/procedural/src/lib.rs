@@ -22,6 +22,8 @@
 mod storage;
 mod construct_runtime;
 mod transactional;
+mod pallet;
+mod no_bound;
 
 use proc_macro::TokenStream;
 
@@ -325,3 +327,211 @@ pub fn construct_runtime(input: TokenStream) -> TokenStream {
 pub fn transactional(attr: TokenStream, input: TokenStream) -> TokenStream {
 	transactional::transactional(attr, input).unwrap_or_else(|e| e.to_compile_error().into())
 }
+
+/// Declare a pallet, the attribute macro alternative to `decl_module!`, `decl_storage!`,
+/// `decl_event!` and `decl_error!`.
+///
+/// The macro is applied to an inlined module, the items of the pallet are annotated with
+/// `#[pallet::*]` attributes. The pallet is expanded into the `decl_*` macros, thus it shares the
+/// metadata, the `Call`, the storages and the genesis config of those and can be used in
+/// `construct_runtime!` as any other pallet.
+///
+/// # Example
+///
+/// ```nocompile
+/// pub use pallet::*;
+///
+/// #[frame_support::pallet]
+/// pub mod pallet {
+/// 	use frame_support::pallet_prelude::*;
+/// 	use frame_system::pallet_prelude::*;
+///
+/// 	/// Configuration trait of this pallet.
+/// 	#[pallet::config]
+/// 	pub trait Trait: frame_system::Trait {
+/// 		/// Exposed in the metadata as a constant of the pallet.
+/// 		#[pallet::constant]
+/// 		type MyGetParam: Get<u32>;
+/// 		type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+/// 	}
+///
+/// 	#[pallet::pallet]
+/// 	#[pallet::generate_store(pub(super) trait Store)]
+/// 	#[pallet::storage_prefix(Example)]
+/// 	pub struct Pallet<T>(PhantomData<T>);
+///
+/// 	#[pallet::hooks]
+/// 	impl<T: Trait> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+///
+/// 	#[pallet::call]
+/// 	impl<T: Trait> Pallet<T> {
+/// 		/// Store the value and deposit an event.
+/// 		#[pallet::weight(0)]
+/// 		fn set(origin: OriginFor<T>, #[pallet::compact] value: u32) -> DispatchResultWithPostInfo {
+/// 			let who = ensure_signed(origin)?;
+/// 			ensure!(value != 0, Error::<T>::ValueZero);
+/// 			<MyValue<T>>::insert(&who, value);
+/// 			Self::deposit_event(Event::ValueSet(who, value));
+/// 			Ok(().into())
+/// 		}
+/// 	}
+///
+/// 	#[pallet::error]
+/// 	pub enum Error<T> {
+/// 		/// Value is zero.
+/// 		ValueZero,
+/// 	}
+///
+/// 	#[pallet::event]
+/// 	#[pallet::metadata(T::AccountId = "AccountId")]
+/// 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+/// 	pub enum Event<T: Trait> {
+/// 		/// A value was set. [who, value]
+/// 		ValueSet(T::AccountId, u32),
+/// 	}
+///
+/// 	#[pallet::storage]
+/// 	#[pallet::getter(fn my_value)]
+/// 	pub(super) type MyValue<T: Trait> = StorageMap<_, Blake2_128Concat, T::AccountId, u32>;
+///
+/// 	#[pallet::genesis_config]
+/// 	#[derive(Default)]
+/// 	pub struct GenesisConfig {
+/// 		pub initial: u32,
+/// 	}
+///
+/// 	#[pallet::genesis_build]
+/// 	impl<T: Trait> GenesisBuild<T> for GenesisConfig {
+/// 		fn build(&self) {}
+/// 	}
+/// }
+/// ```
+///
+/// # Pallet items
+///
+/// * `#[pallet::config]` (required): the configuration trait, it must have
+///   `frame_system::Trait` as explicit supertrait. Associated types bounded by `Get<$Type>` and
+///   annotated with `#[pallet::constant]` are exposed as constants in the metadata. If the pallet
+///   deposits events the trait must declare `type Event`.
+/// * `#[pallet::pallet]` (required): the pallet struct `pub struct Pallet<T>(PhantomData<T>);`.
+///   `#[pallet::generate_store($vis trait Store)]` generates the `Store` trait giving access to
+///   the storages, `#[pallet::storage_prefix($Ident)]` defines the prefix of the storages, it is
+///   required when the pallet declares storages. A type alias `Module` is generated for usage in
+///   `construct_runtime!`.
+/// * `#[pallet::hooks]` (required): the implementation of `Hooks<BlockNumberFor<T>>` on the
+///   pallet struct, its functions are called in the `on_initialize`, `on_finalize`,
+///   `on_runtime_upgrade`, `offchain_worker` and `integrity_test` of the pallet.
+/// * `#[pallet::call]` (required): the implementation block of the dispatchables on the pallet
+///   struct. Each function must have `origin: OriginFor<T>` as first argument, must return
+///   `DispatchResultWithPostInfo` and must be annotated with `#[pallet::weight($expr)]`.
+///   Arguments annotated with `#[pallet::compact]` are compact encoded in the `Call`.
+/// * `#[pallet::error]` (optional): the enum `Error<T>` of the pallet, variants must be unit
+///   variants.
+/// * `#[pallet::event]` (optional): the enum `Event` or `Event<T>` of the pallet, variants must be
+///   unit or tuple variants. `#[pallet::metadata($Type = "$Name", ..)]` defines the name of the
+///   argument types in the metadata, other types are named after their definition.
+///   `#[pallet::generate_deposit($vis fn deposit_event)]` generates the function depositing the
+///   event on the pallet struct.
+/// * `#[pallet::storage]` (optional, multiple): a type alias to `StorageValue<_, Value>`,
+///   `StorageMap<_, Hasher, Key, Value>` or
+///   `StorageDoubleMap<_, Hasher1, Key1, Hasher2, Key2, Value>`, with an optional last generic
+///   `OptionQuery` (the default) or `ValueQuery`. The type alias must be generic over `<T>` if
+///   and only if the keys or the value use `T`. `#[pallet::getter(fn $name)]` generates a getter
+///   function on the pallet struct.
+/// * `#[pallet::genesis_config]` and `#[pallet::genesis_build]` (optional, both or none): the
+///   struct `GenesisConfig` or `GenesisConfig<T>` and its implementation of `GenesisBuild<T>`.
+///
+/// Other items of the module are left untouched.
+///
+/// Instantiable pallets, origins, inherents, unsigned transaction validation, default values
+/// of storages and extra constants are not supported, such pallets must be declared using
+/// `decl_module!` and co.
+#[proc_macro_attribute]
+pub fn pallet(attr: TokenStream, item: TokenStream) -> TokenStream {
+	pallet::pallet(attr, item)
+}
+
+/// Derive [`Clone`] but do not bound any generic.
+///
+/// This is useful for type generic over runtime:
+/// ```nocompile
+/// # use frame_support::CloneNoBound;
+/// trait Trait {
+/// 	type C: Clone;
+/// }
+///
+/// // Foo implements [`Clone`] because `C` bounds [`Clone`].
+/// // Otherwise compilation will fail with an output telling `c` doesn't implement [`Clone`].
+/// #[derive(CloneNoBound)]
+/// struct Foo<T: Trait> {
+/// 	c: T::C,
+/// }
+/// ```
+#[proc_macro_derive(CloneNoBound)]
+pub fn derive_clone_no_bound(input: TokenStream) -> TokenStream {
+	no_bound::derive_clone_no_bound(input)
+}
+
+/// Derive [`Debug`] but do not bound any generics.
+///
+/// This is useful for type generic over runtime:
+/// ```nocompile
+/// # use frame_support::DebugNoBound;
+/// # use core::fmt::Debug;
+/// trait Trait {
+/// 	type C: Debug;
+/// }
+///
+/// // Foo implements [`Debug`] because `C` bounds [`Debug`].
+/// // Otherwise compilation will fail with an output telling `c` doesn't implement [`Debug`].
+/// #[derive(DebugNoBound)]
+/// struct Foo<T: Trait> {
+/// 	c: T::C,
+/// }
+/// ```
+#[proc_macro_derive(DebugNoBound)]
+pub fn derive_debug_no_bound(input: TokenStream) -> TokenStream {
+	no_bound::derive_debug_no_bound(input)
+}
+
+/// Derive [`PartialEq`] but do not bound any generic.
+///
+/// This is useful for type generic over runtime:
+/// ```nocompile
+/// # use frame_support::PartialEqNoBound;
+/// trait Trait {
+/// 	type C: PartialEq;
+/// }
+///
+/// // Foo implements [`PartialEq`] because `C` bounds [`PartialEq`].
+/// // Otherwise compilation will fail with an output telling `c` doesn't implement [`PartialEq`].
+/// #[derive(PartialEqNoBound)]
+/// struct Foo<T: Trait> {
+/// 	c: T::C,
+/// }
+/// ```
+#[proc_macro_derive(PartialEqNoBound)]
+pub fn derive_partial_eq_no_bound(input: TokenStream) -> TokenStream {
+	no_bound::derive_partial_eq_no_bound(input)
+}
+
+/// Derive [`Eq`] but do not bound any generic.
+///
+/// This is useful for type generic over runtime:
+/// ```nocompile
+/// # use frame_support::{EqNoBound, PartialEqNoBound};
+/// trait Trait {
+/// 	type C: Eq;
+/// }
+///
+/// // Foo implements [`Eq`] because `C` bounds [`Eq`].
+/// // Otherwise compilation will fail with an output telling `c` doesn't implement [`Eq`].
+/// #[derive(PartialEqNoBound, EqNoBound)]
+/// struct Foo<T: Trait> {
+/// 	c: T::C,
+/// }
+/// ```
+#[proc_macro_derive(EqNoBound)]
+pub fn derive_eq_no_bound(input: TokenStream) -> TokenStream {
+	no_bound::derive_eq_no_bound(input)
+}
@@ -0,0 +1,76 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use super::{fields_pattern, union_error};
+
+/// Build the constructor of the fields, cloning each binding.
+fn clone_fields(fields: &syn::Fields, bindings: &[syn::Ident]) -> TokenStream {
+	match fields {
+		syn::Fields::Named(named) => {
+			let names = named.named.iter().map(|f| &f.ident);
+			quote!( { #( #names: core::clone::Clone::clone(#bindings), )* } )
+		},
+		syn::Fields::Unnamed(_) => quote!( ( #( core::clone::Clone::clone(#bindings), )* ) ),
+		syn::Fields::Unit => quote!(),
+	}
+}
+
+/// Derive `Clone` without bounding the generic parameters.
+pub fn derive_clone_no_bound(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	let input = syn::parse_macro_input!(input as syn::DeriveInput);
+
+	let name = &input.ident;
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+	let body = match &input.data {
+		syn::Data::Struct(data) => {
+			let (pattern, bindings) = fields_pattern(&data.fields, "_");
+			let clone = clone_fields(&data.fields, &bindings);
+			quote!(
+				let Self #pattern = self;
+				Self #clone
+			)
+		},
+		syn::Data::Enum(data) if data.variants.is_empty() => quote!( match *self {} ),
+		syn::Data::Enum(data) => {
+			let variants = data.variants.iter().map(|variant| {
+				let ident = &variant.ident;
+				let (pattern, bindings) = fields_pattern(&variant.fields, "_");
+				let clone = clone_fields(&variant.fields, &bindings);
+				quote!( Self::#ident #pattern => Self::#ident #clone, )
+			});
+			quote!(
+				match self {
+					#( #variants )*
+				}
+			)
+		},
+		syn::Data::Union(_) => return union_error(&input, "CloneNoBound").into(),
+	};
+
+	quote!(
+		const _: () = {
+			impl #impl_generics core::clone::Clone for #name #ty_generics #where_clause {
+				fn clone(&self) -> Self {
+					#body
+				}
+			}
+		};
+	).into()
+}
@@ -0,0 +1,86 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use super::{fields_pattern, union_error};
+
+/// Build the formatting of the fields, `name` is the name of the struct or variant.
+fn debug_fields(name: &str, fields: &syn::Fields, bindings: &[syn::Ident]) -> TokenStream {
+	match fields {
+		syn::Fields::Named(named) => {
+			let names = named.named.iter()
+				.map(|f| f.ident.as_ref().expect("named fields have ident; qed").to_string());
+			quote!(
+				fmt.debug_struct(#name)
+					#( .field(#names, &#bindings) )*
+					.finish()
+			)
+		},
+		syn::Fields::Unnamed(_) => quote!(
+			fmt.debug_tuple(#name)
+				#( .field(&#bindings) )*
+				.finish()
+		),
+		syn::Fields::Unit => quote!( fmt.write_str(#name) ),
+	}
+}
+
+/// Derive `Debug` without bounding the generic parameters.
+pub fn derive_debug_no_bound(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	let input = syn::parse_macro_input!(input as syn::DeriveInput);
+
+	let name = &input.ident;
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+	let body = match &input.data {
+		syn::Data::Struct(data) => {
+			let (pattern, bindings) = fields_pattern(&data.fields, "_");
+			let debug = debug_fields(&name.to_string(), &data.fields, &bindings);
+			quote!(
+				let Self #pattern = self;
+				#debug
+			)
+		},
+		syn::Data::Enum(data) if data.variants.is_empty() => quote!( match *self {} ),
+		syn::Data::Enum(data) => {
+			let variants = data.variants.iter().map(|variant| {
+				let ident = &variant.ident;
+				let full_name = format!("{}::{}", name, ident);
+				let (pattern, bindings) = fields_pattern(&variant.fields, "_");
+				let debug = debug_fields(&full_name, &variant.fields, &bindings);
+				quote!( Self::#ident #pattern => #debug, )
+			});
+			quote!(
+				match self {
+					#( #variants )*
+				}
+			)
+		},
+		syn::Data::Union(_) => return union_error(&input, "DebugNoBound").into(),
+	};
+
+	quote!(
+		const _: () = {
+			impl #impl_generics core::fmt::Debug for #name #ty_generics #where_clause {
+				fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+					#body
+				}
+			}
+		};
+	).into()
+}
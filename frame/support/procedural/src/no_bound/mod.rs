@@ -0,0 +1,61 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Derive macros for `Clone`, `PartialEq`, `Eq` and `Debug` which don't bound the generic
+//! parameters of the type.
+//!
+//! The derives of the standard library add a bound `T: Trait` for each generic parameter `T`,
+//! the derives here only require each field to implement the trait. This is needed for types
+//! generic over the runtime configuration which itself doesn't implement those traits.
+
+mod clone;
+mod debug;
+mod partial_eq;
+
+pub use clone::derive_clone_no_bound;
+pub use debug::derive_debug_no_bound;
+pub use partial_eq::{derive_partial_eq_no_bound, derive_eq_no_bound};
+
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+
+/// Build the pattern binding all the fields of a struct or a variant, each field is bound to an
+/// identifier made of `prefix` followed by the field index.
+///
+/// Returns the pattern (without the path of the struct or variant) and the bound identifiers.
+fn fields_pattern(fields: &syn::Fields, prefix: &str) -> (TokenStream, Vec<syn::Ident>) {
+	let bindings = (0..fields.len())
+		.map(|i| syn::Ident::new(&format!("{}{}", prefix, i), Span::call_site()))
+		.collect::<Vec<_>>();
+
+	let pattern = match fields {
+		syn::Fields::Named(named) => {
+			let names = named.named.iter().map(|f| &f.ident);
+			quote!( { #( #names: #bindings, )* } )
+		},
+		syn::Fields::Unnamed(_) => quote!( ( #( #bindings, )* ) ),
+		syn::Fields::Unit => quote!(),
+	};
+
+	(pattern, bindings)
+}
+
+/// Error returned for unions, which can't be supported.
+fn union_error(input: &syn::DeriveInput, derive: &str) -> TokenStream {
+	let msg = format!("Union type not supported by `{}`", derive);
+	syn::Error::new(input.ident.span(), msg).to_compile_error()
+}
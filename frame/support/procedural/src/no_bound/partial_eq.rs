@@ -0,0 +1,83 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use quote::quote;
+use super::{fields_pattern, union_error};
+
+/// Derive `PartialEq` without bounding the generic parameters.
+pub fn derive_partial_eq_no_bound(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	let input = syn::parse_macro_input!(input as syn::DeriveInput);
+
+	let name = &input.ident;
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+	let body = match &input.data {
+		syn::Data::Struct(data) => {
+			let (self_pattern, self_bindings) = fields_pattern(&data.fields, "_self_");
+			let (other_pattern, other_bindings) = fields_pattern(&data.fields, "_other_");
+			quote!(
+				let Self #self_pattern = self;
+				let Self #other_pattern = other;
+				true #( && #self_bindings == #other_bindings )*
+			)
+		},
+		syn::Data::Enum(data) => {
+			let variants = data.variants.iter().map(|variant| {
+				let ident = &variant.ident;
+				let (self_pattern, self_bindings) = fields_pattern(&variant.fields, "_self_");
+				let (other_pattern, other_bindings) = fields_pattern(&variant.fields, "_other_");
+				quote!(
+					(Self::#ident #self_pattern, Self::#ident #other_pattern) => {
+						true #( && #self_bindings == #other_bindings )*
+					},
+				)
+			});
+			quote!(
+				match (self, other) {
+					#( #variants )*
+					_ => false,
+				}
+			)
+		},
+		syn::Data::Union(_) => return union_error(&input, "PartialEqNoBound").into(),
+	};
+
+	quote!(
+		const _: () = {
+			impl #impl_generics core::cmp::PartialEq for #name #ty_generics #where_clause {
+				#[allow(unreachable_patterns)]
+				fn eq(&self, other: &Self) -> bool {
+					#body
+				}
+			}
+		};
+	).into()
+}
+
+/// Derive `Eq` without bounding the generic parameters.
+pub fn derive_eq_no_bound(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	let input = syn::parse_macro_input!(input as syn::DeriveInput);
+
+	let name = &input.ident;
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+	quote!(
+		const _: () = {
+			impl #impl_generics core::cmp::Eq for #name #ty_generics #where_clause {}
+		};
+	).into()
+}
@@ -0,0 +1,48 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::pallet::Def;
+
+/// Expand the error enum into `decl_error!`.
+pub fn expand_error(def: &Def, frame_support: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+	let error = if let Some(error) = &def.error {
+		error
+	} else {
+		return Default::default()
+	};
+
+	let pallet = &def.pallet_struct.pallet;
+	let trait_ = &def.config.trait_ident;
+	let error_ident = &error.error;
+	let attrs = &error.attrs;
+
+	let variants = error.variants.iter().map(|(variant, docs)| {
+		quote::quote!(
+			#( #[doc = #docs] )*
+			#variant
+		)
+	});
+
+	quote::quote!(
+		#frame_support::decl_error! {
+			#( #attrs )*
+			pub enum #error_ident for #pallet<T: #trait_> {
+				#( #variants, )*
+			}
+		}
+	)
+}
@@ -0,0 +1,109 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::pallet::Def;
+
+/// * Add derives, and the hidden `__Ignore` variant if the event is generic, to the event enum.
+/// * Implement the function `metadata` on the event, used by `construct_runtime!`.
+/// * Implement `From<Event>` for `()`, as events of `decl_event!`.
+/// * Implement the function `deposit_event` on the pallet if requested.
+pub fn expand_event(def: &mut Def, frame_support: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+	let event = if let Some(event) = &def.event {
+		event
+	} else {
+		return Default::default()
+	};
+
+	let pallet = &def.pallet_struct.pallet;
+	let trait_ = &def.config.trait_ident;
+
+	let item = &mut def.item.content.as_mut().expect("Checked by def parser").1[event.index];
+	let item = if let syn::Item::Enum(item) = item {
+		item
+	} else {
+		unreachable!("Checked by event parser")
+	};
+
+	if event.is_generic {
+		item.variants.push(syn::parse_quote!(
+			#[doc(hidden)]
+			#[codec(skip)]
+			__Ignore(#frame_support::sp_std::marker::PhantomData<T>, #frame_support::Never)
+		));
+	}
+
+	item.attrs.push(syn::parse_quote!(
+		#[derive(
+			#frame_support::CloneNoBound,
+			#frame_support::EqNoBound,
+			#frame_support::PartialEqNoBound,
+			#frame_support::DebugNoBound,
+			#frame_support::codec::Encode,
+			#frame_support::codec::Decode,
+		)]
+	));
+
+	let (impl_generics, ty_generics, where_clause) = item.generics.split_for_impl();
+	let event_ident = &item.ident;
+
+	let metadata = event.metadata.iter().map(|variant| {
+		let name = variant.name.to_string();
+		let args = &variant.args;
+		let docs = &variant.docs;
+		quote::quote!(
+			#frame_support::event::EventMetadata {
+				name: #frame_support::event::DecodeDifferent::Encode(#name),
+				arguments: #frame_support::event::DecodeDifferent::Encode(&[ #( #args, )* ]),
+				documentation: #frame_support::event::DecodeDifferent::Encode(&[ #( #docs, )* ]),
+			},
+		)
+	});
+
+	let deposit_event = event.deposit_event.as_ref().map(|deposit_event| {
+		let fn_vis = &deposit_event.fn_vis;
+		let event_use_gen = if event.is_generic { Some(quote::quote!( <T> )) } else { None };
+
+		quote::quote!(
+			impl<T: #trait_> #pallet<T> {
+				/// Deposit an event of this pallet.
+				#fn_vis fn deposit_event(event: #event_ident #event_use_gen) {
+					let event = <<T as #trait_>::Event as From<#event_ident #event_use_gen>>::from(event);
+					let event = <
+						<T as #trait_>::Event as Into<<T as frame_system::Trait>::Event>
+					>::into(event);
+					<frame_system::Module<T>>::deposit_event(event)
+				}
+			}
+		)
+	});
+
+	quote::quote!(
+		impl #impl_generics #event_ident #ty_generics #where_clause {
+			#[allow(dead_code)]
+			#[doc(hidden)]
+			pub fn metadata() -> &'static [#frame_support::event::EventMetadata] {
+				&[ #( #metadata )* ]
+			}
+		}
+
+		impl #impl_generics From<#event_ident #ty_generics> for () #where_clause {
+			fn from(_: #event_ident #ty_generics) {}
+		}
+
+		#deposit_event
+	)
+}
@@ -0,0 +1,81 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::pallet::Def;
+use quote::ToTokens;
+
+/// * Add derives and serde attributes to the genesis config, as `decl_storage!` does.
+/// * Implement `BuildModuleGenesisStorage` for the genesis config, used by `construct_runtime!`,
+///   by calling the implementation of `GenesisBuild`.
+pub fn expand_genesis_config(
+	def: &mut Def,
+	frame_support: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+	let genesis_config = if let Some(genesis_config) = &def.genesis_config {
+		genesis_config
+	} else {
+		return Default::default()
+	};
+
+	let item = &mut def.item.content.as_mut().expect("Checked by def parser").1
+		[genesis_config.index];
+	let item = if let syn::Item::Struct(item) = item {
+		item
+	} else {
+		unreachable!("Checked by genesis_config parser")
+	};
+
+	item.attrs.push(syn::parse_quote!(
+		#[cfg_attr(feature = "std", derive(#frame_support::Serialize, #frame_support::Deserialize))]
+	));
+	item.attrs.push(syn::parse_quote!(
+		#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+	));
+	item.attrs.push(syn::parse_quote!( #[cfg_attr(feature = "std", serde(deny_unknown_fields))] ));
+
+	if genesis_config.is_generic {
+		let serde_bound = |trait_: &str| genesis_config.field_types.iter()
+			.map(|ty| format!("{} : {}::serde::{}, ", ty.to_token_stream(), frame_support, trait_))
+			.collect::<String>();
+
+		let ser_bound = serde_bound("Serialize");
+		let de_bound = serde_bound("de::DeserializeOwned");
+		item.attrs.push(syn::parse_quote!(
+			#[cfg_attr(feature = "std", serde(bound(serialize = #ser_bound)))]
+		));
+		item.attrs.push(syn::parse_quote!(
+			#[cfg_attr(feature = "std", serde(bound(deserialize = #de_bound)))]
+		));
+	}
+
+	let trait_ = &def.config.trait_ident;
+	let (_, ty_generics, _) = item.generics.split_for_impl();
+
+	quote::quote!(
+		#[cfg(feature = "std")]
+		impl<T: #trait_> #frame_support::sp_runtime::BuildModuleGenesisStorage<T, __InherentHiddenInstance>
+			for GenesisConfig #ty_generics
+		{
+			fn build_module_genesis_storage(
+				&self,
+				storage: &mut #frame_support::sp_runtime::Storage,
+			) -> std::result::Result<(), std::string::String> {
+				<Self as #frame_support::traits::GenesisBuild<T>>::assimilate_storage(self, storage)
+			}
+		}
+	)
+}
@@ -0,0 +1,72 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Expansion of the pallet macro.
+//!
+//! The pallet is expanded into the `decl_*` macros so that it shares the same metadata, call,
+//! storage and genesis implementation as the pallets declared with `decl_module!`,
+//! `decl_storage!` and co:
+//! * the pallet struct, the calls, the constants and the hooks are expanded into
+//!   `decl_module!`,
+//! * the storages are expanded into `decl_storage!`,
+//! * the error is expanded into `decl_error!`,
+//! * the event and the genesis config are completed in place.
+
+mod module;
+mod storage;
+mod error;
+mod event;
+mod genesis_config;
+
+use crate::pallet::Def;
+use frame_support_procedural_tools::generate_crate_access_2018;
+use quote::ToTokens;
+
+/// Expand the pallet definition, see module documentation.
+pub fn expand(mut def: Def) -> proc_macro2::TokenStream {
+	let frame_support = match generate_crate_access_2018() {
+		Ok(frame_support) => frame_support,
+		Err(e) => return e.to_compile_error(),
+	};
+
+	let module = module::expand_module(&def, &frame_support);
+	let storages = storage::expand_storages(&def, &frame_support);
+	let error = error::expand_error(&def, &frame_support);
+	let event = event::expand_event(&mut def, &frame_support);
+	let genesis_config = genesis_config::expand_genesis_config(&mut def, &frame_support);
+
+	// The items replaced by the `decl_*` macros are removed from the module.
+	let removed_items = vec![def.pallet_struct.index, def.call.index].into_iter()
+		.chain(def.error.as_ref().map(|error| error.index))
+		.chain(def.storages.iter().map(|storage| storage.index))
+		.collect::<Vec<_>>();
+
+	let items = &mut def.item.content.as_mut().expect("Checked by def parser").1;
+	for index in removed_items {
+		items[index] = syn::Item::Verbatim(Default::default());
+	}
+
+	items.push(syn::Item::Verbatim(quote::quote!(
+		#module
+		#storages
+		#error
+		#event
+		#genesis_config
+	)));
+
+	def.item.into_token_stream()
+}
@@ -0,0 +1,120 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::pallet::Def;
+
+/// Expand the pallet struct, the calls, the hooks and the constants into `decl_module!`.
+///
+/// * the pallet struct is declared by `decl_module!`,
+/// * the `Error` type is given to `decl_module!` if any,
+/// * each constant of the config is given as a `decl_module!` constant,
+/// * each hook of `decl_module!` is forwarded to the implementation of `Hooks`,
+/// * each call is given as a `decl_module!` dispatchable.
+///
+/// Also expand `Module` type alias to the pallet struct for usage in `construct_runtime!`.
+pub fn expand_module(def: &Def, frame_support: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+	let pallet = &def.pallet_struct.pallet;
+	let pallet_attrs = &def.pallet_struct.attrs;
+	let trait_ = &def.config.trait_ident;
+
+	let error = def.error.as_ref().map(|error| {
+		let error = &error.error;
+		quote::quote!( type Error = #error<T>; )
+	});
+
+	let constants = def.config.consts_metadata.iter().map(|constant| {
+		let ident = &constant.ident;
+		let type_ = &constant.type_;
+		let doc = &constant.doc;
+		quote::quote!(
+			#( #[doc = #doc] )*
+			const #ident: #type_ = <<T as #trait_>::#ident as #frame_support::traits::Get<#type_>>::get();
+		)
+	});
+
+	let block_number = quote::quote!( <T as frame_system::Trait>::BlockNumber );
+	let hooks = quote::quote!( #frame_support::traits::Hooks<#block_number> );
+
+	let calls = def.call.methods.iter().map(|method| {
+		let name = &method.name;
+		let vis = &method.vis;
+		let origin = &method.origin;
+		let weight = &method.weight;
+		let docs = &method.docs;
+		let attrs = &method.attrs;
+		let output = &method.output;
+		let block = &method.block;
+		let args = method.args.iter().map(|(is_compact, arg_name, arg_type)| {
+			let compact = if *is_compact { Some(quote::quote!( #[compact] )) } else { None };
+			quote::quote!( #compact #arg_name: #arg_type )
+		});
+
+		quote::quote!(
+			#( #docs )*
+			#[weight = #weight]
+			#( #attrs )*
+			#vis fn #name(#origin #( , #args )*) -> #output #block
+		)
+	});
+
+	let module_alias = if pallet != "Module" {
+		Some(quote::quote!(
+			/// Type alias to the pallet struct, used by `construct_runtime!`.
+			pub type Module<T> = #pallet<T>;
+		))
+	} else {
+		None
+	};
+
+	quote::quote!(
+		#frame_support::decl_module! {
+			#( #pallet_attrs )*
+			pub struct #pallet<T: #trait_>
+				for enum Call
+				where origin: <T as frame_system::Trait>::Origin, system = frame_system
+			{
+				#error
+
+				#( #constants )*
+
+				fn on_initialize(n: #block_number) -> #frame_support::weights::Weight {
+					<Self as #hooks>::on_initialize(n)
+				}
+
+				fn on_finalize(n: #block_number) {
+					<Self as #hooks>::on_finalize(n)
+				}
+
+				fn on_runtime_upgrade() -> #frame_support::weights::Weight {
+					<Self as #hooks>::on_runtime_upgrade()
+				}
+
+				fn offchain_worker(n: #block_number) {
+					<Self as #hooks>::offchain_worker(n)
+				}
+
+				fn integrity_test() {
+					<Self as #hooks>::integrity_test()
+				}
+
+				#( #calls )*
+			}
+		}
+
+		#module_alias
+	)
+}
@@ -0,0 +1,70 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::pallet::{Def, parse::storage::{StorageKind, QueryKind}};
+
+/// Expand the storages into `decl_storage!`.
+///
+/// `decl_storage!` is always expanded, even without storage, as it declares the storage
+/// metadata and the hidden instance used by the genesis config.
+pub fn expand_storages(def: &Def, frame_support: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+	let pallet = &def.pallet_struct.pallet;
+	let trait_ = &def.config.trait_ident;
+	let prefix = def.pallet_struct.storage_prefix.as_ref().unwrap_or(pallet);
+
+	let store = match &def.pallet_struct.store {
+		Some(vis) => quote::quote!( #vis trait Store ),
+		None => quote::quote!( trait __GeneratedStore ),
+	};
+
+	let lines = def.storages.iter().map(|storage| {
+		let attrs = &storage.attrs;
+		let vis = &storage.vis;
+		let ident = &storage.ident;
+		let getter = storage.getter.as_ref().map(|getter| quote::quote!( get(fn #getter) ));
+
+		let query = |value: &syn::Type| match storage.query_kind {
+			QueryKind::OptionQuery => quote::quote!( Option<#value> ),
+			QueryKind::ValueQuery => quote::quote!( #value ),
+		};
+
+		let type_ = match &storage.kind {
+			StorageKind::Value { value } => query(value),
+			StorageKind::Map { hasher, key, value } => {
+				let value = query(value);
+				quote::quote!( map hasher(#hasher) #key => #value )
+			},
+			StorageKind::DoubleMap { hasher1, key1, hasher2, key2, value } => {
+				let value = query(value);
+				quote::quote!( double_map hasher(#hasher1) #key1, hasher(#hasher2) #key2 => #value )
+			},
+		};
+
+		quote::quote!(
+			#( #attrs )*
+			#vis #ident #getter: #type_;
+		)
+	});
+
+	quote::quote!(
+		#frame_support::decl_storage! {
+			#store for #pallet<T: #trait_> as #prefix {
+				#( #lines )*
+			}
+		}
+	)
+}
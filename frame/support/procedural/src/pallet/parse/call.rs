@@ -0,0 +1,269 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::helper;
+use syn::spanned::Spanned;
+
+/// List of additional token to be used for parsing.
+mod keyword {
+	syn::custom_keyword!(weight);
+	syn::custom_keyword!(compact);
+}
+
+/// Definition of dispatchables typically `impl<T: Trait> Pallet<T> { ... }`
+pub struct CallDef {
+	/// The index of item in pallet module.
+	pub index: usize,
+	/// The ident of the type the calls are implemented on, e.g. `Pallet`.
+	pub pallet: syn::Ident,
+	/// Information on methods (used for expansion).
+	pub methods: Vec<CallVariantDef>,
+}
+
+/// Definition of dispatchable typically: `#[weight...] fn foo(origin .., param1: ...) -> ..`
+pub struct CallVariantDef {
+	/// Function name.
+	pub name: syn::Ident,
+	/// Visibility of the function.
+	pub vis: syn::Visibility,
+	/// Name of the origin argument.
+	pub origin: syn::Ident,
+	/// Information on args: `(is_compact, name, type)`.
+	pub args: Vec<(bool, syn::Ident, Box<syn::Type>)>,
+	/// Weight formula.
+	pub weight: syn::Expr,
+	/// The doc attributes of the function.
+	pub docs: Vec<syn::Attribute>,
+	/// The other attributes of the function.
+	pub attrs: Vec<syn::Attribute>,
+	/// The return type of the function.
+	pub output: Box<syn::Type>,
+	/// The body of the function.
+	pub block: Box<syn::Block>,
+}
+
+/// Attributes for functions in call impl block.
+/// Parse for `#[pallet::weight(expr)]`
+pub struct FunctionAttr {
+	weight: syn::Expr,
+}
+
+impl syn::parse::Parse for FunctionAttr {
+	fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+		let content = helper::parse_pallet_attr_start(input)?;
+		content.parse::<keyword::weight>()?;
+
+		let weight_content;
+		syn::parenthesized!(weight_content in content);
+		Ok(FunctionAttr { weight: weight_content.parse::<syn::Expr>()? })
+	}
+}
+
+/// Attribute for arguments in function in call impl block.
+/// Parse for `#[pallet::compact]`
+pub struct ArgAttrIsCompact;
+
+impl syn::parse::Parse for ArgAttrIsCompact {
+	fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+		let content = helper::parse_pallet_attr_start(input)?;
+		content.parse::<keyword::compact>()?;
+		Ok(ArgAttrIsCompact)
+	}
+}
+
+/// Check the type is `OriginFor<T>`.
+fn check_origin_type(ty: &syn::Type) -> syn::Result<()> {
+	let is_origin_for = helper::last_path_segment(ty)
+		.map_or(false, |segment| segment.ident == "OriginFor");
+
+	if !is_origin_for {
+		let msg = "Invalid pallet::call, expected `OriginFor<T>` as type of the first argument";
+		return Err(syn::Error::new(ty.span(), msg));
+	}
+
+	Ok(())
+}
+
+/// Check the return type is `DispatchResultWithPostInfo`.
+fn check_output_type(output: &syn::ReturnType, span: proc_macro2::Span) -> syn::Result<()> {
+	let msg = "Invalid pallet::call, require return type DispatchResultWithPostInfo";
+	match output {
+		syn::ReturnType::Type(_, ty) => {
+			let is_dispatch_result = helper::last_path_segment(ty)
+				.map_or(false, |segment| segment.ident == "DispatchResultWithPostInfo");
+
+			if !is_dispatch_result {
+				return Err(syn::Error::new(ty.span(), msg));
+			}
+		},
+		syn::ReturnType::Default => return Err(syn::Error::new(span, msg)),
+	}
+
+	Ok(())
+}
+
+impl CallVariantDef {
+	fn try_from(method: syn::ImplItemMethod) -> syn::Result<Self> {
+		let syn::ImplItemMethod { attrs, vis, sig, block, .. } = method;
+
+		if sig.constness.is_some() || sig.asyncness.is_some() || sig.unsafety.is_some()
+			|| sig.abi.is_some() || sig.variadic.is_some()
+		{
+			let msg = "Invalid pallet::call, expected plain function, i.e. not const, async, \
+				unsafe nor extern";
+			return Err(syn::Error::new(sig.span(), msg));
+		}
+
+		if !sig.generics.params.is_empty() || sig.generics.where_clause.is_some() {
+			let msg = "Invalid pallet::call, generics are not supported";
+			return Err(syn::Error::new(sig.generics.span(), msg));
+		}
+
+		let mut inputs = sig.inputs.into_iter();
+
+		let origin = match inputs.next() {
+			Some(syn::FnArg::Typed(arg)) => {
+				check_origin_type(&arg.ty)?;
+				match *arg.pat {
+					syn::Pat::Ident(ref pat) if pat.by_ref.is_none() && pat.mutability.is_none() =>
+						pat.ident.clone(),
+					_ => {
+						let msg = "Invalid pallet::call, expected an identifier for the origin";
+						return Err(syn::Error::new(arg.pat.span(), msg));
+					},
+				}
+			},
+			Some(arg) => {
+				let msg = "Invalid pallet::call, first argument must be a typed argument, \
+					e.g. `origin: OriginFor<T>`";
+				return Err(syn::Error::new(arg.span(), msg));
+			},
+			None => {
+				let msg = "Invalid pallet::call, must have at least origin arg";
+				return Err(syn::Error::new(sig.ident.span(), msg));
+			},
+		};
+
+		let mut args = vec![];
+		for arg in inputs {
+			let mut arg = if let syn::FnArg::Typed(arg) = arg {
+				arg
+			} else {
+				let msg = "Invalid pallet::call, expected typed argument";
+				return Err(syn::Error::new(arg.span(), msg));
+			};
+
+			let arg_attrs: Vec<ArgAttrIsCompact> = helper::take_item_attrs(&mut arg.attrs)?;
+			if arg_attrs.len() > 1 {
+				let msg = "Invalid pallet::call, argument has too many attributes";
+				return Err(syn::Error::new(arg.span(), msg));
+			}
+
+			if let Some(attr) = arg.attrs.first() {
+				let msg = "Invalid pallet::call, only `#[pallet::compact]` is supported on arguments";
+				return Err(syn::Error::new(attr.span(), msg));
+			}
+
+			let arg_ident = match *arg.pat {
+				syn::Pat::Ident(ref pat) if pat.by_ref.is_none() && pat.mutability.is_none()
+					&& pat.subpat.is_none() => pat.ident.clone(),
+				_ => {
+					let msg = "Invalid pallet::call, argument must be ident";
+					return Err(syn::Error::new(arg.pat.span(), msg));
+				},
+			};
+
+			args.push((!arg_attrs.is_empty(), arg_ident, arg.ty));
+		}
+
+		check_output_type(&sig.output, sig.ident.span())?;
+		let output = match sig.output {
+			syn::ReturnType::Type(_, ty) => ty,
+			syn::ReturnType::Default => unreachable!("checked by `check_output_type`; qed"),
+		};
+
+		let mut attrs = attrs;
+		let mut weight_attrs: Vec<FunctionAttr> = helper::take_item_attrs(&mut attrs)?;
+		if weight_attrs.len() != 1 {
+			let msg = if weight_attrs.is_empty() {
+				"Invalid pallet::call, requires weight attribute i.e. `#[pallet::weight($expr)]`"
+			} else {
+				"Invalid pallet::call, too many weight attributes given"
+			};
+			return Err(syn::Error::new(sig.ident.span(), msg));
+		}
+		let weight = weight_attrs.pop().expect("checked above; qed").weight;
+
+		let (docs, attrs): (Vec<_>, Vec<_>) = attrs.into_iter()
+			.partition(|attr| match attr.parse_meta() {
+				Ok(syn::Meta::NameValue(meta)) => meta.path.is_ident("doc"),
+				_ => false,
+			});
+
+		Ok(Self {
+			name: sig.ident,
+			vis,
+			origin,
+			args,
+			weight,
+			docs,
+			attrs,
+			output,
+			block: Box::new(block),
+		})
+	}
+}
+
+impl CallDef {
+	pub fn try_from(index: usize, item: &mut syn::Item) -> syn::Result<Self> {
+		let item = if let syn::Item::Impl(item) = item {
+			item
+		} else {
+			return Err(syn::Error::new(item.span(), "Invalid pallet::call, expected item impl"));
+		};
+
+		if let Some((_, path, _)) = &item.trait_ {
+			let msg = "Invalid pallet::call, expected inherent implementation, i.e. \
+				`impl<T: Trait> Pallet<T>`";
+			return Err(syn::Error::new(path.span(), msg));
+		}
+
+		if let Some(where_clause) = &item.generics.where_clause {
+			let msg = "Invalid pallet::call, where clause is not supported";
+			return Err(syn::Error::new(where_clause.span(), msg));
+		}
+
+		let pallet = helper::last_path_segment(&item.self_ty)
+			.map(|segment| segment.ident.clone())
+			.ok_or_else(|| {
+				let msg = "Invalid pallet::call, expected implementation on `Pallet<T>`";
+				syn::Error::new(item.self_ty.span(), msg)
+			})?;
+
+		let mut methods = vec![];
+		for impl_item in &item.items {
+			if let syn::ImplItem::Method(method) = impl_item {
+				methods.push(CallVariantDef::try_from(method.clone())?);
+			} else {
+				let msg = "Invalid pallet::call, only method accepted";
+				return Err(syn::Error::new(impl_item.span(), msg));
+			}
+		}
+
+		Ok(Self { index, pallet, methods })
+	}
+}
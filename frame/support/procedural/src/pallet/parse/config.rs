@@ -0,0 +1,172 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::helper;
+use syn::spanned::Spanned;
+
+/// List of additional token to be used for parsing.
+mod keyword {
+	syn::custom_keyword!(constant);
+	syn::custom_keyword!(Get);
+}
+
+/// Input definition for the pallet config.
+pub struct ConfigDef {
+	/// The name of the trait, e.g. `Trait`.
+	pub trait_ident: syn::Ident,
+	/// The associated types declared with `#[pallet::constant]`, exposed in the metadata.
+	pub consts_metadata: Vec<ConstMetadataDef>,
+	/// Whether the trait has the associated type `Event`.
+	pub has_event_type: bool,
+}
+
+/// Input definition for a constant in pallet config.
+pub struct ConstMetadataDef {
+	/// Name of the associated type.
+	pub ident: syn::Ident,
+	/// The type in `Get<$type>`, e.g. `u32` for `type Foo: Get<u32>;`.
+	pub type_: syn::Type,
+	/// The doc associated.
+	pub doc: Vec<syn::Lit>,
+}
+
+/// Parse for `#[pallet::constant]`.
+pub struct ConstantAttr;
+
+impl syn::parse::Parse for ConstantAttr {
+	fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+		let content = helper::parse_pallet_attr_start(input)?;
+		content.parse::<keyword::constant>()?;
+		Ok(ConstantAttr)
+	}
+}
+
+/// Parse for `Get<$type>`.
+struct GetType(syn::Type);
+
+impl syn::parse::Parse for GetType {
+	fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+		input.parse::<keyword::Get>()?;
+		input.parse::<syn::Token![<]>()?;
+		let type_ = input.parse::<syn::Type>()?;
+		input.parse::<syn::Token![>]>()?;
+		Ok(GetType(type_))
+	}
+}
+
+impl ConstMetadataDef {
+	fn try_from(trait_ty: &syn::TraitItemType) -> syn::Result<Self> {
+		let err = |span, msg|
+			syn::Error::new(span, format!("Invalid usage of `#[pallet::constant]`: {}", msg));
+
+		let bound = trait_ty.bounds.iter()
+			.find_map(|bound| match bound {
+				syn::TypeParamBound::Trait(bound) => bound.path.segments.last()
+					.filter(|segment| segment.ident == "Get")
+					.map(|segment| segment.clone()),
+				_ => None,
+			})
+			.ok_or_else(|| err(trait_ty.span(), "`Get<T>` trait bound not found"))?;
+
+		let GetType(type_) = syn::parse2(quote::quote!(#bound))
+			.map_err(|_| err(bound.span(), "expected `Get<$type>` trait bound"))?;
+
+		Ok(ConstMetadataDef {
+			ident: trait_ty.ident.clone(),
+			type_,
+			doc: helper::get_doc_literals(&trait_ty.attrs),
+		})
+	}
+}
+
+/// Check the trait has `frame_system::Trait` as supertrait.
+fn has_frame_system_supertrait(item: &syn::ItemTrait) -> bool {
+	item.supertraits.iter().any(|bound| match bound {
+		syn::TypeParamBound::Trait(bound) => {
+			let segments = bound.path.segments.iter().collect::<Vec<_>>();
+			match segments.as_slice() {
+				[system, trait_] => system.ident == "frame_system"
+					&& trait_.ident == "Trait"
+					&& trait_.arguments.is_empty(),
+				_ => false,
+			}
+		},
+		_ => false,
+	})
+}
+
+impl ConfigDef {
+	pub fn try_from(item: &mut syn::Item) -> syn::Result<Self> {
+		let item = if let syn::Item::Trait(item) = item {
+			item
+		} else {
+			let msg = "Invalid pallet::config, expected trait definition";
+			return Err(syn::Error::new(item.span(), msg));
+		};
+
+		if !matches!(item.vis, syn::Visibility::Public(_)) {
+			let msg = "Invalid pallet::config, trait must be public";
+			return Err(syn::Error::new(item.span(), msg));
+		}
+
+		if !item.generics.params.is_empty() || item.generics.where_clause.is_some() {
+			let msg = "Invalid pallet::config, expected no generics nor where_clause";
+			return Err(syn::Error::new(item.generics.span(), msg));
+		}
+
+		if !has_frame_system_supertrait(item) {
+			let msg = format!(
+				"Invalid pallet::config, expected explicit `frame_system::Trait` as supertrait, \
+				found none. (try `pub trait {}: frame_system::Trait {{ ...`)",
+				item.ident,
+			);
+			return Err(syn::Error::new(item.ident.span(), msg));
+		}
+
+		let mut has_event_type = false;
+		let mut consts_metadata = vec![];
+		for trait_item in &mut item.items {
+			if let syn::TraitItem::Type(type_) = trait_item {
+				if type_.ident == "Event" {
+					has_event_type = true;
+				}
+
+				let constant_attrs: Vec<ConstantAttr> = helper::take_item_attrs(&mut type_.attrs)?;
+				match constant_attrs.len() {
+					0 => (),
+					1 => consts_metadata.push(ConstMetadataDef::try_from(type_)?),
+					_ => {
+						let msg = "Invalid pallet::constant, duplicated attribute";
+						return Err(syn::Error::new(type_.span(), msg));
+					},
+				}
+			} else {
+				let attrs: Vec<ConstantAttr> = helper::take_item_attrs(trait_item)?;
+				if !attrs.is_empty() {
+					let msg = "Invalid pallet::constant, expected associated type";
+					return Err(syn::Error::new(trait_item.span(), msg));
+				}
+			}
+		}
+
+		Ok(Self {
+			trait_ident: item.ident.clone(),
+			consts_metadata,
+			has_event_type,
+		})
+	}
+}
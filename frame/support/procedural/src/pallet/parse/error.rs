@@ -0,0 +1,79 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::helper;
+use syn::spanned::Spanned;
+
+/// This checks error declaration as a enum declaration with only variants without fields nor
+/// discriminant.
+pub struct ErrorDef {
+	/// The index of error item in pallet module.
+	pub index: usize,
+	/// The error ident, e.g. `Error`.
+	pub error: syn::Ident,
+	/// The attributes of the enum other than pallet attributes.
+	pub attrs: Vec<syn::Attribute>,
+	/// Variants ident and doc literals (ordered as declaration order).
+	pub variants: Vec<(syn::Ident, Vec<syn::Lit>)>,
+}
+
+impl ErrorDef {
+	pub fn try_from(index: usize, item: &mut syn::Item) -> syn::Result<Self> {
+		let item = if let syn::Item::Enum(item) = item {
+			item
+		} else {
+			return Err(syn::Error::new(item.span(), "Invalid pallet::error, expected item enum"));
+		};
+
+		if !matches!(item.vis, syn::Visibility::Public(_)) {
+			let msg = "Invalid pallet::error, `Error` must be public";
+			return Err(syn::Error::new(item.span(), msg));
+		}
+
+		let msg = "Invalid pallet::error, expected generic `<T>`";
+		helper::check_type_generics(&item.generics, false, item.ident.span(), msg)?;
+
+		let variants = item.variants.iter()
+			.map(|variant| {
+				if !matches!(variant.fields, syn::Fields::Unit) {
+					let msg = "Invalid pallet::error, unexpected fields, must be `Unit`";
+					return Err(syn::Error::new(variant.fields.span(), msg));
+				}
+				if let Some((_, discriminant)) = &variant.discriminant {
+					let msg = "Invalid pallet::error, unexpected discriminant, discriminant are \
+						not supported";
+					return Err(syn::Error::new(discriminant.span(), msg));
+				}
+
+				let doc = helper::get_doc_literals(&variant.attrs);
+				if doc.len() != variant.attrs.len() {
+					let msg = "Invalid pallet::error, only doc attributes are supported on variants";
+					return Err(syn::Error::new(variant.ident.span(), msg));
+				}
+
+				Ok((variant.ident.clone(), doc))
+			})
+			.collect::<Result<_, _>>()?;
+
+		Ok(ErrorDef {
+			index,
+			error: item.ident.clone(),
+			attrs: item.attrs.clone(),
+			variants,
+		})
+	}
+}
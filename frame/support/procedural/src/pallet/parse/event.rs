@@ -0,0 +1,207 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::helper;
+use syn::spanned::Spanned;
+use frame_support_procedural_tools::clean_type_string;
+use quote::ToTokens;
+
+/// List of additional token to be used for parsing.
+mod keyword {
+	syn::custom_keyword!(metadata);
+	syn::custom_keyword!(generate_deposit);
+	syn::custom_keyword!(deposit_event);
+}
+
+/// Definition for pallet event enum.
+pub struct EventDef {
+	/// The index of event item in pallet module.
+	pub index: usize,
+	/// Whether the event is generic over `T`.
+	pub is_generic: bool,
+	/// The name of each event variant with its argument names and doc literals.
+	pub metadata: Vec<EventVariantMetadata>,
+	/// Whether the function `deposit_event` must be generated.
+	pub deposit_event: Option<DepositEventDef>,
+}
+
+/// Metadata of an event variant.
+pub struct EventVariantMetadata {
+	/// The variant ident.
+	pub name: syn::Ident,
+	/// The names of the arguments, as exposed in the metadata.
+	pub args: Vec<String>,
+	/// The doc literals of the variant.
+	pub docs: Vec<syn::Lit>,
+}
+
+/// Definition for `#[pallet::generate_deposit($vis fn deposit_event)]`.
+pub struct DepositEventDef {
+	/// The visibility of the generated function.
+	pub fn_vis: syn::Visibility,
+	/// The span of the pallet::generate_deposit attribute.
+	pub attr_span: proc_macro2::Span,
+}
+
+/// Parse for one variant of:
+/// * `#[pallet::metadata($Type = "$name", ...)]`
+/// * `#[pallet::generate_deposit($vis fn deposit_event)]`
+enum PalletEventAttr {
+	Metadata {
+		span: proc_macro2::Span,
+		types: Vec<(syn::Type, String)>,
+	},
+	DepositEvent {
+		span: proc_macro2::Span,
+		fn_vis: syn::Visibility,
+	},
+}
+
+impl PalletEventAttr {
+	fn span(&self) -> proc_macro2::Span {
+		match self {
+			Self::Metadata { span, .. } => *span,
+			Self::DepositEvent { span, .. } => *span,
+		}
+	}
+}
+
+/// Parse for syntax `$Type = "$SomeString"`.
+fn parse_event_metadata_element(
+	input: syn::parse::ParseStream,
+) -> syn::Result<(syn::Type, String)> {
+	let typ = input.parse::<syn::Type>()?;
+	input.parse::<syn::Token![=]>()?;
+	let name = input.parse::<syn::LitStr>()?.value();
+	Ok((typ, name))
+}
+
+impl syn::parse::Parse for PalletEventAttr {
+	fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+		let content = helper::parse_pallet_attr_start(input)?;
+
+		let lookahead = content.lookahead1();
+		if lookahead.peek(keyword::metadata) {
+			let span = content.parse::<keyword::metadata>()?.span();
+			let metadata_content;
+			syn::parenthesized!(metadata_content in content);
+
+			let types = metadata_content
+				.parse_terminated::<_, syn::Token![,]>(parse_event_metadata_element)?
+				.into_iter()
+				.collect();
+
+			Ok(Self::Metadata { span, types })
+		} else if lookahead.peek(keyword::generate_deposit) {
+			let span = content.parse::<keyword::generate_deposit>()?.span();
+
+			let generate_content;
+			syn::parenthesized!(generate_content in content);
+			let fn_vis = generate_content.parse::<syn::Visibility>()?;
+			generate_content.parse::<syn::Token![fn]>()?;
+			generate_content.parse::<keyword::deposit_event>()?;
+
+			Ok(Self::DepositEvent { span, fn_vis })
+		} else {
+			Err(lookahead.error())
+		}
+	}
+}
+
+impl EventDef {
+	pub fn try_from(index: usize, item: &mut syn::Item) -> syn::Result<Self> {
+		let item = if let syn::Item::Enum(item) = item {
+			item
+		} else {
+			return Err(syn::Error::new(item.span(), "Invalid pallet::event, expected item enum"))
+		};
+
+		let mut metadata_types = None;
+		let mut deposit_event = None;
+
+		let event_attrs: Vec<PalletEventAttr> = helper::take_item_attrs(&mut item.attrs)?;
+		for attr in event_attrs {
+			match attr {
+				PalletEventAttr::Metadata { types, .. } if metadata_types.is_none() => {
+					metadata_types = Some(types);
+				},
+				PalletEventAttr::DepositEvent { fn_vis, span } if deposit_event.is_none() => {
+					deposit_event = Some(DepositEventDef { fn_vis, attr_span: span });
+				},
+				attr => {
+					let msg = "Invalid duplicated attribute";
+					return Err(syn::Error::new(attr.span(), msg));
+				},
+			}
+		}
+		let metadata_types = metadata_types.unwrap_or_default()
+			.into_iter()
+			.map(|(type_, name)| (clean_type_string(&type_.to_token_stream().to_string()), name))
+			.collect::<Vec<_>>();
+
+		if !matches!(item.vis, syn::Visibility::Public(_)) {
+			let msg = "Invalid pallet::event, `Event` must be public";
+			return Err(syn::Error::new(item.span(), msg));
+		}
+
+		if item.ident != "Event" {
+			let msg = "Invalid pallet::event, expected enum named `Event`";
+			return Err(syn::Error::new(item.ident.span(), msg));
+		}
+
+		let msg = "Invalid pallet::event, expected no generics or generic `<T>`";
+		let is_generic =
+			helper::check_type_generics(&item.generics, true, item.ident.span(), msg)?;
+
+		let metadata = item.variants.iter()
+			.map(|variant| {
+				let fields = match &variant.fields {
+					syn::Fields::Named(_) => {
+						let msg = "Invalid pallet::event, named fields are not supported, \
+							variants must be unit or tuple variants";
+						return Err(syn::Error::new(variant.fields.span(), msg));
+					},
+					syn::Fields::Unnamed(fields) => fields.unnamed.iter().collect::<Vec<_>>(),
+					syn::Fields::Unit => vec![],
+				};
+
+				let args = fields.iter()
+					.map(|field| {
+						let type_ = clean_type_string(&field.ty.to_token_stream().to_string());
+						metadata_types.iter()
+							.find(|(metadata_type, _)| *metadata_type == type_)
+							.map(|(_, name)| name.clone())
+							.unwrap_or(type_)
+					})
+					.collect();
+
+				Ok(EventVariantMetadata {
+					name: variant.ident.clone(),
+					args,
+					docs: helper::get_doc_literals(&variant.attrs),
+				})
+			})
+			.collect::<syn::Result<_>>()?;
+
+		Ok(EventDef {
+			index,
+			is_generic,
+			metadata,
+			deposit_event,
+		})
+	}
+}
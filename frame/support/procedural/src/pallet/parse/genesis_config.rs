@@ -0,0 +1,68 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::helper;
+use syn::spanned::Spanned;
+
+/// Definition for pallet genesis config type.
+///
+/// Either:
+/// * `struct GenesisConfig`
+/// * `struct GenesisConfig<T>`
+pub struct GenesisConfigDef {
+	/// The index of item in pallet module.
+	pub index: usize,
+	/// Whether the genesis config is generic over `T`.
+	pub is_generic: bool,
+	/// The types of the fields, used to bound the serde implementations.
+	pub field_types: Vec<syn::Type>,
+	/// The span of the pallet::genesis_config attribute.
+	pub attr_span: proc_macro2::Span,
+}
+
+impl GenesisConfigDef {
+	pub fn try_from(
+		attr_span: proc_macro2::Span,
+		index: usize,
+		item: &mut syn::Item,
+	) -> syn::Result<Self> {
+		let item = if let syn::Item::Struct(item) = item {
+			item
+		} else {
+			let msg = "Invalid pallet::genesis_config, expected struct definition";
+			return Err(syn::Error::new(item.span(), msg));
+		};
+
+		if !matches!(item.vis, syn::Visibility::Public(_)) {
+			let msg = "Invalid pallet::genesis_config, GenesisConfig must be public";
+			return Err(syn::Error::new(item.span(), msg));
+		}
+
+		if item.ident != "GenesisConfig" {
+			let msg = "Invalid pallet::genesis_config, expected struct named `GenesisConfig`";
+			return Err(syn::Error::new(item.ident.span(), msg));
+		}
+
+		let msg = "Invalid pallet::genesis_config, expected no generics or generic `<T>`";
+		let is_generic =
+			helper::check_type_generics(&item.generics, true, item.ident.span(), msg)?;
+
+		let field_types = item.fields.iter().map(|field| field.ty.clone()).collect();
+
+		Ok(GenesisConfigDef { index, is_generic, field_types, attr_span })
+	}
+}
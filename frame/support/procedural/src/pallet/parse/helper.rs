@@ -0,0 +1,178 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Helpers shared by the parsing of the pallet items.
+
+use quote::ToTokens;
+use syn::spanned::Spanned;
+
+/// List of additional token to be used for parsing.
+mod keyword {
+	syn::custom_keyword!(pallet);
+}
+
+/// Trait implemented by everything which can hold `#[pallet::*]` attributes.
+pub trait MutItemAttrs {
+	fn mut_item_attrs(&mut self) -> Option<&mut Vec<syn::Attribute>>;
+}
+
+impl MutItemAttrs for syn::Item {
+	fn mut_item_attrs(&mut self) -> Option<&mut Vec<syn::Attribute>> {
+		match self {
+			Self::Const(item) => Some(&mut item.attrs),
+			Self::Enum(item) => Some(&mut item.attrs),
+			Self::ExternCrate(item) => Some(&mut item.attrs),
+			Self::Fn(item) => Some(&mut item.attrs),
+			Self::ForeignMod(item) => Some(&mut item.attrs),
+			Self::Impl(item) => Some(&mut item.attrs),
+			Self::Macro(item) => Some(&mut item.attrs),
+			Self::Macro2(item) => Some(&mut item.attrs),
+			Self::Mod(item) => Some(&mut item.attrs),
+			Self::Static(item) => Some(&mut item.attrs),
+			Self::Struct(item) => Some(&mut item.attrs),
+			Self::Trait(item) => Some(&mut item.attrs),
+			Self::TraitAlias(item) => Some(&mut item.attrs),
+			Self::Type(item) => Some(&mut item.attrs),
+			Self::Union(item) => Some(&mut item.attrs),
+			Self::Use(item) => Some(&mut item.attrs),
+			_ => None,
+		}
+	}
+}
+
+impl MutItemAttrs for syn::TraitItem {
+	fn mut_item_attrs(&mut self) -> Option<&mut Vec<syn::Attribute>> {
+		match self {
+			Self::Const(item) => Some(&mut item.attrs),
+			Self::Method(item) => Some(&mut item.attrs),
+			Self::Type(item) => Some(&mut item.attrs),
+			Self::Macro(item) => Some(&mut item.attrs),
+			_ => None,
+		}
+	}
+}
+
+impl MutItemAttrs for Vec<syn::Attribute> {
+	fn mut_item_attrs(&mut self) -> Option<&mut Vec<syn::Attribute>> {
+		Some(self)
+	}
+}
+
+/// Return whether the attribute is a `#[pallet::*]` attribute.
+pub fn is_pallet_attr(attr: &syn::Attribute) -> bool {
+	attr.path.segments.first().map_or(false, |segment| segment.ident == "pallet")
+}
+
+/// Take the first `#[pallet::*]` attribute of the item, if any, and parse it as `Attr`.
+pub fn take_first_item_attr<Attr>(item: &mut impl MutItemAttrs) -> syn::Result<Option<Attr>> where
+	Attr: syn::parse::Parse,
+{
+	let attrs = if let Some(attrs) = item.mut_item_attrs() {
+		attrs
+	} else {
+		return Ok(None)
+	};
+
+	if let Some(index) = attrs.iter().position(is_pallet_attr) {
+		let attr = attrs.remove(index);
+		Ok(Some(syn::parse2(attr.into_token_stream())?))
+	} else {
+		Ok(None)
+	}
+}
+
+/// Take all the `#[pallet::*]` attributes of the item and parse them as `Attr`.
+pub fn take_item_attrs<Attr>(item: &mut impl MutItemAttrs) -> syn::Result<Vec<Attr>> where
+	Attr: syn::parse::Parse,
+{
+	let mut pallet_attrs = Vec::new();
+
+	while let Some(attr) = take_first_item_attr(item)? {
+		pallet_attrs.push(attr)
+	}
+
+	Ok(pallet_attrs)
+}
+
+/// Return the literals of all the `#[doc = "..."]` attributes.
+pub fn get_doc_literals(attrs: &[syn::Attribute]) -> Vec<syn::Lit> {
+	attrs.iter()
+		.filter_map(|attr| {
+			if let Ok(syn::Meta::NameValue(meta)) = attr.parse_meta() {
+				if meta.path.get_ident().map_or(false, |ident| ident == "doc") {
+					Some(meta.lit)
+				} else {
+					None
+				}
+			} else {
+				None
+			}
+		})
+		.collect()
+}
+
+/// Return whether the tokens contain the identifier `ident`, looking into the groups.
+pub fn contains_ident(tokens: proc_macro2::TokenStream, ident: &syn::Ident) -> bool {
+	tokens.into_iter().any(|token| match token {
+		proc_macro2::TokenTree::Ident(found) => &found == ident,
+		proc_macro2::TokenTree::Group(group) => contains_ident(group.stream(), ident),
+		_ => false,
+	})
+}
+
+/// Return the last segment of the type if it is a path type without qualified self.
+pub fn last_path_segment(ty: &syn::Type) -> Option<&syn::PathSegment> {
+	match ty {
+		syn::Type::Path(path) if path.qself.is_none() => path.path.segments.last(),
+		_ => None,
+	}
+}
+
+/// Check the generics are exactly `<T>`, possibly with some bounds on `T`.
+///
+/// Return whether the item is generic, an error is returned if `!allow_none` and the item has no
+/// generics. `span` is used for the error when the item has no generics.
+pub fn check_type_generics(
+	generics: &syn::Generics,
+	allow_none: bool,
+	span: proc_macro2::Span,
+	msg: &str,
+) -> syn::Result<bool> {
+	let params = generics.params.iter().collect::<Vec<_>>();
+	match params.as_slice() {
+		[] if allow_none && generics.where_clause.is_none() => Ok(false),
+		[syn::GenericParam::Type(param)]
+			if param.ident == "T" && generics.where_clause.is_none() => Ok(true),
+		_ => {
+			let span = if generics.params.is_empty() { span } else { generics.params.span() };
+			Err(syn::Error::new(span, msg))
+		},
+	}
+}
+
+/// Parse the start of a pallet attribute: `#[pallet::`, and return the content of the brackets
+/// positioned after `::`.
+pub fn parse_pallet_attr_start<'a>(
+	input: syn::parse::ParseStream<'a>,
+) -> syn::Result<syn::parse::ParseBuffer<'a>> {
+	input.parse::<syn::Token![#]>()?;
+	let content;
+	syn::bracketed!(content in input);
+	content.parse::<keyword::pallet>()?;
+	content.parse::<syn::Token![::]>()?;
+	Ok(content)
+}
@@ -0,0 +1,55 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::helper;
+use syn::spanned::Spanned;
+
+/// Implementation of the pallet hooks.
+pub struct HooksDef {
+	/// The ident of the type the hooks are implemented on, e.g. `Pallet`.
+	pub pallet: syn::Ident,
+}
+
+impl HooksDef {
+	pub fn try_from(item: &mut syn::Item) -> syn::Result<Self> {
+		let item = if let syn::Item::Impl(item) = item {
+			item
+		} else {
+			let msg = "Invalid pallet::hooks, expected item impl";
+			return Err(syn::Error::new(item.span(), msg));
+		};
+
+		let is_hooks_impl = item.trait_.as_ref()
+			.and_then(|(_, path, _)| path.segments.last())
+			.map_or(false, |segment| segment.ident == "Hooks");
+
+		if !is_hooks_impl {
+			let msg = "Invalid pallet::hooks, expected `impl<..> Hooks<BlockNumberFor<T>> for \
+				Pallet<T>`";
+			return Err(syn::Error::new(item.span(), msg));
+		}
+
+		let pallet = helper::last_path_segment(&item.self_ty)
+			.map(|segment| segment.ident.clone())
+			.ok_or_else(|| {
+				let msg = "Invalid pallet::hooks, expected implementation on `Pallet<T>`";
+				syn::Error::new(item.self_ty.span(), msg)
+			})?;
+
+		Ok(Self { pallet })
+	}
+}
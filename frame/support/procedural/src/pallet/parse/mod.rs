@@ -0,0 +1,263 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Parse for pallet macro.
+//!
+//! Parse the module into `Def` struct through `Def::try_from` function.
+//! The items annotated with `#[pallet::*]` are parsed into their own definition, their pallet
+//! attributes are removed from the module and the other items are left untouched.
+
+pub mod helper;
+pub mod config;
+pub mod pallet_struct;
+pub mod hooks;
+pub mod call;
+pub mod error;
+pub mod event;
+pub mod storage;
+pub mod genesis_config;
+pub mod genesis_build;
+
+use syn::spanned::Spanned;
+use proc_macro2::Span;
+
+/// Parsed definition of a pallet.
+pub struct Def {
+	/// The module items, pallet attributes are removed.
+	pub item: syn::ItemMod,
+	pub config: config::ConfigDef,
+	pub pallet_struct: pallet_struct::PalletStructDef,
+	pub hooks: hooks::HooksDef,
+	pub call: call::CallDef,
+	pub storages: Vec<storage::StorageDef>,
+	pub error: Option<error::ErrorDef>,
+	pub event: Option<event::EventDef>,
+	pub genesis_config: Option<genesis_config::GenesisConfigDef>,
+}
+
+impl Def {
+	pub fn try_from(mut item: syn::ItemMod) -> syn::Result<Self> {
+		let item_span = item.ident.span();
+		let items = &mut item.content.as_mut()
+			.ok_or_else(|| {
+				let msg = "Invalid pallet definition, expected mod to be inlined.";
+				syn::Error::new(item_span, msg)
+			})?.1;
+
+		let mut config = None;
+		let mut pallet_struct = None;
+		let mut hooks = None;
+		let mut call = None;
+		let mut error = None;
+		let mut event = None;
+		let mut storages = vec![];
+		let mut genesis_config = None;
+		let mut genesis_build = None;
+
+		for (index, item) in items.iter_mut().enumerate() {
+			let pallet_attr: Option<PalletAttr> = helper::take_first_item_attr(item)?;
+
+			match pallet_attr {
+				Some(PalletAttr::Config(_)) if config.is_none() =>
+					config = Some(config::ConfigDef::try_from(item)?),
+				Some(PalletAttr::Pallet(span)) if pallet_struct.is_none() =>
+					pallet_struct = Some(pallet_struct::PalletStructDef::try_from(span, index, item)?),
+				Some(PalletAttr::Hooks(_)) if hooks.is_none() =>
+					hooks = Some(hooks::HooksDef::try_from(item)?),
+				Some(PalletAttr::Call(_)) if call.is_none() =>
+					call = Some(call::CallDef::try_from(index, item)?),
+				Some(PalletAttr::Error(_)) if error.is_none() =>
+					error = Some(error::ErrorDef::try_from(index, item)?),
+				Some(PalletAttr::Event(_)) if event.is_none() =>
+					event = Some(event::EventDef::try_from(index, item)?),
+				Some(PalletAttr::Storage(_)) =>
+					storages.push(storage::StorageDef::try_from(index, item)?),
+				Some(PalletAttr::GenesisConfig(span)) if genesis_config.is_none() =>
+					genesis_config =
+						Some(genesis_config::GenesisConfigDef::try_from(span, index, item)?),
+				Some(PalletAttr::GenesisBuild(span)) if genesis_build.is_none() =>
+					genesis_build = Some(genesis_build::GenesisBuildDef::try_from(span, item)?),
+				Some(attr) => {
+					let msg = "Invalid duplicated attribute";
+					return Err(syn::Error::new(attr.span(), msg));
+				},
+				None => (),
+			}
+		}
+
+		if genesis_config.is_some() != genesis_build.is_some() {
+			let msg = "`#[pallet::genesis_config]` and `#[pallet::genesis_build]` attributes must be \
+				either both used or both not used";
+			let span = genesis_config.as_ref().map(|d: &genesis_config::GenesisConfigDef| d.attr_span)
+				.or_else(|| genesis_build.as_ref().map(|d: &genesis_build::GenesisBuildDef| d.attr_span))
+				.unwrap_or(item_span);
+			return Err(syn::Error::new(span, msg));
+		}
+
+		let missing = |attr: &str| {
+			let msg = format!("Missing `#[pallet::{}]`", attr);
+			syn::Error::new(item_span, msg)
+		};
+
+		let def = Def {
+			config: config.ok_or_else(|| missing("config"))?,
+			pallet_struct: pallet_struct.ok_or_else(|| missing("pallet"))?,
+			hooks: hooks.ok_or_else(|| missing("hooks"))?,
+			call: call.ok_or_else(|| missing("call"))?,
+			storages,
+			error,
+			event,
+			genesis_config,
+			item,
+		};
+
+		def.check_consistency()?;
+
+		Ok(def)
+	}
+
+	/// Check the definitions are consistent with each other.
+	fn check_consistency(&self) -> syn::Result<()> {
+		let pallet = &self.pallet_struct.pallet;
+
+		if self.call.pallet != *pallet {
+			let msg = format!("Invalid pallet::call, expected implementation on `{}<T>`", pallet);
+			return Err(syn::Error::new(self.call.pallet.span(), msg));
+		}
+
+		if self.hooks.pallet != *pallet {
+			let msg = format!("Invalid pallet::hooks, expected implementation on `{}<T>`", pallet);
+			return Err(syn::Error::new(self.hooks.pallet.span(), msg));
+		}
+
+		if !self.storages.is_empty() && self.pallet_struct.storage_prefix.is_none() {
+			let msg = "Invalid pallet::pallet, expected `#[pallet::storage_prefix($ident)]` as the \
+				pallet declares some storages";
+			return Err(syn::Error::new(self.pallet_struct.attr_span, msg));
+		}
+
+		if let Some(event) = &self.event {
+			if let Some(deposit_event) = &event.deposit_event {
+				if !self.config.has_event_type {
+					let msg = format!(
+						"Invalid pallet::event, `#[pallet::generate_deposit]` requires the \
+						associated type `Event` in trait `{}`",
+						self.config.trait_ident,
+					);
+					return Err(syn::Error::new(deposit_event.attr_span, msg));
+				}
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// List of additional token to be used for parsing.
+mod keyword {
+	syn::custom_keyword!(config);
+	syn::custom_keyword!(pallet);
+	syn::custom_keyword!(hooks);
+	syn::custom_keyword!(call);
+	syn::custom_keyword!(error);
+	syn::custom_keyword!(event);
+	syn::custom_keyword!(storage);
+	syn::custom_keyword!(genesis_config);
+	syn::custom_keyword!(genesis_build);
+}
+
+/// Attributes which are not supported by this version of the pallet macro, pallets requiring
+/// them must be declared using `decl_module!` and co.
+const UNSUPPORTED_ATTRS: &[&str] = &[
+	"inherent",
+	"validate_unsigned",
+	"origin",
+	"type_value",
+	"extra_constants",
+];
+
+/// Parse attributes for item in pallet module
+/// syntax must be `pallet::` (e.g. `#[pallet::config]`)
+enum PalletAttr {
+	Config(Span),
+	Pallet(Span),
+	Hooks(Span),
+	Call(Span),
+	Error(Span),
+	Event(Span),
+	Storage(Span),
+	GenesisConfig(Span),
+	GenesisBuild(Span),
+}
+
+impl PalletAttr {
+	fn span(&self) -> Span {
+		match self {
+			Self::Config(span) => *span,
+			Self::Pallet(span) => *span,
+			Self::Hooks(span) => *span,
+			Self::Call(span) => *span,
+			Self::Error(span) => *span,
+			Self::Event(span) => *span,
+			Self::Storage(span) => *span,
+			Self::GenesisConfig(span) => *span,
+			Self::GenesisBuild(span) => *span,
+		}
+	}
+}
+
+impl syn::parse::Parse for PalletAttr {
+	fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+		let content = helper::parse_pallet_attr_start(input)?;
+
+		let lookahead = content.lookahead1();
+		if lookahead.peek(keyword::config) {
+			Ok(PalletAttr::Config(content.parse::<keyword::config>()?.span()))
+		} else if lookahead.peek(keyword::pallet) {
+			Ok(PalletAttr::Pallet(content.parse::<keyword::pallet>()?.span()))
+		} else if lookahead.peek(keyword::hooks) {
+			Ok(PalletAttr::Hooks(content.parse::<keyword::hooks>()?.span()))
+		} else if lookahead.peek(keyword::call) {
+			Ok(PalletAttr::Call(content.parse::<keyword::call>()?.span()))
+		} else if lookahead.peek(keyword::error) {
+			Ok(PalletAttr::Error(content.parse::<keyword::error>()?.span()))
+		} else if lookahead.peek(keyword::event) {
+			Ok(PalletAttr::Event(content.parse::<keyword::event>()?.span()))
+		} else if lookahead.peek(keyword::storage) {
+			Ok(PalletAttr::Storage(content.parse::<keyword::storage>()?.span()))
+		} else if lookahead.peek(keyword::genesis_config) {
+			Ok(PalletAttr::GenesisConfig(content.parse::<keyword::genesis_config>()?.span()))
+		} else if lookahead.peek(keyword::genesis_build) {
+			Ok(PalletAttr::GenesisBuild(content.parse::<keyword::genesis_build>()?.span()))
+		} else {
+			let unsupported = content.fork().parse::<syn::Ident>().ok()
+				.filter(|ident| UNSUPPORTED_ATTRS.iter().any(|attr| ident == attr));
+
+			match unsupported {
+				Some(ident) => {
+					let msg = format!(
+						"`pallet::{}` is not supported by this version of the pallet macro, pallets \
+						requiring it must be declared using `decl_module!`",
+						ident,
+					);
+					Err(syn::Error::new(ident.span(), msg))
+				},
+				None => Err(lookahead.error()),
+			}
+		}
+	}
+}
@@ -0,0 +1,143 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::helper;
+use syn::spanned::Spanned;
+
+/// List of additional token to be used for parsing.
+mod keyword {
+	syn::custom_keyword!(generate_store);
+	syn::custom_keyword!(storage_prefix);
+	syn::custom_keyword!(Store);
+}
+
+/// Definition of the pallet pallet.
+pub struct PalletStructDef {
+	/// The index of item in pallet module.
+	pub index: usize,
+	/// The pallet struct ident, e.g. `Pallet`.
+	pub pallet: syn::Ident,
+	/// The attributes of the struct other than pallet attributes, e.g. the docs.
+	pub attrs: Vec<syn::Attribute>,
+	/// Whether the trait `Store` must be generated, with its visibility.
+	pub store: Option<syn::Visibility>,
+	/// The prefix used for the storages of the pallet.
+	pub storage_prefix: Option<syn::Ident>,
+	/// The span of the pallet::pallet attribute.
+	pub attr_span: proc_macro2::Span,
+}
+
+/// Parse for one variant of:
+/// * `#[pallet::generate_store($vis trait Store)]`
+/// * `#[pallet::storage_prefix($ident)]`
+pub enum PalletStructAttr {
+	GenerateStore {
+		span: proc_macro2::Span,
+		vis: syn::Visibility,
+	},
+	StoragePrefix {
+		span: proc_macro2::Span,
+		prefix: syn::Ident,
+	},
+}
+
+impl PalletStructAttr {
+	fn span(&self) -> proc_macro2::Span {
+		match self {
+			Self::GenerateStore { span, .. } => *span,
+			Self::StoragePrefix { span, .. } => *span,
+		}
+	}
+}
+
+impl syn::parse::Parse for PalletStructAttr {
+	fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+		let content = helper::parse_pallet_attr_start(input)?;
+
+		let lookahead = content.lookahead1();
+		if lookahead.peek(keyword::generate_store) {
+			let span = content.parse::<keyword::generate_store>()?.span();
+
+			let generate_content;
+			syn::parenthesized!(generate_content in content);
+			let vis = generate_content.parse::<syn::Visibility>()?;
+			generate_content.parse::<syn::Token![trait]>()?;
+			generate_content.parse::<keyword::Store>()?;
+			Ok(Self::GenerateStore { span, vis })
+		} else if lookahead.peek(keyword::storage_prefix) {
+			let span = content.parse::<keyword::storage_prefix>()?.span();
+
+			let prefix_content;
+			syn::parenthesized!(prefix_content in content);
+			let prefix = prefix_content.parse::<syn::Ident>()?;
+			Ok(Self::StoragePrefix { span, prefix })
+		} else {
+			Err(lookahead.error())
+		}
+	}
+}
+
+impl PalletStructDef {
+	pub fn try_from(
+		attr_span: proc_macro2::Span,
+		index: usize,
+		item: &mut syn::Item,
+	) -> syn::Result<Self> {
+		let item = if let syn::Item::Struct(item) = item {
+			item
+		} else {
+			let msg = "Invalid pallet::pallet, expected struct definition";
+			return Err(syn::Error::new(item.span(), msg));
+		};
+
+		let mut store = None;
+		let mut storage_prefix = None;
+
+		let struct_attrs: Vec<PalletStructAttr> = helper::take_item_attrs(&mut item.attrs)?;
+		for attr in struct_attrs {
+			match attr {
+				PalletStructAttr::GenerateStore { vis, .. } if store.is_none() => {
+					store = Some(vis);
+				},
+				PalletStructAttr::StoragePrefix { prefix, .. } if storage_prefix.is_none() => {
+					storage_prefix = Some(prefix);
+				},
+				attr => {
+					let msg = "Unexpected duplicated attribute";
+					return Err(syn::Error::new(attr.span(), msg));
+				},
+			}
+		}
+
+		if !matches!(item.vis, syn::Visibility::Public(_)) {
+			let msg = "Invalid pallet::pallet, Pallet must be public";
+			return Err(syn::Error::new(item.span(), msg));
+		}
+
+		let msg = "Invalid pallet::pallet, expected generic `<T>`";
+		helper::check_type_generics(&item.generics, false, item.ident.span(), msg)?;
+
+		Ok(Self {
+			index,
+			pallet: item.ident.clone(),
+			attrs: item.attrs.clone(),
+			store,
+			storage_prefix,
+			attr_span,
+		})
+	}
+}
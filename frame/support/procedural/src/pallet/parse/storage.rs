@@ -0,0 +1,265 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::helper;
+use syn::spanned::Spanned;
+use quote::ToTokens;
+
+/// List of additional token to be used for parsing.
+mod keyword {
+	syn::custom_keyword!(getter);
+}
+
+/// Parse for `#[pallet::getter(fn dummy)]`
+pub struct PalletStorageAttr {
+	getter: syn::Ident,
+}
+
+impl syn::parse::Parse for PalletStorageAttr {
+	fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+		let content = helper::parse_pallet_attr_start(input)?;
+		content.parse::<keyword::getter>()?;
+
+		let generate_content;
+		syn::parenthesized!(generate_content in content);
+		generate_content.parse::<syn::Token![fn]>()?;
+		Ok(Self { getter: generate_content.parse::<syn::Ident>()? })
+	}
+}
+
+/// The kind of the storage with its key and value types. Hashers are the `decl_storage` hasher
+/// keywords, e.g. `blake2_128_concat` for `Blake2_128Concat`.
+pub enum StorageKind {
+	Value {
+		value: syn::Type,
+	},
+	Map {
+		hasher: syn::Ident,
+		key: syn::Type,
+		value: syn::Type,
+	},
+	DoubleMap {
+		hasher1: syn::Ident,
+		key1: syn::Type,
+		hasher2: syn::Ident,
+		key2: syn::Type,
+		value: syn::Type,
+	},
+}
+
+/// The kind of query of the storage.
+pub enum QueryKind {
+	/// `OptionQuery`, `get` returns `Option<Value>`, this is the default.
+	OptionQuery,
+	/// `ValueQuery`, `get` returns `Value`, i.e. the default value if none is stored.
+	ValueQuery,
+}
+
+/// Definition of a storage, storage is a type alias of `StorageValue`, `StorageMap` or
+/// `StorageDoubleMap`.
+pub struct StorageDef {
+	/// The index of storage item in pallet module.
+	pub index: usize,
+	/// Visibility of the storage type.
+	pub vis: syn::Visibility,
+	/// The storage ident.
+	pub ident: syn::Ident,
+	/// The attributes of the type alias other than pallet attributes, e.g. the docs.
+	pub attrs: Vec<syn::Attribute>,
+	/// The getter function if any.
+	pub getter: Option<syn::Ident>,
+	/// The kind of the storage with its keys and value.
+	pub kind: StorageKind,
+	/// The query kind.
+	pub query_kind: QueryKind,
+}
+
+/// Return the `decl_storage` hasher keyword for the hasher type.
+fn hasher_keyword(hasher: &syn::Type) -> syn::Result<syn::Ident> {
+	let keyword = match helper::last_path_segment(hasher) {
+		Some(segment) if segment.arguments.is_empty() => match segment.ident.to_string().as_str() {
+			"Blake2_128Concat" => Some("blake2_128_concat"),
+			"Twox64Concat" => Some("twox_64_concat"),
+			"Identity" => Some("identity"),
+			"Blake2_128" => Some("opaque_blake2_128"),
+			"Blake2_256" => Some("opaque_blake2_256"),
+			"Twox128" => Some("opaque_twox_128"),
+			"Twox256" => Some("opaque_twox_256"),
+			_ => None,
+		},
+		_ => None,
+	};
+
+	keyword
+		.map(|keyword| syn::Ident::new(keyword, hasher.span()))
+		.ok_or_else(|| {
+			let msg = format!(
+				"Invalid pallet::storage, expected hasher, i.e. one of `Blake2_128Concat`, \
+				`Twox64Concat`, `Identity`, `Blake2_128`, `Blake2_256`, `Twox128` or `Twox256`, \
+				found `{}`",
+				hasher.to_token_stream(),
+			);
+			syn::Error::new(hasher.span(), msg)
+		})
+}
+
+/// Return the query kind of the optional last generic argument.
+fn query_kind(query: Option<&syn::Type>) -> syn::Result<QueryKind> {
+	let query = if let Some(query) = query {
+		query
+	} else {
+		return Ok(QueryKind::OptionQuery)
+	};
+
+	match helper::last_path_segment(query) {
+		Some(segment) if segment.ident == "OptionQuery" => Ok(QueryKind::OptionQuery),
+		Some(segment) if segment.ident == "ValueQuery" => Ok(QueryKind::ValueQuery),
+		_ => {
+			let msg = "Invalid pallet::storage, expected `OptionQuery` or `ValueQuery` as last \
+				generic argument";
+			Err(syn::Error::new(query.span(), msg))
+		},
+	}
+}
+
+/// Parse the storage type, e.g. `StorageMap<_, Blake2_128Concat, u32, u32, ValueQuery>`.
+fn parse_storage_type(ty: &syn::Type) -> syn::Result<(StorageKind, QueryKind)> {
+	let expected_msg = || format!(
+		"Invalid pallet::storage, expected ident: `StorageValue` or `StorageMap` or \
+		`StorageDoubleMap` in order to expand metadata, found `{}`",
+		ty.to_token_stream(),
+	);
+
+	let segment = helper::last_path_segment(ty)
+		.ok_or_else(|| syn::Error::new(ty.span(), expected_msg()))?;
+
+	let (name, expected_len) = match segment.ident.to_string().as_str() {
+		"StorageValue" => ("StorageValue", 2),
+		"StorageMap" => ("StorageMap", 4),
+		"StorageDoubleMap" => ("StorageDoubleMap", 6),
+		_ => return Err(syn::Error::new(segment.ident.span(), expected_msg())),
+	};
+
+	let args = match &segment.arguments {
+		syn::PathArguments::AngleBracketed(args) => args,
+		_ => {
+			let msg = format!("Invalid pallet::storage, expected generic arguments for `{}`", name);
+			return Err(syn::Error::new(segment.span(), msg));
+		},
+	};
+
+	let types = args.args.iter()
+		.map(|arg| match arg {
+			syn::GenericArgument::Type(ty) => Ok(ty),
+			_ => {
+				let msg = "Invalid pallet::storage, expected type generic argument";
+				Err(syn::Error::new(arg.span(), msg))
+			},
+		})
+		.collect::<syn::Result<Vec<_>>>()?;
+
+	if types.len() != expected_len && types.len() != expected_len + 1 {
+		let msg = format!(
+			"Invalid pallet::storage, unexpected number of generic argument, expected {} or {} \
+			generic arguments for `{}`, found {}",
+			expected_len,
+			expected_len + 1,
+			name,
+			types.len(),
+		);
+		return Err(syn::Error::new(args.span(), msg));
+	}
+
+	if !matches!(types[0], syn::Type::Infer(_)) {
+		let msg = "Invalid pallet::storage, the first generic argument must be `_`, the prefix \
+			is generated by the macro";
+		return Err(syn::Error::new(types[0].span(), msg));
+	}
+
+	let kind = match expected_len {
+		2 => StorageKind::Value { value: types[1].clone() },
+		4 => StorageKind::Map {
+			hasher: hasher_keyword(types[1])?,
+			key: types[2].clone(),
+			value: types[3].clone(),
+		},
+		6 => StorageKind::DoubleMap {
+			hasher1: hasher_keyword(types[1])?,
+			key1: types[2].clone(),
+			hasher2: hasher_keyword(types[3])?,
+			key2: types[4].clone(),
+			value: types[5].clone(),
+		},
+		_ => unreachable!("expected_len is one of the values above; qed"),
+	};
+
+	Ok((kind, query_kind(types.get(expected_len).copied())?))
+}
+
+impl StorageDef {
+	pub fn try_from(index: usize, item: &mut syn::Item) -> syn::Result<Self> {
+		let item = if let syn::Item::Type(item) = item {
+			item
+		} else {
+			return Err(syn::Error::new(item.span(), "Invalid pallet::storage, expected item type"));
+		};
+
+		let mut attrs: Vec<PalletStorageAttr> = helper::take_item_attrs(&mut item.attrs)?;
+		if attrs.len() > 1 {
+			let msg = "Invalid pallet::storage, multiple argument pallet::getter found";
+			return Err(syn::Error::new(item.ident.span(), msg));
+		}
+		let getter = attrs.pop().map(|attr| attr.getter);
+
+		let msg = "Invalid pallet::storage, expected no generics or generic `<T>`";
+		let is_generic =
+			helper::check_type_generics(&item.generics, true, item.ident.span(), msg)?;
+
+		let (kind, query_kind) = parse_storage_type(&item.ty)?;
+
+		let uses_t = {
+			let t = syn::Ident::new("T", proc_macro2::Span::call_site());
+			match &kind {
+				StorageKind::Value { value } => helper::contains_ident(value.to_token_stream(), &t),
+				StorageKind::Map { key, value, .. } =>
+					helper::contains_ident(quote::quote!(#key #value), &t),
+				StorageKind::DoubleMap { key1, key2, value, .. } =>
+					helper::contains_ident(quote::quote!(#key1 #key2 #value), &t),
+			}
+		};
+
+		if uses_t && !is_generic {
+			let msg = "Invalid pallet::storage, the storage uses `T`, expected generic `<T>`";
+			return Err(syn::Error::new(item.ident.span(), msg));
+		}
+
+		if !uses_t && is_generic {
+			let msg = "Invalid pallet::storage, the storage doesn't use `T`, expected no generics";
+			return Err(syn::Error::new(item.generics.span(), msg));
+		}
+
+		Ok(StorageDef {
+			index,
+			vis: item.vis.clone(),
+			ident: item.ident.clone(),
+			attrs: item.attrs.clone(),
+			getter,
+			kind,
+			query_kind,
+		})
+	}
+}
@@ -81,7 +81,7 @@ pub use self::dispatch::{Parameter, Callable, IsSubType};
 pub use sp_runtime::{self, ConsensusEngineId, print, traits::Printable};
 
 /// A type that cannot be instantiated.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Never {}
 
 /// Create new implementations of the [`Get`](crate::traits::Get) trait.
@@ -267,7 +267,10 @@ macro_rules! ord_parameter_types {
 }
 
 #[doc(inline)]
-pub use frame_support_procedural::{decl_storage, construct_runtime, transactional};
+pub use frame_support_procedural::{
+	decl_storage, construct_runtime, transactional, pallet, CloneNoBound, DebugNoBound,
+	PartialEqNoBound, EqNoBound,
+};
 
 /// Return Err of the expression: `return Err($expression);`.
 ///
@@ -368,6 +371,23 @@ macro_rules! assert_ok {
 #[doc(hidden)]
 pub use serde::{Serialize, Deserialize};
 
+/// Prelude to be used alongside pallet macro, for ease of use.
+pub mod pallet_prelude {
+	pub use sp_std::marker::PhantomData;
+	pub use frame_support::traits::{Get, Hooks, IsType, GenesisBuild};
+	pub use frame_support::dispatch::{
+		DispatchResultWithPostInfo, DispatchResult, DispatchError, Parameter,
+	};
+	pub use frame_support::weights::{Weight, DispatchClass, Pays};
+	pub use frame_support::storage::{StorageValue, StorageMap, StorageDoubleMap};
+	pub use frame_support::{
+		Blake2_128, Blake2_256, Blake2_128Concat, Twox128, Twox256, Twox64Concat, Identity,
+	};
+	pub use frame_support::{RuntimeDebug, ensure, transactional};
+	pub use codec::{Encode, Decode};
+	pub use sp_runtime::traits::{MaybeSerializeDeserialize, Member};
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
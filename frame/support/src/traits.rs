@@ -1494,6 +1494,112 @@ pub trait OffchainWorker<BlockNumber> {
 	fn offchain_worker(_n: BlockNumber) {}
 }
 
+/// The pallet hooks trait, implemented by pallets declared with `#[frame_support::pallet]`.
+///
+/// Each function is called by the implementation of the respective trait on the pallet:
+/// `OnInitialize`, `OnFinalize`, `OnRuntimeUpgrade`, `OffchainWorker` and `IntegrityTest`.
+pub trait Hooks<BlockNumber> {
+	/// The block is being finalized. Implement to have something happen.
+	fn on_finalize(_n: BlockNumber) {}
+
+	/// The block is being initialized. Implement to have something happen.
+	///
+	/// Return the non-negotiable weight consumed in the block.
+	fn on_initialize(_n: BlockNumber) -> crate::weights::Weight { 0 }
+
+	/// Perform a module upgrade.
+	///
+	/// See [`OnRuntimeUpgrade::on_runtime_upgrade`].
+	///
+	/// Return the non-negotiable weight consumed for runtime upgrade.
+	fn on_runtime_upgrade() -> crate::weights::Weight { 0 }
+
+	/// Implementing this function on a module allows you to perform long-running tasks
+	/// that make (by default) validators generate transactions that feed results
+	/// of those long-running computations back on chain.
+	///
+	/// See [`OffchainWorker::offchain_worker`].
+	fn offchain_worker(_n: BlockNumber) {}
+
+	/// Run integrity test.
+	///
+	/// The test is not executed in a externalities provided environment.
+	fn integrity_test() {}
+}
+
+/// The genesis build of a pallet declared with `#[frame_support::pallet]`, implemented on its
+/// genesis config. `T` is the pallet trait.
+pub trait GenesisBuild<T>: Default + MaybeSerializeDeserialize {
+	/// The build function is called within an externalities allowing storage APIs.
+	/// Thus one can write to storage using regular pallet storages.
+	fn build(&self);
+
+	/// Build the storage using `build` inside default storage.
+	#[cfg(feature = "std")]
+	fn build_storage(&self) -> Result<sp_runtime::Storage, String> {
+		let mut storage = Default::default();
+		self.assimilate_storage(&mut storage)?;
+		Ok(storage)
+	}
+
+	/// Assimilate the storage for this module into pre-existing overlays.
+	#[cfg(feature = "std")]
+	fn assimilate_storage(&self, storage: &mut sp_runtime::Storage) -> Result<(), String> {
+		crate::BasicExternalities::execute_with_storage(storage, || {
+			self.build();
+			Ok(())
+		})
+	}
+}
+
+/// A interface for looking up preimages from their hash on chain.
+pub trait PreimageProvider<Hash> {
+	/// Returns whether a preimage exists for a given hash.
+	///
+	/// A value of `true` implies that `get_preimage` is `Some`.
+	fn have_preimage(hash: &Hash) -> bool;
+
+	/// Returns the preimage for a given hash.
+	fn get_preimage(hash: &Hash) -> Option<Vec<u8>>;
+
+	/// Returns whether a preimage request exists for a given hash.
+	fn preimage_requested(hash: &Hash) -> bool;
+
+	/// Request that someone report a preimage. Providers use this to optimise the economics for
+	/// preimage reporting.
+	fn request_preimage(hash: &Hash);
+
+	/// Cancel a previous preimage request.
+	fn unrequest_preimage(hash: &Hash);
+}
+
+/// A provider which knows of no preimages and ignores all requests.
+impl<Hash> PreimageProvider<Hash> for () {
+	fn have_preimage(_: &Hash) -> bool { false }
+	fn get_preimage(_: &Hash) -> Option<Vec<u8>> { None }
+	fn preimage_requested(_: &Hash) -> bool { false }
+	fn request_preimage(_: &Hash) {}
+	fn unrequest_preimage(_: &Hash) {}
+}
+
+/// A interface for managing preimages to hashes on chain.
+///
+/// Note that this API does not assume any underlying user is calling, and thus
+/// does not handle any preimage ownership or fees. Other system level logic that
+/// uses this API should implement that on their own side.
+pub trait PreimageRecipient<Hash>: PreimageProvider<Hash> {
+	/// Maximum size of a preimage.
+	type MaxSize: Get<u32>;
+
+	/// Store the bytes of a preimage on chain. Bytes longer than `MaxSize` are ignored.
+	fn note_preimage(bytes: Vec<u8>);
+
+	/// Clear a previously noted preimage. This is infallible and should be treated more like a
+	/// hint - if it was not previously noted or if it is now requested, then this will not do
+	/// anything.
+	fn unnote_preimage(hash: &Hash);
+}
+
 pub mod schedule {
 	use super::*;
 
@@ -1524,6 +1630,69 @@ pub mod schedule {
 	/// The lowest priority. Most stuff should be around here.
 	pub const LOWEST_PRIORITY: Priority = 255;
 
+	/// Either a value of type `T` or the hash of its encoding, which may be resolved into the value
+	/// through a `PreimageProvider`.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+	pub enum MaybeHashed<T, Hash> {
+		/// The value itself.
+		Value(T),
+		/// The hash of the encoded value.
+		Hash(Hash),
+	}
+
+	impl<T, H> From<T> for MaybeHashed<T, H> {
+		fn from(t: T) -> Self {
+			MaybeHashed::Value(t)
+		}
+	}
+
+	/// The reason a hashed value could not be resolved.
+	#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+	pub enum LookupError {
+		/// The preimage is not known.
+		Unknown,
+		/// The preimage could not be decoded into the expected type.
+		BadFormat,
+	}
+
+	impl<T: Decode, H> MaybeHashed<T, H> {
+		/// Returns the value, if this is not a hash.
+		pub fn as_value(&self) -> Option<&T> {
+			match &self {
+				Self::Value(c) => Some(c),
+				Self::Hash(_) => None,
+			}
+		}
+
+		/// Returns the hash, if this is not a value.
+		pub fn as_hash(&self) -> Option<&H> {
+			match &self {
+				Self::Value(_) => None,
+				Self::Hash(h) => Some(h),
+			}
+		}
+
+		/// Attempt to turn a hash into its value using `P`.
+		///
+		/// Returns the resolved value together with the hash it came from, if a hash was resolved.
+		/// Values are returned untouched.
+		pub fn resolved<P: PreimageProvider<H>>(self) -> (Self, Option<H>) {
+			match self {
+				Self::Value(c) => (Self::Value(c), None),
+				Self::Hash(h) => {
+					let data = match P::get_preimage(&h) {
+						Some(p) => p,
+						None => return (Self::Hash(h), None),
+					};
+					match T::decode(&mut &data[..]) {
+						Ok(c) => (Self::Value(c), Some(h)),
+						Err(_) => (Self::Hash(h), None),
+					}
+				}
+			}
+		}
+	}
+
 	/// A type that can be used as a scheduler.
 	pub trait Anon<BlockNumber, Call, Origin> {
 		/// An address which can be used for removing a scheduled task.
@@ -1581,6 +1750,110 @@ pub mod schedule {
 		/// If it ends up being delayed beyond the point of execution, then it cannot be cancelled.
 		fn cancel_named(id: Vec<u8>) -> Result<(), ()>;
 	}
+
+	/// A scheduler that never accepts any task.
+	impl<BlockNumber, Call, Origin> Named<BlockNumber, Call, Origin> for () {
+		type Address = ();
+
+		fn schedule_named(
+			_id: Vec<u8>,
+			_when: DispatchTime<BlockNumber>,
+			_maybe_periodic: Option<Period<BlockNumber>>,
+			_priority: Priority,
+			_origin: Origin,
+			_call: Call,
+		) -> Result<Self::Address, ()> {
+			Err(())
+		}
+
+		fn cancel_named(_id: Vec<u8>) -> Result<(), ()> {
+			Err(())
+		}
+	}
+}
+
+pub mod voting {
+	use super::*;
+	use sp_runtime::Perbill;
+
+	/// A tally of the votes on a poll.
+	pub trait VoteTally<Votes> {
+		/// The amount of aye votes, post-conviction.
+		fn ayes(&self) -> Votes;
+
+		/// The proportion of `total` which has been placed in support of the poll, pre-conviction.
+		fn support(&self, total: Votes) -> Perbill;
+
+		/// The proportion of aye votes among all aye and nay votes, post-conviction.
+		fn approval(&self) -> Perbill;
+	}
+
+	/// The status of a poll, as seen by a voting pallet.
+	#[derive(Clone, PartialEq, Eq, RuntimeDebug)]
+	pub enum PollStatus<Tally, Moment, Class> {
+		/// There is no such poll.
+		None,
+		/// The poll is ongoing; its tally may be altered, and it belongs to the given class.
+		Ongoing(Tally, Class),
+		/// The poll was completed at the given moment, and was approved or not.
+		Completed(Moment, bool),
+	}
+
+	impl<Tally, Moment, Class> PollStatus<Tally, Moment, Class> {
+		/// Returns the tally and class if the poll is ongoing.
+		pub fn ensure_ongoing(self) -> Option<(Tally, Class)> {
+			match self {
+				PollStatus::Ongoing(tally, class) => Some((tally, class)),
+				_ => None,
+			}
+		}
+	}
+
+	/// Something which provides polls that may be voted on, each of them belonging to a class.
+	///
+	/// Voting pallets use this to alter the tally of a poll; the polling pallet decides what the
+	/// tally means.
+	pub trait Polling<Tally> {
+		/// The index of a poll.
+		type Index: Parameter + Ord + Copy;
+		/// The type in which votes are expressed.
+		type Votes: Parameter + Ord + Copy;
+		/// The class of a poll. Voting may be done, and delegated, separately per class.
+		type Class: Parameter + Ord;
+		/// The type used to express the moment at which a poll completed.
+		type Moment;
+
+		/// Provides a vector of the known classes.
+		fn classes() -> Vec<Self::Class>;
+
+		/// Returns the tally and class of the poll `index` if it is ongoing.
+		fn as_ongoing(index: Self::Index) -> Option<(Tally, Self::Class)>;
+
+		/// Access the status of the poll `index`, with the ability to alter its tally if it is
+		/// ongoing.
+		fn access_poll<R>(
+			index: Self::Index,
+			f: impl FnOnce(PollStatus<&mut Tally, Self::Moment, Self::Class>) -> R,
+		) -> R;
+
+		/// Same as `access_poll`, but the tally is only altered if `f` returns `Ok`.
+		fn try_access_poll<R>(
+			index: Self::Index,
+			f: impl FnOnce(PollStatus<&mut Tally, Self::Moment, Self::Class>) -> Result<R, DispatchError>,
+		) -> Result<R, DispatchError>;
+
+		/// Create an ongoing poll of the given class, with an empty tally.
+		///
+		/// ** Should be used for benchmarking only!!! **
+		#[cfg(feature = "runtime-benchmarks")]
+		fn create_ongoing(class: Self::Class) -> Result<Self::Index, ()>;
+
+		/// Complete the ongoing poll `index`, as approved or not.
+		///
+		/// ** Should be used for benchmarking only!!! **
+		#[cfg(feature = "runtime-benchmarks")]
+		fn end_ongoing(index: Self::Index, approved: bool) -> Result<(), ()>;
+	}
 }
 
 /// Some sort of check on the origin is performed by this object.
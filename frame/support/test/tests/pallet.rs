@@ -0,0 +1,417 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! General tests for the `#[frame_support::pallet]` attribute macro, test for:
+//! * calls, events, errors, storages, hooks and genesis config are usable in `construct_runtime!`
+//! * the metadata is the same as the metadata of the equivalent pallet declared with the
+//!   `decl_*` macros
+
+use frame_support::{
+	weights::{DispatchInfo, DispatchClass, Pays, GetDispatchInfo},
+	traits::{OnInitialize, OnFinalize, OnRuntimeUpgrade, GetCallName},
+	dispatch::UnfilteredDispatchable,
+	StorageValue, StorageMap, StorageDoubleMap,
+};
+use sp_runtime::{DispatchError, BuildStorage};
+use frame_system::{EventRecord, Phase};
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::config]
+	pub trait Trait: frame_system::Trait {
+		/// Some comment
+		/// Some comment
+		#[pallet::constant]
+		type MyGetParam: Get<u32>;
+		type Balance: Parameter + Default;
+		type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(crate) trait Store)]
+	#[pallet::storage_prefix(Example)]
+	pub struct Pallet<T>(PhantomData<T>);
+
+	#[pallet::hooks]
+	impl<T: Trait> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(_: BlockNumberFor<T>) -> Weight {
+			Self::deposit_event(Event::Something(10));
+			10
+		}
+		fn on_finalize(_: BlockNumberFor<T>) {
+			Self::deposit_event(Event::Something(20));
+		}
+		fn on_runtime_upgrade() -> Weight {
+			Self::deposit_event(Event::Something(30));
+			30
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Trait> Pallet<T> {
+		/// Doc comment put in metadata
+		#[pallet::weight(1)]
+		pub fn foo(
+			origin: OriginFor<T>,
+			#[pallet::compact] foo: u32,
+		) -> DispatchResultWithPostInfo {
+			let _ = origin;
+			Value::put(foo);
+			Self::deposit_event(Event::Something(foo));
+			Ok(().into())
+		}
+
+		/// Doc comment put in metadata
+		#[pallet::weight(3)]
+		pub fn propose(origin: OriginFor<T>, balance: T::Balance) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			ensure!(balance != Default::default(), Error::<T>::InsufficientProposersBalance);
+			Self::deposit_event(Event::Proposed(who, balance));
+			Ok(().into())
+		}
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// doc comment put into metadata
+		InsufficientProposersBalance,
+	}
+
+	#[pallet::event]
+	#[pallet::metadata(T::AccountId = "AccountId", T::Balance = "Balance")]
+	#[pallet::generate_deposit(fn deposit_event)]
+	pub enum Event<T: Trait> {
+		/// doc comment put in metadata
+		Proposed(T::AccountId, T::Balance),
+		/// doc
+		Something(u32),
+	}
+
+	#[pallet::storage]
+	pub type Value = StorageValue<_, u32>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn map)]
+	pub type Map = StorageMap<_, Blake2_128Concat, u8, u16, ValueQuery>;
+
+	#[pallet::storage]
+	pub type DoubleMap<T: Trait> =
+		StorageDoubleMap<_, Blake2_128Concat, u8, Twox64Concat, T::AccountId, u32>;
+
+	#[pallet::genesis_config]
+	#[derive(Default)]
+	pub struct GenesisConfig {
+		pub value: u32,
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Trait> GenesisBuild<T> for GenesisConfig {
+		fn build(&self) {
+			Value::put(self.value);
+		}
+	}
+}
+
+/// The same pallet declared with the `decl_*` macros, used to check the metadata.
+pub mod pallet_old {
+	use frame_support::{
+		decl_module, decl_storage, decl_error, decl_event, ensure, weights::Weight, traits::Get,
+		Parameter,
+	};
+	use frame_system::ensure_signed;
+
+	pub trait Trait: frame_system::Trait {
+		type MyGetParam: Get<u32>;
+		type Balance: Parameter + Default;
+		type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+	}
+
+	decl_storage! {
+		trait Store for Module<T: Trait> as Example {
+			pub Value: Option<u32>;
+			pub Map get(fn map): map hasher(blake2_128_concat) u8 => u16;
+			pub DoubleMap: double_map
+				hasher(blake2_128_concat) u8, hasher(twox_64_concat) T::AccountId => Option<u32>;
+		}
+	}
+
+	decl_event!(
+		pub enum Event<T> where
+			<T as frame_system::Trait>::AccountId,
+			<T as Trait>::Balance,
+		{
+			/// doc comment put in metadata
+			Proposed(AccountId, Balance),
+			/// doc
+			Something(u32),
+		}
+	);
+
+	decl_module! {
+		pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+			type Error = Error<T>;
+
+			/// Some comment
+			/// Some comment
+			const MyGetParam: u32 = T::MyGetParam::get();
+
+			fn deposit_event() = default;
+
+			fn on_initialize(_n: T::BlockNumber) -> Weight {
+				Self::deposit_event(RawEvent::Something(10));
+				10
+			}
+
+			fn on_finalize(_n: T::BlockNumber) {
+				Self::deposit_event(RawEvent::Something(20));
+			}
+
+			fn on_runtime_upgrade() -> Weight {
+				Self::deposit_event(RawEvent::Something(30));
+				30
+			}
+
+			/// Doc comment put in metadata
+			#[weight = 1]
+			pub fn foo(origin, #[compact] foo: u32) {
+				let _ = origin;
+				Value::put(foo);
+				Self::deposit_event(RawEvent::Something(foo));
+			}
+
+			/// Doc comment put in metadata
+			#[weight = 3]
+			pub fn propose(origin, balance: T::Balance) {
+				let who = ensure_signed(origin)?;
+				ensure!(balance != Default::default(), Error::<T>::InsufficientProposersBalance);
+				Self::deposit_event(RawEvent::Proposed(who, balance));
+			}
+		}
+	}
+
+	decl_error! {
+		pub enum Error for Module<T: Trait> {
+			/// doc comment put into metadata
+			InsufficientProposersBalance,
+		}
+	}
+}
+
+frame_support::parameter_types!(
+	pub const MyGetParam: u32 = 10;
+	pub const BlockHashCount: u32 = 250;
+	pub const MaximumBlockWeight: frame_support::weights::Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: sp_runtime::Perbill = sp_runtime::Perbill::one();
+);
+
+impl frame_system::Trait for Runtime {
+	type BaseCallFilter = ();
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = u32;
+	type Call = Call;
+	type Hash = sp_core::H256;
+	type Hashing = sp_runtime::traits::BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = sp_runtime::traits::IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = MaximumBlockWeight;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type MaximumBlockLength = MaximumBlockLength;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+}
+impl pallet::Trait for Runtime {
+	type Event = Event;
+	type MyGetParam = MyGetParam;
+	type Balance = u64;
+}
+impl pallet_old::Trait for Runtime {
+	type Event = Event;
+	type MyGetParam = MyGetParam;
+	type Balance = u64;
+}
+
+pub type Header = sp_runtime::generic::Header<u32, sp_runtime::traits::BlakeTwo256>;
+pub type Block = sp_runtime::generic::Block<Header, UncheckedExtrinsic>;
+pub type UncheckedExtrinsic = sp_runtime::generic::UncheckedExtrinsic<u32, Call, (), ()>;
+
+frame_support::construct_runtime!(
+	pub enum Runtime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic
+	{
+		System: frame_system::{Module, Call, Config, Storage, Event<T>},
+		Example: pallet::{Module, Call, Event<T>, Config, Storage},
+		ExampleOld: pallet_old::{Module, Call, Event<T>, Storage},
+	}
+);
+
+fn new_test_ext() -> sp_io::TestExternalities {
+	GenesisConfig {
+		frame_system: Some(Default::default()),
+		pallet: Some(pallet::GenesisConfig { value: 3 }),
+	}.build_storage().unwrap().into()
+}
+
+#[test]
+fn call_expand() {
+	let call_foo = pallet::Call::<Runtime>::foo(3);
+	assert_eq!(
+		call_foo.get_dispatch_info(),
+		DispatchInfo {
+			weight: 1,
+			class: DispatchClass::Normal,
+			pays_fee: Pays::Yes,
+		}
+	);
+	assert_eq!(call_foo.get_call_name(), "foo");
+	assert_eq!(pallet::Call::<Runtime>::get_call_names(), &["foo", "propose"]);
+}
+
+#[test]
+fn error_expand() {
+	assert_eq!(
+		format!("{:?}", pallet::Error::<Runtime>::InsufficientProposersBalance),
+		String::from("InsufficientProposersBalance"),
+	);
+	assert_eq!(
+		<&'static str>::from(pallet::Error::<Runtime>::InsufficientProposersBalance),
+		"InsufficientProposersBalance",
+	);
+	assert_eq!(
+		DispatchError::from(pallet::Error::<Runtime>::InsufficientProposersBalance),
+		DispatchError::Module {
+			index: 1,
+			error: 0,
+			message: Some("InsufficientProposersBalance"),
+		},
+	);
+}
+
+#[test]
+fn dispatch_and_event_expand() {
+	new_test_ext().execute_with(|| {
+		frame_system::Module::<Runtime>::set_block_number(1);
+
+		assert!(
+			pallet::Call::<Runtime>::foo(3).dispatch_bypass_filter(Origin::root()).is_ok()
+		);
+		assert_eq!(pallet::Value::get(), Some(3));
+		assert_eq!(
+			frame_system::Module::<Runtime>::events()[0].event,
+			Event::pallet(pallet::Event::Something(3)),
+		);
+
+		assert_eq!(
+			pallet::Call::<Runtime>::propose(0).dispatch_bypass_filter(Origin::signed(1))
+				.map_err(|e| e.error),
+			Err(pallet::Error::<Runtime>::InsufficientProposersBalance.into()),
+		);
+		assert!(
+			pallet::Call::<Runtime>::propose(2).dispatch_bypass_filter(Origin::signed(1)).is_ok()
+		);
+		assert_eq!(
+			frame_system::Module::<Runtime>::events()[1].event,
+			Event::pallet(pallet::Event::Proposed(1, 2)),
+		);
+	})
+}
+
+#[test]
+fn storage_expand() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(pallet::Value::get(), Some(3));
+
+		pallet::Map::insert(1, 2);
+		assert_eq!(pallet::Map::get(1), 2);
+		assert_eq!(Example::map(1), 2);
+		assert_eq!(Example::map(3), 0);
+
+		pallet::DoubleMap::<Runtime>::insert(1, 2, 3);
+		assert_eq!(pallet::DoubleMap::<Runtime>::get(1, 2), Some(3));
+
+		// The storages are stored under the prefix `Example` as for `decl_storage`.
+		assert_eq!(pallet::Value::get(), pallet_old::Value::get());
+		assert_eq!(pallet::Map::get(1), pallet_old::Map::get(1));
+		assert_eq!(
+			pallet::DoubleMap::<Runtime>::get(1, 2),
+			pallet_old::DoubleMap::<Runtime>::get(1, 2),
+		);
+	})
+}
+
+#[test]
+fn hooks_expand() {
+	new_test_ext().execute_with(|| {
+		frame_system::Module::<Runtime>::set_block_number(1);
+
+		assert_eq!(<Example as OnInitialize<u32>>::on_initialize(1), 10);
+		<Example as OnFinalize<u32>>::on_finalize(1);
+		assert_eq!(<Example as OnRuntimeUpgrade>::on_runtime_upgrade(), 30);
+
+		assert_eq!(
+			frame_system::Module::<Runtime>::events(),
+			vec![10, 20, 30].into_iter()
+				.map(|value| EventRecord {
+					phase: Phase::Initialization,
+					event: Event::pallet(pallet::Event::Something(value)),
+					topics: vec![],
+				})
+				.collect::<Vec<_>>(),
+		);
+	})
+}
+
+#[test]
+fn metadata() {
+	use frame_metadata::*;
+
+	let modules = match Runtime::metadata().1 {
+		RuntimeMetadata::V12(metadata) => match metadata.modules {
+			DecodeDifferent::Encode(modules) => modules,
+			_ => unreachable!(),
+		},
+		_ => panic!("metadata has been bumped, test needs to be updated"),
+	};
+
+	let example = &modules[1];
+	let example_old = &modules[2];
+	assert_eq!(example.name, DecodeDifferent::Encode("Example"));
+	assert_eq!(example_old.name, DecodeDifferent::Encode("ExampleOld"));
+
+	pretty_assertions::assert_eq!(example.storage, example_old.storage);
+	pretty_assertions::assert_eq!(example.calls, example_old.calls);
+	pretty_assertions::assert_eq!(example.event, example_old.event);
+	pretty_assertions::assert_eq!(example.constants, example_old.constants);
+	pretty_assertions::assert_eq!(example.errors, example_old.errors);
+}
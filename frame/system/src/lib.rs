@@ -1375,3 +1375,14 @@ impl<T: Trait> Lookup for ChainContext<T> {
 		<T::Lookup as StaticLookup>::lookup(s)
 	}
 }
+
+/// Prelude to be used alongside pallet macro, for ease of use.
+pub mod pallet_prelude {
+	pub use crate::{ensure_signed, ensure_none, ensure_root};
+
+	/// Type alias for the `Origin` associated type of system trait.
+	pub type OriginFor<T> = <T as crate::Trait>::Origin;
+
+	/// Type alias for the `BlockNumber` associated type of system trait.
+	pub type BlockNumberFor<T> = <T as crate::Trait>::BlockNumber;
+}
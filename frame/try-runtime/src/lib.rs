@@ -0,0 +1,40 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime API for testing a runtime upgrade against existing chain state, before it is enacted.
+//!
+//! It is only meant to be implemented by runtimes built with their `try-runtime` feature, and is
+//! called by the `try-runtime` command of `try-runtime-cli`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use frame_support::weights::Weight;
+
+sp_api::decl_runtime_apis! {
+	/// Runtime api for testing a runtime upgrade.
+	pub trait TryRuntime {
+		/// Execute all `OnRuntimeUpgrade` hooks of the runtime and return the aggregate weight.
+		///
+		/// This does not note the upgrade, so the hooks run again when the next block is
+		/// initialized.
+		fn on_runtime_upgrade() -> Weight;
+
+		/// Execute the given block without checking its resulting digest and state root, and
+		/// return the weight it consumed.
+		fn execute_block_no_check(block: Block) -> Weight;
+	}
+}
@@ -21,6 +21,7 @@ mod construct;
 #[macro_use] mod core;
 mod import;
 mod generator;
+mod runtime_cache;
 mod simple_trie;
 mod state_sizes;
 mod tempdb;
@@ -39,6 +40,7 @@ use crate::{
 	trie::{TrieReadBenchmarkDescription, TrieWriteBenchmarkDescription, DatabaseSize},
 	construct::ConstructionBenchmarkDescription,
 	txpool::PoolBenchmarkDescription,
+	runtime_cache::{RuntimeStartBenchmarkDescription, StartType},
 };
 
 #[derive(Debug, StructOpt)]
@@ -151,6 +153,8 @@ fn main() {
 			database_type: BenchDataBaseType::RocksDb,
 		},
 		PoolBenchmarkDescription { database_type: BenchDataBaseType::RocksDb },
+		RuntimeStartBenchmarkDescription { start_type: StartType::Cold },
+		RuntimeStartBenchmarkDescription { start_type: StartType::Warm },
 	);
 
 	if opt.list {
@@ -0,0 +1,139 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Runtime start benchmarks.
+//!
+//! The goal of this benchmark is to figure out the time a freshly started node needs
+//! until it can execute the first runtime call, with and without the on-disk cache
+//! of compiled runtimes.
+
+use std::borrow::Cow;
+
+use sc_executor::{WasmExecutor, WasmExecutionMethod};
+use sp_core::traits::{CallInWasm, MissingHostFunctions};
+use sp_state_machine::BasicExternalities;
+use sp_wasm_interface::HostFunctions as _;
+
+use crate::core::{self, Path, Mode};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StartType {
+	/// The code cache is empty, the runtime has to be compiled.
+	Cold,
+	/// The code cache was populated by a previous run.
+	Warm,
+}
+
+impl StartType {
+	fn as_str(&self) -> &'static str {
+		match self {
+			StartType::Cold => "cold",
+			StartType::Warm => "warm",
+		}
+	}
+}
+
+pub struct RuntimeStartBenchmarkDescription {
+	pub start_type: StartType,
+}
+
+pub struct RuntimeStartBenchmark {
+	start_type: StartType,
+	code: Vec<u8>,
+	code_hash: Vec<u8>,
+	cache_dir: tempfile::TempDir,
+}
+
+impl core::BenchmarkDescription for RuntimeStartBenchmarkDescription {
+	fn path(&self) -> Path {
+		let mut path = Path::new(&["node", "runtime-start"]);
+		path.push(self.start_type.as_str());
+		path
+	}
+
+	fn setup(self: Box<Self>) -> Box<dyn core::Benchmark> {
+		let code = node_indracore_runtime::wasm_binary_unwrap().to_vec();
+		let code_hash = sp_core::blake2_256(&code).to_vec();
+		let cache_dir = tempfile::tempdir().expect("Failed to create temp dir");
+
+		let benchmark = RuntimeStartBenchmark {
+			start_type: self.start_type,
+			code,
+			code_hash,
+			cache_dir,
+		};
+		if self.start_type == StartType::Warm {
+			// Populate the cache.
+			benchmark.start(benchmark.cache_dir.path().to_path_buf());
+		}
+
+		Box::new(benchmark)
+	}
+
+	fn name(&self) -> Cow<'static, str> {
+		format!("Runtime start ({} code cache)", self.start_type.as_str()).into()
+	}
+}
+
+impl RuntimeStartBenchmark {
+	/// Start an executor as a restarted node would and make the first runtime call.
+	fn start(&self, cache_path: std::path::PathBuf) {
+		let executor = WasmExecutor::new(
+			WasmExecutionMethod::Compiled,
+			None,
+			sp_io::SubstrateHostFunctions::host_functions(),
+			1,
+			Some(cache_path),
+		);
+
+		executor.call_in_wasm(
+			&self.code,
+			Some(self.code_hash.clone()),
+			"Core_version",
+			&[],
+			&mut BasicExternalities::default(),
+			MissingHostFunctions::Disallow,
+		).expect("Failed to call `Core_version`");
+	}
+}
+
+impl core::Benchmark for RuntimeStartBenchmark {
+	fn run(&mut self, mode: Mode) -> std::time::Duration {
+		let cold_cache_dir;
+		let cache_path = match self.start_type {
+			StartType::Cold => {
+				cold_cache_dir = tempfile::tempdir().expect("Failed to create temp dir");
+				cold_cache_dir.path().to_path_buf()
+			},
+			StartType::Warm => self.cache_dir.path().to_path_buf(),
+		};
+
+		if mode == Mode::Profile {
+			std::thread::park_timeout(std::time::Duration::from_secs(3));
+		}
+
+		let start = std::time::Instant::now();
+		self.start(cache_path);
+		let elapsed = start.elapsed();
+
+		if mode == Mode::Profile {
+			std::thread::park_timeout(std::time::Duration::from_secs(1));
+		}
+		elapsed
+	}
+}
@@ -0,0 +1,91 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Inherents and extrinsics for the `benchmark overhead` command.
+
+use codec::Encode;
+use frame_system_rpc_runtime_api::AccountNonceApi;
+use node_executor::Executor;
+use node_indracore_runtime::{Call, RuntimeApi, SignedPayload, SystemCall, UncheckedExtrinsic};
+use node_primitives::Index;
+use sc_cli::Result;
+use sp_api::ProvideRuntimeApi;
+use sp_inherents::{InherentData, InherentDataProviders};
+use sp_keyring::AccountKeyring;
+use sp_runtime::{generic::{BlockId, Era}, OpaqueExtrinsic};
+
+type FullClient = sc_service::TFullClient<node_primitives::Block, RuntimeApi, Executor>;
+
+/// The inherent data for a block on top of the best block of `client`.
+pub fn inherent_data(
+	client: &FullClient,
+	providers: &InherentDataProviders,
+) -> Result<InherentData> {
+	let mut inherent_data = providers.create_inherent_data()
+		.map_err(|e| format!("Creating the inherent data failed: {:?}", e))?;
+	// Only registered by the GRANDPA voter, which is not running.
+	inherent_data.put_data(sp_finality_tracker::INHERENT_IDENTIFIER, &client.chain_info().best_number)
+		.map_err(|e| format!("Adding the finality tracker inherent data failed: {:?}", e))?;
+
+	Ok(inherent_data)
+}
+
+/// Builds `System::remark` extrinsics signed by Alice, the one with the given index following
+/// all extrinsics of Alice in the best block of `client`.
+pub fn remark_builder(client: &FullClient) -> Result<impl Fn(u32) -> OpaqueExtrinsic> {
+	let best = client.chain_info().best_hash;
+	let genesis_hash = client.chain_info().genesis_hash;
+	let version = client.runtime_version_at(&BlockId::Hash(best))?;
+	let nonce: Index = client.runtime_api()
+		.account_nonce(&BlockId::Hash(best), AccountKeyring::Alice.to_account_id())?;
+
+	Ok(move |index: u32| {
+		let function = Call::System(SystemCall::remark(vec![]));
+		let extra = (
+			frame_system::CheckSpecVersion::new(),
+			frame_system::CheckTxVersion::new(),
+			frame_system::CheckGenesis::new(),
+			frame_system::CheckEra::from(Era::Immortal),
+			frame_system::CheckNonce::from(nonce + index),
+			frame_system::CheckWeight::new(),
+			pallet_transaction_payment::ChargeTransactionPayment::from(0),
+		);
+		let raw_payload = SignedPayload::from_raw(
+			function,
+			extra,
+			(
+				version.spec_version,
+				version.transaction_version,
+				genesis_hash,
+				genesis_hash,
+				(),
+				(),
+				(),
+			),
+		);
+		let signature = raw_payload.using_encoded(|payload| AccountKeyring::Alice.sign(payload));
+		let (function, extra, _) = raw_payload.deconstruct();
+
+		UncheckedExtrinsic::new_signed(
+			function,
+			AccountKeyring::Alice.to_account_id().into(),
+			signature.into(),
+			extra,
+		).into()
+	})
+}
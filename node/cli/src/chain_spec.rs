@@ -17,6 +17,10 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 //! Substrate chain configurations.
+
+pub mod permissioned;
+pub mod pow;
+
 use grandpa_primitives::AuthorityId as GrandpaId;
 // use hex_literal::hex;
 use node_indracore_runtime::constants::currency::*;
@@ -28,7 +32,6 @@ use node_indracore_runtime::{
 	BalancesConfig,
 	ContractsConfig,
 	CouncilConfig,
-	DemocracyConfig,
 	ElectionsConfig,
 	GrandpaConfig,
 	ImOnlineConfig,
@@ -223,7 +226,6 @@ pub fn testnet_genesis(
 			slash_reward_fraction: Perbill::from_percent(10),
 			..Default::default()
 		}),
-		pallet_democracy: Some(DemocracyConfig::default()),
 		pallet_elections_phragmen: Some(ElectionsConfig {
 			members: endowed_accounts
 				.iter()
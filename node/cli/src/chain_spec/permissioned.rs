@@ -0,0 +1,185 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Chain configurations of the permissioned runtime.
+//!
+//! The well-known nodes of the local chains are derived from the ed25519 node keys `0x00..01`,
+//! owned by Alice, and `0x00..02`, owned by Bob. Start the nodes with the matching
+//! `--node-key` to let them connect once node authorization is enforced.
+
+use grandpa_primitives::AuthorityId as GrandpaId;
+use node_permissioned_runtime::constants::DOLLARS;
+use node_permissioned_runtime::{
+	wasm_binary_unwrap, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig,
+	NodeAuthorizationConfig, SudoConfig, SystemConfig,
+};
+use sc_network::config::identity;
+use sc_service::ChainType;
+use sp_consensus_aura::sr25519::AuthorityId as AuraId;
+use sp_core::{sr25519, OpaquePeerId};
+
+use super::{get_account_id_from_seed, get_from_seed, AccountId, Balance, Extensions};
+
+/// Specialized `ChainSpec` of the permissioned runtime.
+pub type ChainSpec = sc_service::GenericChainSpec<GenesisConfig, Extensions>;
+
+/// Prefix of the ids of all permissioned chain specs, used to pick the runtime of a chain spec.
+pub const CHAIN_ID_PREFIX: &str = "permissioned";
+
+/// Helper function to generate the Aura and GRANDPA keys of an authority from seed.
+pub fn authority_keys_from_seed(seed: &str) -> (AuraId, GrandpaId) {
+	(
+		get_from_seed::<AuraId>(seed),
+		get_from_seed::<GrandpaId>(seed),
+	)
+}
+
+/// The peer id of a node started with the ed25519 node key whose last byte is `index`, all other
+/// bytes being zero.
+pub fn peer_id_from_node_key(index: u8) -> OpaquePeerId {
+	let mut secret = [0u8; 32];
+	secret[31] = index;
+	let secret = identity::ed25519::SecretKey::from_bytes(&mut secret)
+		.expect("32 bytes are a valid ed25519 secret key; qed");
+	let public = identity::Keypair::Ed25519(secret.into()).public();
+
+	OpaquePeerId(public.into_peer_id().into_bytes())
+}
+
+/// Helper function to create a permissioned `GenesisConfig` for testing.
+pub fn testnet_genesis(
+	initial_authorities: Vec<(AuraId, GrandpaId)>,
+	root_key: AccountId,
+	endowed_accounts: Vec<AccountId>,
+	well_known_nodes: Vec<(OpaquePeerId, AccountId)>,
+) -> GenesisConfig {
+	const ENDOWMENT: Balance = 1_000_000 * DOLLARS;
+
+	GenesisConfig {
+		frame_system: Some(SystemConfig {
+			code: wasm_binary_unwrap().to_vec(),
+			changes_trie_config: Default::default(),
+		}),
+		pallet_balances: Some(BalancesConfig {
+			balances: endowed_accounts.into_iter().map(|k| (k, ENDOWMENT)).collect(),
+		}),
+		pallet_aura: Some(AuraConfig {
+			authorities: initial_authorities.iter().map(|x| x.0.clone()).collect(),
+		}),
+		pallet_grandpa: Some(GrandpaConfig {
+			authorities: initial_authorities.iter().map(|x| (x.1.clone(), 1)).collect(),
+		}),
+		pallet_node_authorization: Some(NodeAuthorizationConfig {
+			nodes: well_known_nodes,
+		}),
+		pallet_sudo: Some(SudoConfig { key: root_key }),
+	}
+}
+
+fn endowed_accounts() -> Vec<AccountId> {
+	vec![
+		get_account_id_from_seed::<sr25519::Public>("Alice"),
+		get_account_id_from_seed::<sr25519::Public>("Bob"),
+		get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
+		get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
+	]
+}
+
+fn development_config_genesis() -> GenesisConfig {
+	testnet_genesis(
+		vec![authority_keys_from_seed("Alice")],
+		get_account_id_from_seed::<sr25519::Public>("Alice"),
+		endowed_accounts(),
+		vec![
+			(peer_id_from_node_key(1), get_account_id_from_seed::<sr25519::Public>("Alice")),
+		],
+	)
+}
+
+/// Permissioned development config (single validator Alice)
+pub fn development_config() -> ChainSpec {
+	ChainSpec::from_genesis(
+		"Permissioned Development",
+		"permissioned_dev",
+		ChainType::Development,
+		development_config_genesis,
+		vec![],
+		None,
+		None,
+		None,
+		Default::default(),
+	)
+}
+
+fn local_testnet_genesis() -> GenesisConfig {
+	testnet_genesis(
+		vec![
+			authority_keys_from_seed("Alice"),
+			authority_keys_from_seed("Bob"),
+		],
+		get_account_id_from_seed::<sr25519::Public>("Alice"),
+		endowed_accounts(),
+		vec![
+			(peer_id_from_node_key(1), get_account_id_from_seed::<sr25519::Public>("Alice")),
+			(peer_id_from_node_key(2), get_account_id_from_seed::<sr25519::Public>("Bob")),
+		],
+	)
+}
+
+/// Permissioned local testnet config (multivalidator Alice + Bob)
+pub fn local_testnet_config() -> ChainSpec {
+	ChainSpec::from_genesis(
+		"Permissioned Local Testnet",
+		"permissioned_local_testnet",
+		ChainType::Local,
+		local_testnet_genesis,
+		vec![],
+		None,
+		None,
+		None,
+		Default::default(),
+	)
+}
+
+/// Whether the chain spec with the given id is one of the permissioned runtime.
+pub fn is_permissioned(id: &str) -> bool {
+	id.starts_with(CHAIN_ID_PREFIX)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_runtime::BuildStorage;
+
+	#[test]
+	fn test_create_development_chain_spec() {
+		development_config().build_storage().unwrap();
+	}
+
+	#[test]
+	fn test_create_local_testnet_chain_spec() {
+		local_testnet_config().build_storage().unwrap();
+	}
+
+	#[test]
+	fn peer_ids_are_distinct() {
+		assert_ne!(peer_id_from_node_key(1), peer_id_from_node_key(2));
+		assert!(is_permissioned(local_testnet_config().id()));
+		assert!(!is_permissioned(crate::chain_spec::local_testnet_config().id()));
+	}
+}
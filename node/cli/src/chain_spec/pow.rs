@@ -0,0 +1,106 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Chain configurations of the proof-of-work development runtime.
+
+use node_pow_runtime::constants::DOLLARS;
+use node_pow_runtime::{
+	wasm_binary_unwrap, BalancesConfig, DifficultyConfig, GenesisConfig, SudoConfig, SystemConfig,
+};
+use sc_service::ChainType;
+use sp_core::{sr25519, U256};
+
+use super::{get_account_id_from_seed, AccountId, Balance, Extensions};
+
+/// Specialized `ChainSpec` of the proof-of-work runtime.
+pub type ChainSpec = sc_service::GenericChainSpec<GenesisConfig, Extensions>;
+
+/// Prefix of the ids of all proof-of-work chain specs, used to pick the runtime of a chain spec.
+pub const CHAIN_ID_PREFIX: &str = "pow";
+
+/// Helper function to create a proof-of-work `GenesisConfig` for testing.
+pub fn testnet_genesis(
+	root_key: AccountId,
+	endowed_accounts: Vec<AccountId>,
+	initial_difficulty: U256,
+) -> GenesisConfig {
+	const ENDOWMENT: Balance = 1_000_000 * DOLLARS;
+
+	GenesisConfig {
+		frame_system: Some(SystemConfig {
+			code: wasm_binary_unwrap().to_vec(),
+			changes_trie_config: Default::default(),
+		}),
+		pallet_difficulty: Some(DifficultyConfig { initial_difficulty }),
+		pallet_balances: Some(BalancesConfig {
+			balances: endowed_accounts.into_iter().map(|k| (k, ENDOWMENT)).collect(),
+		}),
+		pallet_sudo: Some(SudoConfig { key: root_key }),
+	}
+}
+
+fn development_config_genesis() -> GenesisConfig {
+	testnet_genesis(
+		get_account_id_from_seed::<sr25519::Public>("Alice"),
+		vec![
+			get_account_id_from_seed::<sr25519::Public>("Alice"),
+			get_account_id_from_seed::<sr25519::Public>("Bob"),
+			get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
+			get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
+		],
+		U256::from(1_000_000),
+	)
+}
+
+/// Proof-of-work development config (single CPU miner)
+pub fn development_config() -> ChainSpec {
+	ChainSpec::from_genesis(
+		"Proof-of-work Development",
+		"pow_dev",
+		ChainType::Development,
+		development_config_genesis,
+		vec![],
+		None,
+		None,
+		None,
+		Default::default(),
+	)
+}
+
+/// Whether the chain spec with the given id is one of the proof-of-work runtime.
+pub fn is_pow(id: &str) -> bool {
+	id.starts_with(CHAIN_ID_PREFIX)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_runtime::BuildStorage;
+
+	#[test]
+	fn test_create_development_chain_spec() {
+		development_config().build_storage().unwrap();
+	}
+
+	#[test]
+	fn pow_chain_specs_are_recognized() {
+		assert!(is_pow(development_config().id()));
+		assert!(!is_pow(crate::chain_spec::development_config().id()));
+		assert!(!is_pow(crate::chain_spec::permissioned::development_config().id()));
+	}
+}
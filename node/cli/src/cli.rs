@@ -28,6 +28,14 @@ pub struct Cli {
 	#[allow(missing_docs)]
 	#[structopt(flatten)]
 	pub run: RunCmd,
+
+	/// Run the proof-of-work development runtime instead of BABE and GRANDPA.
+	///
+	/// Blocks are mined on the CPU with the reference Blake2 algorithm, at a difficulty
+	/// retargeted by the runtime. With `--dev` the proof-of-work development chain is used,
+	/// other chain specs must be ones of the proof-of-work runtime, e.g. `--chain pow-dev`.
+	#[structopt(long)]
+	pub pow: bool,
 }
 
 /// Possible subcommands of the main binary.
@@ -43,10 +51,17 @@ pub enum Subcommand {
 	)]
 	Inspect(node_inspect::cli::InspectCmd),
 
-	/// The custom benchmark subcommmand benchmarking runtime pallets.
-	#[structopt(name = "benchmark", about = "Benchmark runtime pallets.")]
+	/// The custom benchmark subcommmand benchmarking runtime pallets and the node.
+	#[structopt(name = "benchmark", about = "Benchmark runtime pallets, the database and block overhead.")]
 	Benchmark(frame_benchmarking_cli::BenchmarkCmd),
 
+	/// The custom try-runtime subcommand testing a runtime upgrade against existing chain state.
+	#[structopt(
+		name = "try-runtime",
+		about = "Test a runtime upgrade against the state of a block, without enacting it."
+	)]
+	TryRuntime(try_runtime_cli::TryRuntimeCmd),
+
 	/// Verify a signature for a message, provided on STDIN, with a given (public or secret) key.
 	Verify(VerifyCmd),
 
@@ -71,9 +86,21 @@ pub enum Subcommand {
 	/// Export the state of a given block into a chain spec.
 	ExportState(sc_cli::ExportStateCmd),
 
+	/// Build a development chain spec, with Alice as the only authority, from the state of a block.
+	ForkOff(crate::ForkOffCmd),
+
+	/// Export the state of the last finalized block into a snapshot file.
+	ExportSnapshot(sc_cli::ExportSnapshotCmd),
+
 	/// Import blocks.
 	ImportBlocks(sc_cli::ImportBlocksCmd),
 
+	/// Create the database from a snapshot file.
+	ImportSnapshot(sc_cli::ImportSnapshotCmd),
+
+	/// Copy the chain into a database of another kind.
+	MigrateDatabase(sc_cli::MigrateDatabaseCmd),
+
 	/// Remove the whole chain.
 	PurgeChain(sc_cli::PurgeChainCmd),
 
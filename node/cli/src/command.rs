@@ -17,11 +17,13 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::service::{new_full_base, new_partial, NewFullBase};
-use crate::{chain_spec, service, Cli, Subcommand};
+use crate::{benchmarking, chain_spec, service, Cli, Subcommand};
+use frame_benchmarking_cli::{BenchmarkCmd, BenchmarkSubcommand};
 use node_executor::Executor;
 use node_indracore_runtime::{Block, RuntimeApi};
 use sc_cli::{ChainSpec, Result, Role, RuntimeVersion, SubstrateCli};
 use sc_service::PartialComponents;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
 
 impl SubstrateCli for Cli {
 	fn impl_name() -> String {
@@ -50,18 +52,38 @@ impl SubstrateCli for Cli {
 
 	fn load_spec(&self, id: &str) -> std::result::Result<Box<dyn sc_service::ChainSpec>, String> {
 		Ok(match id {
+			"dev" if self.pow => Box::new(chain_spec::pow::development_config()),
 			"dev" => Box::new(chain_spec::development_config()),
 			"local" => Box::new(chain_spec::local_testnet_config()),
 			"" | "sel" | "indracore-sel" => Box::new(chain_spec::indracore_sel_config()?),
 			"staging" => Box::new(chain_spec::staging_testnet_config()),
-			path => Box::new(chain_spec::ChainSpec::from_json_file(
-				std::path::PathBuf::from(path),
-			)?),
+			"permissioned-dev" => Box::new(chain_spec::permissioned::development_config()),
+			"permissioned-local" => Box::new(chain_spec::permissioned::local_testnet_config()),
+			"pow-dev" => Box::new(chain_spec::pow::development_config()),
+			path => {
+				let path = std::path::PathBuf::from(path);
+				let spec = chain_spec::ChainSpec::from_json_file(path.clone())?;
+				// The genesis is only decoded when the storage is built, so the id can be read
+				// before knowing which runtime the chain spec is for.
+				if chain_spec::permissioned::is_permissioned(spec.id()) {
+					Box::new(chain_spec::permissioned::ChainSpec::from_json_file(path)?)
+				} else if chain_spec::pow::is_pow(spec.id()) {
+					Box::new(chain_spec::pow::ChainSpec::from_json_file(path)?)
+				} else {
+					Box::new(spec)
+				}
+			}
 		})
 	}
 
-	fn native_runtime_version(_: &Box<dyn ChainSpec>) -> &'static RuntimeVersion {
-		&node_indracore_runtime::VERSION
+	fn native_runtime_version(chain_spec: &Box<dyn ChainSpec>) -> &'static RuntimeVersion {
+		if chain_spec::permissioned::is_permissioned(chain_spec.id()) {
+			&node_permissioned_runtime::VERSION
+		} else if chain_spec::pow::is_pow(chain_spec.id()) {
+			&node_pow_runtime::VERSION
+		} else {
+			&node_indracore_runtime::VERSION
+		}
 	}
 }
 
@@ -72,9 +94,22 @@ pub fn run() -> Result<()> {
 	match &cli.subcommand {
 		None => {
 			let runner = cli.create_runner(&cli.run)?;
-			runner.run_node_until_exit(|config| match config.role {
-				Role::Light => service::new_light(config),
-				_ => service::new_full(config),
+			let pow_flag = cli.pow;
+			runner.run_node_until_exit(|config| {
+				let permissioned = chain_spec::permissioned::is_permissioned(config.chain_spec.id());
+				let pow = chain_spec::pow::is_pow(config.chain_spec.id());
+				match config.role {
+					_ if pow_flag && !pow => Err(
+						"Proof-of-work mode requires a chain spec of the proof-of-work runtime, \
+						e.g. `--chain pow-dev`.".into()
+					),
+					Role::Light if pow => Err("Proof-of-work mode is not supported by light clients.".into()),
+					Role::Light if permissioned => service::permissioned::new_light(config),
+					Role::Light => service::new_light(config),
+					_ if pow => service::pow::new_full(config),
+					_ if permissioned => service::permissioned::new_full(config),
+					_ => service::new_full(config),
+				}
 			})
 		}
 		Some(Subcommand::Inspect(cmd)) => {
@@ -82,7 +117,11 @@ pub fn run() -> Result<()> {
 
 			runner.sync_run(|config| cmd.run::<Block, RuntimeApi, Executor>(config))
 		}
-		Some(Subcommand::Benchmark(cmd)) => {
+		Some(Subcommand::Benchmark(BenchmarkCmd { subcommand: None, pallet: cmd }))
+		| Some(Subcommand::Benchmark(BenchmarkCmd {
+			subcommand: Some(BenchmarkSubcommand::Pallet(cmd)),
+			..
+		})) => {
 			if cfg!(feature = "runtime-benchmarks") {
 				let runner = cli.create_runner(cmd)?;
 
@@ -93,6 +132,48 @@ pub fn run() -> Result<()> {
 					.into())
 			}
 		}
+		Some(Subcommand::Benchmark(BenchmarkCmd {
+			subcommand: Some(BenchmarkSubcommand::Storage(cmd)),
+			..
+		})) => {
+			let runner = cli.create_runner(cmd)?;
+
+			runner.sync_run(|config| {
+				let PartialComponents { backend, .. } = new_partial(&config)?;
+				cmd.run(&config, backend)
+			})
+		}
+		Some(Subcommand::Benchmark(BenchmarkCmd {
+			subcommand: Some(BenchmarkSubcommand::Overhead(cmd)),
+			..
+		})) => {
+			let runner = cli.create_runner(cmd)?;
+
+			runner.sync_run(|config| {
+				let PartialComponents { client, inherent_data_providers, .. } = new_partial(&config)?;
+				let inherent_data = benchmarking::inherent_data(&client, &inherent_data_providers)?;
+				let remark = benchmarking::remark_builder(&client)?;
+				cmd.run::<Block, _, _>(client, inherent_data, remark)
+			})
+		}
+		Some(Subcommand::Benchmark(BenchmarkCmd {
+			subcommand: Some(BenchmarkSubcommand::Compare(cmd)),
+			..
+		})) => cmd.run(),
+		Some(Subcommand::TryRuntime(cmd)) => {
+			if cfg!(feature = "try-runtime") {
+				let runner = cli.create_runner(cmd)?;
+
+				runner.sync_run(|config| {
+					let PartialComponents { client, .. } = new_partial(&config)?;
+					cmd.run::<Block, _, _, Executor>(client)
+				})
+			} else {
+				Err("Try-runtime wasn't enabled when building the node. \
+				You can enable it with `--features try-runtime`."
+					.into())
+			}
+		}
 		Some(Subcommand::Key(cmd)) => cmd.run(),
 		Some(Subcommand::Sign(cmd)) => cmd.run(),
 		Some(Subcommand::Verify(cmd)) => cmd.run(),
@@ -153,6 +234,30 @@ pub fn run() -> Result<()> {
 				Ok((cmd.run(client, config.chain_spec), task_manager))
 			})
 		}
+		Some(Subcommand::ForkOff(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+
+			runner.sync_run(|config| {
+				let PartialComponents { client, .. } = new_partial(&config)?;
+				cmd.run(client)
+			})
+		}
+		Some(Subcommand::ExportSnapshot(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.async_run(|config| {
+				let PartialComponents {
+					client,
+					task_manager,
+					..
+				} = new_partial(&config)?;
+				let aux_keys = |header: &<Block as BlockT>::Header| {
+					let mut keys = grandpa::snapshot_aux_keys();
+					keys.extend(sc_consensus_babe::aux_schema::snapshot_aux_keys(header.hash()));
+					keys
+				};
+				Ok((cmd.run(client, aux_keys), task_manager))
+			})
+		}
 		Some(Subcommand::ImportBlocks(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 			runner.async_run(|config| {
@@ -165,6 +270,14 @@ pub fn run() -> Result<()> {
 				Ok((cmd.run(client, import_queue), task_manager))
 			})
 		}
+		Some(Subcommand::ImportSnapshot(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| cmd.run::<Block>(&config))
+		}
+		Some(Subcommand::MigrateDatabase(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| cmd.run::<Block>(&config))
+		}
 		Some(Subcommand::PurgeChain(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 			runner.sync_run(|config| cmd.run(config.database))
@@ -0,0 +1,164 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `fork-off` command, which turns the state of a block into a development chain spec.
+//!
+//! All storage that depends on the set of authorities is replaced by the one of the development
+//! chain, making Alice the only validator and the sudo key. Everything else, like balances and
+//! contracts, is kept as is. The pallet layout of the runtime in the state must match the one of
+//! the native runtime, whose development genesis is used.
+
+use crate::chain_spec;
+use codec::Encode;
+use frame_support::storage::StorageValue;
+use node_executor::Executor;
+use node_indracore_runtime::RuntimeApi;
+use pallet_staking::Forcing;
+use sc_cli::{BlockNumberOrHash, CliConfiguration, DatabaseParams, PruningParams, Result, SharedParams};
+use sc_service::{ChainSpec, chain_ops::{build_spec, export_raw_state}};
+use sp_core::{storage::Storage, twox_128};
+use sp_runtime::BuildStorage;
+use std::{fs, io::Write, path::PathBuf, sync::Arc};
+use structopt::StructOpt;
+
+type FullClient = sc_service::TFullClient<node_primitives::Block, RuntimeApi, Executor>;
+
+/// Pallets whose storage is taken from the development genesis, as it depends on the authorities.
+const DEV_PALLETS: &[&str] = &[
+	"Babe",
+	"GrandpaFinality",
+	"Session",
+	"ImOnline",
+	"AuthorityDiscovery",
+	"Sudo",
+];
+
+/// Well-known keys taken from the development genesis.
+const DEV_KEYS: &[&[u8]] = &[grandpa_primitives::GRANDPA_AUTHORITIES_KEY];
+
+/// The `fork-off` command used to build a development chain spec from the state of a block.
+#[derive(Debug, StructOpt)]
+pub struct ForkOffCmd {
+	/// Block whose state is forked off, the best block if not given.
+	#[structopt(long, value_name = "HASH or NUMBER")]
+	pub at: Option<BlockNumberOrHash>,
+
+	/// Write the raw chain spec to this file instead of stdout.
+	#[structopt(long, short, parse(from_os_str), value_name = "PATH")]
+	pub output: Option<PathBuf>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl ForkOffCmd {
+	/// Run the `fork-off` command on the chain of `client`.
+	pub fn run(&self, client: Arc<FullClient>) -> Result<()> {
+		let block = self.at.as_ref().map(|b| b.parse()).transpose()?;
+		let forked = export_raw_state(client, block)?;
+
+		let mut spec = chain_spec::development_config();
+		let dev = spec.build_storage()?;
+		spec.set_storage(fork_off(forked, dev));
+
+		let json = build_spec(&spec, true)?;
+		match self.output {
+			Some(ref path) => fs::write(path, json)?,
+			None => std::io::stdout().write_all(json.as_bytes())?,
+		}
+
+		Ok(())
+	}
+}
+
+/// Replace the authority dependent storage of `forked` by the one of `dev`.
+///
+/// Staking is forced to never start a new era, as it would elect the validators of the forked
+/// chain, whose keys are not available.
+pub fn fork_off(mut forked: Storage, dev: Storage) -> Storage {
+	let prefixes = DEV_PALLETS.iter().map(|p| twox_128(p.as_bytes())).collect::<Vec<_>>();
+	let from_dev = |key: &[u8]| {
+		DEV_KEYS.contains(&key) || prefixes.iter().any(|prefix| key.starts_with(prefix))
+	};
+
+	forked.top = forked.top.into_iter()
+		.filter(|(key, _)| !from_dev(key))
+		.chain(dev.top.into_iter().filter(|(key, _)| from_dev(key)))
+		.collect();
+	forked.top.insert(pallet_staking::ForceEra::hashed_key().to_vec(), Forcing::ForceNone.encode());
+
+	forked
+}
+
+impl CliConfiguration for ForkOffCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn with_prefix(storage: &Storage, pallet: &str) -> Vec<(Vec<u8>, Vec<u8>)> {
+		let prefix = twox_128(pallet.as_bytes());
+		storage.top.iter()
+			.filter(|(key, _)| key.starts_with(&prefix))
+			.map(|(key, value)| (key.clone(), value.clone()))
+			.collect()
+	}
+
+	#[test]
+	fn authorities_are_taken_from_dev_genesis() {
+		let forked = chain_spec::local_testnet_config().build_storage().unwrap();
+		let dev = chain_spec::development_config().build_storage().unwrap();
+
+		let storage = fork_off(forked.clone(), dev.clone());
+
+		for pallet in DEV_PALLETS {
+			assert_eq!(with_prefix(&storage, pallet), with_prefix(&dev, pallet), "{}", pallet);
+		}
+		assert_eq!(
+			storage.top.get(grandpa_primitives::GRANDPA_AUTHORITIES_KEY),
+			dev.top.get(grandpa_primitives::GRANDPA_AUTHORITIES_KEY),
+		);
+		assert_eq!(with_prefix(&storage, "System"), with_prefix(&forked, "System"));
+		assert_eq!(with_prefix(&storage, "Balances"), with_prefix(&forked, "Balances"));
+		assert_eq!(
+			storage.top.get(&pallet_staking::ForceEra::hashed_key()[..]),
+			Some(&Forcing::ForceNone.encode()),
+		);
+	}
+}
@@ -33,13 +33,17 @@
 pub mod chain_spec;
 
 #[macro_use]
-mod service;
+pub mod service;
 #[cfg(feature = "browser")]
 mod browser;
 #[cfg(feature = "cli")]
+mod benchmarking;
+#[cfg(feature = "cli")]
 mod cli;
 #[cfg(feature = "cli")]
 mod command;
+#[cfg(feature = "cli")]
+mod fork_off;
 
 #[cfg(feature = "browser")]
 pub use browser::*;
@@ -47,3 +51,5 @@ pub use browser::*;
 pub use cli::*;
 #[cfg(feature = "cli")]
 pub use command::*;
+#[cfg(feature = "cli")]
+pub use fork_off::ForkOffCmd;
@@ -37,6 +37,9 @@ use sc_client_api::{ExecutorProvider, RemoteBackend};
 use sp_core::traits::BareCryptoStorePtr;
 use node_executor::Executor;
 
+pub mod permissioned;
+pub mod pow;
+
 type FullClient = sc_service::TFullClient<Block, RuntimeApi, Executor>;
 type FullBackend = sc_service::TFullBackend<Block>;
 type FullSelectChain = sc_consensus::LongestChain<FullBackend, Block>;
@@ -44,6 +47,7 @@ type FullGrandpaBlockImport =
 	grandpa::GrandpaBlockImport<FullBackend, Block, FullClient, FullSelectChain>;
 type LightClient = sc_service::TLightClient<Block, RuntimeApi, Executor>;
 
+/// Creates the parts of a full service which are needed before the network.
 pub fn new_partial(config: &Configuration) -> Result<sc_service::PartialComponents<
 	FullClient, FullBackend, FullSelectChain,
 	sp_consensus::DefaultImportQueue<Block, FullClient>,
@@ -157,18 +161,25 @@ pub fn new_partial(config: &Configuration) -> Result<sc_service::PartialComponen
 	})
 }
 
+/// The components of a full service.
 pub struct NewFullBase {
+	/// Task manager of the service.
 	pub task_manager: TaskManager,
+	/// Inherent data providers used by block authoring.
 	pub inherent_data_providers: InherentDataProviders,
+	/// The client.
 	pub client: Arc<FullClient>,
+	/// The network service.
 	pub network: Arc<NetworkService<Block, <Block as BlockT>::Hash>>,
+	/// Sinks for network status updates.
 	pub network_status_sinks: sc_service::NetworkStatusSinks<Block>,
+	/// The transaction pool.
 	pub transaction_pool: Arc<sc_transaction_pool::FullPool<Block, FullClient>>,
 }
 
 /// Creates a full service from the configuration.
 pub fn new_full_base(
-	config: Configuration,
+	mut config: Configuration,
 	with_startup_data: impl FnOnce(
 		&sc_consensus_babe::BabeBlockImport<Block, FullClient, FullGrandpaBlockImport>,
 		&sc_consensus_babe::BabeLink<Block>,
@@ -182,6 +193,17 @@ pub fn new_full_base(
 
 	let (shared_voter_state, finality_proof_provider) = rpc_setup;
 
+	let (warp_sync_handler, warp_sync_protocol_config) = {
+		let (_, grandpa_link, _) = &import_setup;
+		grandpa::GrandpaWarpSyncRequestHandler::new(
+			&config.protocol_id(),
+			backend.clone(),
+			grandpa_link.shared_authority_set().clone(),
+		)
+	};
+	config.network.request_response_protocols.push(warp_sync_protocol_config);
+	task_manager.spawn_handle().spawn("grandpa-warp-sync", warp_sync_handler.run());
+
 	let (network, network_status_sinks, system_rpc_tx, network_starter) =
 		sc_service::build_network(sc_service::BuildNetworkParams {
 			config: &config,
@@ -353,6 +375,7 @@ pub fn new_full(config: Configuration)
 	})
 }
 
+/// Creates a light service from the configuration.
 pub fn new_light_base(config: Configuration) -> Result<(
 	TaskManager, RpcHandlers, Arc<LightClient>,
 	Arc<NetworkService<Block, <Block as BlockT>::Hash>>,
@@ -667,25 +690,4 @@ mod tests {
 			},
 		);
 	}
-
-	#[test]
-	#[ignore]
-	fn test_consensus() {
-		sc_service_test::consensus(
-			crate::chain_spec::tests::integration_test_config_with_two_authorities(),
-			|config| {
-				let NewFullBase { task_manager, client, network, transaction_pool, .. }
-					= new_full_base(config,|_, _| ())?;
-				Ok(sc_service_test::TestNetComponents::new(task_manager, client, network, transaction_pool))
-			},
-			|config| {
-				let (keep_alive, _, client, network, transaction_pool) = new_light_base(config)?;
-				Ok(sc_service_test::TestNetComponents::new(keep_alive, client, network, transaction_pool))
-			},
-			vec![
-				"//Alice".into(),
-				"//Bob".into(),
-			],
-		)
-	}
 }
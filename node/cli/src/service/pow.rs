@@ -0,0 +1,186 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Service implementation for the proof-of-work development runtime, without finality.
+
+use std::{sync::Arc, time::Duration};
+use codec::Encode;
+use node_primitives::Block;
+use node_pow_runtime::RuntimeApi;
+use node_executor::PowExecutor;
+use sc_client_api::ExecutorProvider;
+use sc_consensus_pow::blake2::{self, Blake2Algorithm};
+use sc_service::{config::Configuration, error::{Error as ServiceError}, TaskManager};
+use sp_core::U256;
+
+/// Number of nonces the development miner tries before checking for a new build.
+const POW_MINING_ROUNDS: usize = 10_000;
+
+/// Builds a full service for a proof-of-work development chain.
+///
+/// Blocks are imported through `sc_consensus_pow` and, on authorities, mined on the CPU with
+/// the reference Blake2 algorithm at the difficulty retargeted by the runtime.
+pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
+	let (client, backend, keystore, mut task_manager) =
+		sc_service::new_full_parts::<Block, RuntimeApi, PowExecutor>(&config)?;
+	let client = Arc::new(client);
+
+	let select_chain = sc_consensus::LongestChain::new(backend.clone());
+
+	let transaction_pool = sc_transaction_pool::BasicPool::new_full(
+		config.transaction_pool.clone(),
+		config.prometheus_registry(),
+		task_manager.spawn_handle(),
+		client.clone(),
+	);
+
+	let inherent_data_providers = sp_inherents::InherentDataProviders::new();
+	let algorithm = Blake2Algorithm::new(client.clone());
+
+	let pow_block_import = sc_consensus_pow::PowBlockImport::new(
+		client.clone(),
+		client.clone(),
+		algorithm.clone(),
+		0,
+		select_chain.clone(),
+		inherent_data_providers.clone(),
+		sp_consensus::CanAuthorWithNativeVersion::new(client.executor().clone()),
+	);
+
+	let import_queue = sc_consensus_pow::import_queue(
+		Box::new(pow_block_import.clone()),
+		None,
+		None,
+		algorithm.clone(),
+		inherent_data_providers.clone(),
+		&task_manager.spawn_handle(),
+		config.prometheus_registry(),
+	)?;
+
+	let (network, network_status_sinks, system_rpc_tx, network_starter) =
+		sc_service::build_network(sc_service::BuildNetworkParams {
+			config: &config,
+			client: client.clone(),
+			transaction_pool: transaction_pool.clone(),
+			spawn_handle: task_manager.spawn_handle(),
+			import_queue,
+			on_demand: None,
+			block_announce_validator_builder: None,
+			finality_proof_request_builder: None,
+			finality_proof_provider: None,
+		})?;
+
+	if config.offchain_worker.enabled {
+		sc_service::build_offchain_workers(
+			&config, backend.clone(), task_manager.spawn_handle(), client.clone(), network.clone(),
+		);
+	}
+
+	let role = config.role.clone();
+	let prometheus_registry = config.prometheus_registry().cloned();
+
+	let rpc_extensions_builder = {
+		let client = client.clone();
+		let pool = transaction_pool.clone();
+
+		move |deny_unsafe, _| {
+			node_rpc::create_basic(node_rpc::MinimalDeps {
+				client: client.clone(),
+				pool: pool.clone(),
+				deny_unsafe,
+			})
+		}
+	};
+
+	sc_service::spawn_tasks(sc_service::SpawnTasksParams {
+		config,
+		backend,
+		client: client.clone(),
+		keystore,
+		network: network.clone(),
+		rpc_extensions_builder: Box::new(rpc_extensions_builder),
+		transaction_pool: transaction_pool.clone(),
+		task_manager: &mut task_manager,
+		on_demand: None,
+		remote_blockchain: None,
+		telemetry_connection_sinks: sc_service::TelemetryConnectionSinks::default(),
+		network_status_sinks,
+		system_rpc_tx,
+	})?;
+
+	if role.is_authority() {
+		let proposer = sc_basic_authorship::ProposerFactory::new(
+			client.clone(),
+			transaction_pool,
+			prometheus_registry.as_ref(),
+		);
+
+		let (worker, worker_task) = sc_consensus_pow::start_mining_worker(
+			Box::new(pow_block_import),
+			client.clone(),
+			select_chain,
+			algorithm,
+			proposer,
+			network.clone(),
+			None,
+			inherent_data_providers,
+			Duration::from_secs(10),
+			Duration::from_secs(2),
+			sp_consensus::CanAuthorWithNativeVersion::new(client.executor().clone()),
+		);
+		task_manager.spawn_essential_handle().spawn_blocking("pow-worker", worker_task);
+
+		task_manager.spawn_essential_handle().spawn_blocking("pow-miner", async move {
+			let mut nonce = U256::zero();
+			let mut pre_hash = None;
+
+			loop {
+				let metadata = worker.lock().metadata();
+				let metadata = match metadata {
+					Some(metadata) => metadata,
+					None => {
+						futures_timer::Delay::new(Duration::from_millis(500)).await;
+						continue
+					},
+				};
+
+				if pre_hash != Some(metadata.pre_hash) {
+					pre_hash = Some(metadata.pre_hash);
+					nonce = U256::zero();
+				}
+
+				match blake2::mine(&metadata.pre_hash, metadata.difficulty, nonce, POW_MINING_ROUNDS) {
+					Some(seal) => {
+						let mut worker = worker.lock();
+						// The build may have been replaced while mining, making the seal useless.
+						if worker.metadata().as_ref() == Some(&metadata) {
+							worker.submit(seal.encode());
+						}
+					},
+					None => nonce = nonce.saturating_add(U256::from(POW_MINING_ROUNDS)),
+				}
+
+				// Yield between rounds, so that the task can be stopped on shutdown.
+				futures_timer::Delay::new(Duration::from_millis(1)).await;
+			}
+		});
+	}
+
+	network_starter.start_network();
+	Ok(task_manager)
+}
@@ -29,3 +29,21 @@ native_executor_instance!(
 	node_indracore_runtime::native_version,
 	frame_benchmarking::benchmarking::HostFunctions,
 );
+
+// Declare an instance of the native executor named `PermissionedExecutor`, for the permissioned
+// runtime.
+native_executor_instance!(
+	pub PermissionedExecutor,
+	node_permissioned_runtime::api::dispatch,
+	node_permissioned_runtime::native_version,
+	frame_benchmarking::benchmarking::HostFunctions,
+);
+
+// Declare an instance of the native executor named `PowExecutor`, for the proof-of-work
+// development runtime.
+native_executor_instance!(
+	pub PowExecutor,
+	node_pow_runtime::api::dispatch,
+	node_pow_runtime::native_version,
+	frame_benchmarking::benchmarking::HostFunctions,
+);
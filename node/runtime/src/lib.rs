@@ -91,6 +91,9 @@ use sp_runtime::generic::Era;
 /// Weights for pallets used in the runtime.
 mod weights;
 
+/// Storage migrations run on runtime upgrades.
+pub mod migrations;
+
 // Make the WASM binary available.
 #[cfg(feature = "std")]
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
@@ -114,10 +117,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	// and set impl_version to 0. If only runtime
 	// implementation changes and behavior does not, then leave spec_version as
 	// is and increment impl_version.
-	spec_version: 259,
+	spec_version: 266,
 	impl_version: 0,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 3,
 };
 
 /// Native version.
@@ -253,7 +256,8 @@ impl InstanceFilter<Call> for ProxyType {
 			),
 			ProxyType::Governance => matches!(
 				c,
-				Call::Democracy(..)
+				Call::Referenda(..)
+					| Call::ConvictionVoting(..)
 					| Call::Council(..)
 					// | Call::Society(..)
 					| Call::TechnicalCommittee(..)
@@ -292,6 +296,7 @@ impl pallet_proxy::Trait for Runtime {
 parameter_types! {
 	pub MaximumSchedulerWeight: Weight = Perbill::from_percent(80) * MaximumBlockWeight::get();
 	pub const MaxScheduledPerBlock: u32 = 50;
+	pub const NoPreimagePostponement: Option<BlockNumber> = Some(10);
 }
 
 impl pallet_scheduler::Trait for Runtime {
@@ -303,6 +308,24 @@ impl pallet_scheduler::Trait for Runtime {
 	type ScheduleOrigin = EnsureRoot<AccountId>;
 	type MaxScheduledPerBlock = MaxScheduledPerBlock;
 	type WeightInfo = weights::pallet_scheduler::WeightInfo;
+	type PreimageProvider = Preimage;
+	type NoPreimagePostponement = NoPreimagePostponement;
+}
+
+parameter_types! {
+	pub const PreimageMaxSize: u32 = 4096 * 1024;
+	pub const PreimageBaseDeposit: Balance = 1 * DOLLARS;
+	pub const PreimageByteDeposit: Balance = 1 * CENTS;
+}
+
+impl pallet_preimage::Trait for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type ManagerOrigin = EnsureRoot<AccountId>;
+	type MaxSize = PreimageMaxSize;
+	type BaseDeposit = PreimageBaseDeposit;
+	type ByteDeposit = PreimageByteDeposit;
+	type WeightInfo = weights::pallet_preimage::WeightInfo;
 }
 
 parameter_types! {
@@ -453,6 +476,8 @@ parameter_types! {
 	pub const MaxIterations: u32 = 10;
 	// 0.05%. The higher the value, the more strict solution acceptance becomes.
 	pub MinSolutionScoreBump: Perbill = Perbill::from_rational_approximation(5u32, 10_000);
+	// a payout of a fully nominated validator takes most of the scheduler's block weight.
+	pub const MaxAutoPayoutsPerBlock: u32 = 1;
 }
 
 impl pallet_staking::Trait for Runtime {
@@ -470,7 +495,7 @@ impl pallet_staking::Trait for Runtime {
 	type SlashCancelOrigin = EnsureOneOf<
 		AccountId,
 		EnsureRoot<AccountId>,
-		pallet_collective::EnsureProportionAtLeast<_3, _4, AccountId, CouncilCollective>,
+		pallet_collective::EnsureWeightedProportionAtLeast<_3, _4, AccountId, CouncilCollective>,
 		>;
 	type SessionInterface = Self;
 	type RewardCurve = RewardCurve;
@@ -481,62 +506,140 @@ impl pallet_staking::Trait for Runtime {
 	type MinSolutionScoreBump = MinSolutionScoreBump;
 	type MaxNominatorRewardedPerValidator = MaxNominatorRewardedPerValidator;
 	type UnsignedPriority = StakingUnsignedPriority;
+	type Scheduler = Scheduler;
+	type PalletsOrigin = OriginCaller;
+	type MaxAutoPayoutsPerBlock = MaxAutoPayoutsPerBlock;
 	type WeightInfo = weights::pallet_staking::WeightInfo;
 }
 
 parameter_types! {
-	pub const LaunchPeriod: BlockNumber = 28 * DAYS;
-	pub const VotingPeriod: BlockNumber = 28 * DAYS;
-	pub const FastTrackVotingPeriod: BlockNumber = 3 * HOURS;
-	pub const InstantAllowed: bool = true;
-	pub const MinimumDeposit: Balance = 100 * DOLLARS;
-	pub const EnactmentPeriod: BlockNumber = 28 * DAYS;
-	pub const CooloffPeriod: BlockNumber =  7 * DAYS;
-	// One cent: $10,000 / MB
-	pub const PreimageByteDeposit: Balance = 1 * CENTS;
+	pub const VoteLockingPeriod: BlockNumber = 7 * DAYS;
 	pub const MaxVotes: u32 = 100;
 }
 
-impl pallet_democracy::Trait for Runtime {
-	type Proposal = Call;
+impl pallet_conviction_voting::Trait for Runtime {
 	type Event = Event;
 	type Currency = Balances;
-	type EnactmentPeriod = EnactmentPeriod;
-	type LaunchPeriod = LaunchPeriod;
-	type VotingPeriod = VotingPeriod;
-	type MinimumDeposit = MinimumDeposit;
-	/// A straight majority of the council can decide what their next motion is.
-	type ExternalOrigin =
-		pallet_collective::EnsureProportionAtLeast<_1, _2, AccountId, CouncilCollective>;
-	/// A super-majority can have the next scheduled referendum be a straight majority-carries vote.
-	type ExternalMajorityOrigin =
-	pallet_collective::EnsureProportionAtLeast<_3, _4, AccountId, CouncilCollective>;
-	/// A unanimous council can have the next scheduled referendum be a straight default-carries
-	/// (NTB) vote.
-	type ExternalDefaultOrigin =
-		pallet_collective::EnsureProportionAtLeast<_1, _1, AccountId, CouncilCollective>;
-	/// Two thirds of the technical committee can have an ExternalMajority/ExternalDefault vote
-	/// be tabled immediately and with a shorter voting/enactment period.
-	type FastTrackOrigin =
-		pallet_collective::EnsureProportionAtLeast<_2, _3, AccountId, TechnicalCollective>;
-	type InstantOrigin =
-		pallet_collective::EnsureProportionAtLeast<_1, _1, AccountId, TechnicalCollective>;
-	type InstantAllowed = InstantAllowed;
-	type FastTrackVotingPeriod = FastTrackVotingPeriod;
-	// To cancel a proposal which has been passed, 2/3 of the council must agree to it.
-	type CancellationOrigin =
-		pallet_collective::EnsureProportionAtLeast<_2, _3, AccountId, CouncilCollective>;
-	// Any single technical committee member may veto a coming council proposal, however they can
-	// only do it once and it lasts only for the cooloff period.
-	type VetoOrigin = pallet_collective::EnsureMember<AccountId, TechnicalCollective>;
-	type CooloffPeriod = CooloffPeriod;
-	type PreimageByteDeposit = PreimageByteDeposit;
-	type OperationalPreimageOrigin = pallet_collective::EnsureMember<AccountId, CouncilCollective>;
-	type Slash = Treasury;
+	type Polls = Referenda;
+	type MaxVotes = MaxVotes;
+	type VoteLockingPeriod = VoteLockingPeriod;
+	type WeightInfo = weights::pallet_conviction_voting::WeightInfo;
+}
+
+parameter_types! {
+	pub const SubmissionDeposit: Balance = 1 * DOLLARS;
+	pub const MaxQueued: u32 = 100;
+	pub const UndecidingTimeout: BlockNumber = 28 * DAYS;
+	pub const AlarmInterval: BlockNumber = 1 * HOURS;
+	pub const TreasurerTrack: pallet_referenda::TrackId = 1;
+	pub const ReferendumCancellerTrack: pallet_referenda::TrackId = 2;
+}
+
+/// The tracks of referenda.
+///
+/// - `root`: proposals dispatched with the _Root_ origin, such as runtime upgrades.
+/// - `treasurer`: treasury spends, see `pallet_treasury::Trait::ApproveOrigin`.
+/// - `referendum_canceller`: cancellation of other referenda.
+///
+/// There is no parameter-change track: the parameters that can be changed without a runtime
+/// upgrade, such as the validator count, are set by `ensure_root` dispatchables, and all others
+/// are `parameter_types!` constants changed by a runtime upgrade. Both are decided on `root`.
+pub struct TracksInfo;
+impl pallet_referenda::TracksInfo<Balance, BlockNumber> for TracksInfo {
+	type Origin = OriginCaller;
+
+	fn tracks()
+		-> &'static [(pallet_referenda::TrackId, pallet_referenda::TrackInfo<Balance, BlockNumber>)]
+	{
+		use pallet_referenda::{TrackInfo, Curve};
+		static DATA: [(pallet_referenda::TrackId, TrackInfo<Balance, BlockNumber>); 3] = [
+			(0, TrackInfo {
+				name: "root",
+				max_deciding: 1,
+				decision_deposit: 1_000 * DOLLARS,
+				prepare_period: 3 * HOURS,
+				decision_period: 28 * DAYS,
+				confirm_period: 3 * DAYS,
+				min_enactment_period: 1 * DAYS,
+				min_approval: Curve::LinearDecreasing {
+					begin: Perbill::from_percent(100),
+					delta: Perbill::from_percent(50),
+				},
+				min_support: Curve::LinearDecreasing {
+					begin: Perbill::from_percent(50),
+					delta: Perbill::from_percent(50),
+				},
+			}),
+			(1, TrackInfo {
+				name: "treasurer",
+				max_deciding: 10,
+				decision_deposit: 100 * DOLLARS,
+				prepare_period: 3 * HOURS,
+				decision_period: 28 * DAYS,
+				confirm_period: 1 * DAYS,
+				min_enactment_period: 1 * DAYS,
+				min_approval: Curve::LinearDecreasing {
+					begin: Perbill::from_percent(100),
+					delta: Perbill::from_percent(50),
+				},
+				min_support: Curve::SteppedDecreasing {
+					begin: Perbill::from_percent(10),
+					end: Perbill::from_percent(1),
+					step: Perbill::from_percent(1),
+					period: Perbill::from_percent(10),
+				},
+			}),
+			(2, TrackInfo {
+				name: "referendum_canceller",
+				max_deciding: 10,
+				decision_deposit: 500 * DOLLARS,
+				prepare_period: 1 * HOURS,
+				decision_period: 7 * DAYS,
+				confirm_period: 3 * HOURS,
+				min_enactment_period: 10 * MINUTES,
+				min_approval: Curve::LinearDecreasing {
+					begin: Perbill::from_percent(100),
+					delta: Perbill::from_percent(50),
+				},
+				min_support: Curve::LinearDecreasing {
+					begin: Perbill::from_percent(10),
+					delta: Perbill::from_percent(9),
+				},
+			}),
+		];
+		&DATA[..]
+	}
+
+	fn track_for(origin: &Self::Origin) -> Result<pallet_referenda::TrackId, ()> {
+		match origin {
+			OriginCaller::system(frame_system::RawOrigin::Root) => Ok(0),
+			OriginCaller::pallet_referenda(pallet_referenda::Origin::Track(id))
+				if *id == TreasurerTrack::get() || *id == ReferendumCancellerTrack::get() => Ok(*id),
+			_ => Err(()),
+		}
+	}
+}
+
+impl pallet_referenda::Trait for Runtime {
+	type Event = Event;
+	type Call = Call;
+	type Currency = Balances;
 	type Scheduler = Scheduler;
 	type PalletsOrigin = OriginCaller;
-	type MaxVotes = MaxVotes;
-	type WeightInfo = weights::pallet_democracy::WeightInfo;
+	type CancelOrigin = EnsureOneOf<
+		AccountId,
+		EnsureRoot<AccountId>,
+		pallet_referenda::EnsureTrack<ReferendumCancellerTrack>,
+	>;
+	type KillOrigin = EnsureRoot<AccountId>;
+	type Slash = Treasury;
+	type Tally = pallet_conviction_voting::TallyOf<Runtime>;
+	type SubmissionDeposit = SubmissionDeposit;
+	type MaxQueued = MaxQueued;
+	type UndecidingTimeout = UndecidingTimeout;
+	type AlarmInterval = AlarmInterval;
+	type Tracks = TracksInfo;
+	type WeightInfo = weights::pallet_referenda::WeightInfo;
 }
 
 parameter_types! {
@@ -554,6 +657,9 @@ impl pallet_collective::Trait<CouncilCollective> for Runtime {
 	type MaxProposals = CouncilMaxProposals;
 	type MaxMembers = CouncilMaxMembers;
 	type DefaultVote = pallet_collective::PrimeDefaultVote;
+	// The proportions of the council are checked with the weighted origins, which also accept
+	// the motions of a weighted mode.
+	type VoteCounting = pallet_collective::OneMemberOneVote;
 	type WeightInfo = weights::pallet_collective::WeightInfo;
 }
 
@@ -604,13 +710,14 @@ impl pallet_collective::Trait<TechnicalCollective> for Runtime {
 	type MaxProposals = TechnicalMaxProposals;
 	type MaxMembers = TechnicalMaxMembers;
 	type DefaultVote = pallet_collective::PrimeDefaultVote;
+	type VoteCounting = pallet_collective::OneMemberOneVote;
 	type WeightInfo = weights::pallet_collective::WeightInfo;
 }
 
 type EnsureRootOrHalfCouncil = EnsureOneOf<
 	AccountId,
 	EnsureRoot<AccountId>,
-	pallet_collective::EnsureProportionMoreThan<_1, _2, AccountId, CouncilCollective>,
+	pallet_collective::EnsureWeightedProportionMoreThan<_1, _2, AccountId, CouncilCollective>,
 >;
 impl pallet_membership::Trait<pallet_membership::Instance1> for Runtime {
 	type Event = Event;
@@ -646,13 +753,21 @@ impl pallet_treasury::Trait for Runtime {
 	type Currency = Balances;
 	type ApproveOrigin = EnsureOneOf<
 		AccountId,
-		EnsureRoot<AccountId>,
-		pallet_collective::EnsureMembers<_4, AccountId, CouncilCollective>,
+		EnsureOneOf<
+			AccountId,
+			EnsureRoot<AccountId>,
+			pallet_collective::EnsureMembers<_4, AccountId, CouncilCollective>,
+		>,
+		pallet_referenda::EnsureTrack<TreasurerTrack>,
 	>;
 	type RejectOrigin = EnsureOneOf<
 		AccountId,
-		EnsureRoot<AccountId>,
-		pallet_collective::EnsureMembers<_2, AccountId, CouncilCollective>,
+		EnsureOneOf<
+			AccountId,
+			EnsureRoot<AccountId>,
+			pallet_collective::EnsureMembers<_2, AccountId, CouncilCollective>,
+		>,
+		pallet_referenda::EnsureTrack<TreasurerTrack>,
 	>;
 	type Tippers = Elections;
 	type TipCountdown = TipCountdown;
@@ -832,6 +947,8 @@ parameter_types! {
 	pub const MaxSubAccounts: u32 = 100;
 	pub const MaxAdditionalFields: u32 = 100;
 	pub const MaxRegistrars: u32 = 20;
+	pub const IdentityBidPeriod: BlockNumber = 7 * DAYS;
+	pub const JudgementExpiry: BlockNumber = 365 * DAYS;
 }
 
 impl pallet_identity::Trait for Runtime {
@@ -846,6 +963,8 @@ impl pallet_identity::Trait for Runtime {
 	type Slashed = Treasury;
 	type ForceOrigin = EnsureRootOrHalfCouncil;
 	type RegistrarOrigin = EnsureRootOrHalfCouncil;
+	type BidPeriod = IdentityBidPeriod;
+	type JudgementExpiry = JudgementExpiry;
 	type WeightInfo = weights::pallet_identity::WeightInfo;
 }
 
@@ -923,8 +1042,9 @@ construct_runtime!(
 		TransactionPayment: pallet_transaction_payment::{Module, Storage},
 		Staking: pallet_staking::{Module, Call, Config<T>, Storage, Event<T>, ValidateUnsigned},
 		Session: pallet_session::{Module, Call, Storage, Event, Config<T>},
-		Democracy: pallet_democracy::{Module, Call, Storage, Config, Event<T>},
-		Council: pallet_collective::<Instance1>::{Module, Call, Storage, Origin<T>, Event<T>, Config<T>},
+		// Index 10 belonged to the removed Democracy pallet, it is left unused so that the calls
+		// already in storage keep decoding.
+		Council: pallet_collective::<Instance1>::{Module, Call, Storage, Origin<T>, Event<T>, Config<T>} = 11,
 		TechnicalCommittee: pallet_collective::<Instance2>::{Module, Call, Storage, Origin<T>, Event<T>, Config<T>},
 		Elections: pallet_elections_phragmen::{Module, Call, Storage, Event<T>, Config<T>},
 		TechnicalMembership: pallet_membership::<Instance1>::{Module, Call, Storage, Event<T>, Config<T>},
@@ -945,6 +1065,9 @@ construct_runtime!(
 		Scheduler: pallet_scheduler::{Module, Call, Storage, Event<T>},
 		Proxy: pallet_proxy::{Module, Call, Storage, Event<T>},
 		Multisig: pallet_multisig::{Module, Call, Storage, Event<T>},
+		Referenda: pallet_referenda::{Module, Call, Storage, Origin, Event<T>},
+		ConvictionVoting: pallet_conviction_voting::{Module, Call, Storage, Event<T>},
+		Preimage: pallet_preimage::{Module, Call, Storage, Event<T>},
 	}
 );
 
@@ -985,6 +1108,8 @@ pub type Executive = frame_executive::Executive<
 	frame_system::ChainContext<Runtime>,
 	Runtime,
 	AllModules,
+	// The scheduler agendas are migrated first, the Democracy cleanup cancels tasks in them.
+	(Scheduler, migrations::RemoveDemocracy),
 >;
 
 impl_runtime_apis! {
@@ -1008,6 +1133,8 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl sp_api::DeterministicStackLimit<Block> for Runtime {}
+
 	impl sp_block_builder::BlockBuilder<Block> for Runtime {
 		fn apply_extrinsic(extrinsic: <Block as BlockT>::Extrinsic) -> ApplyExtrinsicResult {
 			Executive::apply_extrinsic(extrinsic)
@@ -1179,6 +1306,27 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_identity_rpc_runtime_api::IdentityApi<
+		Block,
+		AccountId,
+		Balance,
+		BlockNumber,
+	> for Runtime {
+		fn identity(
+			who: AccountId,
+		) -> pallet_identity_rpc_runtime_api::IdentityView<AccountId, Balance, BlockNumber> {
+			Identity::identity_view(&who)
+		}
+
+		fn registrars() -> Vec<(
+			pallet_identity_rpc_runtime_api::RegistrarIndex,
+			pallet_identity_rpc_runtime_api::RegistrarInfo<Balance, AccountId>,
+			Option<pallet_identity_rpc_runtime_api::RegistrarProfile>,
+		)> {
+			Identity::registrars_with_profiles()
+		}
+	}
+
 	impl sp_session::SessionKeys<Block> for Runtime {
 		fn generate_session_keys(seed: Option<Vec<u8>>) -> Vec<u8> {
 			SessionKeys::generate(seed)
@@ -1191,6 +1339,17 @@ impl_runtime_apis! {
 		}
 	}
 
+	#[cfg(feature = "try-runtime")]
+	impl frame_try_runtime::TryRuntime<Block> for Runtime {
+		fn on_runtime_upgrade() -> Weight {
+			Executive::execute_on_runtime_upgrade()
+		}
+
+		fn execute_block_no_check(block: Block) -> Weight {
+			Executive::execute_block_no_check(block)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn dispatch_benchmark(
@@ -1230,15 +1389,17 @@ impl_runtime_apis! {
 			add_benchmark!(params, batches, pallet_balances, Balances);
 			add_benchmark!(params, batches, pallet_collective, Council);
 			add_benchmark!(params, batches, pallet_contracts, Contracts);
-			add_benchmark!(params, batches, pallet_democracy, Democracy);
+			add_benchmark!(params, batches, pallet_conviction_voting, ConvictionVoting);
 			add_benchmark!(params, batches, pallet_elections_phragmen, Elections);
 			add_benchmark!(params, batches, pallet_grandpa, Grandpa);
 			add_benchmark!(params, batches, pallet_identity, Identity);
 			add_benchmark!(params, batches, pallet_im_online, ImOnline);
 			add_benchmark!(params, batches, pallet_indices, Indices);
 			add_benchmark!(params, batches, pallet_multisig, Multisig);
+			add_benchmark!(params, batches, pallet_preimage, Preimage);
 			add_benchmark!(params, batches, pallet_offences, OffencesBench::<Runtime>);
 			add_benchmark!(params, batches, pallet_proxy, Proxy);
+			add_benchmark!(params, batches, pallet_referenda, Referenda);
 			add_benchmark!(params, batches, pallet_scheduler, Scheduler);
 			add_benchmark!(params, batches, pallet_session, SessionBench::<Runtime>);
 			add_benchmark!(params, batches, pallet_staking, Staking);
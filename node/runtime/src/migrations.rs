@@ -0,0 +1,154 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Storage migrations of the runtime which do not belong to a single pallet.
+
+use sp_std::prelude::*;
+use codec::{Decode, Encode};
+use frame_support::{
+	storage::{migration::{get_storage_value, have_storage_value, StorageIterator}, unhashed},
+	traits::{schedule::Named, Get, LockIdentifier, LockableCurrency, OnRuntimeUpgrade, ReservableCurrency},
+	weights::Weight,
+	StorageHasher, Twox128,
+};
+use node_primitives::{AccountId, Balance, BlockNumber};
+use crate::{Balances, Call, OriginCaller, Runtime, Scheduler};
+
+/// Lock identifier and scheduler name prefix used by the removed Democracy pallet.
+const DEMOCRACY_ID: LockIdentifier = *b"democrac";
+
+/// Storage prefix of the removed Democracy pallet.
+const DEMOCRACY: &[u8] = b"Democracy";
+
+/// Mirror of the Democracy pallet's `PreimageStatus`, to find the deposits of preimages.
+#[derive(Decode)]
+enum PreimageStatus {
+	Missing(#[allow(dead_code)] BlockNumber),
+	Available {
+		#[allow(dead_code)]
+		data: Vec<u8>,
+		provider: AccountId,
+		deposit: Balance,
+		#[allow(dead_code)]
+		since: BlockNumber,
+		#[allow(dead_code)]
+		expiry: Option<BlockNumber>,
+	},
+}
+
+/// Removes what is left of the Democracy pallet, replaced by Referenda and ConvictionVoting.
+///
+/// Vote locks, proposal deposits and preimage deposits are released, enactments still waiting in
+/// the scheduler are cancelled since their calls no longer decode, and the storage of the pallet
+/// is cleared. Does nothing once the Democracy storage is gone.
+///
+/// Must run after the scheduler migrated its agendas to their current format, as cancelling
+/// writes them back.
+pub struct RemoveDemocracy;
+
+impl OnRuntimeUpgrade for RemoveDemocracy {
+	fn on_runtime_upgrade() -> Weight {
+		// Written at genesis and only removed by this migration.
+		if !have_storage_value(DEMOCRACY, b"ReferendumCount", &[]) {
+			return <Runtime as frame_system::Trait>::DbWeight::get().reads(1);
+		}
+
+		let mut reads = 1;
+		let mut writes = 0;
+
+		let mut locked = Vec::new();
+		for (who, locks) in pallet_balances::Locks::<Runtime>::iter() {
+			reads += 1;
+			if locks.iter().any(|lock| lock.id == DEMOCRACY_ID) {
+				locked.push(who);
+			}
+		}
+		for who in locked {
+			<Balances as LockableCurrency<AccountId>>::remove_lock(DEMOCRACY_ID, &who);
+			writes += 1;
+		}
+
+		for (depositors, deposit) in
+			StorageIterator::<(Vec<AccountId>, Balance)>::new(DEMOCRACY, b"DepositOf")
+		{
+			reads += 1;
+			for who in depositors {
+				Balances::unreserve(&who, deposit);
+				writes += 1;
+			}
+		}
+
+		for status in StorageIterator::<PreimageStatus>::new(DEMOCRACY, b"Preimages") {
+			reads += 1;
+			if let PreimageStatus::Available { provider, deposit, .. } = status {
+				Balances::unreserve(&provider, deposit);
+				writes += 1;
+			}
+		}
+
+		let referendum_count = get_storage_value::<u32>(DEMOCRACY, b"ReferendumCount", &[])
+			.unwrap_or_default();
+		for index in 0..referendum_count {
+			// Only approved referenda still waiting for their enactment are scheduled.
+			let name = (DEMOCRACY_ID, index).encode();
+			if <Scheduler as Named<BlockNumber, Call, OriginCaller>>::cancel_named(name).is_ok() {
+				writes += 2;
+			}
+			reads += 1;
+		}
+
+		unhashed::kill_prefix(&Twox128::hash(DEMOCRACY));
+		writes += 1;
+
+		<Runtime as frame_system::Trait>::DbWeight::get().reads_writes(reads, writes)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use frame_support::{storage::migration::put_storage_value, Hashable};
+	use crate::{Executive, System};
+
+	#[test]
+	fn remove_democracy_cancels_enactments_of_v2_agendas() {
+		let mut t: sp_io::TestExternalities =
+			frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap().into();
+		t.execute_with(|| {
+			System::set_block_number(1);
+			let name = (DEMOCRACY_ID, 0u32).encode();
+			let call = Call::System(frame_system::Call::remark(vec![]));
+			let origin = OriginCaller::system(frame_system::RawOrigin::Root);
+			// `ScheduledV2` with the call stored as a plain value, as before the preimage pallet.
+			let agenda = vec![
+				Some((Some(name.clone()), 63u8, call.clone(), None::<(BlockNumber, u32)>, origin.clone())),
+				Some((None, 63u8, call, None::<(BlockNumber, u32)>, origin)),
+			];
+			put_storage_value(b"Scheduler", b"Agenda", &10u32.twox_64_concat(), agenda);
+			put_storage_value(b"Scheduler", b"Lookup", &name.twox_64_concat(), (10u32, 0u32));
+			// `Releases::V2` of the scheduler.
+			put_storage_value(b"Scheduler", b"StorageVersion", &[], 1u8);
+			put_storage_value(DEMOCRACY, b"ReferendumCount", &[], 1u32);
+
+			Executive::execute_on_runtime_upgrade();
+
+			let agenda = pallet_scheduler::Agenda::<Runtime>::get(10);
+			assert_eq!(agenda.iter().map(Option::is_some).collect::<Vec<_>>(), vec![false, true]);
+			assert!(!have_storage_value(DEMOCRACY, b"ReferendumCount", &[]));
+		});
+	}
+}
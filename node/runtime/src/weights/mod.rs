@@ -21,12 +21,14 @@ pub mod frame_system;
 pub mod pallet_balances;
 pub mod pallet_treasury;
 pub mod pallet_collective;
-pub mod pallet_democracy;
+pub mod pallet_conviction_voting;
 pub mod pallet_identity;
 pub mod pallet_indices;
 pub mod pallet_im_online;
 pub mod pallet_multisig;
+pub mod pallet_preimage;
 pub mod pallet_proxy;
+pub mod pallet_referenda;
 pub mod pallet_scheduler;
 pub mod pallet_session;
 pub mod pallet_staking;
@@ -96,4 +96,8 @@ impl pallet_collective::WeightInfo for WeightInfo {
 			.saturating_add(DbWeight::get().reads(1 as Weight))
 			.saturating_add(DbWeight::get().writes(3 as Weight))
 	}
+	fn count_votes(m: u32, ) -> Weight {
+		(1265000 as Weight)
+			.saturating_add((97000 as Weight).saturating_mul(m as Weight))
+	}
 }
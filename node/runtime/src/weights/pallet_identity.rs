@@ -133,4 +133,40 @@ impl pallet_identity::WeightInfo for WeightInfo {
 			.saturating_add(DbWeight::get().reads(2 as Weight))
 			.saturating_add(DbWeight::get().writes(2 as Weight))
 	}
+	fn set_profile(r: u32, ) -> Weight {
+		(24_817_000 as Weight)
+			.saturating_add((381_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().writes(2 as Weight))
+	}
+	fn request_bids() -> Weight {
+		(58_412_000 as Weight)
+			.saturating_add(DbWeight::get().reads(2 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn bid_judgement(r: u32, ) -> Weight {
+		(41_906_000 as Weight)
+			.saturating_add((702_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(DbWeight::get().reads(2 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn accept_bid(r: u32, x: u32, ) -> Weight {
+		(104_322_000 as Weight)
+			.saturating_add((731_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add((3_208_000 as Weight).saturating_mul(x as Weight))
+			.saturating_add(DbWeight::get().reads(2 as Weight))
+			.saturating_add(DbWeight::get().writes(3 as Weight))
+	}
+	fn cancel_bids() -> Weight {
+		(55_160_000 as Weight)
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn expire_judgement(r: u32, x: u32, ) -> Weight {
+		(69_504_000 as Weight)
+			.saturating_add((402_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add((3_176_000 as Weight).saturating_mul(x as Weight))
+			.saturating_add(DbWeight::get().reads(2 as Weight))
+			.saturating_add(DbWeight::get().writes(2 as Weight))
+	}
 }
@@ -0,0 +1,53 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Weights for pallet_preimage.
+//!
+//! The storage reads and writes are those of the worst cases in the pallet's benchmarks. The
+//! execution times are provisional until this file is regenerated on reference hardware with:
+//! `benchmark --chain dev --execution wasm --wasm-execution compiled --pallet pallet_preimage
+//! --extrinsic '*' --steps 50 --repeat 20 --output node/runtime/src/weights/pallet_preimage.rs`
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::weights::{Weight, constants::RocksDbWeight as DbWeight};
+
+pub struct WeightInfo;
+impl pallet_preimage::WeightInfo for WeightInfo {
+	fn note_preimage(s: u32, ) -> Weight {
+		(43_000_000 as Weight)
+			.saturating_add((4_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(DbWeight::get().reads(3 as Weight))
+			.saturating_add(DbWeight::get().writes(3 as Weight))
+	}
+	fn unnote_preimage() -> Weight {
+		(46_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(2 as Weight))
+			.saturating_add(DbWeight::get().writes(3 as Weight))
+	}
+	fn request_preimage() -> Weight {
+		(38_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(2 as Weight))
+			.saturating_add(DbWeight::get().writes(2 as Weight))
+	}
+	fn unrequest_preimage() -> Weight {
+		(42_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(2 as Weight))
+			.saturating_add(DbWeight::get().writes(2 as Weight))
+	}
+}
@@ -0,0 +1,62 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Weights for pallet_referenda.
+//!
+//! The storage reads and writes are those of the worst cases in the pallet's benchmarks. The
+//! execution times are provisional until this file is regenerated on reference hardware with:
+//! `benchmark --chain dev --execution wasm --wasm-execution compiled --pallet pallet_referenda
+//! --extrinsic '*' --steps 50 --repeat 20 --output node/runtime/src/weights/pallet_referenda.rs`
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::weights::{Weight, constants::RocksDbWeight as DbWeight};
+
+pub struct WeightInfo;
+impl pallet_referenda::WeightInfo for WeightInfo {
+	fn submit() -> Weight {
+		(87_424_000 as Weight)
+			.saturating_add(DbWeight::get().reads(4 as Weight))
+			.saturating_add(DbWeight::get().writes(5 as Weight))
+	}
+	fn place_decision_deposit() -> Weight {
+		(96_300_000 as Weight)
+			.saturating_add(DbWeight::get().reads(7 as Weight))
+			.saturating_add(DbWeight::get().writes(7 as Weight))
+	}
+	fn refund_decision_deposit() -> Weight {
+		(42_618_000 as Weight)
+			.saturating_add(DbWeight::get().reads(2 as Weight))
+			.saturating_add(DbWeight::get().writes(2 as Weight))
+	}
+	fn cancel() -> Weight {
+		(63_150_000 as Weight)
+			.saturating_add(DbWeight::get().reads(10 as Weight))
+			.saturating_add(DbWeight::get().writes(11 as Weight))
+	}
+	fn kill() -> Weight {
+		(101_536_000 as Weight)
+			.saturating_add(DbWeight::get().reads(11 as Weight))
+			.saturating_add(DbWeight::get().writes(12 as Weight))
+	}
+	fn nudge_referendum() -> Weight {
+		(118_762_000 as Weight)
+			.saturating_add(DbWeight::get().reads(11 as Weight))
+			.saturating_add(DbWeight::get().writes(11 as Weight))
+	}
+}
@@ -69,6 +69,11 @@ impl pallet_staking::WeightInfo for WeightInfo {
 			.saturating_add(DbWeight::get().reads(2 as Weight))
 			.saturating_add(DbWeight::get().writes(2 as Weight))
 	}
+	fn set_auto_payout() -> Weight {
+		(25014000 as Weight)
+			.saturating_add(DbWeight::get().reads(2 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
 	fn set_payee() -> Weight {
 		(24255000 as Weight)
 			.saturating_add(DbWeight::get().reads(1 as Weight))
@@ -27,7 +27,7 @@ use std::{sync::Arc, path::{Path, PathBuf}, collections::BTreeMap};
 use node_primitives::Block;
 use crate::client::{Client, Backend};
 use crate::keyring::*;
-use sc_client_db::PruningMode;
+use sc_client_db::{BlocksPruning, PruningMode};
 use sc_executor::{NativeExecutor, WasmExecutionMethod};
 use sp_consensus::{
 	BlockOrigin, BlockImport, BlockImportParams,
@@ -344,6 +344,7 @@ impl BenchDb {
 			state_cache_size: 16*1024*1024,
 			state_cache_child_ratio: Some((0, 100)),
 			pruning: PruningMode::ArchiveAll,
+			blocks_pruning: BlocksPruning::All,
 			source: database_type.into_settings(dir.into()),
 		};
 
@@ -0,0 +1,67 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Checks that both node flavours produce and finalize blocks on a local network.
+//!
+//! These tests spawn full and light nodes and take a while, run them with
+//! `cargo test --release -p node-testing --test consensus -- --ignored`.
+
+use node_cli::{chain_spec, service};
+
+#[test]
+#[ignore]
+fn babe_grandpa_produces_and_finalizes_blocks() {
+	sc_service_test::consensus(
+		chain_spec::local_testnet_config(),
+		|config| {
+			let service::NewFullBase { task_manager, client, network, transaction_pool, .. } =
+				service::new_full_base(config, |_, _| ())?;
+			Ok(sc_service_test::TestNetComponents::new(task_manager, client, network, transaction_pool))
+		},
+		|config| {
+			let (keep_alive, _, client, network, transaction_pool) = service::new_light_base(config)?;
+			Ok(sc_service_test::TestNetComponents::new(keep_alive, client, network, transaction_pool))
+		},
+		vec![
+			"//Alice".into(),
+			"//Bob".into(),
+		],
+	)
+}
+
+#[test]
+#[ignore]
+fn aura_grandpa_produces_and_finalizes_blocks() {
+	sc_service_test::consensus(
+		chain_spec::permissioned::local_testnet_config(),
+		|config| {
+			let service::permissioned::NewFullBase { task_manager, client, network, transaction_pool } =
+				service::permissioned::new_full_base(config)?;
+			Ok(sc_service_test::TestNetComponents::new(task_manager, client, network, transaction_pool))
+		},
+		|config| {
+			let (keep_alive, _, client, network, transaction_pool) =
+				service::permissioned::new_light_base(config)?;
+			Ok(sc_service_test::TestNetComponents::new(keep_alive, client, network, transaction_pool))
+		},
+		vec![
+			"//Alice".into(),
+			"//Bob".into(),
+		],
+	)
+}
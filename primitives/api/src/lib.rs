@@ -593,4 +593,10 @@ decl_runtime_apis! {
 		/// Returns the metadata of a runtime.
 		fn metadata() -> OpaqueMetadata;
 	}
+
+	/// Marker api of the runtimes executed with the deterministic stack height limit.
+	///
+	/// The api has no functions, implementing it opts the runtime into the limit. Older runtimes
+	/// are executed without it, so that the blocks they built are imported the same way as before.
+	pub trait DeterministicStackLimit {}
 }
@@ -18,7 +18,7 @@
 //! Block import helpers.
 
 use sp_runtime::traits::{Block as BlockT, DigestItemFor, Header as HeaderT, NumberFor, HashFor};
-use sp_runtime::Justification;
+use sp_runtime::{Justification, Storage};
 use serde::{Serialize, Deserialize};
 use std::borrow::Cow;
 use std::collections::HashMap;
@@ -113,6 +113,34 @@ pub struct BlockCheckParams<Block: BlockT> {
 	pub import_existing: bool,
 }
 
+/// Full state of a block, downloaded from the network instead of being built by
+/// executing the block.
+#[derive(Clone)]
+pub struct ImportedState<Block: BlockT> {
+	/// Hash of the block the state belongs to.
+	pub block: Block::Hash,
+	/// Key/value pairs of the state.
+	pub state: Storage,
+}
+
+impl<Block: BlockT> PartialEq for ImportedState<Block> {
+	fn eq(&self, other: &Self) -> bool {
+		self.block == other.block
+	}
+}
+
+impl<Block: BlockT> Eq for ImportedState<Block> {}
+
+impl<Block: BlockT> std::fmt::Debug for ImportedState<Block> {
+	fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+		fmt.debug_struct("ImportedState")
+			.field("block", &self.block)
+			.field("top_entries", &self.state.top.len())
+			.field("children", &self.state.children_default.len())
+			.finish()
+	}
+}
+
 /// Data required to import a Block.
 #[non_exhaustive]
 pub struct BlockImportParams<Block: BlockT, Transaction> {
@@ -164,6 +192,9 @@ pub struct BlockImportParams<Block: BlockT, Transaction> {
 	pub allow_missing_state: bool,
 	/// Re-validate existing block.
 	pub import_existing: bool,
+	/// Full state of the block, if it was downloaded instead of being built by executing
+	/// the block. The block is then imported without its parent state.
+	pub imported_state: Option<ImportedState<Block>>,
 	/// Cached full header hash (with post-digests applied).
 	pub post_hash: Option<Block::Hash>,
 }
@@ -186,6 +217,7 @@ impl<Block: BlockT, Transaction> BlockImportParams<Block, Transaction> {
 			fork_choice: None,
 			allow_missing_state: false,
 			import_existing: false,
+			imported_state: None,
 			post_hash: None,
 		}
 	}
@@ -226,6 +258,7 @@ impl<Block: BlockT, Transaction> BlockImportParams<Block, Transaction> {
 			allow_missing_state: self.allow_missing_state,
 			fork_choice: self.fork_choice,
 			import_existing: self.import_existing,
+			imported_state: self.imported_state,
 			post_hash: self.post_hash,
 		}
 	}
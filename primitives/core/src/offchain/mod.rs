@@ -53,6 +53,19 @@ pub trait OffchainStorage: Clone + Send + Sync {
 		old_value: Option<&[u8]>,
 		new_value: &[u8],
 	) -> bool;
+
+	/// Retrieve up to `count` entries written through the offchain indexing API, ordered by key.
+	///
+	/// Only keys starting with `key_prefix`, strictly greater than `start_key` and strictly
+	/// lower than `end_key` are returned. Keys and values are those stored under
+	/// [`STORAGE_PREFIX`], with the prefix itself stripped from the keys.
+	fn indexed_entries(
+		&self,
+		key_prefix: &[u8],
+		start_key: Option<&[u8]>,
+		end_key: Option<&[u8]>,
+		count: usize,
+	) -> Vec<(Vec<u8>, Vec<u8>)>;
 }
 
 /// A type of supported crypto.
@@ -43,6 +43,29 @@ impl InMemOffchainStorage {
 		let key: Vec<u8> = prefix.iter().chain(key).cloned().collect();
 		let _ = self.storage.remove(&key);
 	}
+
+	/// Up to `count` entries under `prefix` whose keys start with `key_prefix` and lie strictly
+	/// between `start_key` and `end_key`, ordered by key and with `prefix` stripped.
+	pub fn entries_in_range(
+		&self,
+		prefix: &[u8],
+		key_prefix: &[u8],
+		start_key: Option<&[u8]>,
+		end_key: Option<&[u8]>,
+		count: usize,
+	) -> Vec<(Vec<u8>, Vec<u8>)> {
+		let mut entries: Vec<_> = self.storage.iter()
+			.filter(|(k, _)| k.starts_with(prefix))
+			.map(|(k, v)| (&k[prefix.len()..], v))
+			.filter(|(k, _)| k.starts_with(key_prefix))
+			.filter(|(k, _)| start_key.map_or(true, |start| *k > start))
+			.filter(|(k, _)| end_key.map_or(true, |end| *k < end))
+			.map(|(k, v)| (k.to_vec(), v.clone()))
+			.collect();
+		entries.sort();
+		entries.truncate(count);
+		entries
+	}
 }
 
 impl OffchainStorage for InMemOffchainStorage {
@@ -82,6 +105,16 @@ impl OffchainStorage for InMemOffchainStorage {
 			_ => false,
 		}
 	}
+
+	fn indexed_entries(
+		&self,
+		key_prefix: &[u8],
+		start_key: Option<&[u8]>,
+		end_key: Option<&[u8]>,
+		count: usize,
+	) -> Vec<(Vec<u8>, Vec<u8>)> {
+		self.entries_in_range(super::STORAGE_PREFIX, key_prefix, start_key, end_key, count)
+	}
 }
 
 
@@ -111,6 +111,17 @@ impl OffchainStorage for TestPersistentOffchainDB {
 	) -> bool {
 		self.persistent.write().compare_and_set(prefix, key, old_value, new_value)
 	}
+
+	fn indexed_entries(
+		&self,
+		key_prefix: &[u8],
+		start_key: Option<&[u8]>,
+		end_key: Option<&[u8]>,
+		count: usize,
+	) -> Vec<(Vec<u8>, Vec<u8>)> {
+		// indexed changes are applied without prefix, see `apply_offchain_changes`.
+		self.persistent.read().entries_in_range(b"", key_prefix, start_key, end_key, count)
+	}
 }
 
 
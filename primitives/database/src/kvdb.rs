@@ -54,6 +54,20 @@ impl<D: KeyValueDB, H: Clone> Database<H> for DbAdapter<D> {
 		handle_err(self.0.get(col, key))
 	}
 
+	fn iter_with_prefix(
+		&self,
+		col: ColumnId,
+		prefix: &[u8],
+		f: &mut dyn FnMut(&[u8], &[u8]) -> bool,
+	) -> error::Result<()> {
+		for (key, value) in self.0.iter_with_prefix(col, prefix) {
+			if !f(&key, &value) {
+				break;
+			}
+		}
+		Ok(())
+	}
+
 	fn lookup(&self, _hash: &H) -> Option<Vec<u8>> {
 		unimplemented!();
 	}
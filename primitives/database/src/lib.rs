@@ -123,6 +123,22 @@ pub trait Database<H: Clone>: Send + Sync {
 		self.get(col, key).map(|v| f(&v));
 	}
 
+	/// Call `f` with the key and value of every entry of `col` whose key starts with `prefix`,
+	/// in ascending order of the keys, until `f` returns `false`.
+	///
+	/// Returns an error if the database can't iterate over its keys.
+	fn iter_with_prefix(
+		&self,
+		_col: ColumnId,
+		_prefix: &[u8],
+		_f: &mut dyn FnMut(&[u8], &[u8]) -> bool,
+	) -> error::Result<()> {
+		Err(error::DatabaseError(Box::new(std::io::Error::new(
+			std::io::ErrorKind::Other,
+			"iteration over the keys is not supported by this database",
+		))))
+	}
+
 	/// Set the value of `key` in `col` to `value`, replacing anything that is there currently.
 	fn set(&self, col: ColumnId, key: &[u8], value: &[u8]) -> error::Result<()> {
 		let mut t = Transaction::new();
@@ -48,6 +48,25 @@ impl<H> Database<H> for MemDb<H>
 		s.0.get(&col).and_then(|c| c.get(key).cloned())
 	}
 
+	fn iter_with_prefix(
+		&self,
+		col: ColumnId,
+		prefix: &[u8],
+		f: &mut dyn FnMut(&[u8], &[u8]) -> bool,
+	) -> error::Result<()> {
+		let s = self.0.read();
+		let mut entries = s.0.get(&col)
+			.map(|c| c.iter().filter(|(k, _)| k.starts_with(prefix)).collect::<Vec<_>>())
+			.unwrap_or_default();
+		entries.sort_by(|a, b| a.0.cmp(b.0));
+		for (key, value) in entries {
+			if !f(key, value) {
+				break;
+			}
+		}
+		Ok(())
+	}
+
 	fn lookup(&self, hash: &H) -> Option<Vec<u8>> {
 		let s = self.0.read();
 		s.1.get(hash).cloned()
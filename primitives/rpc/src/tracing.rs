@@ -0,0 +1,133 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Types for the traces of block execution returned over RPC.
+
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+
+/// Container for all related spans and events for the block being traced.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockTrace {
+	/// Hash of the block being traced.
+	pub block_hash: String,
+	/// Parent hash.
+	pub parent_hash: String,
+	/// Module targets that were recorded by the tracing subscriber.
+	/// Empty string means record all targets.
+	pub tracing_targets: String,
+	/// Storage key prefixes of the recorded storage events, empty if all keys are recorded.
+	pub storage_keys: String,
+	/// Spans, sorted by the order they were closed in.
+	pub spans: Vec<Span>,
+	/// Events, sorted by the order they were emitted in.
+	pub events: Vec<Event>,
+}
+
+/// Represents a tracing event, complete with recorded data.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Event {
+	/// Event target.
+	pub target: String,
+	/// Associated data.
+	pub data: Data,
+	/// Parent id, if it exists.
+	pub parent_id: Option<u64>,
+}
+
+/// Represents a single instance of a tracing span.
+///
+/// Exiting a span does not imply that the span will not be re-entered.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Span {
+	/// Id for this span.
+	pub id: u64,
+	/// Id of the parent span, if any.
+	pub parent_id: Option<u64>,
+	/// Name of this span.
+	pub name: String,
+	/// Target, typically module.
+	pub target: String,
+	/// Indicates if the span is from wasm.
+	pub wasm: bool,
+	/// Total time spent inside the span, in nanoseconds.
+	pub overall_time: u64,
+	/// Associated data.
+	pub data: Data,
+}
+
+/// Holds associated values for a tracing span.
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Data {
+	/// HashMap of `String` values recorded while tracing.
+	pub string_values: HashMap<String, String>,
+}
+
+/// Error response for the `state_traceBlock` RPC.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceError {
+	/// Error message.
+	pub error: String,
+}
+
+/// Response for the `state_traceBlock` RPC.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum TraceBlockResponse {
+	/// Error block tracing response.
+	TraceError(TraceError),
+	/// Successful block tracing response.
+	BlockTrace(BlockTrace),
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::assert_deser;
+
+	#[test]
+	fn should_serialize_and_deserialize() {
+		assert_deser(
+			r#"{"traceError":{"error":"Invalid block"}}"#,
+			TraceBlockResponse::TraceError(TraceError { error: "Invalid block".into() }),
+		);
+		assert_deser(
+			r#"{"blockTrace":{"blockHash":"0x01","parentHash":"0x00","tracingTargets":"state","storageKeys":"","spans":[{"id":1,"parentId":null,"name":"execute_block","target":"frame_executive","wasm":false,"overallTime":10,"data":{"stringValues":{}}}],"events":[]}}"#,
+			TraceBlockResponse::BlockTrace(BlockTrace {
+				block_hash: "0x01".into(),
+				parent_hash: "0x00".into(),
+				tracing_targets: "state".into(),
+				storage_keys: "".into(),
+				spans: vec![Span {
+					id: 1,
+					parent_id: None,
+					name: "execute_block".into(),
+					target: "frame_executive".into(),
+					wasm: false,
+					overall_time: 10,
+					data: Default::default(),
+				}],
+				events: vec![],
+			}),
+		);
+	}
+}
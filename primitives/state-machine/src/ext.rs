@@ -226,6 +226,13 @@ where
 			HexDisplay::from(&key),
 			result.as_ref().map(HexDisplay::from)
 		);
+		#[cfg(feature = "std")]
+		tracing::trace!(target: "state",
+			method = "Get",
+			ext_id = self.id,
+			key = %HexDisplay::from(&key),
+			result = ?result.as_ref().map(HexDisplay::from)
+		);
 		result
 	}
 
@@ -264,6 +271,14 @@ where
 			HexDisplay::from(&key),
 			result.as_ref().map(HexDisplay::from)
 		);
+		#[cfg(feature = "std")]
+		tracing::trace!(target: "state",
+			method = "ChildGet",
+			ext_id = self.id,
+			child_info = %HexDisplay::from(&child_info.storage_key()),
+			key = %HexDisplay::from(&key),
+			result = ?result.as_ref().map(HexDisplay::from)
+		);
 
 		result
 	}
@@ -379,6 +394,13 @@ where
 			HexDisplay::from(&key),
 			value.as_ref().map(HexDisplay::from)
 		);
+		#[cfg(feature = "std")]
+		tracing::trace!(target: "state",
+			method = "Put",
+			ext_id = self.id,
+			key = %HexDisplay::from(&key),
+			value = ?value.as_ref().map(HexDisplay::from)
+		);
 		let _guard = guard();
 		if is_child_storage_key(&key) {
 			warn!(target: "trie", "Refuse to directly set child storage key");
@@ -401,6 +423,14 @@ where
 			HexDisplay::from(&key),
 			value.as_ref().map(HexDisplay::from)
 		);
+		#[cfg(feature = "std")]
+		tracing::trace!(target: "state",
+			method = "ChildPut",
+			ext_id = self.id,
+			child_info = %HexDisplay::from(&child_info.storage_key()),
+			key = %HexDisplay::from(&key),
+			value = ?value.as_ref().map(HexDisplay::from)
+		);
 		let _guard = guard();
 
 		self.mark_dirty();
@@ -863,6 +863,131 @@ mod execution {
 		proving_backend.child_storage(child_info, key)
 			.map_err(|e| Box::new(e) as Box<dyn Error>)
 	}
+
+	/// Generate a proof of the storage entries following `start_at` in key order, in the top
+	/// trie or in the given child trie.
+	///
+	/// Entries are read until the size of their keys and values reaches `size_limit` or the end of
+	/// the trie is reached. Returns the entries, whether the end of the trie was reached and the
+	/// proof. If `start_at` is empty, the entry at the empty key is included.
+	pub fn prove_range_read_with_size<B, H>(
+		mut backend: B,
+		child_info: Option<&ChildInfo>,
+		start_at: &[u8],
+		size_limit: usize,
+	) -> Result<(Vec<(Vec<u8>, Vec<u8>)>, bool, StorageProof), Box<dyn Error>>
+	where
+		B: Backend<H>,
+		H: Hasher,
+		H::Out: Ord + Codec,
+	{
+		let trie_backend = backend.as_trie_backend()
+			.ok_or_else(|| Box::new(ExecutionError::UnableToGenerateProof) as Box<dyn Error>)?;
+		let proving_backend = proving_backend::ProvingBackend::<_, H>::new(trie_backend);
+		let mut entries = Vec::new();
+		let mut size = 0;
+		let mut key = start_at.to_vec();
+		if start_at.is_empty() {
+			if let Some(value) = read_range_value(&proving_backend, child_info, &[])? {
+				size += value.len();
+				entries.push((Vec::new(), value));
+			}
+		}
+		let complete = loop {
+			if size >= size_limit {
+				break false;
+			}
+			match read_range_next_key(&proving_backend, child_info, &key)? {
+				Some(next) => {
+					let value = read_range_value(&proving_backend, child_info, &next)?
+						.ok_or_else(|| Box::new("Missing value of an existing key") as Box<dyn Error>)?;
+					size += next.len() + value.len();
+					entries.push((next.clone(), value));
+					key = next;
+				},
+				None => break true,
+			}
+		};
+		Ok((entries, complete, proving_backend.extract_proof()))
+	}
+
+	/// Check a proof generated by `prove_range_read_with_size`.
+	///
+	/// Succeeds if `entries` are exactly the storage entries following `start_at` and, if
+	/// `complete` is set, the last entries of the trie.
+	pub fn read_range_proof_check<H>(
+		root: H::Out,
+		proof: StorageProof,
+		child_info: Option<&ChildInfo>,
+		start_at: &[u8],
+		entries: &[(Vec<u8>, Vec<u8>)],
+		complete: bool,
+	) -> Result<(), Box<dyn Error>>
+	where
+		H: Hasher,
+		H::Out: Ord + Codec,
+	{
+		fn mismatch(key: &[u8]) -> Box<dyn Error> {
+			Box::new(format!("Range proof does not match entry {}", HexDisplay::from(&key)))
+		}
+
+		let proving_backend = create_proof_check_backend::<H>(root, proof)?;
+
+		let mut entries = entries.iter().peekable();
+		let mut key = start_at.to_vec();
+		if start_at.is_empty() {
+			let value = read_range_value(&proving_backend, child_info, &[])?;
+			match entries.peek() {
+				Some((k, v)) if k.is_empty() => {
+					if value.as_ref() != Some(v) {
+						return Err(mismatch(k));
+					}
+					entries.next();
+				},
+				_ => if value.is_some() {
+					return Err(mismatch(&[]));
+				},
+			}
+		}
+		for (k, v) in entries {
+			let next = read_range_next_key(&proving_backend, child_info, &key)?;
+			if next.as_ref() != Some(k) {
+				return Err(mismatch(k));
+			}
+			if read_range_value(&proving_backend, child_info, k)?.as_ref() != Some(v) {
+				return Err(mismatch(k));
+			}
+			key = k.clone();
+		}
+		if complete {
+			if let Some(next) = read_range_next_key(&proving_backend, child_info, &key)? {
+				return Err(mismatch(&next));
+			}
+		}
+		Ok(())
+	}
+
+	fn read_range_next_key<B: Backend<H>, H: Hasher>(
+		backend: &B,
+		child_info: Option<&ChildInfo>,
+		key: &[u8],
+	) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+		match child_info {
+			Some(child_info) => backend.next_child_storage_key(child_info, key),
+			None => backend.next_storage_key(key),
+		}.map_err(|e| Box::new(e) as Box<dyn Error>)
+	}
+
+	fn read_range_value<B: Backend<H>, H: Hasher>(
+		backend: &B,
+		child_info: Option<&ChildInfo>,
+		key: &[u8],
+	) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+		match child_info {
+			Some(child_info) => backend.child_storage(child_info, key),
+			None => backend.storage(key),
+		}.map_err(|e| Box::new(e) as Box<dyn Error>)
+	}
 }
 
 #[cfg(test)]
@@ -1344,6 +1469,68 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn prove_range_read_and_proof_check_works() {
+		let child_info = ChildInfo::new_default(b"sub1");
+		let remote_backend = trie_backend::tests::test_trie();
+		let remote_root = remote_backend.storage_root(::std::iter::empty()).0;
+		let child_root = remote_backend.storage(&child_info.prefixed_storage_key()).unwrap().unwrap();
+		let child_root = <BlakeTwo256 as sp_core::Hasher>::Out::decode(&mut &child_root[..]).unwrap();
+
+		for (root, child) in vec![(remote_root, None), (child_root, Some(&child_info))] {
+			let expected = match child {
+				Some(child) => remote_backend.child_keys(child, &[]).into_iter()
+					.map(|k| {
+						let v = remote_backend.child_storage(child, &k).unwrap().unwrap();
+						(k, v)
+					})
+					.collect::<Vec<_>>(),
+				None => remote_backend.pairs(),
+			};
+
+			// read the whole trie in small chunks, checking each of them.
+			let mut collected = Vec::new();
+			let mut start = Vec::new();
+			loop {
+				let (entries, complete, proof) = prove_range_read_with_size(
+					trie_backend::tests::test_trie(),
+					child,
+					&start,
+					16,
+				).unwrap();
+				read_range_proof_check::<BlakeTwo256>(
+					root,
+					proof.clone(),
+					child,
+					&start,
+					&entries,
+					complete,
+				).unwrap();
+
+				// a chunk with a missing entry or wrongly claimed to be the last one is rejected.
+				if entries.len() > 1 {
+					let mut tampered = entries.clone();
+					tampered.remove(0);
+					assert!(read_range_proof_check::<BlakeTwo256>(
+						root, proof.clone(), child, &start, &tampered, complete,
+					).is_err());
+				}
+				if !complete {
+					assert!(read_range_proof_check::<BlakeTwo256>(
+						root, proof, child, &start, &entries, true,
+					).is_err());
+				}
+
+				start = entries.last().map(|(k, _)| k.clone()).unwrap_or_default();
+				collected.extend(entries);
+				if complete {
+					break;
+				}
+			}
+			assert_eq!(collected, expected);
+		}
+	}
+
 	#[test]
 	fn prove_read_and_proof_check_works() {
 		let child_info = ChildInfo::new_default(b"sub1");
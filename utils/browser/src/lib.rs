@@ -87,12 +87,14 @@ where
 		offchain_worker: Default::default(),
 		prometheus_config: Default::default(),
 		pruning: Default::default(),
+		blocks_pruning: Default::default(),
 		rpc_cors: Default::default(),
 		rpc_http: Default::default(),
 		rpc_ipc: Default::default(),
 		rpc_ws: Default::default(),
 		rpc_ws_max_connections: Default::default(),
 		rpc_methods: Default::default(),
+		rpc_limits: Default::default(),
 		state_cache_child_ratio: Default::default(),
 		state_cache_size: Default::default(),
 		tracing_receiver: Default::default(),
@@ -15,7 +15,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::BenchmarkCmd;
+use crate::PalletCmd;
 use codec::{Decode, Encode};
 use frame_benchmarking::{Analysis, BenchmarkBatch, BenchmarkSelector};
 use sc_cli::{SharedParams, CliConfiguration, ExecutionStrategy, Result};
@@ -32,7 +32,7 @@ use sp_core::{
 };
 use std::fmt::Debug;
 
-impl BenchmarkCmd {
+impl PalletCmd {
 	/// Runs the command and benchmarks the chain.
 	pub fn run<BB, ExecDispatch>(&self, config: Configuration) -> Result<()>
 	where
@@ -41,6 +41,10 @@ impl BenchmarkCmd {
 		<BB as BlockT>::Hash: std::str::FromStr,
 		ExecDispatch: NativeExecutionDispatch + 'static,
 	{
+		let (pallet, extrinsic) = match (&self.pallet, &self.extrinsic) {
+			(Some(pallet), Some(extrinsic)) => (pallet, extrinsic),
+			_ => return Err("Both `--pallet` and `--extrinsic` must be given.".into()),
+		};
 		let spec = config.chain_spec;
 		let wasm_method = self.wasm_method.into();
 		let strategy = self.execution.unwrap_or(ExecutionStrategy::Native);
@@ -69,8 +73,8 @@ impl BenchmarkCmd {
 			&executor,
 			"Benchmark_dispatch_benchmark",
 			&(
-				&self.pallet,
-				&self.extrinsic,
+				pallet,
+				extrinsic,
 				self.lowest_range_values.clone(),
 				self.highest_range_values.clone(),
 				self.steps.clone(),
@@ -90,6 +94,10 @@ impl BenchmarkCmd {
 
 		match results {
 			Ok(batches) => {
+				if let Some(ref path) = self.json_file {
+					crate::writer::write_json(path, &batches)?;
+				}
+
 				// If we are going to output results to a file...
 				if self.output {
 					if self.weight_trait {
@@ -172,7 +180,7 @@ impl BenchmarkCmd {
 	}
 }
 
-impl CliConfiguration for BenchmarkCmd {
+impl CliConfiguration for PalletCmd {
 	fn shared_params(&self) -> &SharedParams {
 		&self.shared_params
 	}
@@ -0,0 +1,283 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use frame_benchmarking::{Analysis, BenchmarkBatch, BenchmarkResults, BenchmarkSelector};
+use sc_cli::Result;
+use std::{collections::BTreeMap, fmt, fs::File, io::BufReader, path::{Path, PathBuf}};
+
+/// The `benchmark compare` command used to detect weight regressions between two benchmark runs.
+///
+/// Every extrinsic is compared by the extrinsic time, reads and writes of its min-squares model,
+/// evaluated at the highest components of the new results. The command fails if any of them grew
+/// by more than the threshold.
+#[derive(Debug, structopt::StructOpt)]
+pub struct CompareCmd {
+	/// The JSON results of the baseline run.
+	#[structopt(parse(from_os_str), value_name = "OLD")]
+	pub old: PathBuf,
+
+	/// The JSON results of the run to check for regressions.
+	#[structopt(parse(from_os_str), value_name = "NEW")]
+	pub new: PathBuf,
+
+	/// Increase in percent above which a change counts as regression.
+	#[structopt(long, default_value = "10")]
+	pub threshold: f64,
+}
+
+impl CompareCmd {
+	/// Runs the command, failing if there are regressions.
+	pub fn run(&self) -> Result<()> {
+		let comparison = compare(&read_results(&self.old)?, &read_results(&self.new)?);
+
+		for change in &comparison.changes {
+			let marker = if change.is_regression(self.threshold) { "  REGRESSION" } else { "" };
+			println!("{}{}", change, marker);
+		}
+		for name in &comparison.added {
+			println!("{}: not in the old results", name);
+		}
+		for name in &comparison.removed {
+			println!("{}: not in the new results", name);
+		}
+
+		let regressions = comparison.regressions(self.threshold);
+		if regressions > 0 {
+			return Err(format!(
+				"{} regressions above {}% between {} and {}",
+				regressions,
+				self.threshold,
+				self.old.display(),
+				self.new.display(),
+			).into())
+		}
+		println!("No regressions above {}%", self.threshold);
+
+		Ok(())
+	}
+}
+
+fn read_results(path: &Path) -> Result<Vec<BenchmarkBatch>> {
+	let file = File::open(path)?;
+	serde_json::from_reader(BufReader::new(file))
+		.map_err(|e| format!("Failed to read the benchmark results of {}: {}", path.display(), e).into())
+}
+
+/// The metrics of a benchmark which are compared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+	/// Execution time of the extrinsic in nanoseconds.
+	ExtrinsicTime,
+	/// Number of database reads.
+	Reads,
+	/// Number of database writes.
+	Writes,
+}
+
+impl Metric {
+	const ALL: [Metric; 3] = [Metric::ExtrinsicTime, Metric::Reads, Metric::Writes];
+
+	fn selector(self) -> BenchmarkSelector {
+		match self {
+			Metric::ExtrinsicTime => BenchmarkSelector::ExtrinsicTime,
+			Metric::Reads => BenchmarkSelector::Reads,
+			Metric::Writes => BenchmarkSelector::Writes,
+		}
+	}
+}
+
+/// The change of one metric of a benchmark between two runs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+	/// `pallet::benchmark`.
+	pub name: String,
+	/// The compared metric.
+	pub metric: Metric,
+	/// Value in the old results.
+	pub old: u128,
+	/// Value in the new results.
+	pub new: u128,
+}
+
+impl Change {
+	/// The relative change in percent, positive if the new value is larger.
+	pub fn percent(&self) -> f64 {
+		if self.old == 0 {
+			return if self.new == 0 { 0.0 } else { f64::INFINITY }
+		}
+		(self.new as f64 - self.old as f64) * 100.0 / self.old as f64
+	}
+
+	/// Whether the value grew by more than `threshold` percent.
+	pub fn is_regression(&self, threshold: f64) -> bool {
+		self.percent() > threshold
+	}
+}
+
+impl fmt::Display for Change {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let unit = if self.metric == Metric::ExtrinsicTime { " ns" } else { "" };
+		write!(
+			f,
+			"{}: {:?} {}{} -> {}{} ({:+.2}%)",
+			self.name, self.metric, self.old, unit, self.new, unit, self.percent(),
+		)
+	}
+}
+
+/// The differences between two sets of benchmark results.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Comparison {
+	/// Changes of the benchmarks in both sets.
+	pub changes: Vec<Change>,
+	/// Benchmarks only in the new set.
+	pub added: Vec<String>,
+	/// Benchmarks only in the old set.
+	pub removed: Vec<String>,
+}
+
+impl Comparison {
+	/// Number of changes which are regressions above `threshold` percent.
+	pub fn regressions(&self, threshold: f64) -> usize {
+		self.changes.iter().filter(|c| c.is_regression(threshold)).count()
+	}
+}
+
+/// Compare the benchmarks of `new` to the ones of `old`.
+pub fn compare(old: &[BenchmarkBatch], new: &[BenchmarkBatch]) -> Comparison {
+	let mut old = by_name(old);
+	let mut comparison = Comparison::default();
+
+	for (name, new_batch) in by_name(new) {
+		let old_batch = match old.remove(&name) {
+			Some(batch) => batch,
+			None => {
+				comparison.added.push(name);
+				continue
+			},
+		};
+		let components = highest_components(&new_batch.results);
+		for metric in Metric::ALL.iter() {
+			let values = (
+				evaluate(old_batch, *metric, &components),
+				evaluate(new_batch, *metric, &components),
+			);
+			if let (Some(old), Some(new)) = values {
+				comparison.changes.push(Change { name: name.clone(), metric: *metric, old, new });
+			}
+		}
+	}
+	comparison.removed = old.into_iter().map(|(name, _)| name).collect();
+
+	comparison
+}
+
+/// Every benchmark with results, by `pallet::benchmark`.
+fn by_name(batches: &[BenchmarkBatch]) -> BTreeMap<String, &BenchmarkBatch> {
+	batches.iter()
+		.filter(|batch| !batch.results.is_empty())
+		.map(|batch| (
+			format!(
+				"{}::{}",
+				String::from_utf8_lossy(&batch.pallet),
+				String::from_utf8_lossy(&batch.benchmark),
+			),
+			batch,
+		))
+		.collect()
+}
+
+/// The highest value of every component in `results`, by name.
+fn highest_components(results: &[BenchmarkResults]) -> BTreeMap<String, u32> {
+	let mut highest = BTreeMap::new();
+	for (param, value) in results.iter().flat_map(|r| r.components.iter()) {
+		let entry = highest.entry(format!("{:?}", param)).or_insert(*value);
+		*entry = (*entry).max(*value);
+	}
+	highest
+}
+
+/// The min-squares model of `metric` evaluated at `components`.
+fn evaluate(
+	batch: &BenchmarkBatch,
+	metric: Metric,
+	components: &BTreeMap<String, u32>,
+) -> Option<u128> {
+	let analysis = Analysis::min_squares_iqr(&batch.results, metric.selector())?;
+	let slopes = analysis.slopes.iter().zip(analysis.names.iter())
+		.map(|(slope, name)| slope.saturating_mul(components.get(name).copied().unwrap_or(0).into()))
+		.fold(0u128, |acc, x| acc.saturating_add(x));
+
+	Some(analysis.base.saturating_add(slopes))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use frame_benchmarking::BenchmarkParameter;
+
+	fn batch(benchmark: &str, base: u128, per_item: u128, reads: u32) -> BenchmarkBatch {
+		BenchmarkBatch {
+			pallet: b"pallet_balances".to_vec(),
+			benchmark: benchmark.as_bytes().to_vec(),
+			results: (1..=10).map(|n| BenchmarkResults {
+				components: vec![(BenchmarkParameter::n, n)],
+				extrinsic_time: base + per_item * n as u128,
+				reads,
+				..Default::default()
+			}).collect(),
+		}
+	}
+
+	#[test]
+	fn reports_regressions_above_threshold() {
+		let old = vec![batch("transfer", 1000, 100, 2), batch("set_balance", 1000, 0, 1)];
+		let new = vec![batch("transfer", 1000, 150, 2), batch("set_balance", 1050, 0, 1)];
+
+		let comparison = compare(&old, &new);
+
+		let time = |name: &str| comparison.changes.iter()
+			.find(|c| c.name == name && c.metric == Metric::ExtrinsicTime)
+			.unwrap()
+			.clone();
+		assert_eq!((time("pallet_balances::transfer").old, time("pallet_balances::transfer").new), (2000, 2500));
+		assert_eq!(time("pallet_balances::set_balance").percent(), 5.0);
+		assert_eq!(comparison.regressions(10.0), 1);
+		assert_eq!(comparison.regressions(30.0), 0);
+	}
+
+	#[test]
+	fn reports_added_and_removed_benchmarks() {
+		let old = vec![batch("transfer", 1000, 100, 2), batch("force_transfer", 1000, 100, 2)];
+		let new = vec![batch("transfer", 1000, 100, 3), batch("transfer_keep_alive", 1000, 100, 2)];
+
+		let comparison = compare(&old, &new);
+
+		assert_eq!(comparison.added, vec!["pallet_balances::transfer_keep_alive".to_string()]);
+		assert_eq!(comparison.removed, vec!["pallet_balances::force_transfer".to_string()]);
+		assert_eq!(comparison.regressions(10.0), 1);
+	}
+
+	#[test]
+	fn results_roundtrip_through_json() {
+		let batches = vec![batch("transfer", 1000, 100, 2)];
+		let json = serde_json::to_string(&batches).unwrap();
+
+		assert!(json.contains("\"pallet_balances\""));
+		assert_eq!(serde_json::from_str::<Vec<BenchmarkBatch>>(&json).unwrap(), batches);
+	}
+}
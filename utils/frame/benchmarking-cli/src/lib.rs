@@ -16,21 +16,65 @@
 // limitations under the License.
 
 mod command;
+mod compare;
+mod overhead;
+mod stats;
+mod storage;
 mod writer;
 
+pub use compare::CompareCmd;
+pub use overhead::OverheadCmd;
+pub use stats::{Stats, StatSelector};
+pub use storage::StorageCmd;
+
 use sc_cli::{ExecutionStrategy, WasmExecutionMethod};
-use std::fmt::Debug;
+use std::{fmt::Debug, path::PathBuf};
+use structopt::clap::AppSettings;
 
-/// The `benchmark` command used to benchmark FRAME Pallets.
+/// The `benchmark` command used to benchmark FRAME Pallets and the node itself.
+///
+/// Without a subcommand the arguments of `benchmark pallet` are accepted directly, so that
+/// `benchmark --pallet ...` keeps working.
 #[derive(Debug, structopt::StructOpt)]
+#[structopt(
+	setting = AppSettings::SubcommandsNegateReqs,
+	setting = AppSettings::ArgsNegateSubcommands,
+)]
 pub struct BenchmarkCmd {
+	#[allow(missing_docs)]
+	#[structopt(subcommand)]
+	pub subcommand: Option<BenchmarkSubcommand>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pallet: PalletCmd,
+}
+
+/// The benchmarks of the `benchmark` command.
+#[derive(Debug, structopt::StructOpt)]
+pub enum BenchmarkSubcommand {
+	/// Benchmark the extrinsics of FRAME Pallets.
+	Pallet(PalletCmd),
+	/// Benchmark the read and write latency of the database, to derive `RuntimeDbWeight`.
+	Storage(StorageCmd),
+	/// Benchmark the execution time of empty blocks and no-op extrinsics, to derive
+	/// `BlockExecutionWeight` and `ExtrinsicBaseWeight`.
+	Overhead(OverheadCmd),
+	/// Compare two sets of pallet benchmark results written by `--json-file`.
+	Compare(CompareCmd),
+}
+
+/// The `benchmark pallet` command used to benchmark FRAME Pallets.
+#[derive(Debug, structopt::StructOpt)]
+pub struct PalletCmd {
 	/// Select a FRAME Pallet to benchmark, or `*` for all (in which case `extrinsic` must be `*`).
-	#[structopt(short, long)]
-	pub pallet: String,
+	// Optional for the parser only: it is not parsed when `benchmark` is given a subcommand.
+	#[structopt(short, long, required = true)]
+	pub pallet: Option<String>,
 
 	/// Select an extrinsic inside the pallet to benchmark, or `*` for all.
-	#[structopt(short, long)]
-	pub extrinsic: String,
+	#[structopt(short, long, required = true)]
+	pub extrinsic: Option<String>,
 
 	/// Select how many samples we should take across the variable components.
 	#[structopt(short, long, use_delimiter = true)]
@@ -68,6 +112,10 @@ pub struct BenchmarkCmd {
 	#[structopt(long)]
 	pub weight_trait: bool,
 
+	/// Write the raw results as JSON to this file, to be diffed by `benchmark compare`.
+	#[structopt(long, parse(from_os_str), value_name = "PATH")]
+	pub json_file: Option<PathBuf>,
+
 	/// Set the heap pages while running benchmarks.
 	#[structopt(long)]
 	pub heap_pages: Option<u64>,
@@ -107,3 +155,29 @@ pub struct BenchmarkCmd {
 	#[structopt(long = "db-cache", value_name = "MiB", default_value = "128")]
 	pub database_cache_size: u32,
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use structopt::StructOpt;
+
+	#[test]
+	fn pallet_arguments_are_accepted_without_subcommand() {
+		let cmd = BenchmarkCmd::from_iter(&["benchmark", "--pallet", "*", "--extrinsic", "*"]);
+		assert!(cmd.subcommand.is_none());
+		assert_eq!(cmd.pallet.pallet.as_deref(), Some("*"));
+		assert_eq!(cmd.pallet.extrinsic.as_deref(), Some("*"));
+
+		let cmd = BenchmarkCmd::from_iter(&["benchmark", "pallet", "--pallet", "*", "--extrinsic", "*"]);
+		match cmd.subcommand {
+			Some(BenchmarkSubcommand::Pallet(cmd)) => assert_eq!(cmd.pallet.as_deref(), Some("*")),
+			_ => panic!("expected the pallet subcommand"),
+		}
+
+		let cmd = BenchmarkCmd::from_iter(&["benchmark", "compare", "old.json", "new.json"]);
+		assert!(matches!(cmd.subcommand, Some(BenchmarkSubcommand::Compare(_))));
+
+		assert!(BenchmarkCmd::from_iter_safe(&["benchmark"]).is_err());
+		assert!(BenchmarkCmd::from_iter_safe(&["benchmark", "pallet", "--pallet", "*"]).is_err());
+	}
+}
@@ -0,0 +1,185 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{writer, Stats, StatSelector};
+use sc_block_builder::{BlockBuilderApi, BlockBuilderProvider};
+use sc_cli::{CliConfiguration, ImportParams, Result, SharedParams};
+use sc_client_api::backend::{Backend, StateBackendFor};
+use sp_api::{ApiExt, Core, ProvideRuntimeApi};
+use sp_consensus::RecordProof;
+use sp_blockchain::HeaderBackend;
+use sp_inherents::InherentData;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+use std::{path::PathBuf, sync::Arc, time::Instant};
+
+/// The `benchmark overhead` command used to benchmark the fixed costs of blocks and extrinsics.
+///
+/// Two blocks are built on top of the best block: one holding only the inherents, and one holding
+/// the inherents plus as many no-op extrinsics as requested. Both are then executed repeatedly on
+/// the state of the best block. The execution time of the empty block is the
+/// `BlockExecutionWeight`, the additional time of the full block divided by its number of
+/// extrinsics the `ExtrinsicBaseWeight`.
+#[derive(Debug, structopt::StructOpt)]
+pub struct OverheadCmd {
+	/// Number of no-op extrinsics in the full block.
+	#[structopt(long, default_value = "1000")]
+	pub extrinsics: u32,
+
+	/// How often each block is executed.
+	#[structopt(long, default_value = "20")]
+	pub repeat: u32,
+
+	/// The statistic of the samples to use as weight.
+	#[structopt(
+		long,
+		value_name = "STATISTIC",
+		possible_values = &StatSelector::variants(),
+		case_insensitive = true,
+		default_value = "Average",
+	)]
+	pub weight_metric: StatSelector,
+
+	/// Write the `BlockExecutionWeight` and `ExtrinsicBaseWeight` to a Rust file at this path.
+	#[structopt(long, parse(from_os_str), value_name = "PATH")]
+	pub output: Option<PathBuf>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub import_params: ImportParams,
+}
+
+impl OverheadCmd {
+	/// Runs the command against the best block of `client`.
+	///
+	/// `extrinsic` builds the no-op extrinsic with the given index in the full block, each of them
+	/// must be valid after all extrinsics with a lower index.
+	pub fn run<B, BA, C>(
+		&self,
+		client: Arc<C>,
+		inherent_data: InherentData,
+		extrinsic: impl Fn(u32) -> B::Extrinsic,
+	) -> Result<()>
+	where
+		B: BlockT,
+		BA: Backend<B>,
+		C: BlockBuilderProvider<BA, B, C> + ProvideRuntimeApi<B> + HeaderBackend<B>,
+		C::Api: Core<B, Error = sp_blockchain::Error>
+			+ BlockBuilderApi<B, Error = sp_blockchain::Error>
+			+ ApiExt<B, StateBackend = StateBackendFor<BA, B>>,
+	{
+		if self.extrinsics == 0 {
+			return Err("At least one extrinsic is needed to measure the ExtrinsicBaseWeight".into())
+		}
+		let parent = BlockId::Hash(client.info().best_hash);
+
+		let (empty, _) = build_block(&*client, &parent, inherent_data.clone(), std::iter::empty())?;
+		let (full, count) = build_block(
+			&*client,
+			&parent,
+			inherent_data,
+			(0..self.extrinsics).map(extrinsic),
+		)?;
+		if count < self.extrinsics {
+			println!("Only {} of {} extrinsics fit into the full block", count, self.extrinsics);
+		}
+		if count == 0 {
+			return Err("Not a single extrinsic could be pushed to the full block".into())
+		}
+
+		let empty_stats = self.execute(&*client, &parent, empty)?;
+		println!("Empty block: {}", empty_stats);
+		let full_stats = self.execute(&*client, &parent, full)?;
+		println!("Block with {} extrinsics: {}", count, full_stats);
+
+		let block_execution = empty_stats.select(self.weight_metric);
+		let extrinsic_base =
+			full_stats.select(self.weight_metric).saturating_sub(block_execution) / count as u64;
+		println!(
+			"{:?}: BlockExecutionWeight {} ns, ExtrinsicBaseWeight {} ns",
+			self.weight_metric, block_execution, extrinsic_base,
+		);
+
+		if let Some(ref path) = self.output {
+			let comment = format!(
+				"{:?} of {} executions of an empty block and of a block with {} extrinsics.",
+				self.weight_metric, self.repeat, count,
+			);
+			writer::write_overhead_weights(path, &comment, block_execution, extrinsic_base)?;
+			println!("Wrote BlockExecutionWeight and ExtrinsicBaseWeight to {}", path.display());
+		}
+
+		Ok(())
+	}
+
+	/// Execute `block` on top of `parent` `self.repeat` times.
+	fn execute<B, C>(&self, client: &C, parent: &BlockId<B>, block: B) -> Result<Stats>
+	where
+		B: BlockT,
+		C: ProvideRuntimeApi<B>,
+		C::Api: Core<B, Error = sp_blockchain::Error>,
+	{
+		let mut samples = Vec::with_capacity(self.repeat as usize);
+		for _ in 0..self.repeat {
+			let block = block.clone();
+			// A fresh runtime api for every execution, to not build on the changes of the last one.
+			let api = client.runtime_api();
+			let start = Instant::now();
+			api.execute_block(parent, block)
+				.map_err(|e| format!("Executing the block failed: {}", e))?;
+			samples.push(start.elapsed().as_nanos() as u64);
+		}
+
+		Ok(Stats::new(&samples)?)
+	}
+}
+
+/// Build a block on top of `parent` with the inherents and as many of `extrinsics` as fit.
+///
+/// Returns the block and the number of `extrinsics` in it.
+fn build_block<B, BA, C>(
+	client: &C,
+	parent: &BlockId<B>,
+	inherent_data: InherentData,
+	extrinsics: impl Iterator<Item = B::Extrinsic>,
+) -> Result<(B, u32)>
+where
+	B: BlockT,
+	BA: Backend<B>,
+	C: BlockBuilderProvider<BA, B, C> + ProvideRuntimeApi<B>,
+	C::Api: BlockBuilderApi<B, Error = sp_blockchain::Error>
+		+ ApiExt<B, StateBackend = StateBackendFor<BA, B>>,
+{
+	let mut builder = client.new_block_at(parent, Default::default(), RecordProof::No)?;
+	for inherent in builder.create_inherents(inherent_data)? {
+		builder.push(inherent)?;
+	}
+
+	let mut count = 0;
+	for extrinsic in extrinsics {
+		match builder.push(extrinsic) {
+			Ok(()) => count += 1,
+			Err(sp_blockchain::Error::ApplyExtrinsicFailed(_)) if count > 0 => break,
+			Err(e) => return Err(format!("Pushing extrinsic #{} failed: {}", count, e).into()),
+		}
+	}
+
+	Ok((builder.build()?.block, count))
+}
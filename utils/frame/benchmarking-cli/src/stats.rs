@@ -0,0 +1,132 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Statistics over the samples of the `storage` and `overhead` benchmarks.
+
+use std::fmt;
+use structopt::clap::arg_enum;
+
+arg_enum! {
+	/// The statistic of the samples used to derive a weight.
+	#[allow(missing_docs)]
+	#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+	pub enum StatSelector {
+		Min,
+		Average,
+		Median,
+		P95,
+		P99,
+		Max,
+	}
+}
+
+/// Statistics of a set of samples, all in nanoseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+	/// Number of samples.
+	pub count: usize,
+	/// Smallest sample.
+	pub min: u64,
+	/// Arithmetic mean of the samples.
+	pub avg: u64,
+	/// Median of the samples.
+	pub median: u64,
+	/// 95th percentile of the samples.
+	pub p95: u64,
+	/// 99th percentile of the samples.
+	pub p99: u64,
+	/// Largest sample.
+	pub max: u64,
+}
+
+impl Stats {
+	/// Calculate the statistics of `samples`, which must not be empty.
+	pub fn new(samples: &[u64]) -> Result<Self, String> {
+		if samples.is_empty() {
+			return Err("Can not calculate the statistics of zero samples".into())
+		}
+
+		let mut sorted = samples.to_vec();
+		sorted.sort_unstable();
+		let sum: u128 = sorted.iter().map(|s| *s as u128).sum();
+
+		Ok(Self {
+			count: sorted.len(),
+			min: sorted[0],
+			avg: (sum / sorted.len() as u128) as u64,
+			median: percentile(&sorted, 50),
+			p95: percentile(&sorted, 95),
+			p99: percentile(&sorted, 99),
+			max: sorted[sorted.len() - 1],
+		})
+	}
+
+	/// The selected statistic.
+	pub fn select(&self, selector: StatSelector) -> u64 {
+		match selector {
+			StatSelector::Min => self.min,
+			StatSelector::Average => self.avg,
+			StatSelector::Median => self.median,
+			StatSelector::P95 => self.p95,
+			StatSelector::P99 => self.p99,
+			StatSelector::Max => self.max,
+		}
+	}
+}
+
+/// The nearest-rank percentile of the non-empty `sorted` samples.
+fn percentile(sorted: &[u64], percent: usize) -> u64 {
+	let rank = (sorted.len() * percent + 99) / 100;
+	sorted[rank.max(1) - 1]
+}
+
+impl fmt::Display for Stats {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(
+			f,
+			"Total: {}, Min: {} ns, Average: {} ns, Median: {} ns, P95: {} ns, P99: {} ns, Max: {} ns",
+			self.count, self.min, self.avg, self.median, self.p95, self.p99, self.max,
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn stats_of_samples() {
+		let samples: Vec<u64> = (1..=100).rev().collect();
+		let stats = Stats::new(&samples).unwrap();
+
+		assert_eq!(stats.count, 100);
+		assert_eq!(stats.min, 1);
+		assert_eq!(stats.avg, 50);
+		assert_eq!(stats.median, 50);
+		assert_eq!(stats.p95, 95);
+		assert_eq!(stats.p99, 99);
+		assert_eq!(stats.max, 100);
+		assert_eq!(stats.select(StatSelector::P95), 95);
+	}
+
+	#[test]
+	fn stats_of_single_sample() {
+		let stats = Stats::new(&[7]).unwrap();
+		assert_eq!((stats.min, stats.median, stats.p99, stats.max), (7, 7, 7, 7));
+		assert!(Stats::new(&[]).is_err());
+	}
+}
@@ -0,0 +1,174 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{writer, Stats, StatSelector};
+use rand::{seq::SliceRandom, Rng};
+use sc_cli::{BlockNumberOrHash, CliConfiguration, ImportParams, Result, SharedParams};
+use sc_client_api::{Backend as _, StateBackend};
+use sc_client_db::Backend;
+use sc_service::{Configuration, DatabaseConfig};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::{Block as BlockT, NumberFor}};
+use std::{fmt::Debug, iter, path::PathBuf, str::FromStr, sync::Arc, time::Instant};
+
+/// The `benchmark storage` command used to benchmark the database of the node.
+///
+/// Storage keys are sampled from the state of a block. Every sampled key is read once, and, unless
+/// `--skip-write` is given, written once with a random value of the same length. Writes commit the
+/// changed trie nodes to the database and remove them again right away, leaving the database as it
+/// was.
+#[derive(Debug, structopt::StructOpt)]
+pub struct StorageCmd {
+	/// Block whose state is benchmarked, the best block if not given.
+	#[structopt(long, value_name = "HASH or NUMBER")]
+	pub at: Option<BlockNumberOrHash>,
+
+	/// Number of storage keys to sample.
+	#[structopt(long, default_value = "10000")]
+	pub keys: usize,
+
+	/// Only benchmark reads.
+	#[structopt(long, conflicts_with = "output")]
+	pub skip_write: bool,
+
+	/// The statistic of the samples to use as weight.
+	#[structopt(
+		long,
+		value_name = "STATISTIC",
+		possible_values = &StatSelector::variants(),
+		case_insensitive = true,
+		default_value = "Average",
+	)]
+	pub weight_metric: StatSelector,
+
+	/// Write the `RuntimeDbWeight` to a Rust file at this path.
+	#[structopt(long, parse(from_os_str), value_name = "PATH")]
+	pub output: Option<PathBuf>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub import_params: ImportParams,
+}
+
+impl StorageCmd {
+	/// Runs the command against the database of `backend`.
+	pub fn run<B>(&self, config: &Configuration, backend: Arc<Backend<B>>) -> Result<()>
+	where
+		B: BlockT,
+		B::Hash: FromStr,
+		<B::Hash as FromStr>::Err: Debug,
+		NumberFor<B>: FromStr,
+		<NumberFor<B> as FromStr>::Err: Debug,
+	{
+		let block = match self.at {
+			Some(ref at) => at.parse::<B>()?,
+			None => BlockId::Hash(backend.blockchain().info().best_hash),
+		};
+		let state = backend.state_at(block)?;
+		let mut rng = rand::thread_rng();
+
+		// Reservoir sampling, to not hold all keys of the state in memory.
+		let mut keys = Vec::with_capacity(self.keys);
+		let mut seen = 0usize;
+		state.for_keys_with_prefix(&[], |key| {
+			seen += 1;
+			if keys.len() < self.keys {
+				keys.push(key.to_vec());
+			} else {
+				let index = rng.gen_range(0, seen);
+				if index < self.keys {
+					keys[index] = key.to_vec();
+				}
+			}
+		});
+		if keys.is_empty() {
+			return Err(format!("The state of block {} is empty", block).into())
+		}
+		keys.shuffle(&mut rng);
+		println!("Sampled {} of the {} keys in the state of block {}", keys.len(), seen, block);
+
+		let mut reads = Vec::with_capacity(keys.len());
+		let mut lengths = Vec::with_capacity(keys.len());
+		for key in &keys {
+			let start = Instant::now();
+			let value = state.storage(key)?;
+			reads.push(start.elapsed().as_nanos() as u64);
+			lengths.push(value.map(|v| v.len()).unwrap_or_default());
+		}
+		let read_stats = Stats::new(&reads)?;
+		println!("Reads: {}", read_stats);
+
+		let write_stats = if self.skip_write {
+			None
+		} else {
+			let mut writes = Vec::with_capacity(keys.len());
+			for (key, len) in keys.iter().zip(lengths) {
+				let mut value = vec![0u8; len];
+				rng.fill(&mut value[..]);
+				let (_, nodes) = state.storage_root(iter::once((&key[..], Some(&value[..]))));
+				writes.push(backend.benchmark_state_write(nodes)?.as_nanos() as u64);
+			}
+			let write_stats = Stats::new(&writes)?;
+			println!("Writes: {}", write_stats);
+			Some(write_stats)
+		};
+
+		if let Some(ref path) = self.output {
+			let name = match config.database {
+				DatabaseConfig::RocksDb { .. } => "RocksDbWeight",
+				DatabaseConfig::ParityDb { .. } => "ParityDbWeight",
+				DatabaseConfig::Custom(_) => "DbWeight",
+			};
+			let comment = format!(
+				"{:?} of {} storage reads and {} writes at block {}.",
+				self.weight_metric,
+				read_stats.count,
+				write_stats.map(|s| s.count).unwrap_or_default(),
+				block,
+			);
+			writer::write_db_weight(
+				path,
+				name,
+				&comment,
+				read_stats.select(self.weight_metric),
+				write_stats.map(|s| s.select(self.weight_metric)).unwrap_or_default(),
+			)?;
+			println!("Wrote {} to {}", name, path.display());
+		}
+
+		Ok(())
+	}
+}
+
+impl CliConfiguration for StorageCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn import_params(&self) -> Option<&ImportParams> {
+		Some(&self.import_params)
+	}
+
+	// The state cache would answer repeated reads without touching the database.
+	fn state_cache_size(&self) -> Result<usize> {
+		Ok(0)
+	}
+}
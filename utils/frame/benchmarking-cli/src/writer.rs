@@ -19,12 +19,13 @@
 
 use std::fs::{File, OpenOptions};
 use std::io::prelude::*;
+use std::path::Path;
 use frame_benchmarking::{BenchmarkBatch, BenchmarkSelector, Analysis};
 use sp_runtime::traits::Zero;
 
 const VERSION: &'static str = env!("CARGO_PKG_VERSION");
 
-pub fn open_file(path: &str) -> Result<File, std::io::Error> {
+pub fn open_file<P: AsRef<Path>>(path: P) -> Result<File, std::io::Error> {
 	OpenOptions::new()
 		.create(true)
 		.write(true)
@@ -238,3 +239,71 @@ pub fn write_results(batches: &[BenchmarkBatch]) -> Result<(), std::io::Error> {
 
 	Ok(())
 }
+
+/// Write the raw results of `batches` as JSON to the file at `path`.
+pub fn write_json(path: &Path, batches: &[BenchmarkBatch]) -> Result<(), std::io::Error> {
+	let file = open_file(path)?;
+	serde_json::to_writer_pretty(file, batches)?;
+
+	Ok(())
+}
+
+/// Write the `RuntimeDbWeight` named `name` to the Rust file at `path`.
+pub fn write_db_weight(
+	path: &Path,
+	name: &str,
+	comment: &str,
+	read: u64,
+	write: u64,
+) -> Result<(), std::io::Error> {
+	let mut file = open_file(path)?;
+
+	write_constants_header(&mut file, comment)?;
+	write!(file, "use frame_support::{{parameter_types, weights::{{RuntimeDbWeight, constants::WEIGHT_PER_NANOS}}}};\n\n")?;
+	write!(file, "parameter_types! {{\n")?;
+	write!(file, "\tpub const {}: RuntimeDbWeight = RuntimeDbWeight {{\n", name)?;
+	write!(file, "\t\tread: {} * WEIGHT_PER_NANOS,\n", underscore(read))?;
+	write!(file, "\t\twrite: {} * WEIGHT_PER_NANOS,\n", underscore(write))?;
+	write!(file, "\t}};\n")?;
+	write!(file, "}}\n")?;
+
+	Ok(())
+}
+
+/// Write the `BlockExecutionWeight` and `ExtrinsicBaseWeight` to the Rust file at `path`.
+pub fn write_overhead_weights(
+	path: &Path,
+	comment: &str,
+	block_execution: u64,
+	extrinsic_base: u64,
+) -> Result<(), std::io::Error> {
+	let mut file = open_file(path)?;
+
+	write_constants_header(&mut file, comment)?;
+	write!(file, "use frame_support::{{parameter_types, weights::{{Weight, constants::WEIGHT_PER_NANOS}}}};\n\n")?;
+	write!(file, "parameter_types! {{\n")?;
+	write!(
+		file,
+		"\tpub const BlockExecutionWeight: Weight = {} * WEIGHT_PER_NANOS;\n",
+		underscore(block_execution),
+	)?;
+	write!(
+		file,
+		"\tpub const ExtrinsicBaseWeight: Weight = {} * WEIGHT_PER_NANOS;\n",
+		underscore(extrinsic_base),
+	)?;
+	write!(file, "}}\n")?;
+
+	Ok(())
+}
+
+fn write_constants_header(file: &mut File, comment: &str) -> Result<(), std::io::Error> {
+	write!(
+		file,
+		"//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION {}\n",
+		VERSION,
+	)?;
+	write!(file, "//! {}\n\n", comment)?;
+
+	Ok(())
+}
@@ -0,0 +1,161 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::TryRuntimeCmd;
+use codec::{Decode, Encode};
+use sc_cli::{CliConfiguration, DatabaseParams, PruningParams, Result, SharedParams};
+use sc_client_api::{BlockBackend, StorageProvider, UsageProvider};
+use sc_executor::NativeExecutor;
+use sc_service::{NativeExecutionDispatch, chain_ops::{export_raw_state, read_snapshot}};
+use sp_blockchain::HeaderBackend;
+use sp_core::{
+	hashing::blake2_256,
+	storage::{Storage, well_known_keys},
+	traits::{CallInWasm, MissingHostFunctions},
+};
+use sp_runtime::generic::BlockId;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, HashFor, NumberFor, One};
+use sp_state_machine::TestExternalities;
+use std::{fmt::Debug, fs, io, str::FromStr, sync::Arc};
+
+/// Weight of the `TryRuntime` calls, mirrors `frame_support::weights::Weight`.
+type Weight = u64;
+
+/// The new runtime, along with everything needed to call into it.
+struct Runtime<D: NativeExecutionDispatch + 'static> {
+	executor: NativeExecutor<D>,
+	code: Vec<u8>,
+	code_hash: Vec<u8>,
+}
+
+impl<D: NativeExecutionDispatch + 'static> Runtime<D> {
+	/// A fresh copy of `storage` with the code replaced by the new runtime.
+	fn externalities<B: BlockT>(&self, storage: &Storage) -> TestExternalities<HashFor<B>> {
+		let mut ext = TestExternalities::new(storage.clone());
+		ext.insert(well_known_keys::CODE.to_vec(), self.code.clone());
+		ext
+	}
+
+	/// Call `method` of the runtime and decode the returned weight.
+	///
+	/// Panics of the runtime are turned into errors.
+	fn call<B: BlockT>(
+		&self,
+		ext: &mut TestExternalities<HashFor<B>>,
+		method: &str,
+		data: &[u8],
+	) -> std::result::Result<Weight, String> {
+		let result = self.executor.call_in_wasm(
+			&self.code,
+			Some(self.code_hash.clone()),
+			method,
+			data,
+			&mut ext.ext(),
+			MissingHostFunctions::Disallow,
+		)?;
+
+		Weight::decode(&mut &result[..])
+			.map_err(|e| format!("Failed to decode the weight returned by {}: {}", method, e.what()))
+	}
+}
+
+impl TryRuntimeCmd {
+	/// Runs the command against the chain of `client`.
+	pub fn run<B, BA, C, D>(&self, client: Arc<C>) -> Result<()>
+	where
+		B: BlockT,
+		BA: sc_client_api::backend::Backend<B>,
+		C: UsageProvider<B> + StorageProvider<B, BA> + BlockBackend<B> + HeaderBackend<B>,
+		D: NativeExecutionDispatch + 'static,
+		B::Hash: FromStr,
+		<B::Hash as FromStr>::Err: Debug,
+		NumberFor<B>: FromStr,
+		<NumberFor<B> as FromStr>::Err: Debug,
+	{
+		let code = fs::read(&self.wasm)?;
+		let runtime = Runtime::<D> {
+			executor: NativeExecutor::new(self.wasm_method.into(), self.heap_pages, 2),
+			code_hash: blake2_256(&code).to_vec(),
+			code,
+		};
+
+		let (header, storage) = match self.snapshot {
+			Some(ref path) => {
+				let snapshot = read_snapshot::<B>(io::BufReader::new(fs::File::open(path)?))?;
+				let storage = snapshot.storage();
+				(snapshot.header, storage)
+			},
+			None => {
+				let block = match self.at {
+					Some(ref at) => at.parse::<B>()?,
+					None => BlockId::Hash(client.info().best_hash),
+				};
+				let header = client.header(block)?
+					.ok_or_else(|| format!("Unknown block {}", block))?;
+				let storage = export_raw_state(client.clone(), Some(BlockId::Hash(header.hash())))?;
+				(header, storage)
+			},
+		};
+		println!(
+			"Loaded the state of block #{} ({}): {} keys, {} child tries",
+			header.number(),
+			header.hash(),
+			storage.top.len(),
+			storage.children_default.len(),
+		);
+
+		let mut ext = runtime.externalities::<B>(&storage);
+		let weight = runtime.call::<B>(&mut ext, "TryRuntime_on_runtime_upgrade", &[])
+			.map_err(|e| format!("Executing the runtime upgrade failed: {}", e))?;
+		println!("Runtime upgrade executed, consuming {} weight", weight);
+
+		// Blocks are executed on top of the original state, their first one runs the upgrade
+		// again as part of its initialization.
+		let mut ext = runtime.externalities::<B>(&storage);
+		let mut number = *header.number();
+		for _ in 0..self.blocks {
+			number = number + One::one();
+			let block = client.block(&BlockId::Number(number))?
+				.ok_or_else(|| format!("Block #{} is not in the database", number))?
+				.block;
+			let hash = block.header().hash();
+
+			let weight = runtime.call::<B>(
+				&mut ext,
+				"TryRuntime_execute_block_no_check",
+				&block.encode(),
+			).map_err(|e| format!("Executing block #{} ({}) failed: {}", number, hash, e))?;
+			println!("Block #{} ({}) executed, consuming {} weight", number, hash, weight);
+		}
+
+		Ok(())
+	}
+}
+
+impl CliConfiguration for TryRuntimeCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
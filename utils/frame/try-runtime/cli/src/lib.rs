@@ -0,0 +1,76 @@
+// This file is part of Indracore.
+
+// Copyright (C) 2020 Selendra.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The `try-runtime` command, which tests a runtime upgrade against existing chain state.
+//!
+//! The state of a block is loaded from the node's database or from a snapshot file written by
+//! the `export-snapshot` command. The
+//! `OnRuntimeUpgrade` hooks of the new runtime are then executed on top of it, followed by an
+//! optional number of the blocks stored after it. Everything runs in the Wasm blob of the new
+//! runtime, which must be built with its `try-runtime` feature to expose the
+//! `frame_try_runtime::TryRuntime` runtime API. Nothing is written to the database.
+
+mod command;
+
+use sc_cli::{BlockNumberOrHash, WasmExecutionMethod};
+use std::path::PathBuf;
+
+/// The `try-runtime` command used to test runtime upgrades.
+#[derive(Debug, structopt::StructOpt)]
+pub struct TryRuntimeCmd {
+	/// Path to the Wasm blob of the runtime to upgrade to.
+	#[structopt(long, parse(from_os_str))]
+	pub wasm: PathBuf,
+
+	/// Block whose state the upgrade is applied to, the best block if not given.
+	#[structopt(long, value_name = "HASH or NUMBER")]
+	pub at: Option<BlockNumberOrHash>,
+
+	/// Load the state from this snapshot file instead of the database.
+	#[structopt(long, parse(from_os_str), value_name = "PATH", conflicts_with = "at")]
+	pub snapshot: Option<PathBuf>,
+
+	/// Number of stored blocks following the state to execute with the new runtime.
+	#[structopt(long, default_value = "0")]
+	pub blocks: u32,
+
+	/// Set the heap pages while executing the runtime.
+	#[structopt(long)]
+	pub heap_pages: Option<u64>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: sc_cli::SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: sc_cli::PruningParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub database_params: sc_cli::DatabaseParams,
+
+	/// Method for executing Wasm runtime code.
+	#[structopt(
+		long = "wasm-execution",
+		value_name = "METHOD",
+		possible_values = &WasmExecutionMethod::enabled_variants(),
+		case_insensitive = true,
+		default_value = "Interpreted"
+	)]
+	pub wasm_method: WasmExecutionMethod,
+}